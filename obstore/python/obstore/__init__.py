@@ -4,8 +4,8 @@ from . import _obstore, store
 from ._obstore import *  # noqa: F403
 
 if TYPE_CHECKING:
-    from . import exceptions  # noqa: TC004
+    from . import exceptions, path, runtime  # noqa: TC004
 
 
-__all__ = ["exceptions", "store"]
+__all__ = ["exceptions", "path", "runtime", "store"]
 __all__ += _obstore.__all__
@@ -0,0 +1,147 @@
+import sys
+from os import PathLike
+from typing import Literal
+
+from ._compression import Compression, Decompression
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+ArchiveFormat: TypeAlias = Literal["tar"]
+"""Supported archive formats.
+
+Only tar archives are supported. Zip archives list their entries in a central directory
+at the end of the file, so they can't be extracted as a stream.
+"""
+
+def archive(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None,
+    dest_store: ObjectStore,
+    dest_path: str | PathLike[str],
+    *,
+    format: ArchiveFormat = "tar",  # noqa: A002
+    chunk_size: int = 5242880,
+    max_concurrency: int = 12,
+    compress: Compression | None = None,
+) -> int:
+    """Write all objects under a prefix into a single archive.
+
+    Objects are streamed one at a time into a multipart upload at `dest_path`, so
+    memory use is bounded by `chunk_size * max_concurrency` regardless of the size of
+    the archive.
+
+    Entries in the archive are named relative to `prefix`.
+
+    This is useful for exporting many objects, such as the artifacts of a run, as one
+    downloadable file:
+
+    ```py
+    import obstore as obs
+
+    obs.archive(store, "runs/42", store, "exports/run-42.tar.gz", compress="gzip")
+    ```
+
+    Args:
+        store: The ObjectStore instance to read objects from.
+        prefix: The prefix of the objects to archive. If `None`, all objects in `store`
+            are archived.
+        dest_store: The ObjectStore instance to write the archive to. This may be the
+            same as `store`, but `dest_path` should not be under `prefix`.
+        dest_path: The path within `dest_store` to write the archive to.
+
+    Keyword Args:
+        format: The archive format. Only `"tar"` is supported; `"zip"` is rejected
+            with a `ValueError`.
+        chunk_size: The size of each part of the multipart upload. Defaults to 5 MiB.
+        max_concurrency: The maximum number of parts to upload concurrently. Defaults
+            to `12`.
+        compress: Compress the archive as it's written, with the given
+            [`Compression`][obstore.Compression], to produce e.g. a `.tar.gz` or
+            `.tar.zst` file. Unlike [`put`][obstore.put], this doesn't set the
+            `Content-Encoding` of the archive, as the compressed file is itself the
+            content to download. Defaults to `None`, which writes a plain tar archive.
+
+    Returns:
+        The number of objects written to the archive.
+
+    """
+
+async def archive_async(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None,
+    dest_store: ObjectStore,
+    dest_path: str | PathLike[str],
+    *,
+    format: ArchiveFormat = "tar",  # noqa: A002
+    chunk_size: int = 5242880,
+    max_concurrency: int = 12,
+    compress: Compression | None = None,
+) -> int:
+    """Call `archive` asynchronously.
+
+    Refer to the documentation for [archive][obstore.archive].
+    """
+
+def extract(
+    store: ObjectStore,
+    archive_path: str | PathLike[str],
+    dest_store: ObjectStore,
+    dest_prefix: str | PathLike[str] | None = None,
+    *,
+    format: ArchiveFormat = "tar",  # noqa: A002
+    chunk_size: int = 5242880,
+    max_concurrency: int = 12,
+    decompress: Decompression | None = None,
+) -> int:
+    """Extract all files in an archive into a store.
+
+    The archive is read with a buffered reader and each file is streamed to its
+    destination, so the archive is never fully loaded into memory.
+
+    Only regular files are extracted. Directories, links, and other entries are
+    skipped. GNU long name and PAX extended header entries larger than 1 MiB are
+    rejected with an `OSError`.
+
+    Args:
+        store: The ObjectStore instance to read the archive from.
+        archive_path: The path within `store` of the archive.
+        dest_store: The ObjectStore instance to write files to.
+        dest_prefix: The prefix to write files under. Defaults to the root of
+            `dest_store`.
+
+    Keyword Args:
+        format: The archive format. Only `"tar"` is supported.
+        chunk_size: The read buffer size, and the size of each part when uploading
+            large files. Defaults to 5 MiB.
+        max_concurrency: The maximum number of parts to upload concurrently. Defaults
+            to `12`.
+        decompress: Decompress the archive with the given
+            [`Decompression`][obstore.Decompression] as it's read, to extract e.g. a
+            `.tar.gz` or `.tar.zst` file written by [`archive`][obstore.archive] with
+            `compress`. The archive is then read with a single streaming request.
+            Defaults to `None`, which reads a plain tar archive.
+
+    Returns:
+        The number of files extracted.
+
+    """
+
+async def extract_async(
+    store: ObjectStore,
+    archive_path: str | PathLike[str],
+    dest_store: ObjectStore,
+    dest_prefix: str | PathLike[str] | None = None,
+    *,
+    format: ArchiveFormat = "tar",  # noqa: A002
+    chunk_size: int = 5242880,
+    max_concurrency: int = 12,
+    decompress: Decompression | None = None,
+) -> int:
+    """Call `extract` asynchronously.
+
+    Refer to the documentation for [extract][obstore.extract].
+    """
@@ -13,6 +13,7 @@ Attribute: TypeAlias = (
         "Content-Language",
         "Content-Type",
         "Cache-Control",
+        "Access-Tier",
     ]
     | str
 )
@@ -40,7 +41,15 @@ Attribute: TypeAlias = (
 
     See [Cache-Control](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Cache-Control).
 
-Any other string key specifies a user-defined metadata field for the object.
+- `"Access-Tier"`: Sets the access tier of the object: one of `"Hot"`, `"Cool"`,
+    `"Cold"`, or `"Archive"`.
+
+    This is only supported for [`AzureStore`][obstore.store.AzureStore], where the
+    tier is set with a separate request once the object has been written. Use
+    [`set_tier`][obstore.set_tier] to change the tier of an existing object.
+
+Any other string key specifies a user-defined metadata field for the object. For
+`AzureStore`, these are stored as `x-ms-meta-*` metadata.
 """
 
 Attributes: TypeAlias = dict[Attribute, str]
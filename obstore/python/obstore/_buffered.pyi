@@ -1,9 +1,14 @@
 import sys
+from collections.abc import AsyncIterable, Iterable
 from contextlib import AbstractAsyncContextManager, AbstractContextManager
+from datetime import timedelta
+from os import PathLike
 
 from ._attributes import Attributes
 from ._bytes import Bytes
+from ._compression import Compression, Decompression
 from ._list import ObjectMeta
+from ._progress import ProgressCallback
 from ._store import ObjectStore
 
 if sys.version_info >= (3, 11):
@@ -18,9 +23,10 @@ else:
 
 def open_reader(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     buffer_size: int = 1024 * 1024,
+    decompress: Decompression | None = None,
 ) -> ReadableFile:
     """Open a readable file object from the specified location.
 
@@ -30,6 +36,12 @@ def open_reader(
 
     Keyword Args:
         buffer_size: The minimum number of bytes to read in a single request. Up to `buffer_size` bytes will be buffered in memory.
+        decompress: Decompress the object with the given
+            [`Decompression`][obstore.Decompression] as it's read, so that e.g. the
+            lines of a large `.gz` log can be iterated over directly. The object is
+            then read with a single streaming request, and the file can only be
+            seeked forwards. `size` and `meta` still describe the stored object.
+            Defaults to `None`, for no decompression.
 
     Returns:
         ReadableFile
@@ -38,9 +50,10 @@ def open_reader(
 
 async def open_reader_async(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     buffer_size: int = 1024 * 1024,
+    decompress: Decompression | None = None,
 ) -> AsyncReadableFile:
     """Call `open_reader` asynchronously, returning a readable file object with asynchronous operations.
 
@@ -68,8 +81,22 @@ class ReadableFile:
     [`get_ranges`][obstore.get_ranges], which will optimise the vectored IO.
 
     [high first-byte latencies]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/optimizing-performance.html
+
+    !!! note "Pickling"
+        The file can be pickled, e.g. to move it between the workers of a dask cluster.
+        It is pickled as its store, the metadata of the object and the current position,
+        so the unpickled file re-opens the same object and continues reading from the
+        same position, without buffered data or a new `head` request. The store must be
+        picklable itself.
     """  # noqa: D205
 
+    def __iter__(self) -> Self:
+        """Iterate over the lines of the file.
+
+        Lines are read lazily and include the trailing newline character, as with
+        [`readline`][obstore.ReadableFile.readline].
+        """
+    def __next__(self) -> Bytes: ...
     def close(self) -> None:
         """Close the current file.
 
@@ -90,6 +117,14 @@ class ReadableFile:
     def readall(self) -> Bytes:
         """Read and return all the bytes from the stream until EOF."""
 
+    def readinto(self, buffer: Buffer, /) -> int:
+        """Read bytes into a pre-allocated, writable bytes-like object `buffer`.
+
+        Reads up to `len(buffer)` bytes, and returns the number of bytes read. This
+        is `0` at EOF.
+
+        This avoids allocating a new buffer for each read.
+        """
     def readline(self) -> Bytes:
         """Read a single line of the file, up until the next newline character."""
 
@@ -109,11 +144,21 @@ class ReadableFile:
         """
 
     def seekable(self) -> bool:
-        """Return True if the stream supports random access."""
+        """Return True if the stream supports random access.
+
+        This is False for files opened with `decompress`, which can only be seeked
+        forwards, raising [`io.UnsupportedOperation`][] otherwise.
+        """
 
     @property
     def size(self) -> int:
-        """The size in bytes of the object."""
+        """The size in bytes of the object.
+
+        Raises:
+            io.UnsupportedOperation: if the file was opened with `decompress`, as the
+                decompressed size isn't known until the file has been read.
+
+        """
 
     def tell(self) -> int:
         """Return the current stream position."""
@@ -139,8 +184,22 @@ class AsyncReadableFile:
     [`get_ranges`][obstore.get_ranges], which will optimise the vectored IO.
 
     [high first-byte latencies]: https://docs.aws.amazon.com/AmazonS3/latest/userguide/optimizing-performance.html
+
+    !!! note "Pickling"
+        The file can be pickled, e.g. to move it between the workers of a dask cluster.
+        It is pickled as its store, the metadata of the object and the current position,
+        so the unpickled file re-opens the same object and continues reading from the
+        same position, without buffered data or a new `head` request. The store must be
+        picklable itself.
     """  # noqa: D205
 
+    def __aiter__(self) -> Self:
+        """Iterate over the lines of the file.
+
+        Lines are read lazily and include the trailing newline character, as with
+        [`readline`][obstore.AsyncReadableFile.readline].
+        """
+    async def __anext__(self) -> Bytes: ...
     def close(self) -> None:
         """Close the current file.
 
@@ -161,6 +220,15 @@ class AsyncReadableFile:
     async def readall(self) -> Bytes:
         """Read and return all the bytes from the stream until EOF."""
 
+    async def readinto(self, buffer: Buffer, /) -> int:
+        """Read bytes into a pre-allocated, writable bytes-like object `buffer`.
+
+        Reads up to `len(buffer)` bytes, and returns the number of bytes read. This
+        is `0` at EOF.
+
+        The buffer must not be resized or released until the returned awaitable has
+        completed.
+        """
     async def readline(self) -> Bytes:
         """Read a single line of the file, up until the next newline character."""
 
@@ -180,23 +248,37 @@ class AsyncReadableFile:
         """
 
     def seekable(self) -> bool:
-        """Return True if the stream supports random access."""
+        """Return True if the stream supports random access.
+
+        This is False for files opened with `decompress`, which can only be seeked
+        forwards, raising [`io.UnsupportedOperation`][] otherwise.
+        """
 
     @property
     def size(self) -> int:
-        """The size in bytes of the object."""
+        """The size in bytes of the object.
+
+        Raises:
+            io.UnsupportedOperation: if the file was opened with `decompress`, as the
+                decompressed size isn't known until the file has been read.
+
+        """
 
     async def tell(self) -> int:
         """Return the current stream position."""
 
 def open_writer(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     attributes: Attributes | None = None,
     buffer_size: int = 10 * 1024 * 1024,
     tags: dict[str, str] | None = None,
     max_concurrency: int = 12,
+    flush_interval: int | timedelta | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    compress: Compression | None = None,
 ) -> WritableFile:
     """Open a writable file object at the specified location.
 
@@ -209,6 +291,10 @@ def open_writer(
         buffer_size: The underlying buffer size to use. Up to `buffer_size` bytes will be buffered in memory. If `buffer_size` is exceeded, data will be uploaded as a multipart upload in chunks of `buffer_size`.
         tags: Provide tags for this object. Defaults to `None`.
         max_concurrency: The maximum number of chunks to upload concurrently. Defaults to 12.
+        flush_interval: Upload buffered data as a part once this threshold is reached, even if fewer than `buffer_size` bytes are buffered. This may be an `int` number of bytes, which must be at least 5 MiB, or a `timedelta` since the last part was uploaded. A `timedelta` is only checked when data is written: there is no background timer, so buffered data isn't uploaded while the writer is idle. As most stores require every part but the last to be at least 5 MiB, data is also held back until at least 5 MiB are buffered, however long ago the last part was uploaded. This is useful for long-lived writers, such as log shippers, that write continuously to one object. Defaults to `None`.
+        progress: A [`ProgressCallback`][obstore.ProgressCallback] to report the number of bytes uploaded so far, as each part completes. The total is always `None`, as it isn't known until the file is closed. Defaults to `None`.
+        progress_interval: The minimum time between two calls of `progress`. Defaults to 100 milliseconds.
+        compress: Compress the data in Rust as it's written, with the given [`Compression`][obstore.Compression]. The `Content-Encoding` attribute is set to the codec, unless `attributes` sets it already, and is left out for stores that don't support attributes. `buffer_size`, `flush_interval` and `progress` then count compressed bytes, while `bytes_written` and `tell` count the bytes written before compression. Data held by the encoder is only uploaded once enough has been written or the file is closed, so `flush` doesn't upload it. Defaults to `None`.
 
     Returns:
         ReadableFile
@@ -217,12 +303,16 @@ def open_writer(
 
 def open_writer_async(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     attributes: Attributes | None = None,
     buffer_size: int = 10 * 1024 * 1024,
     tags: dict[str, str] | None = None,
     max_concurrency: int = 12,
+    flush_interval: int | timedelta | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    compress: Compression | None = None,
 ) -> AsyncWritableFile:
     """Open an **asynchronous** writable file object at the specified location.
 
@@ -238,6 +328,9 @@ class WritableFile(AbstractContextManager):
 
     def __enter__(self) -> Self: ...
     def __exit__(self, exc_type, exc_value, traceback) -> None: ...  # noqa: ANN001
+    @property
+    def bytes_written(self) -> int:
+        """The total number of bytes written to this file."""
     def close(self) -> None:
         """Close the current file."""
 
@@ -250,14 +343,31 @@ class WritableFile(AbstractContextManager):
     def flush(self) -> None:
         """Flushes this output stream, ensuring that all intermediately buffered contents reach their destination."""
 
+    def tell(self) -> int:
+        """Return the current position in the file.
+
+        This is the number of bytes written so far, the same as `bytes_written`.
+        """
+
     def write(self, buffer: bytes | Buffer, /) -> int:
         """Write the [bytes-like object](https://docs.python.org/3/glossary.html#term-bytes-like-object), `buffer`, and return the number of bytes written."""
 
+    def write_from(self, chunks: Iterable[Buffer], /) -> int:
+        """Write every buffer of an iterable, and return the number of bytes written.
+
+        The iterable is consumed in Rust, without a Python loop around
+        [`write`][obstore.WritableFile.write]. No other writes may happen to the file
+        until it has been consumed.
+        """
+
 class AsyncWritableFile(AbstractAsyncContextManager):
     """A buffered writable file object with **asynchronous** operations."""
 
     async def __aenter__(self) -> Self: ...
     async def __aexit__(self, exc_type, exc_value, traceback) -> None: ...  # noqa: ANN001
+    @property
+    def bytes_written(self) -> int:
+        """The total number of bytes written to this file."""
     async def close(self) -> None:
         """Close the current file."""
 
@@ -270,5 +380,23 @@ class AsyncWritableFile(AbstractAsyncContextManager):
     async def flush(self) -> None:
         """Flushes this output stream, ensuring that all intermediately buffered contents reach their destination."""
 
+    def tell(self) -> int:
+        """Return the current position in the file.
+
+        This is the number of bytes written so far, the same as `bytes_written`.
+        """
+
     async def write(self, buffer: bytes | Buffer, /) -> int:
         """Write the [bytes-like object](https://docs.python.org/3/glossary.html#term-bytes-like-object), `buffer`, and return the number of bytes written."""
+
+    async def write_from(
+        self,
+        chunks: AsyncIterable[Buffer] | Iterable[Buffer],
+        /,
+    ) -> int:
+        """Write every buffer of an iterable or async iterable.
+
+        This returns the number of bytes written. The iterable is consumed in Rust,
+        without a Python loop around [`write`][obstore.AsyncWritableFile.write]. No
+        other writes may happen to the file until it has been consumed.
+        """
@@ -0,0 +1,32 @@
+import sys
+from typing import Literal
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+Compression: TypeAlias = Literal["gzip", "zstd"]
+"""How to compress an object as it's written.
+
+- `"gzip"`: compress with gzip, readable with `gzip` or Python's `gzip` module.
+- `"zstd"`: compress with Zstandard, readable with `zstd`.
+
+The data is compressed at the default level of each codec. Objects compressed this
+way can be read back with `decompress="auto"`, which infers the codec from their
+`Content-Encoding`, or from a `.gz` or `.zst` extension in stores that don't keep
+attributes.
+"""
+
+Decompression: TypeAlias = Literal["gzip", "zstd", "auto"]
+"""How to decompress an object as it's read.
+
+- `"gzip"`: decompress gzip data, as written by `gzip` or with a `.gz` extension.
+- `"zstd"`: decompress Zstandard data, as written by `zstd` or with a `.zst`
+  extension.
+- `"auto"`: infer the codec from the `Content-Encoding` of the object, or else from
+  the extension of its path (`.gz`, `.gzip`, `.zst` or `.zstd`). Objects with neither
+  are read as is.
+
+Concatenated gzip members or Zstandard frames are decompressed as one stream.
+"""
@@ -0,0 +1,56 @@
+from collections.abc import Sequence
+from datetime import timedelta
+from os import PathLike
+
+from ._get import BytesStream
+from ._store import ObjectStore
+
+def concat(
+    store: ObjectStore,
+    paths: Sequence[str | PathLike[str]],
+    *,
+    prefetch: int = 1,
+    min_chunk_size: int = 10 * 1024 * 1024,
+    timeout: timedelta | None = None,
+) -> BytesStream:
+    """Stream the bytes of multiple objects, one after the other, as a single stream.
+
+    This lets many objects, such as the daily shards of a log, be consumed as one
+    logical stream, without downloading them to temporary files first. While an
+    object is being read, the next `prefetch` objects are already requested, so that
+    there is no pause between objects.
+
+    The stream can be iterated both synchronously and asynchronously:
+
+    ```py
+    import obstore as obs
+
+    paths = [f"logs/2024-01-{day:02}.jsonl" for day in range(1, 32)]
+    for chunk in obs.concat(store, paths):
+        print(len(chunk))
+
+    async for chunk in obs.concat(store, paths):
+        print(len(chunk))
+    ```
+
+    Args:
+        store: The ObjectStore instance to use.
+        paths: The paths of the objects to concatenate, in order.
+
+    Keyword Args:
+        prefetch: The number of objects to request ahead of the object being read.
+            Defaults to `1`.
+        min_chunk_size: The minimum size in bytes of each chunk of the stream. All
+            chunks except for the last will be at least this size. Chunks may span
+            objects. Defaults to 10MB.
+        timeout: The maximum time the whole stream may take, including reading the
+            bodies of the objects. Defaults to `None`, only limited by the client's
+            `timeout`.
+
+    Returns:
+        A stream of the bytes of all the objects.
+
+    Raises:
+        FileNotFoundError: When iterating, if one of the objects doesn't exist.
+
+    """
@@ -0,0 +1,127 @@
+import sys
+from collections.abc import Awaitable, Callable
+from os import PathLike
+
+from ._attributes import Attributes
+from ._bytes import Bytes
+from ._put import PutResult
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+
+def put_if_absent(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    data: Buffer,
+    *,
+    attributes: Attributes | None = None,
+    tags: dict[str, str] | None = None,
+) -> PutResult | None:
+    """Write an object only if no object exists at `path` yet.
+
+    This is an atomic operation built on `PutMode` `"create"`: if two clients race to
+    create the same object, exactly one of them succeeds.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to write to.
+        data: The content of the object.
+
+    Keyword Args:
+        attributes: Provide a set of `Attributes`. Defaults to `None`.
+        tags: Provide tags for this object. Defaults to `None`.
+
+    Returns:
+        The result of the write, or `None` if an object already exists at `path`.
+
+    """
+
+async def put_if_absent_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    data: Buffer,
+    *,
+    attributes: Attributes | None = None,
+    tags: dict[str, str] | None = None,
+) -> PutResult | None:
+    """Call `put_if_absent` asynchronously.
+
+    Refer to the documentation for [put_if_absent][obstore.put_if_absent].
+    """
+
+def update(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    func: Callable[[Bytes | None], Buffer | None],
+    *,
+    max_retries: int = 10,
+) -> PutResult | None:
+    """Atomically update an object with a read-modify-write loop.
+
+    The object is read, and `func` is called with its current contents (or `None` if
+    it doesn't exist) to compute the new contents. The new contents are only written
+    if the object hasn't been modified since it was read, using its ETag and version.
+    If another client modified the object in the meantime, the object is read again
+    and `func` is called again with the new contents.
+
+    This is the building block for object-store-based locks and manifests, such as
+    the commit protocol of Delta Lake.
+
+    ```py
+    import json
+
+    import obstore as obs
+
+    def add_file(current: obs.Bytes | None) -> bytes:
+        manifest = json.loads(current.to_bytes()) if current is not None else []
+        manifest.append("part-0001.parquet")
+        return json.dumps(manifest).encode()
+
+    obs.update(store, "manifest.json", add_file)
+    ```
+
+    !!! note
+        `func` may be called more than once, so it should not have side effects.
+
+        Not all stores support conditional updates. In particular,
+        [`LocalStore`][obstore.store.LocalStore] does not, and
+        [`S3Store`][obstore.store.S3Store] requires `conditional_put` to be
+        configured.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to update.
+        func: A function that takes the current contents of the object, or `None` if
+            the object doesn't exist, and returns its new contents. If it returns
+            `None`, the update is abandoned and nothing is written.
+
+    Keyword Args:
+        max_retries: The maximum number of times to retry after a conflicting write
+            by another client. Defaults to 10.
+
+    Raises:
+        PreconditionError: if the object was still being modified concurrently after
+            `max_retries` retries.
+
+    Returns:
+        The result of the write, or `None` if `func` returned `None`. The attributes
+        of the existing object are preserved.
+
+    """
+
+async def update_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    func: Callable[[Bytes | None], Buffer | None]
+    | Callable[[Bytes | None], Awaitable[Buffer | None]],
+    *,
+    max_retries: int = 10,
+) -> PutResult | None:
+    """Call `update` asynchronously.
+
+    Refer to the documentation for [update][obstore.update]. In addition to a
+    synchronous function, `func` may also be an async function.
+    """
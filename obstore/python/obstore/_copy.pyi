@@ -1,6 +1,19 @@
+from datetime import timedelta
+from os import PathLike
+
 from ._store import ObjectStore
 
-def copy(store: ObjectStore, from_: str, to: str, *, overwrite: bool = True) -> None:
+def copy(
+    store: ObjectStore,
+    from_: str | PathLike[str],
+    to: str | PathLike[str],
+    *,
+    overwrite: bool = True,
+    multipart_threshold: int | None = None,
+    part_size: int = 512 * 1024 * 1024,
+    max_concurrency: int = 8,
+    timeout: timedelta | None = None,
+) -> None:
     """Copy an object from one path to another in the same object store.
 
     Args:
@@ -15,15 +28,38 @@ def copy(store: ObjectStore, from_: str, to: str, *, overwrite: bool = True) ->
             If `False`: will copy only if destination is empty. Performs an atomic operation if the underlying object storage supports it. If atomic operations are not supported by the underlying object storage (like S3) it will return an error.
 
             Will return an error if the destination already has an object.
+        multipart_threshold: For [`S3Store`][obstore.store.S3Store], the size in
+            bytes above which an object is copied in parts with `UploadPartCopy`,
+            rather than with a single `CopyObject` request. This requires an extra
+            `HEAD` request for the source. Defaults to `None`, in which case objects
+            are only copied in parts if S3 rejects them as larger than the 5 GiB
+            limit of `CopyObject`.
+
+            Each part is copied on the server, so no data passes through the client.
+            Other stores copy with their own server-side copy, and ignore this.
+        part_size: The size in bytes of each part of a multipart copy, between 5 MiB
+            and 5 GiB. Parts are made larger if needed to copy the object in at most
+            10,000 parts. Defaults to 512 MiB.
+        max_concurrency: The maximum number of parts of a multipart copy to copy
+            concurrently. Defaults to 8.
+        timeout: The maximum time the call may take. This can only shorten the
+            timeout configured for the client of the store. If the call hasn't
+            completed by then, it is cancelled and a
+            [`TimeoutError`][obstore.exceptions.TimeoutError] is raised. Defaults to
+            `None`, for no limit beyond the client's.
 
     """
 
 async def copy_async(
     store: ObjectStore,
-    from_: str,
-    to: str,
+    from_: str | PathLike[str],
+    to: str | PathLike[str],
     *,
     overwrite: bool = True,
+    multipart_threshold: int | None = None,
+    part_size: int = 512 * 1024 * 1024,
+    max_concurrency: int = 8,
+    timeout: timedelta | None = None,
 ) -> None:
     """Call `copy` asynchronously.
 
@@ -1,8 +1,16 @@
 from collections.abc import Sequence
+from datetime import timedelta
+from os import PathLike
 
 from ._store import ObjectStore
 
-def delete(store: ObjectStore, paths: str | Sequence[str]) -> None:
+def delete(
+    store: ObjectStore,
+    paths: str | PathLike[str] | Sequence[str | PathLike[str]],
+    *,
+    version: str | None = None,
+    timeout: timedelta | None = None,
+) -> None:
     """Delete the object at the specified location(s).
 
     Args:
@@ -17,10 +25,81 @@ def delete(store: ObjectStore, paths: str | Sequence[str]) -> None:
             filesystems, GCP, and Azure return an error, while S3 and in-memory will
             return Ok.
 
+    Keyword Args:
+        version: The version of the object to delete, as returned by
+            [`list_versions`][obstore.list_versions]. Only this version is deleted,
+            leaving the other versions of the object in place. This requires a single
+            path, and an [`S3Store`][obstore.store.S3Store],
+            [`GCSStore`][obstore.store.GCSStore] or
+            [`AzureStore`][obstore.store.AzureStore]. Defaults to `None`, which deletes
+            the object as usual.
+        timeout: The maximum time the call may take. This can only shorten the
+            timeout configured for the client of the store. If the call hasn't
+            completed by then, it is cancelled and a
+            [`TimeoutError`][obstore.exceptions.TimeoutError] is raised. Defaults to
+            `None`, for no limit beyond the client's.
+
+    Raises:
+        ValueError: if `version` is given with more than one path, or for a store
+            that doesn't keep versions.
+
     """
 
-async def delete_async(store: ObjectStore, paths: str | Sequence[str]) -> None:
+async def delete_async(
+    store: ObjectStore,
+    paths: str | PathLike[str] | Sequence[str | PathLike[str]],
+    *,
+    version: str | None = None,
+    timeout: timedelta | None = None,
+) -> None:
     """Call `delete` asynchronously.
 
     Refer to the documentation for [delete][obstore.delete].
     """
+
+def delete_prefix(
+    store: ObjectStore,
+    prefix: str | PathLike[str],
+    *,
+    timeout: timedelta | None = None,
+) -> None:
+    """Delete every object under a prefix.
+
+    The objects are listed and deleted in Rust, with the bulk operations of
+    [`delete`][obstore.delete] where the store supports them.
+
+    With an [`AzureStore`][obstore.store.AzureStore] of an account with a hierarchical
+    namespace (Azure Data Lake Storage), the directory at `prefix` is instead deleted
+    with its contents by recursive Delete Path requests to the Data Lake Storage
+    endpoint. This is a single server-side operation, however many objects the
+    directory holds, and also deletes the now empty directories. It is an error for
+    `prefix` to be a file rather than a directory. The requests are retried with the
+    `retry_config` of the store. While tracing is enabled with
+    [`enable_tracing`][obstore.runtime.enable_tracing], the objects are listed and
+    deleted instead, so that every request is traced.
+
+    Args:
+        store: The ObjectStore instance to use.
+        prefix: The prefix within the store to delete everything under. Only whole
+            path segments match, so `"data"` deletes `data/a.csv` but not
+            `database.csv`.
+
+    Keyword Args:
+        timeout: The maximum time the call may take. If the call hasn't completed by
+            then, it is cancelled and a
+            [`TimeoutError`][obstore.exceptions.TimeoutError] is raised, possibly after
+            deleting some of the objects. Defaults to `None`, for no limit beyond the
+            client's.
+
+    """
+
+async def delete_prefix_async(
+    store: ObjectStore,
+    prefix: str | PathLike[str],
+    *,
+    timeout: timedelta | None = None,
+) -> None:
+    """Call `delete_prefix` asynchronously.
+
+    Refer to the documentation for [delete_prefix][obstore.delete_prefix].
+    """
@@ -0,0 +1,71 @@
+from typing import TypedDict
+
+from ._store import ObjectStore
+
+class DiagnosticIssue(TypedDict):
+    """A known issue reported by [`diagnostics`][obstore.diagnostics]."""
+
+    id: str
+    """A stable identifier for the issue, such as `"http-not-allowed"`."""
+
+    description: str
+    """What goes wrong."""
+
+    workaround: str
+    """The configuration that avoids the issue."""
+
+class Diagnostics(TypedDict):
+    """The result of [`diagnostics`][obstore.diagnostics]."""
+
+    version: str
+    """The version of obstore."""
+
+    object_store_version: str
+    """The version of the `object_store` crate that obstore was built with."""
+
+    object_store_source: str | None
+    """Where the `object_store` crate was built from, such as a registry or a git
+    repository.
+
+    This is `None` when it was built from a local path, such as a vendored copy.
+    """
+
+    store: str | None
+    """The kind of store that was checked, such as `"S3Store"`, or `None` if no store
+    was passed.
+
+    This is `"other"` for stores whose configuration isn't checked, such as wrapper
+    stores or stores implemented in Python.
+    """
+
+    issues: list[DiagnosticIssue]
+    """The known issues that affect this build of obstore and the store's
+    configuration."""
+
+def diagnostics(store: ObjectStore | None = None) -> Diagnostics:
+    """Report the versions obstore was built with and the known issues that apply.
+
+    Known issues are matched from a table embedded in obstore, which covers bugs in
+    particular versions of the `object_store` crate as well as configurations that
+    commonly fail with emulators and S3-compatible services. Each issue suggests a
+    configuration that works around it.
+
+    Include the output when reporting a bug.
+
+    ```py
+    from obstore.store import S3Store
+    import obstore as obs
+
+    store = S3Store("bucket", endpoint="http://localhost:9000")
+    for issue in obs.diagnostics(store)["issues"]:
+        print(f"{issue['description']} {issue['workaround']}")
+    ```
+
+    Args:
+        store: A store whose configuration to check. If `None`, only the issues
+            affecting every remote store in this build are reported.
+
+    Returns:
+        The diagnostics of this build and store.
+
+    """
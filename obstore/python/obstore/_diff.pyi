@@ -0,0 +1,139 @@
+# ruff: noqa: UP006, UP035
+
+from os import PathLike
+from typing import List, Literal, TypedDict, overload
+
+from arro3.core import Table
+
+from ._list import ObjectMeta
+from ._store import ObjectStore
+
+class DiffEntry(TypedDict):
+    """An object that differs between two listings, returned by
+    [`diff`][obstore.diff].
+    """  # noqa: D205
+
+    path: str
+    """The path of the object, relative to the prefix of each listing."""
+
+    change: Literal["added", "removed", "changed"]
+    """How the object differs.
+
+    - `"added"`: the object is only under `prefix_b`.
+    - `"removed"`: the object is only under `prefix_a`.
+    - `"changed"`: the object is under both prefixes, with a different size or ETag.
+    """
+
+    a: ObjectMeta | None
+    """The metadata of the object under `prefix_a`, if any."""
+
+    b: ObjectMeta | None
+    """The metadata of the object under `prefix_b`, if any."""
+
+@overload
+def diff(
+    store_a: ObjectStore,
+    prefix_a: str | PathLike[str] | None,
+    store_b: ObjectStore,
+    prefix_b: str | PathLike[str] | None,
+    *,
+    compare_e_tag: bool = True,
+    return_arrow: Literal[True],
+) -> Table: ...
+@overload
+def diff(
+    store_a: ObjectStore,
+    prefix_a: str | PathLike[str] | None,
+    store_b: ObjectStore,
+    prefix_b: str | PathLike[str] | None,
+    *,
+    compare_e_tag: bool = True,
+    return_arrow: Literal[False] = False,
+) -> List[DiffEntry]: ...
+def diff(
+    store_a: ObjectStore,
+    prefix_a: str | PathLike[str] | None,
+    store_b: ObjectStore,
+    prefix_b: str | PathLike[str] | None,
+    *,
+    compare_e_tag: bool = True,
+    return_arrow: bool = False,
+) -> Table | List[DiffEntry]:
+    """Compare the objects under two prefixes, in the same store or in two stores.
+
+    Objects are matched by their path relative to each prefix, and the objects that
+    were added, removed or changed going from `prefix_a` to `prefix_b` are returned,
+    in lexicographic order of path. This is the basis of sync tools and of jobs that
+    validate a copy of a dataset.
+
+    ```py
+    import obstore as obs
+    from obstore.store import LocalStore, S3Store
+
+    s3 = S3Store("bucket")
+    local = LocalStore("/data/mirror")
+    for entry in obs.diff(s3, "dataset/", local, None, compare_e_tag=False):
+        print(entry["change"], entry["path"])
+    ```
+
+    Both listings are streamed, and merged as they arrive, so only the differences
+    are held in memory. This relies on stores listing objects in lexicographic order,
+    as most do. Stores that don't, such as [`LocalStore`][obstore.store.LocalStore],
+    are listed again and sorted in full.
+
+    Args:
+        store_a: The store of the first listing.
+        prefix_a: The prefix of the first listing, or `None` for the root of
+            `store_a`.
+        store_b: The store of the second listing.
+        prefix_b: The prefix of the second listing, or `None` for the root of
+            `store_b`.
+
+    Keyword Args:
+        compare_e_tag: If `True`, objects of the same size are also compared by ETag,
+            when both ETags are known. ETags of different kinds of stores generally
+            differ for the same contents, so set this to `False` to only compare sizes
+            across stores. Defaults to `True`.
+        return_arrow: If `True`, return the differences as an Arrow `Table`, with the
+            columns `path`, `change`, and `size`, `last_modified` and `e_tag` suffixed
+            with `_a` and `_b`. If this is `True`, the `arro3-core` Python package must
+            be installed. Defaults to `False`.
+
+    Returns:
+        The objects that differ between the two listings.
+
+    """
+
+@overload
+async def diff_async(
+    store_a: ObjectStore,
+    prefix_a: str | PathLike[str] | None,
+    store_b: ObjectStore,
+    prefix_b: str | PathLike[str] | None,
+    *,
+    compare_e_tag: bool = True,
+    return_arrow: Literal[True],
+) -> Table: ...
+@overload
+async def diff_async(
+    store_a: ObjectStore,
+    prefix_a: str | PathLike[str] | None,
+    store_b: ObjectStore,
+    prefix_b: str | PathLike[str] | None,
+    *,
+    compare_e_tag: bool = True,
+    return_arrow: Literal[False] = False,
+) -> List[DiffEntry]: ...
+async def diff_async(
+    store_a: ObjectStore,
+    prefix_a: str | PathLike[str] | None,
+    store_b: ObjectStore,
+    prefix_b: str | PathLike[str] | None,
+    *,
+    compare_e_tag: bool = True,
+    return_arrow: bool = False,
+) -> Table | List[DiffEntry]:
+    """Call `diff` asynchronously.
+
+    Refer to the documentation for [diff][obstore.diff].
+    """
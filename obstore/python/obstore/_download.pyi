@@ -0,0 +1,77 @@
+import sys
+from os import PathLike
+from pathlib import Path
+from typing import Literal
+
+from ._list import ObjectMeta
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+FsyncPolicy: TypeAlias = Literal["never", "complete", "always"]
+"""When to flush downloaded data to disk.
+
+- `"never"`: never explicitly sync, relying on the operating system to flush data.
+- `"complete"`: sync once the download is complete, before renaming the file into place,
+  and sync its directory after the rename.
+- `"always"`: sync after every chunk, so that a crash loses as little progress as
+  possible. This is significantly slower.
+"""
+
+def download(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    local_path: str | Path,
+    *,
+    fsync: FsyncPolicy = "complete",
+    max_retries: int = 3,
+) -> ObjectMeta:
+    """Download an object to a local file, resuming a previous partial download.
+
+    Data is written to `<local_path>.part`, which is atomically renamed to
+    `local_path` once the download is complete, so `local_path` never contains a
+    partial object.
+
+    The ETag of the object is recorded in `<local_path>.part.etag`. If a download is
+    interrupted, a later call will resume from the end of the existing `.part` file,
+    but only if the object's ETag is unchanged. Otherwise the download restarts from
+    the beginning.
+
+    If the object changes during the download, the `.part` file is removed and
+    [`PreconditionError`][obstore.exceptions.PreconditionError] is raised.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to download.
+        local_path: The local file path to write to.
+
+    Keyword Args:
+        fsync: When to flush downloaded data to disk. Unless `"never"`, the
+            directory containing `local_path` is also synced after the rename, so
+            that the renamed file survives a crash. Defaults to `"complete"`.
+        max_retries: The number of times to resume the download from the current
+            offset after a failed request. Each resume waits for the backoff of the
+            store's `retry_config`, or the default backoff if the store has none.
+            This is in addition to any retries performed by the store itself.
+            Defaults to `3`.
+
+    Returns:
+        The metadata of the downloaded object.
+
+    """
+
+async def download_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    local_path: str | Path,
+    *,
+    fsync: FsyncPolicy = "complete",
+    max_retries: int = 3,
+) -> ObjectMeta:
+    """Call `download` asynchronously.
+
+    Refer to the documentation for [download][obstore.download].
+    """
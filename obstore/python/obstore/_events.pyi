@@ -0,0 +1,54 @@
+from typing import Any
+
+from ._watch import ChangeEvent
+from .store import AzureStore, GCSStore, S3Store
+
+def parse_events(
+    payload: str | bytes | dict[str, Any] | list[Any],
+    *,
+    store: AzureStore | GCSStore | S3Store | None = None,
+) -> list[ChangeEvent]:
+    """Parse the changes in an event notification of a cloud store.
+
+    This lets applications that receive the notifications of a bucket handle them
+    with the same code as the changes found by [`watch`][obstore.watch]. The
+    following notifications are supported:
+
+    - S3 event notifications, as sent to SQS, SNS or Lambda, including SQS and SNS
+      messages whose body is a notification.
+    - S3 events delivered by EventBridge.
+    - Pub/Sub messages of GCS notifications with the `JSON_API_V1` payload, either as
+      the body of a push subscription or as the message itself. The `data` of the
+      message may be passed base64-encoded or already decoded.
+    - Event Grid events of Azure Blob Storage, in the Event Grid or the CloudEvents
+      schema, on their own or in a batch.
+
+    ```py
+    import obstore as obs
+
+    def handler(event, context):
+        for change in obs.parse_events(event, store=store):
+            if change["change"] == "created":
+                process(store, change["path"])
+    ```
+
+    Only objects that are created, overwritten or deleted are reported. Other events,
+    such as restores, tag changes and the test events sent when notifications are
+    set up, are skipped. S3 and Azure don't tell an overwrite from a new object, so
+    overwrites are reported as `"created"`; GCS overwrites are reported as
+    `"modified"`. Notifications of deletes don't include the size of the object, so
+    it's `0` unless the notification has one.
+
+    Args:
+        payload: The notification, as a JSON string or bytes, or its parsed JSON.
+
+    Keyword Args:
+        store: The store that the notifications are for. If given, events for other
+            buckets or containers are skipped, and paths are made relative to the
+            prefix of the store, skipping objects outside of it. Defaults to `None`,
+            which returns paths within the bucket or container of each event.
+
+    Returns:
+        The changes in the notification, in the order they appear in it.
+
+    """
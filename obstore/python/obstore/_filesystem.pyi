@@ -0,0 +1,187 @@
+import sys
+from datetime import datetime
+from typing import Literal, TypedDict, overload
+
+from ._buffered import ReadableFile, WritableFile
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+
+class FileInfo(TypedDict, total=False):
+    """Information about a file or directory, as returned by
+    [`ObstoreFileSystem.ls`][obstore.ObstoreFileSystem.ls] and
+    [`ObstoreFileSystem.info`][obstore.ObstoreFileSystem.info].
+
+    Directories only have the `name`, `size`, and `type` keys.
+    """
+
+    name: str
+    """The full path to the file or directory."""
+
+    size: int
+    """The size in bytes of the file. Always `0` for directories."""
+
+    type: Literal["file", "directory"]
+    """Whether this entry is a file or a directory."""
+
+    last_modified: datetime
+    """The last modified time of the file."""
+
+    e_tag: str | None
+    """The unique identifier for the file.
+
+    <https://datatracker.ietf.org/doc/html/rfc9110#name-etag>
+    """
+
+    version: str | None
+    """A version indicator for this file."""
+
+class ObstoreFileSystem:
+    """A filesystem implementing the core of the fsspec
+    [`AbstractFileSystem`][fsspec.spec.AbstractFileSystem] interface.
+
+    Unlike [`FsspecStore`][obstore.fsspec.FsspecStore], every method is implemented
+    directly in Rust, so there is no Python overhead per call. Paths are relative to
+    the root of the store, and leading or trailing slashes are ignored.
+
+    Since object stores have no real directories, a "directory" is any prefix that
+    contains at least one object.
+
+    Examples:
+        ```py
+        from obstore import ObstoreFileSystem
+        from obstore.store import MemoryStore
+
+        fs = ObstoreFileSystem(MemoryStore())
+        fs.pipe_file("data/file.txt", b"hello world")
+        fs.ls("data", detail=False)  # ["data/file.txt"]
+        fs.cat_file("data/file.txt", start=6)  # b"world"
+        ```
+
+    """
+
+    def __init__(self, store: ObjectStore) -> None:
+        """Create a new filesystem wrapping `store`.
+
+        Args:
+            store: The ObjectStore instance to use.
+
+        """
+
+    def cat_file(
+        self,
+        path: str,
+        start: int | None = None,
+        end: int | None = None,
+    ) -> bytes:
+        """Return the contents of a file, or a byte range of it.
+
+        Args:
+            path: The path of the file.
+            start: The offset to start reading from. Negative values are relative
+                to the end of the file. Defaults to the start of the file.
+            end: The offset to stop reading at, exclusive. Negative values are
+                relative to the end of the file. Defaults to the end of the file.
+
+        Returns:
+            The requested bytes.
+
+        """
+
+    def cp_file(self, path1: str, path2: str) -> None:
+        """Copy a file from `path1` to `path2` within the store.
+
+        Args:
+            path1: The source path.
+            path2: The destination path.
+
+        """
+
+    def info(self, path: str) -> FileInfo:
+        """Return information about a file or directory.
+
+        Args:
+            path: The path of the file or directory.
+
+        Raises:
+            FileNotFoundError: If no file or directory exists at `path`.
+
+        """
+
+    @overload
+    def ls(self, path: str, detail: Literal[True] = True) -> list[FileInfo]: ...
+    @overload
+    def ls(self, path: str, detail: Literal[False]) -> list[str]: ...
+    def ls(self, path: str, detail: bool = True) -> list[FileInfo] | list[str]:  # noqa: FBT001
+        """List the immediate contents of a directory.
+
+        If `path` is a file, a list containing only that file is returned.
+
+        Args:
+            path: The path of the directory.
+            detail: If `True`, return a list of [`FileInfo`][obstore.FileInfo]
+                dicts. Otherwise return a list of paths.
+
+        Raises:
+            FileNotFoundError: If no file or directory exists at `path`.
+
+        """
+
+    @overload
+    def open(
+        self,
+        path: str,
+        mode: Literal["rb"] = "rb",
+        block_size: int | None = None,
+    ) -> ReadableFile: ...
+    @overload
+    def open(
+        self,
+        path: str,
+        mode: Literal["wb"],
+        block_size: int | None = None,
+    ) -> WritableFile: ...
+    def open(
+        self,
+        path: str,
+        mode: Literal["rb", "wb"] = "rb",
+        block_size: int | None = None,
+    ) -> ReadableFile | WritableFile:
+        """Open a file for reading or writing.
+
+        Args:
+            path: The path of the file.
+            mode: `"rb"` to read the file, or `"wb"` to write it.
+            block_size: The buffer size used for reading or writing. Defaults to 1MB
+                for reading and 10MB for writing.
+
+        Returns:
+            A [`ReadableFile`][obstore.ReadableFile] in `"rb"` mode, or a
+            [`WritableFile`][obstore.WritableFile] in `"wb"` mode.
+
+        """
+
+    def pipe_file(self, path: str, value: Buffer) -> None:
+        """Write `value` to a file, replacing any existing contents.
+
+        Args:
+            path: The path of the file.
+            value: The data to write.
+
+        """
+
+    def rm(self, path: str | list[str], recursive: bool = False) -> None:  # noqa: FBT001, FBT002
+        """Delete one or more files.
+
+        Args:
+            path: The path or paths to delete.
+            recursive: If `True`, also delete all files under each path.
+
+        Raises:
+            FileNotFoundError: If nothing exists at a path.
+            IsADirectoryError: If a path is a directory and `recursive` is `False`.
+
+        """
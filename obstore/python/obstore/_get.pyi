@@ -1,12 +1,29 @@
+import sys
 from collections.abc import Sequence
-from datetime import datetime
-from typing import TypedDict
+from datetime import datetime, timedelta
+from os import PathLike
+from pathlib import Path
+from typing import IO, Any, Literal, TypedDict, overload
+
+from numpy.typing import DTypeLike, NDArray
 
 from ._attributes import Attributes
 from ._bytes import Bytes
+from ._compression import Decompression
 from ._list import ObjectMeta
+from ._progress import ProgressCallback
 from .store import ObjectStore
 
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+
+if sys.version_info >= (3, 11):
+    from typing import Self
+else:
+    from typing_extensions import Self
+
 class OffsetRange(TypedDict):
     """Request all bytes starting from a given byte offset."""
 
@@ -96,6 +113,16 @@ class GetOptions(TypedDict, total=False):
     <https://datatracker.ietf.org/doc/html/rfc9110#name-head>
     """
 
+    headers: dict[str, str]
+    """
+    Extra headers to send with the request, such as an `Authorization` header for a
+    URL of an [`HTTPStore`][obstore.store.HTTPStore] that is authorized out of band.
+
+    These are added after the request is signed, so stores that sign their requests,
+    such as [`S3Store`][obstore.store.S3Store], reject headers that they require to
+    be signed.
+    """
+
 class GetResult:
     """Result for a get request.
 
@@ -124,13 +151,39 @@ class GetResult:
 
     Note that after calling `bytes`, `bytes_async`, or `stream`, you will no longer be
     able to call other methods on this object, such as the `meta` attribute.
+
+    Using as a context manager, to only read the metadata of the object:
+    ```py
+    with obs.get(store, path) as resp:
+        print(resp.meta.size)
+    ```
+
+    Leaving the `with` block disposes of the result, which aborts the download of the
+    body if it hasn't been consumed, so that its connection isn't held open. The same
+    holds for `async with`.
+
+    A `GetResult` holds the response to a request, so it can't be pickled and raises a
+    `TypeError` instead. To read an object in another process, pass the store and path,
+    or use [`open_reader`][obstore.open_reader], whose file object can be pickled.
     """
 
+    def __enter__(self) -> Self: ...
+    def __exit__(self, exc_type, exc_value, traceback) -> None: ...  # noqa: ANN001
+    async def __aenter__(self) -> Self: ...
+    async def __aexit__(self, exc_type, exc_value, traceback) -> None: ...  # noqa: ANN001
+    def close(self) -> None:
+        """Dispose of this result.
+
+        This aborts the download of the body if it hasn't been consumed. Afterwards,
+        no other methods can be called on this object.
+        """
+
     @property
     def attributes(self) -> Attributes:
         """Additional object attributes.
 
-        This must be accessed _before_ calling `stream`, `bytes`, or `bytes_async`.
+        This must be accessed _before_ calling `stream`, `bytes`, `bytes_async`, or
+        `to_numpy`.
         """
 
     def bytes(self) -> Bytes:
@@ -147,11 +200,44 @@ class GetResult:
         memory by passing to [`bytes`][].
         """
 
+    def to_numpy(self, dtype: DTypeLike = None) -> NDArray[Any]:
+        """Collect the data into a read-only NumPy array, without copying it.
+
+        The array is a view over the downloaded buffer, created with
+        [`numpy.frombuffer`][], so it can't be written to. Call `.copy()` on it for a
+        writable array.
+
+        ```py
+        import numpy as np
+        import obstore as obs
+
+        resp = obs.get(store, "weights.bin")
+        arr = resp.to_numpy(dtype=np.float32)
+        ```
+
+        This requires [NumPy](https://numpy.org/) to be installed.
+
+        Args:
+            dtype: The data type of the array. The length of the data must be a
+                multiple of its item size. Defaults to `uint8`.
+
+        Returns:
+            A one-dimensional, read-only NumPy array.
+
+        """
+
+    async def to_numpy_async(self, dtype: DTypeLike = None) -> NDArray[Any]:
+        """Collect the data into a read-only NumPy array, without copying it.
+
+        Refer to the documentation for [to_numpy][obstore.GetResult.to_numpy].
+        """
+
     @property
     def meta(self) -> ObjectMeta:
         """The ObjectMeta for this object.
 
-        This must be accessed _before_ calling `stream`, `bytes`, or `bytes_async`.
+        This must be accessed _before_ calling `stream`, `bytes`, `bytes_async`, or
+        `to_numpy`.
         """
 
     @property
@@ -160,22 +246,191 @@ class GetResult:
 
         Note that this is `(start, stop)` **not** `(start, length)`.
 
-        This must be accessed _before_ calling `stream`, `bytes`, or `bytes_async`.
+        This must be accessed _before_ calling `stream`, `bytes`, `bytes_async`, or
+        `to_numpy`.
         """
 
-    def stream(self, min_chunk_size: int = 10 * 1024 * 1024) -> BytesStream:
+    def stream(
+        self,
+        min_chunk_size: int = 10 * 1024 * 1024,
+        *,
+        chunk_timeout: timedelta | None = None,
+        decompress: Decompression | None = None,
+    ) -> BytesStream:
         r"""Return a chunked stream over the result's bytes.
 
+        Compressed objects can be decompressed in Rust as the data arrives, without
+        collecting the compressed data first:
+
+        ```py
+        import obstore as obs
+
+        resp = obs.get(store, "logs/app.log.gz")
+        for chunk in resp.stream(decompress="auto"):
+            ...
+        ```
+
         Args:
             min_chunk_size: The minimum size in bytes for each chunk in the returned
                 `BytesStream`. All chunks except for the last chunk will be at least
                 this size. Defaults to 10\*1024\*1024 (10MB).
 
+        Keyword Args:
+            chunk_timeout: The maximum time to wait for more data while producing each
+                chunk. If some data was received in that time, it is returned as a
+                smaller chunk. Otherwise the stream raises a
+                [`StreamTimeoutError`][obstore.exceptions.StreamTimeoutError], after
+                which iteration may be retried. Defaults to `None`, for no limit.
+            decompress: Decompress the data with the given
+                [`Decompression`][obstore.Decompression] as it arrives. Chunk sizes
+                then apply to the decompressed data, while progress is still reported
+                in downloaded bytes. Defaults to `None`, for no decompression.
+
         Returns:
             A chunked stream
 
         """
 
+    @overload
+    def stream_to(
+        self,
+        destination: str | Path | IO[bytes],
+        *,
+        checksum: None = None,
+    ) -> int: ...
+    @overload
+    def stream_to(
+        self,
+        destination: str | Path | IO[bytes],
+        *,
+        checksum: Literal["sha256"],
+    ) -> tuple[int, str]: ...
+    def stream_to(
+        self,
+        destination: str | Path | IO[bytes],
+        *,
+        checksum: Literal["sha256"] | None = None,
+    ) -> int | tuple[int, str]:
+        """Write the result's bytes to a local file or a file-like object.
+
+        The bytes are written in chunks as they are received, without being collected
+        in memory or passed through a Python loop.
+
+        ```py
+        import obstore as obs
+
+        resp = obs.get(store, "data.parquet")
+        nbytes, sha256 = resp.stream_to("data.parquet", checksum="sha256")
+        ```
+
+        Args:
+            destination: A path to a local file, which is created or truncated, or a
+                file-like object opened in binary write mode.
+
+        Keyword Args:
+            checksum: If `"sha256"`, also compute the SHA-256 checksum of the bytes
+                while writing them. Defaults to `None`.
+
+        Returns:
+            The number of bytes written, or, if `checksum` is provided, a tuple of the
+                number of bytes written and the hex-encoded checksum.
+
+        """
+
+    @overload
+    async def stream_to_async(
+        self,
+        destination: str | Path | IO[bytes],
+        *,
+        checksum: None = None,
+    ) -> int: ...
+    @overload
+    async def stream_to_async(
+        self,
+        destination: str | Path | IO[bytes],
+        *,
+        checksum: Literal["sha256"],
+    ) -> tuple[int, str]: ...
+    async def stream_to_async(
+        self,
+        destination: str | Path | IO[bytes],
+        *,
+        checksum: Literal["sha256"] | None = None,
+    ) -> int | tuple[int, str]:
+        """Call `stream_to` asynchronously.
+
+        Refer to the documentation for [`stream_to`][obstore.GetResult.stream_to]. The
+        `write` method of a file-like `destination` is called synchronously.
+        """
+
+    def iter_for_response(
+        self,
+        chunk_size: int = 1024 * 1024,
+        *,
+        chunk_timeout: timedelta | None = None,
+    ) -> ResponseStream:
+        r"""Return a stream over the result's bytes for the response of a web framework.
+
+        This lets a web application proxy objects to its clients without collecting
+        them in memory first. The stream is both a sync and an async iterator of
+        [`bytes`][], so it can be passed to the streaming response of either WSGI or
+        ASGI frameworks, along with the status and headers for the object:
+
+        ```py
+        import obstore as obs
+        from fastapi.responses import StreamingResponse
+
+        @app.get("/files/{path:path}")
+        async def download(path: str):
+            resp = await obs.get_async(store, path)
+            stream = resp.iter_for_response()
+            return StreamingResponse(
+                stream, status_code=stream.status, headers=stream.headers
+            )
+        ```
+
+        ```py
+        import obstore as obs
+        from flask import Response
+
+        @app.get("/files/<path:path>")
+        def download(path: str):
+            stream = obs.get(store, path).iter_for_response()
+            return Response(stream, status=stream.status, headers=stream.headers)
+        ```
+
+        Unlike [`stream`][obstore.GetResult.stream], which waits for at least
+        `min_chunk_size` bytes, each chunk is returned as soon as any data has
+        arrived, so the client receives data as quickly as the store sends it. Data is
+        only read from the store when the server asks for the next chunk, which it
+        does as the client reads the response, so a slow client slows the download
+        rather than having it buffered in memory.
+
+        The data is read in Rust on the runtime of obstore, without holding the GIL,
+        so iterating synchronously, such as from the worker threads of a WSGI server
+        or the thread pool that ASGI frameworks run sync iterators in, doesn't block
+        other threads or an event loop. Iterating asynchronously requires a running
+        `asyncio` event loop.
+
+        The body is returned as stored, so compressed objects stay compressed, and
+        their `Content-Encoding` attribute is returned in
+        [`headers`][obstore.ResponseStream.headers] for the client to decompress them.
+
+        Args:
+            chunk_size: The maximum size in bytes of each chunk. Defaults to
+                1024\*1024 (1MB).
+
+        Keyword Args:
+            chunk_timeout: The maximum time to wait for data while producing each
+                chunk, after which the stream raises a
+                [`StreamTimeoutError`][obstore.exceptions.StreamTimeoutError]. Defaults
+                to `None`, for no limit.
+
+        Returns:
+            A stream of the result's bytes, with the status and headers for a response.
+
+        """
+
     def __aiter__(self) -> BytesStream:
         """Return a chunked stream over the result's bytes.
 
@@ -211,6 +466,9 @@ class BytesStream:
 
         To fix this, set the `timeout` parameter in the
         [`client_options`][obstore.store.ClientConfig] passed when creating the store.
+
+    Like [`GetResult`][obstore.GetResult], a stream can't be pickled and raises a
+    `TypeError` instead.
     """
 
     def __aiter__(self) -> BytesStream:
@@ -227,11 +485,65 @@ class BytesStream:
     def __next__(self) -> bytes:
         """Return the next chunk of bytes in the stream."""
 
+class ResponseStream:
+    """A stream of the bytes of an object, for the response of a web framework.
+
+    This is returned by
+    [`GetResult.iter_for_response`][obstore.GetResult.iter_for_response], and can be
+    iterated either synchronously or asynchronously.
+
+    Servers that stop iterating a response early, such as when the client
+    disconnects, call `close` on WSGI responses and `aclose` on async iterators. Both
+    abort the download of the rest of the object, which also happens once the stream
+    is garbage collected.
+
+    Like [`GetResult`][obstore.GetResult], a stream can't be pickled and raises a
+    `TypeError` instead.
+    """
+
+    @property
+    def status(self) -> int:
+        """The HTTP status of the response.
+
+        This is `206` if the result is a range of the object, and `200` otherwise.
+        """
+
+    @property
+    def headers(self) -> dict[str, str]:
+        """The HTTP headers of the response, with lowercase names.
+
+        These are `content-length`, `content-range` for a range of the object,
+        `accept-ranges`, `last-modified`, `etag` if the object has one, and the
+        `content-type`, `content-encoding`, `content-disposition`,
+        `content-language` and `cache-control` attributes of the object.
+        """
+
+    def close(self) -> None:
+        """Abort the download of the rest of the body."""
+
+    async def aclose(self) -> None:
+        """Abort the download of the rest of the body."""
+
+    def __aiter__(self) -> ResponseStream:
+        """Return `Self` as an async iterator."""
+
+    def __iter__(self) -> ResponseStream:
+        """Return `Self` as an iterator."""
+
+    async def __anext__(self) -> bytes:
+        """Return the next chunk of bytes in the stream."""
+
+    def __next__(self) -> bytes:
+        """Return the next chunk of bytes in the stream."""
+
 def get(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     options: GetOptions | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    timeout: timedelta | None = None,
 ) -> GetResult:
     """Return the bytes that are stored at the specified location.
 
@@ -239,6 +551,19 @@ def get(
         store: The ObjectStore instance to use.
         path: The path within ObjectStore to retrieve.
         options: options for accessing the file. Defaults to None.
+        progress: A [`ProgressCallback`][obstore.ProgressCallback] to report the
+            number of bytes downloaded so far. Progress is reported as the
+            [`GetResult`][obstore.GetResult] is consumed, with the total being the
+            size of the requested range. Defaults to `None`.
+        progress_interval: The minimum time between two calls of `progress`.
+            Defaults to 100 milliseconds.
+        timeout: The maximum time the call may take, including reading the body of
+            the returned [`GetResult`][obstore.GetResult]. This can only shorten the
+            timeout configured for the client of the store, so a store can be
+            configured with a long timeout for large downloads while metadata calls
+            use a short one. If the call hasn't completed by then, it is cancelled
+            and a [`TimeoutError`][obstore.exceptions.TimeoutError] is raised.
+            Defaults to `None`, for no limit beyond the client's.
 
     Returns:
         GetResult
@@ -247,23 +572,110 @@ def get(
 
 async def get_async(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     options: GetOptions | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    timeout: timedelta | None = None,
 ) -> GetResult:
     """Call `get` asynchronously.
 
     Refer to the documentation for [get][obstore.get].
     """
 
+def get_if_changed(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    etag: str | None = None,
+    last_modified: datetime | None = None,
+    *,
+    timeout: timedelta | None = None,
+) -> GetResult | None:
+    """Return the object at the specified location, unless it hasn't changed.
+
+    This is a conditional [`get`][obstore.get] that returns `None` when the store
+    responds with `304 Not Modified`, instead of raising a
+    [`NotModifiedError`][obstore.exceptions.NotModifiedError]. This suits polling
+    loops, which can pass the ETag of the last version they saw:
+
+    ```py
+    import json
+    import time
+
+    import obstore as obs
+
+    etag = None
+    while True:
+        result = obs.get_if_changed(store, "config.json", etag)
+        if result is not None:
+            etag = result.meta.e_tag
+            config = json.loads(result.bytes().to_bytes())
+        time.sleep(10)
+    ```
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to retrieve.
+        etag: Only return the object if its ETag differs from this one. Defaults to
+            `None`.
+        last_modified: Only return the object if it has been modified since this
+            time. Defaults to `None`.
+
+    Keyword Args:
+        timeout: Refer to [`get`][obstore.get].
+
+    Returns:
+        The object, whose fresh ETag is available as `result.meta.e_tag`, or `None`
+        if it hasn't changed. If neither `etag` nor `last_modified` is passed, the
+        object is always returned.
+
+    """
+
+async def get_if_changed_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    etag: str | None = None,
+    last_modified: datetime | None = None,
+    *,
+    timeout: timedelta | None = None,
+) -> GetResult | None:
+    """Call `get_if_changed` asynchronously.
+
+    Refer to the documentation for [get_if_changed][obstore.get_if_changed].
+    """
+
+@overload
+def get_range(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    start: int,
+    end: int | None = None,
+    length: int | None = None,
+    return_numpy: Literal[False] = False,
+) -> Bytes: ...
+@overload
+def get_range(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    start: int,
+    end: int | None = None,
+    length: int | None = None,
+    return_numpy: Literal[True],
+    dtype: DTypeLike = None,
+) -> NDArray[Any]: ...
 def get_range(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     start: int,
     end: int | None = None,
     length: int | None = None,
-) -> Bytes:
+    return_numpy: bool = False,
+    dtype: DTypeLike = None,
+) -> Bytes | NDArray[Any]:
     """Return the bytes that are stored at the specified location in the given byte range.
 
     If the given range is zero-length or starts after the end of the object, an error
@@ -279,21 +691,51 @@ def get_range(
         start: The start of the byte range.
         end: The end of the byte range (exclusive). Either `end` or `length` must be non-None.
         length: The number of bytes of the byte range. Either `end` or `length` must be non-None.
+        return_numpy: If `True`, return the range as a read-only NumPy array that is a
+            view over the downloaded buffer, without copying it. This requires
+            [NumPy](https://numpy.org/) to be installed. Defaults to `False`.
+        dtype: The data type of the NumPy array. The length of the range must be a
+            multiple of its item size. Can only be passed with `return_numpy=True`.
+            Defaults to `uint8`.
 
     Returns:
         A `Bytes` object implementing the Python buffer protocol, allowing
-            zero-copy access to the underlying memory provided by Rust.
+            zero-copy access to the underlying memory provided by Rust, or a
+            read-only NumPy array if `return_numpy` is `True`.
 
     """
 
+@overload
 async def get_range_async(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     start: int,
     end: int | None = None,
     length: int | None = None,
-) -> Bytes:
+    return_numpy: Literal[False] = False,
+) -> Bytes: ...
+@overload
+async def get_range_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    start: int,
+    end: int | None = None,
+    length: int | None = None,
+    return_numpy: Literal[True],
+    dtype: DTypeLike = None,
+) -> NDArray[Any]: ...
+async def get_range_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    start: int,
+    end: int | None = None,
+    length: int | None = None,
+    return_numpy: bool = False,
+    dtype: DTypeLike = None,
+) -> Bytes | NDArray[Any]:
     """Call `get_range` asynchronously.
 
     Refer to the documentation for [get_range][obstore.get_range].
@@ -301,7 +743,7 @@ async def get_range_async(
 
 def get_ranges(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     starts: Sequence[int],
     ends: Sequence[int] | None = None,
@@ -332,7 +774,7 @@ def get_ranges(
 
 async def get_ranges_async(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     *,
     starts: Sequence[int],
     ends: Sequence[int] | None = None,
@@ -342,3 +784,63 @@ async def get_ranges_async(
 
     Refer to the documentation for [get_ranges][obstore.get_ranges].
     """
+
+def read_ranges_into(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    buffers: Sequence[Buffer],
+    *,
+    starts: Sequence[int],
+    ends: Sequence[int] | None = None,
+    lengths: Sequence[int] | None = None,
+) -> list[int]:
+    """Read byte ranges of an object directly into caller-provided buffers.
+
+    This is like [`get_ranges`][obstore.get_ranges], but instead of returning a new
+    `Bytes` for each range, each range is written into the start of its buffer as its
+    data is received. This lets readers that keep preallocated buffers, such as Zarr
+    chunk decoders, avoid allocating memory for each range.
+
+    ```py
+    import obstore as obs
+
+    buffers = [bytearray(1024), bytearray(1024)]
+    obs.read_ranges_into(store, "data.bin", buffers, starts=[0, 4096])
+    ```
+
+    Ranges are combined and fetched in parallel as with `get_ranges`.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to retrieve.
+        buffers: A writable, C-contiguous buffer for each range, such as a
+            `bytearray`, a `memoryview` or a NumPy array. Each must be at least as
+            large as its range.
+
+    Other Args:
+        starts: A sequence of `int` where each offset starts.
+        ends: A sequence of `int` where each offset ends (exclusive). At most one of
+            `ends` or `lengths` may be non-None.
+        lengths: A sequence of `int` with the number of bytes of each byte range. At
+            most one of `ends` or `lengths` may be non-None. If neither is given, each
+            range is the length of its buffer.
+
+    Returns:
+        The number of bytes written into each buffer. This is less than the length of
+            the range if it extends past the end of the object.
+
+    """
+
+async def read_ranges_into_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    buffers: Sequence[Buffer],
+    *,
+    starts: Sequence[int],
+    ends: Sequence[int] | None = None,
+    lengths: Sequence[int] | None = None,
+) -> list[int]:
+    """Call `read_ranges_into` asynchronously.
+
+    Refer to the documentation for [read_ranges_into][obstore.read_ranges_into].
+    """
@@ -1,19 +1,43 @@
+from datetime import timedelta
+from os import PathLike
+
 from ._list import ObjectMeta
 from .store import ObjectStore
 
-def head(store: ObjectStore, path: str) -> ObjectMeta:
+def head(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    headers: dict[str, str] | None = None,
+    timeout: timedelta | None = None,
+) -> ObjectMeta:
     """Return the metadata for the specified location.
 
     Args:
         store: The ObjectStore instance to use.
         path: The path within ObjectStore to retrieve.
 
+    Keyword Args:
+        headers: Extra headers to send with the request, as with `headers` in
+            [`GetOptions`][obstore.GetOptions]. Defaults to `None`.
+        timeout: The maximum time the call may take. This can only shorten the
+            timeout configured for the client of the store. If the call hasn't
+            completed by then, it is cancelled and a
+            [`TimeoutError`][obstore.exceptions.TimeoutError] is raised. Defaults to
+            `None`, for no limit beyond the client's.
+
     Returns:
         ObjectMeta
 
     """
 
-async def head_async(store: ObjectStore, path: str) -> ObjectMeta:
+async def head_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    headers: dict[str, str] | None = None,
+    timeout: timedelta | None = None,
+) -> ObjectMeta:
     """Call `head` asynchronously.
 
     Refer to the documentation for [head][obstore.head].
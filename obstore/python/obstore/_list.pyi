@@ -1,20 +1,85 @@
-# ruff: noqa: A001, UP006, UP035
+# ruff: noqa: A001, A002, UP006, UP035
 
 import sys
-from datetime import datetime
-from typing import Generic, List, Literal, TypedDict, TypeVar, overload
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import Any, Generic, List, Literal, TypedDict, TypeVar, overload
 
 from arro3.core import RecordBatch, Table
 
 from ._store import ObjectStore
 
 if sys.version_info >= (3, 11):
-    from typing import Self
+    from typing import NotRequired, Self
 else:
-    from typing_extensions import Self
+    from typing_extensions import NotRequired, Self
 
-class ObjectMeta(TypedDict):
-    """The metadata that describes an object."""
+class ObjectMeta:
+    """The metadata that describes an object.
+
+    This is returned by [`head`][obstore.head] and [`list`][obstore.list], and is
+    available as [`GetResult.meta`][obstore.GetResult.meta].
+
+    Instances are immutable, compare equal when all of their fields are equal, and
+    can be pickled. For code written against earlier versions of obstore, which
+    returned a `dict`, fields can also be looked up by key, as in `meta["path"]`. To
+    return plain `dict`s instead, call
+    [`set_object_meta_dicts(True)`][obstore.runtime.set_object_meta_dicts].
+    """
+
+    def __init__(
+        self,
+        path: str,
+        last_modified: datetime,
+        size: int,
+        *,
+        e_tag: str | None = None,
+        version: str | None = None,
+        metadata: dict[str, str] | None = None,
+    ) -> None: ...
+    def __getitem__(self, key: str) -> Any:
+        """Look up a field by name.
+
+        Raises:
+            KeyError: if `key` isn't a field, or is `"metadata"` and the metadata
+                wasn't requested.
+
+        """
+    def __eq__(self, other: object) -> bool: ...
+    @property
+    def path(self) -> str:
+        """The full path to the object."""
+    @property
+    def last_modified(self) -> datetime:
+        """The last modified time."""
+    @property
+    def size(self) -> int:
+        """The size in bytes of the object."""
+    @property
+    def e_tag(self) -> str | None:
+        """The unique identifier for the object.
+
+        <https://datatracker.ietf.org/doc/html/rfc9110#name-etag>
+        """
+    @property
+    def version(self) -> str | None:
+        """A version indicator for this object."""
+    @property
+    def metadata(self) -> dict[str, str] | None:
+        """User-defined metadata of the object.
+
+        This is only set in the results of [`list`][obstore.list] with
+        `include_metadata=True`, and is `None` otherwise.
+        """
+    def to_dict(self) -> ObjectMetaDict:
+        """Convert to the `dict` returned by earlier versions of obstore."""
+
+class ObjectMetaDict(TypedDict):
+    """The metadata that describes an object, as a `dict`.
+
+    This is returned instead of [`ObjectMeta`][obstore.ObjectMeta] after calling
+    [`set_object_meta_dicts(True)`][obstore.runtime.set_object_meta_dicts].
+    """
 
     path: str
     """The full path to the object"""
@@ -33,6 +98,32 @@ class ObjectMeta(TypedDict):
     version: str | None
     """A version indicator for this object"""
 
+    metadata: NotRequired[dict[str, str]]
+    """User-defined metadata of the object.
+
+    This is only present in the results of [`list`][obstore.list] with
+    `include_metadata=True`.
+    """
+
+class ListFilter(TypedDict, total=False):
+    """Predicates on object metadata for filtering the results of
+    [`list`][obstore.list].
+
+    An object is only returned if it satisfies every predicate that is set.
+    """  # noqa: D205
+
+    min_size: int
+    """Only return objects with a size in bytes of at least this value."""
+
+    max_size: int
+    """Only return objects with a size in bytes of at most this value."""
+
+    modified_after: datetime
+    """Only return objects last modified after this time."""
+
+    modified_before: datetime
+    """Only return objects last modified before this time."""
+
 ListChunkType = TypeVar("ListChunkType", List[ObjectMeta], RecordBatch, Table)  # noqa: PYI001
 """The data structure used for holding list results.
 
@@ -41,22 +132,64 @@ for improved performance when listing large buckets, you can pass `return_arrow=
 Then an Arrow `RecordBatch` will be returned instead.
 """
 
-class ListResult(TypedDict, Generic[ListChunkType]):
+class ListResult(Generic[ListChunkType]):
     """Result of a list call.
 
     Includes objects, prefixes (directories) and a token for the next set of results.
     Individual result sets may be limited to 1,000 objects based on the underlying
     object storage's limitations.
 
-    This implements [`obstore.ListResult`][].
+    Fields can also be looked up by key, as in `result["objects"]`, for code written
+    against earlier versions of obstore, which returned a `dict`.
+
+    This implements the [Arrow PyCapsule
+    Interface](https://arrow.apache.org/docs/format/CDataInterface/PyCapsuleInterface.html),
+    exporting the objects as a struct array with one row per object, regardless of
+    `return_arrow`.
     """
 
+    def __getitem__(self, key: Literal["common_prefixes", "objects"]) -> Any: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __arrow_c_array__(
+        self,
+        requested_schema: object | None = None,
+    ) -> tuple[object, object]: ...
+    @property
+    def common_prefixes(self) -> List[str]:
+        """Prefixes that are common (like directories)."""
+    @property
+    def objects(self) -> ListChunkType:
+        """Object metadata for the listing."""
+    def to_arrow(self) -> RecordBatch:
+        """Convert the objects to an Arrow `RecordBatch`.
+
+        This requires [`arro3-core`](https://pypi.org/project/arro3-core/) to be
+        installed.
+        """
+    def to_dict(self) -> ListResultDict[ListChunkType]:
+        """Convert to the `dict` returned by earlier versions of obstore."""
+
+class ListResultDict(TypedDict, Generic[ListChunkType]):
+    """Result of a list call, as a `dict`."""
+
     common_prefixes: List[str]
     """Prefixes that are common (like directories)"""
 
     objects: ListChunkType
     """Object metadata for the listing"""
 
+class ListPage(TypedDict):
+    """A single page of results from [`list_page`][obstore.list_page]."""
+
+    objects: List[ObjectMeta]
+    """Object metadata for this page, in lexicographic order of path."""
+
+    next_start_after: str | None
+    """The key to pass as `start_after` to fetch the next page.
+
+    This is `None` if there are no more results.
+    """
+
 class ListStream(Generic[ListChunkType]):
     """A stream of [ObjectMeta][obstore.ObjectMeta] that can be polled in a sync or
     async fashion.
@@ -64,6 +197,15 @@ class ListStream(Generic[ListChunkType]):
     This implements [`obstore.ListStream`][].
     """  # noqa: D205
 
+    @property
+    def invalid_keys(self) -> List[str]:
+        """The keys of the objects skipped or converted lossily so far.
+
+        This is only populated when `on_invalid_key` is `"skip"` or `"lossy"` in the
+        call to [`list`][obstore.list]. Keys that aren't valid UTF-8 are reported
+        percent-encoded.
+        """
+
     def __aiter__(self) -> Self:
         """Return `Self` as an async iterator."""
 
@@ -90,30 +232,88 @@ class ListStream(Generic[ListChunkType]):
     def __next__(self) -> ListChunkType:
         """Return the next chunk of ObjectMeta in the stream."""
 
+    def __arrow_c_stream__(self, requested_schema: object | None = None) -> object:
+        """Export the remainder of the stream via the [Arrow PyCapsule
+        Interface](https://arrow.apache.org/docs/dev/format/CDataInterface/PyCapsuleInterface.html).
+
+        This allows any Arrow-compatible library, such as DuckDB, polars, or pyarrow,
+        to lazily consume the listing without first materializing it. Batches are
+        only fetched from the store as the consumer requests them, and each batch has
+        up to `chunk_size` rows. This is available regardless of the value of
+        `return_arrow`, and does not require `arro3-core` to be installed.
+
+        ```py
+        import pyarrow as pa
+
+        stream = obs.list(store, chunk_size=1000)
+        reader = pa.RecordBatchReader.from_stream(stream)
+        ```
+
+        The stream can only be consumed once. Any list items already consumed via
+        iteration will not be included.
+
+        Args:
+            requested_schema: The schema requested by the consumer. Defaults to `None`.
+
+        Returns:
+            A PyCapsule containing an `ArrowArrayStream`.
+
+        """  # noqa: D205
+
 @overload
 def list(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
-    offset: str | None = None,
+    offset: str | PathLike[str] | None = None,
     chunk_size: int = 50,
+    stable_chunks: bool = False,
+    order_by: Literal["path", "last_modified", "size"] | None = None,
+    descending: bool = False,
+    sort_memory_limit: int = 268_435_456,
+    glob: str | None = None,
+    filter: ListFilter | None = None,
+    on_invalid_key: Literal["error", "skip", "lossy"] = "error",
+    include_metadata: bool = False,
+    timeout: timedelta | None = None,
+    chunk_timeout: timedelta | None = None,
     return_arrow: Literal[True],
 ) -> ListStream[RecordBatch]: ...
 @overload
 def list(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
-    offset: str | None = None,
+    offset: str | PathLike[str] | None = None,
     chunk_size: int = 50,
+    stable_chunks: bool = False,
+    order_by: Literal["path", "last_modified", "size"] | None = None,
+    descending: bool = False,
+    sort_memory_limit: int = 268_435_456,
+    glob: str | None = None,
+    filter: ListFilter | None = None,
+    on_invalid_key: Literal["error", "skip", "lossy"] = "error",
+    include_metadata: bool = False,
+    timeout: timedelta | None = None,
+    chunk_timeout: timedelta | None = None,
     return_arrow: Literal[False] = False,
 ) -> ListStream[List[ObjectMeta]]: ...
 def list(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
-    offset: str | None = None,
+    offset: str | PathLike[str] | None = None,
     chunk_size: int = 50,
+    stable_chunks: bool = False,
+    order_by: Literal["path", "last_modified", "size"] | None = None,
+    descending: bool = False,
+    sort_memory_limit: int = 268_435_456,
+    glob: str | None = None,
+    filter: ListFilter | None = None,
+    on_invalid_key: Literal["error", "skip", "lossy"] = "error",
+    include_metadata: bool = False,
+    timeout: timedelta | None = None,
+    chunk_timeout: timedelta | None = None,
     return_arrow: bool = False,
 ) -> ListStream[RecordBatch] | ListStream[List[ObjectMeta]]:
     """List all the objects with the given prefix.
@@ -173,9 +373,15 @@ def list(
     batch = stream.collect()
     ```
 
+    Order the listing, e.g. to process the largest objects first:
+
+    ```py
+    stream = obs.list(store, order_by="size", descending=True)
+    ```
+
     !!! note
         The order of returned [`ObjectMeta`][obstore.ObjectMeta] is not
-        guaranteed
+        guaranteed, unless `order_by` or `stable_chunks=True` is passed.
 
     !!! note
         There is no async version of this method, because `list` is not async under the
@@ -194,6 +400,78 @@ def list(
             [`collect`][obstore.ListStream.collect] and
             [`collect_async`][obstore.ListStream.collect_async] methods of
             `ListStream`.
+        stable_chunks: If `True`, emit the listing sorted by path, so that the
+            boundaries of each chunk depend only on the set of keys and `chunk_size`,
+            not on the order or pacing of responses from the store. This makes it
+            meaningful to compare checksums of listings across runs, at the cost of
+            reading the whole listing before the first chunk is returned. This is the
+            same as `order_by="path"`, so at most `sort_memory_limit` bytes of objects
+            are held in memory, with the rest spilled to temporary files as described
+            below. Defaults to `False`.
+        order_by: Sort the listing by `"path"`, `"last_modified"` or `"size"`, with
+            ties broken by path. Defaults to `None`, which returns objects in the
+            order of the store, usually lexicographic order of path.
+
+            The whole listing is read before the first chunk is returned. Listings
+            larger than `sort_memory_limit` are sorted in runs that are spilled to
+            temporary files in the system's temporary directory, and merged as the
+            stream is read, so that listings of millions of objects can be sorted
+            without holding them in memory. The files are removed once the stream is
+            exhausted or dropped.
+        descending: Whether to sort in descending order. Requires `order_by`.
+            Defaults to `False`.
+        sort_memory_limit: The approximate number of bytes of objects that sorting
+            holds in memory before spilling to temporary files. Defaults to 256 MiB.
+        glob: A glob pattern matched against the full path of each object, e.g.
+            `"**/*.parquet"`. `*` does not match across `/`, while `**` matches any
+            number of path segments. Defaults to `None`.
+        filter: Predicates on the size and last modified time of each object. See
+            [`ListFilter`][obstore.ListFilter]. Defaults to `None`.
+
+            Both `glob` and `filter` are applied in Rust as the stream is consumed,
+            so objects that don't match are never converted to Python. Only the
+            leading segments of `glob` without wildcards narrow the listing itself,
+            e.g. `"data/2024/*.parquet"` lists `"data/2024"`, so the store still has
+            to list every object under that part of the pattern.
+        on_invalid_key: How to handle an object whose key can't be represented as a
+            path, for example because it isn't valid UTF-8. Defaults to `"error"`.
+
+            - `"error"`: raise an error, ending the listing.
+            - `"skip"`: omit the object.
+            - `"lossy"`: return the object with any invalid bytes of its key replaced
+              with U+FFFD. Since its other metadata can't be read, its `size` is `0`
+              and its `last_modified` is the Unix epoch.
+
+            With `"skip"` or `"lossy"`, the original keys of these objects are
+            recorded in [`ListStream.invalid_keys`][obstore.ListStream.invalid_keys],
+            so a single bad object doesn't break a large inventory job.
+
+            !!! warning
+                Only [`LocalStore`][obstore.store.LocalStore] reports each invalid
+                object separately. Stores that list in pages, such as
+                [`S3Store`][obstore.store.S3Store], fail on the whole page containing
+                an invalid key, which also ends the listing. With `"skip"` or
+                `"lossy"`, the listing then ends early without raising, so check
+                `invalid_keys` before relying on the listing being complete.
+        include_metadata: If `True`, return the user-defined metadata of each object
+            in its `metadata` key, or in a `metadata` map column when `return_arrow`
+            is `True`. This avoids a separate request per object to read metadata.
+            Defaults to `False`.
+
+            This is only supported for [`AzureStore`][obstore.store.AzureStore],
+            whose List Blobs API can return metadata along with each object. Other
+            stores raise a `ValueError`. It can't be combined with `offset` or
+            `timeout`.
+        timeout: The maximum time the listing may take, including iterating over the
+            returned stream. This can only shorten the timeout configured for the
+            client of the store. If the listing hasn't completed by then, the stream
+            raises a [`TimeoutError`][obstore.exceptions.TimeoutError]. Defaults to
+            `None`, for no limit beyond the client's.
+        chunk_timeout: The maximum time to wait for each object while filling a chunk
+            of the returned stream. If some objects were received in that time, they
+            are returned as a smaller chunk. Otherwise the stream raises a
+            [`StreamTimeoutError`][obstore.exceptions.StreamTimeoutError], after which
+            iteration may be retried. Defaults to `None`, for no limit.
         return_arrow: If `True`, return each batch of list items as an Arrow
             `RecordBatch`, not as a list of Python `dict`s. Arrow removes serialization
             overhead between Rust and Python and so this can be significantly faster for
@@ -209,20 +487,20 @@ def list(
 @overload
 def list_with_delimiter(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
     return_arrow: Literal[True],
 ) -> ListResult[Table]: ...
 @overload
 def list_with_delimiter(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
     return_arrow: Literal[False] = False,
 ) -> ListResult[List[ObjectMeta]]: ...
 def list_with_delimiter(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
     return_arrow: bool = False,
 ) -> ListResult[Table] | ListResult[List[ObjectMeta]]:
@@ -260,20 +538,20 @@ def list_with_delimiter(
 @overload
 async def list_with_delimiter_async(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
     return_arrow: Literal[True],
 ) -> ListResult[Table]: ...
 @overload
 async def list_with_delimiter_async(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
     return_arrow: Literal[False] = False,
 ) -> ListResult[List[ObjectMeta]]: ...
 async def list_with_delimiter_async(
     store: ObjectStore,
-    prefix: str | None = None,
+    prefix: str | PathLike[str] | None = None,
     *,
     return_arrow: bool = False,
 ) -> ListResult[Table] | ListResult[List[ObjectMeta]]:
@@ -282,3 +560,150 @@ async def list_with_delimiter_async(
     Refer to the documentation for
     [list_with_delimiter][obstore.list_with_delimiter].
     """
+
+def list_page(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    max_keys: int = 1000,
+    start_after: str | PathLike[str] | None = None,
+) -> ListPage:
+    """List a single page of objects with the given prefix.
+
+    Unlike [`list`][obstore.list], this doesn't require keeping a stream alive between
+    pages, so it's well suited for paginated UIs, where each page may be requested in a
+    separate web request. Pass the returned `next_start_after` as `start_after` to
+    fetch the following page.
+
+    ```py
+    import obstore as obs
+
+    page = obs.list_page(store, "data/", max_keys=100)
+    while page["next_start_after"] is not None:
+        page = obs.list_page(
+            store, "data/", max_keys=100, start_after=page["next_start_after"]
+        )
+    ```
+
+    Results are always returned in lexicographic order of path.
+    [`S3Store`][obstore.store.S3Store], [`GCSStore`][obstore.store.GCSStore],
+    [`AzureStore`][obstore.store.AzureStore] and
+    [`MemoryStore`][obstore.store.MemoryStore] list in this order natively, in which
+    case only as many objects as needed are read from the store. For any other store,
+    such as [`LocalStore`][obstore.store.LocalStore] or a store wrapping another
+    store, the first page lists every object under `prefix` and keeps the sorted
+    listing in memory. The following pages of the same store object and prefix are
+    served from that listing for up to 5 minutes, so they don't list the store again,
+    but they also don't include objects written or deleted since the first page. The
+    listings of up to 16 stores and prefixes are kept.
+
+    Args:
+        store: The ObjectStore instance to use.
+        prefix: The prefix within ObjectStore to use for listing. Defaults to None.
+
+    Keyword Args:
+        max_keys: The maximum number of objects to return. Defaults to 1000.
+        start_after: If provided, only return objects with a path greater than
+            `start_after`. Defaults to `None`.
+
+    Returns:
+        ListPage
+
+    """
+
+async def list_page_async(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    max_keys: int = 1000,
+    start_after: str | PathLike[str] | None = None,
+) -> ListPage:
+    """Call `list_page` asynchronously.
+
+    Refer to the documentation for [list_page][obstore.list_page].
+    """
+
+@overload
+def expand_paths(
+    store: ObjectStore,
+    pattern: str,
+    *,
+    return_arrow: Literal[True],
+    max_concurrency: int = 12,
+) -> Table: ...
+@overload
+def expand_paths(
+    store: ObjectStore,
+    pattern: str,
+    *,
+    return_arrow: Literal[False] = False,
+    max_concurrency: int = 12,
+) -> List[ObjectMeta]: ...
+def expand_paths(
+    store: ObjectStore,
+    pattern: str,
+    *,
+    return_arrow: bool = False,
+    max_concurrency: int = 12,
+) -> Table | List[ObjectMeta]:
+    """Find the objects whose paths match a glob pattern.
+
+    `*` matches any characters within a path segment, `**` matches any number of path
+    segments, `?` matches a single character, and `[...]` matches one of a set of
+    characters, as with the `glob` parameter of [`list`][obstore.list].
+
+    ```py
+    import obstore as obs
+
+    metas = obs.expand_paths(store, "data/year=*/month=0[1-6]/*.parquet")
+    ```
+
+    Unlike filtering the output of [`list`][obstore.list], only the directories that
+    can contain matches are listed. The leading segments of the pattern without
+    wildcards are never listed, and the directories matching each following segment
+    are listed concurrently, one level at a time. Once a `**` segment is reached,
+    everything below the directories matched so far is listed.
+
+    Args:
+        store: The ObjectStore instance to use.
+        pattern: The glob pattern matched against the full path of each object.
+
+    Keyword Args:
+        return_arrow: If `True`, return the matching objects as an Arrow `Table`, not
+            as a list of Python `dict`s. If this is `True`, the `arro3-core` Python
+            package must be installed. Defaults to `False`.
+        max_concurrency: The maximum number of directories to list concurrently.
+            Defaults to `12`.
+
+    Returns:
+        The metadata of the matching objects, in lexicographic order of path.
+
+    """
+
+@overload
+async def expand_paths_async(
+    store: ObjectStore,
+    pattern: str,
+    *,
+    return_arrow: Literal[True],
+    max_concurrency: int = 12,
+) -> Table: ...
+@overload
+async def expand_paths_async(
+    store: ObjectStore,
+    pattern: str,
+    *,
+    return_arrow: Literal[False] = False,
+    max_concurrency: int = 12,
+) -> List[ObjectMeta]: ...
+async def expand_paths_async(
+    store: ObjectStore,
+    pattern: str,
+    *,
+    return_arrow: bool = False,
+    max_concurrency: int = 12,
+) -> Table | List[ObjectMeta]:
+    """Call `expand_paths` asynchronously.
+
+    Refer to the documentation for [expand_paths][obstore.expand_paths].
+    """
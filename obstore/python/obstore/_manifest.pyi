@@ -0,0 +1,52 @@
+from os import PathLike
+
+from ._store import ObjectStore
+
+def verify(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    manifest: str | PathLike[str],
+    *,
+    max_concurrency: int = 12,
+) -> None:
+    """Check an object against the integrity manifest written when it was uploaded.
+
+    Pass `manifest` to [`put`][obstore.put] to record the SHA-256 checksum of each
+    part of an upload in a sidecar manifest:
+
+    ```py
+    import obstore as obs
+
+    obs.put(store, "archive.tar", path, manifest="archive.tar.manifest.json")
+    obs.verify(store, "archive.tar", "archive.tar.manifest.json")
+    ```
+
+    Each part is fetched with a separate range request and hashed as it is
+    downloaded, so verifying a large object doesn't require holding it in memory.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore of the object to verify.
+        manifest: The path within ObjectStore of the manifest.
+
+    Keyword Args:
+        max_concurrency: The maximum number of parts to verify concurrently. Defaults
+            to 12.
+
+    Raises:
+        ValueError: if the size of the object or the checksum of any part does not
+            match the manifest, or if the manifest is invalid.
+
+    """
+
+async def verify_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    manifest: str | PathLike[str],
+    *,
+    max_concurrency: int = 12,
+) -> None:
+    """Call `verify` asynchronously.
+
+    Refer to the documentation for [verify][obstore.verify].
+    """
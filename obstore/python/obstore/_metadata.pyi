@@ -0,0 +1,154 @@
+from os import PathLike
+from typing import Literal
+
+from ._attributes import Attributes
+from ._store import AzureStore, ObjectStore, S3Store
+
+def get_attributes(store: ObjectStore, path: str | PathLike[str]) -> Attributes:
+    """Read back the attributes of an object.
+
+    This makes a single `HEAD` request, and returns the attributes that were set
+    when the object was written, such as `Content-Type` or `Cache-Control`.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to read attributes for.
+
+    Returns:
+        The attributes of the object.
+
+    """
+
+async def get_attributes_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+) -> Attributes:
+    """Call `get_attributes` asynchronously.
+
+    Refer to the documentation for [get_attributes][obstore.get_attributes].
+    """
+
+def get_tags(store: S3Store, path: str | PathLike[str]) -> dict[str, str]:
+    """Read back the tags of an object.
+
+    This is only supported for [`S3Store`][obstore.store.S3Store], as other
+    backends have no equivalent of the `GetObjectTagging` API.
+
+    Args:
+        store: The S3Store instance to use.
+        path: The path within S3Store to read tags for.
+
+    Raises:
+        ValueError: if `store` is not an `S3Store`.
+
+    Returns:
+        The tags of the object.
+
+    """
+
+async def get_tags_async(store: S3Store, path: str | PathLike[str]) -> dict[str, str]:
+    """Call `get_tags` asynchronously.
+
+    Refer to the documentation for [get_tags][obstore.get_tags].
+    """
+
+def put_attributes(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    attributes: Attributes,
+) -> None:
+    """Replace the attributes of an existing object.
+
+    On [`S3Store`][obstore.store.S3Store], [`GCSStore`][obstore.store.GCSStore] and
+    [`AzureStore`][obstore.store.AzureStore], the object is updated in place without
+    downloading it, and its tags are preserved. S3 objects larger than 5 GiB are
+    copied onto themselves in parts.
+
+    !!! warning
+        Other stores have no way to update the attributes of an object, so it is
+        downloaded and written back with the new attributes. This fails if the object
+        changed in the meantime, and any tags on the object are not preserved. This is
+        not supported by [`LocalStore`][obstore.store.LocalStore].
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore of the object to update.
+        attributes: The new attributes of the object. These replace all existing
+            attributes.
+
+    """
+
+async def put_attributes_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    attributes: Attributes,
+) -> None:
+    """Call `put_attributes` asynchronously.
+
+    Refer to the documentation for [put_attributes][obstore.put_attributes].
+    """
+
+def put_tags(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    tags: dict[str, str],
+) -> None:
+    """Replace the tags of an existing object.
+
+    On [`S3Store`][obstore.store.S3Store] and [`AzureStore`][obstore.store.AzureStore],
+    the tags are replaced in place without downloading the object. GCS has no object
+    tags, so [`GCSStore`][obstore.store.GCSStore] raises `NotSupportedError`.
+
+    !!! warning
+        Other stores have no way to update the tags of an object, so it is downloaded
+        and written back with the new tags. This fails if the object changed in the
+        meantime. The existing attributes of the object are preserved.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore of the object to update.
+        tags: The new tags of the object. These replace all existing tags.
+
+    """
+
+async def put_tags_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    tags: dict[str, str],
+) -> None:
+    """Call `put_tags` asynchronously.
+
+    Refer to the documentation for [put_tags][obstore.put_tags].
+    """
+
+def set_tier(
+    store: AzureStore,
+    path: str | PathLike[str],
+    tier: Literal["Hot", "Cool", "Cold", "Archive"],
+) -> None:
+    """Set the access tier of an existing block blob.
+
+    This is only supported for [`AzureStore`][obstore.store.AzureStore]. To set the tier
+    when writing an object, pass the `"Access-Tier"` attribute to
+    [`put`][obstore.put] instead.
+
+    !!! note
+        Moving a blob to the `"Archive"` tier takes it offline: it must be rehydrated
+        by setting another tier before it can be read again, which can take hours.
+
+    Args:
+        store: The AzureStore instance to use.
+        path: The path within AzureStore of the blob to update.
+        tier: The new access tier of the blob. This is case-insensitive.
+
+    """
+
+async def set_tier_async(
+    store: AzureStore,
+    path: str | PathLike[str],
+    tier: Literal["Hot", "Cool", "Cold", "Archive"],
+) -> None:
+    """Call `set_tier` asynchronously.
+
+    Refer to the documentation for [set_tier][obstore.set_tier].
+    """
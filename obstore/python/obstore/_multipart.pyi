@@ -0,0 +1,160 @@
+import sys
+from os import PathLike
+from typing import TypedDict
+
+from ._put import PutResult
+from .store import AzureStore, GCSStore, MemoryStore, S3Store
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+
+MultipartCapableStore: TypeAlias = AzureStore | GCSStore | MemoryStore | S3Store
+"""ObjectStore instances that expose explicit control over multipart uploads."""
+
+class MultipartUploadState(TypedDict):
+    """The serializable state of an in-progress multipart upload.
+
+    This only contains JSON-compatible values, so it can be persisted with
+    [`json.dumps`][json.dumps] and later passed to
+    [`resume_multipart_upload`][obstore.resume_multipart_upload].
+    """
+
+    path: str
+    """The path of the object being uploaded."""
+
+    upload_id: str
+    """The identifier assigned to this upload by the object store."""
+
+    chunk_size: int
+    """The size in bytes of every part except the last."""
+
+    parts: list[str]
+    """The identifiers of the parts that have been uploaded, in order."""
+
+def create_multipart_upload(
+    store: MultipartCapableStore,
+    path: str | PathLike[str],
+    *,
+    chunk_size: int = 5242880,
+) -> MultipartUpload:
+    """Start a new multipart upload at the specified location.
+
+    Unlike [`put`][obstore.put], the returned upload can be serialized at any time with
+    [`MultipartUpload.state`][obstore.MultipartUpload.state] and resumed in a later
+    process with [`resume_multipart_upload`][obstore.resume_multipart_upload].
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore for where to save the file.
+
+    Keyword Args:
+        chunk_size: The size of each uploaded part. Must be at least 5 MiB, the minimum
+            size of all parts except the last on S3 and GCS. Defaults to 5 MiB.
+
+    Returns:
+        MultipartUpload
+
+    """
+
+async def create_multipart_upload_async(
+    store: MultipartCapableStore,
+    path: str | PathLike[str],
+    *,
+    chunk_size: int = 5242880,
+) -> AsyncMultipartUpload:
+    """Call `create_multipart_upload` asynchronously, returning an upload with asynchronous operations.
+
+    Refer to the documentation for
+    [create_multipart_upload][obstore.create_multipart_upload].
+    """
+
+def resume_multipart_upload(
+    store: MultipartCapableStore,
+    state: MultipartUploadState,
+) -> MultipartUpload:
+    """Resume a multipart upload from a previously serialized state.
+
+    Only complete parts are recorded in the state, so any data that was written but not
+    yet uploaded must be written again. Seek the source to
+    [`bytes_uploaded`][obstore.MultipartUpload.bytes_uploaded] before continuing to
+    write.
+
+    Args:
+        store: The ObjectStore instance to use. This must refer to the same store that
+            the upload was created with.
+        state: The state returned by
+            [`MultipartUpload.state`][obstore.MultipartUpload.state].
+
+    Returns:
+        MultipartUpload
+
+    """
+
+def resume_multipart_upload_async(
+    store: MultipartCapableStore,
+    state: MultipartUploadState,
+) -> AsyncMultipartUpload:
+    """Resume a multipart upload with **asynchronous** operations.
+
+    Refer to the documentation for
+    [resume_multipart_upload][obstore.resume_multipart_upload].
+    """
+
+class MultipartUpload:
+    """An in-progress multipart upload with synchronous operations.
+
+    Written data is buffered until `chunk_size` bytes are available, at which point it
+    is uploaded as a single part.
+    """
+
+    def abort(self) -> None:
+        """Abort the upload, discarding any parts that have already been uploaded."""
+    @property
+    def bytes_uploaded(self) -> int:
+        """The number of bytes that have been uploaded as complete parts."""
+    @property
+    def chunk_size(self) -> int:
+        """The size in bytes of every part except the last."""
+    def complete(self) -> PutResult:
+        """Upload any remaining buffered data and complete the upload."""
+    @property
+    def path(self) -> str:
+        """The path of the object being uploaded."""
+    def state(self) -> MultipartUploadState:
+        """Return the serializable state of this upload."""
+    @property
+    def upload_id(self) -> str:
+        """The identifier assigned to this upload by the object store."""
+    def write(self, buffer: bytes | Buffer, /) -> int:
+        """Write the [bytes-like object](https://docs.python.org/3/glossary.html#term-bytes-like-object), `buffer`, and return the number of bytes written."""
+
+class AsyncMultipartUpload:
+    """An in-progress multipart upload with **asynchronous** operations."""
+
+    async def abort(self) -> None:
+        """Abort the upload, discarding any parts that have already been uploaded."""
+    @property
+    def bytes_uploaded(self) -> int:
+        """The number of bytes that have been uploaded as complete parts."""
+    @property
+    def chunk_size(self) -> int:
+        """The size in bytes of every part except the last."""
+    async def complete(self) -> PutResult:
+        """Upload any remaining buffered data and complete the upload."""
+    @property
+    def path(self) -> str:
+        """The path of the object being uploaded."""
+    def state(self) -> MultipartUploadState:
+        """Return the serializable state of this upload."""
+    @property
+    def upload_id(self) -> str:
+        """The identifier assigned to this upload by the object store."""
+    async def write(self, buffer: bytes | Buffer, /) -> int:
+        """Write the [bytes-like object](https://docs.python.org/3/glossary.html#term-bytes-like-object), `buffer`, and return the number of bytes written."""
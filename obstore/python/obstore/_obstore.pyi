@@ -1,4 +1,5 @@
 from . import _store
+from ._archive import ArchiveFormat, archive, archive_async, extract, extract_async
 from ._attributes import Attribute, Attributes
 from ._buffered import (
     AsyncReadableFile,
@@ -11,35 +12,132 @@ from ._buffered import (
     open_writer_async,
 )
 from ._bytes import Bytes
+from ._compression import Compression, Decompression
+from ._concat import concat
+from ._conditional import put_if_absent, put_if_absent_async, update, update_async
 from ._copy import copy, copy_async
-from ._delete import delete, delete_async
+from ._delete import delete, delete_async, delete_prefix, delete_prefix_async
+from ._diagnostics import DiagnosticIssue, Diagnostics, diagnostics
+from ._diff import DiffEntry, diff, diff_async
+from ._download import FsyncPolicy, download, download_async
+from ._events import parse_events
+from ._filesystem import FileInfo, ObstoreFileSystem
 from ._get import (
     BytesStream,
     GetOptions,
     GetResult,
     OffsetRange,
+    ResponseStream,
     SuffixRange,
     get,
     get_async,
+    get_if_changed,
+    get_if_changed_async,
     get_range,
     get_range_async,
     get_ranges,
     get_ranges_async,
+    read_ranges_into,
+    read_ranges_into_async,
 )
 from ._head import head, head_async
 from ._list import (
     ListChunkType,
+    ListFilter,
+    ListPage,
     ListResult,
+    ListResultDict,
     ListStream,
     ObjectMeta,
+    ObjectMetaDict,
+    expand_paths,
+    expand_paths_async,
     list,  # noqa: A004
+    list_page,
+    list_page_async,
     list_with_delimiter,
     list_with_delimiter_async,
 )
-from ._put import PutMode, PutResult, UpdateVersion, put, put_async
+from ._manifest import verify, verify_async
+from ._metadata import (
+    get_attributes,
+    get_attributes_async,
+    get_tags,
+    get_tags_async,
+    put_attributes,
+    put_attributes_async,
+    put_tags,
+    put_tags_async,
+    set_tier,
+    set_tier_async,
+)
+from ._multipart import (
+    AsyncMultipartUpload,
+    MultipartCapableStore,
+    MultipartUpload,
+    MultipartUploadState,
+    create_multipart_upload,
+    create_multipart_upload_async,
+    resume_multipart_upload,
+    resume_multipart_upload_async,
+)
+from ._probe import ProbeResult, probe, probe_async
+from ._progress import ProgressCallback
+from ._put import (
+    MultipartPolicy,
+    PutMode,
+    PutResult,
+    PutResultDict,
+    UpdateVersion,
+    put,
+    put_async,
+    put_slices,
+    put_slices_async,
+)
+from ._put_dir import PutDirEntry, put_dir, put_dir_async
 from ._rename import rename, rename_async
 from ._scheme import parse_scheme
-from ._sign import HTTP_METHOD, SignCapableStore, sign, sign_async
+from ._serialize import (
+    get_json,
+    get_json_async,
+    get_pickle,
+    get_pickle_async,
+    put_json,
+    put_json_async,
+    put_pickle,
+    put_pickle_async,
+)
+from ._serve import Server, serve
+from ._resumable import (
+    resumable_upload_offset,
+    resumable_upload_offset_async,
+    start_resumable_upload,
+    start_resumable_upload_async,
+    upload_resumable_chunk,
+    upload_resumable_chunk_async,
+)
+from ._sign import (
+    HTTP_METHOD,
+    PostCondition,
+    PresignedPost,
+    SignCapableStore,
+    sign,
+    sign_async,
+    sign_post,
+    sign_post_async,
+)
+from ._tree import (
+    PrefixStats,
+    TreeNode,
+    distinct_prefixes,
+    distinct_prefixes_async,
+    stat_prefix,
+    stat_prefix_async,
+    tree,
+    tree_async,
+)
+from ._versions import ObjectVersion, VersionedStore, list_versions, list_versions_async
+from ._watch import ChangeEvent, WatchStream, watch
 
 __version__: str
 _object_store_version: str
@@ -47,54 +145,159 @@ _object_store_source: str
 
 __all__ = [
     "HTTP_METHOD",
+    "ArchiveFormat",
+    "AsyncMultipartUpload",
     "AsyncReadableFile",
     "AsyncWritableFile",
     "Attribute",
     "Attributes",
     "Bytes",
     "BytesStream",
+    "ChangeEvent",
+    "Compression",
+    "Decompression",
+    "DiagnosticIssue",
+    "Diagnostics",
+    "DiffEntry",
+    "FileInfo",
+    "FsyncPolicy",
     "GetOptions",
     "GetResult",
     "ListChunkType",
+    "ListFilter",
+    "ListPage",
     "ListResult",
+    "ListResultDict",
     "ListStream",
+    "MultipartCapableStore",
+    "MultipartPolicy",
+    "MultipartUpload",
+    "MultipartUploadState",
     "ObjectMeta",
+    "ObjectMetaDict",
+    "ObjectVersion",
+    "ObstoreFileSystem",
     "OffsetRange",
+    "PostCondition",
+    "PrefixStats",
+    "PresignedPost",
+    "ProbeResult",
+    "ProgressCallback",
+    "PutDirEntry",
     "PutMode",
     "PutResult",
+    "PutResultDict",
     "ReadableFile",
+    "ResponseStream",
+    "Server",
     "SignCapableStore",
     "SuffixRange",
+    "TreeNode",
     "UpdateVersion",
+    "VersionedStore",
+    "WatchStream",
     "WritableFile",
     "__version__",
     "_object_store_source",
     "_object_store_version",
     "_store",
+    "archive",
+    "archive_async",
+    "concat",
     "copy",
     "copy_async",
+    "create_multipart_upload",
+    "create_multipart_upload_async",
     "delete",
     "delete_async",
+    "delete_prefix",
+    "delete_prefix_async",
+    "diagnostics",
+    "diff",
+    "diff_async",
+    "distinct_prefixes",
+    "distinct_prefixes_async",
+    "download",
+    "download_async",
+    "expand_paths",
+    "expand_paths_async",
+    "extract",
+    "extract_async",
     "get",
     "get_async",
+    "get_attributes",
+    "get_attributes_async",
+    "get_if_changed",
+    "get_if_changed_async",
+    "get_json",
+    "get_json_async",
+    "get_pickle",
+    "get_pickle_async",
     "get_range",
     "get_range_async",
     "get_ranges",
     "get_ranges_async",
+    "get_tags",
+    "get_tags_async",
     "head",
     "head_async",
     "list",
+    "list_page",
+    "list_page_async",
+    "list_versions",
+    "list_versions_async",
     "list_with_delimiter",
     "list_with_delimiter_async",
     "open_reader",
     "open_reader_async",
     "open_writer",
     "open_writer_async",
+    "parse_events",
     "parse_scheme",
+    "probe",
+    "probe_async",
     "put",
     "put_async",
+    "put_attributes",
+    "put_attributes_async",
+    "put_dir",
+    "put_dir_async",
+    "put_if_absent",
+    "put_if_absent_async",
+    "put_json",
+    "put_json_async",
+    "put_pickle",
+    "put_pickle_async",
+    "put_slices",
+    "put_slices_async",
+    "put_tags",
+    "put_tags_async",
+    "read_ranges_into",
+    "read_ranges_into_async",
     "rename",
     "rename_async",
+    "resumable_upload_offset",
+    "resumable_upload_offset_async",
+    "resume_multipart_upload",
+    "resume_multipart_upload_async",
+    "serve",
     "sign",
     "sign_async",
+    "sign_post",
+    "sign_post_async",
+    "set_tier",
+    "set_tier_async",
+    "start_resumable_upload",
+    "start_resumable_upload_async",
+    "stat_prefix",
+    "stat_prefix_async",
+    "tree",
+    "tree_async",
+    "update",
+    "update_async",
+    "upload_resumable_chunk",
+    "upload_resumable_chunk_async",
+    "verify",
+    "verify_async",
+    "watch",
 ]
@@ -0,0 +1,59 @@
+from datetime import datetime
+from os import PathLike
+from typing import TypedDict
+
+from ._store import ObjectStore
+
+class ProbeResult(TypedDict):
+    """The result of [`probe`][obstore.probe]."""
+
+    path: str
+    """The full path to the object."""
+
+    size: int
+    """The size in bytes of the object."""
+
+    e_tag: str | None
+    """The unique identifier for the object.
+
+    <https://datatracker.ietf.org/doc/html/rfc9110#name-etag>
+    """
+
+    last_modified: datetime
+    """The last modified time."""
+
+    content_type: str | None
+    """The `Content-Type` of the object, if known."""
+
+    supports_ranges: bool
+    """Whether the server honors range requests for this object.
+
+    This is always `False` for empty objects, which have no valid byte range.
+    """
+
+def probe(store: ObjectStore, path: str | PathLike[str]) -> ProbeResult:
+    """Inspect an object's capabilities before choosing a read strategy.
+
+    This makes a `HEAD` request for the object's metadata and content type, followed
+    by a request for the first byte of the object to check whether range requests
+    are supported. This is particularly useful with
+    [`HTTPStore`][obstore.store.HTTPStore], where an arbitrary server may not
+    support range requests, and so
+    [`get_range`][obstore.get_range] or [`open_reader`][obstore.open_reader] would
+    fail. Other errors of the range request, such as a timeout, are raised rather than
+    reported as `supports_ranges=False`.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to probe.
+
+    Returns:
+        ProbeResult
+
+    """
+
+async def probe_async(store: ObjectStore, path: str | PathLike[str]) -> ProbeResult:
+    """Call `probe` asynchronously.
+
+    Refer to the documentation for [probe][obstore.probe].
+    """
@@ -0,0 +1,37 @@
+import sys
+from collections.abc import Callable
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+ProgressCallback: TypeAlias = Callable[[int, "int | None"], object]
+"""A callback to report the progress of a transfer.
+
+The callback is called with two arguments: the number of bytes transferred so far,
+and the total number of bytes to transfer, or `None` if it is not known up front
+(for example when uploading from an iterator).
+
+The callback is called at most once per `progress_interval`, and always once more
+when the transfer completes, with the final number of bytes transferred. It is
+called from Rust while briefly holding the GIL, so it should return quickly.
+Exceptions raised by the callback are reported with
+[`sys.unraisablehook`][sys.unraisablehook] and do not interrupt the transfer.
+
+For example, to display a progress bar with [tqdm](https://tqdm.github.io/):
+
+```py
+from tqdm import tqdm
+
+import obstore as obs
+
+with tqdm(unit="B", unit_scale=True) as bar:
+
+    def progress(transferred: int, total: int | None) -> None:
+        bar.total = total
+        bar.update(transferred - bar.n)
+
+    obs.put(store, "large-file.bin", path, progress=progress)
+```
+"""
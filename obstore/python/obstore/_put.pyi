@@ -1,9 +1,21 @@
 import sys
-from collections.abc import AsyncIterable, AsyncIterator, Iterable, Iterator
+from collections.abc import (
+    AsyncIterable,
+    AsyncIterator,
+    Iterable,
+    Iterator,
+    Sequence,
+)
+from datetime import timedelta
+from os import PathLike
 from pathlib import Path
 from typing import IO, Literal, TypedDict
 
+from arro3.core import RecordBatch
+
 from ._attributes import Attributes
+from ._compression import Compression
+from ._progress import ProgressCallback
 from .store import ObjectStore
 
 if sys.version_info >= (3, 10):
@@ -49,8 +61,82 @@ If a `dict` is provided, it must meet the criteria of
 [`UpdateVersion`][obstore.UpdateVersion].
 """
 
-class PutResult(TypedDict):
-    """Result for a put request."""
+class MultipartPolicy(TypedDict, total=False):
+    """Configure when `put` uses a multipart upload, and how large each part is.
+
+    Any key that is not provided falls back to its default.
+    """
+
+    threshold: int
+    """Inputs larger than this many bytes use a multipart upload.
+
+    Defaults to `part_size`. Inputs whose size is not known up front, such as
+    iterators, always use a multipart upload.
+    """
+
+    part_size: int
+    """The size in bytes of the first part of the upload.
+
+    Defaults to the `chunk_size` passed to `put`.
+    """
+
+    part_size_growth: float
+    """Factor by which the part size grows after every tenth of `max_parts`.
+
+    This allows uploads of unknown size to grow past `part_size * max_parts`, at the
+    cost of larger parts later in the upload. Must be at least `1`. Defaults to `1`,
+    i.e. a fixed part size.
+    """
+
+    max_parts: int
+    """The maximum number of parts in the upload.
+
+    An input of known size that would need more parts raises a `ValueError` before
+    any data is uploaded. Defaults to `10000`, the limit of S3 and GCS.
+    """
+
+class PutResult:
+    """Result for a put request.
+
+    For code written against earlier versions of obstore, which returned a `dict`,
+    fields can also be looked up with `result["e_tag"]`, `get` and `keys`, and a
+    result is equal to a `dict` with the same fields.
+
+    This implements the [Arrow PyCapsule
+    Interface](https://arrow.apache.org/docs/format/CDataInterface/PyCapsuleInterface.html),
+    exporting a struct array with a single row with `e_tag` and `version` fields.
+    """
+
+    def __getitem__(self, key: Literal["e_tag", "version"]) -> str | None: ...
+    def get(self, key: str, default: object = None) -> object:
+        """Get a field by name, or `default` if there's no field with that name."""
+    def keys(self) -> list[str]:
+        """The names of the fields, as in the `dict` returned by earlier versions."""
+    def __eq__(self, other: object) -> bool: ...
+    def __arrow_c_array__(
+        self,
+        requested_schema: object | None = None,
+    ) -> tuple[object, object]: ...
+    @property
+    def e_tag(self) -> str | None:
+        """The unique identifier for the newly created object.
+
+        <https://datatracker.ietf.org/doc/html/rfc9110#name-etag>
+        """
+    @property
+    def version(self) -> str | None:
+        """A version indicator for the newly created object."""
+    def to_arrow(self) -> RecordBatch:
+        """Convert to an Arrow `RecordBatch` with a single row.
+
+        This requires [`arro3-core`](https://pypi.org/project/arro3-core/) to be
+        installed.
+        """
+    def to_dict(self) -> PutResultDict:
+        """Convert to the `dict` returned by earlier versions of obstore."""
+
+class PutResultDict(TypedDict):
+    """Result for a put request, as a `dict`."""
 
     e_tag: str | None
     """
@@ -63,7 +149,7 @@ class PutResult(TypedDict):
 
 def put(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     file: IO[bytes] | Path | bytes | Buffer | Iterator[Buffer] | Iterable[Buffer],
     *,
     attributes: Attributes | None = None,
@@ -72,6 +158,12 @@ def put(
     use_multipart: bool | None = None,
     chunk_size: int = ...,
     max_concurrency: int = 12,
+    multipart_policy: MultipartPolicy | Literal["auto"] | None = None,
+    manifest: str | PathLike[str] | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    timeout: timedelta | None = None,
+    compress: Compression | None = None,
 ) -> PutResult:
     """Save the provided bytes to the specified location.
 
@@ -121,12 +213,47 @@ def put(
         use_multipart: Whether to use a multipart upload under the hood. Defaults using a multipart upload if the length of the file is greater than `chunk_size`. When `use_multipart` is `False`, the entire input will be materialized in memory as part of the upload.
         chunk_size: The size of chunks to use within each part of the multipart upload. Defaults to 5 MB.
         max_concurrency: The maximum number of chunks to upload concurrently. Defaults to 12.
+        multipart_policy: Configure when to use a multipart upload and how to size
+            its parts. Refer to [`MultipartPolicy`][obstore.MultipartPolicy].
+
+            If `"auto"`, the part size is chosen from the size of the input, so that
+            it fits within 10,000 parts, and is never smaller than `chunk_size`. For
+            input of unknown size, the part size doubles after every 1,000 parts,
+            which allows uploads of up to ~5 TB with the default `chunk_size`.
+
+            Defaults to `None`, which uses fixed `chunk_size` parts with no limit on
+            the number of parts.
+        manifest: A path within the same store to write an integrity manifest to once
+            the upload completes. The manifest is a JSON document recording the
+            SHA-256 checksum of each part of the upload, which can be checked later
+            with [`verify`][obstore.verify]. Defaults to `None`, which doesn't write
+            a manifest.
+        progress: A [`ProgressCallback`][obstore.ProgressCallback] to report the
+            number of bytes uploaded so far. For multipart uploads, progress is
+            reported as each part completes. The total is only known for files and
+            buffers, not for iterators. Defaults to `None`.
+        progress_interval: The minimum time between two calls of `progress`.
+            Defaults to 100 milliseconds.
+        timeout: The maximum time the upload may take, including every part of a
+            multipart upload. This can only shorten the timeout configured for the
+            client of the store. If the upload hasn't completed by then, it is
+            cancelled and a [`TimeoutError`][obstore.exceptions.TimeoutError] is
+            raised. Defaults to `None`, for no limit beyond the client's.
+        compress: Compress the data in Rust as it's uploaded, with the given
+            [`Compression`][obstore.Compression]. This works for every kind of
+            `file`, including iterators, which are compressed chunk by chunk. The
+            `Content-Encoding` attribute is set to the codec, unless `attributes`
+            sets it already. Stores that don't support attributes, such as
+            `LocalStore`, store the compressed data without it. Since the compressed
+            size isn't known up front, `progress` then reports the number of
+            compressed bytes uploaded, with no total. Defaults to `None`, which
+            uploads the data as is.
 
     """
 
 async def put_async(
     store: ObjectStore,
-    path: str,
+    path: str | PathLike[str],
     file: IO[bytes]
     | Path
     | bytes
@@ -142,6 +269,12 @@ async def put_async(
     use_multipart: bool | None = None,
     chunk_size: int = ...,
     max_concurrency: int = 12,
+    multipart_policy: MultipartPolicy | Literal["auto"] | None = None,
+    manifest: str | PathLike[str] | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    timeout: timedelta | None = None,
+    compress: Compression | None = None,
 ) -> PutResult:
     """Call `put` asynchronously.
 
@@ -161,4 +294,89 @@ async def put_async(
     # A streaming upload is created to copy the file to path2
     await obs.put_async(store2, path2)
     ```
+
+    If the task awaiting `put_async` is cancelled, its requests are cancelled too, and
+    a multipart upload in progress is aborted, so that its parts don't linger in the
+    store.
+    """
+
+def put_slices(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    buffers: Sequence[Buffer],
+    *,
+    attributes: Attributes | None = None,
+    tags: dict[str, str] | None = None,
+    mode: PutMode | None = None,
+    use_multipart: bool | None = None,
+    chunk_size: int = ...,
+    max_concurrency: int = 12,
+    multipart_policy: MultipartPolicy | Literal["auto"] | None = None,
+    manifest: str | PathLike[str] | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    timeout: timedelta | None = None,
+    compress: Compression | None = None,
+) -> PutResult:
+    """Save the concatenation of multiple buffers to the specified location.
+
+    This uploads `buffers` as a single object, in order, without concatenating them
+    in Python first. The buffers are not copied: a non-multipart upload sends them
+    as one payload, and a multipart upload slices its parts out of them.
+
+    ```py
+    import obstore as obs
+
+    header = b"..."
+    body = memoryview(large_array)
+    obs.put_slices(store, "file.bin", [header, body])
+    ```
+
+    Since the size of the input is known up front, a multipart upload is used in
+    the same cases as for a single buffer passed to [`put`][obstore.put].
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore for where to save the file.
+        buffers: A sequence of objects implementing the Python [buffer
+            protocol](https://docs.python.org/3/c-api/buffer.html). Note that only
+            1-dimensional, contiguous, uint8-typed buffers are supported.
+
+    Keyword Args:
+        attributes: Refer to [`put`][obstore.put].
+        tags: Refer to [`put`][obstore.put].
+        mode: Refer to [`put`][obstore.put].
+        use_multipart: Refer to [`put`][obstore.put].
+        chunk_size: Refer to [`put`][obstore.put].
+        max_concurrency: Refer to [`put`][obstore.put].
+        multipart_policy: Refer to [`put`][obstore.put].
+        manifest: Refer to [`put`][obstore.put].
+        progress: Refer to [`put`][obstore.put].
+        progress_interval: Refer to [`put`][obstore.put].
+        timeout: Refer to [`put`][obstore.put].
+        compress: Refer to [`put`][obstore.put].
+
+    """
+
+async def put_slices_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    buffers: Sequence[Buffer],
+    *,
+    attributes: Attributes | None = None,
+    tags: dict[str, str] | None = None,
+    mode: PutMode | None = None,
+    use_multipart: bool | None = None,
+    chunk_size: int = ...,
+    max_concurrency: int = 12,
+    multipart_policy: MultipartPolicy | Literal["auto"] | None = None,
+    manifest: str | PathLike[str] | None = None,
+    progress: ProgressCallback | None = None,
+    progress_interval: timedelta = ...,
+    timeout: timedelta | None = None,
+    compress: Compression | None = None,
+) -> PutResult:
+    """Call `put_slices` asynchronously.
+
+    Refer to the documentation for [`put_slices`][obstore.put_slices].
     """
@@ -0,0 +1,108 @@
+from os import PathLike
+from pathlib import Path
+from typing import TypedDict
+
+from ._store import ObjectStore
+
+class PutDirEntry(TypedDict):
+    """An object uploaded by [`put_dir`][obstore.put_dir]."""
+
+    path: str
+    """The full path to the object."""
+
+    size: int
+    """The size in bytes of the object."""
+
+    sha256: str
+    """The hex-encoded SHA-256 checksum of the object."""
+
+    content_type: str
+    """The `Content-Type` the object was uploaded with, guessed from the extension of
+    the local file.
+
+    Files with an unrecognized extension are uploaded as
+    `application/octet-stream`.
+    """
+
+def put_dir(
+    store: ObjectStore,
+    local_dir: str | Path,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    manifest: str | PathLike[str] | None = None,
+    chunk_size: int = 5242880,
+    max_concurrency: int = 12,
+) -> list[PutDirEntry]:
+    """Upload every file under a local directory.
+
+    Each file is uploaded to its path relative to `local_dir`, joined onto `prefix`.
+    Symlinks are followed. Files are streamed to the store and hashed as they are
+    read, so memory use is bounded by `chunk_size` for each file being uploaded.
+
+    Pass `manifest` to write a JSON manifest of the uploaded objects once every file
+    has been uploaded. Consumers can read the manifest to enumerate or validate the
+    uploaded tree without listing the store:
+
+    ```py
+    import obstore as obs
+
+    obs.put_dir(store, "build/", "site", manifest="site/manifest.json")
+    ```
+
+    The manifest has the form:
+
+    ```json
+    {
+      "version": 1,
+      "prefix": "site",
+      "size": 1234,
+      "algorithm": "sha256",
+      "objects": [
+        {
+          "path": "site/index.html",
+          "size": 1234,
+          "sha256": "...",
+          "content_type": "text/html"
+        }
+      ]
+    }
+    ```
+
+    Only JSON manifests are currently supported.
+
+    Args:
+        store: The ObjectStore instance to use.
+        local_dir: The local directory to upload.
+        prefix: The prefix to upload the files under. If `None`, files are uploaded
+            relative to the root of `store`.
+
+    Keyword Args:
+        manifest: The path within ObjectStore to write a manifest of the uploaded
+            objects to. If `None`, no manifest is written. Defaults to `None`.
+        chunk_size: The size of each part of a multipart upload, and of each read from
+            a local file. Defaults to 5 MiB.
+        max_concurrency: The maximum number of files to upload concurrently. Defaults
+            to `12`.
+
+    Returns:
+        The uploaded objects, sorted by path.
+
+    Raises:
+        ValueError: if `local_dir` is not a directory, or contains a file name that is
+            not valid UTF-8.
+
+    """
+
+async def put_dir_async(
+    store: ObjectStore,
+    local_dir: str | Path,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    manifest: str | PathLike[str] | None = None,
+    chunk_size: int = 5242880,
+    max_concurrency: int = 12,
+) -> list[PutDirEntry]:
+    """Call `put_dir` asynchronously.
+
+    Refer to the documentation for [put_dir][obstore.put_dir].
+    """
@@ -1,6 +1,17 @@
+from datetime import timedelta
+from os import PathLike
+
 from ._store import ObjectStore
 
-def rename(store: ObjectStore, from_: str, to: str, *, overwrite: bool = True) -> None:
+def rename(
+    store: ObjectStore,
+    from_: str | PathLike[str],
+    to: str | PathLike[str],
+    *,
+    overwrite: bool = True,
+    multipart_threshold: int | None = None,
+    timeout: timedelta | None = None,
+) -> None:
     """Move an object from one path to another in the same object store.
 
     By default, this is implemented as a copy and then delete source. It may not check
@@ -15,15 +26,31 @@ def rename(store: ObjectStore, from_: str, to: str, *, overwrite: bool = True) -
         overwrite: If `True`, if there exists an object at the destination, it will be
             overwritten. If `False`, will return an error if the destination already has
             an object.
+        multipart_threshold: For [`S3Store`][obstore.store.S3Store], the size in
+            bytes above which an object is copied to the destination in parts with
+            `UploadPartCopy` before being deleted, rather than copied with a single
+            `CopyObject` request. This requires an extra `HEAD` request for the
+            source. Defaults to `None`, in which case objects are only copied in parts
+            if S3 rejects them as larger than the 5 GiB limit of `CopyObject`.
+
+            Each part is copied on the server, so no data passes through the client.
+            Other stores rename with their own server-side copy, and ignore this.
+        timeout: The maximum time the call may take. This can only shorten the
+            timeout configured for the client of the store. If the call hasn't
+            completed by then, it is cancelled and a
+            [`TimeoutError`][obstore.exceptions.TimeoutError] is raised. Defaults to
+            `None`, for no limit beyond the client's.
 
     """
 
 async def rename_async(
     store: ObjectStore,
-    from_: str,
-    to: str,
+    from_: str | PathLike[str],
+    to: str | PathLike[str],
     *,
     overwrite: bool = True,
+    multipart_threshold: int | None = None,
+    timeout: timedelta | None = None,
 ) -> None:
     """Call `rename` asynchronously.
 
@@ -0,0 +1,137 @@
+import sys
+from os import PathLike
+
+from .store import GCSStore
+
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+
+def start_resumable_upload(
+    store: GCSStore,
+    path: str | PathLike[str],
+    *,
+    content_type: str | None = None,
+) -> str:
+    """Initiate a resumable upload to GCS, returning its session URI.
+
+    The session URI authorizes uploading the object without the credentials of the
+    store, so it can be handed to another process or machine, which uploads the data
+    with [`upload_resumable_chunk`][obstore.upload_resumable_chunk]. If that upload is
+    interrupted, [`resumable_upload_offset`][obstore.resumable_upload_offset] gives
+    the offset to resume it from. A session expires after a week.
+
+    ```py
+    import obstore
+    from obstore.store import GCSStore
+
+    store = GCSStore("my-bucket")
+    session_uri = obstore.start_resumable_upload(store, "data.bin")
+
+    # In another worker:
+    chunk_size = 8 * 1024 * 1024
+    offset = obstore.resumable_upload_offset(session_uri) or 0
+    with open("data.bin", "rb") as f:
+        f.seek(offset)
+        while chunk := f.read(chunk_size):
+            last = len(chunk) < chunk_size
+            offset = obstore.upload_resumable_chunk(
+                session_uri, chunk, offset, last=last
+            )
+            if last:
+                break
+    ```
+
+    See <https://cloud.google.com/storage/docs/resumable-uploads>.
+
+    Args:
+        store: The GCSStore to upload to.
+        path: The path of the object within the store.
+
+    Keyword Args:
+        content_type: The content type of the object. Defaults to `None`, for GCS's
+            default.
+
+    Returns:
+        The session URI of the upload. Treat it like a credential.
+
+    """
+
+async def start_resumable_upload_async(
+    store: GCSStore,
+    path: str | PathLike[str],
+    *,
+    content_type: str | None = None,
+) -> str:
+    """Call `start_resumable_upload` asynchronously.
+
+    Refer to the documentation for
+    [start_resumable_upload][obstore.start_resumable_upload].
+    """
+
+def resumable_upload_offset(session_uri: str) -> int | None:
+    """Get the number of bytes that a resumable upload has persisted.
+
+    Args:
+        session_uri: The session URI returned by
+            [`start_resumable_upload`][obstore.start_resumable_upload].
+
+    Returns:
+        The offset to upload the next chunk at, or `None` if the upload is complete.
+
+    Raises:
+        NotFoundError: if the session has expired or was cancelled.
+
+    """
+
+async def resumable_upload_offset_async(session_uri: str) -> int | None:
+    """Call `resumable_upload_offset` asynchronously.
+
+    Refer to the documentation for
+    [resumable_upload_offset][obstore.resumable_upload_offset].
+    """
+
+def upload_resumable_chunk(
+    session_uri: str,
+    data: Buffer,
+    offset: int,
+    *,
+    last: bool = False,
+) -> int | None:
+    """Upload a chunk of a resumable upload.
+
+    Args:
+        session_uri: The session URI returned by
+            [`start_resumable_upload`][obstore.start_resumable_upload].
+        data: The bytes of the chunk. Every chunk but the last must be a non-zero
+            multiple of 256 KiB.
+        offset: The offset of the chunk in the object.
+
+    Keyword Args:
+        last: Whether this is the last chunk, which completes the upload. The last
+            chunk may be empty, to complete an upload whose size wasn't known.
+
+    Returns:
+        The number of bytes persisted so far, at which to upload the next chunk, or
+        `None` if the upload is complete. GCS may persist fewer bytes than were sent,
+        in which case the rest must be sent again.
+
+    Raises:
+        ValueError: if a chunk other than the last isn't a multiple of 256 KiB.
+        NotFoundError: if the session has expired or was cancelled.
+
+    """
+
+async def upload_resumable_chunk_async(
+    session_uri: str,
+    data: Buffer,
+    offset: int,
+    *,
+    last: bool = False,
+) -> int | None:
+    """Call `upload_resumable_chunk` asynchronously.
+
+    Refer to the documentation for
+    [upload_resumable_chunk][obstore.upload_resumable_chunk].
+    """
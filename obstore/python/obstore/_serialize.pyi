@@ -0,0 +1,168 @@
+from os import PathLike
+from typing import Any
+
+from ._attributes import Attributes
+from ._compression import Compression
+from ._put import PutResult
+from ._store import ObjectStore
+
+def put_pickle(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    obj: Any,
+    *,
+    protocol: int | None = None,
+    compression: Compression | None = None,
+    max_size: int | None = None,
+    attributes: Attributes | None = None,
+) -> PutResult:
+    """Pickle a Python object and save it to the specified location.
+
+    The object is tagged with a `Content-Type` of `application/python-pickle`, and a
+    `Content-Encoding` of the codec if compressed, on stores that support attributes.
+
+    !!! warning
+        Only unpickle data you trust. Unpickling data from an untrusted source can
+        execute arbitrary code.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore for where to save the object.
+        obj: The Python object to pickle.
+
+    Keyword Args:
+        protocol: The pickle protocol to use. Defaults to `None`, which uses
+            [`pickle.DEFAULT_PROTOCOL`][pickle.DEFAULT_PROTOCOL].
+        compression: Compress the pickled data with the given
+            [`Compression`][obstore.Compression] before uploading. Defaults to `None`.
+        max_size: The maximum size in bytes of the uploaded data, after compression.
+            Raises `ValueError` without uploading if exceeded. Defaults to `None`.
+        attributes: Provide a set of `Attributes`, which take precedence over the
+            inferred `Content-Type` and `Content-Encoding`. Defaults to `None`.
+
+    """
+
+async def put_pickle_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    obj: Any,
+    *,
+    protocol: int | None = None,
+    compression: Compression | None = None,
+    max_size: int | None = None,
+    attributes: Attributes | None = None,
+) -> PutResult:
+    """Call `put_pickle` asynchronously.
+
+    Refer to the documentation for [put_pickle][obstore.put_pickle].
+    """
+
+def get_pickle(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    max_size: int | None = None,
+) -> Any:
+    """Load a pickled Python object from the specified location.
+
+    Data compressed by [`put_pickle`][obstore.put_pickle] is decompressed
+    automatically.
+
+    !!! warning
+        Only unpickle data you trust. Unpickling data from an untrusted source can
+        execute arbitrary code.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to load from.
+
+    Keyword Args:
+        max_size: The maximum size in bytes of the object, both as stored and after
+            decompression. Raises `ValueError` if exceeded. Defaults to `None`.
+
+    """
+
+async def get_pickle_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    max_size: int | None = None,
+) -> Any:
+    """Call `get_pickle` asynchronously.
+
+    Refer to the documentation for [get_pickle][obstore.get_pickle].
+    """
+
+def put_json(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    obj: Any,
+    *,
+    compression: Compression | None = None,
+    max_size: int | None = None,
+    attributes: Attributes | None = None,
+) -> PutResult:
+    """Serialize a Python object as JSON and save it to the specified location.
+
+    The object is tagged with a `Content-Type` of `application/json`, and a
+    `Content-Encoding` of the codec if compressed, on stores that support attributes.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore for where to save the object.
+        obj: The Python object to serialize with [`json.dumps`][json.dumps].
+
+    Keyword Args:
+        compression: Compress the serialized data with the given
+            [`Compression`][obstore.Compression] before uploading. Defaults to `None`.
+        max_size: The maximum size in bytes of the uploaded data, after compression.
+            Raises `ValueError` without uploading if exceeded. Defaults to `None`.
+        attributes: Provide a set of `Attributes`, which take precedence over the
+            inferred `Content-Type` and `Content-Encoding`. Defaults to `None`.
+
+    """
+
+async def put_json_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    obj: Any,
+    *,
+    compression: Compression | None = None,
+    max_size: int | None = None,
+    attributes: Attributes | None = None,
+) -> PutResult:
+    """Call `put_json` asynchronously.
+
+    Refer to the documentation for [put_json][obstore.put_json].
+    """
+
+def get_json(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    max_size: int | None = None,
+) -> Any:
+    """Load a JSON document from the specified location.
+
+    Data compressed by [`put_json`][obstore.put_json] is decompressed automatically.
+
+    Args:
+        store: The ObjectStore instance to use.
+        path: The path within ObjectStore to load from.
+
+    Keyword Args:
+        max_size: The maximum size in bytes of the object, both as stored and after
+            decompression. Raises `ValueError` if exceeded. Defaults to `None`.
+
+    """
+
+async def get_json_async(
+    store: ObjectStore,
+    path: str | PathLike[str],
+    *,
+    max_size: int | None = None,
+) -> Any:
+    """Call `get_json` asynchronously.
+
+    Refer to the documentation for [get_json][obstore.get_json].
+    """
@@ -0,0 +1,103 @@
+import sys
+from os import PathLike
+from types import TracebackType
+
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 11):
+    from typing import Self
+else:
+    from typing_extensions import Self
+
+class Server:
+    """A running HTTP server, returned by [`serve`][obstore.serve].
+
+    The server stops once [`shutdown`][obstore.Server.shutdown] is called, once the
+    `with` block it's used in exits, or once it's garbage collected, so keep a
+    reference to it for as long as it should run.
+    """
+
+    @property
+    def address(self) -> str:
+        """The address the server listens on, as `host:port`."""
+
+    @property
+    def port(self) -> int:
+        """The port the server listens on."""
+
+    @property
+    def url(self) -> str:
+        """The URL of the root of the server, e.g. `http://127.0.0.1:8000/`."""
+
+    def shutdown(self) -> None:
+        """Stop the server, closing its open connections.
+
+        This waits until the server has stopped, and does nothing if it already has.
+        """
+
+    def __enter__(self) -> Self: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> None: ...
+
+def serve(
+    store: ObjectStore,
+    addr: str = "127.0.0.1:0",
+    *,
+    prefix: str | PathLike[str] | None = None,
+) -> Server:
+    """Serve the objects of a store over HTTP, in the background.
+
+    This lets local tools that read from URLs, such as browsers, media players and
+    debuggers, read remote objects, without downloading them first or being given
+    credentials for the store:
+
+    ```py
+    import obstore as obs
+
+    with obs.serve(store, "127.0.0.1:8000") as server:
+        # The object at "videos/clip.mp4" is served at this URL
+        print(f"{server.url}videos/clip.mp4")
+        input("Press enter to stop the server")
+    ```
+
+    Requests are served by Rust on the runtime of obstore, without holding the GIL.
+    Each `GET` or `HEAD` request for an object is a single request to the store:
+
+    - A `Range` header with a single range of bytes is passed on to the store, and
+      answered with a `206 Partial Content` response, which lets players seek
+      through media files. Multiple ranges aren't supported, and return the whole
+      object.
+    - The conditional headers `If-Match`, `If-None-Match`, `If-Modified-Since`,
+      `If-Unmodified-Since`, and `If-Range` with an ETag, are passed on to the store.
+    - The `ETag`, `Last-Modified`, and the `Content-Type`, `Content-Encoding`,
+      `Content-Disposition`, `Content-Language` and `Cache-Control` attributes of
+      the object are returned as headers.
+    - The body is streamed from the store as the client reads it.
+
+    A request for the root of the server, or for a path ending in `/`, returns an
+    HTML page listing the directories and objects directly under that path.
+
+    !!! warning
+        The server has no authentication, so anyone who can connect to it can read
+        every object in `store`, or under `prefix`. It listens on the local machine
+        by default; only pass an address reachable from other machines on networks
+        you trust.
+
+    Args:
+        store: The ObjectStore instance to serve.
+        addr: The address to listen on, as `host:port`. Defaults to
+            `"127.0.0.1:0"`, which listens on the local machine on a free port,
+            available as [`Server.port`][obstore.Server.port].
+
+    Keyword Args:
+        prefix: Only serve the objects under this prefix, at paths relative to it.
+            Defaults to `None`, which serves the whole store.
+
+    Returns:
+        The running server.
+
+    """
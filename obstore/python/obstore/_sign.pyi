@@ -1,7 +1,8 @@
 import sys
 from collections.abc import Sequence
 from datetime import timedelta
-from typing import Literal, overload
+from os import PathLike
+from typing import Literal, TypedDict, overload
 
 from .store import AzureStore, GCSStore, S3Store
 
@@ -30,21 +31,30 @@ SignCapableStore: TypeAlias = AzureStore | GCSStore | S3Store
 def sign(  # type: ignore # noqa: PGH003
     store: SignCapableStore,
     method: HTTP_METHOD,
-    paths: str,
+    paths: str | PathLike[str],
     expires_in: timedelta,
+    *,
+    headers: dict[str, str] | None = None,
+    query: dict[str, str] | None = None,
 ) -> str: ...
 @overload
 def sign(
     store: SignCapableStore,
     method: HTTP_METHOD,
-    paths: Sequence[str],
+    paths: Sequence[str | PathLike[str]],
     expires_in: timedelta,
+    *,
+    headers: dict[str, str] | None = None,
+    query: dict[str, str] | None = None,
 ) -> list[str]: ...
 def sign(
     store: SignCapableStore,
     method: HTTP_METHOD,
-    paths: str | Sequence[str],
+    paths: str | PathLike[str] | Sequence[str | PathLike[str]],
     expires_in: timedelta,
+    *,
+    headers: dict[str, str] | None = None,
+    query: dict[str, str] | None = None,
 ) -> str | list[str]:
     """Create a signed URL.
 
@@ -60,6 +70,24 @@ def sign(
         paths: The path(s) within ObjectStore to retrieve. If
         expires_in: How long the signed URL(s) should be valid.
 
+    Keyword Args:
+        headers: Headers that requests using the URL must send with exactly these
+            values, such as `{"Content-Type": "image/png"}` to only allow uploads of
+            PNG images with a signed `PUT` URL. These are signed along with the URL.
+            This is only supported for [`S3Store`][obstore.store.S3Store]. Defaults
+            to `None`, for no headers beyond the host.
+        query: Query parameters to add to the URL, signed along with it, such as
+            `{"response-content-disposition": "attachment; filename=data.csv"}` to
+            make a signed `GET` URL download the object under that name. This is
+            supported for [`S3Store`][obstore.store.S3Store] and
+            [`GCSStore`][obstore.store.GCSStore]. A `GCSStore` signs these URLs with
+            its service account key if it has one, and otherwise with the IAM
+            `signBlob` API, as for URLs without query parameters. Defaults to `None`.
+
+    Raises:
+        ValueError: if `headers` is given for a store other than an `S3Store`, or
+            `query` is given for an `AzureStore`.
+
     Returns:
         _description_
 
@@ -69,23 +97,115 @@ def sign(
 async def sign_async(
     store: SignCapableStore,
     method: HTTP_METHOD,
-    paths: str,
+    paths: str | PathLike[str],
     expires_in: timedelta,
+    *,
+    headers: dict[str, str] | None = None,
+    query: dict[str, str] | None = None,
 ) -> str: ...
 @overload
 async def sign_async(
     store: SignCapableStore,
     method: HTTP_METHOD,
-    paths: Sequence[str],
+    paths: Sequence[str | PathLike[str]],
     expires_in: timedelta,
+    *,
+    headers: dict[str, str] | None = None,
+    query: dict[str, str] | None = None,
 ) -> list[str]: ...
 async def sign_async(
     store: SignCapableStore,
     method: HTTP_METHOD,
-    paths: str | Sequence[str],
+    paths: str | PathLike[str] | Sequence[str | PathLike[str]],
     expires_in: timedelta,
+    *,
+    headers: dict[str, str] | None = None,
+    query: dict[str, str] | None = None,
 ) -> str | list[str]:
     """Call `sign` asynchronously.
 
     Refer to the documentation for [sign][obstore.sign].
     """
+
+PostCondition: TypeAlias = (
+    dict[str, str]
+    | tuple[Literal["eq", "starts-with"], str, str]
+    | tuple[Literal["content-length-range"], int, int]
+)
+"""A condition of an S3 POST policy.
+
+This is one of:
+
+- A dict of form fields that must have exactly these values, such as
+  `{"Content-Type": "image/png"}`. These fields are also included in the returned
+  [`fields`][obstore.PresignedPost.fields].
+- `("eq", "$field", value)` or `("starts-with", "$field", prefix)`, such as
+  `("starts-with", "$Content-Type", "image/")`.
+- `("content-length-range", min, max)`, limiting the size in bytes of the upload.
+
+Lists are accepted in place of tuples.
+"""
+
+class PresignedPost(TypedDict):
+    """A presigned POST upload, as returned by [`sign_post`][obstore.sign_post]."""
+
+    url: str
+    """The URL to send the form to."""
+
+    fields: dict[str, str]
+    """The fields to include in the form, before the `file` field with the content
+    of the upload."""
+
+def sign_post(
+    store: S3Store,
+    path: str | PathLike[str],
+    expires_in: timedelta,
+    conditions: Sequence[PostCondition] | None = None,
+) -> PresignedPost:
+    """Create a presigned POST policy for uploading an object from a browser.
+
+    This lets a web app hand out a form that uploads directly to S3, without
+    exposing the store's credentials. The upload is rejected unless its form
+    satisfies every condition, which can for example limit its size or content type.
+
+    ```py
+    post = obs.sign_post(
+        store,
+        "uploads/avatar.png",
+        timedelta(minutes=10),
+        conditions=[
+            {"Content-Type": "image/png"},
+            ("content-length-range", 0, 1024 * 1024),
+        ],
+    )
+    requests.post(post["url"], data=post["fields"], files={"file": data})
+    ```
+
+    This is only supported for [`S3Store`][obstore.store.S3Store], as other backends
+    have no equivalent of POST policies.
+
+    Args:
+        store: The S3Store instance to use.
+        path: The path within S3Store that the object is uploaded to.
+        expires_in: How long the policy should be valid.
+        conditions: Additional conditions that the form must satisfy. The bucket,
+            the path and the signing fields are always conditions of the policy.
+
+    Raises:
+        ValueError: if `store` is not an `S3Store`, or a condition is invalid.
+
+    Returns:
+        The URL and fields of the form.
+
+    """
+
+async def sign_post_async(
+    store: S3Store,
+    path: str | PathLike[str],
+    expires_in: timedelta,
+    conditions: Sequence[PostCondition] | None = None,
+) -> PresignedPost:
+    """Call `sign_post` asynchronously.
+
+    Refer to the documentation for [sign_post][obstore.sign_post].
+    """
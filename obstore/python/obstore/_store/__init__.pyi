@@ -1,9 +1,12 @@
 # TODO: move to reusable types package
 import sys
-from collections.abc import Callable
+from collections.abc import Callable, Mapping, MutableMapping, Sequence
+from datetime import timedelta
+from os import PathLike
 from pathlib import Path
-from typing import Any, overload
+from typing import Any, Literal, Protocol, overload
 
+from ._aws import S3Compatibility as S3Compatibility
 from ._aws import S3Config as S3Config
 from ._aws import S3Credential as S3Credential
 from ._aws import S3CredentialProvider as S3CredentialProvider
@@ -16,13 +19,21 @@ from ._azure import AzureCredentialProvider as AzureCredentialProvider
 from ._azure import AzureSASToken as AzureSASToken
 from ._azure import AzureStore as AzureStore
 from ._client import ClientConfig as ClientConfig
+from ._client import Resolver as Resolver
+from ._credentials import ChainCredentialProvider as ChainCredentialProvider
+from ._credentials import CredentialProviderHandle as CredentialProviderHandle
 from ._gcs import GCSConfig as GCSConfig
 from ._gcs import GCSCredential as GCSCredential
 from ._gcs import GCSCredentialProvider as GCSCredentialProvider
 from ._gcs import GCSStore as GCSStore
+from ._hdfs import HdfsStore as HdfsStore
 from ._http import HTTPStore as HTTPStore
+from ._limit import LimitConfig as LimitConfig
 from ._retry import BackoffConfig as BackoffConfig
 from ._retry import RetryConfig as RetryConfig
+from ._retry import RetryEvent as RetryEvent
+from ._sftp import SFTPStore as SFTPStore
+from ._throttle import ThrottleConfig as ThrottleConfig
 
 if sys.version_info >= (3, 10):
     from typing import TypeAlias
@@ -34,6 +45,11 @@ if sys.version_info >= (3, 11):
 else:
     from typing_extensions import Self, Unpack
 
+if sys.version_info >= (3, 12):
+    from collections.abc import Buffer
+else:
+    from typing_extensions import Buffer
+
 @overload
 def from_url(
     url: str,
@@ -41,7 +57,15 @@ def from_url(
     config: S3Config | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
-    credential_provider: S3CredentialProvider | None = None,
+    limit_config: LimitConfig | None = None,
+    compatibility: S3Compatibility | None = None,
+    credential_provider: S3CredentialProvider
+    | str
+    | ChainCredentialProvider
+    | list[S3CredentialProvider | str]
+    | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Unpack[S3Config],
 ) -> ObjectStore: ...
 @overload
@@ -51,7 +75,14 @@ def from_url(
     config: GCSConfig | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
-    credential_provider: GCSCredentialProvider | None = None,
+    limit_config: LimitConfig | None = None,
+    credential_provider: GCSCredentialProvider
+    | str
+    | ChainCredentialProvider
+    | list[GCSCredentialProvider | str]
+    | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Unpack[GCSConfig],
 ) -> ObjectStore: ...
 @overload
@@ -61,7 +92,14 @@ def from_url(
     config: AzureConfig | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
-    credential_provider: AzureCredentialProvider | None = None,
+    limit_config: LimitConfig | None = None,
+    credential_provider: AzureCredentialProvider
+    | str
+    | ChainCredentialProvider
+    | list[AzureCredentialProvider | str]
+    | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Unpack[AzureConfig],
 ) -> ObjectStore: ...
 @overload
@@ -71,8 +109,12 @@ def from_url(  # type: ignore reportOverlappingOverload
     config: None = None,
     client_options: None = None,
     retry_config: None = None,
+    limit_config: None = None,
     automatic_cleanup: bool = False,
     mkdir: bool = False,
+    symlinks: Literal["follow", "skip", "error"] = "follow",
+    direct_io: bool = False,
+    preallocate: bool = False,
 ) -> ObjectStore: ...
 def from_url(
     url: str,
@@ -80,7 +122,11 @@ def from_url(
     config: S3Config | GCSConfig | AzureConfig | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
+    limit_config: LimitConfig | None = None,
+    compatibility: S3Compatibility | None = None,
     credential_provider: Callable | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Any,
 ) -> ObjectStore:
     """Easy construction of store by URL, identifying the relevant store.
@@ -107,6 +153,10 @@ def from_url(
     - `amazonaws.com` -> [`S3Store`][obstore.store.S3Store]
     - `r2.cloudflarestorage.com` -> [`S3Store`][obstore.store.S3Store]
 
+    With a `compatibility` preset, any other `http(s)` URL is the path-style URL of an
+    S3-compatible service, such as `http://minio.internal:9000/bucket/prefix`, and
+    creates an [`S3Store`][obstore.store.S3Store].
+
     !!! note
         For best static typing, use the constructors on individual store classes
         directly.
@@ -119,7 +169,17 @@ def from_url(
             inferred from the url. Defaults to None.
         client_options: HTTP Client options. Defaults to None.
         retry_config: Retry configuration. Defaults to None.
+        limit_config: Bandwidth and concurrency limits shared by all operations
+            through this store. Defaults to None.
+        compatibility: A preset of configuration for an S3-compatible service, only
+            for S3 URLs. See [`S3Compatibility`][obstore.store.S3Compatibility].
+            Defaults to None.
         credential_provider: A callback to provide custom credentials to the underlying store classes.
+        resolver: A resolver of the hostnames the store connects to. See
+            [`Resolver`][obstore.store.Resolver]. Defaults to None.
+        request_tags: Static key/value tags attached to every request, for attributing
+            costs to a workload. How they are sent depends on the store. Defaults to
+            None.
         kwargs: per-store configuration passed down to store-specific builders.
 
     """
@@ -144,9 +204,15 @@ class LocalStore:
         *,
         automatic_cleanup: bool = False,
         mkdir: bool = False,
+        symlinks: Literal["follow", "skip", "error"] = "follow",
+        direct_io: bool = False,
+        preallocate: bool = False,
     ) -> None:
         """Create a new LocalStore.
 
+        Renames, and copies, between directories on different filesystems mounted
+        under the store copy the contents of the file instead of failing.
+
         Args:
             prefix: Use the specified prefix applied to all paths. Defaults to `None`.
 
@@ -156,6 +222,21 @@ class LocalStore:
             mkdir: if `True` and `prefix` is not `None`, the directory at `prefix` will
                 attempt to be created. Note that this root directory will not be cleaned
                 up, even if `automatic_cleanup` is `True`.
+            symlinks: What listing and reading do with symbolic links, to files or to
+                directories, under `prefix`. With `"follow"`, they are followed as the
+                files and directories they point to. With `"skip"`, they are left out of
+                listings, and reading them fails with `FileNotFoundError`. With
+                `"error"`, listing or reading them fails. Without a `prefix`, every
+                directory of a path is checked. Defaults to `"follow"`.
+            direct_io: if `True`, files are written bypassing the page cache, with
+                `O_DIRECT` on Linux and `F_NOCACHE` on macOS, for large writes that
+                shouldn't evict other data from memory. Filesystems without direct
+                I/O, such as tmpfs, are written through the page cache. Defaults to
+                False.
+            preallocate: if `True`, the space of a file is allocated up front on
+                Linux when its size is known, which is when putting bytes, or a file
+                or path whose size can be read. This avoids fragmentation of large
+                files. Defaults to False.
 
         """
     @classmethod
@@ -165,6 +246,9 @@ class LocalStore:
         *,
         automatic_cleanup: bool = False,
         mkdir: bool = False,
+        symlinks: Literal["follow", "skip", "error"] = "follow",
+        direct_io: bool = False,
+        preallocate: bool = False,
     ) -> Self:
         """Construct a new LocalStore from a `file://` URL.
 
@@ -185,9 +269,54 @@ class LocalStore:
 
     def __eq__(self, value: object) -> bool: ...
     def __getnewargs_ex__(self): ...
+    def to_token(self, *, allow_secrets: bool = False) -> str:
+        """Serialize the configuration of the store to a token.
+
+        A token is a compact string that recreates the store with
+        [`from_token`][obstore.store.LocalStore.from_token]. It can also be passed
+        in place of the store to any function that takes a store, which reuses the
+        store recreated from a recently passed token. This makes tokens a simple way to
+        send a store to the workers of a distributed framework such as Dask or Ray.
+
+        Keyword Args:
+            allow_secrets: Has no effect, as this store has no secrets. Accepted for
+                compatibility with the other stores.
+
+        Returns:
+            The token.
+
+        """
+    @classmethod
+    def from_token(cls, token: str) -> Self:
+        """Create a store from a token returned by `to_token`.
+
+        A token is trusted input, as it chooses the directory that the store reads and
+        writes. Only create stores from tokens, or pass tokens in place of stores, when
+        they come from a trusted source.
+
+        Args:
+            token: The token.
+
+        Returns:
+            LocalStore
+
+        Raises:
+            ValueError: If the token is invalid, or is the token of another class of
+                store.
+
+        """
     @property
     def prefix(self) -> Path | None:
         """Get the prefix applied to all operations in this store, if any."""
+    @property
+    def symlinks(self) -> Literal["follow", "skip", "error"]:
+        """Get what listing and reading do with symbolic links."""
+    @property
+    def direct_io(self) -> bool:
+        """Get whether files are written bypassing the page cache."""
+    @property
+    def preallocate(self) -> bool:
+        """Get whether the space of files is allocated up front."""
 
 class MemoryStore:
     """A fully in-memory implementation of ObjectStore.
@@ -196,12 +325,585 @@ class MemoryStore:
     ```py
     store = MemoryStore()
     ```
+
+    Pickling a memory store pickles a snapshot of its contents, as returned by
+    [`to_dict`][obstore.store.MemoryStore.to_dict].
     """
 
     def __init__(self) -> None: ...
+    @classmethod
+    def from_dict(cls, mapping: Mapping[str, Buffer]) -> Self:
+        """Create a new store holding the given objects.
+
+        ```py
+        store = MemoryStore.from_dict({"data/file.txt": b"hello"})
+        ```
+
+        Args:
+            mapping: The contents of the objects of the store, keyed by their paths.
+
+        Returns:
+            MemoryStore
+
+        """
+    def fill(self, prefix: str, files: Mapping[str, Buffer]) -> None:
+        """Write objects under a prefix, replacing any objects at the same paths.
+
+        ```py
+        store.fill("data", {"a.txt": b"foo", "nested/b.txt": b"bar"})
+        assert store.to_dict() == {"data/a.txt": b"foo", "data/nested/b.txt": b"bar"}
+        ```
+
+        Args:
+            prefix: The prefix that the paths of `files` are relative to. An empty
+                string writes the objects at the paths of `files`.
+            files: The contents of the objects to write, keyed by their paths
+                relative to `prefix`.
+
+        """
+    def to_dict(self) -> dict[str, bytes]:
+        """Get the contents of all the objects of the store, keyed by their paths.
+
+        The attributes of the objects aren't included.
+        """
+
+class DictStore:
+    """A store whose objects are the values of a Python mapping, keyed by their paths.
+
+    Reads, writes and deletes call into the mapping, such as a `dict`, under the GIL.
+    This is much slower than [`MemoryStore`][obstore.store.MemoryStore], but the
+    contents of the store are plain Python objects, which is useful to check what
+    code handed a store has written, or to expose storage already accessible from
+    Python.
+
+    Values are `bytes`, or any object supporting the buffer protocol, and are written
+    as `bytes`. Keys that aren't valid paths fail listing. As the mapping only holds
+    the contents of the objects, the last modified time of an object is when this
+    store last wrote it, or when the store was created, and objects have no e-tag.
+
+    ```py
+    import obstore as obs
+    from obstore.store import DictStore
+
+    contents = {"data/file.txt": b"hello"}
+    store = DictStore(contents)
+    obs.put(store, "data/other.txt", b"world")
+    assert contents["data/other.txt"] == b"world"
+    ```
+    """
+
+    def __init__(self, mapping: MutableMapping[str, Buffer] | None = None) -> None:
+        """Construct a new DictStore.
+
+        Args:
+            mapping: The mapping holding the objects of the store. Defaults to a new
+                empty `dict`.
+
+        """
+    def __eq__(self, value: object) -> bool: ...
+    def __getnewargs_ex__(self): ...
+    @property
+    def mapping(self) -> MutableMapping[str, Buffer]:
+        """Get the mapping holding the objects of the store."""
+
+class CacheStore:
+    """A store wrapper that caches whole objects on local disk.
+
+    Repeated reads of the same remote objects are served from `cache_dir` instead of
+    being downloaded again. Once the cached objects exceed `max_size` bytes, the least
+    recently used are evicted.
+
+    Every read of a cached object is still validated against the wrapped store with a
+    conditional request on its e-tag, so a changed object is never served stale, while
+    an unchanged object only costs a request without a body. Objects are only cached
+    when they're read in full. Ranged reads, such as [`get_range`][obstore.get_range],
+    are served from a current cached object, and are otherwise passed through to the
+    wrapped store, so a small range of a large object is never downloaded in full. Use
+    [`RangeCacheStore`][obstore.store.RangeCacheStore] to cache the ranges themselves.
+    Objects larger than `max_size`, or for which the store doesn't return an e-tag, are
+    never cached.
+
+    Writes through this store, including [`put`][obstore.put],
+    [`delete`][obstore.delete], and [`rename`][obstore.rename], remove the cached
+    copies of the affected paths. The cache directory persists, so a later
+    `CacheStore` using the same directory reuses the objects cached by earlier ones.
+
+    ```py
+    from obstore.store import CacheStore, S3Store
+
+    store = CacheStore(S3Store("bucket"), "/tmp/obstore-cache", 10 * 1024**3)
+    ```
+    """
+
+    def __init__(
+        self,
+        store: ObjectStore,
+        cache_dir: str | Path,
+        max_size: int,
+    ) -> None:
+        """Create a new CacheStore.
+
+        Args:
+            store: The store to wrap.
+            cache_dir: The directory in which to cache objects. It is created if it
+                doesn't exist.
+            max_size: The maximum total size in bytes of the cached objects.
+
+        """
+    @property
+    def cache_dir(self) -> Path:
+        """Get the directory in which objects are cached."""
+    @property
+    def max_size(self) -> int:
+        """Get the maximum total size in bytes of the cached objects."""
+    @property
+    def size(self) -> int:
+        """Get the current total size in bytes of the cached objects."""
+    def clear(self) -> None:
+        """Remove all objects from the cache."""
+
+class ChaosStore:
+    """A store wrapper that injects latency and random failures, for testing.
+
+    Every call is delayed by `latency` and then fails with a
+    [`GenericError`][obstore.exceptions.GenericError] with probability `failure_rate`.
+    This can be used to check that an application handles slow or flaky storage
+    without needing a real remote backend. A listing fails before its first entry, and
+    each part of a multipart upload may fail independently.
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import ChaosStore, MemoryStore
+
+    store = ChaosStore(
+        MemoryStore(),
+        failure_rate=0.1,
+        latency=timedelta(milliseconds=20),
+        seed=42,
+    )
+    ```
+    """
+
+    def __init__(
+        self,
+        store: ObjectStore,
+        *,
+        failure_rate: float = 0.0,
+        latency: timedelta = timedelta(0),
+        seed: int | None = None,
+    ) -> None:
+        """Create a new ChaosStore.
+
+        Args:
+            store: The store to wrap.
+
+        Keyword Args:
+            failure_rate: The probability, between 0 and 1, that a call fails. Defaults
+                to 0.
+            latency: The delay added to every call. Defaults to no delay.
+            seed: A seed for the random number generator, to make the sequence of
+                failures reproducible. By default, the generator is seeded from the
+                operating system.
+
+        """
+
+class ConsistentStore:
+    """A store wrapper that provides read-after-write consistency for its own writes.
+
+    Some backends and emulators are only eventually consistent, so a read made just
+    after a write may still return the previous version of an object, or report that a
+    new object doesn't exist yet. This store remembers the e-tag and version of each
+    object written through it. A read of such an object that returns an older version,
+    or raises `FileNotFoundError`, is retried with exponential backoff until the
+    written version is observed. Likewise, a read of an object deleted through this
+    store is retried until the object is reported as not found.
+
+    If the written version still isn't observed once the retries are exhausted, for
+    example because another process has since overwritten the object, the last
+    response is returned as is. Listing is not affected.
+
+    ```py
+    from obstore.store import ConsistentStore, S3Store
+
+    store = ConsistentStore(S3Store("bucket"))
+    ```
+    """
+
+    def __init__(
+        self,
+        store: ObjectStore,
+        *,
+        max_retries: int = 10,
+        retry_delay: timedelta = timedelta(milliseconds=50),
+        ttl: timedelta = timedelta(seconds=60),
+        max_entries: int = 10_000,
+    ) -> None:
+        """Create a new ConsistentStore.
+
+        Args:
+            store: The store to wrap.
+
+        Keyword Args:
+            max_retries: The maximum number of times to retry a read that doesn't
+                observe the written version. Defaults to 10.
+            retry_delay: The delay before the first retry. The delay doubles with each
+                further retry, up to 5 seconds. Defaults to 50 milliseconds.
+            ttl: How long a write is remembered. Defaults to 60 seconds.
+            max_entries: The maximum number of writes to remember. Once full of
+                unexpired entries, further writes are not tracked. Defaults to 10,000.
+
+        """
+    def forget(self, path: str | None = None) -> None:
+        """Stop tracking the last write to a path, or to all paths.
+
+        Args:
+            path: The path to forget. If `None`, all writes are forgotten. Defaults to
+                `None`.
+
+        """
+
+class _Timeout(Protocol):
+    def when(self) -> float | None: ...
+
+class DeadlineStore:
+    """A store wrapper that stops every call at a deadline.
+
+    Calls made after the deadline fail immediately with a
+    [`TimeoutError`][obstore.exceptions.TimeoutError], and calls still in progress at
+    the deadline are cancelled. This includes streaming the body of a
+    [`get`][obstore.get] and the results of a [`list`][obstore.list]. A multipart
+    upload that runs past the deadline can still be aborted.
+
+    Cancelling an asyncio task, for example at the end of an `asyncio.timeout()`
+    block, stops an awaited call, but not work that is driven from a synchronous call
+    or from a stream that is no longer being consumed. Passing the `asyncio.Timeout` to
+    this store stops that work at the same deadline:
+
+    ```py
+    import asyncio
+
+    import obstore as obs
+    from obstore.store import DeadlineStore, S3Store
+
+    store = S3Store("bucket")
+
+    async with asyncio.timeout(10) as timeout:
+        deadline_store = DeadlineStore(store, timeout)
+        async for chunk in obs.list(deadline_store):
+            ...
+    ```
+
+    The deadline is fixed when the store is created: rescheduling the timeout later
+    doesn't move it.
+    """
+
+    def __init__(self, store: ObjectStore, deadline: timedelta | _Timeout) -> None:
+        """Create a new DeadlineStore.
+
+        Args:
+            store: The store to wrap.
+            deadline: The time remaining until the deadline, or an `asyncio.Timeout`
+                whose deadline to use. A timeout that has no deadline set applies no
+                deadline.
+
+        """
+    @property
+    def remaining(self) -> timedelta | None:
+        """Get the time remaining until the deadline, or `None` if there is none."""
+
+class KeyProvider(Protocol):
+    """Wraps and unwraps the content keys of an
+    [`EncryptedStore`][obstore.store.EncryptedStore].
+    """  # noqa: D205
+
+    def wrap_key(self, key: bytes) -> bytes:
+        """Wrap (encrypt) a 32-byte content key."""
+    def unwrap_key(self, wrapped_key: bytes) -> bytes:
+        """Unwrap (decrypt) a content key wrapped by `wrap_key`."""
+
+class EncryptedStore:
+    """A store wrapper that encrypts objects on the client.
+
+    Objects are encrypted with envelope encryption: each object is encrypted with its
+    own random content key, using AES-256-GCM, and the content key is wrapped by
+    `key_provider` and stored, encoded as base64, in the `obstore-encryption-key`
+    metadata of the object. The inner store must therefore support
+    [attributes][obstore.Attributes], and return them with [`get`][obstore.get].
+
+    ```py
+    import os
+
+    import obstore as obs
+    from obstore.store import EncryptedStore, S3Store
+
+    store = EncryptedStore(S3Store("bucket"), os.urandom(32))
+    obs.put(store, "secret.txt", b"foo")
+    assert obs.get(store, "secret.txt").bytes() == b"foo"
+    ```
+
+    Instead of a master key, `key_provider` may be an object with `wrap_key` and
+    `unwrap_key` methods, for example wrapping keys with a key management service.
+
+    Objects are encrypted in blocks of 64 KiB, each authenticated on its own, so that
+    ranged reads such as [`get_range`][obstore.get_range] only fetch and decrypt the
+    blocks covering the range, rather than the whole object. The sizes returned by
+    [`head`][obstore.head] and [`list`][obstore.list] are those of the plaintext.
+    Every object read through this store must have been written through it.
+
+    Encrypted objects can be copied and renamed, as long as the inner store preserves
+    their metadata.
+    """
+
+    def __init__(self, store: ObjectStore, key_provider: bytes | KeyProvider) -> None:
+        """Create a new EncryptedStore.
+
+        Args:
+            store: The store to wrap.
+            key_provider: A 32-byte master key wrapping content keys with AES-256-GCM,
+                or an object wrapping and unwrapping content keys.
+
+        """
+
+class MirrorStore:
+    """A store wrapper that mirrors writes to several stores.
+
+    This is useful for live migrations between buckets or providers. Writes, including
+    [`put`][obstore.put], [`delete`][obstore.delete], [`copy`][obstore.copy], and
+    [`rename`][obstore.rename], are made to the first (primary) store. Once that
+    succeeds, they are made to every other (secondary) store concurrently.
+
+    Writes aren't atomic across the stores. If a write to a secondary store fails, the
+    write to the primary store isn't undone, and the writes to the other secondary
+    stores still complete. A [`GenericError`][obstore.exceptions.GenericError] is then
+    raised that lists each store that failed, by its index in `stores`, with its
+    error. Deleting an object that is missing from a secondary store is not an error.
+
+    Reads, including [`list`][obstore.list], are made to the primary store. If that
+    fails, including because the object isn't found, each secondary store is tried in
+    turn. If every store fails, the error of the primary store is raised. A listing
+    only fails over before its first result is returned.
+
+    Conditional writes are only checked against the primary store: a write
+    conditional on an e-tag or version overwrites the object in the secondary stores.
+    Copies and renames require the source object to exist in every store.
+
+    ```py
+    from obstore.store import GCSStore, MirrorStore, S3Store
+
+    store = MirrorStore([GCSStore("new-bucket"), S3Store("old-bucket")])
+    ```
+    """
+
+    def __init__(self, stores: Sequence[ObjectStore]) -> None:
+        """Create a new MirrorStore.
+
+        Args:
+            stores: The stores to mirror, starting with the primary store. At least one
+                store must be given.
+
+        """
+
+class NegativeCacheStore:
+    """A store wrapper that caches negative lookups (404s) for a short time.
+
+    Repeatedly probing for optional files, such as `_metadata` or `.zarray`, can
+    otherwise issue many requests that are known to fail. While an entry is cached,
+    [`get`][obstore.get], [`head`][obstore.head], and the other read operations raise
+    `FileNotFoundError` for that path without making a request.
+
+    Any write to a path through this store, including [`put`][obstore.put],
+    [`copy`][obstore.copy], and [`rename`][obstore.rename], invalidates the cached
+    entry for that path. Writes made by other processes or through other store
+    instances are only observed once the entry expires.
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import NegativeCacheStore, S3Store
+
+    store = NegativeCacheStore(S3Store("bucket"), ttl=timedelta(seconds=10))
+    ```
+    """
+
+    def __init__(
+        self,
+        store: ObjectStore,
+        *,
+        ttl: timedelta = timedelta(seconds=5),
+        max_entries: int = 10_000,
+    ) -> None:
+        """Create a new NegativeCacheStore.
+
+        Args:
+            store: The store to wrap.
+
+        Keyword Args:
+            ttl: How long a path is remembered as not existing. Defaults to 5 seconds.
+            max_entries: The maximum number of paths to remember. Once the cache is
+                full of unexpired entries, further negative lookups are not cached.
+                Defaults to 10,000.
+
+        """
+    def invalidate(self, path: str | None = None) -> None:
+        """Remove a path from the cache, or clear the cache entirely.
+
+        Args:
+            path: The path to invalidate. If `None`, all entries are removed.
+                Defaults to `None`.
+
+        """
+
+class PrefixStore:
+    """A store wrapper that scopes an existing store to a prefix.
+
+    Every path passed to this store is joined onto `prefix`, and the prefix is removed
+    again from the paths it returns, such as from [`list`][obstore.list]. The wrapped
+    store is shared rather than rebuilt, so no new connection pool or credentials are
+    created. This makes it cheap to hand out sub-stores scoped to one part of a
+    bucket.
+
+    ```py
+    from obstore.store import PrefixStore, S3Store
+
+    store = S3Store("bucket")
+    users = PrefixStore(store, "data/users")
+    ```
+
+    Prefixes compose, so wrapping a `PrefixStore` in another `PrefixStore` joins the
+    two prefixes.
+    """
+
+    def __init__(self, store: ObjectStore, prefix: str | PathLike[str]) -> None:
+        """Create a new PrefixStore.
+
+        Args:
+            store: The store to wrap.
+            prefix: The prefix applied to all paths.
+
+        """
+    @property
+    def prefix(self) -> str:
+        """Get the prefix applied to all paths."""
+
+class RangeCacheStore:
+    """A store wrapper that caches ranged reads in memory.
+
+    Workloads such as reading Parquet or Zarr data tend to read the same footers and
+    headers many times, with separate [`get_range`][obstore.get_range] calls. This
+    store keeps the responses to ranged reads in memory, keyed by path, e-tag, and the
+    exact range requested, so that repeated reads don't make another request. Once the
+    cached ranges exceed `max_size` bytes, the least recently used are evicted. Reads
+    of whole objects are not cached; see [`CacheStore`][obstore.store.CacheStore] for
+    a disk cache of whole objects.
+
+    Any response through this store with a different e-tag than the cached ranges of
+    its path drops them. By default, a cached range is returned without making a
+    request, so a change made by another writer is only noticed once another read
+    through this store, such as [`head`][obstore.head], sees the new e-tag. Pass `validate=True` to instead
+    revalidate each cached range with a conditional request, which saves the transfer
+    but not the round trip. Writes through this store drop the cached ranges of the
+    affected paths.
+
+    ```py
+    from obstore.store import RangeCacheStore, S3Store
+
+    store = RangeCacheStore(S3Store("bucket"), 256 * 1024**2)
+    ```
+    """
+
+    def __init__(
+        self,
+        store: ObjectStore,
+        max_size: int,
+        *,
+        validate: bool = False,
+    ) -> None:
+        """Create a new RangeCacheStore.
+
+        Args:
+            store: The store to wrap.
+            max_size: The maximum total size in bytes of the cached ranges.
+
+        Keyword Args:
+            validate: Whether to revalidate each cached range with a conditional
+                request before returning it. Defaults to `False`.
+
+        """
+    @property
+    def max_size(self) -> int:
+        """Get the maximum total size in bytes of the cached ranges."""
+    @property
+    def size(self) -> int:
+        """Get the current total size in bytes of the cached ranges."""
+    def clear(self) -> None:
+        """Remove all ranges from the cache."""
+
+class ThrottleStore:
+    """A store wrapper that sleeps before or after each call, for testing.
+
+    This simulates the latency and limited bandwidth of a remote backend on top of a
+    fast store such as [`MemoryStore`][obstore.store.MemoryStore]. The delays are
+    configured by a [`ThrottleConfig`][obstore.store.ThrottleConfig].
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import MemoryStore, ThrottleStore
+
+    store = ThrottleStore(
+        MemoryStore(),
+        {"wait_get_per_call": timedelta(milliseconds=50)},
+    )
+    ```
+    """
+
+    def __init__(
+        self,
+        store: ObjectStore,
+        config: ThrottleConfig | None = None,
+    ) -> None:
+        """Create a new ThrottleStore.
+
+        Args:
+            store: The store to wrap.
+            config: The delays to apply. Defaults to no delays.
+
+        """
+    @property
+    def config(self) -> ThrottleConfig:
+        """Get the current delays of this store."""
+    def update_config(self, config: ThrottleConfig) -> None:
+        """Replace the delays of this store.
+
+        The new delays apply to all calls made after this returns, including through
+        other references to this store. Any wait missing from `config` is set to zero.
+
+        Args:
+            config: The new delays.
+
+        """
 
 ObjectStore: TypeAlias = (
-    AzureStore | GCSStore | HTTPStore | S3Store | LocalStore | MemoryStore
+    AzureStore
+    | GCSStore
+    | HTTPStore
+    | HdfsStore
+    | S3Store
+    | SFTPStore
+    | LocalStore
+    | MemoryStore
+    | DictStore
+    | CacheStore
+    | ChaosStore
+    | ConsistentStore
+    | DeadlineStore
+    | EncryptedStore
+    | MirrorStore
+    | NegativeCacheStore
+    | PrefixStore
+    | RangeCacheStore
+    | ThrottleStore
 )
 """All supported ObjectStore implementations.
 
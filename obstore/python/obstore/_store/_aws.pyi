@@ -1,9 +1,12 @@
 import sys
 from collections.abc import Coroutine
 from datetime import datetime
+from os import PathLike
 from typing import Any, Literal, Protocol, TypedDict
 
-from ._client import ClientConfig
+from ._client import ClientConfig, Resolver
+from ._credentials import ChainCredentialProvider, CredentialProviderHandle
+from ._limit import LimitConfig
 from ._retry import RetryConfig
 
 if sys.version_info >= (3, 10):
@@ -308,6 +311,8 @@ class S3Config(TypedDict, total=False):
     """
     The base64 encoded, 256-bit customer encryption key to use for server-side
     encryption. If set, the server side encryption config value must be `"sse-c"`.
+    [`S3Store.with_sse_customer_key`][obstore.store.S3Store.with_sse_customer_key]
+    sets both values from raw key bytes.
 
     **Environment variable**: `AWS_SSE_CUSTOMER_KEY_BASE64`.
     """
@@ -455,6 +460,47 @@ class S3CredentialProvider(Protocol):
     def __call__() -> S3Credential | Coroutine[Any, Any, S3Credential]:
         """Return an `S3Credential`."""
 
+S3Compatibility: TypeAlias = Literal["minio", "ceph", "generic"]
+"""A preset of configuration for an S3-compatible service.
+
+Many on-premises gateways reject some of the requests that obstore makes to AWS S3 by
+default. Each preset sets defaults for the following configuration keys of
+[`S3Config`][obstore.store.S3Config]. A key also passed in `config` or as a keyword
+argument keeps that value.
+
+- All presets set `virtual_hosted_style_request` to `False`, so that requests are
+  made path-style, e.g. to `https://host:9000/bucket/key`. This works with custom
+  ports and doesn't need wildcard DNS for bucket subdomains.
+- `"minio"` sets `conditional_put` to `"etag"`, since MinIO supports conditional
+  writes.
+- `"ceph"` sets nothing else, as the Ceph Object Gateway supports the rest of the
+  requests made by default, including object tagging.
+- `"generic"` sets `disable_tagging` to `True`, since tagging is not widely
+  supported.
+
+With a preset, [`S3Store.from_url`][obstore.store.S3Store.from_url] and
+[`from_url`][obstore.store.from_url] also accept the path-style URL of the service,
+such as `http://minio.internal:9000/bucket/prefix`, whose origin is used as the
+`endpoint`, keeping its port. An `http` endpoint also needs `allow_http` in
+`client_options`.
+
+Every request is signed with AWS Signature Version 4, with the SHA-256 of the whole
+body in the `x-amz-content-sha256` header unless `unsigned_payload` is set. obstore
+never uses chunked "streaming" payload signatures, and never sends checksum headers
+unless `checksum_algorithm` is set. Legacy (version 2) signatures are not supported.
+
+!!! warning "Not importable at runtime"
+
+    To use this type hint in your code, import it within a `TYPE_CHECKING` block:
+
+    ```py
+    from __future__ import annotations
+    from typing import TYPE_CHECKING
+    if TYPE_CHECKING:
+        from obstore.store import S3Compatibility
+    ```
+"""
+
 class S3Store:
     """Interface to an Amazon S3 bucket.
 
@@ -465,37 +511,106 @@ class S3Store:
 
     **Using requester-pays buckets**:
 
-    Pass `request_payer=True` as a keyword argument or have `AWS_REQUESTER_PAYS=True`
-    set in the environment.
+    Pass `request_payer=True` as a keyword argument or have `AWS_REQUEST_PAYER=True`
+    set in the environment. This sets the `x-amz-request-payer` header on every
+    request:
+
+    ```py
+    store = S3Store("sentinel-s2-l2a", region="eu-central-1", request_payer=True)
+    ```
 
     **Anonymous requests**:
 
     Pass `skip_signature=True` as a keyword argument or have `AWS_SKIP_SIGNATURE=True`
     set in the environment.
+
+    **S3-compatible services**:
+
+    Pass the `endpoint` of the service, and a `compatibility` preset if it rejects the
+    defaults used for AWS S3:
+
+    ```py
+    store = S3Store(
+        "bucket",
+        endpoint="http://minio.internal:9000",
+        allow_http=True,
+        compatibility="minio",
+    )
+    ```
     """
 
     def __init__(
         self,
         bucket: str | None = None,
         *,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         config: S3Config | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: S3CredentialProvider | None = None,
+        limit_config: LimitConfig | None = None,
+        compatibility: S3Compatibility | None = None,
+        credential_provider: S3CredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[S3CredentialProvider | str]
+        | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
+        extra_headers: dict[str, str] | None = None,
         **kwargs: Unpack[S3Config],  # type: ignore[GeneralTypeIssues] (bucket key overlaps with positional arg)
     ) -> None:
         """Create a new S3Store.
 
         Args:
-            bucket: The AWS bucket to use.
+            bucket: The AWS bucket to use. This can also be the ARN of an S3 access point
+                or Object Lambda access point, such as
+                `arn:aws:s3:us-west-2:123456789012:accesspoint/my-access-point`, in
+                which case the region and endpoint default to those of the access
+                point. Note that requests are signed for the `s3` service, while
+                Object Lambda access points only accept signed requests for the
+                `s3-object-lambda` service, so they can only be used anonymously.
 
         Keyword Args:
             prefix: A prefix within the bucket to use for all operations.
             config: AWS configuration. Values in this config will override values inferred from the environment. Defaults to None.
             client_options: HTTP Client options. Defaults to None.
             retry_config: Retry configuration. Defaults to None.
-            credential_provider: A callback to provide custom S3 credentials.
+            limit_config: Bandwidth and concurrency limits shared by all operations
+                through this store. Defaults to None.
+            compatibility: A preset of configuration for an S3-compatible service. See
+                [`S3Compatibility`][obstore.store.S3Compatibility]. Defaults to None.
+            credential_provider: A callback to provide custom S3 credentials, or the
+                name of a built-in credential provider:
+
+                - `"profile"` or `"profile:<name>"`: A profile of the shared AWS
+                  config and credentials files, by default the one named by
+                  `AWS_PROFILE`. Supports static keys, `credential_process`, SSO,
+                  and assuming roles with `role_arn` and `source_profile` or
+                  `credential_source`.
+                - `"sso"` or `"sso:<name>"`: The SSO session of a profile, which
+                  must have been started with `aws sso login`.
+                - `"imds"`: The role of the EC2 instance, fetched with IMDSv2.
+                - `"web-identity"`: The role in `AWS_ROLE_ARN`, assumed with the
+                  token in `AWS_WEB_IDENTITY_TOKEN_FILE`.
+
+                The STS endpoint can be overridden with `AWS_ENDPOINT_URL_STS`.
+                Built-in providers make requests with the `client_options` of the
+                store, except that IMDS is always reached over plain HTTP.
+
+                A list of providers, or a
+                [`ChainCredentialProvider`][obstore.store.ChainCredentialProvider], is
+                tried in order until one of them returns a credential.
+            resolver: A resolver of the hostnames the store connects to. See
+                [`Resolver`][obstore.store.Resolver]. Defaults to None.
+            request_tags: Static key/value tags for attributing costs to a workload.
+                They are appended to the user agent of every request as `key/value`,
+                which S3 records in its server access logs. Keys and values may
+                only contain ASCII letters, digits, `-`, `_` and `.`. Defaults to None.
+            extra_headers: Headers sent with every request of the store. A header
+                passed to an individual operation, such as the `headers` of `get`,
+                takes precedence. These headers aren't signed, so S3 rejects
+                `x-amz-*` headers on signed requests: use config values such as
+                `request_payer` for those. Defaults to None.
             kwargs: AWS configuration values. Supports the same values as `config`, but as named keyword args.
 
         Returns:
@@ -510,7 +625,16 @@ class S3Store:
         config: S3Config | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: S3CredentialProvider | None = None,
+        limit_config: LimitConfig | None = None,
+        compatibility: S3Compatibility | None = None,
+        credential_provider: S3CredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[S3CredentialProvider | str]
+        | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
+        extra_headers: dict[str, str] | None = None,
         **kwargs: Unpack[S3Config],
     ) -> Self:
         """Parse available connection info from a well-known storage URL.
@@ -522,6 +646,11 @@ class S3Store:
         - `https://s3.<region>.amazonaws.com/<bucket>`
         - `https://<bucket>.s3.<region>.amazonaws.com`
         - `https://ACCOUNT_ID.r2.cloudflarestorage.com/bucket`
+        - `s3://<access point ARN>/<path>`
+        - `https://<name>-<account id>.s3-accesspoint.<region>.amazonaws.com`
+        - `https://<name>-<account id>.s3-object-lambda.<region>.amazonaws.com`
+        - `http(s)://<host>[:<port>]/<bucket>/<path>`, the path-style URL of an
+          S3-compatible service, only with a `compatibility` preset
 
         Args:
             url: well-known storage URL.
@@ -530,7 +659,42 @@ class S3Store:
             config: AWS Configuration. Values in this config will override values inferred from the url. Defaults to None.
             client_options: HTTP Client options. Defaults to None.
             retry_config: Retry configuration. Defaults to None.
-            credential_provider: A callback to provide custom S3 credentials.
+            limit_config: Bandwidth and concurrency limits shared by all operations
+                through this store. Defaults to None.
+            compatibility: A preset of configuration for an S3-compatible service. See
+                [`S3Compatibility`][obstore.store.S3Compatibility]. Defaults to None.
+            credential_provider: A callback to provide custom S3 credentials, or the
+                name of a built-in credential provider:
+
+                - `"profile"` or `"profile:<name>"`: A profile of the shared AWS
+                  config and credentials files, by default the one named by
+                  `AWS_PROFILE`. Supports static keys, `credential_process`, SSO,
+                  and assuming roles with `role_arn` and `source_profile` or
+                  `credential_source`.
+                - `"sso"` or `"sso:<name>"`: The SSO session of a profile, which
+                  must have been started with `aws sso login`.
+                - `"imds"`: The role of the EC2 instance, fetched with IMDSv2.
+                - `"web-identity"`: The role in `AWS_ROLE_ARN`, assumed with the
+                  token in `AWS_WEB_IDENTITY_TOKEN_FILE`.
+
+                The STS endpoint can be overridden with `AWS_ENDPOINT_URL_STS`.
+                Built-in providers make requests with the `client_options` of the
+                store, except that IMDS is always reached over plain HTTP.
+
+                A list of providers, or a
+                [`ChainCredentialProvider`][obstore.store.ChainCredentialProvider], is
+                tried in order until one of them returns a credential.
+            resolver: A resolver of the hostnames the store connects to. See
+                [`Resolver`][obstore.store.Resolver]. Defaults to None.
+            request_tags: Static key/value tags for attributing costs to a workload.
+                They are appended to the user agent of every request as `key/value`,
+                which S3 records in its server access logs. Keys and values may
+                only contain ASCII letters, digits, `-`, `_` and `.`. Defaults to None.
+            extra_headers: Headers sent with every request of the store. A header
+                passed to an individual operation, such as the `headers` of `get`,
+                takes precedence. These headers aren't signed, so S3 rejects
+                `x-amz-*` headers on signed requests: use config values such as
+                `request_payer` for those. Defaults to None.
             kwargs: AWS configuration values. Supports the same values as `config`, but as named keyword args.
 
 
@@ -541,6 +705,100 @@ class S3Store:
 
     def __eq__(self, value: object) -> bool: ...
     def __getnewargs_ex__(self): ...
+    def to_token(self, *, allow_secrets: bool = False) -> str:
+        """Serialize the configuration of the store to a token.
+
+        A token is a compact string that recreates the store with
+        [`from_token`][obstore.store.S3Store.from_token]. It can also be passed
+        in place of the store to any function that takes a store, which reuses the
+        store recreated from a recently passed token. This makes tokens a simple way to
+        send a store to the workers of a distributed framework such as Dask or Ray.
+
+        Keyword Args:
+            allow_secrets: Whether to include the access key, secret key, session token
+                and SSE-C customer key of the config, the password of the proxy URL,
+                and the `Authorization`, `Proxy-Authorization`, `Cookie`, `x-api-key`
+                and `x-amz-security-token` headers of `extra_headers` in the token. By
+                default they are left out, so that the recreated store authenticates
+                with the credentials of the environment it's created in. Anyone with a
+                token that includes secrets can read them.
+
+        Raises:
+            TypeError: If part of the configuration can't be serialized, such as a
+                credential provider that is a Python callback.
+
+        Returns:
+            The token.
+
+        """
+    @classmethod
+    def from_token(cls, token: str) -> Self:
+        """Create a store from a token returned by `to_token`.
+
+        A token is trusted input, as it chooses the endpoint that requests and
+        credentials are sent to. Only create stores from tokens, or pass tokens in place
+        of stores, when they come from a trusted source.
+
+        Args:
+            token: The token.
+
+        Returns:
+            S3Store
+
+        Raises:
+            ValueError: If the token is invalid, or is the token of another class of
+                store.
+
+        """
+    def reset_connections(self) -> None:
+        """Close the store's connections and open new ones for the next requests.
+
+        Requests in progress finish on their current connections. This is useful
+        after network changes, or to recover from connections that the provider has
+        reset while idle. To replace connections periodically, set
+        `connection_max_lifetime` in the store's client options instead.
+        """
+    def with_sse_customer_key(
+        self,
+        key: bytes | str,
+        *,
+        key_md5: str | None = None,
+    ) -> Self:
+        """Create a store that encrypts objects with a customer-provided key (SSE-C).
+
+        The new store has the same parameters as this store, with
+        `aws_server_side_encryption` set to `"sse-c"` and
+        `aws_sse_customer_key_base64` set to `key`. It sends the key and its MD5
+        digest with every `get`, `put` and `head` request, and as the source and
+        destination keys of `copy`. Objects written with SSE-C can only be read
+        back with the same key.
+
+        Since S3 requires these headers to be signed, they can't be passed per
+        request through `headers`. Use a store per key instead:
+
+        ```py
+        import os
+
+        key = os.urandom(32)
+        encrypted = store.with_sse_customer_key(key)
+        obstore.put(encrypted, "secret.txt", b"hello")
+        ```
+
+        Args:
+            key: The 256-bit encryption key, as 32 raw bytes or as a base64-encoded string.
+
+        Keyword Args:
+            key_md5: The base64-encoded MD5 digest of the key. The digest is always
+                computed from `key`; if passed, it is checked against that computed
+                value to catch a corrupted key early.
+
+        Returns:
+            A new store of the same class.
+
+        Raises:
+            ValueError: if the key isn't 32 bytes long or `key_md5` doesn't match it.
+
+        """
     @property
     def prefix(self) -> str | None:
         """Get the prefix applied to all operations in this store, if any."""
@@ -551,8 +809,27 @@ class S3Store:
     def client_options(self) -> ClientConfig | None:
         """Get the store's client configuration."""
     @property
-    def credential_provider(self) -> S3CredentialProvider | None:
-        """Get the store's credential provider."""
+    def credential_provider(self) -> CredentialProviderHandle | None:
+        """Get the store's credential provider.
+
+        This wraps the credential provider passed to the store, giving access to the
+        credential that the store has cached from it.
+        """
+    @property
+    def resolver(self) -> Resolver | None:
+        """Get the store's hostname resolver."""
+    @property
+    def request_tags(self) -> dict[str, str] | None:
+        """Get the tags attached to every request of the store."""
+    @property
+    def extra_headers(self) -> dict[str, str] | None:
+        """Get the headers sent with every request of the store."""
     @property
     def retry_config(self) -> RetryConfig | None:
         """Get the store's retry configuration."""
+    @property
+    def limit_config(self) -> LimitConfig | None:
+        """Get the store's bandwidth and concurrency limits."""
+    @property
+    def compatibility(self) -> S3Compatibility | None:
+        """Get the store's S3 compatibility preset, if any."""
@@ -1,9 +1,12 @@
 import sys
 from collections.abc import Coroutine
 from datetime import datetime
+from os import PathLike
 from typing import Any, Protocol, TypedDict
 
-from ._client import ClientConfig
+from ._client import ClientConfig, Resolver
+from ._credentials import ChainCredentialProvider, CredentialProviderHandle
+from ._limit import LimitConfig
 from ._retry import RetryConfig
 
 if sys.version_info >= (3, 10):
@@ -328,11 +331,18 @@ class AzureStore:
         self,
         container_name: str | None = None,
         *,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         config: AzureConfig | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: AzureCredentialProvider | None = None,
+        limit_config: LimitConfig | None = None,
+        credential_provider: AzureCredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[AzureCredentialProvider | str]
+        | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
         **kwargs: Unpack[AzureConfig],  # type: ignore[GeneralTypeIssues] (container_name key overlaps with positional arg)
     ) -> None:
         """Construct a new AzureStore.
@@ -345,7 +355,28 @@ class AzureStore:
             config: Azure Configuration. Values in this config will override values inferred from the url. Defaults to None.
             client_options: HTTP Client options. Defaults to None.
             retry_config: Retry configuration. Defaults to None.
-            credential_provider: A callback to provide custom Azure credentials.
+            limit_config: Bandwidth and concurrency limits shared by all operations
+                through this store. Defaults to None.
+            credential_provider: A callback to provide custom Azure credentials, or
+                the name of a built-in credential provider:
+
+                - `"cli"`: The account signed in to the Azure CLI with `az login`.
+                - `"managed-identity"` or `"managed-identity:<client_id>"`: The
+                  system-assigned managed identity, or the user-assigned identity
+                  with the given client ID.
+                - `"workload-identity"`: The federated token that Azure workload
+                  identity mounts into Kubernetes pods, exchanged for a token of the
+                  application in `AZURE_CLIENT_ID`.
+
+                A list of providers, or a
+                [`ChainCredentialProvider`][obstore.store.ChainCredentialProvider], is
+                tried in order until one of them returns a credential.
+            resolver: A resolver of the hostnames the store connects to. See
+                [`Resolver`][obstore.store.Resolver]. Defaults to None.
+            request_tags: Static key/value tags for attributing costs to a workload.
+                They are appended to the user agent of every request as `key/value`,
+                which Azure Storage records in its resource logs. Keys and values may
+                only contain ASCII letters, digits, `-`, `_` and `.`. Defaults to None.
             kwargs: Azure configuration values. Supports the same values as `config`, but as named keyword args.
 
         Returns:
@@ -358,11 +389,18 @@ class AzureStore:
         cls,
         url: str,
         *,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         config: AzureConfig | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: AzureCredentialProvider | None = None,
+        limit_config: LimitConfig | None = None,
+        credential_provider: AzureCredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[AzureCredentialProvider | str]
+        | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
         **kwargs: Unpack[AzureConfig],
     ) -> Self:
         """Construct a new AzureStore with values populated from a well-known storage URL.
@@ -391,7 +429,28 @@ class AzureStore:
             config: Azure Configuration. Values in this config will override values inferred from the url. Defaults to None.
             client_options: HTTP Client options. Defaults to None.
             retry_config: Retry configuration. Defaults to None.
-            credential_provider: A callback to provide custom Azure credentials.
+            limit_config: Bandwidth and concurrency limits shared by all operations
+                through this store. Defaults to None.
+            credential_provider: A callback to provide custom Azure credentials, or
+                the name of a built-in credential provider:
+
+                - `"cli"`: The account signed in to the Azure CLI with `az login`.
+                - `"managed-identity"` or `"managed-identity:<client_id>"`: The
+                  system-assigned managed identity, or the user-assigned identity
+                  with the given client ID.
+                - `"workload-identity"`: The federated token that Azure workload
+                  identity mounts into Kubernetes pods, exchanged for a token of the
+                  application in `AZURE_CLIENT_ID`.
+
+                A list of providers, or a
+                [`ChainCredentialProvider`][obstore.store.ChainCredentialProvider], is
+                tried in order until one of them returns a credential.
+            resolver: A resolver of the hostnames the store connects to. See
+                [`Resolver`][obstore.store.Resolver]. Defaults to None.
+            request_tags: Static key/value tags for attributing costs to a workload.
+                They are appended to the user agent of every request as `key/value`,
+                which Azure Storage records in its resource logs. Keys and values may
+                only contain ASCII letters, digits, `-`, `_` and `.`. Defaults to None.
             kwargs: Azure configuration values. Supports the same values as `config`, but as named keyword args.
 
         Returns:
@@ -401,6 +460,57 @@ class AzureStore:
 
     def __eq__(self, value: object) -> bool: ...
     def __getnewargs_ex__(self): ...
+    def to_token(self, *, allow_secrets: bool = False) -> str:
+        """Serialize the configuration of the store to a token.
+
+        A token is a compact string that recreates the store with
+        [`from_token`][obstore.store.AzureStore.from_token]. It can also be passed
+        in place of the store to any function that takes a store, which reuses the
+        store recreated from a recently passed token. This makes tokens a simple way to
+        send a store to the workers of a distributed framework such as Dask or Ray.
+
+        Keyword Args:
+            allow_secrets: Whether to include the access key, client secret, SAS key and
+                tokens of the config, and the password of the proxy URL, in the token. By
+                default they are left out, so that the recreated store authenticates
+                with the credentials of the environment it's created in. Anyone with a
+                token that includes secrets can read them.
+
+        Raises:
+            TypeError: If part of the configuration can't be serialized, such as a
+                credential provider that is a Python callback.
+
+        Returns:
+            The token.
+
+        """
+    @classmethod
+    def from_token(cls, token: str) -> Self:
+        """Create a store from a token returned by `to_token`.
+
+        A token is trusted input, as it chooses the endpoint that requests and
+        credentials are sent to. Only create stores from tokens, or pass tokens in place
+        of stores, when they come from a trusted source.
+
+        Args:
+            token: The token.
+
+        Returns:
+            AzureStore
+
+        Raises:
+            ValueError: If the token is invalid, or is the token of another class of
+                store.
+
+        """
+    def reset_connections(self) -> None:
+        """Close the store's connections and open new ones for the next requests.
+
+        Requests in progress finish on their current connections. This is useful
+        after network changes, or to recover from connections that the provider has
+        reset while idle. To replace connections periodically, set
+        `connection_max_lifetime` in the store's client options instead.
+        """
     @property
     def prefix(self) -> str | None:
         """Get the prefix applied to all operations in this store, if any."""
@@ -411,8 +521,21 @@ class AzureStore:
     def client_options(self) -> ClientConfig | None:
         """Get the store's client configuration."""
     @property
-    def credential_provider(self) -> AzureCredentialProvider | None:
-        """Get the store's credential provider."""
+    def credential_provider(self) -> CredentialProviderHandle | None:
+        """Get the store's credential provider.
+
+        This wraps the credential provider passed to the store, giving access to the
+        credential that the store has cached from it.
+        """
+    @property
+    def resolver(self) -> Resolver | None:
+        """Get the store's hostname resolver."""
+    @property
+    def request_tags(self) -> dict[str, str] | None:
+        """Get the tags attached to every request of the store."""
     @property
     def retry_config(self) -> RetryConfig | None:
         """Get the store's retry configuration."""
+    @property
+    def limit_config(self) -> LimitConfig | None:
+        """Get the store's bandwidth and concurrency limits."""
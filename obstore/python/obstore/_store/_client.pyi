@@ -1,12 +1,19 @@
+import sys
+from collections.abc import Callable, Sequence
 from datetime import timedelta
 from typing import TypedDict
 
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
 class ClientConfig(TypedDict, total=False):
     """HTTP client configuration.
 
-    For timeout values (`connect_timeout`, `http2_keep_alive_timeout`,
-    `pool_idle_timeout`, and `timeout`), values can either be Python `timedelta`
-    objects, or they can be "human-readable duration strings".
+    For duration values (`connect_timeout`, `connection_max_lifetime`,
+    `http2_keep_alive_timeout`, `pool_idle_timeout`, and `timeout`), values can either
+    be Python `timedelta` objects, or they can be "human-readable duration strings".
 
     The human-readable duration string is a concatenation of time spans. Where each time
     span is an integer number and a suffix. Supported suffixes:
@@ -54,6 +61,16 @@ class ClientConfig(TypedDict, total=False):
     """
     connect_timeout: str | timedelta
     """Timeout for only the connect phase of a Client"""
+    connection_max_lifetime: str | timedelta
+    """The maximum duration that connections are reused for.
+
+    Once it has elapsed, the store opens new connections for its next requests, while
+    requests in progress finish on the previous ones. This avoids failures from stale
+    connections in long-lived processes, e.g. after the provider has reset them. Set
+    `pool_idle_timeout` to also close connections that have been idle for a while.
+
+    Defaults to reusing connections indefinitely.
+    """
     default_content_type: str
     """default `CONTENT_TYPE` for uploads"""
     http1_only: bool
@@ -66,6 +83,27 @@ class ClientConfig(TypedDict, total=False):
     """Enable HTTP2 keep alive pings for idle connections"""
     http2_only: bool
     """Only use http2 connections"""
+    log_requests: bool
+    """Log every request of the store to the `obstore.http` logger.
+
+    Each request is logged with its method, URL, response status and duration, at the
+    `INFO` level, or at the `WARNING` level if it fails without a response. The
+    values of credentials in the query string, such as the signatures of SAS tokens
+    and presigned URLs, are redacted.
+
+    ```py
+    import logging
+
+    from obstore.store import S3Store
+
+    logging.basicConfig()
+    logging.getLogger("obstore.http").setLevel(logging.INFO)
+
+    store = S3Store("bucket", client_options={"log_requests": True})
+    ```
+
+    Defaults to `False`.
+    """
     pool_idle_timeout: str | timedelta
     """The pool max idle timeout.
 
@@ -83,3 +121,45 @@ class ClientConfig(TypedDict, total=False):
     """
     user_agent: str
     """User-Agent header to be used by this client."""
+
+Resolver: TypeAlias = (
+    dict[str, str | Sequence[str]] | Callable[[str], str | Sequence[str] | None]
+)
+"""A resolver of the hostnames that a store connects to.
+
+This applies only to the HTTP client of the store it's passed to, which is useful on
+split-horizon networks, e.g. to reach a bucket through a private endpoint without
+changing the DNS configuration of the whole machine.
+
+A resolver is either a `dict` mapping hostnames to addresses, or a callable taking a
+hostname and returning its addresses. Addresses are IP addresses, given as a single
+string or a sequence of strings, and may include a port. The port is only used when the
+URL of a request doesn't specify one.
+
+Hostnames are matched case-insensitively. Any hostname that isn't in the `dict`, or for
+which the callable returns `None`, is resolved through the system's DNS as usual.
+Because TLS certificates are still verified against the hostname, a resolver can send
+requests to a different address without weakening certificate validation.
+
+The callable is called synchronously from a worker thread on each new connection, so it
+may block, but it must not be a coroutine function.
+
+```py
+store = S3Store(
+    "bucket",
+    region="us-east-1",
+    resolver={"bucket.s3.us-east-1.amazonaws.com": ["10.0.1.12", "10.0.2.12"]},
+)
+```
+
+!!! warning "Not importable at runtime"
+
+    To use this type hint in your code, import it within a `TYPE_CHECKING` block:
+
+    ```py
+    from __future__ import annotations
+    from typing import TYPE_CHECKING
+    if TYPE_CHECKING:
+        from obstore.store import Resolver
+    ```
+"""
@@ -0,0 +1,89 @@
+from datetime import datetime
+from collections.abc import Sequence
+from typing import Any
+
+class ChainCredentialProvider:
+    """A credential provider that tries each of its providers in order until one of
+    them returns a credential.
+
+    Each provider is anything accepted as the `credential_provider` of the store: a
+    callback, the name of a built-in credential provider, or another chain. If every
+    provider fails, the error raised lists the error of each provider.
+
+    This mirrors the default credential chains of the cloud SDKs while keeping control
+    over which sources are consulted. Passing a list of providers as
+    `credential_provider` is equivalent.
+
+    **Example:**
+
+    ```py
+    from obstore.store import ChainCredentialProvider, S3Store
+
+    credential_provider = ChainCredentialProvider(["web-identity", "imds", "profile"])
+    store = S3Store("bucket", credential_provider=credential_provider)
+    ```
+
+    The providers are tried in order each time a new credential is needed. Attributes
+    of the providers such as `refresh_threshold` and `config` are ignored, and
+    credentials are cached with the default refresh threshold.
+    """
+
+    def __init__(self, providers: Sequence[Any]) -> None:
+        """Create a new ChainCredentialProvider.
+
+        Args:
+            providers: The credential providers to try, in order.
+
+        Raises:
+            ValueError: If `providers` is empty.
+        """
+    def __eq__(self, other: object) -> bool: ...
+    @property
+    def providers(self) -> list[Any]:
+        """The credential providers of the chain."""
+
+class CredentialProviderHandle:
+    """The credential provider of a store, as returned by its `credential_provider`
+    property.
+
+    This behaves like the credential provider passed to the store: calling it calls
+    the provider and attributes are looked up on the provider. In addition, it gives
+    access to the credential that the store has cached from the provider.
+
+    **Example:**
+
+    Force the store to fetch a new credential after rotating a key:
+
+    ```py
+    from obstore.store import S3Store
+
+    store = S3Store("bucket", credential_provider=get_credentials)
+    ...
+    store.credential_provider.invalidate()
+    ```
+
+    To refresh credentials before they expire without delaying a request, set a
+    `background_refresh = True` attribute on the credential provider. The store then
+    fetches a new credential `refresh_threshold` before the cached one expires, in a
+    background task.
+    """
+
+    def __call__(self, *args: Any, **kwargs: Any) -> Any:
+        """Call the credential provider."""
+    def __getattr__(self, name: str) -> Any: ...
+    @property
+    def callback(self) -> Any:
+        """The credential provider passed to the store."""
+    def current_expiry(self) -> datetime | None:
+        """The expiry of the credential cached by the store.
+
+        Returns `None` if no credential has been fetched yet or if the cached credential
+        doesn't expire.
+        """
+    def invalidate(self) -> None:
+        """Drop the credential cached by the store.
+
+        The next request made by the store calls the credential provider for a new
+        credential. Credentials cached by other stores created with the same provider
+        are not affected.
+        """
@@ -0,0 +1,176 @@
+import sys
+
+from ._client import ClientConfig, Resolver
+from ._limit import LimitConfig
+from ._retry import RetryConfig
+
+if sys.version_info >= (3, 11):
+    from typing import Self
+else:
+    from typing_extensions import Self
+
+class HdfsStore:
+    """Configure a connection to HDFS, through the WebHDFS REST API.
+
+    The store talks to the WebHDFS API of a namenode, or to an HttpFS gateway, and
+    paths are relative to the directory of the URL. Writes are redirected by the
+    namenode to the datanodes, which must be reachable from the client. Files whose
+    names end with `#` and digits are used to stage multipart uploads, and are left
+    out of listings.
+
+    ```py
+    from obstore.store import HdfsStore
+
+    store = HdfsStore("webhdfs://namenode.example.com/data", user="etl")
+    ```
+
+    **Kerberos**
+
+    The store doesn't authenticate with Kerberos itself. Instead, clusters secured
+    with Kerberos are accessed with a delegation token, which can be fetched with
+    `hdfs fetchdt`, or from the `GETDELEGATIONTOKEN` operation of WebHDFS with
+    `curl --negotiate -u :`.
+
+    ```py
+    store = HdfsStore(
+        "swebhdfs://namenode.example.com/data",
+        delegation_token="HAAEaGRmcwRoZGZzAIoBj...",
+    )
+    ```
+    """
+
+    def __init__(
+        self,
+        url: str,
+        *,
+        user: str | None = None,
+        delegation_token: str | None = None,
+        client_options: ClientConfig | None = None,
+        retry_config: RetryConfig | None = None,
+        limit_config: LimitConfig | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
+    ) -> None:
+        """Construct a new HdfsStore from a URL.
+
+        Args:
+            url: The URL of the directory that paths are relative to. The scheme is
+                one of `webhdfs` and `swebhdfs`, which default to the ports 9870 and
+                9871 of a namenode, or `http` and `https`, such as for an HttpFS
+                gateway on port 14000. The `/webhdfs/v1` prefix of the API may be
+                left out of the path.
+
+        Keyword Args:
+            user: The user to make requests as, on clusters without Kerberos.
+                Defaults to None, in which case the cluster decides on the user.
+            delegation_token: A delegation token to authenticate requests with.
+                Cannot be given with `user`. Defaults to None.
+            client_options: HTTP Client options. Plain HTTP is allowed for `webhdfs`
+                and `http` URLs. Defaults to None.
+            retry_config: Retry configuration. Reads are retried on connection
+                errors and server errors; writes are not retried. Defaults to None.
+            limit_config: Bandwidth and concurrency limits shared by all operations
+                through this store. Defaults to None.
+            resolver: A resolver of the hostnames the store connects to. See
+                [`Resolver`][obstore.store.Resolver]. Defaults to None.
+            request_tags: Static key/value tags for attributing costs to a workload.
+                They are appended to the user agent of every request as `key/value`.
+                Keys and values may only contain ASCII letters, digits, `-`, `_` and
+                `.`. Defaults to None.
+
+        Returns:
+            HdfsStore
+
+        """
+
+    @classmethod
+    def from_url(
+        cls,
+        url: str,
+        *,
+        user: str | None = None,
+        delegation_token: str | None = None,
+        client_options: ClientConfig | None = None,
+        retry_config: RetryConfig | None = None,
+        limit_config: LimitConfig | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
+    ) -> Self:
+        """Construct a new HdfsStore from a URL.
+
+        This is an alias of [`HdfsStore.__init__`][obstore.store.HdfsStore.__init__].
+        """
+
+    def __eq__(self, value: object) -> bool: ...
+    def __getnewargs_ex__(self): ...
+    def to_token(self, *, allow_secrets: bool = False) -> str:
+        """Serialize the configuration of the store to a token.
+
+        A token is a compact string that recreates the store with
+        [`from_token`][obstore.store.HdfsStore.from_token]. It can also be passed
+        in place of the store to any function that takes a store, which reuses the
+        store recreated from a recently passed token. This makes tokens a simple way to
+        send a store to the workers of a distributed framework such as Dask or Ray.
+
+        Keyword Args:
+            allow_secrets: Whether to include the delegation token and the password of
+                the proxy URL in the token. By default they are left out, so that the recreated store authenticates
+                with the credentials of the environment it's created in. Anyone with a
+                token that includes secrets can read them.
+
+        Raises:
+            TypeError: If part of the configuration can't be serialized, such as a
+                credential provider that is a Python callback.
+
+        Returns:
+            The token.
+
+        """
+    @classmethod
+    def from_token(cls, token: str) -> Self:
+        """Create a store from a token returned by `to_token`.
+
+        A token is trusted input, as it chooses the cluster that requests are sent to.
+        Only create stores from tokens, or pass tokens in place of stores, when they
+        come from a trusted source.
+
+        Args:
+            token: The token.
+
+        Returns:
+            HdfsStore
+
+        Raises:
+            ValueError: If the token is invalid, or is the token of another class of
+                store.
+
+        """
+    def reset_connections(self) -> None:
+        """Close the store's connections and open new ones for the next requests.
+
+        Requests in progress finish on their current connections. This is useful
+        after network changes, or to recover from connections that the provider has
+        reset while idle. To replace connections periodically, set
+        `connection_max_lifetime` in the store's client options instead.
+        """
+    @property
+    def url(self) -> str:
+        """Get the URL of the directory that paths are relative to."""
+    @property
+    def user(self) -> str | None:
+        """Get the user that requests are made as."""
+    @property
+    def client_options(self) -> ClientConfig | None:
+        """Get the store's client configuration."""
+    @property
+    def retry_config(self) -> RetryConfig | None:
+        """Get the store's retry configuration."""
+    @property
+    def limit_config(self) -> LimitConfig | None:
+        """Get the store's bandwidth and concurrency limits."""
+    @property
+    def resolver(self) -> Resolver | None:
+        """Get the store's hostname resolver."""
+    @property
+    def request_tags(self) -> dict[str, str] | None:
+        """Get the tags attached to every request of the store."""
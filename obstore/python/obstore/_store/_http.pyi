@@ -1,6 +1,7 @@
 import sys
 
-from ._client import ClientConfig
+from ._client import ClientConfig, Resolver
+from ._limit import LimitConfig
 from ._retry import RetryConfig
 
 if sys.version_info >= (3, 11):
@@ -17,6 +18,10 @@ class HTTPStore:
         *,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
+        limit_config: LimitConfig | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
+        webdav: bool = False,
     ) -> None:
         """Construct a new HTTPStore from a URL.
 
@@ -26,6 +31,19 @@ class HTTPStore:
         Keyword Args:
             client_options: HTTP Client options. Defaults to None.
             retry_config: Retry configuration. Defaults to None.
+            limit_config: Bandwidth and concurrency limits shared by all operations
+                through this store. Defaults to None.
+            resolver: A resolver of the hostnames the store connects to. See
+                [`Resolver`][obstore.store.Resolver]. Defaults to None.
+            request_tags: Static key/value tags for attributing costs to a workload.
+                They are appended to the user agent of every request as `key/value`.
+                Keys and values may only contain ASCII letters, digits, `-`, `_` and
+                `.`. Defaults to None.
+            webdav: List the objects of the store with WebDAV `PROPFIND` requests
+                that walk its collections one level at a time. This works with WebDAV
+                servers such as Nextcloud and dCache, which reject the single
+                `Depth: infinity` request that listing otherwise makes. Defaults to
+                False.
 
         Returns:
             HTTPStore
@@ -39,6 +57,10 @@ class HTTPStore:
         *,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
+        limit_config: LimitConfig | None = None,
+        resolver: Resolver | None = None,
+        request_tags: dict[str, str] | None = None,
+        webdav: bool = False,
     ) -> Self:
         """Construct a new HTTPStore from a URL.
 
@@ -47,6 +69,55 @@ class HTTPStore:
 
     def __eq__(self, value: object) -> bool: ...
     def __getnewargs_ex__(self): ...
+    def to_token(self, *, allow_secrets: bool = False) -> str:
+        """Serialize the configuration of the store to a token.
+
+        A token is a compact string that recreates the store with
+        [`from_token`][obstore.store.HTTPStore.from_token]. It can also be passed
+        in place of the store to any function that takes a store, which reuses the
+        store recreated from a recently passed token. This makes tokens a simple way to
+        send a store to the workers of a distributed framework such as Dask or Ray.
+
+        Keyword Args:
+            allow_secrets: Whether to include the password of the proxy URL in the
+                token. By default it is left out. Anyone with a token that includes
+                secrets can read them.
+
+        Raises:
+            TypeError: If part of the configuration can't be serialized, such as a
+                credential provider that is a Python callback.
+
+        Returns:
+            The token.
+
+        """
+    @classmethod
+    def from_token(cls, token: str) -> Self:
+        """Create a store from a token returned by `to_token`.
+
+        A token is trusted input, as it chooses the URL that requests are sent to. Only
+        create stores from tokens, or pass tokens in place of stores, when they come
+        from a trusted source.
+
+        Args:
+            token: The token.
+
+        Returns:
+            HTTPStore
+
+        Raises:
+            ValueError: If the token is invalid, or is the token of another class of
+                store.
+
+        """
+    def reset_connections(self) -> None:
+        """Close the store's connections and open new ones for the next requests.
+
+        Requests in progress finish on their current connections. This is useful
+        after network changes, or to recover from connections that the provider has
+        reset while idle. To replace connections periodically, set
+        `connection_max_lifetime` in the store's client options instead.
+        """
     @property
     def url(self) -> str:
         """Get the base url of this store."""
@@ -56,3 +127,15 @@ class HTTPStore:
     @property
     def retry_config(self) -> RetryConfig | None:
         """Get the store's retry configuration."""
+    @property
+    def limit_config(self) -> LimitConfig | None:
+        """Get the store's bandwidth and concurrency limits."""
+    @property
+    def resolver(self) -> Resolver | None:
+        """Get the store's hostname resolver."""
+    @property
+    def request_tags(self) -> dict[str, str] | None:
+        """Get the tags attached to every request of the store."""
+    @property
+    def webdav(self) -> bool:
+        """Whether the store lists its objects with WebDAV requests."""
@@ -0,0 +1,42 @@
+from typing import TypedDict
+
+class LimitConfig(TypedDict, total=False):
+    """Limits on the bandwidth and number of concurrent requests of a store.
+
+    The limits are shared by all operations through the same store instance, including
+    copies of it passed to other threads. Any limit that is not set is not enforced.
+
+    ```py
+    from obstore.store import S3Store
+
+    store = S3Store(
+        "bucket",
+        limit_config={"max_bytes_per_second": 10 * 1024 * 1024},
+    )
+    ```
+
+    !!! warning "Not importable at runtime"
+
+        To use this type hint in your code, import it within a `TYPE_CHECKING` block:
+
+        ```py
+        from __future__ import annotations
+        from typing import TYPE_CHECKING
+        if TYPE_CHECKING:
+            from obstore.store import LimitConfig
+        ```
+    """
+
+    max_bytes_per_second: int
+    """The maximum average number of bytes per second uploaded and downloaded.
+
+    Uploads are throttled before each request or part is sent, and downloads as the
+    response body is read. Bursts of up to one second's worth of bytes are allowed.
+    """
+
+    max_concurrent_requests: int
+    """The maximum number of requests in flight at once.
+
+    A download counts as in flight until its response body has been read or dropped,
+    and a listing until it has been exhausted or dropped.
+    """
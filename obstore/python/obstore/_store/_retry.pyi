@@ -1,3 +1,4 @@
+from collections.abc import Callable
 from datetime import timedelta
 from typing import TypedDict
 
@@ -36,12 +37,46 @@ class BackoffConfig(TypedDict, total=False):
     Defaults to `2`.
     """
 
+class RetryEvent(TypedDict):
+    """A failed request attempt, passed to the `on_retry` callback of
+    [`RetryConfig`][obstore.store.RetryConfig].
+
+    !!! warning "Not importable at runtime"
+
+        To use this type hint in your code, import it within a `TYPE_CHECKING` block:
+
+        ```py
+        from __future__ import annotations
+        from typing import TYPE_CHECKING
+        if TYPE_CHECKING:
+            from obstore.store import RetryEvent
+        ```
+    """
+
+    method: str
+    """The HTTP method of the request."""
+
+    url: str
+    """The URL of the request, without its query string."""
+
+    status: int | None
+    """The status of the response, or `None` if no response was received."""
+
+    error: str | None
+    """The error the request failed with, or `None` if a response was received."""
+
+    consecutive_failures: int
+    """The number of requests of the store that have failed in a row, including this
+    one.
+    """
+
 class RetryConfig(TypedDict, total=False):
     """The configuration for how to respond to request errors.
 
     The following categories of error will be retried:
 
     * 5xx server errors
+    * 429 Too Many Requests
     * Connection errors
     * Dropped connections
     * Timeouts for [safe] / read-only requests
@@ -50,6 +85,28 @@ class RetryConfig(TypedDict, total=False):
     backoff with jitter. See [`BackoffConfig`][obstore.store.BackoffConfig] for
     more information
 
+    Heavy workloads may be rate limited by the server, which responds with 429 or 503
+    and often a `Retry-After` header saying how long to wait. Set
+    `respect_retry_after` to wait as long as the server asks, and `throttle_backoff`
+    to back off from rate limiting differently from other errors:
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import GCSStore
+
+    store = GCSStore(
+        "bucket",
+        retry_config={
+            "respect_retry_after": True,
+            "throttle_backoff": {
+                "init_backoff": timedelta(seconds=1),
+                "max_backoff": timedelta(seconds=60),
+            },
+        },
+    )
+    ```
+
     [safe]: https://datatracker.ietf.org/doc/html/rfc7231#section-4.2.1
 
     !!! warning "Not importable at runtime"
@@ -95,3 +152,75 @@ class RetryConfig(TypedDict, total=False):
 
     Defaults to 3 minutes.
     """
+
+    respect_retry_after: bool
+    """Whether to honor the `Retry-After` header of 429 and 503 responses.
+
+    If `True`, a rate limited request is retried after the delay the server asks for,
+    if any, instead of after the delay given by the backoff. The delay still counts
+    towards `retry_timeout`: if it would exceed it, the request fails instead.
+
+    Defaults to `False`.
+    """
+
+    throttle_backoff: BackoffConfig | None
+    """The backoff used for 429 and 503 responses.
+
+    If `None`, rate limited requests use `backoff`. Setting either this or
+    `respect_retry_after` retries rate limited requests separately from other
+    errors, up to `max_retries` times. A request that is still rate limited once
+    those retries are exhausted fails without being retried further.
+
+    Defaults to `None`.
+    """
+
+    on_retry: Callable[[RetryEvent], None] | None
+    """A function called with each request attempt that fails with a retryable error.
+
+    Such an attempt is retried, unless `max_retries` or `retry_timeout` is exhausted.
+    This lets applications count errors, for example to stop submitting new work when
+    the error rate of a store spikes:
+
+    ```py
+    from obstore.store import S3Store
+
+    def on_retry(event):
+        print(event["method"], event["url"], event["status"] or event["error"])
+
+    store = S3Store("bucket", retry_config={"on_retry": on_retry})
+    ```
+
+    The function is called from the runtime's threads, so it should be quick and must
+    not call obstore itself. Exceptions it raises are reported with
+    [`sys.unraisablehook`][sys.unraisablehook] rather than failing the request.
+
+    Defaults to `None`.
+    """
+
+    fail_fast_after: int | None
+    """The number of requests failing in a row after which the store fails fast.
+
+    Once this many requests of the store have failed with a retryable error in a
+    row, new requests fail immediately, without being sent, for
+    `fail_fast_cooldown`. After that, requests are sent again: the first to succeed
+    resets the count, while the first to fail opens the circuit again.
+
+    Defaults to `None`, never failing fast.
+    """
+
+    fail_fast_cooldown: timedelta
+    """How long the store fails fast once `fail_fast_after` requests failed in a row.
+
+    Defaults to 30 seconds.
+    """
+
+    retry_error_body: bool
+    """Whether to keep the body of the last server error (5xx) response.
+
+    Errors raised once the retries of a request are exhausted have the `status`,
+    `body` and `retries` attributes of its last response. The body of request
+    timeout (408) and throttled (429) responses is always kept, but the body of
+    server errors is only read when this is set.
+
+    Defaults to `False`.
+    """
@@ -0,0 +1,45 @@
+from datetime import timedelta
+from typing import TypedDict
+
+class ThrottleConfig(TypedDict, total=False):
+    """Delays applied by a [`ThrottleStore`][obstore.store.ThrottleStore].
+
+    Each wait defaults to zero. Waits are applied before the call is forwarded to the
+    wrapped store, except for the per-byte and per-entry waits, which are applied once
+    the response is available.
+
+    !!! warning "Not importable at runtime"
+
+        To use this type hint in your code, import it within a `TYPE_CHECKING` block:
+
+        ```py
+        from __future__ import annotations
+        from typing import TYPE_CHECKING
+        if TYPE_CHECKING:
+            from obstore.store import ThrottleConfig
+        ```
+    """
+
+    wait_delete_per_call: timedelta
+    """Sleep duration for every call to [`delete`][obstore.delete]."""
+
+    wait_get_per_byte: timedelta
+    """Sleep duration for every byte received during [`get`][obstore.get]."""
+
+    wait_get_per_call: timedelta
+    """Sleep duration for every call to [`get`][obstore.get] and [`head`][obstore.head]."""
+
+    wait_list_per_call: timedelta
+    """Sleep duration for every call to [`list`][obstore.list]."""
+
+    wait_list_per_entry: timedelta
+    """Sleep duration for every entry received during [`list`][obstore.list]."""
+
+    wait_list_with_delimiter_per_call: timedelta
+    """Sleep duration for every call to [`list_with_delimiter`][obstore.list_with_delimiter]."""
+
+    wait_list_with_delimiter_per_entry: timedelta
+    """Sleep duration for every entry received during [`list_with_delimiter`][obstore.list_with_delimiter]."""
+
+    wait_put_per_call: timedelta
+    """Sleep duration for every call to [`put`][obstore.put]."""
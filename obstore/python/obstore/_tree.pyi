@@ -0,0 +1,204 @@
+import sys
+from datetime import datetime
+from os import PathLike
+from typing import TypedDict
+
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 11):
+    from typing import NotRequired
+else:
+    from typing_extensions import NotRequired
+
+class TreeNode(TypedDict):
+    """A directory in the tree returned by [`tree`][obstore.tree].
+
+    The `count` and `size` of a node include all objects under it, at any depth.
+    """
+
+    path: str
+    """The full path of this directory."""
+
+    count: int
+    """The number of objects under this directory."""
+
+    size: int
+    """The total size in bytes of all objects under this directory."""
+
+    children: dict[str, TreeNode]
+    """The subdirectories of this directory, keyed by name.
+
+    This is empty for nodes at the maximum requested `depth`.
+    """
+
+def tree(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    depth: int = 1,
+    max_concurrency: int = 12,
+) -> TreeNode:
+    """Aggregate the number and total size of objects under a prefix, by directory.
+
+    The top-level directories under `prefix` are listed concurrently, so this can be
+    significantly faster than aggregating the output of [`list`][obstore.list] in
+    Python.
+
+    !!! note
+        This lists every object under `prefix`, which may be expensive for large
+        prefixes.
+
+    Args:
+        store: The ObjectStore instance to use.
+        prefix: The prefix to aggregate. Defaults to the root of the store.
+
+    Keyword Args:
+        depth: The number of directory levels below `prefix` to include as `children`.
+            Objects below this depth are still counted in their ancestors. Defaults to
+            `1`.
+        max_concurrency: The maximum number of directories to list concurrently.
+            Defaults to `12`.
+
+    Returns:
+        The root node of the tree.
+
+    """
+
+async def tree_async(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    depth: int = 1,
+    max_concurrency: int = 12,
+) -> TreeNode:
+    """Call `tree` asynchronously.
+
+    Refer to the documentation for [tree][obstore.tree].
+    """
+
+def distinct_prefixes(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    depth: int = 1,
+    *,
+    max_concurrency: int = 12,
+) -> list[str]:
+    """Find the directories up to `depth` levels below a prefix.
+
+    This is what tree views of a bucket need to show its directories, without
+    listing every object like [`tree`][obstore.tree]. Each level is found with a
+    delimiter listing of every directory of the level above, made concurrently in
+    Rust, instead of one Python call per directory:
+
+    ```py
+    import obstore as obs
+
+    obs.distinct_prefixes(store, "data", 2)
+    # ['data/2024', 'data/2024/01', 'data/2024/02', 'data/2025', 'data/2025/01']
+    ```
+
+    The directories are returned once each, in the order of a tree: each directory
+    comes right before the directories under it.
+
+    Args:
+        store: The ObjectStore instance to use.
+        prefix: The prefix to find directories under. Defaults to the root of the
+            store.
+        depth: The number of directory levels below `prefix` to find. Each level is
+            one more round of requests. Defaults to `1`, which only finds the
+            directories directly under `prefix`.
+
+    Keyword Args:
+        max_concurrency: The maximum number of directories to list concurrently.
+            Defaults to `12`.
+
+    Returns:
+        The full paths of the directories, without a trailing `/`.
+
+    """
+
+async def distinct_prefixes_async(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    depth: int = 1,
+    *,
+    max_concurrency: int = 12,
+) -> list[str]:
+    """Call `distinct_prefixes` asynchronously.
+
+    Refer to the documentation for [distinct_prefixes][obstore.distinct_prefixes].
+    """
+
+class PrefixStats(TypedDict):
+    """Statistics of the objects under a prefix, returned by
+    [`stat_prefix`][obstore.stat_prefix].
+    """  # noqa: D205
+
+    count: int
+    """The number of objects under the prefix."""
+
+    size: int
+    """The total size in bytes of the objects under the prefix."""
+
+    min_last_modified: datetime | None
+    """The earliest last modified time of the objects, or `None` if there are none."""
+
+    max_last_modified: datetime | None
+    """The latest last modified time of the objects, or `None` if there are none."""
+
+    directories: NotRequired[dict[str, PrefixStats]]
+    """The statistics of each directory directly under the prefix, keyed by name.
+
+    This is only present with `by_directory=True`. Objects directly under the prefix
+    are only counted in the totals.
+    """
+
+def stat_prefix(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    by_directory: bool = False,
+    max_concurrency: int = 12,
+) -> PrefixStats:
+    """Count the objects under a prefix, and aggregate their size and last modified
+    times.
+
+    Like `du -s`, this consumes the whole listing of `prefix` in Rust, without
+    converting each object to Python, and lists the top-level directories under
+    `prefix` concurrently. This makes it much faster than iterating over the output
+    of [`list`][obstore.list] for prefixes with millions of objects.
+
+    ```py
+    import obstore as obs
+
+    stats = obs.stat_prefix(store, "data/", by_directory=True)
+    for name, directory in stats["directories"].items():
+        print(name, directory["count"], directory["size"])
+    ```
+
+    Args:
+        store: The ObjectStore instance to use.
+        prefix: The prefix to aggregate. Defaults to the root of the store.
+
+    Keyword Args:
+        by_directory: If `True`, also return the statistics of each directory directly
+            under `prefix`, as `directories`. Defaults to `False`.
+        max_concurrency: The maximum number of directories to list concurrently.
+            Defaults to `12`.
+
+    Returns:
+        The statistics of the objects under `prefix`.
+
+    """  # noqa: D205
+
+async def stat_prefix_async(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    by_directory: bool = False,
+    max_concurrency: int = 12,
+) -> PrefixStats:
+    """Call `stat_prefix` asynchronously.
+
+    Refer to the documentation for [stat_prefix][obstore.stat_prefix].
+    """
@@ -0,0 +1,93 @@
+import sys
+from datetime import datetime, timedelta
+from os import PathLike
+from typing import TypedDict
+
+from .store import AzureStore, GCSStore, S3Store
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+VersionedStore: TypeAlias = AzureStore | GCSStore | S3Store
+"""ObjectStore instances whose backends can keep versions of objects."""
+
+class ObjectVersion(TypedDict):
+    """A version of an object, as returned by
+    [`list_versions`][obstore.list_versions].
+    """  # noqa: D205
+
+    path: str
+    """The path to the object"""
+
+    version: str
+    """The identifier of the version.
+
+    This is the version ID on S3 and Azure, and the generation on GCS. It can be
+    passed as `version` in [`GetOptions`][obstore.GetOptions] to read this version,
+    or to [`delete`][obstore.delete] to delete it.
+    """
+
+    is_latest: bool
+    """Whether this is the current version of the object."""
+
+    is_delete_marker: bool
+    """Whether this version is a delete marker, left by deleting the object.
+
+    Delete markers are only used by S3. On GCS and Azure, deleting an object instead
+    leaves none of its versions as the latest.
+    """
+
+    last_modified: datetime
+    """The time this version was written"""
+
+    size: int
+    """The size in bytes of this version. This is `0` for delete markers."""
+
+    e_tag: str | None
+    """The unique identifier for this version
+    <https://datatracker.ietf.org/doc/html/rfc9110#name-etag>
+    """
+
+def list_versions(
+    store: VersionedStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    timeout: timedelta | None = None,
+) -> list[ObjectVersion]:
+    """List every version of the objects under a prefix.
+
+    This requires versioning to be enabled on the bucket or container. Versions are
+    returned in the order of the store, which lists all versions of an object
+    together.
+
+    Args:
+        store: The S3Store, GCSStore or AzureStore instance to use.
+        prefix: The prefix within the store to list versions under. Prefixes are
+            evaluated on a path segment basis, as in [`list`][obstore.list].
+
+    Keyword Args:
+        timeout: The maximum time the call may take, across all of its requests. If
+            the call hasn't completed by then, a
+            [`TimeoutError`][obstore.exceptions.TimeoutError] is raised. Defaults to
+            `None`, for no limit.
+
+    Raises:
+        ValueError: if `store` is not an `S3Store`, `GCSStore` or `AzureStore`.
+
+    Returns:
+        The versions of the objects, including delete markers.
+
+    """
+
+async def list_versions_async(
+    store: VersionedStore,
+    prefix: str | PathLike[str] | None = None,
+    *,
+    timeout: timedelta | None = None,
+) -> list[ObjectVersion]:
+    """Call `list_versions` asynchronously.
+
+    Refer to the documentation for [list_versions][obstore.list_versions].
+    """
@@ -0,0 +1,99 @@
+import sys
+from datetime import timedelta
+from os import PathLike
+from typing import Literal, TypedDict
+
+from ._list import ObjectMeta
+from ._store import ObjectStore
+
+if sys.version_info >= (3, 11):
+    from typing import Self
+else:
+    from typing_extensions import Self
+
+class ChangeEvent(TypedDict):
+    """A change to an object, returned by [`watch`][obstore.watch] and
+    [`parse_events`][obstore.parse_events].
+    """  # noqa: D205
+
+    change: Literal["created", "modified", "deleted"]
+    """How the object changed.
+
+    - `"created"`: the object didn't exist at the previous poll.
+    - `"modified"`: the object was overwritten since the previous poll, as seen by a
+      different ETag, or a different size or modification time in stores without
+      ETags.
+    - `"deleted"`: the object existed at the previous poll, but doesn't anymore.
+
+    For event notifications, these are the kind of event instead.
+    """
+
+    path: str
+    """The path of the object within the store."""
+
+    meta: ObjectMeta
+    """The metadata of the object, or its last known metadata if it was deleted."""
+
+class WatchStream:
+    """An endless async iterator of [`ChangeEvent`s][obstore.ChangeEvent], returned by
+    [`watch`][obstore.watch].
+    """  # noqa: D205
+
+    def __aiter__(self) -> Self:
+        """Return `Self` as an async iterator."""
+
+    async def __anext__(self) -> ChangeEvent:
+        """Return the next change, waiting for it as long as needed."""
+
+def watch(
+    store: ObjectStore,
+    prefix: str | PathLike[str] | None = None,
+    poll_interval: timedelta = ...,
+    *,
+    include_existing: bool = False,
+) -> WatchStream:
+    """Watch a prefix for objects that are created, modified or deleted.
+
+    This lets applications react to new files landing in a bucket, without wiring up
+    the notification services of each cloud. The prefix is listed every
+    `poll_interval`, and each listing is compared with the one before it in Rust, so
+    only the changes cross into Python:
+
+    ```py
+    from datetime import timedelta
+
+    import obstore as obs
+
+    async for event in obs.watch(store, "incoming", timedelta(seconds=30)):
+        if event["change"] == "created":
+            await process(event["path"])
+    ```
+
+    Changes are coalesced between two polls: an object that is overwritten several
+    times is reported as modified once, and an object that is created and deleted
+    again in between isn't reported at all. The changes found by one poll are
+    returned in order of path.
+
+    !!! note
+        Each poll lists every object under `prefix`, which is billed as one list
+        request per 1,000 objects on most cloud stores. For large prefixes, choose a
+        narrow prefix or a longer `poll_interval`, or use the event notifications of
+        the store instead.
+
+    Args:
+        store: The ObjectStore instance to watch.
+        prefix: The prefix to watch. Defaults to `None`, which watches every object
+            in `store`.
+        poll_interval: The time between the start of two polls. Defaults to 10
+            seconds.
+
+    Keyword Args:
+        include_existing: Whether to report the objects found by the first poll as
+            created. Defaults to `False`, which only reports changes after the first
+            poll.
+
+    Returns:
+        An async iterator of changes, which never ends by itself. A failed poll
+        raises its error from the iterator.
+
+    """
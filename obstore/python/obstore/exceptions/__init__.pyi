@@ -2,8 +2,31 @@
 # pylance isn't able to find that. So this is an exceptions module with only
 # `__init__.pyi` to work around pylance's bug.
 
+import builtins
+
 class BaseError(Exception):
-    """The base exception class."""
+    """The base exception class.
+
+    Errors caused by a request that got a server error (5xx), request timeout (408)
+    or throttled (429) response once its retries were exhausted carry details of
+    the last response to it. These attributes are `None` for other errors, such as
+    client error (4xx) responses, whose status and body are in the message.
+    """
+
+    status: int | None
+    """The HTTP status of the last response, if any."""
+
+    body: str | None
+    """The body of the last response, if any.
+
+    The body of server error (5xx) responses is only kept when the
+    [`retry_error_body`][obstore.store.RetryConfig.retry_error_body] retry option is
+    set.
+    """
+
+    retries: int | None
+    """How many times the request was retried before the error was raised, if the
+    error was caused by a response."""
 
 class GenericError(BaseError):
     """A fallback error type when no variant matches."""
@@ -41,3 +64,33 @@ class UnauthenticatedError(BaseError):
 
 class UnknownConfigurationKeyError(BaseError):
     """Error when a configuration key is invalid for the store used."""
+
+class TimeoutError(BaseError, builtins.TimeoutError):  # noqa: A001
+    """Error when a request to the store times out.
+
+    This is raised once the request has been retried according to the `retry_config`
+    of the store, and can be caught to retry transient network failures. This also
+    subclasses the builtin `TimeoutError`, like
+    [`StreamTimeoutError`][obstore.exceptions.StreamTimeoutError].
+
+    A timeout is recognized from the error that caused it, so a request that times
+    out before a response is received is only raised as this error with versions of
+    `object_store` that expose the cause of failed requests. Otherwise, it's raised
+    as a [`GenericError`][obstore.exceptions.GenericError].
+    """
+
+class ConnectError(BaseError):
+    """Error when a connection to the store can't be established.
+
+    This is raised once the request has been retried according to the `retry_config`
+    of the store, and can be caught to retry transient network failures. Like
+    [`TimeoutError`][obstore.exceptions.TimeoutError], this is only raised with
+    versions of `object_store` that expose the cause of failed requests.
+    """
+
+class StreamTimeoutError(builtins.TimeoutError):
+    """Error when a stream receives no data within its `chunk_timeout`.
+
+    This subclasses the builtin `TimeoutError`, which is also raised by
+    `asyncio.wait_for`. The stream isn't closed by the error, so iteration can resume.
+    """
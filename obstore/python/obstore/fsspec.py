@@ -56,6 +56,7 @@ if TYPE_CHECKING:
     from obstore.store import (
         AzureConfig,
         AzureCredentialProvider,
+        ChainCredentialProvider,
         ClientConfig,
         GCSConfig,
         GCSCredentialProvider,
@@ -130,7 +131,11 @@ class FsspecStore(fsspec.asyn.AsyncFileSystem):
         config: S3Config | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: S3CredentialProvider | None = None,
+        credential_provider: S3CredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[S3CredentialProvider | str]
+        | None = None,
         asynchronous: bool = False,
         max_cache_size: int = 10,
         loop: Any = None,
@@ -145,7 +150,11 @@ class FsspecStore(fsspec.asyn.AsyncFileSystem):
         config: GCSConfig | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: GCSCredentialProvider | None = None,
+        credential_provider: GCSCredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[GCSCredentialProvider | str]
+        | None = None,
         asynchronous: bool = False,
         max_cache_size: int = 10,
         loop: Any = None,
@@ -160,7 +169,11 @@ class FsspecStore(fsspec.asyn.AsyncFileSystem):
         config: AzureConfig | None = None,
         client_options: ClientConfig | None = None,
         retry_config: RetryConfig | None = None,
-        credential_provider: AzureCredentialProvider | None = None,
+        credential_provider: AzureCredentialProvider
+        | str
+        | ChainCredentialProvider
+        | list[AzureCredentialProvider | str]
+        | None = None,
         asynchronous: bool = False,
         max_cache_size: int = 10,
         loop: Any = None,
@@ -336,6 +349,37 @@ class FsspecStore(fsspec.asyn.AsyncFileSystem):
         store = self._construct_store(bucket)
         return await obs.delete_async(store, path)
 
+    async def _rm(
+        self,
+        path: str | list[str],
+        recursive: bool = False,
+        batch_size: int | None = None,  # noqa: ARG002
+        **_kwargs: Any,
+    ) -> None:
+        files: list[str] = []
+        for p in await self._expand_path(path):
+            # Raises FileNotFoundError if nothing exists at `p`
+            info = await self._info(p)
+            if info["type"] == "file":
+                files.append(p)
+            elif recursive:
+                # Directories don't exist as objects, so only the files under them are
+                # deleted
+                files.extend(await self._find(p))
+            else:
+                raise IsADirectoryError(p)
+
+        paths_by_bucket: dict[str, list[str]] = defaultdict(list)
+        for file in files:
+            bucket, path_no_bucket = self._split_path(file)
+            paths_by_bucket[bucket].append(path_no_bucket)
+        await asyncio.gather(
+            *(
+                obs.delete_async(self._construct_store(bucket), paths)
+                for bucket, paths in paths_by_bucket.items()
+            ),
+        )
+
     async def _cp_file(self, path1: str, path2: str, **_kwargs: Any) -> None:
         bucket1, path1_no_bucket = self._split_path(path1)
         bucket2, path2_no_bucket = self._split_path(path2)
@@ -376,10 +420,13 @@ class FsspecStore(fsspec.asyn.AsyncFileSystem):
             resp = await obs.get_async(store, path)
             return (await resp.bytes_async()).to_bytes()
 
-        if start is None or end is None:
-            raise NotImplementedError(
-                "cat_file not implemented for start=None xor end=None",
-            )
+        if start is None or end is None or start < 0 or end < 0:
+            # Open-ended and negative offsets are relative to the size of the file
+            size = (await obs.head_async(store, path))["size"]
+            start, end, _ = slice(start, end).indices(size)
+
+        if start >= end:
+            return b""
 
         range_bytes = await obs.get_range_async(store, path, start=start, end=end)
         return range_bytes.to_bytes()
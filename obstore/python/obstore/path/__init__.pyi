@@ -0,0 +1,32 @@
+# Note: This is a module with only `__init__.pyi`, like `obstore.exceptions`, to work
+# around pylance not finding a `path.pyi` file one level above.
+
+from os import PathLike
+
+def normalize(path: str | PathLike[str]) -> str:
+    """Sanitize a path with the rules applied by the underlying Rust `object_store`.
+
+    Functions that take a path, such as [`get`][obstore.get] and
+    [`put`][obstore.put], accept a `str` or any
+    [`os.PathLike`][os.PathLike], such as a [`pathlib.PurePosixPath`][pathlib.PurePosixPath].
+    They raise an [`InvalidPathError`][obstore.exceptions.InvalidPathError] for a path
+    with empty segments, `.` or `..` segments, or control characters.
+
+    `normalize` instead turns any string into a valid path: leading, trailing and
+    repeated `/` are removed, and characters that some stores don't support are
+    percent-encoded.
+
+    ```py
+    from obstore.path import normalize
+
+    normalize("/data//2024/file.txt")  # "data/2024/file.txt"
+    normalize("data/../file.txt")  # "data/%2E%2E/file.txt"
+    ```
+
+    Args:
+        path: The path to normalize.
+
+    Returns:
+        The normalized path.
+
+    """
@@ -0,0 +1,208 @@
+# Note: This is a module with only `__init__.pyi`, like `obstore.exceptions`, to work
+# around pylance not finding a `runtime.pyi` file one level above.
+
+import sys
+from collections.abc import Callable
+from datetime import datetime, timedelta
+from typing import Literal, TypedDict
+
+if sys.version_info >= (3, 10):
+    from typing import TypeAlias
+else:
+    from typing_extensions import TypeAlias
+
+Runtime: TypeAlias = Literal["sync", "async"]
+"""One of the two tokio runtimes used by obstore.
+
+- `"sync"`: the runtime that runs requests made by synchronous functions, such as
+  [`get`][obstore.get].
+- `"async"`: the runtime that runs requests made by asynchronous functions, such as
+  [`get_async`][obstore.get_async].
+"""
+
+class RuntimeStats(TypedDict):
+    """A snapshot of the metrics of a tokio runtime.
+
+    Some metrics are only collected by tokio when obstore is built with
+    `RUSTFLAGS="--cfg tokio_unstable"`. In release builds they are `None`.
+    """
+
+    workers: int
+    """The number of worker threads running tasks."""
+
+    alive_tasks: int
+    """The number of tasks that have been spawned and have not yet completed."""
+
+    global_queue_depth: int
+    """The number of tasks waiting in the runtime's global queue.
+
+    A queue that stays deep means that tasks are being scheduled faster than the
+    workers can run them.
+    """
+
+    blocking_threads: int | None
+    """The number of threads spawned for blocking work, such as file system access.
+
+    `None` unless built with `tokio_unstable`.
+    """
+
+    idle_blocking_threads: int | None
+    """The number of blocking threads that are idle.
+
+    `None` unless built with `tokio_unstable`.
+    """
+
+    worker_utilization: list[float] | None
+    """The fraction of time each worker was busy, between 0 and 1.
+
+    This covers the time since the previous call to [`stats`][obstore.runtime.stats]
+    for the same runtime. `None` on the first call for a runtime, or unless built with
+    `tokio_unstable`.
+    """
+
+def stats(runtime: Runtime = "sync") -> RuntimeStats:
+    """Sample the metrics of one of obstore's tokio runtimes.
+
+    This helps diagnose calls that are slow because the runtime is starved, rather
+    than because the store is slow to respond. For example, many tasks waiting in the
+    global queue while all workers are busy means that more work is being submitted
+    than the runtime can keep up with.
+
+    ```py
+    from obstore.runtime import stats
+
+    runtime_stats = stats("async")
+    print(runtime_stats["alive_tasks"], runtime_stats["global_queue_depth"])
+    ```
+
+    Args:
+        runtime: The runtime to sample. Defaults to `"sync"`.
+
+    Returns:
+        The runtime's current metrics.
+
+    """
+
+class TraceRecord(TypedDict):
+    """The record of a finished store operation, passed to the callback of
+    [`enable_tracing`][obstore.runtime.enable_tracing].
+    """
+
+    operation: str
+    """The operation, such as `"get"`, `"put"`, `"list"` or `"delete"`."""
+
+    path: str
+    """The path of the operation, or the prefix of a list operation."""
+
+    bytes: int
+    """The number of bytes uploaded or downloaded."""
+
+    start: datetime
+    """When the operation started."""
+
+    duration: timedelta
+    """How long the operation took.
+
+    Operations returning a stream, such as [`get`][obstore.get] and
+    [`list`][obstore.list], finish once the stream has been consumed or dropped.
+    """
+
+    error: str | None
+    """The error the operation failed with, if any."""
+
+def enable_tracing(callback: Callable[[TraceRecord], None] | None = None) -> None:
+    """Trace every store operation started from now on.
+
+    Each operation, such as a get, put, list or delete, emits a span named
+    `store_operation` with target `obstore` and `operation`, `path`, `bytes` and
+    `error` fields, so that it is reported to any Rust
+    [`tracing`](https://docs.rs/tracing) subscriber.
+
+    If `callback` is given, it is also called with a
+    [`TraceRecord`][obstore.runtime.TraceRecord] once each operation finishes. This
+    makes it possible to export operations from Python, such as to an OTLP collector
+    with the [OpenTelemetry SDK](https://opentelemetry.io/docs/languages/python/):
+
+    ```py
+    from opentelemetry import trace
+    from obstore.runtime import enable_tracing
+
+    tracer = trace.get_tracer("obstore")
+
+    def export(record):
+        start = int(record["start"].timestamp() * 1e9)
+        span = tracer.start_span(
+            f"obstore.{record['operation']}",
+            start_time=start,
+            attributes={"path": record["path"], "bytes": record["bytes"]},
+        )
+        if record["error"] is not None:
+            span.set_status(trace.Status(trace.StatusCode.ERROR, record["error"]))
+        span.end(end_time=start + int(record["duration"].total_seconds() * 1e9))
+
+    enable_tracing(export)
+    ```
+
+    The callback is called from the runtime's threads, so it should be quick and must
+    not call obstore itself. Exceptions raised by the callback are reported with
+    [`sys.unraisablehook`][sys.unraisablehook] rather than failing the operation.
+
+    Calling `enable_tracing` again replaces the callback.
+
+    Args:
+        callback: A function called with the record of each finished operation.
+            Defaults to `None`, only reporting operations to `tracing` subscribers.
+
+    """
+
+def disable_tracing() -> None:
+    """Stop tracing store operations started from now on.
+
+    This reverses [`enable_tracing`][obstore.runtime.enable_tracing].
+    """
+
+def set_object_meta_dicts(dicts: bool) -> None:  # noqa: FBT001
+    """Set whether object metadata is returned as plain `dict`s.
+
+    [`head`][obstore.head], [`list`][obstore.list] and
+    [`GetResult.meta`][obstore.GetResult.meta] return
+    [`ObjectMeta`][obstore.ObjectMeta] instances by default. Passing `True` restores
+    the [`ObjectMetaDict`][obstore.ObjectMetaDict] returned by earlier versions of
+    obstore, e.g. for code that serializes the metadata as JSON or mutates it.
+
+    ```py
+    from obstore.runtime import set_object_meta_dicts
+
+    set_object_meta_dicts(True)
+    meta = obs.head(store, "file.txt")
+    meta["size"] += 1
+    ```
+
+    The setting applies to the whole process.
+
+    Args:
+        dicts: Whether object metadata is returned as `dict`s.
+
+    """
+
+def set_strict_keys(strict: bool) -> None:  # noqa: FBT001
+    """Set whether unknown keys in option dicts raise an error.
+
+    Option dicts, such as the `options` of [`get`][obstore.get], the `mode` of
+    [`put`][obstore.put], and the `retry_config` of stores, are strict by default: a
+    key that isn't part of the dict's type raises a `ValueError` listing the unknown
+    keys along with the nearest expected key, so that a misspelled option isn't
+    silently ignored.
+
+    ```py
+    obs.get(store, "file.txt", options={"if_matches": etag})
+    # ValueError: Unknown key in options: 'if_matches' (did you mean 'if_match'?). ...
+    ```
+
+    Passing `False` ignores unknown keys instead, e.g. for code that shares a dict
+    between functions or must run on several versions of obstore.
+
+    Args:
+        strict: Whether unknown keys raise an error.
+
+    """
@@ -2,7 +2,9 @@
 
 from __future__ import annotations
 
-from typing import TYPE_CHECKING, Union, overload
+import os
+from datetime import timedelta
+from typing import TYPE_CHECKING, Any, ClassVar, Union, overload
 
 import obstore as obs
 from obstore._obstore import _store
@@ -18,17 +20,24 @@ if TYPE_CHECKING:
         Iterator,
         Sequence,
     )
+    from datetime import datetime
+    from os import PathLike
     from pathlib import Path
-    from typing import IO, Any, Literal
+    from typing import IO, Literal
 
     from arro3.core import RecordBatch, Table
+    from numpy.typing import DTypeLike, NDArray
 
     from obstore import (
         Attributes,
+        Compression,
         GetOptions,
+        ListFilter,
         ListResult,
         ListStream,
+        MultipartPolicy,
         ObjectMeta,
+        ProgressCallback,
         PutMode,
         PutResult,
     )
@@ -45,10 +54,16 @@ if TYPE_CHECKING:
         GCSConfig,  # noqa: TC004
         GCSCredential,  # noqa: TC004
         GCSCredentialProvider,  # noqa: TC004
+        KeyProvider,  # noqa: TC004
+        LimitConfig,  # noqa: TC004
+        Resolver,  # noqa: TC004
         RetryConfig,  # noqa: TC004
+        RetryEvent,  # noqa: TC004
+        S3Compatibility,  # noqa: TC004
         S3Config,  # noqa: TC004
         S3Credential,  # noqa: TC004
         S3CredentialProvider,  # noqa: TC004
+        ThrottleConfig,  # noqa: TC004
     )
 
     if sys.version_info >= (3, 10):
@@ -57,9 +72,9 @@ if TYPE_CHECKING:
         from typing_extensions import TypeAlias
 
     if sys.version_info >= (3, 11):
-        from typing import Unpack
+        from typing import Self, Unpack
     else:
-        from typing_extensions import Unpack
+        from typing_extensions import Self, Unpack
 
     if sys.version_info >= (3, 12):
         from collections.abc import Buffer
@@ -76,25 +91,59 @@ __all__ = [
     "AzureSASToken",
     "AzureStore",
     "BackoffConfig",
+    "CacheStore",
+    "ChainCredentialProvider",
+    "ChaosStore",
     "ClientConfig",
+    "ConsistentStore",
+    "CredentialProviderHandle",
+    "DeadlineStore",
+    "DictStore",
+    "EncryptedStore",
     "GCSConfig",
     "GCSCredential",
     "GCSCredentialProvider",
     "GCSStore",
     "HTTPStore",
+    "HdfsStore",
+    "KeyProvider",
+    "LimitConfig",
     "LocalStore",
     "MemoryStore",
+    "MirrorStore",
+    "NegativeCacheStore",
+    "PrefixStore",
+    "RangeCacheStore",
+    "Resolver",
     "RetryConfig",
+    "RetryEvent",
+    "S3Compatibility",
     "S3Config",
     "S3Credential",
     "S3CredentialProvider",
     "S3Store",
+    "SFTPStore",
+    "ThrottleConfig",
+    "ThrottleStore",
     "from_url",
 ]
 
+ChainCredentialProvider = _store.ChainCredentialProvider
+CredentialProviderHandle = _store.CredentialProviderHandle
+
 
 class _ObjectStoreMixin:
-    def copy(self, from_: str, to: str, *, overwrite: bool = True) -> None:
+    def copy(
+        self,
+        from_: str | PathLike[str],
+        to: str | PathLike[str],
+        *,
+        overwrite: bool = True,
+        multipart_threshold: int | None = None,
+        part_size: int = 512 * 1024 * 1024,
+        max_concurrency: int = 8,
+        timeout: timedelta | None = None,
+    ) -> None:
         """Copy an object from one path to another in the same object store.
 
         Refer to the documentation for [copy][obstore.copy].
@@ -104,14 +153,22 @@ class _ObjectStoreMixin:
             from_,
             to,
             overwrite=overwrite,
+            multipart_threshold=multipart_threshold,
+            part_size=part_size,
+            max_concurrency=max_concurrency,
+            timeout=timeout,
         )
 
     async def copy_async(
         self,
-        from_: str,
-        to: str,
+        from_: str | PathLike[str],
+        to: str | PathLike[str],
         *,
         overwrite: bool = True,
+        multipart_threshold: int | None = None,
+        part_size: int = 512 * 1024 * 1024,
+        max_concurrency: int = 8,
+        timeout: timedelta | None = None,
     ) -> None:
         """Call `copy` asynchronously.
 
@@ -122,9 +179,19 @@ class _ObjectStoreMixin:
             from_,
             to,
             overwrite=overwrite,
+            multipart_threshold=multipart_threshold,
+            part_size=part_size,
+            max_concurrency=max_concurrency,
+            timeout=timeout,
         )
 
-    def delete(self, paths: str | Sequence[str]) -> None:
+    def delete(
+        self,
+        paths: str | PathLike[str] | Sequence[str | PathLike[str]],
+        *,
+        version: str | None = None,
+        timeout: timedelta | None = None,
+    ) -> None:
         """Delete the object at the specified location(s).
 
         Refer to the documentation for [delete][obstore.delete].
@@ -132,9 +199,17 @@ class _ObjectStoreMixin:
         return obs.delete(
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             paths,
+            version=version,
+            timeout=timeout,
         )
 
-    async def delete_async(self, paths: str | Sequence[str]) -> None:
+    async def delete_async(
+        self,
+        paths: str | PathLike[str] | Sequence[str | PathLike[str]],
+        *,
+        version: str | None = None,
+        timeout: timedelta | None = None,
+    ) -> None:
         """Call `delete` asynchronously.
 
         Refer to the documentation for [delete][obstore.delete].
@@ -142,13 +217,18 @@ class _ObjectStoreMixin:
         return await obs.delete_async(
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             paths,
+            version=version,
+            timeout=timeout,
         )
 
     def get(
         self,
-        path: str,
+        path: str | PathLike[str],
         *,
         options: GetOptions | None = None,
+        progress: ProgressCallback | None = None,
+        progress_interval: timedelta = timedelta(milliseconds=100),
+        timeout: timedelta | None = None,
     ) -> GetResult:
         """Return the bytes that are stored at the specified location.
 
@@ -158,13 +238,19 @@ class _ObjectStoreMixin:
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             path,
             options=options,
+            progress=progress,
+            progress_interval=progress_interval,
+            timeout=timeout,
         )
 
     async def get_async(
         self,
-        path: str,
+        path: str | PathLike[str],
         *,
         options: GetOptions | None = None,
+        progress: ProgressCallback | None = None,
+        progress_interval: timedelta = timedelta(milliseconds=100),
+        timeout: timedelta | None = None,
     ) -> GetResult:
         """Call `get` asynchronously.
 
@@ -174,51 +260,168 @@ class _ObjectStoreMixin:
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             path,
             options=options,
+            progress=progress,
+            progress_interval=progress_interval,
+            timeout=timeout,
         )
 
+    def get_if_changed(
+        self,
+        path: str | PathLike[str],
+        etag: str | None = None,
+        last_modified: datetime | None = None,
+        *,
+        timeout: timedelta | None = None,
+    ) -> GetResult | None:
+        """Return the object at the specified location, unless it hasn't changed.
+
+        Refer to the documentation for [get_if_changed][obstore.get_if_changed].
+        """
+        return obs.get_if_changed(
+            self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
+            path,
+            etag,
+            last_modified,
+            timeout=timeout,
+        )
+
+    async def get_if_changed_async(
+        self,
+        path: str | PathLike[str],
+        etag: str | None = None,
+        last_modified: datetime | None = None,
+        *,
+        timeout: timedelta | None = None,
+    ) -> GetResult | None:
+        """Call `get_if_changed` asynchronously.
+
+        Refer to the documentation for [get_if_changed][obstore.get_if_changed].
+        """
+        return await obs.get_if_changed_async(
+            self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
+            path,
+            etag,
+            last_modified,
+            timeout=timeout,
+        )
+
+    @overload
+    def get_range(
+        self,
+        path: str | PathLike[str],
+        *,
+        start: int,
+        end: int | None = None,
+        length: int | None = None,
+        return_numpy: Literal[False] = False,
+    ) -> Bytes: ...
+    @overload
     def get_range(
         self,
-        path: str,
+        path: str | PathLike[str],
         *,
         start: int,
         end: int | None = None,
         length: int | None = None,
-    ) -> Bytes:
+        return_numpy: Literal[True],
+        dtype: DTypeLike = None,
+    ) -> NDArray[Any]: ...
+    def get_range(
+        self,
+        path: str | PathLike[str],
+        *,
+        start: int,
+        end: int | None = None,
+        length: int | None = None,
+        return_numpy: bool = False,
+        dtype: DTypeLike = None,
+    ) -> Bytes | NDArray[Any]:
         """Return the bytes stored at the specified location in the given byte range.
 
         Refer to the documentation for [get_range][obstore.get_range].
         """
+        # Splitting these fixes the typing issue with the `return_numpy` parameter, by
+        # converting from a bool to a Literal[True] or Literal[False]
+        if return_numpy:
+            return obs.get_range(
+                self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
+                path,
+                start=start,
+                end=end,
+                length=length,
+                return_numpy=return_numpy,
+                dtype=dtype,
+            )
+
         return obs.get_range(
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             path,
             start=start,
             end=end,
             length=length,
+            return_numpy=return_numpy,
         )
 
+    @overload
+    async def get_range_async(
+        self,
+        path: str | PathLike[str],
+        *,
+        start: int,
+        end: int | None = None,
+        length: int | None = None,
+        return_numpy: Literal[False] = False,
+    ) -> Bytes: ...
+    @overload
     async def get_range_async(
         self,
-        path: str,
+        path: str | PathLike[str],
         *,
         start: int,
         end: int | None = None,
         length: int | None = None,
-    ) -> Bytes:
+        return_numpy: Literal[True],
+        dtype: DTypeLike = None,
+    ) -> NDArray[Any]: ...
+    async def get_range_async(
+        self,
+        path: str | PathLike[str],
+        *,
+        start: int,
+        end: int | None = None,
+        length: int | None = None,
+        return_numpy: bool = False,
+        dtype: DTypeLike = None,
+    ) -> Bytes | NDArray[Any]:
         """Call `get_range` asynchronously.
 
         Refer to the documentation for [get_range][obstore.get_range].
         """
+        # Splitting these fixes the typing issue with the `return_numpy` parameter, by
+        # converting from a bool to a Literal[True] or Literal[False]
+        if return_numpy:
+            return await obs.get_range_async(
+                self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
+                path,
+                start=start,
+                end=end,
+                length=length,
+                return_numpy=return_numpy,
+                dtype=dtype,
+            )
+
         return await obs.get_range_async(
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             path,
             start=start,
             end=end,
             length=length,
+            return_numpy=return_numpy,
         )
 
     def get_ranges(
         self,
-        path: str,
+        path: str | PathLike[str],
         *,
         starts: Sequence[int],
         ends: Sequence[int] | None = None,
@@ -238,7 +441,7 @@ class _ObjectStoreMixin:
 
     async def get_ranges_async(
         self,
-        path: str,
+        path: str | PathLike[str],
         *,
         starts: Sequence[int],
         ends: Sequence[int] | None = None,
@@ -256,7 +459,57 @@ class _ObjectStoreMixin:
             lengths=lengths,
         )
 
-    def head(self, path: str) -> ObjectMeta:
+    def read_ranges_into(
+        self,
+        path: str | PathLike[str],
+        buffers: Sequence[Buffer],
+        *,
+        starts: Sequence[int],
+        ends: Sequence[int] | None = None,
+        lengths: Sequence[int] | None = None,
+    ) -> list[int]:
+        """Read byte ranges of an object directly into caller-provided buffers.
+
+        Refer to the documentation for [read_ranges_into][obstore.read_ranges_into].
+        """
+        return obs.read_ranges_into(
+            self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
+            path,
+            buffers,
+            starts=starts,
+            ends=ends,
+            lengths=lengths,
+        )
+
+    async def read_ranges_into_async(
+        self,
+        path: str | PathLike[str],
+        buffers: Sequence[Buffer],
+        *,
+        starts: Sequence[int],
+        ends: Sequence[int] | None = None,
+        lengths: Sequence[int] | None = None,
+    ) -> list[int]:
+        """Call `read_ranges_into` asynchronously.
+
+        Refer to the documentation for [read_ranges_into][obstore.read_ranges_into].
+        """
+        return await obs.read_ranges_into_async(
+            self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
+            path,
+            buffers,
+            starts=starts,
+            ends=ends,
+            lengths=lengths,
+        )
+
+    def head(
+        self,
+        path: str | PathLike[str],
+        *,
+        headers: dict[str, str] | None = None,
+        timeout: timedelta | None = None,
+    ) -> ObjectMeta:
         """Return the metadata for the specified location.
 
         Refer to the documentation for [head][obstore.head].
@@ -264,9 +517,17 @@ class _ObjectStoreMixin:
         return obs.head(
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             path,
+            headers=headers,
+            timeout=timeout,
         )
 
-    async def head_async(self, path: str) -> ObjectMeta:
+    async def head_async(
+        self,
+        path: str | PathLike[str],
+        *,
+        headers: dict[str, str] | None = None,
+        timeout: timedelta | None = None,
+    ) -> ObjectMeta:
         """Call `head` asynchronously.
 
         Refer to the documentation for [head_async][obstore.head_async].
@@ -274,32 +535,64 @@ class _ObjectStoreMixin:
         return await obs.head_async(
             self,  # type: ignore (Argument of type "Self@_ObjectStoreMixin" cannot be assigned to parameter "store")
             path,
+            headers=headers,
+            timeout=timeout,
         )
 
     @overload
     def list(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
-        offset: str | None = None,
+        offset: str | PathLike[str] | None = None,
         chunk_size: int = 50,
+        stable_chunks: bool = False,
+        order_by: Literal["path", "last_modified", "size"] | None = None,
+        descending: bool = False,
+        sort_memory_limit: int = 268_435_456,
+        glob: str | None = None,
+        filter: ListFilter | None = None,  # noqa: A002
+        on_invalid_key: Literal["error", "skip", "lossy"] = "error",
+        include_metadata: bool = False,
+        timeout: timedelta | None = None,
+        chunk_timeout: timedelta | None = None,
         return_arrow: Literal[True],
     ) -> ListStream[RecordBatch]: ...
     @overload
     def list(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
-        offset: str | None = None,
+        offset: str | PathLike[str] | None = None,
         chunk_size: int = 50,
+        stable_chunks: bool = False,
+        order_by: Literal["path", "last_modified", "size"] | None = None,
+        descending: bool = False,
+        sort_memory_limit: int = 268_435_456,
+        glob: str | None = None,
+        filter: ListFilter | None = None,  # noqa: A002
+        on_invalid_key: Literal["error", "skip", "lossy"] = "error",
+        include_metadata: bool = False,
+        timeout: timedelta | None = None,
+        chunk_timeout: timedelta | None = None,
         return_arrow: Literal[False] = False,
     ) -> ListStream[list[ObjectMeta]]: ...
     def list(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
-        offset: str | None = None,
+        offset: str | PathLike[str] | None = None,
         chunk_size: int = 50,
+        stable_chunks: bool = False,
+        order_by: Literal["path", "last_modified", "size"] | None = None,
+        descending: bool = False,
+        sort_memory_limit: int = 268_435_456,
+        glob: str | None = None,
+        filter: ListFilter | None = None,  # noqa: A002
+        on_invalid_key: Literal["error", "skip", "lossy"] = "error",
+        include_metadata: bool = False,
+        timeout: timedelta | None = None,
+        chunk_timeout: timedelta | None = None,
         return_arrow: bool = False,
     ) -> ListStream[RecordBatch] | ListStream[list[ObjectMeta]]:
         """List all the objects with the given prefix.
@@ -314,6 +607,16 @@ class _ObjectStoreMixin:
                 prefix,
                 offset=offset,
                 chunk_size=chunk_size,
+                stable_chunks=stable_chunks,
+                order_by=order_by,
+                descending=descending,
+                sort_memory_limit=sort_memory_limit,
+                glob=glob,
+                filter=filter,
+                on_invalid_key=on_invalid_key,
+                include_metadata=include_metadata,
+                timeout=timeout,
+                chunk_timeout=chunk_timeout,
                 return_arrow=return_arrow,
             )
 
@@ -322,26 +625,36 @@ class _ObjectStoreMixin:
             prefix,
             offset=offset,
             chunk_size=chunk_size,
+            stable_chunks=stable_chunks,
+            order_by=order_by,
+            descending=descending,
+            sort_memory_limit=sort_memory_limit,
+            glob=glob,
+            filter=filter,
+            on_invalid_key=on_invalid_key,
+            include_metadata=include_metadata,
+            timeout=timeout,
+            chunk_timeout=chunk_timeout,
             return_arrow=return_arrow,
         )
 
     @overload
     def list_with_delimiter(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
         return_arrow: Literal[True],
     ) -> ListResult[Table]: ...
     @overload
     def list_with_delimiter(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
         return_arrow: Literal[False] = False,
     ) -> ListResult[list[ObjectMeta]]: ...
     def list_with_delimiter(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
         return_arrow: bool = False,
     ) -> ListResult[Table] | ListResult[list[ObjectMeta]]:
@@ -369,20 +682,20 @@ class _ObjectStoreMixin:
     @overload
     async def list_with_delimiter_async(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
         return_arrow: Literal[True],
     ) -> ListResult[Table]: ...
     @overload
     async def list_with_delimiter_async(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
         return_arrow: Literal[False] = False,
     ) -> ListResult[list[ObjectMeta]]: ...
     async def list_with_delimiter_async(
         self,
-        prefix: str | None = None,
+        prefix: str | PathLike[str] | None = None,
         *,
         return_arrow: bool = False,
     ) -> ListResult[Table] | ListResult[list[ObjectMeta]]:
@@ -408,7 +721,7 @@ class _ObjectStoreMixin:
 
     def put(  # noqa: PLR0913
         self,
-        path: str,
+        path: str | PathLike[str],
         file: IO[bytes] | Path | bytes | Buffer | Iterator[Buffer] | Iterable[Buffer],
         *,
         attributes: Attributes | None = None,
@@ -417,6 +730,12 @@ class _ObjectStoreMixin:
         use_multipart: bool | None = None,
         chunk_size: int = 5 * 1024 * 1024,
         max_concurrency: int = 12,
+        multipart_policy: MultipartPolicy | Literal["auto"] | None = None,
+        manifest: str | PathLike[str] | None = None,
+        progress: ProgressCallback | None = None,
+        progress_interval: timedelta = timedelta(milliseconds=100),
+        timeout: timedelta | None = None,
+        compress: Compression | None = None,
     ) -> PutResult:
         """Save the provided bytes to the specified location.
 
@@ -432,11 +751,17 @@ class _ObjectStoreMixin:
             use_multipart=use_multipart,
             chunk_size=chunk_size,
             max_concurrency=max_concurrency,
+            multipart_policy=multipart_policy,
+            manifest=manifest,
+            progress=progress,
+            progress_interval=progress_interval,
+            timeout=timeout,
+            compress=compress,
         )
 
     async def put_async(  # noqa: PLR0913
         self,
-        path: str,
+        path: str | PathLike[str],
         file: IO[bytes]
         | Path
         | bytes
@@ -452,6 +777,12 @@ class _ObjectStoreMixin:
         use_multipart: bool | None = None,
         chunk_size: int = 5 * 1024 * 1024,
         max_concurrency: int = 12,
+        multipart_policy: MultipartPolicy | Literal["auto"] | None = None,
+        manifest: str | PathLike[str] | None = None,
+        progress: ProgressCallback | None = None,
+        progress_interval: timedelta = timedelta(milliseconds=100),
+        timeout: timedelta | None = None,
+        compress: Compression | None = None,
     ) -> PutResult:
         """Call `put` asynchronously.
 
@@ -482,9 +813,23 @@ class _ObjectStoreMixin:
             use_multipart=use_multipart,
             chunk_size=chunk_size,
             max_concurrency=max_concurrency,
+            multipart_policy=multipart_policy,
+            manifest=manifest,
+            progress=progress,
+            progress_interval=progress_interval,
+            timeout=timeout,
+            compress=compress,
         )
 
-    def rename(self, from_: str, to: str, *, overwrite: bool = True) -> None:
+    def rename(
+        self,
+        from_: str | PathLike[str],
+        to: str | PathLike[str],
+        *,
+        overwrite: bool = True,
+        multipart_threshold: int | None = None,
+        timeout: timedelta | None = None,
+    ) -> None:
         """Move an object from one path to another in the same object store.
 
         Refer to the documentation for [rename][obstore.rename].
@@ -494,14 +839,18 @@ class _ObjectStoreMixin:
             from_,
             to,
             overwrite=overwrite,
+            multipart_threshold=multipart_threshold,
+            timeout=timeout,
         )
 
     async def rename_async(
         self,
-        from_: str,
-        to: str,
+        from_: str | PathLike[str],
+        to: str | PathLike[str],
         *,
         overwrite: bool = True,
+        multipart_threshold: int | None = None,
+        timeout: timedelta | None = None,
     ) -> None:
         """Call `rename` asynchronously.
 
@@ -512,18 +861,144 @@ class _ObjectStoreMixin:
             from_,
             to,
             overwrite=overwrite,
+            multipart_threshold=multipart_threshold,
+            timeout=timeout,
         )
 
 
-class AzureStore(_ObjectStoreMixin, _store.AzureStore):
+_DURATION_KEYS = frozenset(
+    {"retry_timeout", "init_backoff", "max_backoff", "fail_fast_cooldown"},
+)
+"""Keys of `retry_config` and its backoff configs holding a `timedelta`."""
+
+
+def _to_json(value: Any) -> Any:
+    if isinstance(value, timedelta):
+        return value.total_seconds()
+    if isinstance(value, os.PathLike):
+        return os.fspath(value)
+    if isinstance(value, dict):
+        return {key: _to_json(val) for key, val in value.items()}
+    if isinstance(value, (list, tuple)):
+        return [_to_json(val) for val in value]
+    return value
+
+
+def _retry_config_from_json(value: Any) -> Any:
+    if not isinstance(value, dict):
+        return value
+    return {
+        key: timedelta(seconds=val)
+        if key in _DURATION_KEYS and isinstance(val, (int, float))
+        else _retry_config_from_json(val)
+        for key, val in value.items()
+    }
+
+
+class _ConfigDictMixin:
+    _config_dict_type: ClassVar[str]
+    """The value of `"type"` in the config dict of this store."""
+
+    _config_dict_args: ClassVar[tuple[str, ...]] = ()
+    """The names of the positional arguments returned by `__getnewargs_ex__`."""
+
+    _secret_keys: ClassVar[frozenset[str]] = frozenset()
+    """The keys of `config` left out with `mask_secrets=True`."""
+
+    def to_config_dict(self, *, mask_secrets: bool = False) -> dict[str, Any]:
+        """Export the configuration of this store as a JSON-serializable dict.
+
+        Unlike pickling, the dict only holds plain values, so that it can be written to
+        a config file or passed to another process or language. Durations are given in
+        seconds. A store is created back from it with `from_config_dict`:
+
+        ```py
+        import json
+
+        from obstore.store import S3Store
+
+        store = S3Store("bucket", region="us-east-1")
+        config_dict = store.to_config_dict(mask_secrets=True)
+        print(json.dumps(config_dict))
+        new_store = S3Store.from_config_dict(config_dict)
+        ```
+
+        Args:
+            mask_secrets: If `True`, leave secrets such as access keys out of `config`,
+                so that the dict can be logged or shared. A store created from such a
+                dict reads its credentials from the environment instead. Defaults to
+                `False`.
+
+        Raises:
+            ValueError: If the store has a `credential_provider`, a callable
+                `resolver` or an `on_retry` callback, which can't be represented as
+                plain values.
+
+        Returns:
+            The configuration of this store, with its kind under `"type"`.
+
+        """
+        args, kwargs = self.__getnewargs_ex__()  # type: ignore (Cannot access attribute "__getnewargs_ex__")
+        config_dict: dict[str, Any] = {"type": self._config_dict_type}
+        config_dict.update(zip(self._config_dict_args, args))
+        for key, value in kwargs.items():
+            if key == "credential_provider" or (key == "resolver" and callable(value)):
+                msg = f"A store with a {key} can't be exported to a config dict."
+                raise ValueError(msg)
+            if key == "retry_config" and value.get("on_retry") is not None:
+                msg = "A store with an on_retry can't be exported to a config dict."
+                raise ValueError(msg)
+            config_dict[key] = value
+        if mask_secrets and "config" in config_dict:
+            config_dict["config"] = {
+                key: value
+                for key, value in config_dict["config"].items()
+                if key not in self._secret_keys
+            }
+        return _to_json(config_dict)
+
+    @classmethod
+    def from_config_dict(cls, config_dict: dict[str, Any]) -> Self:
+        """Create a store from a dict returned by `to_config_dict`.
+
+        Args:
+            config_dict: The configuration of the store.
+
+        Raises:
+            ValueError: If the dict describes a different kind of store.
+
+        Returns:
+            The new store.
+
+        """
+        kwargs = dict(config_dict)
+        store_type = kwargs.pop("type", None)
+        if store_type != cls._config_dict_type:
+            msg = (
+                f"Expected a config dict of type '{cls._config_dict_type}', "
+                f"got {store_type!r}."
+            )
+            raise ValueError(msg)
+        if "retry_config" in kwargs:
+            kwargs["retry_config"] = _retry_config_from_json(kwargs["retry_config"])
+        args = [kwargs.pop(name, None) for name in cls._config_dict_args]
+        return cls(*args, **kwargs)
+
+
+class AzureStore(_ConfigDictMixin, _ObjectStoreMixin, _store.AzureStore):
     """Interface to a Microsoft Azure Blob Storage container.
 
     All constructors will check for environment variables. Refer to
     [`AzureConfig`][obstore.store.AzureConfig] for valid environment variables.
     """
 
+    _config_dict_type = "azure"
+    _secret_keys = frozenset(
+        {"account_key", "client_secret", "sas_key", "token", "fabric_session_token"},
+    )
+
 
-class GCSStore(_ObjectStoreMixin, _store.GCSStore):
+class GCSStore(_ConfigDictMixin, _ObjectStoreMixin, _store.GCSStore):
     """Interface to Google Cloud Storage.
 
     All constructors will check for environment variables. Refer to
@@ -534,8 +1009,11 @@ class GCSStore(_ObjectStoreMixin, _store.GCSStore):
     [here](https://cloud.google.com/docs/authentication/application-default-credentials).
     """
 
+    _config_dict_type = "gcs"
+    _secret_keys = frozenset({"service_account_key"})
 
-class HTTPStore(_ObjectStoreMixin, _store.HTTPStore):
+
+class HTTPStore(_ConfigDictMixin, _ObjectStoreMixin, _store.HTTPStore):
     """Configure a connection to a generic HTTP server.
 
     **Example**
@@ -555,8 +1033,42 @@ class HTTPStore(_ObjectStoreMixin, _store.HTTPStore):
     ```
     """
 
+    _config_dict_type = "http"
+    _config_dict_args = ("url",)
+
+
+class HdfsStore(_ObjectStoreMixin, _store.HdfsStore):
+    """Configure a connection to HDFS, through the WebHDFS REST API.
+
+    The store talks to the WebHDFS API of a namenode, or to an HttpFS gateway, and
+    paths are relative to the directory of the URL. Writes are redirected by the
+    namenode to the datanodes, which must be reachable from the client. Files whose
+    names end with `#` and digits are used to stage multipart uploads, and are left
+    out of listings.
+
+    ```py
+    from obstore.store import HdfsStore
+
+    store = HdfsStore("webhdfs://namenode.example.com/data", user="etl")
+    ```
+
+    **Kerberos**
+
+    The store doesn't authenticate with Kerberos itself. Instead, clusters secured
+    with Kerberos are accessed with a delegation token, which can be fetched with
+    `hdfs fetchdt`, or from the `GETDELEGATIONTOKEN` operation of WebHDFS with
+    `curl --negotiate -u :`.
+
+    ```py
+    store = HdfsStore(
+        "swebhdfs://namenode.example.com/data",
+        delegation_token="HAAEaGRmcwRoZGZzAIoBj...",
+    )
+    ```
+    """
+
 
-class LocalStore(_ObjectStoreMixin, _store.LocalStore):
+class LocalStore(_ConfigDictMixin, _ObjectStoreMixin, _store.LocalStore):
     """An ObjectStore interface to local filesystem storage.
 
     Can optionally be created with a directory prefix.
@@ -570,6 +1082,9 @@ class LocalStore(_ObjectStoreMixin, _store.LocalStore):
     ```
     """
 
+    _config_dict_type = "local"
+    _config_dict_args = ("prefix",)
+
 
 class MemoryStore(_ObjectStoreMixin, _store.MemoryStore):
     """A fully in-memory implementation of ObjectStore.
@@ -578,10 +1093,285 @@ class MemoryStore(_ObjectStoreMixin, _store.MemoryStore):
     ```py
     store = MemoryStore()
     ```
+
+    Pickling a memory store pickles a snapshot of its contents, as returned by
+    [`to_dict`][obstore.store.MemoryStore.to_dict].
+    """
+
+
+class DictStore(_ObjectStoreMixin, _store.DictStore):
+    """A store whose objects are the values of a Python mapping, keyed by their paths.
+
+    Reads, writes and deletes call into the mapping, such as a `dict`, under the GIL.
+    This is much slower than [`MemoryStore`][obstore.store.MemoryStore], but the
+    contents of the store are plain Python objects, which is useful to check what
+    code handed a store has written, or to expose storage already accessible from
+    Python.
+
+    Values are `bytes`, or any object supporting the buffer protocol, and are written
+    as `bytes`. Keys that aren't valid paths fail listing. As the mapping only holds
+    the contents of the objects, the last modified time of an object is when this
+    store last wrote it, or when the store was created, and objects have no e-tag.
+
+    ```py
+    import obstore as obs
+    from obstore.store import DictStore
+
+    contents = {"data/file.txt": b"hello"}
+    store = DictStore(contents)
+    obs.put(store, "data/other.txt", b"world")
+    assert contents["data/other.txt"] == b"world"
+    ```
+    """
+
+
+class CacheStore(_ObjectStoreMixin, _store.CacheStore):
+    """A store wrapper that caches whole objects on local disk.
+
+    Repeated reads of the same remote objects are served from `cache_dir` instead of
+    being downloaded again. Once the cached objects exceed `max_size` bytes, the least
+    recently used are evicted.
+
+    Every read of a cached object is still validated against the wrapped store with a
+    conditional request on its e-tag, so a changed object is never served stale, while
+    an unchanged object only costs a request without a body. Objects are only cached
+    when they're read in full. Ranged reads, such as [`get_range`][obstore.get_range],
+    are served from a current cached object, and are otherwise passed through to the
+    wrapped store, so a small range of a large object is never downloaded in full. Use
+    [`RangeCacheStore`][obstore.store.RangeCacheStore] to cache the ranges themselves.
+    Objects larger than `max_size`, or for which the store doesn't return an e-tag, are
+    never cached.
+
+    Writes through this store, including [`put`][obstore.put],
+    [`delete`][obstore.delete], and [`rename`][obstore.rename], remove the cached
+    copies of the affected paths. The cache directory persists, so a later
+    `CacheStore` using the same directory reuses the objects cached by earlier ones.
+
+    ```py
+    from obstore.store import CacheStore, S3Store
+
+    store = CacheStore(S3Store("bucket"), "/tmp/obstore-cache", 10 * 1024**3)
+    ```
+    """
+
+
+class ChaosStore(_ObjectStoreMixin, _store.ChaosStore):
+    """A store wrapper that injects latency and random failures, for testing.
+
+    Every call is delayed by `latency` and then fails with a
+    [`GenericError`][obstore.exceptions.GenericError] with probability `failure_rate`.
+    This can be used to check that an application handles slow or flaky storage
+    without needing a real remote backend. A listing fails before its first entry, and
+    each part of a multipart upload may fail independently.
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import ChaosStore, MemoryStore
+
+    store = ChaosStore(
+        MemoryStore(),
+        failure_rate=0.1,
+        latency=timedelta(milliseconds=20),
+        seed=42,
+    )
+    ```
+    """
+
+
+class ConsistentStore(_ObjectStoreMixin, _store.ConsistentStore):
+    """A store wrapper that provides read-after-write consistency for its own writes.
+
+    Some backends and emulators are only eventually consistent, so a read made just
+    after a write may still return the previous version of an object, or report that a
+    new object doesn't exist yet. This store remembers the e-tag and version of each
+    object written through it. A read of such an object that returns an older version,
+    or raises `FileNotFoundError`, is retried with exponential backoff until the
+    written version is observed. Likewise, a read of an object deleted through this
+    store is retried until the object is reported as not found.
+
+    If the written version still isn't observed once the retries are exhausted, for
+    example because another process has since overwritten the object, the last
+    response is returned as is. Listing is not affected.
+
+    ```py
+    from obstore.store import ConsistentStore, S3Store
+
+    store = ConsistentStore(S3Store("bucket"))
+    ```
+    """
+
+
+class DeadlineStore(_ObjectStoreMixin, _store.DeadlineStore):
+    """A store wrapper that stops every call at a deadline.
+
+    Calls made after the deadline fail immediately with a
+    [`GenericError`][obstore.exceptions.GenericError], and calls still in progress at
+    the deadline are cancelled. This includes streaming the body of a
+    [`get`][obstore.get] and the results of a [`list`][obstore.list]. A multipart
+    upload that runs past the deadline can still be aborted.
+
+    Cancelling an asyncio task, for example at the end of an `asyncio.timeout()`
+    block, stops an awaited call, but not work that is driven from a synchronous call
+    or from a stream that is no longer being consumed. Passing the `asyncio.Timeout` to
+    this store stops that work at the same deadline:
+
+    ```py
+    import asyncio
+
+    import obstore as obs
+    from obstore.store import DeadlineStore, S3Store
+
+    store = S3Store("bucket")
+
+    async with asyncio.timeout(10) as timeout:
+        deadline_store = DeadlineStore(store, timeout)
+        async for chunk in obs.list(deadline_store):
+            ...
+    ```
+
+    The deadline is fixed when the store is created: rescheduling the timeout later
+    doesn't move it.
+    """
+
+
+class EncryptedStore(_ObjectStoreMixin, _store.EncryptedStore):
+    """A store wrapper that encrypts objects on the client.
+
+    Objects are encrypted with envelope encryption: each object is encrypted with its
+    own random content key, using AES-256-GCM, and the content key is wrapped by
+    `key_provider` and stored, encoded as base64, in the `obstore-encryption-key`
+    metadata of the object. The inner store must therefore support
+    [attributes][obstore.Attributes], and return them with [`get`][obstore.get].
+
+    ```py
+    import os
+
+    import obstore as obs
+    from obstore.store import EncryptedStore, S3Store
+
+    store = EncryptedStore(S3Store("bucket"), os.urandom(32))
+    obs.put(store, "secret.txt", b"foo")
+    assert obs.get(store, "secret.txt").bytes() == b"foo"
+    ```
+
+    Instead of a master key, `key_provider` may be an object with `wrap_key` and
+    `unwrap_key` methods, for example wrapping keys with a key management service.
+
+    Objects are encrypted in blocks of 64 KiB, each authenticated on its own, so that
+    ranged reads such as [`get_range`][obstore.get_range] only fetch and decrypt the
+    blocks covering the range, rather than the whole object. The sizes returned by
+    [`head`][obstore.head] and [`list`][obstore.list] are those of the plaintext.
+    Every object read through this store must have been written through it.
+
+    Encrypted objects can be copied and renamed, as long as the inner store preserves
+    their metadata.
+    """
+
+
+class MirrorStore(_ObjectStoreMixin, _store.MirrorStore):
+    """A store wrapper that mirrors writes to several stores.
+
+    This is useful for live migrations between buckets or providers. Writes, including
+    [`put`][obstore.put], [`delete`][obstore.delete], [`copy`][obstore.copy], and
+    [`rename`][obstore.rename], are made to the first (primary) store. Once that
+    succeeds, they are made to every other (secondary) store concurrently.
+
+    Writes aren't atomic across the stores. If a write to a secondary store fails, the
+    write to the primary store isn't undone, and the writes to the other secondary
+    stores still complete. A [`GenericError`][obstore.exceptions.GenericError] is then
+    raised that lists each store that failed, by its index in `stores`, with its
+    error. Deleting an object that is missing from a secondary store is not an error.
+
+    Reads, including [`list`][obstore.list], are made to the primary store. If that
+    fails, including because the object isn't found, each secondary store is tried in
+    turn. If every store fails, the error of the primary store is raised. A listing
+    only fails over before its first result is returned.
+
+    Conditional writes are only checked against the primary store: a write
+    conditional on an e-tag or version overwrites the object in the secondary stores.
+    Copies and renames require the source object to exist in every store.
+
+    ```py
+    from obstore.store import GCSStore, MirrorStore, S3Store
+
+    store = MirrorStore([GCSStore("new-bucket"), S3Store("old-bucket")])
+    ```
     """
 
 
-class S3Store(_ObjectStoreMixin, _store.S3Store):
+class NegativeCacheStore(_ObjectStoreMixin, _store.NegativeCacheStore):
+    """A store wrapper that caches negative lookups (404s) for a short time.
+
+    Repeatedly probing for optional files, such as `_metadata` or `.zarray`, can
+    otherwise issue many requests that are known to fail. While an entry is cached,
+    [`get`][obstore.get], [`head`][obstore.head], and the other read operations raise
+    `FileNotFoundError` for that path without making a request.
+
+    Any write to a path through this store, including [`put`][obstore.put],
+    [`copy`][obstore.copy], and [`rename`][obstore.rename], invalidates the cached
+    entry for that path. Writes made by other processes or through other store
+    instances are only observed once the entry expires.
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import NegativeCacheStore, S3Store
+
+    store = NegativeCacheStore(S3Store("bucket"), ttl=timedelta(seconds=10))
+    ```
+    """
+
+
+class PrefixStore(_ObjectStoreMixin, _store.PrefixStore):
+    """A store wrapper that scopes an existing store to a prefix.
+
+    Every path passed to this store is joined onto `prefix`, and the prefix is removed
+    again from the paths it returns, such as from [`list`][obstore.list]. The wrapped
+    store is shared rather than rebuilt, so no new connection pool or credentials are
+    created. This makes it cheap to hand out sub-stores scoped to one part of a
+    bucket.
+
+    ```py
+    from obstore.store import PrefixStore, S3Store
+
+    store = S3Store("bucket")
+    users = PrefixStore(store, "data/users")
+    ```
+
+    Prefixes compose, so wrapping a `PrefixStore` in another `PrefixStore` joins the
+    two prefixes.
+    """
+
+
+class RangeCacheStore(_ObjectStoreMixin, _store.RangeCacheStore):
+    """A store wrapper that caches ranged reads in memory.
+
+    Workloads such as reading Parquet or Zarr data tend to read the same footers and
+    headers many times, with separate [`get_range`][obstore.get_range] calls. This
+    store keeps the responses to ranged reads in memory, keyed by path, e-tag, and the
+    exact range requested, so that repeated reads don't make another request. Once the
+    cached ranges exceed `max_size` bytes, the least recently used are evicted. Reads
+    of whole objects are not cached; see [`CacheStore`][obstore.store.CacheStore] for
+    a disk cache of whole objects.
+
+    The e-tag of each path is the latest one seen in any response through this store.
+    By default, a cached range is returned without making a request, so a change made
+    by another writer is only noticed once another read through this store, such as
+    [`head`][obstore.head], sees the new e-tag. Pass `validate=True` to instead
+    revalidate each cached range with a conditional request, which saves the transfer
+    but not the round trip. Writes through this store drop the cached ranges of the
+    affected paths.
+
+    ```py
+    from obstore.store import RangeCacheStore, S3Store
+
+    store = RangeCacheStore(S3Store("bucket"), 256 * 1024**2)
+    ```
+    """
+
+
+class S3Store(_ConfigDictMixin, _ObjectStoreMixin, _store.S3Store):
     """Interface to an Amazon S3 bucket.
 
     All constructors will check for environment variables. Refer to
@@ -591,13 +1381,98 @@ class S3Store(_ObjectStoreMixin, _store.S3Store):
 
     **Using requester-pays buckets**:
 
-    Pass `request_payer=True` as a keyword argument or have `AWS_REQUESTER_PAYS=True`
-    set in the environment.
+    Pass `request_payer=True` as a keyword argument or have `AWS_REQUEST_PAYER=True`
+    set in the environment. This sets the `x-amz-request-payer` header on every
+    request:
+
+    ```py
+    store = S3Store("sentinel-s2-l2a", region="eu-central-1", request_payer=True)
+    ```
 
     **Anonymous requests**:
 
     Pass `skip_signature=True` as a keyword argument or have `AWS_SKIP_SIGNATURE=True`
     set in the environment.
+
+    **S3-compatible services**:
+
+    Pass the `endpoint` of the service, and a `compatibility` preset if it rejects the
+    defaults used for AWS S3:
+
+    ```py
+    store = S3Store(
+        "bucket",
+        endpoint="http://minio.internal:9000",
+        allow_http=True,
+        compatibility="minio",
+    )
+    ```
+    """
+
+    _config_dict_type = "s3"
+    _secret_keys = frozenset(
+        {"secret_access_key", "session_token", "sse_customer_key_base64"},
+    )
+
+
+if TYPE_CHECKING or hasattr(_store, "SFTPStore"):
+    _SFTPStore = _store.SFTPStore
+else:
+
+    class _SFTPStore:
+        def __init__(self, *args: Any, **kwargs: Any) -> None:
+            msg = "obstore was built without the sftp feature, which SFTPStore needs."
+            raise NotImplementedError(msg)
+
+
+class SFTPStore(_ObjectStoreMixin, _SFTPStore):
+    """Configure a connection to a server over SSH, using the SFTP protocol.
+
+    Objects are files on the server, and the directories of their paths are created as
+    needed when writing. The store opens a single SSH session on its first operation,
+    over which operations are made one at a time: concurrent operations, such as the
+    parts of a multipart upload, wait for each other rather than running in parallel.
+    Create several stores to run operations over several sessions. Files whose names
+    end with `#` and digits are used to stage writes, and are left out of listings.
+
+    The key of the server is checked against `~/.ssh/known_hosts`, or the
+    `known_hosts` file that is given.
+
+    !!! note
+        This store isn't included in the published wheels, as it builds libssh2 and
+        OpenSSL from source. Build obstore with the `sftp` cargo feature to use it,
+        e.g. `maturin develop -m obstore/Cargo.toml --features sftp`.
+
+    ```py
+    from obstore.store import SFTPStore
+
+    store = SFTPStore(
+        "sftp.example.com",
+        "user",
+        key_file="/home/user/.ssh/id_ed25519",
+        prefix="/data",
+    )
+    ```
+    """
+
+
+class ThrottleStore(_ObjectStoreMixin, _store.ThrottleStore):
+    """A store wrapper that sleeps before or after each call, for testing.
+
+    This simulates the latency and limited bandwidth of a remote backend on top of a
+    fast store such as [`MemoryStore`][obstore.store.MemoryStore]. The delays are
+    configured by a [`ThrottleConfig`][obstore.store.ThrottleConfig].
+
+    ```py
+    from datetime import timedelta
+
+    from obstore.store import MemoryStore, ThrottleStore
+
+    store = ThrottleStore(
+        MemoryStore(),
+        {"wait_get_per_call": timedelta(milliseconds=50)},
+    )
+    ```
     """
 
 
@@ -605,9 +1480,22 @@ ObjectStore: TypeAlias = Union[
     AzureStore,
     GCSStore,
     HTTPStore,
+    HdfsStore,
     S3Store,
+    SFTPStore,
     LocalStore,
     MemoryStore,
+    DictStore,
+    CacheStore,
+    ChaosStore,
+    ConsistentStore,
+    DeadlineStore,
+    EncryptedStore,
+    MirrorStore,
+    NegativeCacheStore,
+    PrefixStore,
+    RangeCacheStore,
+    ThrottleStore,
 ]
 """All supported ObjectStore implementations."""
 
@@ -620,7 +1508,15 @@ def from_url(
     config: S3Config | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
-    credential_provider: S3CredentialProvider | None = None,
+    limit_config: LimitConfig | None = None,
+    compatibility: S3Compatibility | None = None,
+    credential_provider: S3CredentialProvider
+    | str
+    | ChainCredentialProvider
+    | list[S3CredentialProvider | str]
+    | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Unpack[S3Config],
 ) -> ObjectStore: ...
 @overload
@@ -630,7 +1526,14 @@ def from_url(
     config: GCSConfig | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
-    credential_provider: GCSCredentialProvider | None = None,
+    limit_config: LimitConfig | None = None,
+    credential_provider: GCSCredentialProvider
+    | str
+    | ChainCredentialProvider
+    | list[GCSCredentialProvider | str]
+    | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Unpack[GCSConfig],
 ) -> ObjectStore: ...
 @overload
@@ -640,7 +1543,14 @@ def from_url(
     config: AzureConfig | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
-    credential_provider: AzureCredentialProvider | None = None,
+    limit_config: LimitConfig | None = None,
+    credential_provider: AzureCredentialProvider
+    | str
+    | ChainCredentialProvider
+    | list[AzureCredentialProvider | str]
+    | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Unpack[AzureConfig],
 ) -> ObjectStore: ...
 @overload
@@ -650,8 +1560,12 @@ def from_url(  # type: ignore (parameter overlap)
     config: None = None,
     client_options: None = None,
     retry_config: None = None,
+    limit_config: None = None,
     automatic_cleanup: bool = False,
     mkdir: bool = False,
+    symlinks: Literal["follow", "skip", "error"] = "follow",
+    direct_io: bool = False,
+    preallocate: bool = False,
 ) -> ObjectStore: ...
 def from_url(  # noqa: C901
     url: str,
@@ -659,10 +1573,14 @@ def from_url(  # noqa: C901
     config: S3Config | GCSConfig | AzureConfig | None = None,
     client_options: ClientConfig | None = None,
     retry_config: RetryConfig | None = None,
+    limit_config: LimitConfig | None = None,
+    compatibility: S3Compatibility | None = None,
     credential_provider: S3CredentialProvider
     | GCSCredentialProvider
     | AzureCredentialProvider
     | None = None,
+    resolver: Resolver | None = None,
+    request_tags: dict[str, str] | None = None,
     **kwargs: Any,
 ) -> ObjectStore:
     """Easy construction of store by URL, identifying the relevant store.
@@ -689,6 +1607,10 @@ def from_url(  # noqa: C901
     - `amazonaws.com` -> [`S3Store`][obstore.store.S3Store]
     - `r2.cloudflarestorage.com` -> [`S3Store`][obstore.store.S3Store]
 
+    With a `compatibility` preset, any other `http(s)` URL is the path-style URL of an
+    S3-compatible service, such as `http://minio.internal:9000/bucket/prefix`, and
+    creates an [`S3Store`][obstore.store.S3Store].
+
     !!! note
         For best static typing, use the constructors on individual store classes
         directly.
@@ -701,28 +1623,49 @@ def from_url(  # noqa: C901
             inferred from the url. Defaults to None.
         client_options: HTTP Client options. Defaults to None.
         retry_config: Retry configuration. Defaults to None.
+        limit_config: Bandwidth and concurrency limits shared by all operations
+            through this store. Defaults to None.
+        compatibility: A preset of configuration for an S3-compatible service, only
+            for S3 URLs. See [`S3Compatibility`][obstore.store.S3Compatibility].
+            Defaults to None.
         credential_provider: A callback to provide custom credentials to the underlying
             store classes.
+        resolver: A resolver of the hostnames the store connects to. See
+            [`Resolver`][obstore.store.Resolver]. Defaults to None.
+        request_tags: Static key/value tags attached to every request, for attributing
+            costs to a workload. How they are sent depends on the store. Defaults to
+            None.
         kwargs: per-store configuration passed down to store-specific builders.
 
     """
     scheme = _parse_scheme(url)
-    if scheme == "s3":
+    # With a compatibility preset, an HTTP URL is the URL of an S3-compatible service
+    if scheme == "s3" or (scheme == "http" and compatibility is not None):
         return S3Store.from_url(
             url,
             config=config,  # type: ignore (config narrowing)
             client_options=client_options,
             retry_config=retry_config,
+            limit_config=limit_config,
+            compatibility=compatibility,
             credential_provider=credential_provider,  # type: ignore (config narrowing)
+            resolver=resolver,
+            request_tags=request_tags,
             **kwargs,
         )
+    if compatibility is not None:
+        msg = f"compatibility can only be passed for S3 URLs, got {url}"
+        raise BaseError(msg)
     if scheme == "gcs":
         return GCSStore.from_url(
             url,
             config=config,  # type: ignore (config narrowing)
             client_options=client_options,
             retry_config=retry_config,
+            limit_config=limit_config,
             credential_provider=credential_provider,  # type: ignore (config narrowing)
+            resolver=resolver,
+            request_tags=request_tags,
             **kwargs,
         )
     if scheme == "azure":
@@ -731,7 +1674,10 @@ def from_url(  # noqa: C901
             config=config,  # type: ignore (config narrowing)
             client_options=client_options,
             retry_config=retry_config,
+            limit_config=limit_config,
             credential_provider=credential_provider,  # type: ignore (config narrowing)
+            resolver=resolver,
+            request_tags=request_tags,
             **kwargs,
         )
     if scheme == "http":
@@ -743,6 +1689,9 @@ def from_url(  # noqa: C901
             url,
             client_options=client_options,
             retry_config=retry_config,
+            limit_config=limit_config,
+            resolver=resolver,
+            request_tags=request_tags,
         )
     if scheme == "local":
         automatic_cleanup = False
@@ -751,11 +1700,17 @@ def from_url(  # noqa: C901
             automatic_cleanup = kwargs.pop("automatic_cleanup")
         if "mkdir" in kwargs:
             mkdir = kwargs.pop("mkdir")
+        symlinks = kwargs.pop("symlinks", "follow")
+        direct_io = kwargs.pop("direct_io", False)
+        preallocate = kwargs.pop("preallocate", False)
 
         return LocalStore.from_url(
             url,
             automatic_cleanup=automatic_cleanup,
             mkdir=mkdir,
+            symlinks=symlinks,
+            direct_io=direct_io,
+            preallocate=preallocate,
         )
     if scheme == "memory":
         if config or kwargs:
@@ -0,0 +1,579 @@
+//! Streaming creation and extraction of tar archives.
+//!
+//! Archives are written with the ustar format, using GNU long name entries for paths longer
+//! than 100 bytes. Objects are streamed one at a time into a multipart upload, so memory use is
+//! bounded by `chunk_size * max_concurrency` regardless of the size of the archive. Archives can be
+//! compressed as they're written, to produce a `.tar.gz` or `.tar.zst` file, and decompressed as
+//! they're extracted.
+//!
+//! Zip archives aren't supported: their entries are listed in a central directory at the end of
+//! the file, so unlike a tar archive, a zip archive can't be extracted as a stream.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::TryStreamExt;
+use object_store::buffered::{BufReader, BufWriter};
+use object_store::path::{Path, PathPart};
+use object_store::ObjectStore;
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+use crate::compression::{decompress_reader, Codec, Compressor, PyDecompression};
+use crate::runtime::get_runtime;
+
+const BLOCK_SIZE: usize = 512;
+const LONG_NAME: &[u8] = b"././@LongLink";
+
+/// The read size used when copying an archive entry to its destination
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The largest GNU long name or PAX extended header that is read into memory
+const MAX_HEADER_DATA_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PyArchiveFormat {
+    Tar,
+}
+
+impl<'py> FromPyObject<'py> for PyArchiveFormat {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "tar" => Ok(Self::Tar),
+            "zip" => Err(PyValueError::new_err(
+                "Unsupported archive format: 'zip'. Zip archives are not supported, use 'tar' with `compress` instead.",
+            )),
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported archive format: '{}'. Only 'tar' is currently supported.",
+                s
+            ))),
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix, dest_store, dest_path, *, format=PyArchiveFormat::Tar, chunk_size=5242880, max_concurrency=12, compress=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn archive(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    dest_store: PyObjectStore,
+    dest_path: PyPath,
+    format: PyArchiveFormat,
+    chunk_size: usize,
+    max_concurrency: usize,
+    compress: Option<Codec>,
+) -> PyObjectStoreResult<usize> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(archive_inner(
+            store.into_inner(),
+            prefix.map(|s| s.into()),
+            dest_store.into_inner(),
+            dest_path.into(),
+            format,
+            chunk_size,
+            max_concurrency,
+            compress,
+        ))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix, dest_store, dest_path, *, format=PyArchiveFormat::Tar, chunk_size=5242880, max_concurrency=12, compress=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn archive_async(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    dest_store: PyObjectStore,
+    dest_path: PyPath,
+    format: PyArchiveFormat,
+    chunk_size: usize,
+    max_concurrency: usize,
+    compress: Option<Codec>,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = archive_inner(
+            store.into_inner(),
+            prefix.map(|s| s.into()),
+            dest_store.into_inner(),
+            dest_path.into(),
+            format,
+            chunk_size,
+            max_concurrency,
+            compress,
+        )
+        .await?;
+        Ok(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, archive_path, dest_store, dest_prefix=None, *, format=PyArchiveFormat::Tar, chunk_size=5242880, max_concurrency=12, decompress=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extract(
+    py: Python,
+    store: PyObjectStore,
+    archive_path: PyPath,
+    dest_store: PyObjectStore,
+    dest_prefix: Option<PyPath>,
+    format: PyArchiveFormat,
+    chunk_size: usize,
+    max_concurrency: usize,
+    decompress: Option<PyDecompression>,
+) -> PyObjectStoreResult<usize> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(extract_inner(
+            store.into_inner(),
+            archive_path.into(),
+            dest_store.into_inner(),
+            dest_prefix.map(|s| s.into()).unwrap_or_default(),
+            format,
+            chunk_size,
+            max_concurrency,
+            decompress,
+        ))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, archive_path, dest_store, dest_prefix=None, *, format=PyArchiveFormat::Tar, chunk_size=5242880, max_concurrency=12, decompress=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extract_async(
+    py: Python,
+    store: PyObjectStore,
+    archive_path: PyPath,
+    dest_store: PyObjectStore,
+    dest_prefix: Option<PyPath>,
+    format: PyArchiveFormat,
+    chunk_size: usize,
+    max_concurrency: usize,
+    decompress: Option<PyDecompression>,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = extract_inner(
+            store.into_inner(),
+            archive_path.into(),
+            dest_store.into_inner(),
+            dest_prefix.map(|s| s.into()).unwrap_or_default(),
+            format,
+            chunk_size,
+            max_concurrency,
+            decompress,
+        )
+        .await?;
+        Ok(out)
+    })
+}
+
+/// Write every object under `prefix` into a tar archive at `dest_path`, returning the number of
+/// objects archived.
+#[allow(clippy::too_many_arguments)]
+async fn archive_inner(
+    store: Arc<dyn ObjectStore>,
+    prefix: Option<Path>,
+    dest_store: Arc<dyn ObjectStore>,
+    dest_path: Path,
+    _format: PyArchiveFormat,
+    chunk_size: usize,
+    max_concurrency: usize,
+    compress: Option<Codec>,
+) -> PyObjectStoreResult<usize> {
+    let mut writer = ArchiveWriter {
+        writer: BufWriter::with_capacity(dest_store, dest_path, chunk_size)
+            .with_max_concurrency(max_concurrency),
+        compressor: compress.map(Compressor::new).transpose()?,
+    };
+    match write_tar(&store, prefix.as_ref(), &mut writer).await {
+        Ok(count) => {
+            writer.shutdown().await?;
+            Ok(count)
+        }
+        Err(err) => {
+            writer.abort().await?;
+            Err(err)
+        }
+    }
+}
+
+/// The destination of an archive, which compresses it on the way if requested.
+struct ArchiveWriter {
+    writer: BufWriter,
+    compressor: Option<Compressor>,
+}
+
+impl ArchiveWriter {
+    async fn put(&mut self, bytes: Bytes) -> PyObjectStoreResult<()> {
+        let bytes = match &mut self.compressor {
+            Some(compressor) => compressor.compress(&bytes)?,
+            None => bytes,
+        };
+        if !bytes.is_empty() {
+            self.writer.put(bytes).await?;
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> PyObjectStoreResult<()> {
+        if let Some(compressor) = self.compressor.take() {
+            self.writer.put(compressor.finish()?).await?;
+        }
+        self.writer.shutdown().await?;
+        Ok(())
+    }
+
+    async fn abort(&mut self) -> PyObjectStoreResult<()> {
+        self.writer.abort().await?;
+        Ok(())
+    }
+}
+
+async fn write_tar(
+    store: &Arc<dyn ObjectStore>,
+    prefix: Option<&Path>,
+    writer: &mut ArchiveWriter,
+) -> PyObjectStoreResult<usize> {
+    let mut count = 0;
+    let mut objects = store.list(prefix);
+    while let Some(meta) = objects.try_next().await? {
+        let name = match prefix.and_then(|prefix| meta.location.prefix_match(prefix)) {
+            Some(parts) => parts
+                .map(|part| part.as_ref().to_string())
+                .collect::<Vec<_>>(),
+            None => meta
+                .location
+                .parts()
+                .map(|part| part.as_ref().to_string())
+                .collect(),
+        }
+        .join("/");
+
+        let mtime = meta.last_modified.timestamp().max(0) as u64;
+        for block in entry_headers(name.as_bytes(), meta.size, mtime) {
+            writer.put(block).await?;
+        }
+
+        let mut stream = store.get(&meta.location).await?.into_stream();
+        let mut written = 0;
+        while let Some(chunk) = stream.try_next().await? {
+            written += chunk.len() as u64;
+            writer.put(chunk).await?;
+        }
+        if written != meta.size {
+            return Err(PyIOError::new_err(format!(
+                "Object '{}' changed while being archived: expected {} bytes, read {}",
+                meta.location, meta.size, written
+            ))
+            .into());
+        }
+
+        let padding = padding(meta.size);
+        if padding > 0 {
+            writer.put(Bytes::from(vec![0; padding])).await?;
+        }
+        count += 1;
+    }
+
+    // An archive ends with two zero-filled blocks
+    writer.put(Bytes::from(vec![0; BLOCK_SIZE * 2])).await?;
+    Ok(count)
+}
+
+/// Build the header blocks for a regular file entry.
+///
+/// Names that do not fit in the 100 byte name field are preceded by a GNU long name entry.
+fn entry_headers(name: &[u8], size: u64, mtime: u64) -> Vec<Bytes> {
+    let mut blocks = vec![];
+    if name.len() > 100 {
+        let mut long_name = name.to_vec();
+        long_name.push(0);
+        let long_name_size = long_name.len() as u64;
+        long_name.resize(long_name.len() + padding(long_name_size), 0);
+        blocks.push(Bytes::copy_from_slice(&header(
+            LONG_NAME,
+            long_name_size,
+            0,
+            b'L',
+        )));
+        blocks.push(Bytes::from(long_name));
+    }
+    blocks.push(Bytes::copy_from_slice(&header(
+        &name[..name.len().min(100)],
+        size,
+        mtime,
+        b'0',
+    )));
+    blocks
+}
+
+fn header(name: &[u8], size: u64, mtime: u64, typeflag: u8) -> [u8; BLOCK_SIZE] {
+    let mut block = [0; BLOCK_SIZE];
+    block[..name.len()].copy_from_slice(name);
+    write_number(&mut block[100..108], 0o644);
+    write_number(&mut block[108..116], 0);
+    write_number(&mut block[116..124], 0);
+    write_number(&mut block[124..136], size);
+    write_number(&mut block[136..148], mtime);
+    block[156] = typeflag;
+    block[257..263].copy_from_slice(b"ustar\0");
+    block[263..265].copy_from_slice(b"00");
+
+    // The checksum is computed with the checksum field itself filled with spaces
+    block[148..156].fill(b' ');
+    let checksum = block.iter().map(|b| *b as u64).sum::<u64>();
+    block[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+    block
+}
+
+/// Write a numeric header field as NUL-terminated octal, falling back to the base-256 encoding
+/// for values that do not fit.
+fn write_number(field: &mut [u8], value: u64) {
+    let digits = field.len() - 1;
+    let octal = format!("{:0width$o}", value, width = digits);
+    if octal.len() <= digits {
+        field[..digits].copy_from_slice(octal.as_bytes());
+        field[digits] = 0;
+    } else {
+        field.fill(0);
+        let len = field.len();
+        field[len - 8..].copy_from_slice(&value.to_be_bytes());
+        field[0] |= 0x80;
+    }
+}
+
+fn read_number(field: &[u8]) -> PyObjectStoreResult<u64> {
+    if field[0] & 0x80 != 0 {
+        let mut value = (field[0] & 0x7f) as u64;
+        for b in &field[1..] {
+            value = (value << 8) | *b as u64;
+        }
+        return Ok(value);
+    }
+
+    let s = std::str::from_utf8(field)
+        .map_err(|_| PyIOError::new_err("Invalid numeric field in tar header"))?
+        .trim_matches(|c: char| c == '\0' || c == ' ');
+    if s.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(s, 8)
+        .map_err(|_| PyIOError::new_err("Invalid numeric field in tar header").into())
+}
+
+/// The number of zero bytes needed to pad `size` to a multiple of the block size.
+fn padding(size: u64) -> usize {
+    let remainder = (size % BLOCK_SIZE as u64) as usize;
+    if remainder == 0 {
+        0
+    } else {
+        BLOCK_SIZE - remainder
+    }
+}
+
+/// Read a NUL-terminated string from a header field.
+fn read_str(field: &[u8]) -> &[u8] {
+    let end = field.iter().position(|b| *b == 0).unwrap_or(field.len());
+    &field[..end]
+}
+
+/// Join a name from the archive onto the destination prefix.
+///
+/// Empty and `.` segments are dropped. Other segments are percent-encoded as needed by
+/// [`PathPart`], so a name cannot escape the destination prefix.
+fn dest_path(dest_prefix: &Path, name: &str) -> Path {
+    dest_prefix
+        .parts()
+        .chain(
+            name.split('/')
+                .filter(|part| !part.is_empty() && *part != ".")
+                .map(PathPart::from),
+        )
+        .collect()
+}
+
+/// A reader of the tar archive at `archive_path`, decompressing it if requested by `decompress`.
+async fn open_archive(
+    store: Arc<dyn ObjectStore>,
+    archive_path: &Path,
+    chunk_size: usize,
+    decompress: Option<PyDecompression>,
+) -> PyObjectStoreResult<Box<dyn AsyncRead + Send + Unpin>> {
+    let Some(decompress) = decompress else {
+        let meta = store.head(archive_path).await?;
+        return Ok(Box::new(BufReader::with_capacity(store, &meta, chunk_size)));
+    };
+    let result = store.get(archive_path).await?;
+    match decompress.resolve(&result.attributes, archive_path) {
+        Some(codec) => Ok(Box::new(tokio::io::BufReader::with_capacity(
+            chunk_size,
+            decompress_reader(codec, result.into_stream()),
+        ))),
+        None => {
+            let meta = result.meta.clone();
+            // Dropping the body of the response closes its connection
+            drop(result);
+            Ok(Box::new(BufReader::with_capacity(store, &meta, chunk_size)))
+        }
+    }
+}
+
+/// Extract every regular file in the tar archive at `archive_path` under `dest_prefix`,
+/// returning the number of objects written.
+#[allow(clippy::too_many_arguments)]
+async fn extract_inner(
+    store: Arc<dyn ObjectStore>,
+    archive_path: Path,
+    dest_store: Arc<dyn ObjectStore>,
+    dest_prefix: Path,
+    _format: PyArchiveFormat,
+    chunk_size: usize,
+    max_concurrency: usize,
+    decompress: Option<PyDecompression>,
+) -> PyObjectStoreResult<usize> {
+    let mut reader = open_archive(store, &archive_path, chunk_size, decompress).await?;
+
+    let mut count = 0;
+    let mut long_name: Option<String> = None;
+    let mut block = [0; BLOCK_SIZE];
+    loop {
+        reader.read_exact(&mut block).await?;
+        if block.iter().all(|b| *b == 0) {
+            break;
+        }
+
+        let checksum = read_number(&block[148..156])?;
+        let mut computed = block.iter().map(|b| *b as u64).sum::<u64>();
+        computed -= block[148..156].iter().map(|b| *b as u64).sum::<u64>();
+        computed += b' ' as u64 * 8;
+        if checksum != computed {
+            return Err(PyIOError::new_err("Invalid tar header checksum").into());
+        }
+
+        let size = read_number(&block[124..136])?;
+        let typeflag = block[156];
+        match typeflag {
+            // GNU long name: the data is the name of the following entry
+            b'L' => {
+                let data = read_header_data(&mut reader, size).await?;
+                long_name = Some(String::from_utf8_lossy(read_str(&data)).into_owned());
+            }
+            // PAX extended header: only the path record is used
+            b'x' => {
+                let data = read_header_data(&mut reader, size).await?;
+                if let Some(path) = parse_pax_path(&data[..size as usize]) {
+                    long_name = Some(path);
+                }
+            }
+            // Regular file
+            b'0' | b'\0' | b'7' => {
+                let name = match long_name.take() {
+                    Some(name) => name,
+                    None => {
+                        let name = String::from_utf8_lossy(read_str(&block[..100]));
+                        let prefix = read_str(&block[345..500]);
+                        if &block[257..262] == b"ustar" && !prefix.is_empty() {
+                            format!("{}/{}", String::from_utf8_lossy(prefix), name)
+                        } else {
+                            name.into_owned()
+                        }
+                    }
+                };
+
+                let path = dest_path(&dest_prefix, &name);
+                let mut writer = BufWriter::with_capacity(dest_store.clone(), path, chunk_size)
+                    .with_max_concurrency(max_concurrency);
+                if let Err(err) = copy_entry(&mut reader, &mut writer, size).await {
+                    writer.abort().await?;
+                    return Err(err);
+                }
+                writer.shutdown().await?;
+
+                let mut pad = vec![0; padding(size)];
+                reader.read_exact(&mut pad).await?;
+                count += 1;
+            }
+            // Directories, links, and other entries are skipped
+            _ => {
+                long_name = None;
+                let mut remaining = size.checked_add(padding(size) as u64).ok_or_else(|| {
+                    PyIOError::new_err(format!("Invalid tar entry size of {} bytes", size))
+                })?;
+                let mut buf = vec![0; COPY_BUFFER_SIZE.min(remaining as usize)];
+                while remaining > 0 {
+                    let n = remaining.min(buf.len() as u64) as usize;
+                    reader.read_exact(&mut buf[..n]).await?;
+                    remaining -= n as u64;
+                }
+            }
+        }
+    }
+
+    Ok(count)
+}
+
+/// Read the data of a GNU long name or PAX extended header entry, including its padding.
+///
+/// The size comes from the archive, so it's capped to avoid allocating an arbitrary amount of
+/// memory for a corrupt or malicious archive.
+async fn read_header_data(
+    reader: &mut (dyn AsyncRead + Send + Unpin),
+    size: u64,
+) -> PyObjectStoreResult<Vec<u8>> {
+    if size > MAX_HEADER_DATA_SIZE {
+        return Err(PyIOError::new_err(format!(
+            "Extended tar header of {} bytes exceeds the maximum of {} bytes",
+            size, MAX_HEADER_DATA_SIZE
+        ))
+        .into());
+    }
+    let mut data = vec![0; size as usize + padding(size)];
+    reader.read_exact(&mut data).await?;
+    Ok(data)
+}
+
+async fn copy_entry(
+    reader: &mut (dyn AsyncRead + Send + Unpin),
+    writer: &mut BufWriter,
+    size: u64,
+) -> PyObjectStoreResult<()> {
+    let mut remaining = size;
+    while remaining > 0 {
+        let n = remaining.min(COPY_BUFFER_SIZE as u64) as usize;
+        let mut buf = vec![0; n];
+        reader.read_exact(&mut buf).await?;
+        writer.put(Bytes::from(buf)).await?;
+        remaining -= n as u64;
+    }
+    Ok(())
+}
+
+/// Find the `path` record in a PAX extended header.
+///
+/// Records have the form `"<length> <key>=<value>\n"`.
+fn parse_pax_path(mut data: &[u8]) -> Option<String> {
+    while !data.is_empty() {
+        let space = data.iter().position(|b| *b == b' ')?;
+        let len = std::str::from_utf8(&data[..space])
+            .ok()?
+            .parse::<usize>()
+            .ok()?;
+        if len <= space || len > data.len() {
+            return None;
+        }
+        let record = &data[space + 1..len - 1];
+        if let Some(value) = record.strip_prefix(b"path=") {
+            return Some(String::from_utf8_lossy(value).into_owned());
+        }
+        data = &data[len..];
+    }
+    None
+}
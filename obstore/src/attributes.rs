@@ -15,10 +15,10 @@ impl<'py> FromPyObject<'py> for PyAttribute {
         let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
         match s.as_str() {
             "content-disposition" | "contentdisposition" => Ok(Self(Attribute::ContentDisposition)),
-            "Content-Encoding" | "ContentEncoding" => Ok(Self(Attribute::ContentEncoding)),
-            "Content-Language" | "ContentLanguage" => Ok(Self(Attribute::ContentLanguage)),
-            "Content-Type" | "ContentType" => Ok(Self(Attribute::ContentType)),
-            "Cache-Control" | "CacheControl" => Ok(Self(Attribute::CacheControl)),
+            "content-encoding" | "contentencoding" => Ok(Self(Attribute::ContentEncoding)),
+            "content-language" | "contentlanguage" => Ok(Self(Attribute::ContentLanguage)),
+            "content-type" | "contenttype" => Ok(Self(Attribute::ContentType)),
+            "cache-control" | "cachecontrol" => Ok(Self(Attribute::CacheControl)),
             _ => Ok(Self(Attribute::Metadata(Cow::Owned(s)))),
         }
     }
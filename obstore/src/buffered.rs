@@ -1,78 +1,315 @@
-use std::io::SeekFrom;
+use std::io::{self, SeekFrom};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
 
 use bytes::Bytes;
-use object_store::buffered::{BufReader, BufWriter};
-use object_store::{ObjectMeta, ObjectStore};
-use pyo3::exceptions::{PyIOError, PyStopAsyncIteration, PyStopIteration};
+use chrono::{DateTime, Utc};
+use object_store::buffered::BufReader;
+use object_store::path::Path;
+use object_store::{
+    Attributes, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions,
+    PutPayloadMut, TagSet,
+};
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{
+    PyBufferError, PyIOError, PyStopAsyncIteration, PyStopIteration, PyTypeError, PyValueError,
+};
+use pyo3::import_exception;
 use pyo3::prelude::*;
-use pyo3::types::PyString;
+use pyo3::types::{PyBool, PyString, PyTuple};
 use pyo3::{intern, IntoPyObjectExt};
 use pyo3_async_runtimes::tokio::future_into_py;
 use pyo3_bytes::PyBytes;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
-use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, Lines};
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncSeekExt, ReadBuf};
 use tokio::sync::Mutex;
+use tokio::task::JoinSet;
 
 use crate::attributes::PyAttributes;
+use crate::compression::{
+    decompress_reader, set_content_encoding, Codec, Compressor, PyDecompression,
+};
 use crate::list::PyObjectMeta;
+use crate::progress::{PyProgress, DEFAULT_PROGRESS_INTERVAL};
+use crate::put::PutInput;
 use crate::runtime::get_runtime;
 use crate::tags::PyTagSet;
 
+import_exception!(io, UnsupportedOperation);
+
 #[pyfunction]
-#[pyo3(signature = (store, path, *, buffer_size=1024 * 1024))]
+#[pyo3(signature = (store, path, *, buffer_size=1024 * 1024, decompress=None))]
 pub(crate) fn open_reader(
     py: Python,
-    store: PyObjectStore,
-    path: String,
+    store: Bound<PyAny>,
+    path: PyPath,
     buffer_size: usize,
+    decompress: Option<PyDecompression>,
 ) -> PyObjectStoreResult<PyReadableFile> {
-    let store = store.into_inner();
+    let py_store = store.clone().unbind();
+    let store = store.extract::<PyObjectStore>()?.into_inner();
     let runtime = get_runtime(py)?;
-    let (reader, meta) =
-        py.allow_threads(|| runtime.block_on(create_reader(store, path, buffer_size)))?;
-    Ok(PyReadableFile::new(reader, meta, false))
+    let (reader, meta, codec) = py.allow_threads(|| {
+        runtime.block_on(open_file(store, path.into(), buffer_size, decompress))
+    })?;
+    Ok(PyReadableFile::new(
+        py_store,
+        reader,
+        meta,
+        buffer_size,
+        codec,
+        false,
+    ))
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, *, buffer_size=1024 * 1024))]
-pub(crate) fn open_reader_async(
-    py: Python,
-    store: PyObjectStore,
-    path: String,
+#[pyo3(signature = (store, path, *, buffer_size=1024 * 1024, decompress=None))]
+pub(crate) fn open_reader_async<'py>(
+    py: Python<'py>,
+    store: Bound<'py, PyAny>,
+    path: PyPath,
     buffer_size: usize,
-) -> PyResult<Bound<PyAny>> {
-    let store = store.into_inner();
+    decompress: Option<PyDecompression>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py_store = store.clone().unbind();
+    let store = store.extract::<PyObjectStore>()?.into_inner();
     future_into_py(py, async move {
-        let (reader, meta) = create_reader(store, path, buffer_size).await?;
-        Ok(PyReadableFile::new(reader, meta, true))
+        let (reader, meta, codec) = open_file(store, path.into(), buffer_size, decompress).await?;
+        Ok(PyReadableFile::new(
+            py_store,
+            reader,
+            meta,
+            buffer_size,
+            codec,
+            true,
+        ))
     })
 }
 
-async fn create_reader(
+/// Re-create a [`PyReadableFile`] that was pickled, without fetching the metadata of the object
+/// again.
+#[pyfunction]
+#[pyo3(name = "_restore_readable_file")]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn restore_readable_file(
+    py: Python,
+    store: Bound<PyAny>,
+    path: PyPath,
+    last_modified: DateTime<Utc>,
+    size: u64,
+    e_tag: Option<String>,
+    version: Option<String>,
+    buffer_size: usize,
+    position: u64,
+    r#async: bool,
+    decompress: Option<PyDecompression>,
+) -> PyObjectStoreResult<PyReadableFile> {
+    let py_store = store.clone().unbind();
+    let store = store.extract::<PyObjectStore>()?.into_inner();
+    let runtime = get_runtime(py)?;
+    let meta = ObjectMeta {
+        location: path.into(),
+        last_modified,
+        size,
+        e_tag,
+        version,
+    };
+    let (reader, codec) = match decompress {
+        None => (
+            BufReader::with_capacity(store, &meta, buffer_size).into(),
+            None,
+        ),
+        // A decompressed file is read again from the start, up to the position it was at
+        Some(decompress) => {
+            let location = meta.location.clone();
+            let (reader, _, codec) = py.allow_threads(|| {
+                runtime.block_on(open_file(store, location, buffer_size, Some(decompress)))
+            })?;
+            (reader, codec)
+        }
+    };
+    let file = PyReadableFile::new(py_store, reader, meta, buffer_size, codec, r#async);
+    let reader = file.reader.clone();
+    py.allow_threads(|| runtime.block_on(seek(reader, SeekFrom::Start(position))))?;
+    Ok(file)
+}
+
+pub(crate) async fn create_reader(
     store: Arc<dyn ObjectStore>,
-    path: String,
+    path: Path,
     capacity: usize,
 ) -> PyObjectStoreResult<(BufReader, ObjectMeta)> {
     let meta = store
-        .head(&path.into())
+        .head(&path)
         .await
         .map_err(PyObjectStoreError::ObjectStoreError)?;
     Ok((BufReader::with_capacity(store, &meta, capacity), meta))
 }
 
+/// Open the object at `path` for reading, decompressing it if requested by `decompress`.
+///
+/// Returns the codec that the object is decompressed with, which is `None` if `decompress` is
+/// `"auto"` and no codec could be inferred for the object, in which case it's read as is.
+async fn open_file(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    capacity: usize,
+    decompress: Option<PyDecompression>,
+) -> PyObjectStoreResult<(FileReader, ObjectMeta, Option<Codec>)> {
+    let Some(decompress) = decompress else {
+        let (reader, meta) = create_reader(store, path, capacity).await?;
+        return Ok((reader.into(), meta, None));
+    };
+    let result = store
+        .get(&path)
+        .await
+        .map_err(PyObjectStoreError::ObjectStoreError)?;
+    let meta = result.meta.clone();
+    match decompress.resolve(&result.attributes, &meta.location) {
+        Some(codec) => {
+            let reader = decompress_reader(codec, result.into_stream());
+            let reader = FileReader::Decompressed {
+                reader: tokio::io::BufReader::with_capacity(capacity, reader),
+                position: 0,
+            };
+            Ok((reader, meta, Some(codec)))
+        }
+        None => {
+            // Dropping the body of the response closes its connection
+            drop(result);
+            Ok((
+                BufReader::with_capacity(store, &meta, capacity).into(),
+                meta,
+                None,
+            ))
+        }
+    }
+}
+
+/// The reader of a [`PyReadableFile`].
+pub(crate) enum FileReader {
+    /// The object as stored, which can be seeked to any position
+    Plain(BufReader),
+    /// The decompressed contents of the object, which can only be read forwards
+    Decompressed {
+        reader: tokio::io::BufReader<Box<dyn AsyncRead + Send + Unpin>>,
+        /// The number of decompressed bytes read so far
+        position: u64,
+    },
+}
+
+impl From<BufReader> for FileReader {
+    fn from(reader: BufReader) -> Self {
+        Self::Plain(reader)
+    }
+}
+
+impl FileReader {
+    /// Seek to `pos`, which for a decompressed file must be at or after the current position, as
+    /// the data in between is read and discarded.
+    async fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (position, target) = match self {
+            Self::Plain(reader) => return reader.seek(pos).await,
+            Self::Decompressed { position, .. } => {
+                let target = match pos {
+                    SeekFrom::Start(target) => Some(target),
+                    SeekFrom::Current(offset) => position.checked_add_signed(offset),
+                    SeekFrom::End(_) => None,
+                };
+                (*position, target)
+            }
+        };
+        match target {
+            Some(target) if target >= position => {
+                tokio::io::copy(
+                    &mut (&mut *self).take(target - position),
+                    &mut tokio::io::sink(),
+                )
+                .await?;
+                self.tell().await
+            }
+            _ => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "A decompressed file can only be seeked forwards from the current position",
+            )),
+        }
+    }
+
+    async fn tell(&mut self) -> io::Result<u64> {
+        match self {
+            Self::Plain(reader) => reader.stream_position().await,
+            Self::Decompressed { position, .. } => Ok(*position),
+        }
+    }
+}
+
+impl AsyncRead for FileReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Plain(reader) => Pin::new(reader).poll_read(cx, buf),
+            Self::Decompressed { reader, position } => {
+                let filled = buf.filled().len();
+                ready!(Pin::new(reader).poll_read(cx, buf))?;
+                *position += (buf.filled().len() - filled) as u64;
+                Poll::Ready(Ok(()))
+            }
+        }
+    }
+}
+
+impl AsyncBufRead for FileReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        match self.get_mut() {
+            Self::Plain(reader) => Pin::new(reader).poll_fill_buf(cx),
+            Self::Decompressed { reader, .. } => Pin::new(reader).poll_fill_buf(cx),
+        }
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        match self.get_mut() {
+            Self::Plain(reader) => Pin::new(reader).consume(amt),
+            Self::Decompressed { reader, position } => {
+                *position += amt as u64;
+                Pin::new(reader).consume(amt);
+            }
+        }
+    }
+}
+
 #[pyclass(name = "ReadableFile", frozen)]
 pub(crate) struct PyReadableFile {
-    reader: Arc<Mutex<BufReader>>,
+    /// The Python store that the file was opened from, kept so that the file can be pickled
+    store: PyObject,
+    reader: Arc<Mutex<FileReader>>,
     meta: ObjectMeta,
+    buffer_size: usize,
+    /// The codec that the object is decompressed with, if any
+    decompress: Option<Codec>,
     r#async: bool,
 }
 
 impl PyReadableFile {
-    fn new(reader: BufReader, meta: ObjectMeta, r#async: bool) -> Self {
+    pub(crate) fn new(
+        store: PyObject,
+        reader: FileReader,
+        meta: ObjectMeta,
+        buffer_size: usize,
+        decompress: Option<Codec>,
+        r#async: bool,
+    ) -> Self {
         Self {
+            store,
             reader: Arc::new(Mutex::new(reader)),
             meta,
+            buffer_size,
+            decompress,
             r#async,
         }
     }
@@ -80,17 +317,51 @@ impl PyReadableFile {
 
 #[pymethods]
 impl PyReadableFile {
-    // Note: to enable this, we'd have to make the PyReadableFile contain an `Option<>` that here
-    // we could move out.
-    // async fn __aiter__(&mut self) -> PyObjectStoreResult<PyLinesReader> {
-    //     let reader = self.reader.clone();
-    //     let reader = reader.lock().await;
-    //     let lines = reader.lines();
-    //     Ok(PyLinesReader(Arc::new(Mutex::new(lines))))
-    // }
+    /// Pickle the file as its store, the metadata of the object, and the current position, so
+    /// that it can be re-opened in another process.
+    fn __reduce__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let runtime = get_runtime(py)?;
+        let reader = self.reader.clone();
+        let position = py.allow_threads(|| runtime.block_on(tell(reader)))?;
+        let restore = py
+            .import(intern!(py, "obstore._obstore"))?
+            .getattr(intern!(py, "_restore_readable_file"))?;
+        let args = (
+            self.store.clone_ref(py),
+            self.meta.location.as_ref(),
+            self.meta.last_modified,
+            self.meta.size,
+            self.meta.e_tag.as_deref(),
+            self.meta.version.as_deref(),
+            self.buffer_size,
+            position,
+            self.r#async,
+            self.decompress.map(|codec| codec.name()),
+        );
+        (restore, args).into_pyobject(py)
+    }
+
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let reader = self.reader.clone();
+        future_into_py(py, next_line(reader, true))
+    }
+
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __next__<'py>(&'py self, py: Python<'py>) -> PyResult<PyBytes> {
+        let runtime = get_runtime(py)?;
+        let reader = self.reader.clone();
+        py.allow_threads(|| runtime.block_on(next_line(reader, false)))
+    }
 
     // Maybe this should dispose of the internal reader? In that case we want to store an
-    // `Option<Arc<Mutex<BufReader>>>`.
+    // `Option<Arc<Mutex<FileReader>>>`.
     fn close(&self) {}
 
     #[getter]
@@ -115,6 +386,28 @@ impl PyReadableFile {
         self.read(py, None)
     }
 
+    fn readinto<'py>(&'py self, py: Python<'py>, buffer: PyBuffer<u8>) -> PyResult<PyObject> {
+        if buffer.readonly() {
+            return Err(PyTypeError::new_err(
+                "readinto() argument must be a writable bytes-like object",
+            ));
+        }
+
+        let reader = self.reader.clone();
+        let size = buffer.item_count();
+        if self.r#async {
+            let out = future_into_py(py, async move {
+                let data = read_up_to(reader, size).await?;
+                Python::with_gil(|py| copy_into_buffer(py, &buffer, &data))
+            })?;
+            Ok(out.unbind())
+        } else {
+            let runtime = get_runtime(py)?;
+            let data = py.allow_threads(|| runtime.block_on(read_up_to(reader, size)))?;
+            copy_into_buffer(py, &buffer, &data)?.into_py_any(py)
+        }
+    }
+
     fn readline<'py>(&'py self, py: Python<'py>) -> PyResult<PyObject> {
         let reader = self.reader.clone();
         if self.r#async {
@@ -170,12 +463,19 @@ impl PyReadableFile {
     }
 
     fn seekable(&self) -> bool {
-        true
+        self.decompress.is_none()
     }
 
+    /// The size of the object, which isn't known for a decompressed file until it's been read,
+    /// as the stored object is compressed.
     #[getter]
-    fn size(&self) -> u64 {
-        self.meta.size
+    fn size(&self) -> PyResult<u64> {
+        if self.decompress.is_some() {
+            return Err(UnsupportedOperation::new_err(
+                "The size of a decompressed file isn't known until it's been read",
+            ));
+        }
+        Ok(self.meta.size)
     }
 
     fn tell<'py>(&'py self, py: Python<'py>) -> PyResult<PyObject> {
@@ -191,7 +491,7 @@ impl PyReadableFile {
     }
 }
 
-async fn read(reader: Arc<Mutex<BufReader>>, size: Option<usize>) -> PyResult<PyBytes> {
+async fn read(reader: Arc<Mutex<FileReader>>, size: Option<usize>) -> PyResult<PyBytes> {
     let mut reader = reader.lock().await;
     if let Some(size) = size {
         let mut buf = vec![0; size as _];
@@ -204,14 +504,35 @@ async fn read(reader: Arc<Mutex<BufReader>>, size: Option<usize>) -> PyResult<Py
     }
 }
 
-async fn readline(reader: Arc<Mutex<BufReader>>) -> PyResult<PyBytes> {
+/// Read until `size` bytes have been read or EOF is reached.
+async fn read_up_to(reader: Arc<Mutex<FileReader>>, size: usize) -> PyResult<Vec<u8>> {
+    let mut reader = reader.lock().await;
+    let mut buf = Vec::with_capacity(size);
+    (&mut *reader)
+        .take(size as u64)
+        .read_to_end(&mut buf)
+        .await?;
+    Ok(buf)
+}
+
+fn copy_into_buffer(py: Python, buffer: &PyBuffer<u8>, data: &[u8]) -> PyResult<usize> {
+    let slice = buffer.as_mut_slice(py).ok_or_else(|| {
+        PyBufferError::new_err("readinto() argument must be a C-contiguous writable buffer")
+    })?;
+    for (cell, byte) in slice.iter().zip(data) {
+        cell.set(*byte);
+    }
+    Ok(data.len())
+}
+
+async fn readline(reader: Arc<Mutex<FileReader>>) -> PyResult<PyBytes> {
     let mut reader = reader.lock().await;
     let mut buf = String::new();
     reader.read_line(&mut buf).await?;
     Ok(Bytes::from(buf.into_bytes()).into())
 }
 
-async fn readlines(reader: Arc<Mutex<BufReader>>, hint: i64) -> PyResult<Vec<PyBytes>> {
+async fn readlines(reader: Arc<Mutex<FileReader>>, hint: i64) -> PyResult<Vec<PyBytes>> {
     let mut reader = reader.lock().await;
     if hint <= 0 {
         let mut lines = Vec::new();
@@ -244,39 +565,33 @@ async fn readlines(reader: Arc<Mutex<BufReader>>, hint: i64) -> PyResult<Vec<PyB
     }
 }
 
-async fn seek(reader: Arc<Mutex<BufReader>>, pos: SeekFrom) -> PyResult<u64> {
+async fn seek(reader: Arc<Mutex<FileReader>>, pos: SeekFrom) -> PyResult<u64> {
     let mut reader = reader.lock().await;
-    let pos = reader.seek(pos).await?;
+    let pos = reader.seek(pos).await.map_err(|err| {
+        if err.kind() == io::ErrorKind::Unsupported {
+            UnsupportedOperation::new_err(err.to_string())
+        } else {
+            err.into()
+        }
+    })?;
     Ok(pos)
 }
 
-async fn tell(reader: Arc<Mutex<BufReader>>) -> PyResult<u64> {
+async fn tell(reader: Arc<Mutex<FileReader>>) -> PyResult<u64> {
     let mut reader = reader.lock().await;
-    let pos = reader.stream_position().await?;
+    let pos = reader.tell().await?;
     Ok(pos)
 }
 
-#[pyclass(frozen)]
-pub(crate) struct PyLinesReader(Arc<Mutex<Lines<BufReader>>>);
-
-#[pymethods]
-impl PyLinesReader {
-    fn __anext__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
-        let lines = self.0.clone();
-        future_into_py(py, next_line(lines, true))
-    }
-
-    fn __next__<'py>(&'py self, py: Python<'py>) -> PyResult<String> {
-        let runtime = get_runtime(py)?;
-        let lines = self.0.clone();
-        py.allow_threads(|| runtime.block_on(next_line(lines, false)))
-    }
-}
-
-async fn next_line(reader: Arc<Mutex<Lines<BufReader>>>, r#async: bool) -> PyResult<String> {
+/// Read the next line, including the trailing newline, raising `StopIteration` at EOF.
+///
+/// Unlike `readline`, this reads raw bytes so that lines need not be valid UTF-8.
+async fn next_line(reader: Arc<Mutex<FileReader>>, r#async: bool) -> PyResult<PyBytes> {
     let mut reader = reader.lock().await;
-    if let Some(line) = reader.next_line().await.unwrap() {
-        Ok(line)
+    let mut buf = Vec::new();
+    let n = reader.read_until(b'\n', &mut buf).await?;
+    if n > 0 {
+        Ok(Bytes::from(buf).into())
     } else if r#async {
         Err(PyStopAsyncIteration::new_err("stream exhausted"))
     } else {
@@ -285,66 +600,348 @@ async fn next_line(reader: Arc<Mutex<Lines<BufReader>>>, r#async: bool) -> PyRes
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, *, attributes=None, buffer_size=10 * 1024 * 1024, tags=None, max_concurrency=12))]
+#[pyo3(signature = (store, path, *, attributes=None, buffer_size=10 * 1024 * 1024, tags=None, max_concurrency=12, flush_interval=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, compress=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn open_writer(
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     attributes: Option<PyAttributes>,
     buffer_size: usize,
     tags: Option<PyTagSet>,
     max_concurrency: usize,
+    flush_interval: Option<FlushInterval>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    compress: Option<Codec>,
 ) -> PyObjectStoreResult<PyWritableFile> {
     Ok(PyWritableFile::new(
-        create_writer(store, path, attributes, buffer_size, tags, max_concurrency),
+        create_writer(
+            store.into_inner(),
+            path.into(),
+            attributes,
+            buffer_size,
+            tags,
+            max_concurrency,
+            flush_interval,
+            PyProgress::new(progress, progress_interval, None),
+            compress,
+        )?,
         false,
     ))
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, *, attributes=None, buffer_size=10 * 1024 * 1024, tags=None, max_concurrency=12))]
+#[pyo3(signature = (store, path, *, attributes=None, buffer_size=10 * 1024 * 1024, tags=None, max_concurrency=12, flush_interval=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, compress=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn open_writer_async(
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     attributes: Option<PyAttributes>,
     buffer_size: usize,
     tags: Option<PyTagSet>,
     max_concurrency: usize,
+    flush_interval: Option<FlushInterval>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    compress: Option<Codec>,
 ) -> PyResult<PyWritableFile> {
     Ok(PyWritableFile::new(
-        create_writer(store, path, attributes, buffer_size, tags, max_concurrency),
+        create_writer(
+            store.into_inner(),
+            path.into(),
+            attributes,
+            buffer_size,
+            tags,
+            max_concurrency,
+            flush_interval,
+            PyProgress::new(progress, progress_interval, None),
+            compress,
+        )?,
         true,
     ))
 }
 
-fn create_writer(
-    store: PyObjectStore,
-    path: String,
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_writer(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
     attributes: Option<PyAttributes>,
     capacity: usize,
     tags: Option<PyTagSet>,
     max_concurrency: usize,
-) -> Arc<Mutex<Option<BufWriter>>> {
-    let store = store.into_inner();
-    let mut writer = BufWriter::with_capacity(store, path.into(), capacity)
-        .with_max_concurrency(max_concurrency);
-    if let Some(attributes) = attributes {
-        writer = writer.with_attributes(attributes.into_inner());
+    flush_interval: Option<FlushInterval>,
+    progress: Option<Arc<PyProgress>>,
+    compress: Option<Codec>,
+) -> PyObjectStoreResult<Arc<Mutex<Option<ObjectWriter>>>> {
+    let mut attributes = attributes.map(|x| x.into_inner()).unwrap_or_default();
+    let inferred_attributes =
+        compress.is_some_and(|codec| set_content_encoding(&mut attributes, codec));
+    let writer = ObjectWriter {
+        store,
+        path,
+        capacity,
+        max_concurrency: max_concurrency.max(1),
+        attributes,
+        inferred_attributes,
+        tags: tags.map(|x| x.into_inner()).unwrap_or_default(),
+        flush_interval,
+        buffer: PutPayloadMut::new(),
+        upload: None,
+        tasks: JoinSet::new(),
+        last_flush: Instant::now(),
+        progress,
+        compressor: compress.map(Compressor::new).transpose()?,
+    };
+    Ok(Arc::new(Mutex::new(Some(writer))))
+}
+
+/// The minimum size of every part but the last, as required by S3 and GCS.
+const MIN_FLUSH_BYTES: usize = 5 * 1024 * 1024;
+
+/// A threshold after which buffered data is uploaded as a part, even if the buffer is not full.
+///
+/// A duration is only checked when data is written, and only once [`MIN_FLUSH_BYTES`] are
+/// buffered, so that every part but the last meets the minimum part size.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum FlushInterval {
+    Bytes(usize),
+    Duration(Duration),
+}
+
+impl<'py> FromPyObject<'py> for FlushInterval {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        // `bool` is a subclass of `int`, but `True` isn't a meaningful number of bytes
+        if ob.is_instance_of::<PyBool>() {
+            Err(PyValueError::new_err(format!(
+                "Expected flush_interval to be an int number of bytes or a timedelta, got {}",
+                ob.repr()?
+            )))
+        } else if let Ok(bytes) = ob.extract::<usize>() {
+            if bytes < MIN_FLUSH_BYTES {
+                return Err(PyValueError::new_err(format!(
+                    "flush_interval must be at least 5 MiB ({MIN_FLUSH_BYTES} bytes), got {bytes}."
+                )));
+            }
+            Ok(Self::Bytes(bytes))
+        } else if let Ok(duration) = ob.extract() {
+            Ok(Self::Duration(duration))
+        } else {
+            Err(PyValueError::new_err(format!(
+                "Expected flush_interval to be an int number of bytes or a timedelta, got {}",
+                ob.repr()?
+            )))
+        }
     }
-    if let Some(tags) = tags {
-        writer = writer.with_tags(tags.into_inner());
+}
+
+/// A buffered writer to an object store.
+///
+/// This is similar to [`object_store::buffered::BufWriter`], except that it can upload a
+/// partially-filled buffer as a part when a [`FlushInterval`] is reached. Like `BufWriter`, data
+/// is written with a single `put` if the writer is closed before `capacity` bytes have been
+/// buffered.
+///
+/// With a [`Compressor`], data is compressed as it's written, and `capacity` applies to the
+/// compressed data.
+pub(crate) struct ObjectWriter {
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    capacity: usize,
+    max_concurrency: usize,
+    attributes: Attributes,
+    /// Whether `attributes` only holds the `Content-Encoding` set for `compressor`, which is
+    /// dropped for stores that don't support attributes
+    inferred_attributes: bool,
+    tags: TagSet,
+    flush_interval: Option<FlushInterval>,
+    buffer: PutPayloadMut,
+    upload: Option<Box<dyn MultipartUpload>>,
+    /// In-flight part uploads, each of which resolves to the size of its part
+    tasks: JoinSet<object_store::Result<usize>>,
+    last_flush: Instant,
+    progress: Option<Arc<PyProgress>>,
+    compressor: Option<Compressor>,
+}
+
+impl ObjectWriter {
+    async fn put(&mut self, bytes: Bytes) -> PyObjectStoreResult<()> {
+        let bytes = match &mut self.compressor {
+            Some(compressor) => compressor.compress(&bytes)?,
+            None => bytes,
+        };
+        self.buffer_bytes(bytes).await?;
+
+        if self.flush_due() {
+            self.upload_part().await?;
+        }
+        Ok(())
+    }
+
+    async fn buffer_bytes(&mut self, mut bytes: Bytes) -> PyObjectStoreResult<()> {
+        // Upload a part as soon as the buffer holds `capacity` bytes. Parts are only smaller
+        // than that when a flush interval is reached first.
+        while !bytes.is_empty() {
+            let remaining = self.capacity - self.buffer.content_length();
+            if bytes.len() < remaining {
+                self.buffer.push(bytes);
+                break;
+            }
+            self.buffer.push(bytes.split_to(remaining));
+            self.upload_part().await?;
+        }
+        Ok(())
+    }
+
+    fn flush_due(&self) -> bool {
+        match self.flush_interval {
+            Some(FlushInterval::Bytes(bytes)) => self.buffer.content_length() >= bytes,
+            Some(FlushInterval::Duration(duration)) => {
+                self.buffer.content_length() >= MIN_FLUSH_BYTES
+                    && self.last_flush.elapsed() >= duration
+            }
+            None => false,
+        }
+    }
+
+    /// Upload the current buffer as the next part, starting the multipart upload if necessary.
+    async fn upload_part(&mut self) -> PyObjectStoreResult<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+
+        if self.upload.is_none() {
+            let mut opts = PutMultipartOptions {
+                attributes: std::mem::take(&mut self.attributes),
+                tags: std::mem::take(&mut self.tags),
+                ..Default::default()
+            };
+            let upload = match self
+                .store
+                .put_multipart_opts(&self.path, opts.clone())
+                .await
+            {
+                // Stores that don't support attributes still get the compressed data
+                Err(object_store::Error::NotImplemented) if self.inferred_attributes => {
+                    opts.attributes = Attributes::new();
+                    self.store.put_multipart_opts(&self.path, opts).await?
+                }
+                result => result?,
+            };
+            self.upload = Some(upload);
+        }
+
+        while self.tasks.len() >= self.max_concurrency {
+            if let Some(result) = self.tasks.join_next().await {
+                self.part_uploaded(result.map_err(std::io::Error::from)??);
+            }
+        }
+
+        let part = std::mem::take(&mut self.buffer).freeze();
+        let part_size = part.content_length();
+        let upload_part = self.upload.as_mut().unwrap().put_part(part);
+        self.tasks
+            .spawn(async move { upload_part.await.map(|()| part_size) });
+        self.last_flush = Instant::now();
+        Ok(())
+    }
+
+    fn part_uploaded(&self, part_size: usize) {
+        if let Some(progress) = &self.progress {
+            progress.update(part_size as u64);
+        }
+    }
+
+    /// Wait for all in-flight part uploads to complete.
+    async fn flush(&mut self) -> PyObjectStoreResult<()> {
+        while let Some(result) = self.tasks.join_next().await {
+            self.part_uploaded(result.map_err(std::io::Error::from)??);
+        }
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> PyObjectStoreResult<()> {
+        if let Some(compressor) = self.compressor.take() {
+            let result = async {
+                let rest = compressor.finish()?;
+                self.buffer_bytes(rest).await
+            }
+            .await;
+            if result.is_err() {
+                self.abort().await?;
+                return result;
+            }
+        }
+
+        if self.upload.is_none() {
+            let mut opts = PutOptions {
+                attributes: std::mem::take(&mut self.attributes),
+                tags: std::mem::take(&mut self.tags),
+                ..Default::default()
+            };
+            let payload = std::mem::take(&mut self.buffer).freeze();
+            let part_size = payload.content_length();
+            match self
+                .store
+                .put_opts(&self.path, payload.clone(), opts.clone())
+                .await
+            {
+                // Stores that don't support attributes still get the compressed data
+                Err(object_store::Error::NotImplemented) if self.inferred_attributes => {
+                    opts.attributes = Attributes::new();
+                    self.store.put_opts(&self.path, payload, opts).await?;
+                }
+                result => {
+                    result?;
+                }
+            }
+            self.part_uploaded(part_size);
+            if let Some(progress) = &self.progress {
+                progress.finish();
+            }
+            return Ok(());
+        }
+
+        let result = async {
+            self.upload_part().await?;
+            self.flush().await?;
+            if let Some(upload) = self.upload.as_mut() {
+                upload.complete().await?;
+            }
+            if let Some(progress) = &self.progress {
+                progress.finish();
+            }
+            Ok::<_, PyObjectStoreError>(())
+        }
+        .await;
+        if result.is_err() {
+            self.abort().await?;
+        }
+        result
+    }
+
+    async fn abort(&mut self) -> PyObjectStoreResult<()> {
+        self.tasks.shutdown().await;
+        if let Some(mut upload) = self.upload.take() {
+            upload.abort().await?;
+        }
+        Ok(())
     }
-    Arc::new(Mutex::new(Some(writer)))
 }
 
 #[pyclass(name = "WritableFile", frozen)]
 pub(crate) struct PyWritableFile {
-    writer: Arc<Mutex<Option<BufWriter>>>,
+    writer: Arc<Mutex<Option<ObjectWriter>>>,
+    bytes_written: Arc<AtomicU64>,
     r#async: bool,
 }
 
 impl PyWritableFile {
-    fn new(writer: Arc<Mutex<Option<BufWriter>>>, r#async: bool) -> Self {
-        Self { writer, r#async }
+    pub(crate) fn new(writer: Arc<Mutex<Option<ObjectWriter>>>, r#async: bool) -> Self {
+        Self {
+            writer,
+            bytes_written: Arc::new(AtomicU64::new(0)),
+            r#async,
+        }
     }
 }
 
@@ -403,6 +1000,11 @@ impl PyWritableFile {
         }
     }
 
+    #[getter]
+    fn bytes_written(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
     fn close<'py>(&'py self, py: Python<'py>) -> PyResult<PyObject> {
         let writer = self.writer.clone();
         if self.r#async {
@@ -417,13 +1019,14 @@ impl PyWritableFile {
 
     /// It's a little unfortunate that this is a method instead of an attribute.
     ///
-    /// We need an `Option` somewhere in order to be able to drop the internal `BufWriter` to check
-    /// that it has already been closed. (The `object_store` API will error if the file is closed
-    /// twice, but doesn't give a way to check if the file has already been closed).
+    /// We need an `Option` somewhere in order to be able to drop the internal `ObjectWriter` to
+    /// check that it has already been closed. (The `object_store` API will error if the file is
+    /// closed twice, but doesn't give a way to check if the file has already been closed).
     ///
-    /// This being an async method is an artifact of storing the underlying BufWriter inside of an
+    /// This being an async method is an artifact of storing the underlying ObjectWriter inside of
+    /// an
     /// ```rs
-    /// Arc<Mutex<Option<BufWriter>>>
+    /// Arc<Mutex<Option<ObjectWriter>>>
     /// ```
     /// where the `Mutex` is a `tokio::sync::Mutex`.
     ///
@@ -456,32 +1059,66 @@ impl PyWritableFile {
 
     fn write<'py>(&'py self, py: Python<'py>, buffer: PyBytes) -> PyResult<PyObject> {
         let writer = self.writer.clone();
+        let bytes_written = self.bytes_written.clone();
+        if self.r#async {
+            let out = future_into_py(py, write(writer, bytes_written, buffer))?;
+            Ok(out.unbind())
+        } else {
+            let runtime = get_runtime(py)?;
+            let out =
+                py.allow_threads(|| runtime.block_on(write(writer, bytes_written, buffer)))?;
+            out.into_py_any(py)
+        }
+    }
+
+    fn tell(&self) -> u64 {
+        self.bytes_written.load(Ordering::Relaxed)
+    }
+
+    fn write_from<'py>(&'py self, py: Python<'py>, chunks: PutInput) -> PyResult<PyObject> {
+        let writer = self.writer.clone();
+        let bytes_written = self.bytes_written.clone();
+        match chunks {
+            PutInput::SyncPush(_) => {}
+            PutInput::AsyncPush(_) if self.r#async => {}
+            PutInput::AsyncPush(_) => {
+                return Err(PyValueError::new_err(
+                    "Async input not allowed in 'write_from'. Use 'open_writer_async'.",
+                ))
+            }
+            _ => {
+                return Err(PyTypeError::new_err(
+                    "Expected an iterable or async iterable of buffers. Use 'write' for a single buffer.",
+                ))
+            }
+        }
         if self.r#async {
-            let out = future_into_py(py, write(writer, buffer))?;
+            let out = future_into_py(py, write_from(writer, bytes_written, chunks))?;
             Ok(out.unbind())
         } else {
             let runtime = get_runtime(py)?;
-            let out = py.allow_threads(|| runtime.block_on(write(writer, buffer)))?;
+            let out =
+                py.allow_threads(|| runtime.block_on(write_from(writer, bytes_written, chunks)))?;
             out.into_py_any(py)
         }
     }
 }
 
-async fn is_closed(writer: Arc<Mutex<Option<BufWriter>>>) -> PyResult<bool> {
+async fn is_closed(writer: Arc<Mutex<Option<ObjectWriter>>>) -> PyResult<bool> {
     let writer = writer.lock().await;
     Ok(writer.is_none())
 }
 
-async fn abort_writer(writer: Arc<Mutex<Option<BufWriter>>>) -> PyResult<()> {
+async fn abort_writer(writer: Arc<Mutex<Option<ObjectWriter>>>) -> PyResult<()> {
     let mut writer = writer.lock().await;
     let mut writer = writer
         .take()
         .ok_or(PyIOError::new_err("Writer already closed."))?;
-    writer.abort().await.map_err(PyObjectStoreError::from)?;
+    writer.abort().await?;
     Ok(())
 }
 
-async fn close_writer(writer: Arc<Mutex<Option<BufWriter>>>) -> PyResult<()> {
+async fn close_writer(writer: Arc<Mutex<Option<ObjectWriter>>>) -> PyResult<()> {
     let mut writer = writer.lock().await;
     let mut writer = writer
         .take()
@@ -490,7 +1127,7 @@ async fn close_writer(writer: Arc<Mutex<Option<BufWriter>>>) -> PyResult<()> {
     Ok(())
 }
 
-async fn flush(writer: Arc<Mutex<Option<BufWriter>>>) -> PyResult<()> {
+async fn flush(writer: Arc<Mutex<Option<ObjectWriter>>>) -> PyResult<()> {
     let mut writer = writer.lock().await;
     let writer = writer
         .as_mut()
@@ -499,13 +1136,49 @@ async fn flush(writer: Arc<Mutex<Option<BufWriter>>>) -> PyResult<()> {
     Ok(())
 }
 
-async fn write(writer: Arc<Mutex<Option<BufWriter>>>, buffer: PyBytes) -> PyResult<usize> {
+async fn write(
+    writer: Arc<Mutex<Option<ObjectWriter>>>,
+    bytes_written: Arc<AtomicU64>,
+    buffer: PyBytes,
+) -> PyResult<usize> {
     let mut writer = writer.lock().await;
     let writer = writer
         .as_mut()
         .ok_or(PyIOError::new_err("Writer already closed."))?;
     let buffer = buffer.into_inner();
     let buffer_length = buffer.len();
-    writer.put(buffer).await.map_err(PyObjectStoreError::from)?;
+    writer.put(buffer).await?;
+    bytes_written.fetch_add(buffer_length as u64, Ordering::Relaxed);
     Ok(buffer_length)
 }
+
+/// Write every chunk of a push source to `writer`, returning the number of bytes written.
+///
+/// The writer is locked for the duration, so that the chunks aren't interleaved with other
+/// writes.
+async fn write_from(
+    writer: Arc<Mutex<Option<ObjectWriter>>>,
+    bytes_written: Arc<AtomicU64>,
+    mut chunks: PutInput,
+) -> PyResult<u64> {
+    let mut writer = writer.lock().await;
+    let writer = writer
+        .as_mut()
+        .ok_or(PyIOError::new_err("Writer already closed."))?;
+    let mut total = 0;
+    loop {
+        let buffer = match &mut chunks {
+            PutInput::SyncPush(source) => source.next_chunk()?,
+            PutInput::AsyncPush(source) => source.next_chunk().await?,
+            _ => unreachable!("checked by PyWritableFile::write_from"),
+        };
+        let Some(buffer) = buffer else {
+            break;
+        };
+        let buffer_length = buffer.len() as u64;
+        writer.put(buffer).await?;
+        bytes_written.fetch_add(buffer_length, Ordering::Relaxed);
+        total += buffer_length;
+    }
+    Ok(total)
+}
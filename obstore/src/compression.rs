@@ -0,0 +1,228 @@
+//! Streaming compression of uploaded objects, and decompression of downloaded objects.
+//!
+//! Data is decompressed in Rust as it arrives, so that large compressed objects can be iterated
+//! over without first being downloaded in full or decompressed by a second pass in Python.
+//! Likewise, uploads are compressed chunk by chunk as they're sent, including uploads from
+//! iterators of unknown length.
+
+use std::io::{self, Write};
+
+use async_compression::tokio::bufread::{GzipDecoder, ZstdDecoder};
+use bytes::Bytes;
+use flate2::write::GzEncoder;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{Attribute, Attributes, PutPayload};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use tokio::io::AsyncRead;
+use tokio_util::io::{ReaderStream, StreamReader};
+
+/// A compression codec that objects can be compressed or decompressed with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Codec {
+    Gzip,
+    Zstd,
+}
+
+impl Codec {
+    /// The codec of a `Content-Encoding`, if it's one that can be decompressed.
+    fn from_content_encoding(encoding: &str) -> Option<Self> {
+        match encoding.trim().to_ascii_lowercase().as_str() {
+            "gzip" | "x-gzip" => Some(Self::Gzip),
+            "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    /// The codec of a file extension, if it's one that can be decompressed.
+    fn from_extension(path: &Path) -> Option<Self> {
+        match path.extension()?.to_ascii_lowercase().as_str() {
+            "gz" | "gzip" => Some(Self::Gzip),
+            "zst" | "zstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+}
+
+/// The `compress` parameter of `put` and `open_writer`.
+impl<'py> FromPyObject<'py> for Codec {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "gzip" => Ok(Self::Gzip),
+            "zstd" => Ok(Self::Zstd),
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported compression: '{}'. Expected 'gzip' or 'zstd'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// The `decompress` parameter of `GetResult.stream` and `open_reader`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PyDecompression {
+    Codec(Codec),
+    /// Infer the codec from the `Content-Encoding` of the object, or else from the extension of
+    /// its path, and don't decompress objects of neither.
+    Auto,
+}
+
+impl PyDecompression {
+    /// The codec to decompress the object at `location` with.
+    pub(crate) fn resolve(&self, attributes: &Attributes, location: &Path) -> Option<Codec> {
+        match self {
+            Self::Codec(codec) => Some(*codec),
+            Self::Auto => attributes
+                .get(&Attribute::ContentEncoding)
+                .and_then(|encoding| Codec::from_content_encoding(encoding))
+                .or_else(|| Codec::from_extension(location)),
+        }
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyDecompression {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "gzip" => Ok(Self::Codec(Codec::Gzip)),
+            "zstd" => Ok(Self::Codec(Codec::Zstd)),
+            "auto" => Ok(Self::Auto),
+            _ => Err(PyValueError::new_err(format!(
+                "Unsupported decompression: '{}'. Expected one of 'gzip', 'zstd', or 'auto'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// A reader of the decompressed contents of `stream`.
+///
+/// Concatenated gzip and zstd streams, as written by appending to a compressed file, are read as
+/// one.
+pub(crate) fn decompress_reader(
+    codec: Codec,
+    stream: BoxStream<'static, object_store::Result<Bytes>>,
+) -> Box<dyn AsyncRead + Send + Unpin> {
+    let reader = StreamReader::new(stream.map_err(io::Error::other));
+    match codec {
+        Codec::Gzip => {
+            let mut decoder = GzipDecoder::new(reader);
+            decoder.multiple_members(true);
+            Box::new(decoder)
+        }
+        Codec::Zstd => {
+            let mut decoder = ZstdDecoder::new(reader);
+            decoder.multiple_members(true);
+            Box::new(decoder)
+        }
+    }
+}
+
+/// The decompressed contents of `stream`, in chunks of up to `chunk_size` bytes.
+pub(crate) fn decompress_stream(
+    codec: Codec,
+    stream: BoxStream<'static, object_store::Result<Bytes>>,
+    chunk_size: usize,
+) -> BoxStream<'static, object_store::Result<Bytes>> {
+    ReaderStream::with_capacity(decompress_reader(codec, stream), chunk_size)
+        .map_err(move |err| into_object_store_error(codec, err))
+        .boxed()
+}
+
+/// Convert an error of decompression back to an error of the store, if it was one, so that
+/// request failures raise the same exceptions as without decompression.
+fn into_object_store_error(codec: Codec, err: io::Error) -> object_store::Error {
+    let message = err.to_string();
+    match err
+        .into_inner()
+        .map(|inner| inner.downcast::<object_store::Error>())
+    {
+        Some(Ok(err)) => *err,
+        _ => object_store::Error::Generic {
+            store: "decompress",
+            source: format!("Failed to decompress {} data: {}", codec.name(), message).into(),
+        },
+    }
+}
+
+/// An encoder that compresses an upload chunk by chunk.
+pub(crate) enum Compressor {
+    Gzip(GzEncoder<Vec<u8>>),
+    Zstd(zstd::Encoder<'static, Vec<u8>>),
+}
+
+impl Compressor {
+    pub(crate) fn new(codec: Codec) -> io::Result<Self> {
+        match codec {
+            Codec::Gzip => Ok(Self::Gzip(GzEncoder::new(
+                Vec::new(),
+                flate2::Compression::default(),
+            ))),
+            Codec::Zstd => Ok(Self::Zstd(zstd::Encoder::new(
+                Vec::new(),
+                zstd::DEFAULT_COMPRESSION_LEVEL,
+            )?)),
+        }
+    }
+
+    /// Compress `data`, returning the compressed output that's ready so far.
+    ///
+    /// The encoders hold on to data until they have enough to compress, so this is often empty.
+    pub(crate) fn compress(&mut self, data: &[u8]) -> io::Result<Bytes> {
+        let output = match self {
+            Self::Gzip(encoder) => {
+                encoder.write_all(data)?;
+                encoder.get_mut()
+            }
+            Self::Zstd(encoder) => {
+                encoder.write_all(data)?;
+                encoder.get_mut()
+            }
+        };
+        Ok(std::mem::take(output).into())
+    }
+
+    /// Finish the compressed stream, returning the rest of its output.
+    pub(crate) fn finish(self) -> io::Result<Bytes> {
+        let output = match self {
+            Self::Gzip(encoder) => encoder.finish()?,
+            Self::Zstd(encoder) => encoder.finish()?,
+        };
+        Ok(output.into())
+    }
+}
+
+/// The compressed contents of `payload`.
+pub(crate) fn compress_payload(codec: Codec, payload: &PutPayload) -> io::Result<PutPayload> {
+    let mut compressor = Compressor::new(codec)?;
+    let mut chunks = vec![];
+    for chunk in payload {
+        chunks.push(compressor.compress(chunk)?);
+    }
+    chunks.push(compressor.finish()?);
+    chunks.retain(|chunk| !chunk.is_empty());
+    Ok(PutPayload::from_iter(chunks))
+}
+
+/// Set the `Content-Encoding` of an upload compressed with `codec`, unless it was set explicitly.
+///
+/// Returns whether the `Content-Encoding` is the only attribute, so that uploads to stores that
+/// don't support attributes can be retried without it.
+pub(crate) fn set_content_encoding(attributes: &mut Attributes, codec: Codec) -> bool {
+    if attributes.get(&Attribute::ContentEncoding).is_some() {
+        return false;
+    }
+    attributes.insert(Attribute::ContentEncoding, codec.name().into());
+    attributes.len() == 1
+}
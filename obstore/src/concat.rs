@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreResult, PyPath};
+
+use crate::get::{PyBytesStream, DEFAULT_BYTES_CHUNK_SIZE};
+use crate::utils::AbortOnDrop;
+
+#[pyfunction]
+#[pyo3(signature = (store, paths, *, prefetch=1, min_chunk_size=DEFAULT_BYTES_CHUNK_SIZE, timeout=None))]
+pub(crate) fn concat(
+    store: PyObjectStore,
+    paths: Vec<PyPath>,
+    prefetch: usize,
+    min_chunk_size: usize,
+    timeout: Option<Duration>,
+) -> PyObjectStoreResult<PyBytesStream> {
+    if paths.is_empty() {
+        return Err(PyValueError::new_err("Expected at least one path to concatenate.").into());
+    }
+    let store = store.with_timeout(timeout).into_inner();
+    // Each request is spawned, so that the next objects are requested while the body of the
+    // current object is being read. They are aborted if the stream is dropped before reaching them.
+    let stream = futures::stream::iter(paths)
+        .map(move |path| {
+            let store = store.clone();
+            let path = Path::from(path);
+            AbortOnDrop::spawn(async move { store.get(&path).await })
+        })
+        .buffered(prefetch + 1)
+        .map(|joined| {
+            joined.map_err(|err| object_store::Error::Generic {
+                store: "concat",
+                source: Box::new(err),
+            })?
+        })
+        .map_ok(|result| result.into_stream())
+        .try_flatten()
+        .boxed();
+    Ok(PyBytesStream::new(stream, min_chunk_size, None))
+}
@@ -0,0 +1,207 @@
+//! Higher-level conditional writes, built on `PutMode::Create` and `PutMode::Update`.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::future::Either;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutMode, PutOptions, PutResult, UpdateVersion};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3_bytes::PyBytes;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreResult, PyPath};
+
+use crate::attributes::PyAttributes;
+use crate::put::PyPutResult;
+use crate::runtime::get_runtime;
+use crate::tags::PyTagSet;
+
+#[pyfunction]
+#[pyo3(signature = (store, path, data, *, attributes=None, tags=None))]
+pub(crate) fn put_if_absent(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    data: PyBytes,
+    attributes: Option<PyAttributes>,
+    tags: Option<PyTagSet>,
+) -> PyObjectStoreResult<Option<PyPutResult>> {
+    let runtime = get_runtime(py)?;
+    let opts = create_options(attributes, tags);
+    py.allow_threads(|| {
+        runtime.block_on(put_if_absent_inner(
+            store.into_inner(),
+            path.into(),
+            data.into_inner(),
+            opts,
+        ))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, data, *, attributes=None, tags=None))]
+pub(crate) fn put_if_absent_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    data: PyBytes,
+    attributes: Option<PyAttributes>,
+    tags: Option<PyTagSet>,
+) -> PyResult<Bound<PyAny>> {
+    let opts = create_options(attributes, tags);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        Ok(put_if_absent_inner(store.into_inner(), path.into(), data.into_inner(), opts).await?)
+    })
+}
+
+fn create_options(attributes: Option<PyAttributes>, tags: Option<PyTagSet>) -> PutOptions {
+    PutOptions {
+        mode: PutMode::Create,
+        attributes: attributes.map(|x| x.into_inner()).unwrap_or_default(),
+        tags: tags.map(|x| x.into_inner()).unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// Write `data` to `path` only if no object exists there yet, returning `None` if one does.
+async fn put_if_absent_inner(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    data: Bytes,
+    opts: PutOptions,
+) -> PyObjectStoreResult<Option<PyPutResult>> {
+    match store.put_opts(&path, data.into(), opts).await {
+        Ok(result) => Ok(Some(PyPutResult(result))),
+        Err(object_store::Error::AlreadyExists { .. }) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, func, *, max_retries=10))]
+pub(crate) fn update(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    func: PyObject,
+    max_retries: usize,
+) -> PyObjectStoreResult<Option<PyPutResult>> {
+    let runtime = get_runtime(py)?;
+    let store = store.into_inner();
+    let path = Path::from(path);
+    let mut retries = 0;
+    loop {
+        let (current, opts) = py.allow_threads(|| runtime.block_on(read_current(&store, &path)))?;
+        // Call the function outside of the runtime, so that it can make its own requests
+        let new = func.call1(py, (current.map(PyBytes::new),))?;
+        let Some(new) = extract_update(new.bind(py))? else {
+            return Ok(None);
+        };
+        let retry = retries < max_retries;
+        let result = py.allow_threads(|| {
+            runtime.block_on(write_if_unchanged(&store, &path, new, opts, retry))
+        })?;
+        match result {
+            Some(result) => return Ok(Some(PyPutResult(result))),
+            None => retries += 1,
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, func, *, max_retries=10))]
+pub(crate) fn update_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    func: PyObject,
+    max_retries: usize,
+) -> PyResult<Bound<PyAny>> {
+    let store = store.into_inner();
+    let path = Path::from(path);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let mut retries = 0;
+        loop {
+            let (current, opts) = read_current(&store, &path).await?;
+            let Some(new) = call_update_fn_async(&func, current).await? else {
+                return Ok(None);
+            };
+            let retry = retries < max_retries;
+            match write_if_unchanged(&store, &path, new, opts, retry).await? {
+                Some(result) => return Ok(Some(PyPutResult(result))),
+                None => retries += 1,
+            }
+        }
+    })
+}
+
+/// Call the user's update function, which may be either sync or async, with the current contents
+/// of the object.
+async fn call_update_fn_async(func: &PyObject, current: Option<Bytes>) -> PyResult<Option<Bytes>> {
+    let new = Python::with_gil(|py| {
+        let new = func.bind(py).call1((current.map(PyBytes::new),))?;
+        if new.hasattr(intern!(py, "__await__"))? {
+            Ok::<_, PyErr>(Either::Left(pyo3_async_runtimes::tokio::into_future(new)?))
+        } else {
+            Ok(Either::Right(std::future::ready(Ok(new.unbind()))))
+        }
+    })?
+    .await?;
+    Python::with_gil(|py| extract_update(new.bind(py)))
+}
+
+fn extract_update(new: &Bound<PyAny>) -> PyResult<Option<Bytes>> {
+    Ok(new.extract::<Option<PyBytes>>()?.map(|x| x.into_inner()))
+}
+
+/// Read the current contents of `path` for a read-modify-write update.
+///
+/// Returns the contents (`None` if the object doesn't exist) and the options for writing the new
+/// contents only if the object hasn't changed since. The attributes of the existing object are
+/// carried over to the new contents.
+async fn read_current(
+    store: &Arc<dyn ObjectStore>,
+    path: &Path,
+) -> PyObjectStoreResult<(Option<Bytes>, PutOptions)> {
+    match store.get(path).await {
+        Ok(result) => {
+            let opts = PutOptions {
+                mode: PutMode::Update(UpdateVersion {
+                    e_tag: result.meta.e_tag.clone(),
+                    version: result.meta.version.clone(),
+                }),
+                attributes: result.attributes.clone(),
+                ..Default::default()
+            };
+            Ok((Some(result.bytes().await?), opts))
+        }
+        Err(object_store::Error::NotFound { .. }) => {
+            let opts = PutOptions {
+                mode: PutMode::Create,
+                ..Default::default()
+            };
+            Ok((None, opts))
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Write the new contents of an update.
+///
+/// Returns `None` if another writer modified the object since it was read and `retry` is set, in
+/// which case the update should start again from the new version.
+async fn write_if_unchanged(
+    store: &Arc<dyn ObjectStore>,
+    path: &Path,
+    data: Bytes,
+    opts: PutOptions,
+    retry: bool,
+) -> PyObjectStoreResult<Option<PutResult>> {
+    match store.put_opts(path, data.into(), opts).await {
+        Ok(result) => Ok(Some(result)),
+        Err(
+            object_store::Error::Precondition { .. } | object_store::Error::AlreadyExists { .. },
+        ) if retry => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
@@ -1,51 +1,419 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
+use http::Method;
+use object_store::aws::AmazonS3;
+use object_store::client::HttpClient;
+use object_store::path::Path;
 use object_store::ObjectStore;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+use pyo3_object_store::{
+    resolve_store_token, DeadlineExceeded, LimitedStore, MaybePrefixedStore, PyObjectStore,
+    PyObjectStoreError, PyObjectStoreResult, PyPath, PyS3Store,
+};
+use tokio::time::Instant;
 
+use crate::metadata::{generic_error, unescape_xml, xml_element};
+use crate::requests;
 use crate::runtime::get_runtime;
 use crate::utils::PyNone;
 
 #[pyfunction]
-#[pyo3(signature = (store, from_, to, *, overwrite=true))]
+#[pyo3(signature = (store, from_, to, *, overwrite=true, multipart_threshold=None, part_size=MULTIPART_COPY_PART_SIZE, max_concurrency=MULTIPART_COPY_CONCURRENCY, timeout=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn copy(
     py: Python,
-    store: PyObjectStore,
-    from_: String,
-    to: String,
+    store: PyCopyStore,
+    from_: PyPath,
+    to: PyPath,
     overwrite: bool,
+    multipart_threshold: Option<u64>,
+    part_size: u64,
+    max_concurrency: usize,
+    timeout: Option<Duration>,
 ) -> PyObjectStoreResult<()> {
     let runtime = get_runtime(py)?;
+    let options = MultipartCopyOptions::new(multipart_threshold, part_size, max_concurrency)?;
+    let deadline = deadline(timeout);
+    let PyCopyStore { store, s3 } = store;
+    let store = store.with_timeout(timeout).into_inner();
     let from_ = from_.into();
     let to = to.into();
     py.allow_threads(|| {
-        let fut = if overwrite {
-            store.as_ref().copy(&from_, &to)
-        } else {
-            store.as_ref().copy_if_not_exists(&from_, &to)
-        };
-        runtime.block_on(fut)?;
+        runtime.block_on(copy_inner(
+            &store,
+            s3.as_ref(),
+            &from_,
+            &to,
+            overwrite,
+            options,
+            deadline,
+        ))?;
         Ok::<_, PyObjectStoreError>(())
     })
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, from_, to, *, overwrite=true))]
+#[pyo3(signature = (store, from_, to, *, overwrite=true, multipart_threshold=None, part_size=MULTIPART_COPY_PART_SIZE, max_concurrency=MULTIPART_COPY_CONCURRENCY, timeout=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn copy_async(
     py: Python,
-    store: PyObjectStore,
-    from_: String,
-    to: String,
+    store: PyCopyStore,
+    from_: PyPath,
+    to: PyPath,
     overwrite: bool,
+    multipart_threshold: Option<u64>,
+    part_size: u64,
+    max_concurrency: usize,
+    timeout: Option<Duration>,
 ) -> PyResult<Bound<PyAny>> {
+    let options = MultipartCopyOptions::new(multipart_threshold, part_size, max_concurrency)?;
+    let deadline = deadline(timeout);
+    let PyCopyStore { store, s3 } = store;
+    let store = store.with_timeout(timeout).into_inner();
     let from_ = from_.into();
     let to = to.into();
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let fut = if overwrite {
-            store.as_ref().copy(&from_, &to)
-        } else {
-            store.as_ref().copy_if_not_exists(&from_, &to)
-        };
-        fut.await.map_err(PyObjectStoreError::ObjectStoreError)?;
+        copy_inner(
+            &store,
+            s3.as_ref(),
+            &from_,
+            &to,
+            overwrite,
+            options,
+            deadline,
+        )
+        .await
+        .map_err(PyObjectStoreError::ObjectStoreError)?;
         Ok(PyNone)
     })
 }
+
+/// The instant at which a call with `timeout` fails.
+pub(crate) fn deadline(timeout: Option<Duration>) -> Option<Instant> {
+    timeout.and_then(|timeout| Instant::now().checked_add(timeout))
+}
+
+/// Copy `from` to `to`.
+///
+/// With an S3 store, the object is copied in parts if it's larger than the threshold of
+/// `options`, or if S3 rejects it as too large to copy with a single `CopyObject` request.
+/// `store` is expected to already be limited to `deadline`, which then also applies to the
+/// requests of a multipart copy.
+pub(crate) async fn copy_inner(
+    store: &Arc<dyn ObjectStore>,
+    s3: Option<&S3MultipartCopy>,
+    from: &Path,
+    to: &Path,
+    overwrite: bool,
+    options: MultipartCopyOptions,
+    deadline: Option<Instant>,
+) -> object_store::Result<()> {
+    let single_copy = || async move {
+        if overwrite {
+            store.copy(from, to).await
+        } else {
+            store.copy_if_not_exists(from, to).await
+        }
+    };
+    let Some(s3) = s3 else {
+        return single_copy().await;
+    };
+    let size = match options.threshold {
+        Some(threshold) => Some(store.head(from).await?.size).filter(|size| *size > threshold),
+        None => None,
+    };
+    let size = match size {
+        Some(size) => size,
+        None => match single_copy().await {
+            Err(err) if is_too_large_to_copy(&err) => store.head(from).await?.size,
+            result => return result,
+        },
+    };
+    let copy = s3.copy(from, to, size, overwrite, options, &[]);
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, copy).await.map_err(|_| {
+            object_store::Error::Generic {
+                store: "S3",
+                source: Box::new(DeadlineExceeded::new("multipart copy")),
+            }
+        })?,
+        None => copy.await,
+    }
+}
+
+/// Whether `err` is S3 rejecting an object as too large for `CopyObject`.
+pub(crate) fn is_too_large_to_copy(err: &object_store::Error) -> bool {
+    let mut source: Option<&dyn std::error::Error> = Some(err);
+    while let Some(err) = source {
+        if err
+            .to_string()
+            .contains("maximum allowable size for a copy source")
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Default size of each part of a multipart copy
+const MULTIPART_COPY_PART_SIZE: u64 = 512 * 1024 * 1024;
+
+/// Default number of parts that may be copied concurrently in a multipart copy
+const MULTIPART_COPY_CONCURRENCY: usize = 8;
+
+/// S3 allows at most 10,000 parts in a multipart upload
+const S3_MAX_PARTS: u64 = 10_000;
+
+/// Every part but the last must be at least 5 MiB
+const S3_MIN_PART_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Parts may be at most 5 GiB
+const S3_MAX_PART_SIZE: u64 = 5 * 1024 * 1024 * 1024;
+
+/// The characters of a key that are percent-encoded in the `x-amz-copy-source` header
+pub(crate) const COPY_SOURCE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// How objects are copied in parts by [`S3MultipartCopy`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct MultipartCopyOptions {
+    /// Objects larger than this are always copied in parts
+    threshold: Option<u64>,
+    part_size: u64,
+    max_concurrency: usize,
+}
+
+impl MultipartCopyOptions {
+    pub(crate) fn new(
+        threshold: Option<u64>,
+        part_size: u64,
+        max_concurrency: usize,
+    ) -> PyResult<Self> {
+        if !(S3_MIN_PART_SIZE..=S3_MAX_PART_SIZE).contains(&part_size) {
+            return Err(PyValueError::new_err(format!(
+                "part_size must be between {} and {} bytes, got {}",
+                S3_MIN_PART_SIZE, S3_MAX_PART_SIZE, part_size
+            )));
+        }
+        if max_concurrency == 0 {
+            return Err(PyValueError::new_err("max_concurrency must be at least 1"));
+        }
+        Ok(Self {
+            threshold,
+            part_size,
+            max_concurrency,
+        })
+    }
+
+    /// The options for objects larger than `threshold`, with the default part size and
+    /// concurrency.
+    pub(crate) fn with_threshold(threshold: Option<u64>) -> Self {
+        Self {
+            threshold,
+            part_size: MULTIPART_COPY_PART_SIZE,
+            max_concurrency: MULTIPART_COPY_CONCURRENCY,
+        }
+    }
+}
+
+/// A store to copy objects within, which can also copy large objects in parts if it's an
+/// [`S3Store`][PyS3Store].
+pub(crate) struct PyCopyStore {
+    pub(crate) store: PyObjectStore,
+    pub(crate) s3: Option<S3MultipartCopy>,
+}
+
+impl<'py> FromPyObject<'py> for PyCopyStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        let s3 = ob
+            .downcast::<PyS3Store>()
+            .ok()
+            .map(|store| S3MultipartCopy::new(store.get()));
+        Ok(Self {
+            store: ob.extract()?,
+            s3,
+        })
+    }
+}
+
+/// Copies objects within an S3 bucket with `UploadPartCopy`, which unlike `CopyObject` isn't
+/// limited to objects of up to 5 GiB.
+///
+/// `object_store` doesn't expose these requests, so we make them ourselves with
+/// [`requests::s3_request`].
+pub(crate) struct S3MultipartCopy {
+    store: Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>,
+    bucket: String,
+}
+
+impl S3MultipartCopy {
+    pub(crate) fn new(store: &PyS3Store) -> Self {
+        Self {
+            store: store.as_ref().clone(),
+            bucket: store.bucket().to_string(),
+        }
+    }
+
+    fn s3(&self) -> &AmazonS3 {
+        self.store.inner().inner()
+    }
+
+    /// Copy the object at `from`, of `size` bytes, to `to`, failing if `to` exists and
+    /// `overwrite` is `false`.
+    ///
+    /// `headers` are sent when creating the upload, such as to set the attributes and tags of
+    /// the copy, which are otherwise those of the bucket's defaults rather than of `from`.
+    pub(crate) async fn copy(
+        &self,
+        from: &Path,
+        to: &Path,
+        size: u64,
+        overwrite: bool,
+        options: MultipartCopyOptions,
+        headers: &[(&str, String)],
+    ) -> object_store::Result<()> {
+        let from = self.store.inner().full_path(from);
+        let to = self.store.inner().full_path(to);
+        let client = requests::client()?;
+
+        let body = requests::s3_request(
+            self.s3(),
+            &client,
+            Method::POST,
+            &to,
+            &[("uploads", "")],
+            headers,
+            Bytes::new(),
+        )
+        .await?;
+        let upload_id = xml_element(&body, "UploadId")
+            .map(unescape_xml)
+            .ok_or_else(|| generic_error("CreateMultipartUpload response has no UploadId"))?;
+
+        let copied = async {
+            let e_tags = self
+                .copy_parts(&client, &from, &to, &upload_id, size, options)
+                .await?;
+            self.complete(&client, &to, &upload_id, &e_tags, overwrite)
+                .await
+        }
+        .await;
+        if copied.is_err() {
+            // Don't leave the parts copied so far in the bucket. The error of the copy is more
+            // useful than that of the abort, so the latter is ignored.
+            let _ = requests::s3_request(
+                self.s3(),
+                &client,
+                Method::DELETE,
+                &to,
+                &[("uploadId", upload_id.as_str())],
+                &[],
+                Bytes::new(),
+            )
+            .await;
+        }
+        copied
+    }
+
+    /// Copy each part of the object with `UploadPartCopy`, returning the `ETag`s of the parts.
+    async fn copy_parts(
+        &self,
+        client: &HttpClient,
+        from: &Path,
+        to: &Path,
+        upload_id: &str,
+        size: u64,
+        options: MultipartCopyOptions,
+    ) -> object_store::Result<Vec<String>> {
+        let copy_source = format!(
+            "/{}/{}",
+            self.bucket,
+            utf8_percent_encode(from.as_ref(), COPY_SOURCE)
+        );
+        // Parts are made larger if needed to fit the object in the maximum number of parts
+        let part_size = options.part_size.max(size.div_ceil(S3_MAX_PARTS));
+        let parts = (0..size).step_by(part_size as usize).enumerate();
+        futures::stream::iter(parts)
+            .map(|(idx, start)| {
+                let part_number = (idx + 1).to_string();
+                let end = (start + part_size).min(size) - 1;
+                let headers = [
+                    ("x-amz-copy-source", copy_source.clone()),
+                    (
+                        "x-amz-copy-source-range",
+                        format!("bytes={}-{}", start, end),
+                    ),
+                ];
+                async move {
+                    let query = [
+                        ("partNumber", part_number.as_str()),
+                        ("uploadId", upload_id),
+                    ];
+                    let body = requests::s3_request(
+                        self.s3(),
+                        client,
+                        Method::PUT,
+                        to,
+                        &query,
+                        &headers,
+                        Bytes::new(),
+                    )
+                    .await?;
+                    // The ETag is kept escaped, as it's sent back in the XML body of
+                    // CompleteMultipartUpload
+                    xml_element(&body, "ETag")
+                        .map(String::from)
+                        .ok_or_else(|| generic_error("UploadPartCopy response has no ETag"))
+                }
+            })
+            .buffered(options.max_concurrency)
+            .try_collect()
+            .await
+    }
+
+    async fn complete(
+        &self,
+        client: &HttpClient,
+        to: &Path,
+        upload_id: &str,
+        e_tags: &[String],
+        overwrite: bool,
+    ) -> object_store::Result<()> {
+        let mut body = String::from("<CompleteMultipartUpload>");
+        for (idx, e_tag) in e_tags.iter().enumerate() {
+            body.push_str(&format!(
+                "<Part><ETag>{}</ETag><PartNumber>{}</PartNumber></Part>",
+                e_tag,
+                idx + 1
+            ));
+        }
+        body.push_str("</CompleteMultipartUpload>");
+        let headers = if overwrite {
+            vec![]
+        } else {
+            vec![("if-none-match", "*".to_string())]
+        };
+        requests::s3_request(
+            self.s3(),
+            client,
+            Method::POST,
+            to,
+            &[("uploadId", upload_id)],
+            &headers,
+            Bytes::from(body),
+        )
+        .await?;
+        Ok(())
+    }
+}
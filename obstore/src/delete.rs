@@ -1,15 +1,77 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::ObjectStore;
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+use pyo3_object_store::{
+    resolve_store_token, DeadlineExceeded, PyAzureStore, PyObjectStore, PyObjectStoreError,
+    PyObjectStoreResult, PyPath,
+};
+use tokio::time::Instant;
 
+use crate::copy::deadline;
+use crate::dfs::AzureDfs;
 use crate::path::PyPaths;
 use crate::runtime::get_runtime;
 use crate::utils::PyNone;
+use crate::versions::{with_deadline, VersionedStore};
+
+/// A store to delete objects from, which can also delete a single version of an object if its
+/// backend keeps versions.
+pub(crate) struct PyDeleteStore {
+    store: PyObjectStore,
+    ob: PyObject,
+}
+
+impl<'py> FromPyObject<'py> for PyDeleteStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        Ok(Self {
+            store: ob.extract()?,
+            ob: ob.clone().unbind(),
+        })
+    }
+}
+
+impl PyDeleteStore {
+    /// The store and path to delete a version of the object at `paths` from.
+    ///
+    /// Raises a `ValueError` if the backend of the store doesn't keep versions, or if `paths`
+    /// isn't a single path.
+    fn into_versioned(self, py: Python, paths: PyPaths) -> PyResult<(VersionedStore, Path)> {
+        let PyPaths::One(path) = paths else {
+            return Err(PyValueError::new_err(
+                "A version can only be deleted from a single path.",
+            ));
+        };
+        Ok((self.ob.extract(py)?, path))
+    }
+}
 
 #[pyfunction]
-pub(crate) fn delete(py: Python, store: PyObjectStore, paths: PyPaths) -> PyObjectStoreResult<()> {
+#[pyo3(signature = (store, paths, *, version=None, timeout=None))]
+pub(crate) fn delete(
+    py: Python,
+    store: PyDeleteStore,
+    paths: PyPaths,
+    version: Option<String>,
+    timeout: Option<Duration>,
+) -> PyObjectStoreResult<()> {
     let runtime = get_runtime(py)?;
-    let store = store.into_inner();
+    if let Some(version) = version {
+        let (store, path) = store.into_versioned(py, paths)?;
+        return py.allow_threads(|| {
+            runtime.block_on(with_deadline(
+                deadline(timeout),
+                store.delete_version(&path, &version),
+            ))?;
+            Ok::<_, PyObjectStoreError>(())
+        });
+    }
+    let store = store.store.with_timeout(timeout).into_inner();
     py.allow_threads(|| {
         match paths {
             PyPaths::One(path) => {
@@ -27,12 +89,25 @@ pub(crate) fn delete(py: Python, store: PyObjectStore, paths: PyPaths) -> PyObje
 }
 
 #[pyfunction]
+#[pyo3(signature = (store, paths, *, version=None, timeout=None))]
 pub(crate) fn delete_async(
     py: Python,
-    store: PyObjectStore,
+    store: PyDeleteStore,
     paths: PyPaths,
+    version: Option<String>,
+    timeout: Option<Duration>,
 ) -> PyResult<Bound<PyAny>> {
-    let store = store.into_inner();
+    if let Some(version) = version {
+        let (store, path) = store.into_versioned(py, paths)?;
+        let deadline = deadline(timeout);
+        return pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            with_deadline(deadline, store.delete_version(&path, &version))
+                .await
+                .map_err(PyObjectStoreError::ObjectStoreError)?;
+            Ok(PyNone)
+        });
+    }
+    let store = store.store.with_timeout(timeout).into_inner();
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
         match paths {
             PyPaths::One(path) => {
@@ -54,3 +129,103 @@ pub(crate) fn delete_async(
         Ok(PyNone)
     })
 }
+
+/// A store to delete everything under a prefix from, which for Azure can delete the directory
+/// of an account with a hierarchical namespace in a single request.
+pub(crate) struct PyDeletePrefixStore {
+    store: PyObjectStore,
+    azure: Option<AzureDfs>,
+}
+
+impl<'py> FromPyObject<'py> for PyDeletePrefixStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        let store: PyObjectStore = ob.extract()?;
+        let azure = match ob.downcast::<PyAzureStore>() {
+            // DFS requests would bypass the layer set around the store, so the objects of a
+            // layered store are listed and deleted through the layer instead
+            Ok(_) if store.is_layered() => None,
+            // Stores that we can't make requests for are listed and deleted like any other
+            Ok(azure) => AzureDfs::new(azure.get()).ok(),
+            Err(_) => None,
+        };
+        Ok(Self { store, azure })
+    }
+}
+
+/// Delete every object under `prefix`, failing if that hasn't completed by `deadline`.
+async fn delete_prefix_inner(
+    store: Arc<dyn ObjectStore>,
+    azure: Option<AzureDfs>,
+    prefix: Path,
+    deadline: Option<Instant>,
+) -> object_store::Result<()> {
+    let fut = async {
+        if let Some(azure) = azure {
+            if !prefix.as_ref().is_empty() && azure.is_hierarchical().await {
+                return azure.delete_directory(&prefix).await;
+            }
+        }
+        let locations = store
+            .list(Some(&prefix))
+            .map_ok(|meta| meta.location)
+            .boxed();
+        store
+            .delete_stream(locations)
+            .try_collect::<Vec<_>>()
+            .await?;
+        Ok(())
+    };
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, fut).await.map_err(|_| {
+            object_store::Error::Generic {
+                store: "delete_prefix",
+                source: Box::new(DeadlineExceeded::new("delete_prefix")),
+            }
+        })?,
+        None => fut.await,
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix, *, timeout=None))]
+pub(crate) fn delete_prefix(
+    py: Python,
+    store: PyDeletePrefixStore,
+    prefix: PyPath,
+    timeout: Option<Duration>,
+) -> PyObjectStoreResult<()> {
+    let runtime = get_runtime(py)?;
+    let deadline = deadline(timeout);
+    py.allow_threads(|| {
+        runtime.block_on(delete_prefix_inner(
+            store.store.into_inner(),
+            store.azure,
+            prefix.into(),
+            deadline,
+        ))?;
+        Ok(())
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix, *, timeout=None))]
+pub(crate) fn delete_prefix_async(
+    py: Python,
+    store: PyDeletePrefixStore,
+    prefix: PyPath,
+    timeout: Option<Duration>,
+) -> PyResult<Bound<PyAny>> {
+    let deadline = deadline(timeout);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        delete_prefix_inner(
+            store.store.into_inner(),
+            store.azure,
+            prefix.into(),
+            deadline,
+        )
+        .await
+        .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(PyNone)
+    })
+}
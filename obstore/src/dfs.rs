@@ -0,0 +1,210 @@
+//! Requests to the Data Lake Storage (DFS) endpoint of Azure storage accounts with a hierarchical
+//! namespace, which `object_store` doesn't make.
+//!
+//! With a hierarchical namespace, directories are real, and a directory can be deleted with all
+//! of its contents by a single recursive Delete Path request, instead of listing and deleting
+//! each blob under it.
+//!
+//! See <https://learn.microsoft.com/en-us/rest/api/storageservices/datalakestoragegen2/path/delete>.
+
+use std::sync::Arc;
+
+use http::{Method, StatusCode};
+use object_store::azure::{AzureAuthorizer, MicrosoftAzure};
+use object_store::client::{HttpClient, HttpRequestBody};
+use object_store::path::Path;
+use pyo3_object_store::{LimitedStore, MaybePrefixedStore, PyAzureStore};
+use url::Url;
+
+/// The name of the store in errors.
+const STORE: &str = "MicrosoftAzure";
+
+/// The header of a Delete Path response that is set when the delete must be continued with
+/// another request.
+const CONTINUATION_HEADER: &str = "x-ms-continuation";
+
+/// The header of a Get Properties response with the type of the resource at a path.
+const RESOURCE_TYPE_HEADER: &str = "x-ms-resource-type";
+
+/// The header of a Get Account Information response that tells whether the account has a
+/// hierarchical namespace.
+const HNS_ENABLED_HEADER: &str = "x-ms-is-hns-enabled";
+
+/// An Azure store, with what's needed to make requests to its DFS endpoint.
+#[derive(Debug)]
+pub(crate) struct AzureDfs {
+    store: Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>,
+    client: HttpClient,
+    container_url: Url,
+    account: String,
+}
+
+impl AzureDfs {
+    pub(crate) fn new(store: &PyAzureStore) -> object_store::Result<Self> {
+        let (container_url, account) = store
+            .container_url()
+            .map_err(|err| dfs_error(err.to_string()))?;
+        Ok(Self {
+            store: store.as_ref().clone(),
+            client: store.http_client()?,
+            container_url,
+            account,
+        })
+    }
+
+    fn azure(&self) -> &MicrosoftAzure {
+        self.store.inner().inner()
+    }
+
+    async fn execute(
+        &self,
+        method: Method,
+        url: &Url,
+    ) -> object_store::Result<object_store::client::HttpResponse> {
+        let credential = self.azure().credentials().get_credential().await?;
+        let mut request = http::Request::builder()
+            .method(method)
+            .uri(url.as_str())
+            .body(HttpRequestBody::empty())
+            .map_err(|err| dfs_error(err.to_string()))?;
+        AzureAuthorizer::new(&credential, &self.account).authorize(&mut request);
+        self.client
+            .execute(request)
+            .await
+            .map_err(|err| object_store::Error::Generic {
+                store: STORE,
+                source: Box::new(err),
+            })
+    }
+
+    /// Whether the account has a hierarchical namespace, from a Get Account Information request.
+    ///
+    /// The emulator and custom endpoints have no known DFS endpoint, and credentials scoped to
+    /// the container may not be allowed to make this request, so these are all treated as not
+    /// having one.
+    pub(crate) async fn is_hierarchical(&self) -> bool {
+        if self.dfs_url().is_none() {
+            return false;
+        }
+        let mut url = self.container_url.clone();
+        url.query_pairs_mut()
+            .append_pair("restype", "account")
+            .append_pair("comp", "properties");
+        let Ok(response) = self.execute(Method::GET, &url).await else {
+            return false;
+        };
+        response.status().is_success()
+            && response
+                .headers()
+                .get(HNS_ENABLED_HEADER)
+                .is_some_and(|value| value.as_bytes().eq_ignore_ascii_case(b"true"))
+    }
+
+    /// The URL of the container on the DFS endpoint, if the store uses a known blob endpoint.
+    fn dfs_url(&self) -> Option<Url> {
+        let host = self.container_url.host_str()?;
+        let (account, domain) = host.split_once(".blob.")?;
+        if !matches!(domain, "core.windows.net" | "fabric.microsoft.com") {
+            return None;
+        }
+        let mut url = self.container_url.clone();
+        url.set_host(Some(&format!("{}.dfs.{}", account, domain)))
+            .ok()?;
+        Some(url)
+    }
+
+    /// Delete the directory at `prefix` with all of its contents, with recursive Delete Path
+    /// requests.
+    ///
+    /// Nothing is deleted if there's nothing at `prefix`. It's an error for there to be a file
+    /// at `prefix`, rather than a directory, and the root of the container can't be deleted this
+    /// way.
+    pub(crate) async fn delete_directory(&self, prefix: &Path) -> object_store::Result<()> {
+        let Some(mut url) = self.dfs_url() else {
+            return Err(dfs_error(
+                "The store has no known Data Lake Storage endpoint",
+            ));
+        };
+        let path = self.store.inner().full_path(prefix).into_owned();
+        if path.as_ref().is_empty() {
+            return Err(dfs_error(
+                "The root of the container can't be deleted as a directory",
+            ));
+        }
+        url.path_segments_mut()
+            .map_err(|_| dfs_error("Container URL cannot be a base"))?
+            .extend(path.parts());
+
+        let response = self.execute(Method::HEAD, &url).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(());
+        }
+        if !response.status().is_success() {
+            return Err(request_failed("Get Properties", response).await);
+        }
+        let is_directory = response
+            .headers()
+            .get(RESOURCE_TYPE_HEADER)
+            .is_some_and(|value| value.as_bytes() == b"directory");
+        if !is_directory {
+            return Err(dfs_error(format!(
+                "Can't delete '{}' as a directory, as it is a file",
+                prefix
+            )));
+        }
+
+        let mut continuation: Option<String> = None;
+        loop {
+            let mut url = url.clone();
+            {
+                let mut query = url.query_pairs_mut();
+                query.append_pair("recursive", "true");
+                if let Some(continuation) = &continuation {
+                    query.append_pair("continuation", continuation);
+                }
+            }
+            let response = self.execute(Method::DELETE, &url).await?;
+            let status = response.status();
+            // Directories that are deleted with too many entries for one request, or with ACLs
+            // checked on every entry, are deleted in batches
+            continuation = response
+                .headers()
+                .get(CONTINUATION_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| !value.is_empty())
+                .map(String::from);
+            if status == StatusCode::NOT_FOUND {
+                return Ok(());
+            }
+            if !status.is_success() {
+                return Err(request_failed("Delete Path", response).await);
+            }
+            if continuation.is_none() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// The error of an unsuccessful response to a `request` request.
+async fn request_failed(
+    request: &str,
+    response: object_store::client::HttpResponse,
+) -> object_store::Error {
+    let status = response.status();
+    let body = match response.into_body().bytes().await {
+        Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+        Err(err) => err.to_string(),
+    };
+    dfs_error(format!(
+        "{} request failed with status {}: {}",
+        request, status, body
+    ))
+}
+
+fn dfs_error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: msg.into().into(),
+    }
+}
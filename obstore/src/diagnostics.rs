@@ -0,0 +1,237 @@
+//! Diagnostics for support triage: the versions obstore was built with and the known issues that
+//! affect a store's configuration.
+
+use std::collections::HashMap;
+
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3_object_store::{resolve_store_token, PyAzureStore, PyGCSStore, PyHttpStore, PyS3Store};
+
+use crate::{OBJECT_STORE_SOURCE, OBJECT_STORE_VERSION, VERSION};
+
+/// The configuration of a store that known issues are matched against.
+struct StoreInfo {
+    name: &'static str,
+    config: HashMap<String, String>,
+    client_options: HashMap<String, String>,
+}
+
+impl StoreInfo {
+    fn from_store(store: &Bound<PyAny>) -> PyResult<Self> {
+        let store = &resolve_store_token(store)?;
+        let py = store.py();
+        let name = if store.downcast::<PyS3Store>().is_ok() {
+            "S3Store"
+        } else if store.downcast::<PyGCSStore>().is_ok() {
+            "GCSStore"
+        } else if store.downcast::<PyAzureStore>().is_ok() {
+            "AzureStore"
+        } else if store.downcast::<PyHttpStore>().is_ok() {
+            "HTTPStore"
+        } else {
+            "other"
+        };
+        let mut config = match name {
+            "S3Store" | "GCSStore" | "AzureStore" => {
+                to_strings(&store.getattr(intern!(py, "config"))?)?
+            }
+            _ => HashMap::new(),
+        };
+        // The endpoint of an HTTP store is its URL
+        if name == "HTTPStore" {
+            let url = store.getattr(intern!(py, "url"))?.str()?.to_string();
+            config.insert("endpoint".to_string(), url);
+        }
+        let client_options = match name {
+            "other" => HashMap::new(),
+            _ => to_strings(&store.getattr(intern!(py, "client_options"))?)?,
+        };
+        Ok(Self {
+            name,
+            config,
+            client_options,
+        })
+    }
+
+    fn is_true(values: &HashMap<String, String>, key: &str) -> bool {
+        values
+            .get(key)
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    /// The configured endpoint, if any.
+    fn endpoint(&self) -> Option<url::Url> {
+        self.config
+            .get("endpoint")
+            .and_then(|endpoint| url::Url::parse(endpoint).ok())
+    }
+}
+
+/// The values of a config dict, converted to strings. `None` gives an empty map.
+fn to_strings(ob: &Bound<PyAny>) -> PyResult<HashMap<String, String>> {
+    let Some(dict) = ob.extract::<Option<HashMap<String, Bound<PyAny>>>>()? else {
+        return Ok(HashMap::new());
+    };
+    dict.into_iter()
+        .map(|(key, value)| Ok((key, value.str()?.to_string())))
+        .collect()
+}
+
+/// A known issue, affecting the versions of `object_store` in `[min_version, max_version)` when
+/// used with a store for which `applies` returns `true`.
+///
+/// `applies` is called with `None` when no store is given, in which case only the issues that
+/// affect every remote store are reported.
+struct KnownIssue {
+    id: &'static str,
+    min_version: (u64, u64, u64),
+    max_version: Option<(u64, u64, u64)>,
+    applies: fn(Option<&StoreInfo>) -> bool,
+    description: &'static str,
+    workaround: &'static str,
+}
+
+const KNOWN_ISSUES: &[KnownIssue] = &[
+    KnownIssue {
+        id: "body-errors-not-retried",
+        min_version: (0, 0, 0),
+        max_version: Some((0, 12, 2)),
+        applies: is_remote,
+        description: "Connection errors, and connections closed while reading a response body, \
+            are not retried.",
+        workaround: "Read large objects in smaller ranges with `get_ranges`, retrying the \
+            ranges that fail.",
+    },
+    KnownIssue {
+        id: "throttling-not-retried",
+        min_version: (0, 0, 0),
+        max_version: Some((0, 12, 3)),
+        applies: is_remote,
+        description: "Responses with status 429 (Too Many Requests) are not retried.",
+        workaround: "Set `throttle_backoff` in `retry_config` so that obstore retries \
+            throttled requests itself.",
+    },
+    KnownIssue {
+        id: "http-not-allowed",
+        min_version: (0, 0, 0),
+        max_version: None,
+        applies: |store| {
+            let Some(store) = store else { return false };
+            store
+                .endpoint()
+                .is_some_and(|endpoint| endpoint.scheme() == "http")
+                && !StoreInfo::is_true(&store.config, "use_emulator")
+                && !StoreInfo::is_true(&store.client_options, "allow_http")
+        },
+        description: "The endpoint uses plain HTTP, which is rejected unless explicitly \
+            allowed. This is common with local emulators such as MinIO or LocalStack.",
+        workaround: "Pass `client_options={\"allow_http\": True}`.",
+    },
+    KnownIssue {
+        id: "virtual-hosted-local-endpoint",
+        min_version: (0, 0, 0),
+        max_version: None,
+        applies: |store| {
+            let Some(store) = store else { return false };
+            store.name == "S3Store"
+                && StoreInfo::is_true(&store.config, "virtual_hosted_style_request")
+                && store.endpoint().is_some_and(|endpoint| {
+                    matches!(
+                        endpoint.host(),
+                        Some(url::Host::Ipv4(_) | url::Host::Ipv6(_))
+                    ) || endpoint.host_str() == Some("localhost")
+                })
+        },
+        description: "Virtual hosted-style requests prefix the endpoint's host with the \
+            bucket name, which doesn't resolve for an IP address or `localhost`.",
+        workaround: "Pass `virtual_hosted_style_request=False`, or a `compatibility` preset \
+            such as `\"minio\"`.",
+    },
+    KnownIssue {
+        id: "r2-tagging",
+        min_version: (0, 0, 0),
+        max_version: None,
+        applies: |store| {
+            let Some(store) = store else { return false };
+            store.name == "S3Store"
+                && !StoreInfo::is_true(&store.config, "disable_tagging")
+                && store.endpoint().is_some_and(|endpoint| {
+                    endpoint
+                        .host_str()
+                        .is_some_and(|host| host.ends_with(".r2.cloudflarestorage.com"))
+                })
+        },
+        description: "Cloudflare R2 doesn't support object tagging, so requests that set tags \
+            are rejected.",
+        workaround: "Pass `disable_tagging=True`.",
+    },
+];
+
+fn is_remote(store: Option<&StoreInfo>) -> bool {
+    store.map_or(true, |store| store.name != "other")
+}
+
+/// Parse the `major.minor.patch` prefix of a version, ignoring any pre-release or build suffix.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.split(['-', '+']).next()?;
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+#[derive(IntoPyObject)]
+pub(crate) struct PyDiagnosticIssue {
+    #[pyo3(item)]
+    id: &'static str,
+    #[pyo3(item)]
+    description: &'static str,
+    #[pyo3(item)]
+    workaround: &'static str,
+}
+
+#[derive(IntoPyObject)]
+pub(crate) struct PyDiagnostics {
+    #[pyo3(item)]
+    version: &'static str,
+    #[pyo3(item)]
+    object_store_version: &'static str,
+    #[pyo3(item)]
+    object_store_source: Option<&'static str>,
+    #[pyo3(item)]
+    store: Option<&'static str>,
+    #[pyo3(item)]
+    issues: Vec<PyDiagnosticIssue>,
+}
+
+#[pyfunction]
+#[pyo3(signature = (store=None))]
+pub(crate) fn diagnostics(store: Option<&Bound<PyAny>>) -> PyResult<PyDiagnostics> {
+    let info = store.map(StoreInfo::from_store).transpose()?;
+    let version = parse_version(OBJECT_STORE_VERSION);
+    let issues = KNOWN_ISSUES
+        .iter()
+        .filter(|issue| {
+            // If the version can't be parsed, only report issues affecting all versions
+            let in_range = match version {
+                Some(version) => {
+                    version >= issue.min_version
+                        && issue.max_version.map_or(true, |max| version < max)
+                }
+                None => issue.min_version == (0, 0, 0) && issue.max_version.is_none(),
+            };
+            in_range && (issue.applies)(info.as_ref())
+        })
+        .map(|issue| PyDiagnosticIssue {
+            id: issue.id,
+            description: issue.description,
+            workaround: issue.workaround,
+        })
+        .collect();
+    Ok(PyDiagnostics {
+        version: VERSION,
+        object_store_version: OBJECT_STORE_VERSION,
+        // Empty for a path dependency, such as a vendored copy of object_store
+        object_store_source: Some(OBJECT_STORE_SOURCE).filter(|source| !source.is_empty()),
+        store: info.map(|info| info.name),
+        issues,
+    })
+}
@@ -0,0 +1,365 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use arrow::array::{
+    ArrayRef, RecordBatch, StringBuilder, TimestampMicrosecondBuilder, UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath};
+
+use crate::list::{check_arrow, PyObjectMeta, PyTableWrapper};
+use crate::runtime::get_runtime;
+
+/// How an object differs between the two listings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Change {
+    /// Only in the second listing
+    Added,
+    /// Only in the first listing
+    Removed,
+    /// In both listings, with a different size or ETag
+    Changed,
+}
+
+impl Change {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Changed => "changed",
+        }
+    }
+}
+
+/// An object that differs between the two listings.
+pub(crate) struct PyDiffEntry {
+    /// The path of the object relative to the prefix of each listing
+    path: String,
+    change: Change,
+    a: Option<ObjectMeta>,
+    b: Option<ObjectMeta>,
+}
+
+impl<'py> IntoPyObject<'py> for PyDiffEntry {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(4);
+        dict.insert("path", self.path.into_bound_py_any(py)?);
+        dict.insert("change", self.change.as_str().into_bound_py_any(py)?);
+        dict.insert("a", self.a.map(PyObjectMeta::new).into_bound_py_any(py)?);
+        dict.insert("b", self.b.map(PyObjectMeta::new).into_bound_py_any(py)?);
+        dict.into_pyobject(py)
+    }
+}
+
+#[derive(IntoPyObject)]
+pub(crate) enum PyDiffResult {
+    Arrow(PyTableWrapper),
+    Native(Vec<PyDiffEntry>),
+}
+
+impl PyDiffResult {
+    fn new(entries: Vec<PyDiffEntry>, return_arrow: bool) -> PyResult<Self> {
+        if return_arrow {
+            let batch = diff_to_batch(&entries);
+            Ok(Self::Arrow(PyTableWrapper::new(
+                vec![batch],
+                diff_schema(),
+            )?))
+        } else {
+            Ok(Self::Native(entries))
+        }
+    }
+}
+
+fn diff_schema() -> SchemaRef {
+    let timestamp = DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into()));
+    Arc::new(Schema::new(vec![
+        Field::new("path", DataType::Utf8, false),
+        Field::new("change", DataType::Utf8, false),
+        Field::new("size_a", DataType::UInt64, true),
+        Field::new("size_b", DataType::UInt64, true),
+        Field::new("last_modified_a", timestamp.clone(), true),
+        Field::new("last_modified_b", timestamp, true),
+        Field::new("e_tag_a", DataType::Utf8, true),
+        Field::new("e_tag_b", DataType::Utf8, true),
+    ]))
+}
+
+fn diff_to_batch(entries: &[PyDiffEntry]) -> RecordBatch {
+    let mut path = StringBuilder::new();
+    let mut change = StringBuilder::new();
+    let mut size = [UInt64Builder::new(), UInt64Builder::new()];
+    let mut last_modified = [
+        TimestampMicrosecondBuilder::new(),
+        TimestampMicrosecondBuilder::new(),
+    ];
+    let mut e_tag = [StringBuilder::new(), StringBuilder::new()];
+
+    for entry in entries {
+        path.append_value(&entry.path);
+        change.append_value(entry.change.as_str());
+        for (i, meta) in [&entry.a, &entry.b].into_iter().enumerate() {
+            size[i].append_option(meta.as_ref().map(|meta| meta.size));
+            last_modified[i].append_option(
+                meta.as_ref()
+                    .map(|meta| meta.last_modified.timestamp_micros()),
+            );
+            e_tag[i].append_option(meta.as_ref().and_then(|meta| meta.e_tag.as_ref()));
+        }
+    }
+
+    let [mut size_a, mut size_b] = size;
+    let [mut last_modified_a, mut last_modified_b] = last_modified;
+    let [mut e_tag_a, mut e_tag_b] = e_tag;
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(path.finish()),
+        Arc::new(change.finish()),
+        Arc::new(size_a.finish()),
+        Arc::new(size_b.finish()),
+        Arc::new(last_modified_a.finish().with_timezone("UTC")),
+        Arc::new(last_modified_b.finish().with_timezone("UTC")),
+        Arc::new(e_tag_a.finish()),
+        Arc::new(e_tag_b.finish()),
+    ];
+    // This unwrap is ok because we know the RecordBatch is valid.
+    RecordBatch::try_new(diff_schema(), columns).unwrap()
+}
+
+#[pyfunction]
+#[pyo3(signature = (store_a, prefix_a, store_b, prefix_b, *, compare_e_tag=true, return_arrow=false))]
+pub(crate) fn diff(
+    py: Python,
+    store_a: PyObjectStore,
+    prefix_a: Option<PyPath>,
+    store_b: PyObjectStore,
+    prefix_b: Option<PyPath>,
+    compare_e_tag: bool,
+    return_arrow: bool,
+) -> PyObjectStoreResult<PyDiffResult> {
+    check_arrow(py, return_arrow)?;
+    let runtime = get_runtime(py)?;
+    let entries = py.allow_threads(|| {
+        runtime.block_on(diff_listings(
+            store_a.into_inner(),
+            prefix_a.map(|s| s.into()).unwrap_or_default(),
+            store_b.into_inner(),
+            prefix_b.map(|s| s.into()).unwrap_or_default(),
+            compare_e_tag,
+        ))
+    })?;
+    Ok(PyDiffResult::new(entries, return_arrow)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (store_a, prefix_a, store_b, prefix_b, *, compare_e_tag=true, return_arrow=false))]
+pub(crate) fn diff_async(
+    py: Python,
+    store_a: PyObjectStore,
+    prefix_a: Option<PyPath>,
+    store_b: PyObjectStore,
+    prefix_b: Option<PyPath>,
+    compare_e_tag: bool,
+    return_arrow: bool,
+) -> PyResult<Bound<PyAny>> {
+    check_arrow(py, return_arrow)?;
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let entries = diff_listings(
+            store_a.into_inner(),
+            prefix_a.map(|s| s.into()).unwrap_or_default(),
+            store_b.into_inner(),
+            prefix_b.map(|s| s.into()).unwrap_or_default(),
+            compare_e_tag,
+        )
+        .await?;
+        PyDiffResult::new(entries, return_arrow)
+    })
+}
+
+/// One side of the diff: a listing, and the prefix that its paths are relative to.
+struct Listing {
+    stream: BoxStream<'static, object_store::Result<ObjectMeta>>,
+    prefix: Path,
+    /// The relative path of the last object pulled from the stream
+    last: Option<String>,
+    /// Whether the objects pulled so far were in lexicographic order of path
+    sorted: bool,
+}
+
+impl Listing {
+    fn new(store: &Arc<dyn ObjectStore>, prefix: Path) -> Self {
+        Self::from_stream(store.list(Some(&prefix)), prefix)
+    }
+
+    fn from_stream(
+        stream: BoxStream<'static, object_store::Result<ObjectMeta>>,
+        prefix: Path,
+    ) -> Self {
+        Self {
+            stream,
+            prefix,
+            last: None,
+            sorted: true,
+        }
+    }
+
+    fn relative_path(&self, meta: &ObjectMeta) -> String {
+        match meta.location.prefix_match(&self.prefix) {
+            Some(parts) => parts
+                .map(|part| part.as_ref().to_string())
+                .collect::<Vec<_>>()
+                .join("/"),
+            None => meta.location.to_string(),
+        }
+    }
+
+    async fn next(&mut self) -> object_store::Result<Option<(String, ObjectMeta)>> {
+        let Some(meta) = self.stream.try_next().await? else {
+            return Ok(None);
+        };
+        let path = self.relative_path(&meta);
+        if self.last.as_ref().is_some_and(|last| path <= *last) {
+            self.sorted = false;
+        }
+        self.last = Some(path.clone());
+        Ok(Some((path, meta)))
+    }
+
+    /// Collect the rest of the listing, sorted by relative path, into a listing that is sorted.
+    async fn into_sorted(self, store: &Arc<dyn ObjectStore>) -> object_store::Result<Self> {
+        let mut metas = store
+            .list(Some(&self.prefix))
+            .try_collect::<Vec<_>>()
+            .await?;
+        metas.sort_unstable_by(|a, b| a.location.cmp(&b.location));
+        let stream = futures::stream::iter(metas.into_iter().map(Ok)).boxed();
+        Ok(Self::from_stream(stream, self.prefix))
+    }
+}
+
+/// Whether the object differs between the two listings: by size, and by ETag if both are known
+/// and `compare_e_tag` is set.
+fn is_changed(a: &ObjectMeta, b: &ObjectMeta, compare_e_tag: bool) -> bool {
+    if a.size != b.size {
+        return true;
+    }
+    match (&a.e_tag, &b.e_tag) {
+        (Some(e_tag_a), Some(e_tag_b)) if compare_e_tag => e_tag_a != e_tag_b,
+        _ => false,
+    }
+}
+
+/// Diff the listings of `prefix_a` in `store_a` and `prefix_b` in `store_b`.
+///
+/// Most stores list in lexicographic order, in which case the two listings are merged as they are
+/// streamed, and only the differences are held in memory. If either listing turns out not to be
+/// ordered, we fall back to listing it again in full and sorting it.
+async fn diff_listings(
+    store_a: Arc<dyn ObjectStore>,
+    prefix_a: Path,
+    store_b: Arc<dyn ObjectStore>,
+    prefix_b: Path,
+    compare_e_tag: bool,
+) -> Result<Vec<PyDiffEntry>, PyObjectStoreError> {
+    let mut a = Listing::new(&store_a, prefix_a);
+    let mut b = Listing::new(&store_b, prefix_b);
+    if let Some(entries) = merge(&mut a, &mut b, compare_e_tag).await? {
+        return Ok(entries);
+    }
+
+    let mut a = if a.sorted {
+        Listing::new(&store_a, a.prefix)
+    } else {
+        a.into_sorted(&store_a).await?
+    };
+    let mut b = if b.sorted {
+        Listing::new(&store_b, b.prefix)
+    } else {
+        b.into_sorted(&store_b).await?
+    };
+    match merge(&mut a, &mut b, compare_e_tag).await? {
+        Some(entries) => Ok(entries),
+        // The sorted listing can still be out of order if a listing that looked sorted the first
+        // time isn't this time, in which case both are sorted
+        None => {
+            let mut a = a.into_sorted(&store_a).await?;
+            let mut b = b.into_sorted(&store_b).await?;
+            // This unwrap is ok because both listings are sorted.
+            Ok(merge(&mut a, &mut b, compare_e_tag).await?.unwrap())
+        }
+    }
+}
+
+/// Merge two listings that are in lexicographic order of relative path, returning `None` as soon
+/// as either turns out not to be.
+async fn merge(
+    a: &mut Listing,
+    b: &mut Listing,
+    compare_e_tag: bool,
+) -> object_store::Result<Option<Vec<PyDiffEntry>>> {
+    let mut entries = vec![];
+    let mut next_a = a.next().await?;
+    let mut next_b = b.next().await?;
+    loop {
+        if !a.sorted || !b.sorted {
+            return Ok(None);
+        }
+        let ordering = match (&next_a, &next_b) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((path_a, _)), Some((path_b, _))) => path_a.cmp(path_b),
+        };
+        match ordering {
+            Ordering::Less => {
+                // This unwrap is ok because `next_a` is only ordered first when it's set.
+                let (path, meta) = next_a.take().unwrap();
+                entries.push(PyDiffEntry {
+                    path,
+                    change: Change::Removed,
+                    a: Some(meta),
+                    b: None,
+                });
+                next_a = a.next().await?;
+            }
+            Ordering::Greater => {
+                // This unwrap is ok because `next_b` is only ordered first when it's set.
+                let (path, meta) = next_b.take().unwrap();
+                entries.push(PyDiffEntry {
+                    path,
+                    change: Change::Added,
+                    a: None,
+                    b: Some(meta),
+                });
+                next_b = b.next().await?;
+            }
+            Ordering::Equal => {
+                // These unwraps are ok because both are set when their paths are equal.
+                let (path, meta_a) = next_a.take().unwrap();
+                let (_, meta_b) = next_b.take().unwrap();
+                if is_changed(&meta_a, &meta_b, compare_e_tag) {
+                    entries.push(PyDiffEntry {
+                        path,
+                        change: Change::Changed,
+                        a: Some(meta_a),
+                        b: Some(meta_b),
+                    });
+                }
+                next_a = a.next().await?;
+                next_b = b.next().await?;
+            }
+        }
+    }
+    Ok(Some(entries))
+}
@@ -0,0 +1,257 @@
+use std::ffi::OsString;
+use std::io::SeekFrom;
+use std::path::{Path as LocalPath, PathBuf};
+use std::sync::Arc;
+
+use futures::TryStreamExt;
+use object_store::path::Path;
+use object_store::{GetOptions, GetRange, ObjectMeta, ObjectStore};
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3_object_store::{
+    resolve_store_token, PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath,
+    PyRetryConfig,
+};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+
+use crate::list::PyObjectMeta;
+use crate::runtime::get_runtime;
+
+/// When to flush downloaded data to disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PyFsyncPolicy {
+    /// Never explicitly sync; rely on the operating system
+    Never,
+    /// Sync once the download is complete, before renaming into place
+    Complete,
+    /// Sync after every chunk, so that a crash loses as little progress as possible
+    Always,
+}
+
+impl<'py> FromPyObject<'py> for PyFsyncPolicy {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "never" => Ok(Self::Never),
+            "complete" => Ok(Self::Complete),
+            "always" => Ok(Self::Always),
+            _ => Err(PyValueError::new_err(format!(
+                "Unexpected input for fsync: {}. Expected one of 'never', 'complete', or 'always'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// The store of a [`download`], along with the `retry_config` of the store, whose backoff is
+/// used between resumed requests.
+pub(crate) struct DownloadStore {
+    store: Arc<dyn ObjectStore>,
+    retry_config: Option<PyRetryConfig>,
+}
+
+impl<'py> FromPyObject<'py> for DownloadStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let resolved = resolve_store_token(ob)?;
+        // Only the stores that make requests over the network have a `retry_config`
+        let name = intern!(ob.py(), "retry_config");
+        let retry_config = if resolved.hasattr(name)? {
+            resolved.getattr(name)?.extract()?
+        } else {
+            None
+        };
+        Ok(Self {
+            store: ob.extract::<PyObjectStore>()?.into_inner(),
+            retry_config,
+        })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, local_path, *, fsync=PyFsyncPolicy::Complete, max_retries=3))]
+pub(crate) fn download(
+    py: Python,
+    store: DownloadStore,
+    path: PyPath,
+    local_path: PathBuf,
+    fsync: PyFsyncPolicy,
+    max_retries: usize,
+) -> PyObjectStoreResult<PyObjectMeta> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let meta = runtime.block_on(download_inner(
+            store,
+            path.into(),
+            local_path,
+            fsync,
+            max_retries,
+        ))?;
+        Ok::<_, PyObjectStoreError>(PyObjectMeta::new(meta))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, local_path, *, fsync=PyFsyncPolicy::Complete, max_retries=3))]
+pub(crate) fn download_async(
+    py: Python,
+    store: DownloadStore,
+    path: PyPath,
+    local_path: PathBuf,
+    fsync: PyFsyncPolicy,
+    max_retries: usize,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let meta = download_inner(store, path.into(), local_path, fsync, max_retries).await?;
+        Ok(PyObjectMeta::new(meta))
+    })
+}
+
+/// Append a suffix to the file name of `path`, e.g. `data.bin` -> `data.bin.part`.
+fn with_suffix(path: &LocalPath, suffix: &str) -> PathBuf {
+    let mut s = OsString::from(path.as_os_str());
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// Download an object to a local file.
+///
+/// Data is written to `<local_path>.part`, which is renamed to `local_path` once the download is
+/// complete. The ETag of the object is stored alongside in `<local_path>.part.etag`, so that a
+/// later call only resumes a partial download if the object has not changed since.
+///
+/// A failed download is resumed after waiting for the backoff of the store's `retry_config`.
+async fn download_inner(
+    DownloadStore {
+        store,
+        retry_config,
+    }: DownloadStore,
+    path: Path,
+    local_path: PathBuf,
+    fsync: PyFsyncPolicy,
+    max_retries: usize,
+) -> PyObjectStoreResult<ObjectMeta> {
+    let part_path = with_suffix(&local_path, ".part");
+    let etag_path = with_suffix(&local_path, ".part.etag");
+
+    let meta = store.head(&path).await?;
+
+    // Only resume if the partial file was downloaded from the same version of the object
+    let mut offset = 0;
+    if let Some(e_tag) = &meta.e_tag {
+        if fs::read_to_string(&etag_path).await.ok().as_ref() == Some(e_tag) {
+            if let Ok(part_meta) = fs::metadata(&part_path).await {
+                offset = part_meta.len().min(meta.size);
+            }
+        }
+    }
+    if offset == 0 {
+        match &meta.e_tag {
+            Some(e_tag) => fs::write(&etag_path, e_tag).await?,
+            None => remove_if_exists(&etag_path).await?,
+        }
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&part_path)
+        .await?;
+    file.set_len(offset).await?;
+    file.seek(SeekFrom::Start(offset)).await?;
+
+    let mut retries = 0;
+    let mut backoff = PyRetryConfig::backoff(retry_config.as_ref());
+    while offset < meta.size {
+        match download_from(&store, &path, &meta, &mut offset, &mut file, fsync).await {
+            Ok(()) if offset >= meta.size => {}
+            Ok(()) if retries >= max_retries => {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("Download of '{}' ended before the end of the object", path),
+                )
+                .into());
+            }
+            // The object changed since the download started, so the partial file is invalid
+            Err(PyObjectStoreError::ObjectStoreError(
+                err @ object_store::Error::Precondition { .. },
+            )) => {
+                drop(file);
+                remove_if_exists(&part_path).await?;
+                remove_if_exists(&etag_path).await?;
+                return Err(err.into());
+            }
+            Err(err) if retries >= max_retries => return Err(err),
+            _ => {
+                retries += 1;
+                tokio::time::sleep(backoff.next_delay()).await;
+            }
+        }
+    }
+
+    file.flush().await?;
+    if fsync != PyFsyncPolicy::Never {
+        file.sync_all().await?;
+    }
+    drop(file);
+
+    fs::rename(&part_path, &local_path).await?;
+    if fsync != PyFsyncPolicy::Never {
+        sync_parent_dir(&local_path).await?;
+    }
+    remove_if_exists(&etag_path).await?;
+    Ok(meta)
+}
+
+/// Sync the directory containing `path`, so that a rename into it survives a crash.
+///
+/// Directories can't be opened as files on Windows, where renames are made durable by the
+/// file system itself.
+#[cfg(unix)]
+async fn sync_parent_dir(path: &LocalPath) -> std::io::Result<()> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => LocalPath::new("."),
+    };
+    File::open(parent).await?.sync_all().await
+}
+
+#[cfg(not(unix))]
+async fn sync_parent_dir(_path: &LocalPath) -> std::io::Result<()> {
+    Ok(())
+}
+
+/// Stream the object from `offset` into `file`, advancing `offset` as data is written.
+async fn download_from(
+    store: &Arc<dyn ObjectStore>,
+    path: &Path,
+    meta: &ObjectMeta,
+    offset: &mut u64,
+    file: &mut File,
+    fsync: PyFsyncPolicy,
+) -> PyObjectStoreResult<()> {
+    let options = GetOptions {
+        if_match: meta.e_tag.clone(),
+        range: (*offset > 0).then_some(GetRange::Offset(*offset)),
+        ..Default::default()
+    };
+    let mut stream = store.get_opts(path, options).await?.into_stream();
+    while let Some(chunk) = stream.try_next().await? {
+        file.write_all(&chunk).await?;
+        if fsync == PyFsyncPolicy::Always {
+            file.sync_data().await?;
+        }
+        *offset += chunk.len() as u64;
+    }
+    Ok(())
+}
+
+async fn remove_if_exists(path: &LocalPath) -> std::io::Result<()> {
+    match fs::remove_file(path).await {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
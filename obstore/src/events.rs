@@ -0,0 +1,459 @@
+//! Parsing the event notifications of each cloud into the same change events as `watch`.
+//!
+//! Notifications name the bucket or container of an object, and the path of the object within
+//! it. Given the store that the notifications are for, events for other buckets are skipped and
+//! paths are made relative to the prefix of the store, so that they can be passed back to it.
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use object_store::path::Path;
+use object_store::ObjectMeta;
+use percent_encoding::percent_decode_str;
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::pybacked::{PyBackedBytes, PyBackedStr};
+use pyo3_object_store::{resolve_store_token, PyAzureStore, PyGCSStore, PyS3Store};
+use serde_json::Value;
+
+use crate::watch::{ChangeKind, PyChangeEvent};
+
+#[pyfunction]
+#[pyo3(signature = (payload, *, store=None))]
+pub(crate) fn parse_events(
+    payload: EventPayload,
+    store: Option<EventStore>,
+) -> PyResult<Vec<PyChangeEvent>> {
+    let mut events = vec![];
+    parse_value(&payload.0, store.as_ref(), &mut events)?;
+    Ok(events)
+}
+
+/// The JSON of a notification, passed as a string, bytes, or the parsed dict or list.
+pub(crate) struct EventPayload(Value);
+
+impl<'py> FromPyObject<'py> for EventPayload {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let value = if let Ok(s) = ob.extract::<PyBackedStr>() {
+            serde_json::from_str(&s)
+        } else if let Ok(b) = ob.extract::<PyBackedBytes>() {
+            serde_json::from_slice(&b)
+        } else {
+            let py = ob.py();
+            let json = py
+                .import(intern!(py, "json"))?
+                .call_method1(intern!(py, "dumps"), (ob,))?
+                .extract::<PyBackedStr>()?;
+            serde_json::from_str(&json)
+        };
+        let value = value.map_err(|err| invalid(format!("not valid JSON: {}", err)))?;
+        Ok(Self(value))
+    }
+}
+
+/// The bucket or container that notifications are expected for, and the prefix of the store
+/// within it.
+pub(crate) enum EventStore {
+    S3 {
+        bucket: String,
+        prefix: Option<Path>,
+    },
+    Gcs {
+        bucket: String,
+        prefix: Option<Path>,
+    },
+    Azure {
+        account: String,
+        container: String,
+        prefix: Option<Path>,
+    },
+}
+
+impl<'py> FromPyObject<'py> for EventStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyS3Store>() {
+            let store = store.get();
+            return Ok(Self::S3 {
+                bucket: store.bucket().to_string(),
+                prefix: store.as_ref().inner().prefix().cloned(),
+            });
+        }
+        if let Ok(store) = ob.downcast::<PyGCSStore>() {
+            let store = store.get();
+            return Ok(Self::Gcs {
+                bucket: store.bucket().to_string(),
+                prefix: store.as_ref().inner().prefix().cloned(),
+            });
+        }
+        if let Ok(store) = ob.downcast::<PyAzureStore>() {
+            let store = store.get();
+            let (container_url, account) = store.container_url()?;
+            let container = container_url
+                .path_segments()
+                .and_then(|mut segments| segments.next_back())
+                .unwrap_or_default()
+                .to_string();
+            return Ok(Self::Azure {
+                account,
+                container,
+                prefix: store.as_ref().inner().prefix().cloned(),
+            });
+        }
+
+        let py = ob.py();
+        // Check for object-store instance from other library
+        let cls_name = ob
+            .getattr(intern!(py, "__class__"))?
+            .getattr(intern!(py, "__name__"))?
+            .extract::<PyBackedStr>()?;
+        if matches!(cls_name.as_ref(), "S3Store" | "GCSStore" | "AzureStore") {
+            return Err(PyValueError::new_err("You must use an object store instance exported from **the same library** as this function. They cannot be used across libraries.\nThis is because object store instances are compiled with a specific version of Rust and Python." ));
+        }
+
+        Err(PyValueError::new_err(format!(
+            "Event notifications are only supported for S3Store, GCSStore and AzureStore instances, got {}",
+            ob.repr()?
+        )))
+    }
+}
+
+impl EventStore {
+    /// The path within the store of `path` within `bucket`, or `None` if the object isn't in
+    /// the store.
+    ///
+    /// `account` is the Azure storage account of the notification, if it names one.
+    fn store_path(&self, account: Option<&str>, bucket: &str, path: Path) -> Option<Path> {
+        let prefix = match self {
+            Self::S3 {
+                bucket: expected,
+                prefix,
+            }
+            | Self::Gcs {
+                bucket: expected,
+                prefix,
+            } => {
+                if bucket != expected {
+                    return None;
+                }
+                prefix
+            }
+            Self::Azure {
+                account: expected_account,
+                container,
+                prefix,
+            } => {
+                if bucket != container || account.is_some_and(|account| account != expected_account)
+                {
+                    return None;
+                }
+                prefix
+            }
+        };
+        match prefix {
+            Some(prefix) => Some(path.prefix_match(prefix)?.collect()),
+            None => Some(path),
+        }
+    }
+}
+
+fn invalid(reason: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(format!("Invalid event notification: {}", reason))
+}
+
+/// Parse every change in a notification, or in a message that a notification was delivered in.
+fn parse_value(
+    value: &Value,
+    store: Option<&EventStore>,
+    events: &mut Vec<PyChangeEvent>,
+) -> PyResult<()> {
+    // Event Grid delivers events in batches
+    if let Some(values) = value.as_array() {
+        for value in values {
+            parse_value(value, store, events)?;
+        }
+        return Ok(());
+    }
+    // Messages of SQS and SNS, including as delivered to Lambda, with a notification as their
+    // body
+    for key in ["body", "Body", "Message"] {
+        if let Some(body) = value[key].as_str() {
+            let body = serde_json::from_str(body)
+                .map_err(|err| invalid(format!("{} is not valid JSON: {}", key, err)))?;
+            return parse_value(&body, store, events);
+        }
+    }
+    if value["Sns"].is_object() {
+        return parse_value(&value["Sns"], store, events);
+    }
+    if let Some(records) = value["Records"].as_array() {
+        for record in records {
+            if record["s3"].is_object() {
+                parse_s3_record(record, store, events)?;
+            } else {
+                parse_value(record, store, events)?;
+            }
+        }
+        return Ok(());
+    }
+    // The test event that S3 sends when notifications are configured
+    if value["Event"].as_str() == Some("s3:TestEvent") {
+        return Ok(());
+    }
+    if value["source"].as_str() == Some("aws.s3") && value["detail"].is_object() {
+        return parse_eventbridge_event(value, store, events);
+    }
+    // A push subscription of Pub/Sub wraps the message
+    if value["message"].is_object() {
+        return parse_value(&value["message"], store, events);
+    }
+    if value["attributes"]["eventType"].is_string() {
+        return parse_pubsub_message(value, store, events);
+    }
+    // Event Grid events in the Event Grid schema have an `eventType`, and in the CloudEvents
+    // schema a `type`
+    if let Some(event_type) = value["eventType"].as_str().or(value["type"].as_str()) {
+        if event_type.starts_with("Microsoft.") {
+            return parse_event_grid_event(event_type, value, store, events);
+        }
+    }
+    Err(invalid(
+        "expected an S3 event notification, an EventBridge event, a Pub/Sub message or an Event Grid event",
+    ))
+}
+
+/// Queue the change of an object named `key` within `bucket`, unless it's outside of the store.
+fn push_event(
+    events: &mut Vec<PyChangeEvent>,
+    store: Option<&EventStore>,
+    change: ChangeKind,
+    account: Option<&str>,
+    bucket: &str,
+    key: &str,
+    meta: EventMeta,
+) -> PyResult<()> {
+    let path = Path::parse(key).map_err(|err| invalid(err.to_string()))?;
+    let location = match store {
+        Some(store) => match store.store_path(account, bucket, path) {
+            Some(path) => path,
+            None => return Ok(()),
+        },
+        None => path,
+    };
+    // The prefix of the store itself isn't an object within it
+    if location.as_ref().is_empty() {
+        return Ok(());
+    }
+    events.push(PyChangeEvent::new(
+        change,
+        ObjectMeta {
+            location,
+            last_modified: meta.last_modified,
+            size: meta.size,
+            e_tag: meta.e_tag,
+            version: meta.version,
+        },
+    ));
+    Ok(())
+}
+
+/// The metadata of an object that a notification includes.
+struct EventMeta {
+    last_modified: DateTime<Utc>,
+    size: u64,
+    e_tag: Option<String>,
+    version: Option<String>,
+}
+
+fn parse_time(value: &Value) -> PyResult<DateTime<Utc>> {
+    value
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+        .ok_or_else(|| invalid(format!("invalid time {}", value)))
+}
+
+fn required_str<'a>(value: &'a Value, name: &str) -> PyResult<&'a str> {
+    value
+        .as_str()
+        .ok_or_else(|| invalid(format!("missing {}", name)))
+}
+
+/// Quote an ETag as stores return it in the `ETag` header, which notifications leave out.
+fn quote_e_tag(value: &Value) -> Option<String> {
+    let e_tag = value.as_str()?;
+    if e_tag.starts_with('"') {
+        Some(e_tag.to_string())
+    } else {
+        Some(format!("\"{}\"", e_tag))
+    }
+}
+
+/// Parse a record of an S3 event notification, as sent to SQS, SNS and Lambda.
+///
+/// Keys are URL-encoded in these notifications, with spaces encoded as `+`.
+fn parse_s3_record(
+    record: &Value,
+    store: Option<&EventStore>,
+    events: &mut Vec<PyChangeEvent>,
+) -> PyResult<()> {
+    let event_name = required_str(&record["eventName"], "eventName")?;
+    let change = if event_name.starts_with("ObjectCreated:") {
+        ChangeKind::Created
+    } else if event_name.starts_with("ObjectRemoved:")
+        || event_name.starts_with("LifecycleExpiration:")
+    {
+        ChangeKind::Deleted
+    } else {
+        // Restores, replication, tagging and ACL changes don't change the object
+        return Ok(());
+    };
+
+    let object = &record["s3"]["object"];
+    let key = required_str(&object["key"], "key")?.replace('+', " ");
+    let key = percent_decode_str(&key)
+        .decode_utf8()
+        .map_err(|err| invalid(err.to_string()))?;
+    let meta = EventMeta {
+        last_modified: parse_time(&record["eventTime"])?,
+        size: object["size"].as_u64().unwrap_or_default(),
+        e_tag: quote_e_tag(&object["eTag"]),
+        version: object["versionId"].as_str().map(String::from),
+    };
+    let bucket = required_str(&record["s3"]["bucket"]["name"], "bucket name")?;
+    push_event(events, store, change, None, bucket, &key, meta)
+}
+
+/// Parse an S3 event delivered by EventBridge, whose keys aren't URL-encoded.
+fn parse_eventbridge_event(
+    event: &Value,
+    store: Option<&EventStore>,
+    events: &mut Vec<PyChangeEvent>,
+) -> PyResult<()> {
+    let change = match required_str(&event["detail-type"], "detail-type")? {
+        "Object Created" => ChangeKind::Created,
+        "Object Deleted" => ChangeKind::Deleted,
+        _ => return Ok(()),
+    };
+
+    let detail = &event["detail"];
+    let object = &detail["object"];
+    let meta = EventMeta {
+        last_modified: parse_time(&event["time"])?,
+        size: object["size"].as_u64().unwrap_or_default(),
+        e_tag: quote_e_tag(&object["etag"]),
+        version: object["version-id"].as_str().map(String::from),
+    };
+    let bucket = required_str(&detail["bucket"]["name"], "bucket name")?;
+    let key = required_str(&object["key"], "key")?;
+    push_event(events, store, change, None, bucket, key, meta)
+}
+
+/// Parse a Pub/Sub message of a GCS notification, whose data is the JSON of the object.
+///
+/// Overwriting an object sends an `OBJECT_FINALIZE` for the new object, and an `OBJECT_DELETE`
+/// or `OBJECT_ARCHIVE` for the old one, which tell that the object was overwritten by their
+/// attributes. The overwrite is reported as a single modification.
+fn parse_pubsub_message(
+    message: &Value,
+    store: Option<&EventStore>,
+    events: &mut Vec<PyChangeEvent>,
+) -> PyResult<()> {
+    let attributes = &message["attributes"];
+    let change = match required_str(&attributes["eventType"], "eventType")? {
+        "OBJECT_FINALIZE" if attributes["overwroteGeneration"].is_string() => ChangeKind::Modified,
+        "OBJECT_FINALIZE" => ChangeKind::Created,
+        "OBJECT_DELETE" | "OBJECT_ARCHIVE" if attributes["overwrittenByGeneration"].is_string() => {
+            return Ok(())
+        }
+        "OBJECT_DELETE" | "OBJECT_ARCHIVE" => ChangeKind::Deleted,
+        _ => return Ok(()),
+    };
+
+    // The data is base64-encoded in the message, but may be passed already decoded
+    let decoded;
+    let object = match &message["data"] {
+        Value::String(data) => {
+            let data = BASE64_STANDARD
+                .decode(data)
+                .map_err(|err| invalid(format!("data is not valid base64: {}", err)))?;
+            decoded = serde_json::from_slice::<Value>(&data)
+                .map_err(|err| invalid(format!("data is not valid JSON: {}", err)))?;
+            &decoded
+        }
+        data => data,
+    };
+    let meta = EventMeta {
+        last_modified: parse_time(&object["updated"])?,
+        // The JSON API returns sizes as strings
+        size: object["size"]
+            .as_str()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or_default(),
+        e_tag: gcs_e_tag(object),
+        version: object["generation"].as_str().map(String::from),
+    };
+    let bucket = attributes["bucketId"]
+        .as_str()
+        .or(object["bucket"].as_str())
+        .ok_or_else(|| invalid("missing bucketId"))?;
+    let key = attributes["objectId"]
+        .as_str()
+        .or(object["name"].as_str())
+        .ok_or_else(|| invalid("missing objectId"))?;
+    push_event(events, store, change, None, bucket, key, meta)
+}
+
+/// The ETag that the XML API, which `GCSStore` uses, returns for an object.
+///
+/// That's the hex MD5 of the object, unlike the `etag` of the JSON API. Composite objects have no
+/// MD5, and an ETag that can't be derived from the notification.
+fn gcs_e_tag(object: &Value) -> Option<String> {
+    let md5 = BASE64_STANDARD.decode(object["md5Hash"].as_str()?).ok()?;
+    let hex = md5.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    Some(format!("\"{}\"", hex))
+}
+
+/// Parse an Event Grid event of Azure Blob Storage, in either the Event Grid or the CloudEvents
+/// schema.
+///
+/// The subject of an event is `/blobServices/default/containers/<container>/blobs/<path>`.
+fn parse_event_grid_event(
+    event_type: &str,
+    event: &Value,
+    store: Option<&EventStore>,
+    events: &mut Vec<PyChangeEvent>,
+) -> PyResult<()> {
+    let change = match event_type {
+        "Microsoft.Storage.BlobCreated" => ChangeKind::Created,
+        "Microsoft.Storage.BlobDeleted" => ChangeKind::Deleted,
+        // Including the validation event sent when a subscription is created
+        _ => return Ok(()),
+    };
+
+    let subject = required_str(&event["subject"], "subject")?;
+    let (container, key) = subject
+        .strip_prefix("/blobServices/default/containers/")
+        .and_then(|rest| rest.split_once("/blobs/"))
+        .ok_or_else(|| invalid(format!("unexpected subject {}", subject)))?;
+    // The topic, or the source in the CloudEvents schema, is the resource ID of the account
+    let account = event["topic"]
+        .as_str()
+        .or(event["source"].as_str())
+        .and_then(|topic| topic.rsplit_once("/storageAccounts/"))
+        .map(|(_, account)| account);
+
+    let time = match &event["eventTime"] {
+        Value::Null => &event["time"],
+        time => time,
+    };
+    let data = &event["data"];
+    let meta = EventMeta {
+        last_modified: parse_time(time)?,
+        size: data["contentLength"].as_u64().unwrap_or_default(),
+        e_tag: quote_e_tag(&data["eTag"]),
+        version: data["versionId"].as_str().map(String::from),
+    };
+    push_event(events, store, change, account, container, key, meta)
+}
@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+
+use crate::list::{check_arrow, object_meta_to_arrow, PyGlob, PyObjectMeta, PyTableWrapper};
+use crate::runtime::get_runtime;
+
+/// A segment of a glob pattern, between two separators.
+enum Segment {
+    /// A segment without wildcards, which only matches itself.
+    Literal(String),
+    /// A segment with wildcards, which matches a single path segment.
+    Wildcard(PyGlob),
+    /// `**`, which matches any number of path segments.
+    Recursive,
+}
+
+impl Segment {
+    fn matches(&self, name: Option<&str>) -> bool {
+        match (self, name) {
+            (Self::Literal(literal), Some(name)) => literal == name,
+            (Self::Wildcard(glob), Some(name)) => glob.matches_str(name),
+            _ => false,
+        }
+    }
+}
+
+/// A glob pattern, split into its static prefix and the segments after it.
+pub(crate) struct PyGlobPattern {
+    glob: PyGlob,
+    /// The leading segments without wildcards, which are never listed
+    prefix: Path,
+    /// The remaining segments, of which there is at least one
+    segments: Vec<Segment>,
+}
+
+impl<'py> FromPyObject<'py> for PyGlobPattern {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let pattern = ob.extract::<PyBackedStr>()?;
+        let pattern = pattern.trim_matches('/');
+        if pattern.is_empty() {
+            return Err(PyValueError::new_err("Glob pattern must not be empty"));
+        }
+        let glob = PyGlob::new(pattern)?;
+
+        let mut segments = pattern
+            .split('/')
+            .map(|part| {
+                if part == "**" {
+                    Ok(Segment::Recursive)
+                } else if part.contains(['*', '?', '[']) {
+                    // Character classes can't contain a separator, so each segment must be a
+                    // valid pattern by itself
+                    Ok(Segment::Wildcard(PyGlob::new(part)?))
+                } else {
+                    Ok(Segment::Literal(part.to_string()))
+                }
+            })
+            .collect::<PyResult<Vec<_>>>()?;
+
+        // The last segment is always matched against a listing, even without wildcards
+        let static_len = segments
+            .iter()
+            .position(|segment| !matches!(segment, Segment::Literal(_)))
+            .unwrap_or(segments.len())
+            .min(segments.len() - 1);
+        let prefix = segments
+            .drain(..static_len)
+            .filter_map(|segment| match segment {
+                Segment::Literal(part) => Some(part),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        Ok(Self {
+            glob,
+            prefix: prefix.as_str().into(),
+            segments,
+        })
+    }
+}
+
+#[derive(IntoPyObject)]
+pub(crate) enum PyExpandResult {
+    Arrow(PyTableWrapper),
+    Native(Vec<PyObjectMeta>),
+}
+
+impl PyExpandResult {
+    fn new(metas: Vec<ObjectMeta>, return_arrow: bool) -> PyResult<Self> {
+        let metas = metas.into_iter().map(PyObjectMeta::new).collect::<Vec<_>>();
+        if return_arrow {
+            Ok(Self::Arrow(
+                object_meta_to_arrow(&metas, false).into_table()?,
+            ))
+        } else {
+            Ok(Self::Native(metas))
+        }
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, pattern, *, return_arrow=false, max_concurrency=12))]
+pub(crate) fn expand_paths(
+    py: Python,
+    store: PyObjectStore,
+    pattern: PyGlobPattern,
+    return_arrow: bool,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<PyExpandResult> {
+    check_arrow(py, return_arrow)?;
+    let runtime = get_runtime(py)?;
+    let metas = py.allow_threads(|| {
+        runtime.block_on(expand(store.into_inner(), &pattern, max_concurrency))
+    })?;
+    Ok(PyExpandResult::new(metas, return_arrow)?)
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, pattern, *, return_arrow=false, max_concurrency=12))]
+pub(crate) fn expand_paths_async(
+    py: Python,
+    store: PyObjectStore,
+    pattern: PyGlobPattern,
+    return_arrow: bool,
+    max_concurrency: usize,
+) -> PyResult<Bound<PyAny>> {
+    check_arrow(py, return_arrow)?;
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let metas = expand(store.into_inner(), &pattern, max_concurrency).await?;
+        PyExpandResult::new(metas, return_arrow)
+    })
+}
+
+/// Find the objects whose paths match `pattern`, in lexicographic order of path.
+///
+/// Starting from the static prefix of the pattern, the directories matching each segment are
+/// listed with a delimiter, concurrently, so that only the directories that can contain matches
+/// are listed. Once a `**` segment is reached, everything below the directories matched so far is
+/// listed, and matched against the whole pattern.
+async fn expand(
+    store: Arc<dyn ObjectStore>,
+    pattern: &PyGlobPattern,
+    max_concurrency: usize,
+) -> Result<Vec<ObjectMeta>, PyObjectStoreError> {
+    let max_concurrency = max_concurrency.max(1);
+    let last = pattern.segments.len() - 1;
+    let mut dirs = vec![pattern.prefix.clone()];
+    let mut matches = vec![];
+    for (i, segment) in pattern.segments.iter().enumerate() {
+        if dirs.is_empty() {
+            break;
+        }
+        match segment {
+            Segment::Recursive => {
+                let metas = futures::stream::iter(dirs)
+                    .map(|dir| store.list(Some(&dir)).try_collect::<Vec<_>>())
+                    .buffer_unordered(max_concurrency)
+                    .try_concat()
+                    .await?;
+                matches.extend(metas.into_iter().filter(|meta| pattern.glob.matches(meta)));
+                break;
+            }
+            Segment::Literal(part) if i < last => {
+                dirs = dirs
+                    .into_iter()
+                    .map(|dir| dir.child(part.as_str()))
+                    .collect();
+            }
+            segment => {
+                let results = futures::stream::iter(dirs)
+                    .map(|dir| {
+                        let store = store.clone();
+                        async move { store.list_with_delimiter(Some(&dir)).await }
+                    })
+                    .buffer_unordered(max_concurrency)
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                if i == last {
+                    matches.extend(results.into_iter().flat_map(|result| {
+                        result
+                            .objects
+                            .into_iter()
+                            .filter(|meta| segment.matches(meta.location.filename()))
+                    }));
+                    dirs = vec![];
+                } else {
+                    dirs = results
+                        .into_iter()
+                        .flat_map(|result| result.common_prefixes)
+                        .filter(|dir| segment.matches(dir.filename()))
+                        .collect();
+                }
+            }
+        }
+    }
+    matches.sort_unstable_by(|a, b| a.location.cmp(&b.location));
+    Ok(matches)
+}
@@ -0,0 +1,291 @@
+//! A filesystem class implementing the core of the fsspec `AbstractFileSystem` interface.
+//!
+//! Every method is implemented directly in Rust against the underlying store, so that libraries
+//! such as pandas, pyarrow and dask can use an obstore store without round-tripping through a
+//! Python implementation for every call.
+
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{GetRange, ObjectMeta, ObjectStore};
+use pyo3::exceptions::{PyFileNotFoundError, PyIsADirectoryError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+
+use crate::buffered::{create_reader, create_writer, PyReadableFile, PyWritableFile};
+use crate::path::PyPaths;
+use crate::runtime::get_runtime;
+
+/// Information about a file or directory, as returned by `ls` and `info`.
+pub(crate) enum PyFileInfo {
+    File(ObjectMeta),
+    Directory(Path),
+}
+
+impl<'py> IntoPyObject<'py> for PyFileInfo {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(6);
+        match self {
+            Self::File(meta) => {
+                dict.insert("name", meta.location.as_ref().into_bound_py_any(py)?);
+                dict.insert("size", meta.size.into_bound_py_any(py)?);
+                dict.insert("type", "file".into_bound_py_any(py)?);
+                dict.insert("last_modified", meta.last_modified.into_bound_py_any(py)?);
+                dict.insert("e_tag", meta.e_tag.into_bound_py_any(py)?);
+                dict.insert("version", meta.version.into_bound_py_any(py)?);
+            }
+            Self::Directory(path) => {
+                dict.insert("name", path.as_ref().into_bound_py_any(py)?);
+                dict.insert("size", 0.into_bound_py_any(py)?);
+                dict.insert("type", "directory".into_bound_py_any(py)?);
+            }
+        }
+        dict.into_pyobject(py)
+    }
+}
+
+impl PyFileInfo {
+    fn name(&self) -> &str {
+        match self {
+            Self::File(meta) => meta.location.as_ref(),
+            Self::Directory(path) => path.as_ref(),
+        }
+    }
+}
+
+/// Convert an fsspec-style path, which may have a leading or trailing slash, to a store path.
+fn to_path(path: &str) -> Path {
+    path.trim_matches('/').into()
+}
+
+#[pyclass(name = "ObstoreFileSystem", frozen)]
+pub(crate) struct PyObstoreFileSystem {
+    /// The Python store, which files opened for reading keep so that they can be pickled
+    py_store: PyObject,
+    store: Arc<dyn ObjectStore>,
+}
+
+#[pymethods]
+impl PyObstoreFileSystem {
+    #[new]
+    fn py_new(store: Bound<PyAny>) -> PyResult<Self> {
+        Ok(Self {
+            py_store: store.clone().unbind(),
+            store: store.extract::<PyObjectStore>()?.into_inner(),
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("ObstoreFileSystem({})", self.store)
+    }
+
+    #[pyo3(signature = (path, start=None, end=None))]
+    fn cat_file(
+        &self,
+        py: Python,
+        path: &str,
+        start: Option<i64>,
+        end: Option<i64>,
+    ) -> PyObjectStoreResult<PyObject> {
+        let runtime = get_runtime(py)?;
+        let store = self.store.clone();
+        let path = to_path(path);
+        let data = py.allow_threads(|| {
+            runtime.block_on(async move {
+                let range = match (start, end) {
+                    (None | Some(0), None) => None,
+                    (Some(start), None) if start > 0 => Some(GetRange::Offset(start as u64)),
+                    (Some(start), None) => Some(GetRange::Suffix(start.unsigned_abs())),
+                    (start, Some(end)) => {
+                        // Negative offsets are relative to the end of the file
+                        let size = store.head(&path).await?.size;
+                        let resolve = |offset: i64| {
+                            if offset < 0 {
+                                size.saturating_sub(offset.unsigned_abs())
+                            } else {
+                                (offset as u64).min(size)
+                            }
+                        };
+                        let start = resolve(start.unwrap_or(0));
+                        let end = resolve(end);
+                        if start >= end {
+                            return Ok(bytes::Bytes::new());
+                        }
+                        Some(GetRange::Bounded(start..end))
+                    }
+                };
+                let options = object_store::GetOptions {
+                    range,
+                    ..Default::default()
+                };
+                let result = store.get_opts(&path, options).await?;
+                Ok::<_, PyObjectStoreError>(result.bytes().await?)
+            })
+        })?;
+        Ok(pyo3::types::PyBytes::new(py, &data).into_any().unbind())
+    }
+
+    fn cp_file(&self, py: Python, path1: &str, path2: &str) -> PyObjectStoreResult<()> {
+        let runtime = get_runtime(py)?;
+        let (from, to) = (to_path(path1), to_path(path2));
+        py.allow_threads(|| runtime.block_on(self.store.copy(&from, &to)))?;
+        Ok(())
+    }
+
+    fn info(&self, py: Python, path: &str) -> PyObjectStoreResult<PyFileInfo> {
+        let runtime = get_runtime(py)?;
+        let path = to_path(path);
+        py.allow_threads(|| runtime.block_on(info(&self.store, path)))
+    }
+
+    #[pyo3(signature = (path, detail=true))]
+    fn ls(&self, py: Python, path: &str, detail: bool) -> PyObjectStoreResult<PyObject> {
+        let runtime = get_runtime(py)?;
+        let path = to_path(path);
+        let entries = py.allow_threads(|| runtime.block_on(ls(&self.store, path)))?;
+        if detail {
+            Ok(entries.into_py_any(py)?)
+        } else {
+            let names = entries
+                .iter()
+                .map(|entry| entry.name().to_string())
+                .collect::<Vec<_>>();
+            Ok(names.into_py_any(py)?)
+        }
+    }
+
+    #[pyo3(signature = (path, mode="rb", block_size=None))]
+    fn open(
+        &self,
+        py: Python,
+        path: &str,
+        mode: &str,
+        block_size: Option<usize>,
+    ) -> PyObjectStoreResult<PyObject> {
+        let store = self.store.clone();
+        let path = to_path(path);
+        match mode {
+            "rb" => {
+                let runtime = get_runtime(py)?;
+                let capacity = block_size.unwrap_or(1024 * 1024);
+                let (reader, meta) =
+                    py.allow_threads(|| runtime.block_on(create_reader(store, path, capacity)))?;
+                let py_store = self.py_store.clone_ref(py);
+                let file =
+                    PyReadableFile::new(py_store, reader.into(), meta, capacity, None, false);
+                Ok(file.into_py_any(py)?)
+            }
+            "wb" => {
+                let capacity = block_size.unwrap_or(10 * 1024 * 1024);
+                let writer =
+                    create_writer(store, path, None, capacity, None, 12, None, None, None)?;
+                Ok(PyWritableFile::new(writer, false).into_py_any(py)?)
+            }
+            other => Err(PyValueError::new_err(format!(
+                "Unsupported mode '{}'. Only 'rb' and 'wb' are supported.",
+                other
+            ))
+            .into()),
+        }
+    }
+
+    fn pipe_file(
+        &self,
+        py: Python,
+        path: &str,
+        value: pyo3_bytes::PyBytes,
+    ) -> PyObjectStoreResult<()> {
+        let runtime = get_runtime(py)?;
+        let path = to_path(path);
+        let payload = value.into_inner().into();
+        py.allow_threads(|| runtime.block_on(self.store.put(&path, payload)))?;
+        Ok(())
+    }
+
+    #[pyo3(signature = (path, recursive=false))]
+    fn rm(&self, py: Python, path: PyPaths, recursive: bool) -> PyObjectStoreResult<()> {
+        let runtime = get_runtime(py)?;
+        let paths = match path {
+            PyPaths::One(path) => vec![path],
+            PyPaths::Many(paths) => paths,
+        };
+        let paths = paths
+            .iter()
+            .map(|path| to_path(path.as_ref()))
+            .collect::<Vec<_>>();
+        py.allow_threads(|| runtime.block_on(rm(&self.store, paths, recursive)))
+    }
+}
+
+async fn info(store: &Arc<dyn ObjectStore>, path: Path) -> PyObjectStoreResult<PyFileInfo> {
+    match store.head(&path).await {
+        Ok(meta) => Ok(PyFileInfo::File(meta)),
+        Err(object_store::Error::NotFound { .. }) => {
+            // Directories don't exist as objects, so check whether anything is under the prefix
+            let mut stream = store.list(Some(&path));
+            if stream.try_next().await?.is_some() {
+                Ok(PyFileInfo::Directory(path))
+            } else {
+                Err(PyFileNotFoundError::new_err(path.to_string()).into())
+            }
+        }
+        Err(err) => Err(err.into()),
+    }
+}
+
+async fn ls(store: &Arc<dyn ObjectStore>, path: Path) -> PyObjectStoreResult<Vec<PyFileInfo>> {
+    let list_result = store.list_with_delimiter(Some(&path)).await?;
+    let mut entries = list_result
+        .common_prefixes
+        .into_iter()
+        .map(PyFileInfo::Directory)
+        .chain(list_result.objects.into_iter().map(PyFileInfo::File))
+        .collect::<Vec<_>>();
+
+    // As in fsspec, listing a file returns the file itself
+    if entries.is_empty() {
+        entries.push(info(store, path).await?);
+    }
+    Ok(entries)
+}
+
+async fn rm(
+    store: &Arc<dyn ObjectStore>,
+    paths: Vec<Path>,
+    recursive: bool,
+) -> PyObjectStoreResult<()> {
+    let mut to_delete = vec![];
+    for path in paths {
+        // Raises FileNotFoundError if nothing exists at `path`, as deleting a missing object
+        // succeeds on some stores, such as S3
+        match info(store, path).await? {
+            PyFileInfo::File(meta) => to_delete.push(meta.location),
+            // Directories don't exist as objects, so only the files under them are deleted
+            PyFileInfo::Directory(path) if recursive => {
+                let children = store
+                    .list(Some(&path))
+                    .map_ok(|meta| meta.location)
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                to_delete.extend(children);
+            }
+            PyFileInfo::Directory(path) => {
+                return Err(PyIsADirectoryError::new_err(path.to_string()).into());
+            }
+        }
+    }
+
+    store
+        .delete_stream(futures::stream::iter(to_delete.into_iter().map(Ok)).boxed())
+        .try_collect::<Vec<_>>()
+        .await?;
+    Ok(())
+}
@@ -1,24 +1,41 @@
 use std::collections::HashMap;
 use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
-use bytes::Bytes;
+use bytes::{Bytes, BytesMut};
 use chrono::{DateTime, Utc};
 use futures::stream::{BoxStream, Fuse};
-use futures::StreamExt;
-use object_store::{GetOptions, GetRange, GetResult, ObjectStore};
-use pyo3::exceptions::{PyStopAsyncIteration, PyStopIteration, PyValueError};
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{GetOptions, GetRange, GetResult, ObjectStore, OBJECT_STORE_COALESCE_DEFAULT};
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::{
+    PyBufferError, PyImportError, PyStopAsyncIteration, PyStopIteration, PyTypeError, PyValueError,
+};
+use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3::types::PyDict;
 use pyo3_bytes::PyBytes;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+use pyo3_object_store::{
+    check_keys, PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath, RequestHeaders,
+};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
 use tokio::sync::Mutex;
 
 use crate::attributes::PyAttributes;
+use crate::compression::{decompress_stream, PyDecompression};
 use crate::list::PyObjectMeta;
+use crate::progress::{track_stream, PyProgress, DEFAULT_PROGRESS_INTERVAL};
+use crate::response::{PyResponseStream, DEFAULT_RESPONSE_CHUNK_SIZE};
 use crate::runtime::get_runtime;
+use crate::utils::next_within;
 
 /// 10MB default chunk size
-const DEFAULT_BYTES_CHUNK_SIZE: usize = 10 * 1024 * 1024;
+pub(crate) const DEFAULT_BYTES_CHUNK_SIZE: usize = 10 * 1024 * 1024;
 
 pub(crate) struct PyGetOptions {
     if_match: Option<String>,
@@ -28,10 +45,25 @@ pub(crate) struct PyGetOptions {
     range: Option<PyGetRange>,
     version: Option<String>,
     head: bool,
+    headers: Option<RequestHeaders>,
 }
 
 impl<'py> FromPyObject<'py> for PyGetOptions {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        check_keys(
+            ob,
+            "options",
+            &[
+                "if_match",
+                "if_none_match",
+                "if_modified_since",
+                "if_unmodified_since",
+                "range",
+                "version",
+                "head",
+                "headers",
+            ],
+        )?;
         // Update to use derive(FromPyObject) when default is implemented:
         // https://github.com/PyO3/pyo3/issues/4643
         let dict = ob.extract::<HashMap<String, Bound<PyAny>>>()?;
@@ -53,6 +85,7 @@ impl<'py> FromPyObject<'py> for PyGetOptions {
                 .map(|x| x.extract())
                 .transpose()?
                 .unwrap_or(false),
+            headers: dict.get("headers").map(|x| x.extract()).transpose()?,
         })
     }
 }
@@ -67,7 +100,10 @@ impl From<PyGetOptions> for GetOptions {
             range: value.range.map(|inner| inner.0),
             version: value.version,
             head: value.head,
-            extensions: Default::default(),
+            extensions: value
+                .headers
+                .map(RequestHeaders::into_extensions)
+                .unwrap_or_default(),
         }
     }
 }
@@ -119,49 +155,216 @@ impl<'py> FromPyObject<'py> for PyGetRange {
 }
 
 #[pyclass(name = "GetResult", frozen)]
-pub(crate) struct PyGetResult(std::sync::Mutex<Option<GetResult>>);
+pub(crate) struct PyGetResult {
+    result: std::sync::Mutex<Option<GetResult>>,
+    progress: Option<Arc<PyProgress>>,
+}
 
 impl PyGetResult {
-    fn new(result: GetResult) -> Self {
-        Self(std::sync::Mutex::new(Some(result)))
+    fn new(result: GetResult, progress: Option<Arc<PyProgress>>) -> Self {
+        Self {
+            result: std::sync::Mutex::new(Some(result)),
+            progress,
+        }
+    }
+
+    fn take(&self) -> PyResult<GetResult> {
+        self.result
+            .lock()
+            .unwrap()
+            .take()
+            .ok_or(PyValueError::new_err("Result has already been disposed."))
+    }
+}
+
+/// Read the full body of `result`, reporting progress if requested.
+async fn get_result_bytes(
+    result: GetResult,
+    progress: Option<Arc<PyProgress>>,
+) -> object_store::Result<Bytes> {
+    if progress.is_none() {
+        return result.bytes().await;
+    }
+    let len = (result.range.end - result.range.start) as usize;
+    let mut stream = track_stream(result.into_stream(), progress);
+    let mut buffer = BytesMut::with_capacity(len);
+    while let Some(bytes) = stream.try_next().await? {
+        buffer.extend_from_slice(&bytes);
+    }
+    Ok(buffer.freeze())
+}
+
+/// Where `GetResult.stream_to` writes the body of a response.
+pub(crate) enum PyStreamDestination {
+    /// A local file, which is created or truncated
+    Path(PathBuf),
+    /// A Python object with a `write` method accepting buffer protocol objects
+    FileLike(PyObject),
+}
+
+impl<'py> FromPyObject<'py> for PyStreamDestination {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(path) = ob.extract::<PathBuf>() {
+            Ok(Self::Path(path))
+        } else if ob.hasattr(intern!(ob.py(), "write"))? {
+            Ok(Self::FileLike(ob.clone().unbind()))
+        } else {
+            Err(PyTypeError::new_err(
+                "Expected a path or a file-like object with a 'write' method.",
+            ))
+        }
+    }
+}
+
+/// The checksum `GetResult.stream_to` computes while copying.
+pub(crate) enum PyChecksumAlgorithm {
+    Sha256,
+}
+
+impl<'py> FromPyObject<'py> for PyChecksumAlgorithm {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "sha256" => Ok(Self::Sha256),
+            _ => Err(PyValueError::new_err(format!(
+                "Unexpected input for checksum: {}. Expected 'sha256'.",
+                s
+            ))),
+        }
+    }
+}
+
+enum Sink {
+    File(tokio::fs::File),
+    FileLike(PyObject),
+}
+
+#[derive(IntoPyObject)]
+pub(crate) enum PyStreamToResult {
+    BytesWritten(u64),
+    WithChecksum((u64, String)),
+}
+
+/// Write the full body of `result` to `destination`, one chunk at a time, returning the number
+/// of bytes written and, if requested, the checksum of the body.
+async fn stream_to(
+    result: GetResult,
+    progress: Option<Arc<PyProgress>>,
+    destination: PyStreamDestination,
+    checksum: Option<PyChecksumAlgorithm>,
+) -> PyObjectStoreResult<PyStreamToResult> {
+    let mut stream = track_stream(result.into_stream(), progress);
+    let mut hasher = checksum.map(|algorithm| match algorithm {
+        PyChecksumAlgorithm::Sha256 => Sha256::new(),
+    });
+    let mut sink = match destination {
+        PyStreamDestination::Path(path) => Sink::File(tokio::fs::File::create(path).await?),
+        PyStreamDestination::FileLike(writer) => Sink::FileLike(writer),
+    };
+    let mut bytes_written = 0;
+    while let Some(bytes) = stream.try_next().await? {
+        if let Some(hasher) = &mut hasher {
+            hasher.update(&bytes);
+        }
+        bytes_written += bytes.len() as u64;
+        match &mut sink {
+            Sink::File(file) => file.write_all(&bytes).await?,
+            Sink::FileLike(writer) => {
+                Python::with_gil(|py| {
+                    writer.call_method1(py, intern!(py, "write"), (PyBytes::new(bytes),))
+                })?;
+            }
+        }
+    }
+    if let Sink::File(mut file) = sink {
+        file.flush().await?;
+    }
+    match hasher {
+        Some(hasher) => Ok(PyStreamToResult::WithChecksum((
+            bytes_written,
+            format!("{:x}", hasher.finalize()),
+        ))),
+        None => Ok(PyStreamToResult::BytesWritten(bytes_written)),
     }
 }
 
 #[pymethods]
 impl PyGetResult {
     fn bytes(&self, py: Python) -> PyObjectStoreResult<PyBytes> {
-        let get_result = self
-            .0
-            .lock()
-            .unwrap()
-            .take()
-            .ok_or(PyValueError::new_err("Result has already been disposed."))?;
+        let get_result = self.take()?;
+        let progress = self.progress.clone();
         let runtime = get_runtime(py)?;
         py.allow_threads(|| {
-            let bytes = runtime.block_on(get_result.bytes())?;
+            let bytes = runtime.block_on(get_result_bytes(get_result, progress))?;
             Ok::<_, PyObjectStoreError>(PyBytes::new(bytes))
         })
     }
 
     fn bytes_async<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
-        let get_result = self
-            .0
-            .lock()
-            .unwrap()
-            .take()
-            .ok_or(PyValueError::new_err("Result has already been disposed."))?;
+        let get_result = self.take()?;
+        let progress = self.progress.clone();
         pyo3_async_runtimes::tokio::future_into_py(py, async move {
-            let bytes = get_result
-                .bytes()
+            let bytes = get_result_bytes(get_result, progress)
                 .await
                 .map_err(PyObjectStoreError::ObjectStoreError)?;
             Ok(PyBytes::new(bytes))
         })
     }
 
+    #[pyo3(signature = (dtype = None))]
+    fn to_numpy(&self, py: Python, dtype: Option<PyObject>) -> PyResult<PyObject> {
+        let bytes = self.bytes(py)?;
+        bytes_to_numpy(py, bytes, dtype)
+    }
+
+    #[pyo3(signature = (dtype = None))]
+    fn to_numpy_async<'py>(
+        &'py self,
+        py: Python<'py>,
+        dtype: Option<PyObject>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let get_result = self.take()?;
+        let progress = self.progress.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let bytes = get_result_bytes(get_result, progress)
+                .await
+                .map_err(PyObjectStoreError::ObjectStoreError)?;
+            Python::with_gil(|py| bytes_to_numpy(py, PyBytes::new(bytes), dtype))
+        })
+    }
+
+    #[pyo3(signature = (destination, *, checksum = None))]
+    fn stream_to(
+        &self,
+        py: Python,
+        destination: PyStreamDestination,
+        checksum: Option<PyChecksumAlgorithm>,
+    ) -> PyObjectStoreResult<PyStreamToResult> {
+        let get_result = self.take()?;
+        let progress = self.progress.clone();
+        let runtime = get_runtime(py)?;
+        py.allow_threads(|| {
+            runtime.block_on(stream_to(get_result, progress, destination, checksum))
+        })
+    }
+
+    #[pyo3(signature = (destination, *, checksum = None))]
+    fn stream_to_async<'py>(
+        &'py self,
+        py: Python<'py>,
+        destination: PyStreamDestination,
+        checksum: Option<PyChecksumAlgorithm>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let get_result = self.take()?;
+        let progress = self.progress.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            Ok(stream_to(get_result, progress, destination, checksum).await?)
+        })
+    }
+
     #[getter]
     fn attributes(&self) -> PyResult<PyAttributes> {
-        let inner = self.0.lock().unwrap();
+        let inner = self.result.lock().unwrap();
         let inner = inner
             .as_ref()
             .ok_or(PyValueError::new_err("Result has already been disposed."))?;
@@ -170,7 +373,7 @@ impl PyGetResult {
 
     #[getter]
     fn meta(&self) -> PyResult<PyObjectMeta> {
-        let inner = self.0.lock().unwrap();
+        let inner = self.result.lock().unwrap();
         let inner = inner
             .as_ref()
             .ok_or(PyValueError::new_err("Result has already been disposed."))?;
@@ -179,7 +382,7 @@ impl PyGetResult {
 
     #[getter]
     fn range(&self) -> PyResult<(u64, u64)> {
-        let inner = self.0.lock().unwrap();
+        let inner = self.result.lock().unwrap();
         let range = &inner
             .as_ref()
             .ok_or(PyValueError::new_err("Result has already been disposed."))?
@@ -187,23 +390,90 @@ impl PyGetResult {
         Ok((range.start, range.end))
     }
 
-    #[pyo3(signature = (min_chunk_size = DEFAULT_BYTES_CHUNK_SIZE))]
-    fn stream(&self, min_chunk_size: usize) -> PyResult<PyBytesStream> {
-        let get_result = self
-            .0
-            .lock()
-            .unwrap()
-            .take()
-            .ok_or(PyValueError::new_err("Result has already been disposed."))?;
-        Ok(PyBytesStream::new(get_result.into_stream(), min_chunk_size))
+    #[pyo3(signature = (min_chunk_size = DEFAULT_BYTES_CHUNK_SIZE, *, chunk_timeout = None, decompress = None))]
+    fn stream(
+        &self,
+        min_chunk_size: usize,
+        chunk_timeout: Option<Duration>,
+        decompress: Option<PyDecompression>,
+    ) -> PyResult<PyBytesStream> {
+        let get_result = self.take()?;
+        let codec = decompress.and_then(|decompress| {
+            decompress.resolve(&get_result.attributes, &get_result.meta.location)
+        });
+        // Progress is reported in bytes downloaded, before decompression
+        let mut stream = track_stream(get_result.into_stream(), self.progress.clone());
+        if let Some(codec) = codec {
+            stream = decompress_stream(codec, stream, min_chunk_size);
+        }
+        Ok(PyBytesStream::new(stream, min_chunk_size, chunk_timeout))
+    }
+
+    #[pyo3(signature = (chunk_size = DEFAULT_RESPONSE_CHUNK_SIZE, *, chunk_timeout = None))]
+    fn iter_for_response(
+        &self,
+        chunk_size: usize,
+        chunk_timeout: Option<Duration>,
+    ) -> PyResult<PyResponseStream> {
+        let get_result = self.take()?;
+        PyResponseStream::new(get_result, self.progress.clone(), chunk_size, chunk_timeout)
     }
 
     fn __aiter__(&self) -> PyResult<PyBytesStream> {
-        self.stream(DEFAULT_BYTES_CHUNK_SIZE)
+        self.stream(DEFAULT_BYTES_CHUNK_SIZE, None, None)
     }
 
     fn __iter__(&self) -> PyResult<PyBytesStream> {
-        self.stream(DEFAULT_BYTES_CHUNK_SIZE)
+        self.stream(DEFAULT_BYTES_CHUNK_SIZE, None, None)
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "GetResult can't be pickled, as it holds the response to a request. Pickle the store and path instead, or use open_reader(), whose ReadableFile can be pickled.",
+        ))
+    }
+
+    /// Dispose of the result, aborting the download of the body if it hasn't been consumed.
+    fn close(&self, py: Python) {
+        let result = self.result.lock().unwrap().take();
+        // Dropping a streaming body closes its connection
+        py.allow_threads(|| drop(result));
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __aenter__(slf: Py<Self>, py: Python) -> PyResult<Bound<PyAny>> {
+        pyo3_async_runtimes::tokio::future_into_py(py, async move { Ok(slf) })
+    }
+
+    #[allow(unused_variables)]
+    #[pyo3(signature = (exc_type, exc_value, traceback))]
+    fn __exit__(
+        &self,
+        py: Python,
+        exc_type: Option<PyObject>,
+        exc_value: Option<PyObject>,
+        traceback: Option<PyObject>,
+    ) {
+        self.close(py)
+    }
+
+    #[allow(unused_variables)]
+    #[pyo3(signature = (exc_type, exc_value, traceback))]
+    fn __aexit__<'py>(
+        &'py self,
+        py: Python<'py>,
+        exc_type: Option<PyObject>,
+        exc_value: Option<PyObject>,
+        traceback: Option<PyObject>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let result = self.result.lock().unwrap().take();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            drop(result);
+            Ok(())
+        })
     }
 }
 
@@ -213,13 +483,20 @@ impl PyGetResult {
 pub struct PyBytesStream {
     stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<Bytes>>>>>,
     min_chunk_size: usize,
+    /// How long to wait for each piece of data before raising a `StreamTimeoutError`
+    chunk_timeout: Option<Duration>,
 }
 
 impl PyBytesStream {
-    fn new(stream: BoxStream<'static, object_store::Result<Bytes>>, min_chunk_size: usize) -> Self {
+    pub(crate) fn new(
+        stream: BoxStream<'static, object_store::Result<Bytes>>,
+        min_chunk_size: usize,
+        chunk_timeout: Option<Duration>,
+    ) -> Self {
         Self {
             stream: Arc::new(Mutex::new(stream.fuse())),
             min_chunk_size,
+            chunk_timeout,
         }
     }
 }
@@ -227,13 +504,20 @@ impl PyBytesStream {
 async fn next_stream(
     stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<Bytes>>>>>,
     min_chunk_size: usize,
+    chunk_timeout: Option<Duration>,
     sync: bool,
 ) -> PyResult<PyBytesWrapper> {
     let mut stream = stream.lock().await;
     let mut buffers: Vec<Bytes> = vec![];
     let mut total_buffer_len = 0;
     loop {
-        match stream.next().await {
+        let next = match next_within(&mut *stream, chunk_timeout).await {
+            Ok(next) => next,
+            // Return the data received so far rather than dropping it
+            Err(_) if !buffers.is_empty() => return Ok(PyBytesWrapper::new_multiple(buffers)),
+            Err(err) => return Err(err),
+        };
+        match next {
             Some(Ok(bytes)) => {
                 total_buffer_len += bytes.len();
                 buffers.push(bytes);
@@ -265,6 +549,12 @@ impl PyBytesStream {
         slf
     }
 
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "BytesStream can't be pickled, as it holds the response to a request. Pickle the store and path instead, or use open_reader(), whose ReadableFile can be pickled.",
+        ))
+    }
+
     fn __iter__(slf: Py<Self>) -> Py<Self> {
         slf
     }
@@ -273,21 +563,24 @@ impl PyBytesStream {
         let stream = self.stream.clone();
         pyo3_async_runtimes::tokio::future_into_py(
             py,
-            next_stream(stream, self.min_chunk_size, false),
+            next_stream(stream, self.min_chunk_size, self.chunk_timeout, false),
         )
     }
 
     fn __next__<'py>(&'py self, py: Python<'py>) -> PyResult<PyBytesWrapper> {
         let runtime = get_runtime(py)?;
         let stream = self.stream.clone();
-        runtime.block_on(next_stream(stream, self.min_chunk_size, true))
+        let (min_chunk_size, chunk_timeout) = (self.min_chunk_size, self.chunk_timeout);
+        py.allow_threads(|| {
+            runtime.block_on(next_stream(stream, min_chunk_size, chunk_timeout, true))
+        })
     }
 }
 
-struct PyBytesWrapper(Vec<Bytes>);
+pub(crate) struct PyBytesWrapper(Vec<Bytes>);
 
 impl PyBytesWrapper {
-    fn new_multiple(buffers: Vec<Bytes>) -> Self {
+    pub(crate) fn new_multiple(buffers: Vec<Bytes>) -> Self {
         Self(buffers)
     }
 }
@@ -316,15 +609,29 @@ impl<'py> IntoPyObject<'py> for PyBytesWrapper {
     }
 }
 
+/// Create the progress reporter for a `get`, once the size of the response is known.
+fn get_progress(
+    result: &GetResult,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+) -> Option<Arc<PyProgress>> {
+    let total = result.range.end - result.range.start;
+    PyProgress::new(progress, progress_interval, Some(total))
+}
+
 #[pyfunction]
-#[pyo3(signature = (store, path, *, options=None))]
+#[pyo3(signature = (store, path, *, options=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, timeout=None))]
 pub(crate) fn get(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     options: Option<PyGetOptions>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    timeout: Option<Duration>,
 ) -> PyObjectStoreResult<PyGetResult> {
     let runtime = get_runtime(py)?;
+    let store = store.with_timeout(timeout);
     py.allow_threads(|| {
         let path = &path.into();
         let fut = if let Some(options) = options {
@@ -333,18 +640,23 @@ pub(crate) fn get(
             store.as_ref().get(path)
         };
         let out = runtime.block_on(fut)?;
-        Ok::<_, PyObjectStoreError>(PyGetResult::new(out))
+        let progress = get_progress(&out, progress, progress_interval);
+        Ok::<_, PyObjectStoreError>(PyGetResult::new(out, progress))
     })
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, *, options=None))]
+#[pyo3(signature = (store, path, *, options=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, timeout=None))]
 pub(crate) fn get_async(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     options: Option<PyGetOptions>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    timeout: Option<Duration>,
 ) -> PyResult<Bound<PyAny>> {
+    let store = store.with_timeout(timeout);
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
         let path = &path.into();
         let fut = if let Some(options) = options {
@@ -353,46 +665,157 @@ pub(crate) fn get_async(
             store.as_ref().get(path)
         };
         let out = fut.await.map_err(PyObjectStoreError::ObjectStoreError)?;
-        Ok(PyGetResult::new(out))
+        let progress = get_progress(&out, progress, progress_interval);
+        Ok(PyGetResult::new(out, progress))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, etag=None, last_modified=None, *, timeout=None))]
+pub(crate) fn get_if_changed(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    etag: Option<String>,
+    last_modified: Option<DateTime<Utc>>,
+    timeout: Option<Duration>,
+) -> PyObjectStoreResult<Option<PyGetResult>> {
+    let runtime = get_runtime(py)?;
+    let store = store.with_timeout(timeout);
+    py.allow_threads(|| {
+        runtime.block_on(get_if_changed_inner(
+            store.into_inner(),
+            path.into(),
+            etag,
+            last_modified,
+        ))
     })
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, *, start, end=None, length=None))]
+#[pyo3(signature = (store, path, etag=None, last_modified=None, *, timeout=None))]
+pub(crate) fn get_if_changed_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    etag: Option<String>,
+    last_modified: Option<DateTime<Utc>>,
+    timeout: Option<Duration>,
+) -> PyResult<Bound<PyAny>> {
+    let store = store.with_timeout(timeout);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        Ok(get_if_changed_inner(store.into_inner(), path.into(), etag, last_modified).await?)
+    })
+}
+
+/// Fetch the object at `path` unless it still has the ETag `etag`, or hasn't been modified since
+/// `last_modified`, returning `None` in that case instead of a `NotModified` error.
+async fn get_if_changed_inner(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    etag: Option<String>,
+    last_modified: Option<DateTime<Utc>>,
+) -> PyObjectStoreResult<Option<PyGetResult>> {
+    let options = GetOptions {
+        if_none_match: etag,
+        if_modified_since: last_modified,
+        ..Default::default()
+    };
+    match store.get_opts(&path, options).await {
+        Ok(result) => Ok(Some(PyGetResult::new(result, None))),
+        Err(object_store::Error::NotModified { .. }) => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// View `bytes` as a read-only NumPy array of `dtype`, which defaults to `uint8`.
+///
+/// The array is created with `numpy.frombuffer` over the buffer protocol of [PyBytes], so the
+/// downloaded data isn't copied. The buffer is exported as read-only, so the array is too.
+fn bytes_to_numpy(py: Python, bytes: PyBytes, dtype: Option<PyObject>) -> PyResult<PyObject> {
+    let numpy = py.import(intern!(py, "numpy")).map_err(|err| {
+        let import_err = PyImportError::new_err("NumPy is required to return NumPy arrays.");
+        import_err.set_cause(py, Some(err));
+        import_err
+    })?;
+    let kwargs = PyDict::new(py);
+    match dtype {
+        Some(dtype) => kwargs.set_item(intern!(py, "dtype"), dtype)?,
+        None => kwargs.set_item(intern!(py, "dtype"), intern!(py, "uint8"))?,
+    }
+    Ok(numpy
+        .call_method(intern!(py, "frombuffer"), (bytes,), Some(&kwargs))?
+        .unbind())
+}
+
+/// Check that `dtype` is only passed along with `return_numpy`.
+fn validate_numpy_args(return_numpy: bool, dtype: &Option<PyObject>) -> PyResult<()> {
+    if !return_numpy && dtype.is_some() {
+        return Err(PyValueError::new_err(
+            "dtype can only be passed with return_numpy=True.",
+        ));
+    }
+    Ok(())
+}
+
+/// Convert a downloaded range to the object returned to Python.
+fn range_to_py(
+    py: Python,
+    bytes: Bytes,
+    return_numpy: bool,
+    dtype: Option<PyObject>,
+) -> PyResult<PyObject> {
+    let bytes = PyBytes::new(bytes);
+    if return_numpy {
+        bytes_to_numpy(py, bytes, dtype)
+    } else {
+        Ok(bytes.into_pyobject(py)?.into_any().unbind())
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, start, end=None, length=None, return_numpy=false, dtype=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn get_range(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     start: u64,
     end: Option<u64>,
     length: Option<u64>,
-) -> PyObjectStoreResult<pyo3_bytes::PyBytes> {
+    return_numpy: bool,
+    dtype: Option<PyObject>,
+) -> PyObjectStoreResult<PyObject> {
     let runtime = get_runtime(py)?;
     let range = params_to_range(start, end, length)?;
-    py.allow_threads(|| {
-        let out = runtime.block_on(store.as_ref().get_range(&path.into(), range))?;
-        Ok::<_, PyObjectStoreError>(pyo3_bytes::PyBytes::new(out))
-    })
+    validate_numpy_args(return_numpy, &dtype)?;
+    let out =
+        py.allow_threads(|| runtime.block_on(store.as_ref().get_range(&path.into(), range)))?;
+    Ok(range_to_py(py, out, return_numpy, dtype)?)
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, *, start, end=None, length=None))]
+#[pyo3(signature = (store, path, *, start, end=None, length=None, return_numpy=false, dtype=None))]
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn get_range_async(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     start: u64,
     end: Option<u64>,
     length: Option<u64>,
+    return_numpy: bool,
+    dtype: Option<PyObject>,
 ) -> PyResult<Bound<PyAny>> {
     let range = params_to_range(start, end, length)?;
+    validate_numpy_args(return_numpy, &dtype)?;
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
         let out = store
             .as_ref()
             .get_range(&path.into(), range)
             .await
             .map_err(PyObjectStoreError::ObjectStoreError)?;
-        Ok(pyo3_bytes::PyBytes::new(out))
+        Python::with_gil(|py| range_to_py(py, out, return_numpy, dtype))
     })
 }
 
@@ -416,7 +839,7 @@ fn params_to_range(
 pub(crate) fn get_ranges(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     starts: Vec<u64>,
     ends: Option<Vec<u64>>,
     lengths: Option<Vec<u64>>,
@@ -434,7 +857,7 @@ pub(crate) fn get_ranges(
 pub(crate) fn get_ranges_async(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     starts: Vec<u64>,
     ends: Option<Vec<u64>>,
     lengths: Option<Vec<u64>>,
@@ -453,6 +876,209 @@ pub(crate) fn get_ranges_async(
     })
 }
 
+#[pyfunction]
+#[pyo3(signature = (store, path, buffers, *, starts, ends=None, lengths=None))]
+pub(crate) fn read_ranges_into(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    buffers: Vec<PyBuffer<u8>>,
+    starts: Vec<u64>,
+    ends: Option<Vec<u64>>,
+    lengths: Option<Vec<u64>>,
+) -> PyObjectStoreResult<Vec<usize>> {
+    let runtime = get_runtime(py)?;
+    let ranges = params_to_buffer_ranges(&buffers, starts, ends, lengths)?;
+    py.allow_threads(|| {
+        runtime.block_on(read_ranges_into_inner(
+            store.into_inner(),
+            path.into(),
+            &buffers,
+            &ranges,
+        ))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, buffers, *, starts, ends=None, lengths=None))]
+pub(crate) fn read_ranges_into_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    buffers: Vec<PyBuffer<u8>>,
+    starts: Vec<u64>,
+    ends: Option<Vec<u64>>,
+    lengths: Option<Vec<u64>>,
+) -> PyResult<Bound<PyAny>> {
+    let ranges = params_to_buffer_ranges(&buffers, starts, ends, lengths)?;
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        Ok(read_ranges_into_inner(store.into_inner(), path.into(), &buffers, &ranges).await?)
+    })
+}
+
+/// The ranges to read into `buffers`, checking that there's a writable buffer large enough for
+/// each range.
+///
+/// If neither `ends` nor `lengths` is given, each range is the length of its buffer.
+fn params_to_buffer_ranges(
+    buffers: &[PyBuffer<u8>],
+    starts: Vec<u64>,
+    ends: Option<Vec<u64>>,
+    lengths: Option<Vec<u64>>,
+) -> PyObjectStoreResult<Vec<Range<u64>>> {
+    if buffers.len() != starts.len() {
+        return Err(PyValueError::new_err(format!(
+            "Expected one buffer for each of the {} ranges, got {} buffers.",
+            starts.len(),
+            buffers.len()
+        ))
+        .into());
+    }
+    let lengths = match (&ends, lengths) {
+        (None, None) => Some(
+            buffers
+                .iter()
+                .map(|buffer| buffer.item_count() as u64)
+                .collect(),
+        ),
+        (_, lengths) => lengths,
+    };
+    let ranges = params_to_ranges(starts, ends, lengths)?;
+    for (i, (buffer, range)) in buffers.iter().zip(&ranges).enumerate() {
+        if buffer.readonly() || !buffer.is_c_contiguous() {
+            return Err(PyBufferError::new_err(format!(
+                "Buffer {i} must be a C-contiguous writable buffer."
+            ))
+            .into());
+        }
+        let length = range.end - range.start;
+        if (buffer.item_count() as u64) < length {
+            return Err(PyValueError::new_err(format!(
+                "Buffer {i} has {} bytes, which is too small for range {}..{} of {length} bytes.",
+                buffer.item_count(),
+                range.start,
+                range.end
+            ))
+            .into());
+        }
+    }
+    Ok(ranges)
+}
+
+/// Read each of `ranges` into the start of its buffer, returning the number of bytes read into
+/// each, which is less than the requested length for a range that extends past the end of the
+/// object.
+///
+/// As in [ObjectStore::get_ranges], ranges less than [OBJECT_STORE_COALESCE_DEFAULT] apart are
+/// fetched with a single request. The body of each request is copied into the buffers as it's
+/// received, rather than collected into a [Bytes] per range first.
+async fn read_ranges_into_inner(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    buffers: &[PyBuffer<u8>],
+    ranges: &[Range<u64>],
+) -> PyObjectStoreResult<Vec<usize>> {
+    let mut read = vec![0; ranges.len()];
+    let mut requests = futures::stream::iter(coalesce_buffer_ranges(ranges))
+        .map(|(fetch, members)| {
+            read_request_into(store.as_ref(), &path, buffers, ranges, fetch, members)
+        })
+        .buffer_unordered(READ_RANGES_CONCURRENCY);
+    while let Some(members) = requests.try_next().await? {
+        for (index, len) in members {
+            read[index] = len;
+        }
+    }
+    Ok(read)
+}
+
+/// Up to this many requests of [read_ranges_into_inner] are made concurrently, as in
+/// [ObjectStore::get_ranges].
+const READ_RANGES_CONCURRENCY: usize = 10;
+
+/// Group `ranges` into the ranges to fetch, each with the indices of the ranges within it.
+fn coalesce_buffer_ranges(ranges: &[Range<u64>]) -> Vec<(Range<u64>, Vec<usize>)> {
+    let mut indices = (0..ranges.len()).collect::<Vec<_>>();
+    indices.sort_unstable_by_key(|&index| ranges[index].start);
+    let mut fetches: Vec<(Range<u64>, Vec<usize>)> = vec![];
+    for index in indices {
+        let range = &ranges[index];
+        match fetches.last_mut() {
+            Some((fetch, members))
+                if range.start <= fetch.end.saturating_add(OBJECT_STORE_COALESCE_DEFAULT) =>
+            {
+                fetch.end = fetch.end.max(range.end);
+                members.push(index);
+            }
+            _ => fetches.push((range.clone(), vec![index])),
+        }
+    }
+    fetches
+}
+
+/// Fetch `fetch`, copying each chunk of its body into the buffers of the `members` it overlaps,
+/// and return the number of bytes read into each member.
+async fn read_request_into(
+    store: &dyn ObjectStore,
+    path: &Path,
+    buffers: &[PyBuffer<u8>],
+    ranges: &[Range<u64>],
+    fetch: Range<u64>,
+    members: Vec<usize>,
+) -> PyObjectStoreResult<Vec<(usize, usize)>> {
+    let mut read = members
+        .into_iter()
+        .map(|index| (index, 0))
+        .collect::<Vec<_>>();
+    // An empty range can't be requested, and has nothing to read
+    if fetch.is_empty() {
+        return Ok(read);
+    }
+    let options = GetOptions {
+        range: Some(fetch.clone().into()),
+        ..Default::default()
+    };
+    let mut stream = store.get_opts(path, options).await?.into_stream();
+    let mut offset = fetch.start;
+    while let Some(chunk) = stream.try_next().await? {
+        let chunk_range = offset..offset + chunk.len() as u64;
+        Python::with_gil(|py| {
+            for (index, len) in read.iter_mut() {
+                *len +=
+                    copy_chunk_into(py, &buffers[*index], &ranges[*index], &chunk_range, &chunk)?;
+            }
+            Ok::<_, PyErr>(())
+        })?;
+        offset = chunk_range.end;
+    }
+    Ok(read)
+}
+
+/// Copy the part of `chunk`, which holds `chunk_range` of the object, that overlaps `range` into
+/// `buffer`, which holds `range`, returning the number of bytes copied.
+fn copy_chunk_into(
+    py: Python,
+    buffer: &PyBuffer<u8>,
+    range: &Range<u64>,
+    chunk_range: &Range<u64>,
+    chunk: &Bytes,
+) -> PyResult<usize> {
+    let start = range.start.max(chunk_range.start);
+    let end = range.end.min(chunk_range.end);
+    if start >= end {
+        return Ok(0);
+    }
+    let slice = buffer
+        .as_mut_slice(py)
+        .ok_or_else(|| PyBufferError::new_err("Buffers must be C-contiguous writable buffers."))?;
+    let dest = &slice[(start - range.start) as usize..(end - range.start) as usize];
+    let src = &chunk[(start - chunk_range.start) as usize..(end - chunk_range.start) as usize];
+    for (cell, byte) in dest.iter().zip(src) {
+        cell.set(*byte);
+    }
+    Ok(src.len())
+}
+
 fn params_to_ranges(
     starts: Vec<u64>,
     ends: Option<Vec<u64>>,
@@ -1,28 +1,66 @@
+use std::time::Duration;
+
+use object_store::path::Path;
+use object_store::{GetOptions, ObjectMeta, ObjectStore};
 use pyo3::prelude::*;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+use pyo3_object_store::{
+    PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath, RequestHeaders,
+};
 
 use crate::list::PyObjectMeta;
 use crate::runtime::get_runtime;
 
 #[pyfunction]
-pub fn head(py: Python, store: PyObjectStore, path: String) -> PyObjectStoreResult<PyObjectMeta> {
+#[pyo3(signature = (store, path, *, headers=None, timeout=None))]
+pub fn head(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    headers: Option<RequestHeaders>,
+    timeout: Option<Duration>,
+) -> PyObjectStoreResult<PyObjectMeta> {
     let runtime = get_runtime(py)?;
-    let store = store.into_inner();
+    let store = store.with_timeout(timeout).into_inner();
 
     py.allow_threads(|| {
-        let meta = runtime.block_on(store.head(&path.into()))?;
+        let meta = runtime.block_on(head_inner(store.as_ref(), &path.into(), headers))?;
         Ok::<_, PyObjectStoreError>(PyObjectMeta::new(meta))
     })
 }
 
 #[pyfunction]
-pub fn head_async(py: Python, store: PyObjectStore, path: String) -> PyResult<Bound<PyAny>> {
-    let store = store.into_inner().clone();
+#[pyo3(signature = (store, path, *, headers=None, timeout=None))]
+pub fn head_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    headers: Option<RequestHeaders>,
+    timeout: Option<Duration>,
+) -> PyResult<Bound<PyAny>> {
+    let store = store.with_timeout(timeout).into_inner();
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let meta = store
-            .head(&path.into())
+        let meta = head_inner(store.as_ref(), &path.into(), headers)
             .await
             .map_err(PyObjectStoreError::ObjectStoreError)?;
         Ok(PyObjectMeta::new(meta))
     })
 }
+
+/// Make a `HEAD` request, sending `headers` with it if given.
+async fn head_inner(
+    store: &dyn ObjectStore,
+    path: &Path,
+    headers: Option<RequestHeaders>,
+) -> object_store::Result<ObjectMeta> {
+    match headers {
+        Some(headers) => {
+            let options = GetOptions {
+                head: true,
+                extensions: headers.into_extensions(),
+                ..Default::default()
+            };
+            Ok(store.get_opts(path, options).await?.meta)
+        }
+        None => store.head(path).await,
+    }
+}
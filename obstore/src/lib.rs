@@ -1,21 +1,48 @@
 // Except for explicit areas where we enable unsafe
 #![deny(unsafe_code)]
 
+mod archive;
 mod attributes;
 mod buffered;
+mod compression;
+mod concat;
+mod conditional;
 mod copy;
 mod delete;
+mod dfs;
+mod diagnostics;
+mod diff;
+mod download;
+mod events;
+mod expand;
+mod filesystem;
 mod get;
 mod head;
 mod list;
+mod manifest;
+mod metadata;
+mod multipart;
 mod path;
+mod probe;
+mod progress;
 mod put;
+mod put_dir;
 mod rename;
+mod requests;
+mod response;
+mod resumable;
 mod runtime;
 mod scheme;
+mod serialize;
+mod serve;
 mod signer;
+mod sort;
 mod tags;
+mod trace;
+mod tree;
 mod utils;
+mod versions;
+mod watch;
 
 use pyo3::prelude::*;
 
@@ -43,10 +70,28 @@ fn check_debug_build(_py: Python) -> PyResult<()> {
     Ok(())
 }
 
+/// Forward the log records of obstore, such as those of requests logged with the `log_requests`
+/// client option, to Python's `logging` module.
+///
+/// Records of other crates are only forwarded from the warning level, as the HTTP stack is very
+/// verbose at lower levels.
+fn init_logging(py: Python) -> PyResult<()> {
+    let logger = pyo3_log::Logger::new(py, pyo3_log::Caching::Loggers)?
+        .filter(log::LevelFilter::Warn)
+        .filter_target("obstore".to_owned(), log::LevelFilter::Trace);
+    // Another extension module may already have installed a logger, which we then leave in place
+    let _ = logger.install();
+    Ok(())
+}
+
 /// A Python module implemented in Rust.
-#[pymodule]
+///
+/// The module doesn't rely on the GIL for thread safety, so that it can be imported on
+/// free-threaded Python without the interpreter enabling the GIL.
+#[pymodule(gil_used = false)]
 fn _obstore(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     check_debug_build(py)?;
+    init_logging(py)?;
 
     m.add("__version__", VERSION)?;
     m.add("_object_store_version", OBJECT_STORE_VERSION)?;
@@ -54,37 +99,117 @@ fn _obstore(py: Python, m: &Bound<PyModule>) -> PyResult<()> {
 
     pyo3_object_store::register_store_module(py, m, "obstore", "_store")?;
     pyo3_object_store::register_exceptions_module(py, m, "obstore", "exceptions")?;
+    path::register_path_module(py, m, "obstore", "path")?;
+    runtime::register_runtime_module(py, m, "obstore", "runtime")?;
 
     m.add_class::<pyo3_bytes::PyBytes>()?;
+    m.add_class::<filesystem::PyObstoreFileSystem>()?;
+    m.add_class::<list::PyListResult>()?;
+    m.add_class::<list::PyObjectMetaClass>()?;
+    m.add_class::<put::PyPutResult>()?;
     // Set the value of `__module__` correctly on PyBytes
     m.getattr("Bytes")?.setattr("__module__", "obstore")?;
 
+    m.add_wrapped(wrap_pyfunction!(archive::archive_async))?;
+    m.add_wrapped(wrap_pyfunction!(archive::archive))?;
+    m.add_wrapped(wrap_pyfunction!(archive::extract_async))?;
+    m.add_wrapped(wrap_pyfunction!(archive::extract))?;
     m.add_wrapped(wrap_pyfunction!(buffered::open_reader))?;
     m.add_wrapped(wrap_pyfunction!(buffered::open_reader_async))?;
     m.add_wrapped(wrap_pyfunction!(buffered::open_writer))?;
+    m.add_wrapped(wrap_pyfunction!(buffered::restore_readable_file))?;
     m.add_wrapped(wrap_pyfunction!(buffered::open_writer_async))?;
+    m.add_wrapped(wrap_pyfunction!(concat::concat))?;
+    m.add_wrapped(wrap_pyfunction!(conditional::put_if_absent_async))?;
+    m.add_wrapped(wrap_pyfunction!(conditional::put_if_absent))?;
+    m.add_wrapped(wrap_pyfunction!(conditional::update_async))?;
+    m.add_wrapped(wrap_pyfunction!(conditional::update))?;
     m.add_wrapped(wrap_pyfunction!(copy::copy_async))?;
     m.add_wrapped(wrap_pyfunction!(copy::copy))?;
     m.add_wrapped(wrap_pyfunction!(delete::delete_async))?;
     m.add_wrapped(wrap_pyfunction!(delete::delete))?;
+    m.add_wrapped(wrap_pyfunction!(delete::delete_prefix_async))?;
+    m.add_wrapped(wrap_pyfunction!(delete::delete_prefix))?;
+    m.add_wrapped(wrap_pyfunction!(diagnostics::diagnostics))?;
+    m.add_wrapped(wrap_pyfunction!(diff::diff_async))?;
+    m.add_wrapped(wrap_pyfunction!(diff::diff))?;
+    m.add_wrapped(wrap_pyfunction!(download::download_async))?;
+    m.add_wrapped(wrap_pyfunction!(download::download))?;
+    m.add_wrapped(wrap_pyfunction!(events::parse_events))?;
+    m.add_wrapped(wrap_pyfunction!(expand::expand_paths_async))?;
+    m.add_wrapped(wrap_pyfunction!(expand::expand_paths))?;
     m.add_wrapped(wrap_pyfunction!(get::get_async))?;
+    m.add_wrapped(wrap_pyfunction!(get::get_if_changed_async))?;
+    m.add_wrapped(wrap_pyfunction!(get::get_if_changed))?;
     m.add_wrapped(wrap_pyfunction!(get::get_range_async))?;
     m.add_wrapped(wrap_pyfunction!(get::get_range))?;
     m.add_wrapped(wrap_pyfunction!(get::get_ranges_async))?;
     m.add_wrapped(wrap_pyfunction!(get::get_ranges))?;
     m.add_wrapped(wrap_pyfunction!(get::get))?;
+    m.add_wrapped(wrap_pyfunction!(get::read_ranges_into_async))?;
+    m.add_wrapped(wrap_pyfunction!(get::read_ranges_into))?;
     m.add_wrapped(wrap_pyfunction!(head::head_async))?;
     m.add_wrapped(wrap_pyfunction!(head::head))?;
     m.add_wrapped(wrap_pyfunction!(list::list_with_delimiter_async))?;
     m.add_wrapped(wrap_pyfunction!(list::list_with_delimiter))?;
+    m.add_wrapped(wrap_pyfunction!(list::list_page_async))?;
+    m.add_wrapped(wrap_pyfunction!(list::list_page))?;
     m.add_wrapped(wrap_pyfunction!(list::list))?;
+    m.add_wrapped(wrap_pyfunction!(manifest::verify_async))?;
+    m.add_wrapped(wrap_pyfunction!(manifest::verify))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::get_attributes_async))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::get_attributes))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::get_tags_async))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::get_tags))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::put_attributes_async))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::put_attributes))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::put_tags_async))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::put_tags))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::set_tier_async))?;
+    m.add_wrapped(wrap_pyfunction!(metadata::set_tier))?;
+    m.add_wrapped(wrap_pyfunction!(multipart::create_multipart_upload_async))?;
+    m.add_wrapped(wrap_pyfunction!(multipart::create_multipart_upload))?;
+    m.add_wrapped(wrap_pyfunction!(multipart::resume_multipart_upload_async))?;
+    m.add_wrapped(wrap_pyfunction!(multipart::resume_multipart_upload))?;
+    m.add_wrapped(wrap_pyfunction!(probe::probe_async))?;
+    m.add_wrapped(wrap_pyfunction!(probe::probe))?;
     m.add_wrapped(wrap_pyfunction!(put::put_async))?;
     m.add_wrapped(wrap_pyfunction!(put::put))?;
+    m.add_wrapped(wrap_pyfunction!(put::put_slices_async))?;
+    m.add_wrapped(wrap_pyfunction!(put::put_slices))?;
+    m.add_wrapped(wrap_pyfunction!(put_dir::put_dir_async))?;
+    m.add_wrapped(wrap_pyfunction!(put_dir::put_dir))?;
     m.add_wrapped(wrap_pyfunction!(rename::rename_async))?;
     m.add_wrapped(wrap_pyfunction!(rename::rename))?;
+    m.add_wrapped(wrap_pyfunction!(resumable::resumable_upload_offset_async))?;
+    m.add_wrapped(wrap_pyfunction!(resumable::resumable_upload_offset))?;
+    m.add_wrapped(wrap_pyfunction!(resumable::start_resumable_upload_async))?;
+    m.add_wrapped(wrap_pyfunction!(resumable::start_resumable_upload))?;
+    m.add_wrapped(wrap_pyfunction!(resumable::upload_resumable_chunk_async))?;
+    m.add_wrapped(wrap_pyfunction!(resumable::upload_resumable_chunk))?;
     m.add_wrapped(wrap_pyfunction!(scheme::parse_scheme))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::get_json_async))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::get_json))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::get_pickle_async))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::get_pickle))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::put_json_async))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::put_json))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::put_pickle_async))?;
+    m.add_wrapped(wrap_pyfunction!(serialize::put_pickle))?;
+    m.add_wrapped(wrap_pyfunction!(serve::serve))?;
     m.add_wrapped(wrap_pyfunction!(signer::sign_async))?;
+    m.add_wrapped(wrap_pyfunction!(signer::sign_post_async))?;
+    m.add_wrapped(wrap_pyfunction!(signer::sign_post))?;
     m.add_wrapped(wrap_pyfunction!(signer::sign))?;
+    m.add_wrapped(wrap_pyfunction!(tree::distinct_prefixes_async))?;
+    m.add_wrapped(wrap_pyfunction!(tree::distinct_prefixes))?;
+    m.add_wrapped(wrap_pyfunction!(tree::stat_prefix_async))?;
+    m.add_wrapped(wrap_pyfunction!(tree::stat_prefix))?;
+    m.add_wrapped(wrap_pyfunction!(tree::tree_async))?;
+    m.add_wrapped(wrap_pyfunction!(tree::tree))?;
+    m.add_wrapped(wrap_pyfunction!(versions::list_versions_async))?;
+    m.add_wrapped(wrap_pyfunction!(versions::list_versions))?;
+    m.add_wrapped(wrap_pyfunction!(watch::watch))?;
 
     Ok(())
 }
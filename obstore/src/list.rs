@@ -1,60 +1,231 @@
+use std::collections::{HashMap, VecDeque};
 use std::ops::AddAssign;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use arrow::array::{
-    ArrayRef, RecordBatch, StringBuilder, TimestampMicrosecondBuilder, UInt64Builder,
+    Array, ArrayRef, MapBuilder, RecordBatch, StringBuilder, TimestampMicrosecondBuilder,
+    UInt64Builder,
 };
 use arrow::datatypes::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow::error::ArrowError;
+use arrow::record_batch::RecordBatchReader;
+use chrono::{DateTime, SubsecRound, Utc};
 use futures::stream::{BoxStream, Fuse};
-use futures::StreamExt;
+use futures::{StreamExt, TryStreamExt};
 use indexmap::IndexMap;
 use object_store::path::Path;
 use object_store::{ListResult, ObjectMeta, ObjectStore};
-use pyo3::exceptions::{PyImportError, PyStopAsyncIteration, PyStopIteration};
+use pyo3::exceptions::{
+    PyImportError, PyKeyError, PyStopAsyncIteration, PyStopIteration, PyValueError,
+};
 use pyo3::prelude::*;
-use pyo3::types::PyDict;
+use pyo3::pybacked::PyBackedStr;
+use pyo3::types::{PyCapsule, PyDict, PyTuple};
 use pyo3::{intern, IntoPyObjectExt};
-use pyo3_arrow::{PyRecordBatch, PyTable};
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+use pyo3_arrow::{PyRecordBatch, PyRecordBatchReader, PyTable};
+use pyo3_object_store::{
+    check_keys, resolve_store_token, PyAzureStore, PyGCSStore, PyMemoryStore, PyObjectStore,
+    PyObjectStoreError, PyObjectStoreResult, PyPath, PyS3Store,
+};
+use tokio::runtime::Runtime;
 use tokio::sync::Mutex;
 
+use crate::metadata::AzureListStore;
 use crate::runtime::get_runtime;
+use crate::sort::{sort_stream, PyListOrder, SortOrder, DEFAULT_SORT_MEMORY_LIMIT};
+use crate::utils::next_within;
+
+/// Whether object metadata is returned to Python as a plain `dict` instead of an
+/// [`ObjectMeta`][PyObjectMetaClass] instance.
+static OBJECT_META_DICTS: AtomicBool = AtomicBool::new(false);
 
-pub(crate) struct PyObjectMeta(ObjectMeta);
+/// Set whether object metadata is returned to Python as a plain `dict`.
+pub(crate) fn set_object_meta_dicts(dicts: bool) {
+    OBJECT_META_DICTS.store(dicts, Ordering::Relaxed);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PyObjectMeta {
+    meta: ObjectMeta,
+    /// User-defined metadata, only set when it was requested while listing.
+    metadata: Option<IndexMap<String, String>>,
+}
 
 impl PyObjectMeta {
     pub(crate) fn new(meta: ObjectMeta) -> Self {
-        Self(meta)
+        Self {
+            meta,
+            metadata: None,
+        }
+    }
+
+    pub(crate) fn with_metadata(meta: ObjectMeta, metadata: IndexMap<String, String>) -> Self {
+        Self {
+            meta,
+            metadata: Some(metadata),
+        }
+    }
+
+    /// The user-defined metadata of the object, if it was requested while listing.
+    pub(crate) fn metadata(&self) -> Option<&IndexMap<String, String>> {
+        self.metadata.as_ref()
+    }
+
+    fn into_dict(self, py: Python) -> PyResult<Bound<PyDict>> {
+        let mut dict = IndexMap::with_capacity(6);
+        // Note, this uses "path" instead of "location" because we standardize the API to accept
+        // the keyword "path" everywhere.
+        dict.insert("path", self.meta.location.as_ref().into_bound_py_any(py)?);
+        dict.insert(
+            "last_modified",
+            self.meta.last_modified.into_bound_py_any(py)?,
+        );
+        dict.insert("size", self.meta.size.into_bound_py_any(py)?);
+        dict.insert("e_tag", self.meta.e_tag.into_bound_py_any(py)?);
+        dict.insert("version", self.meta.version.into_bound_py_any(py)?);
+        if let Some(metadata) = self.metadata {
+            dict.insert("metadata", metadata.into_bound_py_any(py)?);
+        }
+        dict.into_pyobject(py)
     }
 }
 
 impl AsRef<ObjectMeta> for PyObjectMeta {
     fn as_ref(&self) -> &ObjectMeta {
-        &self.0
+        &self.meta
     }
 }
 
 impl From<ObjectMeta> for PyObjectMeta {
     fn from(value: ObjectMeta) -> Self {
-        Self(value)
+        Self::new(value)
     }
 }
 
 impl<'py> IntoPyObject<'py> for PyObjectMeta {
-    type Target = PyDict;
-    type Output = Bound<'py, PyDict>;
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
     type Error = PyErr;
 
     fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        let mut dict = IndexMap::with_capacity(5);
-        // Note, this uses "path" instead of "location" because we standardize the API to accept
-        // the keyword "path" everywhere.
-        dict.insert("path", self.0.location.as_ref().into_bound_py_any(py)?);
-        dict.insert("last_modified", self.0.last_modified.into_bound_py_any(py)?);
-        dict.insert("size", self.0.size.into_bound_py_any(py)?);
-        dict.insert("e_tag", self.0.e_tag.into_bound_py_any(py)?);
-        dict.insert("version", self.0.version.into_bound_py_any(py)?);
-        dict.into_pyobject(py)
+        if OBJECT_META_DICTS.load(Ordering::Relaxed) {
+            self.into_dict(py).map(Bound::into_any)
+        } else {
+            PyObjectMetaClass(self).into_bound_py_any(py)
+        }
+    }
+}
+
+/// The metadata that describes an object, exposed to Python as `obstore.ObjectMeta`.
+#[pyclass(name = "ObjectMeta", module = "obstore", frozen, eq)]
+pub(crate) struct PyObjectMetaClass(PyObjectMeta);
+
+/// Instances compare at the microsecond precision of Python datetimes, so that an `ObjectMeta`
+/// equals one rebuilt from its own fields, as when it's pickled.
+impl PartialEq for PyObjectMetaClass {
+    fn eq(&self, other: &Self) -> bool {
+        let (a, b) = (&self.0, &other.0);
+        a.meta.location == b.meta.location
+            && a.meta.last_modified.trunc_subsecs(6) == b.meta.last_modified.trunc_subsecs(6)
+            && a.meta.size == b.meta.size
+            && a.meta.e_tag == b.meta.e_tag
+            && a.meta.version == b.meta.version
+            && a.metadata == b.metadata
+    }
+}
+
+#[pymethods]
+impl PyObjectMetaClass {
+    #[new]
+    #[pyo3(signature = (path, last_modified, size, *, e_tag=None, version=None, metadata=None))]
+    fn py_new(
+        path: String,
+        last_modified: DateTime<Utc>,
+        size: u64,
+        e_tag: Option<String>,
+        version: Option<String>,
+        metadata: Option<IndexMap<String, String>>,
+    ) -> Self {
+        let meta = ObjectMeta {
+            location: path.into(),
+            last_modified,
+            size,
+            e_tag,
+            version,
+        };
+        Self(PyObjectMeta { meta, metadata })
+    }
+
+    /// Look up a field by name, as with the `dict` returned by earlier versions.
+    fn __getitem__<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        let meta = &self.0.meta;
+        match key {
+            "path" => meta.location.as_ref().into_bound_py_any(py),
+            "last_modified" => meta.last_modified.into_bound_py_any(py),
+            "size" => meta.size.into_bound_py_any(py),
+            "e_tag" => meta.e_tag.as_deref().into_bound_py_any(py),
+            "version" => meta.version.as_deref().into_bound_py_any(py),
+            "metadata" if self.0.metadata.is_some() => self.metadata(py),
+            _ => Err(PyKeyError::new_err(key.to_string())),
+        }
+    }
+
+    fn __getnewargs_ex__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        let meta = &self.0.meta;
+        let args = (meta.location.as_ref(), meta.last_modified, meta.size).into_pyobject(py)?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "e_tag"), meta.e_tag.as_deref())?;
+        kwargs.set_item(intern!(py, "version"), meta.version.as_deref())?;
+        kwargs.set_item(intern!(py, "metadata"), self.metadata(py)?)?;
+        (args, kwargs).into_pyobject(py)
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        let meta = &self.0.meta;
+        Ok(format!(
+            "ObjectMeta(path={}, last_modified={}, size={}, e_tag={}, version={})",
+            meta.location.as_ref().into_pyobject(py)?.repr()?,
+            meta.last_modified.into_pyobject(py)?.repr()?,
+            meta.size,
+            meta.e_tag.as_deref().into_bound_py_any(py)?.repr()?,
+            meta.version.as_deref().into_bound_py_any(py)?.repr()?,
+        ))
+    }
+
+    #[getter]
+    fn path(&self) -> &str {
+        self.0.meta.location.as_ref()
+    }
+
+    #[getter]
+    fn last_modified(&self) -> DateTime<Utc> {
+        self.0.meta.last_modified
+    }
+
+    #[getter]
+    fn size(&self) -> u64 {
+        self.0.meta.size
+    }
+
+    #[getter]
+    fn e_tag(&self) -> Option<&str> {
+        self.0.meta.e_tag.as_deref()
+    }
+
+    #[getter]
+    fn version(&self) -> Option<&str> {
+        self.0.meta.version.as_deref()
+    }
+
+    #[getter]
+    fn metadata<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        self.0.metadata.as_ref().into_bound_py_any(py)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        self.0.clone().into_dict(py)
     }
 }
 
@@ -76,25 +247,52 @@ impl<'py> IntoPyObject<'py> for PyObjectMeta {
 // - https://docs.rs/futures/latest/futures/prelude/stream/trait.StreamExt.html#method.fuse
 #[pyclass(name = "ListStream", frozen)]
 pub(crate) struct PyListStream {
-    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<ObjectMeta>>>>>,
+    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<PyObjectMeta>>>>>,
     chunk_size: usize,
+    /// How long to wait for each entry before raising a `StreamTimeoutError`
+    chunk_timeout: Option<Duration>,
     return_arrow: bool,
+    include_metadata: bool,
+    invalid_keys: InvalidKeys,
 }
 
 impl PyListStream {
     fn new(
-        stream: BoxStream<'static, object_store::Result<ObjectMeta>>,
+        stream: BoxStream<'static, object_store::Result<PyObjectMeta>>,
         chunk_size: usize,
+        chunk_timeout: Option<Duration>,
         return_arrow: bool,
+        include_metadata: bool,
+        invalid_keys: InvalidKeys,
     ) -> Self {
         Self {
             stream: Arc::new(Mutex::new(stream.fuse())),
             chunk_size,
+            chunk_timeout,
             return_arrow,
+            include_metadata,
+            invalid_keys,
+        }
+    }
+
+    fn format(&self) -> ListFormat {
+        if self.return_arrow {
+            ListFormat::Arrow {
+                include_metadata: self.include_metadata,
+            }
+        } else {
+            ListFormat::Native
         }
     }
 }
 
+/// How chunks of list results are converted for Python.
+#[derive(Debug, Clone, Copy)]
+enum ListFormat {
+    Native,
+    Arrow { include_metadata: bool },
+}
+
 #[pymethods]
 impl PyListStream {
     fn __aiter__(slf: Py<Self>) -> Py<Self> {
@@ -105,34 +303,104 @@ impl PyListStream {
         slf
     }
 
+    /// The keys of the entries skipped or converted lossily so far.
+    #[getter]
+    fn invalid_keys(&self) -> Vec<String> {
+        self.invalid_keys.lock().unwrap().clone()
+    }
+
     fn collect(&self, py: Python) -> PyResult<PyListIterResult> {
         let runtime = get_runtime(py)?;
         let stream = self.stream.clone();
-        runtime.block_on(collect_stream(stream, self.return_arrow))
+        let (chunk_timeout, format) = (self.chunk_timeout, self.format());
+        py.allow_threads(|| runtime.block_on(collect_stream(stream, chunk_timeout, format)))
     }
 
     fn collect_async<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let stream = self.stream.clone();
-        pyo3_async_runtimes::tokio::future_into_py(py, collect_stream(stream, self.return_arrow))
+        pyo3_async_runtimes::tokio::future_into_py(
+            py,
+            collect_stream(stream, self.chunk_timeout, self.format()),
+        )
     }
 
     fn __anext__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
         let stream = self.stream.clone();
         pyo3_async_runtimes::tokio::future_into_py(
             py,
-            next_stream(stream, self.chunk_size, false, self.return_arrow),
+            next_stream(
+                stream,
+                self.chunk_size,
+                self.chunk_timeout,
+                false,
+                self.format(),
+            ),
         )
     }
 
     fn __next__<'py>(&'py self, py: Python<'py>) -> PyResult<PyListIterResult> {
         let runtime = get_runtime(py)?;
         let stream = self.stream.clone();
-        runtime.block_on(next_stream(
-            stream,
+        let (chunk_size, chunk_timeout, format) =
+            (self.chunk_size, self.chunk_timeout, self.format());
+        py.allow_threads(|| {
+            runtime.block_on(next_stream(stream, chunk_size, chunk_timeout, true, format))
+        })
+    }
+
+    /// Export the remainder of the stream as an Arrow C Stream.
+    ///
+    /// Batches are fetched lazily as the consumer pulls from the stream, each with up to
+    /// `chunk_size` rows.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_stream__<'py>(
+        &'py self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyCapsule>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let reader = ListStreamReader {
+            stream: self.stream.clone(),
+            chunk_size: self.chunk_size,
+            chunk_timeout: self.chunk_timeout,
+            runtime: get_runtime(py)?,
+            include_metadata: self.include_metadata,
+            schema: object_meta_schema(self.include_metadata),
+        };
+        PyRecordBatchReader::new(Box::new(reader))
+            .into_pyobject(py)?
+            .call_method1(intern!(py, "__arrow_c_stream__"), (requested_schema,))
+    }
+}
+
+/// A [`RecordBatchReader`] that pulls batches from a list stream on demand.
+struct ListStreamReader {
+    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<PyObjectMeta>>>>>,
+    chunk_size: usize,
+    chunk_timeout: Option<Duration>,
+    runtime: &'static Runtime,
+    include_metadata: bool,
+    schema: SchemaRef,
+}
+
+impl Iterator for ListStreamReader {
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.runtime.block_on(next_chunk(
+            self.stream.clone(),
             self.chunk_size,
-            true,
-            self.return_arrow,
-        ))
+            self.chunk_timeout,
+        )) {
+            Ok(metas) if metas.is_empty() => None,
+            Ok(metas) => Some(Ok(object_meta_to_batch(&metas, self.include_metadata))),
+            Err(err) => Some(Err(ArrowError::ExternalError(Box::new(err)))),
+        }
+    }
+}
+
+impl RecordBatchReader for ListStreamReader {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
     }
 }
 
@@ -142,86 +410,89 @@ enum PyListIterResult {
     Native(Vec<PyObjectMeta>),
 }
 
-async fn next_stream(
-    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<ObjectMeta>>>>>,
+impl PyListIterResult {
+    fn new(metas: Vec<PyObjectMeta>, format: ListFormat) -> Self {
+        match format {
+            ListFormat::Native => Self::Native(metas),
+            ListFormat::Arrow { include_metadata } => {
+                Self::Arrow(object_meta_to_arrow(&metas, include_metadata))
+            }
+        }
+    }
+}
+
+/// Pull up to `chunk_size` items from the stream.
+///
+/// An empty result means that the stream is exhausted. If no item arrives within
+/// `chunk_timeout`, the items pulled so far are returned, or a `StreamTimeoutError` is raised if
+/// there are none.
+async fn next_chunk(
+    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<PyObjectMeta>>>>>,
     chunk_size: usize,
-    sync: bool,
-    return_arrow: bool,
-) -> PyResult<PyListIterResult> {
+    chunk_timeout: Option<Duration>,
+) -> PyResult<Vec<PyObjectMeta>> {
     let mut stream = stream.lock().await;
     let mut metas: Vec<PyObjectMeta> = vec![];
-    loop {
-        match stream.next().await {
-            Some(Ok(meta)) => {
-                metas.push(PyObjectMeta(meta));
-                if metas.len() >= chunk_size {
-                    match return_arrow {
-                        true => {
-                            return Ok(PyListIterResult::Arrow(object_meta_to_arrow(&metas)));
-                        }
-                        false => {
-                            return Ok(PyListIterResult::Native(metas));
-                        }
-                    }
-                }
-            }
-            Some(Err(e)) => return Err(PyObjectStoreError::from(e).into()),
-            None => {
-                if metas.is_empty() {
-                    // Depending on whether the iteration is sync or not, we raise either a
-                    // StopIteration or a StopAsyncIteration
-                    if sync {
-                        return Err(PyStopIteration::new_err("stream exhausted"));
-                    } else {
-                        return Err(PyStopAsyncIteration::new_err("stream exhausted"));
-                    }
-                } else {
-                    match return_arrow {
-                        true => {
-                            return Ok(PyListIterResult::Arrow(object_meta_to_arrow(&metas)));
-                        }
-                        false => {
-                            return Ok(PyListIterResult::Native(metas));
-                        }
-                    }
-                }
-            }
+    while metas.len() < chunk_size.max(1) {
+        let next = match next_within(&mut *stream, chunk_timeout).await {
+            Ok(next) => next,
+            Err(_) if !metas.is_empty() => break,
+            Err(err) => return Err(err),
         };
+        match next {
+            Some(meta) => metas.push(meta.map_err(PyObjectStoreError::from)?),
+            None => break,
+        }
     }
+    Ok(metas)
+}
+
+async fn next_stream(
+    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<PyObjectMeta>>>>>,
+    chunk_size: usize,
+    chunk_timeout: Option<Duration>,
+    sync: bool,
+    format: ListFormat,
+) -> PyResult<PyListIterResult> {
+    let metas = next_chunk(stream, chunk_size, chunk_timeout).await?;
+    if metas.is_empty() {
+        // Depending on whether the iteration is sync or not, we raise either a
+        // StopIteration or a StopAsyncIteration
+        if sync {
+            return Err(PyStopIteration::new_err("stream exhausted"));
+        } else {
+            return Err(PyStopAsyncIteration::new_err("stream exhausted"));
+        }
+    }
+    Ok(PyListIterResult::new(metas, format))
 }
 
 async fn collect_stream(
-    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<ObjectMeta>>>>>,
-    return_arrow: bool,
+    stream: Arc<Mutex<Fuse<BoxStream<'static, object_store::Result<PyObjectMeta>>>>>,
+    chunk_timeout: Option<Duration>,
+    format: ListFormat,
 ) -> PyResult<PyListIterResult> {
     let mut stream = stream.lock().await;
     let mut metas: Vec<PyObjectMeta> = vec![];
     loop {
-        match stream.next().await {
+        match next_within(&mut *stream, chunk_timeout).await? {
             Some(Ok(meta)) => {
-                metas.push(PyObjectMeta(meta));
+                metas.push(meta);
             }
             Some(Err(e)) => return Err(PyObjectStoreError::from(e).into()),
-            None => match return_arrow {
-                true => {
-                    return Ok(PyListIterResult::Arrow(object_meta_to_arrow(&metas)));
-                }
-                false => {
-                    return Ok(PyListIterResult::Native(metas));
-                }
-            },
+            None => return Ok(PyListIterResult::new(metas, format)),
         };
     }
 }
 
-struct PyRecordBatchWrapper(PyRecordBatch);
+pub(crate) struct PyRecordBatchWrapper(PyRecordBatch);
 
 impl PyRecordBatchWrapper {
-    fn new(batch: RecordBatch) -> Self {
+    pub(crate) fn new(batch: RecordBatch) -> Self {
         Self(PyRecordBatch::new(batch))
     }
 
-    fn into_table(self) -> PyResult<PyTableWrapper> {
+    pub(crate) fn into_table(self) -> PyResult<PyTableWrapper> {
         let batch = self.0.into_inner();
         let schema = batch.schema();
         PyTableWrapper::new(vec![batch], schema)
@@ -243,10 +514,10 @@ impl<'py> IntoPyObject<'py> for PyRecordBatchWrapper {
     }
 }
 
-struct PyTableWrapper(PyTable);
+pub(crate) struct PyTableWrapper(PyTable);
 
 impl PyTableWrapper {
-    fn new(batches: Vec<RecordBatch>, schema: SchemaRef) -> PyResult<Self> {
+    pub(crate) fn new(batches: Vec<RecordBatch>, schema: SchemaRef) -> PyResult<Self> {
         Ok(Self(PyTable::try_new(batches, schema)?))
     }
 }
@@ -298,12 +569,40 @@ impl AddAssign<&ObjectMeta> for ObjectMetaCapacity {
 fn object_meta_capacities(metas: &[PyObjectMeta]) -> ObjectMetaCapacity {
     let mut capacity = ObjectMetaCapacity::new();
     for meta in metas {
-        capacity += &meta.0;
+        capacity += meta.as_ref();
     }
     capacity
 }
 
-fn object_meta_to_arrow(metas: &[PyObjectMeta]) -> PyRecordBatchWrapper {
+/// The Arrow type of the `metadata` column, a map from string keys to string values.
+fn metadata_data_type() -> DataType {
+    MapBuilder::new(None, StringBuilder::new(), StringBuilder::new())
+        .finish()
+        .data_type()
+        .clone()
+}
+
+fn object_meta_schema(include_metadata: bool) -> SchemaRef {
+    let mut fields = vec![
+        // Note, this uses "path" instead of "location" because we standardize the API to accept
+        // the keyword "path" everywhere.
+        Field::new("path", DataType::Utf8, false),
+        Field::new(
+            "last_modified",
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            false,
+        ),
+        Field::new("size", DataType::UInt64, false),
+        Field::new("e_tag", DataType::Utf8, true),
+        Field::new("version", DataType::Utf8, true),
+    ];
+    if include_metadata {
+        fields.push(Field::new("metadata", metadata_data_type(), true));
+    }
+    Arc::new(Schema::new(fields))
+}
+
+fn object_meta_to_batch(metas: &[PyObjectMeta], include_metadata: bool) -> RecordBatch {
     let capacity = object_meta_capacities(metas);
 
     let mut location = StringBuilder::with_capacity(metas.len(), capacity.location);
@@ -320,95 +619,345 @@ fn object_meta_to_arrow(metas: &[PyObjectMeta]) -> PyRecordBatchWrapper {
         version.append_option(meta.as_ref().version.as_ref());
     }
 
-    let fields = vec![
-        // Note, this uses "path" instead of "location" because we standardize the API to accept
-        // the keyword "path" everywhere.
-        Field::new("path", DataType::Utf8, false),
-        Field::new(
-            "last_modified",
-            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
-            false,
-        ),
-        Field::new("size", DataType::UInt64, false),
-        Field::new("e_tag", DataType::Utf8, true),
-        Field::new("version", DataType::Utf8, true),
-    ];
-    let schema = Schema::new(fields);
-
-    let columns: Vec<ArrayRef> = vec![
+    let mut columns: Vec<ArrayRef> = vec![
         Arc::new(location.finish()),
         Arc::new(last_modified.finish().with_timezone("UTC")),
         Arc::new(size.finish()),
         Arc::new(e_tag.finish()),
         Arc::new(version.finish()),
     ];
+    if include_metadata {
+        let mut metadata = MapBuilder::new(None, StringBuilder::new(), StringBuilder::new());
+        for meta in metas {
+            match &meta.metadata {
+                Some(map) => {
+                    for (key, value) in map {
+                        metadata.keys().append_value(key);
+                        metadata.values().append_value(value);
+                    }
+                    // This unwrap is ok because keys and values are always appended in pairs.
+                    metadata.append(true).unwrap();
+                }
+                None => metadata.append(false).unwrap(),
+            }
+        }
+        columns.push(Arc::new(metadata.finish()));
+    }
     // This unwrap is ok because we know the RecordBatch is valid.
-    let batch = RecordBatch::try_new(schema.into(), columns).unwrap();
-    PyRecordBatchWrapper::new(batch)
+    RecordBatch::try_new(object_meta_schema(include_metadata), columns).unwrap()
+}
+
+pub(crate) fn object_meta_to_arrow(
+    metas: &[PyObjectMeta],
+    include_metadata: bool,
+) -> PyRecordBatchWrapper {
+    PyRecordBatchWrapper::new(object_meta_to_batch(metas, include_metadata))
 }
 
+/// The result of a list call with a delimiter, exposed to Python as `obstore.ListResult`.
+#[pyclass(name = "ListResult", module = "obstore", frozen)]
 pub(crate) struct PyListResult {
-    result: ListResult,
+    common_prefixes: Vec<String>,
+    objects: Vec<PyObjectMeta>,
     return_arrow: bool,
 }
 
 impl PyListResult {
     fn new(result: ListResult, return_arrow: bool) -> Self {
         Self {
-            result,
+            common_prefixes: result
+                .common_prefixes
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            objects: result.objects.into_iter().map(PyObjectMeta::new).collect(),
             return_arrow,
         }
     }
 }
 
-impl<'py> IntoPyObject<'py> for PyListResult {
-    type Target = PyDict;
-    type Output = Bound<'py, PyDict>;
-    type Error = PyErr;
+#[pymethods]
+impl PyListResult {
+    /// Look up a field by name, as with the `dict` returned by earlier versions.
+    fn __getitem__<'py>(&self, py: Python<'py>, key: &str) -> PyResult<Bound<'py, PyAny>> {
+        match key {
+            "common_prefixes" => self.common_prefixes.clone().into_bound_py_any(py),
+            "objects" => self.objects(py),
+            _ => Err(PyKeyError::new_err(key.to_string())),
+        }
+    }
 
-    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+    fn __eq__(&self, other: &Bound<PyAny>) -> bool {
+        other
+            .downcast::<PyListResult>()
+            .map(|other| {
+                let other = other.get();
+                self.common_prefixes == other.common_prefixes && self.objects == other.objects
+            })
+            .unwrap_or(false)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ListResult(common_prefixes={}, objects={})",
+            self.common_prefixes.len(),
+            self.objects.len()
+        )
+    }
+
+    /// Export the objects as an Arrow C Array, as a struct array with one row per object.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyCapsule>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        PyRecordBatch::new(object_meta_to_batch(&self.objects, false))
+            .into_pyobject(py)?
+            .call_method1(intern!(py, "__arrow_c_array__"), (requested_schema,))
+    }
+
+    #[getter]
+    fn common_prefixes(&self) -> Vec<String> {
+        self.common_prefixes.clone()
+    }
+
+    #[getter]
+    fn objects<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        if self.return_arrow {
+            object_meta_to_arrow(&self.objects, false)
+                .into_table()?
+                .into_bound_py_any(py)
+        } else {
+            self.objects.clone().into_bound_py_any(py)
+        }
+    }
+
+    fn to_arrow(&self) -> PyRecordBatchWrapper {
+        object_meta_to_arrow(&self.objects, false)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
         let mut dict = IndexMap::with_capacity(2);
         dict.insert(
             "common_prefixes",
-            self.result
-                .common_prefixes
-                .into_iter()
-                .map(String::from)
-                .collect::<Vec<_>>()
-                .into_bound_py_any(py)?,
+            self.common_prefixes.clone().into_bound_py_any(py)?,
         );
-        let objects = self
-            .result
-            .objects
-            .into_iter()
-            .map(PyObjectMeta)
-            .collect::<Vec<_>>();
-        let objects = if self.return_arrow {
-            object_meta_to_arrow(&objects)
-                .into_table()?
-                .into_bound_py_any(py)
-        } else {
-            objects.into_bound_py_any(py)
-        }?;
-        dict.insert("objects", objects);
+        dict.insert("objects", self.objects(py)?);
         dict.into_pyobject(py)
     }
 }
 
-#[pyfunction]
-#[pyo3(signature = (store, prefix=None, *, offset=None, chunk_size=50, return_arrow=false))]
-pub(crate) fn list(
-    py: Python,
-    store: PyObjectStore,
-    prefix: Option<String>,
-    offset: Option<String>,
-    chunk_size: usize,
-    return_arrow: bool,
-) -> PyObjectStoreResult<PyListStream> {
+/// How to handle a listed entry whose key isn't a valid path, e.g. because it isn't valid UTF-8.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum PyInvalidKeyPolicy {
+    /// Raise an error.
+    #[default]
+    Error,
+    /// Omit the entry.
+    Skip,
+    /// Keep the entry, with its key converted lossily and its other metadata unknown.
+    Lossy,
+}
+
+impl<'py> FromPyObject<'py> for PyInvalidKeyPolicy {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_lowercase();
+        match s.as_str() {
+            "error" => Ok(Self::Error),
+            "skip" => Ok(Self::Skip),
+            "lossy" => Ok(Self::Lossy),
+            _ => Err(PyValueError::new_err(format!(
+                "Unexpected on_invalid_key: '{}'. Expected one of 'error', 'skip', or 'lossy'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// The keys of the entries of a listing that were skipped or converted lossily.
+type InvalidKeys = Arc<std::sync::Mutex<Vec<String>>>;
+
+/// A listed key that couldn't be parsed as a [`Path`].
+struct InvalidKey {
+    /// The key as reported by the store. Keys that aren't valid UTF-8 are percent-encoded.
+    key: String,
+    /// Whether `key` is percent-encoded.
+    encoded: bool,
+}
+
+impl InvalidKey {
+    /// Extract the offending key from an error yielded by a list stream, if the error is due to
+    /// a single invalid entry.
+    fn from_error(err: &object_store::Error) -> Option<Self> {
+        let object_store::Error::InvalidPath { source } = err else {
+            return None;
+        };
+        match source {
+            // LocalStore reports the key with the leading separator of a URL path
+            object_store::path::Error::NonUnicode { path, .. } => Some(Self {
+                key: path.trim_start_matches('/').to_string(),
+                encoded: true,
+            }),
+            object_store::path::Error::BadSegment { path, .. }
+            | object_store::path::Error::EmptySegment { path } => Some(Self {
+                key: path.trim_start_matches('/').to_string(),
+                encoded: false,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Metadata for the entry, with any invalid bytes of the key replaced with U+FFFD. The size
+    /// and last modified time are unknown and set to zero and the Unix epoch.
+    fn to_lossy_meta(&self) -> ObjectMeta {
+        let key = if self.encoded {
+            percent_encoding::percent_decode_str(&self.key).decode_utf8_lossy()
+        } else {
+            self.key.as_str().into()
+        };
+        ObjectMeta {
+            location: Path::from(key.as_ref()),
+            // The default of `DateTime<Utc>` is the Unix epoch
+            last_modified: DateTime::<Utc>::default(),
+            size: 0,
+            e_tag: None,
+            version: None,
+        }
+    }
+}
+
+/// Apply `policy` to entries of `stream` with invalid keys, recording their keys in
+/// `invalid_keys`.
+///
+/// Any other error is passed through unchanged.
+fn handle_invalid_keys(
+    stream: BoxStream<'static, object_store::Result<PyObjectMeta>>,
+    policy: PyInvalidKeyPolicy,
+    invalid_keys: InvalidKeys,
+) -> BoxStream<'static, object_store::Result<PyObjectMeta>> {
+    if policy == PyInvalidKeyPolicy::Error {
+        return stream;
+    }
+    stream
+        .filter_map(move |result| {
+            let out = match result {
+                Ok(meta) => Some(Ok(meta)),
+                Err(err) => match InvalidKey::from_error(&err) {
+                    Some(invalid) => {
+                        let meta = (policy == PyInvalidKeyPolicy::Lossy)
+                            .then(|| Ok(PyObjectMeta::new(invalid.to_lossy_meta())));
+                        invalid_keys.lock().unwrap().push(invalid.key);
+                        meta
+                    }
+                    None => Some(Err(err)),
+                },
+            };
+            futures::future::ready(out)
+        })
+        .boxed()
+}
+
+/// How glob patterns match paths: `*` does not match across directories, while `**` does.
+const GLOB_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A glob pattern matched against the full path of each listed object.
+pub(crate) struct PyGlob(glob::Pattern);
+
+impl PyGlob {
+    pub(crate) fn new(pattern: &str) -> PyResult<Self> {
+        let pattern = glob::Pattern::new(pattern).map_err(|err| {
+            PyValueError::new_err(format!("Invalid glob pattern '{}': {}", pattern, err))
+        })?;
+        Ok(Self(pattern))
+    }
+
+    pub(crate) fn matches(&self, meta: &ObjectMeta) -> bool {
+        self.matches_str(meta.location.as_ref())
+    }
+
+    pub(crate) fn matches_str(&self, path: &str) -> bool {
+        self.0.matches_with(path, GLOB_OPTIONS)
+    }
+
+    /// The leading path segments of the pattern without wildcards, under which every match lies.
+    fn literal_prefix(&self) -> Option<Path> {
+        let mut parts = self.0.as_str().split('/').collect::<Vec<_>>();
+        // The last segment is a file name, not a directory to list
+        parts.pop();
+        let literal = parts
+            .into_iter()
+            .take_while(|part| !part.contains(['*', '?', '[']))
+            .collect::<Vec<_>>()
+            .join("/");
+        Path::parse(literal).ok().filter(|path| path.as_ref() != "")
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyGlob {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        Self::new(&ob.extract::<PyBackedStr>()?)
+    }
+}
+
+/// Predicates on object metadata applied while the list stream is consumed.
+#[derive(Debug, Default)]
+pub(crate) struct PyListFilter {
+    min_size: Option<u64>,
+    max_size: Option<u64>,
+    modified_after: Option<DateTime<Utc>>,
+    modified_before: Option<DateTime<Utc>>,
+}
+
+impl PyListFilter {
+    fn matches(&self, meta: &ObjectMeta) -> bool {
+        // Compare at the precision of Python datetimes, so that a time returned for an object
+        // compares equal to its own modification time
+        let last_modified = meta.last_modified.trunc_subsecs(6);
+        self.min_size.map_or(true, |min_size| meta.size >= min_size)
+            && self.max_size.map_or(true, |max_size| meta.size <= max_size)
+            && self
+                .modified_after
+                .map_or(true, |after| last_modified > after)
+            && self
+                .modified_before
+                .map_or(true, |before| last_modified < before)
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyListFilter {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        check_keys(
+            ob,
+            "filter",
+            &["min_size", "max_size", "modified_after", "modified_before"],
+        )?;
+        let dict = ob.extract::<HashMap<String, Bound<PyAny>>>()?;
+        Ok(Self {
+            min_size: dict.get("min_size").map(|x| x.extract()).transpose()?,
+            max_size: dict.get("max_size").map(|x| x.extract()).transpose()?,
+            modified_after: dict
+                .get("modified_after")
+                .map(|x| x.extract())
+                .transpose()?,
+            modified_before: dict
+                .get("modified_before")
+                .map(|x| x.extract())
+                .transpose()?,
+        })
+    }
+}
+
+/// Ensure that arro3.core is installed if returning results as arrow.
+///
+/// The IntoPy impl is infallible, but `PyRecordBatch::to_arro3` can fail if arro3 is not
+/// installed.
+pub(crate) fn check_arrow(py: Python, return_arrow: bool) -> PyResult<()> {
     if return_arrow {
-        // Ensure that arro3.core is installed if returning as arrow.
-        // The IntoPy impl is infallible, but `PyRecordBatch::to_arro3` can fail if arro3 is not
-        // installed.
         let msg = concat!(
             "arro3.core is a required dependency for returning results as arrow.\n",
             "\nInstall with `pip install arro3-core`."
@@ -416,15 +965,102 @@ pub(crate) fn list(
         py.import(intern!(py, "arro3.core"))
             .map_err(|err| PyImportError::new_err(format!("{}\n\n{}", msg, err)))?;
     }
+    Ok(())
+}
 
-    let store = store.into_inner().clone();
-    let prefix = prefix.map(|s| s.into());
-    let stream = if let Some(offset) = offset {
-        store.list_with_offset(prefix.as_ref(), &offset.into())
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, offset=None, chunk_size=50, return_arrow=false, stable_chunks=false, order_by=None, descending=false, sort_memory_limit=DEFAULT_SORT_MEMORY_LIMIT, glob=None, filter=None, on_invalid_key=PyInvalidKeyPolicy::Error, include_metadata=false, timeout=None, chunk_timeout=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn list(
+    py: Python,
+    store: &Bound<PyAny>,
+    prefix: Option<PyPath>,
+    offset: Option<PyPath>,
+    chunk_size: usize,
+    return_arrow: bool,
+    stable_chunks: bool,
+    order_by: Option<PyListOrder>,
+    descending: bool,
+    sort_memory_limit: usize,
+    glob: Option<PyGlob>,
+    filter: Option<PyListFilter>,
+    on_invalid_key: PyInvalidKeyPolicy,
+    include_metadata: bool,
+    timeout: Option<Duration>,
+    chunk_timeout: Option<Duration>,
+) -> PyObjectStoreResult<PyListStream> {
+    check_arrow(py, return_arrow)?;
+    if descending && order_by.is_none() {
+        return Err(PyValueError::new_err("descending requires order_by").into());
+    }
+
+    let mut prefix: Option<Path> = prefix.map(|s| s.into());
+    // Only list the part of the store that the glob can match
+    if let Some(literal) = glob.as_ref().and_then(PyGlob::literal_prefix) {
+        if prefix
+            .as_ref()
+            .map_or(true, |prefix| literal.prefix_matches(prefix))
+        {
+            prefix = Some(literal);
+        }
+    }
+    let stream = if include_metadata {
+        if offset.is_some() {
+            return Err(
+                PyValueError::new_err("offset is not supported with include_metadata").into(),
+            );
+        }
+        if timeout.is_some() {
+            return Err(
+                PyValueError::new_err("timeout is not supported with include_metadata").into(),
+            );
+        }
+        store
+            .extract::<AzureListStore>()?
+            .list_with_metadata(prefix.as_ref())
     } else {
-        store.list(prefix.as_ref())
+        let store = store
+            .extract::<PyObjectStore>()?
+            .with_timeout(timeout)
+            .into_inner();
+        let stream = if let Some(offset) = offset {
+            store.list_with_offset(prefix.as_ref(), &offset.into())
+        } else {
+            store.list(prefix.as_ref())
+        };
+        stream.map_ok(PyObjectMeta::new).boxed()
     };
-    Ok(PyListStream::new(stream, chunk_size, return_arrow))
+    let invalid_keys = InvalidKeys::default();
+    let stream = handle_invalid_keys(stream, on_invalid_key, invalid_keys.clone());
+    let stream = if glob.is_some() || filter.is_some() {
+        let filter = filter.unwrap_or_default();
+        stream
+            .try_filter(move |meta| {
+                let meta = meta.as_ref();
+                let keep =
+                    glob.as_ref().map_or(true, |glob| glob.matches(meta)) && filter.matches(meta);
+                futures::future::ready(keep)
+            })
+            .boxed()
+    } else {
+        stream
+    };
+    // Stable chunks only need some order that depends on nothing but the keys
+    let order_by = order_by.or(stable_chunks.then_some(PyListOrder::Path));
+    let stream = if let Some(key) = order_by {
+        let order = SortOrder { key, descending };
+        sort_stream(stream, order, sort_memory_limit)
+    } else {
+        stream
+    };
+    Ok(PyListStream::new(
+        stream,
+        chunk_size,
+        chunk_timeout,
+        return_arrow,
+        include_metadata,
+        invalid_keys,
+    ))
 }
 
 #[pyfunction]
@@ -432,7 +1068,7 @@ pub(crate) fn list(
 pub(crate) fn list_with_delimiter(
     py: Python,
     store: PyObjectStore,
-    prefix: Option<String>,
+    prefix: Option<PyPath>,
     return_arrow: bool,
 ) -> PyObjectStoreResult<PyListResult> {
     let runtime = get_runtime(py)?;
@@ -451,7 +1087,7 @@ pub(crate) fn list_with_delimiter(
 pub(crate) fn list_with_delimiter_async(
     py: Python,
     store: PyObjectStore,
-    prefix: Option<String>,
+    prefix: Option<PyPath>,
     return_arrow: bool,
 ) -> PyResult<Bound<PyAny>> {
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
@@ -465,6 +1101,235 @@ pub(crate) fn list_with_delimiter_async(
     })
 }
 
+/// A single page of list results.
+pub(crate) struct PyListPage {
+    objects: Vec<PyObjectMeta>,
+    next_start_after: Option<String>,
+}
+
+impl<'py> IntoPyObject<'py> for PyListPage {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(2);
+        dict.insert("objects", self.objects.into_bound_py_any(py)?);
+        dict.insert(
+            "next_start_after",
+            self.next_start_after.into_bound_py_any(py)?,
+        );
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, max_keys=1000, start_after=None))]
+pub(crate) fn list_page(
+    py: Python,
+    store: ListPageStore,
+    prefix: Option<PyPath>,
+    max_keys: usize,
+    start_after: Option<PyPath>,
+) -> PyObjectStoreResult<PyListPage> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(list_page_materialize(
+            store,
+            prefix.map(|s| s.into()),
+            max_keys,
+            start_after.map(|s| s.into()),
+        ))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, max_keys=1000, start_after=None))]
+pub(crate) fn list_page_async(
+    py: Python,
+    store: ListPageStore,
+    prefix: Option<PyPath>,
+    max_keys: usize,
+    start_after: Option<PyPath>,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = list_page_materialize(
+            store,
+            prefix.map(|s| s.into()),
+            max_keys,
+            start_after.map(|s| s.into()),
+        )
+        .await?;
+        Ok(out)
+    })
+}
+
+/// How many listings of stores that don't list in order are kept for the pages after the first.
+const MAX_LISTINGS: usize = 16;
+
+/// How long a listing is used for the pages after the first, before the store is listed again.
+const LISTING_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// The sorted listings of stores that don't list in order, most recent last, so that the pages
+/// after the first are served without listing every object under the prefix again. Once
+/// [`MAX_LISTINGS`] are kept, the oldest is dropped for each new one.
+static LISTINGS: std::sync::Mutex<VecDeque<Listing>> = std::sync::Mutex::new(VecDeque::new());
+
+/// Every object under `prefix` in `store`, sorted by path.
+struct Listing {
+    /// The store as passed from Python, without any layer, which identifies it
+    store: Arc<dyn ObjectStore>,
+    prefix: Option<Path>,
+    listed_at: Instant,
+    metas: Arc<Vec<ObjectMeta>>,
+}
+
+impl Listing {
+    /// The listing of `prefix` in `store`, if it's recent enough to be used.
+    fn get(store: &Arc<dyn ObjectStore>, prefix: Option<&Path>) -> Option<Arc<Vec<ObjectMeta>>> {
+        LISTINGS
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|listing| Arc::ptr_eq(&listing.store, store) && listing.prefix.as_ref() == prefix)
+            .filter(|listing| listing.listed_at.elapsed() < LISTING_TTL)
+            .map(|listing| listing.metas.clone())
+    }
+
+    /// Keep the listing of `prefix` in `store`, replacing any earlier one.
+    fn insert(store: Arc<dyn ObjectStore>, prefix: Option<Path>, metas: Arc<Vec<ObjectMeta>>) {
+        let mut listings = LISTINGS.lock().unwrap();
+        listings
+            .retain(|listing| !(Arc::ptr_eq(&listing.store, &store) && listing.prefix == prefix));
+        if listings.len() == MAX_LISTINGS {
+            listings.pop_front();
+        }
+        listings.push_back(Self {
+            store,
+            prefix,
+            listed_at: Instant::now(),
+            metas,
+        });
+    }
+}
+
+/// The store of a [`list_page`], along with whether it lists in lexicographic order of path.
+pub(crate) struct ListPageStore {
+    store: Arc<dyn ObjectStore>,
+    /// The store without any layer, which identifies its listings
+    unlayered: Arc<dyn ObjectStore>,
+    ordered: bool,
+}
+
+impl<'py> FromPyObject<'py> for ListPageStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let resolved = resolve_store_token(ob)?;
+        // The object stores and MemoryStore return keys in lexicographic order, while other stores,
+        // such as LocalStore, return them in whatever order they're found.
+        let ordered = resolved.is_instance_of::<PyS3Store>()
+            || resolved.is_instance_of::<PyGCSStore>()
+            || resolved.is_instance_of::<PyAzureStore>()
+            || resolved.is_instance_of::<PyMemoryStore>();
+        Ok(Self {
+            store: ob.extract::<PyObjectStore>()?.into_inner(),
+            unlayered: ob.extract::<PyObjectStore>()?.into_dyn(),
+            ordered,
+        })
+    }
+}
+
+/// List at most `max_keys` objects, in lexicographic order of path, after `start_after`.
+///
+/// If the store lists in lexicographic order, we stop reading the stream as soon as the page is
+/// full. Otherwise the first page reads and sorts the whole listing, which is kept for the
+/// following pages.
+async fn list_page_materialize(
+    store: ListPageStore,
+    prefix: Option<Path>,
+    max_keys: usize,
+    start_after: Option<Path>,
+) -> PyObjectStoreResult<PyListPage> {
+    if max_keys == 0 {
+        return Err(PyValueError::new_err("max_keys must be at least 1").into());
+    }
+
+    let ListPageStore {
+        store,
+        unlayered,
+        ordered,
+    } = store;
+    if !ordered {
+        return list_page_from_listing(store, unlayered, prefix, max_keys, start_after).await;
+    }
+
+    let mut stream = match start_after.as_ref() {
+        Some(offset) => store.list_with_offset(prefix.as_ref(), offset),
+        None => store.list(prefix.as_ref()),
+    };
+
+    // We retain one more than `max_keys` to know whether there's another page
+    let limit = max_keys + 1;
+    let mut metas: Vec<ObjectMeta> = vec![];
+    while let Some(meta) = stream.try_next().await? {
+        metas.push(meta);
+        if metas.len() >= limit {
+            break;
+        }
+    }
+
+    let has_more = metas.len() > max_keys;
+    metas.truncate(max_keys);
+    let next_start_after = has_more
+        .then(|| metas.last().map(|meta| meta.location.to_string()))
+        .flatten();
+    Ok(PyListPage {
+        objects: metas.into_iter().map(PyObjectMeta::new).collect(),
+        next_start_after,
+    })
+}
+
+/// A page of the sorted listing of a store that doesn't list in order.
+///
+/// The first page lists every object under `prefix`. Later pages use that listing, while it's
+/// recent enough, so that paging through `n` objects doesn't take `n` listings of all of them.
+async fn list_page_from_listing(
+    store: Arc<dyn ObjectStore>,
+    unlayered: Arc<dyn ObjectStore>,
+    prefix: Option<Path>,
+    max_keys: usize,
+    start_after: Option<Path>,
+) -> PyObjectStoreResult<PyListPage> {
+    let listing = start_after
+        .as_ref()
+        .and_then(|_| Listing::get(&unlayered, prefix.as_ref()));
+    let metas = match listing {
+        Some(metas) => metas,
+        None => {
+            let mut metas: Vec<ObjectMeta> = store.list(prefix.as_ref()).try_collect().await?;
+            metas.sort_unstable_by(|a, b| a.location.cmp(&b.location));
+            let metas = Arc::new(metas);
+            Listing::insert(unlayered, prefix, metas.clone());
+            metas
+        }
+    };
+
+    let start = start_after.map_or(0, |offset| {
+        metas.partition_point(|meta| meta.location <= offset)
+    });
+    let end = metas.len().min(start.saturating_add(max_keys));
+    let next_start_after = (end < metas.len()).then(|| metas[end - 1].location.to_string());
+    Ok(PyListPage {
+        objects: metas[start..end]
+            .iter()
+            .cloned()
+            .map(PyObjectMeta::new)
+            .collect(),
+        next_start_after,
+    })
+}
+
 async fn list_with_delimiter_materialize(
     store: Arc<dyn ObjectStore>,
     prefix: Option<&Path>,
@@ -0,0 +1,214 @@
+//! Integrity manifests recording a checksum for each part of an upload.
+
+use std::sync::Arc;
+
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{GetOptions, GetRange, ObjectStore, PutPayload, PutResult};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreResult, PyPath};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::runtime::get_runtime;
+use crate::utils::PyNone;
+
+/// Version of the manifest format written by [`PartChecksums::write_manifest`].
+const MANIFEST_VERSION: u64 = 1;
+
+/// The SHA-256 checksum of one part of an object.
+#[derive(Debug, Clone)]
+struct PartChecksum {
+    offset: u64,
+    size: u64,
+    sha256: String,
+}
+
+/// Checksums of the parts of an upload, in order.
+#[derive(Debug, Default)]
+pub(crate) struct PartChecksums {
+    parts: Vec<PartChecksum>,
+    size: u64,
+}
+
+impl PartChecksums {
+    /// Record the checksum of the next part of the upload.
+    pub(crate) fn push(&mut self, part: &PutPayload) {
+        let mut hasher = Sha256::new();
+        for chunk in part.iter() {
+            hasher.update(chunk);
+        }
+        let size = part.content_length() as u64;
+        self.parts.push(PartChecksum {
+            offset: self.size,
+            size,
+            sha256: format!("{:x}", hasher.finalize()),
+        });
+        self.size += size;
+    }
+
+    /// Write a JSON manifest of these checksums to `manifest_path`, alongside the object at
+    /// `path`.
+    pub(crate) async fn write_manifest(
+        self,
+        store: &Arc<dyn ObjectStore>,
+        manifest_path: &Path,
+        path: &Path,
+        result: &PutResult,
+    ) -> PyObjectStoreResult<()> {
+        let parts = self
+            .parts
+            .into_iter()
+            .map(|part| {
+                json!({
+                    "offset": part.offset,
+                    "size": part.size,
+                    "sha256": part.sha256,
+                })
+            })
+            .collect::<Vec<_>>();
+        let manifest = json!({
+            "version": MANIFEST_VERSION,
+            "path": path.as_ref(),
+            "size": self.size,
+            "e_tag": result.e_tag,
+            "version_id": result.version,
+            "algorithm": "sha256",
+            "parts": parts,
+        });
+        let data = serde_json::to_vec_pretty(&manifest).unwrap();
+        store.put(manifest_path, data.into()).await?;
+        Ok(())
+    }
+
+    /// Parse a manifest previously written by [`Self::write_manifest`].
+    fn from_manifest(manifest: &[u8]) -> PyResult<Self> {
+        let invalid = |msg: &str| PyValueError::new_err(format!("Invalid manifest: {}", msg));
+        let manifest: Value =
+            serde_json::from_slice(manifest).map_err(|err| invalid(&err.to_string()))?;
+        if manifest["version"].as_u64() != Some(MANIFEST_VERSION) {
+            return Err(invalid("unsupported version"));
+        }
+        if manifest["algorithm"].as_str() != Some("sha256") {
+            return Err(invalid("unsupported algorithm"));
+        }
+
+        let parts = manifest["parts"]
+            .as_array()
+            .ok_or_else(|| invalid("missing parts"))?
+            .iter()
+            .map(|part| {
+                Some(PartChecksum {
+                    offset: part["offset"].as_u64()?,
+                    size: part["size"].as_u64()?,
+                    sha256: part["sha256"].as_str()?.to_string(),
+                })
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("malformed part"))?;
+        let size = manifest["size"]
+            .as_u64()
+            .ok_or_else(|| invalid("missing size"))?;
+        Ok(Self { parts, size })
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, manifest, *, max_concurrency=12))]
+pub(crate) fn verify(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    manifest: PyPath,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<()> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        runtime.block_on(verify_inner(
+            store.into_inner(),
+            path.into(),
+            manifest.into(),
+            max_concurrency,
+        ))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, manifest, *, max_concurrency=12))]
+pub(crate) fn verify_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    manifest: PyPath,
+    max_concurrency: usize,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        verify_inner(
+            store.into_inner(),
+            path.into(),
+            manifest.into(),
+            max_concurrency,
+        )
+        .await?;
+        Ok(PyNone)
+    })
+}
+
+/// Check the object at `path` against the part checksums in the manifest at `manifest_path`.
+///
+/// Each part is fetched with a separate range request and hashed as it streams in, so that
+/// verifying a large object doesn't need to hold it in memory.
+async fn verify_inner(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    manifest_path: Path,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<()> {
+    let manifest = store.get(&manifest_path).await?.bytes().await?;
+    let checksums = PartChecksums::from_manifest(&manifest)?;
+
+    let meta = store.head(&path).await?;
+    if meta.size != checksums.size {
+        return Err(PyValueError::new_err(format!(
+            "Size mismatch for '{}': expected {} bytes, found {} bytes",
+            path, checksums.size, meta.size
+        ))
+        .into());
+    }
+
+    futures::stream::iter(checksums.parts.into_iter().enumerate())
+        .map(|(idx, part)| verify_part(&store, &path, idx, part))
+        .buffer_unordered(max_concurrency.max(1))
+        .try_collect::<()>()
+        .await
+}
+
+async fn verify_part(
+    store: &Arc<dyn ObjectStore>,
+    path: &Path,
+    idx: usize,
+    part: PartChecksum,
+) -> PyObjectStoreResult<()> {
+    let end = part.offset + part.size;
+    let mut hasher = Sha256::new();
+    if part.size > 0 {
+        let options = GetOptions {
+            range: Some(GetRange::Bounded(part.offset..end)),
+            ..Default::default()
+        };
+        let mut stream = store.get_opts(path, options).await?.into_stream();
+        while let Some(chunk) = stream.try_next().await? {
+            hasher.update(&chunk);
+        }
+    }
+    let actual = format!("{:x}", hasher.finalize());
+    if actual != part.sha256 {
+        return Err(PyValueError::new_err(format!(
+            "Checksum mismatch for part {} (bytes {}-{}) of '{}': expected sha256 {}, found {}",
+            idx, part.offset, end, path, part.sha256, actual
+        ))
+        .into());
+    }
+    Ok(())
+}
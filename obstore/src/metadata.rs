@@ -0,0 +1,944 @@
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use http::header::{AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use indexmap::IndexMap;
+use object_store::azure::{AzureAuthorizer, MicrosoftAzure};
+use object_store::client::{HttpClient, HttpConnector, HttpRequestBody, ReqwestConnector};
+use object_store::gcp::GoogleCloudStorage;
+use object_store::path::Path;
+use object_store::{
+    Attribute, Attributes, ClientOptions, GetOptions, ObjectMeta, ObjectStore, PutMode, PutOptions,
+    TagSet, UpdateVersion,
+};
+use percent_encoding::utf8_percent_encode;
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3_object_store::{
+    resolve_store_token, set_blob_tier, AccessTier, LimitedStore, MaybePrefixedStore, PyAzureStore,
+    PyGCSStore, PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath, PyS3Store,
+};
+use quick_xml::escape::escape;
+use serde::Deserialize;
+use url::Url;
+
+use crate::attributes::PyAttributes;
+use crate::copy::{is_too_large_to_copy, MultipartCopyOptions, S3MultipartCopy, COPY_SOURCE};
+use crate::list::PyObjectMeta;
+use crate::requests::{self, request_error, S3Requests};
+use crate::response::attribute_header;
+use crate::runtime::get_runtime;
+use crate::tags::PyTagSet;
+use crate::utils::PyNone;
+use crate::versions::gcs_objects_url;
+
+#[pyfunction]
+pub(crate) fn get_attributes(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+) -> PyObjectStoreResult<PyAttributes> {
+    let runtime = get_runtime(py)?;
+    let store = store.into_inner();
+    py.allow_threads(|| {
+        let attributes = runtime.block_on(get_attributes_inner(store, path.into()))?;
+        Ok::<_, PyObjectStoreError>(PyAttributes::new(attributes))
+    })
+}
+
+#[pyfunction]
+pub(crate) fn get_attributes_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+) -> PyResult<Bound<PyAny>> {
+    let store = store.into_inner();
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let attributes = get_attributes_inner(store, path.into()).await?;
+        Ok(PyAttributes::new(attributes))
+    })
+}
+
+async fn get_attributes_inner(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+) -> PyObjectStoreResult<Attributes> {
+    let options = GetOptions {
+        head: true,
+        ..Default::default()
+    };
+    let result = store.get_opts(&path, options).await?;
+    Ok(result.attributes)
+}
+
+#[pyfunction]
+pub(crate) fn put_attributes(
+    py: Python,
+    store: RewriteStore,
+    path: PyPath,
+    attributes: PyAttributes,
+) -> PyObjectStoreResult<()> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        runtime.block_on(rewrite(
+            store,
+            path.into(),
+            Some(attributes.into_inner()),
+            None,
+        ))
+    })
+}
+
+#[pyfunction]
+pub(crate) fn put_attributes_async(
+    py: Python,
+    store: RewriteStore,
+    path: PyPath,
+    attributes: PyAttributes,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        rewrite(store, path.into(), Some(attributes.into_inner()), None).await?;
+        Ok(PyNone)
+    })
+}
+
+#[pyfunction]
+pub(crate) fn put_tags(
+    py: Python,
+    store: RewriteStore,
+    path: PyPath,
+    tags: PyTagSet,
+) -> PyObjectStoreResult<()> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        runtime.block_on(rewrite(store, path.into(), None, Some(tags.into_inner())))
+    })
+}
+
+#[pyfunction]
+pub(crate) fn put_tags_async(
+    py: Python,
+    store: RewriteStore,
+    path: PyPath,
+    tags: PyTagSet,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        rewrite(store, path.into(), None, Some(tags.into_inner())).await?;
+        Ok(PyNone)
+    })
+}
+
+#[pyfunction]
+pub(crate) fn set_tier(
+    py: Python,
+    store: PyAzureStore,
+    path: PyPath,
+    tier: AccessTier,
+) -> PyObjectStoreResult<()> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| runtime.block_on(set_blob_tier(&store, &path.into(), tier)))?;
+    Ok(())
+}
+
+#[pyfunction]
+pub(crate) fn set_tier_async(
+    py: Python,
+    store: PyAzureStore,
+    path: PyPath,
+    tier: AccessTier,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        set_blob_tier(&store, &path.into(), tier)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(PyNone)
+    })
+}
+
+/// The store of an object whose attributes or tags are replaced.
+///
+/// S3, GCS and Azure update objects in place, with requests that `object_store` doesn't expose.
+/// Any other store rewrites the whole object.
+pub(crate) enum RewriteStore {
+    S3 {
+        requests: S3Requests,
+        multipart: S3MultipartCopy,
+    },
+    Gcs {
+        store: Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>>,
+        bucket: String,
+        client: HttpClient,
+    },
+    Azure {
+        store: Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>,
+        container_url: Url,
+        account: String,
+        client: HttpClient,
+    },
+    Other(Arc<dyn ObjectStore>),
+}
+
+impl<'py> FromPyObject<'py> for RewriteStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyS3Store>() {
+            let store = store.get();
+            return Ok(Self::S3 {
+                requests: S3Requests::new(store).map_err(PyObjectStoreError::from)?,
+                multipart: S3MultipartCopy::new(store),
+            });
+        }
+        if let Ok(store) = ob.downcast::<PyGCSStore>() {
+            let store = store.get();
+            return Ok(Self::Gcs {
+                store: store.as_ref().clone(),
+                bucket: store.bucket().to_string(),
+                client: store.http_client().map_err(PyObjectStoreError::from)?,
+            });
+        }
+        if let Ok(store) = ob.downcast::<PyAzureStore>() {
+            let store = store.get();
+            let (container_url, account) = store.container_url()?;
+            return Ok(Self::Azure {
+                store: store.as_ref().clone(),
+                container_url,
+                account,
+                client: store.http_client().map_err(PyObjectStoreError::from)?,
+            });
+        }
+        Ok(Self::Other(ob.extract::<PyObjectStore>()?.into_inner()))
+    }
+}
+
+/// Replace the attributes and/or tags of the object at `path`, preserving the other.
+///
+/// At least one of `attributes` and `tags` is expected to be provided.
+async fn rewrite(
+    store: RewriteStore,
+    path: Path,
+    attributes: Option<Attributes>,
+    tags: Option<TagSet>,
+) -> PyObjectStoreResult<()> {
+    match store {
+        RewriteStore::S3 {
+            requests,
+            multipart,
+        } => rewrite_s3(&requests, &multipart, &path, attributes, tags).await?,
+        RewriteStore::Gcs {
+            store,
+            bucket,
+            client,
+        } => {
+            if tags.is_some() {
+                return Err(object_store::Error::NotSupported {
+                    source: "GCS doesn't support object tags".into(),
+                }
+                .into());
+            }
+            if let Some(attributes) = attributes {
+                let path = store.inner().full_path(&path);
+                rewrite_gcs(store.inner().inner(), &client, &bucket, &path, &attributes).await?;
+            }
+        }
+        RewriteStore::Azure {
+            store,
+            container_url,
+            account,
+            client,
+        } => {
+            let path = store.inner().full_path(&path);
+            let azure = AzureRequests {
+                azure: store.inner().inner(),
+                client: &client,
+                account: &account,
+                url: blob_url(&container_url, &path),
+                path: &path,
+            };
+            if let Some(attributes) = attributes {
+                azure.set_attributes(&attributes).await?;
+            }
+            if let Some(tags) = tags {
+                azure.set_tags(&tags).await?;
+            }
+        }
+        RewriteStore::Other(store) => rewrite_object(&store, &path, attributes, tags).await?,
+    }
+    Ok(())
+}
+
+/// Write the object at `path` back with new attributes and/or tags.
+///
+/// The object is only replaced if it hasn't changed since it was read, so that a concurrent write
+/// isn't lost. Tags can't be read back generically, so any existing tags are replaced.
+async fn rewrite_object(
+    store: &Arc<dyn ObjectStore>,
+    path: &Path,
+    attributes: Option<Attributes>,
+    tags: Option<TagSet>,
+) -> object_store::Result<()> {
+    let result = store.get(path).await?;
+    let version = UpdateVersion {
+        e_tag: result.meta.e_tag.clone(),
+        version: result.meta.version.clone(),
+    };
+    let attributes = attributes.unwrap_or_else(|| result.attributes.clone());
+    let data = result.bytes().await?;
+    let opts = PutOptions {
+        mode: PutMode::Update(version),
+        attributes,
+        tags: tags.unwrap_or_default(),
+        ..Default::default()
+    };
+    store.put_opts(path, data.into(), opts).await?;
+    Ok(())
+}
+
+/// The headers setting `attributes`, where the name of each standard header is preceded by
+/// `standard_prefix` and each user-defined metadata key by `metadata_prefix`.
+fn attribute_headers(
+    attributes: &Attributes,
+    standard_prefix: &str,
+    metadata_prefix: &str,
+) -> Vec<(String, String)> {
+    attributes
+        .iter()
+        .filter_map(|(attribute, value)| {
+            let name = match attribute {
+                Attribute::Metadata(key) => format!("{}{}", metadata_prefix, key),
+                attribute => format!("{}{}", standard_prefix, attribute_header(attribute)?),
+            };
+            Some((name, value.to_string()))
+        })
+        .collect()
+}
+
+/// Borrow owned headers as the headers of a request.
+fn header_refs(headers: &[(String, String)]) -> Vec<(&str, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.clone()))
+        .collect()
+}
+
+/// The `TagSet` element of a tagging request on S3 or Azure.
+fn tag_set_xml(tags: &TagSet) -> String {
+    let mut xml = String::from("<TagSet>");
+    for (key, value) in url::form_urlencoded::parse(tags.encoded().as_bytes()) {
+        xml.push_str(&format!(
+            "<Tag><Key>{}</Key><Value>{}</Value></Tag>",
+            escape(key.as_ref()),
+            escape(value.as_ref())
+        ));
+    }
+    xml.push_str("</TagSet>");
+    xml
+}
+
+/// Replace the attributes and/or tags of an S3 object in place.
+///
+/// Tags alone are replaced with `PutObjectTagging`. Attributes can only be replaced by copying the
+/// object onto itself, with `CopyObject`, or in parts if it's too large for that, which keeps its
+/// tags unless new ones are given.
+async fn rewrite_s3(
+    requests: &S3Requests,
+    multipart: &S3MultipartCopy,
+    path: &Path,
+    attributes: Option<Attributes>,
+    tags: Option<TagSet>,
+) -> object_store::Result<()> {
+    let full_path = requests.full_path(path);
+    let Some(attributes) = attributes else {
+        if let Some(tags) = tags {
+            let body = format!("<Tagging>{}</Tagging>", tag_set_xml(&tags));
+            requests
+                .request(
+                    Method::PUT,
+                    &full_path,
+                    &[("tagging", "")],
+                    &[],
+                    Bytes::from(body),
+                )
+                .await?;
+        }
+        return Ok(());
+    };
+
+    let mut headers = attribute_headers(&attributes, "", "x-amz-meta-");
+    if let Some(tags) = &tags {
+        headers.push(("x-amz-tagging".to_string(), tags.encoded().to_string()));
+    }
+    let mut copy_headers = headers.clone();
+    copy_headers.push((
+        "x-amz-copy-source".to_string(),
+        format!(
+            "/{}/{}",
+            requests.bucket(),
+            utf8_percent_encode(full_path.as_ref(), COPY_SOURCE)
+        ),
+    ));
+    copy_headers.push((
+        "x-amz-metadata-directive".to_string(),
+        "REPLACE".to_string(),
+    ));
+    if tags.is_some() {
+        copy_headers.push(("x-amz-tagging-directive".to_string(), "REPLACE".to_string()));
+    }
+    let copied = requests
+        .request(
+            Method::PUT,
+            &full_path,
+            &[],
+            &header_refs(&copy_headers),
+            Bytes::new(),
+        )
+        .await;
+    match copied {
+        Err(err) if is_too_large_to_copy(&err) => {}
+        result => return result.map(|_| ()),
+    }
+
+    // A copy in parts doesn't keep the tags of the object, so they're read first
+    if tags.is_none() {
+        let existing = get_s3_tags(requests, path).await?;
+        let mut tag_set = TagSet::default();
+        for (key, value) in existing.0.iter() {
+            tag_set.push(key, value);
+        }
+        headers.push(("x-amz-tagging".to_string(), tag_set.encoded().to_string()));
+    }
+    let size = requests.head(path).await?.size;
+    multipart
+        .copy(
+            path,
+            path,
+            size,
+            true,
+            MultipartCopyOptions::with_threshold(None),
+            &header_refs(&headers),
+        )
+        .await
+}
+
+/// Replace the attributes of a GCS object in place, by rewriting it onto itself with new
+/// metadata, which within a bucket doesn't copy its data.
+///
+/// Large objects may take several requests, each continuing from the token of the last.
+async fn rewrite_gcs(
+    gcs: &GoogleCloudStorage,
+    client: &HttpClient,
+    bucket: &str,
+    path: &Path,
+    attributes: &Attributes,
+) -> object_store::Result<()> {
+    let mut resource = serde_json::Map::new();
+    let mut metadata = serde_json::Map::new();
+    for (attribute, value) in attributes.iter() {
+        let field = match attribute {
+            Attribute::CacheControl => "cacheControl",
+            Attribute::ContentDisposition => "contentDisposition",
+            Attribute::ContentEncoding => "contentEncoding",
+            Attribute::ContentLanguage => "contentLanguage",
+            Attribute::ContentType => "contentType",
+            Attribute::Metadata(key) => {
+                metadata.insert(key.to_string(), value.to_string().into());
+                continue;
+            }
+            _ => continue,
+        };
+        resource.insert(field.to_string(), value.to_string().into());
+    }
+    resource.insert("metadata".to_string(), metadata.into());
+    let body = serde_json::Value::from(resource).to_string();
+
+    let mut url = gcs_objects_url(bucket);
+    url.path_segments_mut().expect("GCS URL is a base").extend([
+        path.as_ref(),
+        "rewriteTo",
+        "b",
+        bucket,
+        "o",
+        path.as_ref(),
+    ]);
+    let mut rewrite_token: Option<String> = None;
+    loop {
+        let mut url = url.clone();
+        if let Some(token) = &rewrite_token {
+            url.query_pairs_mut().append_pair("rewriteToken", token);
+        }
+        let credential = gcs.credentials().get_credential().await?;
+        let request = http::Request::builder()
+            .method(Method::POST)
+            .uri(url.as_str())
+            .header(AUTHORIZATION, format!("Bearer {}", credential.bearer))
+            .header(CONTENT_TYPE, "application/json")
+            .body(HttpRequestBody::from(body.clone()))
+            .map_err(|err| request_error("GCS", err.to_string()))?;
+        let response = requests::send(client, request, path.as_ref(), "GCS").await?;
+        let response: serde_json::Value =
+            serde_json::from_str(&response).map_err(|err| request_error("GCS", err.to_string()))?;
+        if response["done"].as_bool().unwrap_or(false) {
+            return Ok(());
+        }
+        let token = response["rewriteToken"]
+            .as_str()
+            .ok_or_else(|| request_error("GCS", "Rewrite response has no rewriteToken"))?;
+        rewrite_token = Some(token.to_string());
+    }
+}
+
+/// The URL of the blob at `path` within the container at `container_url`.
+fn blob_url(container_url: &Url, path: &Path) -> Url {
+    let mut url = container_url.clone();
+    url.path_segments_mut()
+        .expect("Azure URL is a base")
+        .pop_if_empty()
+        .extend(path.parts());
+    url
+}
+
+/// Requests updating an Azure blob in place.
+struct AzureRequests<'a> {
+    azure: &'a MicrosoftAzure,
+    client: &'a HttpClient,
+    account: &'a str,
+    url: Url,
+    path: &'a Path,
+}
+
+impl AzureRequests<'_> {
+    async fn request(
+        &self,
+        comp: &str,
+        headers: &[(String, String)],
+        body: String,
+    ) -> object_store::Result<()> {
+        let mut url = self.url.clone();
+        url.query_pairs_mut().append_pair("comp", comp);
+        let credential = self.azure.credentials().get_credential().await?;
+        // The length is signed along with the other headers, so it must be set beforehand
+        let mut builder = http::Request::builder()
+            .method(Method::PUT)
+            .uri(url.as_str())
+            .header(CONTENT_LENGTH, body.len());
+        for (name, value) in headers {
+            builder = builder.header(name, value);
+        }
+        let mut request = builder
+            .body(HttpRequestBody::from(body))
+            .map_err(|err| azure_error(err.to_string()))?;
+        AzureAuthorizer::new(&credential, self.account).authorize(&mut request);
+        requests::send(self.client, request, self.path.as_ref(), "MicrosoftAzure").await?;
+        Ok(())
+    }
+
+    /// Replace the properties and the metadata of the blob, with Set Blob Properties and Set
+    /// Blob Metadata. Properties that aren't given are cleared.
+    async fn set_attributes(&self, attributes: &Attributes) -> object_store::Result<()> {
+        let (metadata, properties): (Vec<_>, Vec<_>) =
+            attribute_headers(attributes, "x-ms-blob-", "x-ms-meta-")
+                .into_iter()
+                .partition(|(name, _)| name.starts_with("x-ms-meta-"));
+        self.request("properties", &properties, String::new())
+            .await?;
+        self.request("metadata", &metadata, String::new()).await
+    }
+
+    /// Replace the tags of the blob, with Set Blob Tags.
+    async fn set_tags(&self, tags: &TagSet) -> object_store::Result<()> {
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?><Tags>{}</Tags>",
+            tag_set_xml(tags)
+        );
+        let headers = [(CONTENT_TYPE.to_string(), "application/xml".to_string())];
+        self.request("tags", &headers, body).await
+    }
+}
+
+/// An S3 store, which is the only backend that supports reading object tags.
+pub(crate) struct S3TagStore(S3Requests);
+
+impl<'py> FromPyObject<'py> for S3TagStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyS3Store>() {
+            let requests = S3Requests::new(store.get()).map_err(PyObjectStoreError::from)?;
+            return Ok(Self(requests));
+        }
+
+        let py = ob.py();
+        // Check for object-store instance from other library
+        let cls_name = ob
+            .getattr(intern!(py, "__class__"))?
+            .getattr(intern!(py, "__name__"))?
+            .extract::<PyBackedStr>()?;
+        if &*cls_name == "S3Store" {
+            return Err(PyValueError::new_err("You must use an object store instance exported from **the same library** as this function. They cannot be used across libraries.\nThis is because object store instances are compiled with a specific version of Rust and Python." ));
+        }
+
+        Err(PyValueError::new_err(format!(
+            "Reading tags is only supported for S3Store instances, got {}",
+            ob.repr()?
+        )))
+    }
+}
+
+/// The tags of an object, in the order returned by the store.
+pub(crate) struct PyTags(IndexMap<String, String>);
+
+impl<'py> IntoPyObject<'py> for PyTags {
+    type Target = pyo3::types::PyDict;
+    type Output = Bound<'py, pyo3::types::PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        self.0.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+pub(crate) fn get_tags(py: Python, store: S3TagStore, path: PyPath) -> PyObjectStoreResult<PyTags> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| Ok(runtime.block_on(get_s3_tags(&store.0, &path.into()))?))
+}
+
+#[pyfunction]
+pub(crate) fn get_tags_async(
+    py: Python,
+    store: S3TagStore,
+    path: PyPath,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let tags = get_s3_tags(&store.0, &path.into())
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(tags)
+    })
+}
+
+/// Make a `GetObjectTagging` request, which `object_store` doesn't expose.
+async fn get_s3_tags(requests: &S3Requests, path: &Path) -> object_store::Result<PyTags> {
+    let full_path = requests.full_path(path);
+    let body = requests
+        .request(
+            Method::GET,
+            &full_path,
+            &[("tagging", "")],
+            &[],
+            Bytes::new(),
+        )
+        .await?;
+    Ok(PyTags(parse_tagging(&body)?))
+}
+
+/// An Azure store, which is the only backend that returns user-defined metadata when listing.
+pub(crate) struct AzureListStore {
+    store: Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>,
+    container_url: Url,
+    account: String,
+}
+
+impl<'py> FromPyObject<'py> for AzureListStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyAzureStore>() {
+            let store = store.get();
+            let (container_url, account) = store.container_url()?;
+            return Ok(Self {
+                store: store.as_ref().clone(),
+                container_url,
+                account,
+            });
+        }
+
+        let py = ob.py();
+        // Check for object-store instance from other library
+        let cls_name = ob
+            .getattr(intern!(py, "__class__"))?
+            .getattr(intern!(py, "__name__"))?
+            .extract::<PyBackedStr>()?;
+        if &*cls_name == "AzureStore" {
+            return Err(PyValueError::new_err("You must use an object store instance exported from **the same library** as this function. They cannot be used across libraries.\nThis is because object store instances are compiled with a specific version of Rust and Python." ));
+        }
+
+        Err(PyValueError::new_err(format!(
+            "include_metadata is only supported for AzureStore instances, got {}",
+            ob.repr()?
+        )))
+    }
+}
+
+impl AzureListStore {
+    /// List the objects under `prefix` along with their user-defined metadata.
+    ///
+    /// `object_store` doesn't expose the `include=metadata` option of List Blobs, so we make the
+    /// requests ourselves, one page at a time.
+    pub(crate) fn list_with_metadata(
+        self,
+        prefix: Option<&Path>,
+    ) -> BoxStream<'static, object_store::Result<PyObjectMeta>> {
+        let default_prefix = Path::default();
+        let prefix = self
+            .store
+            .inner()
+            .full_path(prefix.unwrap_or(&default_prefix))
+            .into_owned();
+        let store = Arc::new(self);
+        // `None` once the last page has been read, otherwise the marker of the next page.
+        let initial: Option<Option<String>> = Some(None);
+        futures::stream::try_unfold(initial, move |marker| {
+            let store = store.clone();
+            let prefix = prefix.clone();
+            async move {
+                let Some(marker) = marker else {
+                    return Ok::<_, object_store::Error>(None);
+                };
+                let (metas, next_marker) = store.list_page(&prefix, marker.as_deref()).await?;
+                let metas =
+                    futures::stream::iter(metas.into_iter().map(Ok::<_, object_store::Error>));
+                Ok(Some((metas, next_marker.map(Some))))
+            }
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    /// Make a single List Blobs request, returning its objects and the marker of the next page.
+    async fn list_page(
+        &self,
+        prefix: &Path,
+        marker: Option<&str>,
+    ) -> object_store::Result<(Vec<PyObjectMeta>, Option<String>)> {
+        // Prefixes are evaluated on a path segment basis, as in `ObjectStore::list`
+        let request_prefix = (!prefix.as_ref().is_empty()).then(|| format!("{}/", prefix));
+
+        let mut url = self.container_url.clone();
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("restype", "container")
+                .append_pair("comp", "list")
+                .append_pair("include", "metadata");
+            if let Some(request_prefix) = &request_prefix {
+                query.append_pair("prefix", request_prefix);
+            }
+            if let Some(marker) = marker {
+                query.append_pair("marker", marker);
+            }
+        }
+
+        let credential = self
+            .store
+            .inner()
+            .inner()
+            .credentials()
+            .get_credential()
+            .await?;
+        let mut request = http::Request::builder()
+            .method(Method::GET)
+            .uri(url.as_str())
+            .body(HttpRequestBody::empty())
+            .map_err(|err| azure_error(err.to_string()))?;
+        AzureAuthorizer::new(&credential, &self.account).authorize(&mut request);
+
+        // The emulator is served over plain HTTP
+        let options = ClientOptions::new().with_allow_http(url.scheme() == "http");
+        let client = ReqwestConnector::default().connect(&options)?;
+        let response = client
+            .execute(request)
+            .await
+            .map_err(|err| azure_error(err.to_string()))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|err| azure_error(err.to_string()))?;
+        let body = String::from_utf8_lossy(&body);
+
+        if status == StatusCode::NOT_FOUND {
+            return Err(object_store::Error::NotFound {
+                path: prefix.to_string(),
+                source: body.to_string().into(),
+            });
+        }
+        if !status.is_success() {
+            return Err(azure_error(format!(
+                "List Blobs request failed with status {}: {}",
+                status, body
+            )));
+        }
+
+        let metas = parse_blob_list(&body, request_prefix.as_deref().unwrap_or_default())?
+            .into_iter()
+            .filter_map(|(meta, metadata)| {
+                // Strip the prefix of the store, as `MaybePrefixedStore` does
+                let location = match self.store.inner().prefix() {
+                    Some(store_prefix) => meta.location.prefix_match(store_prefix)?.collect(),
+                    None => meta.location,
+                };
+                Some(PyObjectMeta::with_metadata(
+                    ObjectMeta { location, ..meta },
+                    metadata,
+                ))
+            })
+            .collect();
+        let next_marker = xml_element(&body, "NextMarker")
+            .filter(|marker| !marker.is_empty())
+            .map(unescape_xml);
+        Ok((metas, next_marker))
+    }
+}
+
+pub(crate) fn generic_error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "S3",
+        source: msg.into().into(),
+    }
+}
+
+fn azure_error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "MicrosoftAzure",
+        source: msg.into().into(),
+    }
+}
+
+/// The text content of the first `name` element in `s`, which may have attributes.
+///
+/// Returns an empty string for an empty element, e.g. `<Metadata />`.
+pub(crate) fn xml_element<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    let open = format!("<{}", name);
+    let mut from = 0;
+    loop {
+        let start = from + s[from..].find(&open)? + open.len();
+        let rest = &s[start..];
+        // Skip elements whose name only starts with `name`
+        if !rest.starts_with(['>', ' ', '/']) {
+            from = start;
+            continue;
+        }
+        let tag_end = start + rest.find('>')?;
+        if s[..tag_end].ends_with('/') {
+            return Some("");
+        }
+        let content_start = tag_end + 1;
+        let content_end = content_start + s[content_start..].find(&format!("</{}>", name))?;
+        return Some(&s[content_start..content_end]);
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Tagging {
+    tag_set: TagSetXml,
+}
+
+#[derive(Deserialize)]
+struct TagSetXml {
+    #[serde(rename = "Tag", default)]
+    tags: Vec<TagXml>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TagXml {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+/// Parse the `Key` and `Value` of each `Tag` in a `GetObjectTagging` response.
+fn parse_tagging(body: &str) -> object_store::Result<IndexMap<String, String>> {
+    let tagging: Tagging = quick_xml::de::from_str(body)
+        .map_err(|err| generic_error(format!("Invalid GetObjectTagging response: {}", err)))?;
+    Ok(tagging
+        .tag_set
+        .tags
+        .into_iter()
+        .map(|tag| (tag.key, tag.value))
+        .collect())
+}
+
+/// Parse each `Blob` of a List Blobs response with its metadata.
+///
+/// As in `object_store`, directories of accounts with a hierarchical namespace and the blob named
+/// exactly `prefix` are omitted.
+fn parse_blob_list(
+    body: &str,
+    prefix: &str,
+) -> object_store::Result<Vec<(ObjectMeta, IndexMap<String, String>)>> {
+    let mut out = vec![];
+    for blob in body.split("<Blob>").skip(1) {
+        let name = unescape_xml(
+            xml_element(blob, "Name").ok_or_else(|| azure_error("Blob is missing a Name"))?,
+        );
+        let properties = xml_element(blob, "Properties").unwrap_or_default();
+        if xml_element(properties, "ResourceType") == Some("directory")
+            || name.len() <= prefix.len()
+        {
+            continue;
+        }
+
+        let last_modified = xml_element(properties, "Last-Modified")
+            .and_then(|s| DateTime::parse_from_rfc2822(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| azure_error(format!("Invalid Last-Modified for blob {}", name)))?;
+        let size = xml_element(properties, "Content-Length")
+            .and_then(|s| s.parse::<u64>().ok())
+            .ok_or_else(|| azure_error(format!("Invalid Content-Length for blob {}", name)))?;
+        let e_tag = xml_element(properties, "Etag").map(unescape_xml);
+        let metadata = xml_element(blob, "Metadata")
+            .map(parse_xml_children)
+            .unwrap_or_default();
+
+        let meta = ObjectMeta {
+            location: Path::parse(name)?,
+            last_modified,
+            size,
+            e_tag,
+            // For consistency with `object_store`, which doesn't include this when listing
+            version: None,
+        };
+        out.push((meta, metadata));
+    }
+    Ok(out)
+}
+
+/// Parse a sequence of simple elements, e.g. `<a>1</a><b>2</b>`, into a map of name to text.
+fn parse_xml_children(s: &str) -> IndexMap<String, String> {
+    let mut out = IndexMap::new();
+    let mut rest = s;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('>') else {
+            break;
+        };
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+        if let Some(name) = tag.strip_suffix('/') {
+            out.insert(name.trim().to_string(), String::new());
+            continue;
+        }
+        let close = format!("</{}>", tag);
+        let Some(value_end) = rest.find(&close) else {
+            break;
+        };
+        out.insert(tag.to_string(), unescape_xml(&rest[..value_end]));
+        rest = &rest[value_end + close.len()..];
+    }
+    out
+}
+
+pub(crate) fn unescape_xml(s: &str) -> String {
+    s.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
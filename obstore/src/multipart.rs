@@ -0,0 +1,399 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use object_store::multipart::{MultipartStore, PartId};
+use object_store::path::Path;
+use object_store::{MultipartId, PutPayload};
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3::types::{PyDict, PyString};
+use pyo3::{intern, IntoPyObjectExt};
+use pyo3_async_runtimes::tokio::future_into_py;
+use pyo3_bytes::PyBytes;
+use pyo3_object_store::{
+    resolve_store_token, PyAzureStore, PyGCSStore, PyMemoryStore, PyObjectStoreError,
+    PyObjectStoreResult, PyPath, PyS3Store,
+};
+use tokio::sync::Mutex;
+
+use crate::put::PyPutResult;
+use crate::runtime::get_runtime;
+use crate::utils::PyNone;
+
+/// A store that exposes explicit control over multipart uploads.
+#[derive(Clone)]
+pub(crate) struct MultipartCapableStore(Arc<dyn MultipartStore>);
+
+impl<'py> FromPyObject<'py> for MultipartCapableStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyS3Store>() {
+            Ok(Self(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyGCSStore>() {
+            Ok(Self(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyAzureStore>() {
+            Ok(Self(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyMemoryStore>() {
+            Ok(Self(store.get().as_ref().clone()))
+        } else {
+            let py = ob.py();
+            // Check for object-store instance from other library
+            let cls_name = ob
+                .getattr(intern!(py, "__class__"))?
+                .getattr(intern!(py, "__name__"))?
+                .extract::<PyBackedStr>()?;
+            if ["AzureStore", "GCSStore", "MemoryStore", "S3Store"].contains(&cls_name.as_ref()) {
+                return Err(PyValueError::new_err("You must use an object store instance exported from **the same library** as this function. They cannot be used across libraries.\nThis is because object store instances are compiled with a specific version of Rust and Python." ));
+            }
+
+            Err(PyValueError::new_err(format!(
+                "Expected an S3Store, GCSStore, AzureStore, or MemoryStore instance, got {}",
+                ob.repr()?
+            )))
+        }
+    }
+}
+
+/// The minimum size of every part but the last, as required by S3 and GCS.
+const MIN_CHUNK_SIZE: usize = 5 * 1024 * 1024;
+
+fn check_chunk_size(chunk_size: usize) -> PyResult<()> {
+    if chunk_size < MIN_CHUNK_SIZE {
+        return Err(PyValueError::new_err(format!(
+            "chunk_size must be at least 5 MiB ({MIN_CHUNK_SIZE} bytes), got {chunk_size}."
+        )));
+    }
+    Ok(())
+}
+
+/// The serializable state of an in-progress multipart upload.
+#[derive(Debug, Clone)]
+pub(crate) struct PyMultipartUploadState {
+    path: Path,
+    upload_id: MultipartId,
+    chunk_size: usize,
+    parts: Vec<PartId>,
+}
+
+impl<'py> FromPyObject<'py> for PyMultipartUploadState {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let py = ob.py();
+        let path = ob.get_item(intern!(py, "path"))?.extract::<String>()?;
+        let upload_id = ob.get_item(intern!(py, "upload_id"))?.extract()?;
+        let chunk_size = ob.get_item(intern!(py, "chunk_size"))?.extract()?;
+        check_chunk_size(chunk_size)?;
+        let parts = ob
+            .get_item(intern!(py, "parts"))?
+            .extract::<Vec<String>>()?
+            .into_iter()
+            .map(|content_id| PartId { content_id })
+            .collect();
+        Ok(Self {
+            path: path.into(),
+            upload_id,
+            chunk_size,
+            parts,
+        })
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyMultipartUploadState {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let parts = self
+            .parts
+            .into_iter()
+            .map(|part| part.content_id)
+            .collect::<Vec<_>>();
+        let mut dict = IndexMap::with_capacity(4);
+        dict.insert("path", self.path.as_ref().into_bound_py_any(py)?);
+        dict.insert("upload_id", self.upload_id.into_bound_py_any(py)?);
+        dict.insert("chunk_size", self.chunk_size.into_bound_py_any(py)?);
+        dict.insert("parts", parts.into_bound_py_any(py)?);
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, chunk_size=5242880))]
+pub(crate) fn create_multipart_upload(
+    py: Python,
+    store: MultipartCapableStore,
+    path: PyPath,
+    chunk_size: usize,
+) -> PyObjectStoreResult<PyMultipartUpload> {
+    check_chunk_size(chunk_size)?;
+    let runtime = get_runtime(py)?;
+    let path = Path::from(path);
+    let upload_id = py.allow_threads(|| runtime.block_on(store.0.create_multipart(&path)))?;
+    let state = PyMultipartUploadState {
+        path,
+        upload_id,
+        chunk_size,
+        parts: vec![],
+    };
+    Ok(PyMultipartUpload::new(store, state, false))
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, chunk_size=5242880))]
+pub(crate) fn create_multipart_upload_async(
+    py: Python,
+    store: MultipartCapableStore,
+    path: PyPath,
+    chunk_size: usize,
+) -> PyResult<Bound<PyAny>> {
+    check_chunk_size(chunk_size)?;
+    future_into_py(py, async move {
+        let path = Path::from(path);
+        let upload_id = store
+            .0
+            .create_multipart(&path)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        let state = PyMultipartUploadState {
+            path,
+            upload_id,
+            chunk_size,
+            parts: vec![],
+        };
+        Ok(PyMultipartUpload::new(store, state, true))
+    })
+}
+
+#[pyfunction]
+pub(crate) fn resume_multipart_upload(
+    store: MultipartCapableStore,
+    state: PyMultipartUploadState,
+) -> PyMultipartUpload {
+    PyMultipartUpload::new(store, state, false)
+}
+
+#[pyfunction]
+pub(crate) fn resume_multipart_upload_async(
+    store: MultipartCapableStore,
+    state: PyMultipartUploadState,
+) -> PyMultipartUpload {
+    PyMultipartUpload::new(store, state, true)
+}
+
+#[derive(Debug)]
+struct UploadBuffer {
+    /// Bytes that have been written but not yet uploaded as a part
+    buffer: Vec<u8>,
+    closed: bool,
+}
+
+#[pyclass(name = "MultipartUpload", frozen)]
+pub(crate) struct PyMultipartUpload {
+    store: MultipartCapableStore,
+    path: Path,
+    upload_id: MultipartId,
+    chunk_size: usize,
+    /// Completed parts are tracked separately from the write buffer so that the upload state can
+    /// be serialized while a part is still being uploaded.
+    parts: Arc<std::sync::Mutex<Vec<PartId>>>,
+    buffer: Arc<Mutex<UploadBuffer>>,
+    r#async: bool,
+}
+
+impl PyMultipartUpload {
+    fn new(store: MultipartCapableStore, state: PyMultipartUploadState, r#async: bool) -> Self {
+        Self {
+            store,
+            path: state.path,
+            upload_id: state.upload_id,
+            chunk_size: state.chunk_size,
+            parts: Arc::new(std::sync::Mutex::new(state.parts)),
+            buffer: Arc::new(Mutex::new(UploadBuffer {
+                buffer: Vec::with_capacity(state.chunk_size),
+                closed: false,
+            })),
+            r#async,
+        }
+    }
+
+    fn upload(&self) -> Upload {
+        Upload {
+            store: self.store.clone(),
+            path: self.path.clone(),
+            upload_id: self.upload_id.clone(),
+            chunk_size: self.chunk_size,
+            parts: self.parts.clone(),
+            buffer: self.buffer.clone(),
+        }
+    }
+}
+
+#[pymethods]
+impl PyMultipartUpload {
+    fn __repr__<'py>(&'py self, py: Python<'py>) -> &'py Bound<'py, PyString> {
+        if self.r#async {
+            intern!(py, "AsyncMultipartUpload")
+        } else {
+            intern!(py, "MultipartUpload")
+        }
+    }
+
+    fn abort<'py>(&'py self, py: Python<'py>) -> PyResult<PyObject> {
+        let upload = self.upload();
+        if self.r#async {
+            let out = future_into_py(py, async move {
+                upload.abort().await?;
+                Ok(PyNone)
+            })?;
+            Ok(out.unbind())
+        } else {
+            let runtime = get_runtime(py)?;
+            py.allow_threads(|| runtime.block_on(upload.abort()))?;
+            Ok(py.None())
+        }
+    }
+
+    #[getter]
+    fn bytes_uploaded(&self) -> usize {
+        self.parts.lock().unwrap().len() * self.chunk_size
+    }
+
+    #[getter]
+    fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    fn complete<'py>(&'py self, py: Python<'py>) -> PyResult<PyObject> {
+        let upload = self.upload();
+        if self.r#async {
+            let out = future_into_py(py, upload.complete())?;
+            Ok(out.unbind())
+        } else {
+            let runtime = get_runtime(py)?;
+            let out = py.allow_threads(|| runtime.block_on(upload.complete()))?;
+            out.into_py_any(py)
+        }
+    }
+
+    #[getter]
+    fn path(&self) -> &str {
+        self.path.as_ref()
+    }
+
+    fn state(&self) -> PyMultipartUploadState {
+        PyMultipartUploadState {
+            path: self.path.clone(),
+            upload_id: self.upload_id.clone(),
+            chunk_size: self.chunk_size,
+            parts: self.parts.lock().unwrap().clone(),
+        }
+    }
+
+    #[getter]
+    fn upload_id(&self) -> &str {
+        &self.upload_id
+    }
+
+    fn write<'py>(&'py self, py: Python<'py>, buffer: PyBytes) -> PyResult<PyObject> {
+        let upload = self.upload();
+        if self.r#async {
+            let out = future_into_py(py, upload.write(buffer))?;
+            Ok(out.unbind())
+        } else {
+            let runtime = get_runtime(py)?;
+            let out = py.allow_threads(|| runtime.block_on(upload.write(buffer)))?;
+            out.into_py_any(py)
+        }
+    }
+}
+
+/// An owned handle to the shared upload state, so that it can be moved into a future.
+struct Upload {
+    store: MultipartCapableStore,
+    path: Path,
+    upload_id: MultipartId,
+    chunk_size: usize,
+    parts: Arc<std::sync::Mutex<Vec<PartId>>>,
+    buffer: Arc<Mutex<UploadBuffer>>,
+}
+
+impl Upload {
+    /// Upload `data` as the next part of this upload.
+    async fn put_part(&self, data: Vec<u8>) -> PyObjectStoreResult<()> {
+        let part_idx = self.parts.lock().unwrap().len();
+        let part = self
+            .store
+            .0
+            .put_part(
+                &self.path,
+                &self.upload_id,
+                part_idx,
+                PutPayload::from(data),
+            )
+            .await?;
+        self.parts.lock().unwrap().push(part);
+        Ok(())
+    }
+
+    async fn write(self, buffer: PyBytes) -> PyResult<usize> {
+        let mut state = self.buffer.lock().await;
+        if state.closed {
+            return Err(PyIOError::new_err("Multipart upload already closed."));
+        }
+
+        let mut data = buffer.as_slice();
+        let buffer_length = data.len();
+        while !data.is_empty() {
+            let remaining = self.chunk_size - state.buffer.len();
+            let to_copy = remaining.min(data.len());
+            state.buffer.extend_from_slice(&data[..to_copy]);
+            data = &data[to_copy..];
+
+            if state.buffer.len() == self.chunk_size {
+                let chunk =
+                    std::mem::replace(&mut state.buffer, Vec::with_capacity(self.chunk_size));
+                self.put_part(chunk).await?;
+            }
+        }
+        Ok(buffer_length)
+    }
+
+    async fn complete(self) -> PyResult<PyPutResult> {
+        let mut state = self.buffer.lock().await;
+        if state.closed {
+            return Err(PyIOError::new_err("Multipart upload already closed."));
+        }
+
+        if !state.buffer.is_empty() {
+            let chunk = std::mem::take(&mut state.buffer);
+            self.put_part(chunk).await?;
+        }
+
+        let parts = self.parts.lock().unwrap().clone();
+        let result = self
+            .store
+            .0
+            .complete_multipart(&self.path, &self.upload_id, parts)
+            .await
+            .map_err(PyObjectStoreError::from)?;
+        state.closed = true;
+        Ok(PyPutResult(result))
+    }
+
+    async fn abort(self) -> PyResult<()> {
+        let mut state = self.buffer.lock().await;
+        if state.closed {
+            return Err(PyIOError::new_err("Multipart upload already closed."));
+        }
+
+        self.store
+            .0
+            .abort_multipart(&self.path, &self.upload_id)
+            .await
+            .map_err(PyObjectStoreError::from)?;
+        state.closed = true;
+        state.buffer.clear();
+        Ok(())
+    }
+}
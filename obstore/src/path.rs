@@ -1,6 +1,13 @@
 use object_store::path::Path;
 use pyo3::exceptions::PyTypeError;
 use pyo3::prelude::*;
+use pyo3::types::PyString;
+use pyo3::{intern, wrap_pyfunction};
+use pyo3_object_store::{extract_path_str, PyPath};
+
+fn is_path_like(ob: &Bound<PyAny>) -> PyResult<bool> {
+    Ok(ob.is_instance_of::<PyString>() || ob.hasattr(intern!(ob.py(), "__fspath__"))?)
+}
 
 pub(crate) enum PyPaths {
     One(Path),
@@ -10,16 +17,54 @@ pub(crate) enum PyPaths {
 
 impl<'py> FromPyObject<'py> for PyPaths {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        if let Ok(path) = ob.extract::<String>() {
-            Ok(Self::One(path.into()))
-        } else if let Ok(paths) = ob.extract::<Vec<String>>() {
+        if is_path_like(ob)? {
+            Ok(Self::One(ob.extract::<PyPath>()?.into_inner()))
+        } else if let Ok(paths) = ob.extract::<Vec<Bound<PyAny>>>() {
             Ok(Self::Many(
-                paths.into_iter().map(|path| path.into()).collect(),
+                paths
+                    .iter()
+                    .map(|path| Ok(path.extract::<PyPath>()?.into_inner()))
+                    .collect::<PyResult<_>>()?,
             ))
         } else {
-            Err(PyTypeError::new_err(
-                "Expected string path or sequence of string paths.",
-            ))
+            Err(PyTypeError::new_err("Expected path or sequence of paths."))
         }
     }
 }
+
+/// Sanitize `path` with the rules `object_store` applies when constructing a path from a
+/// string, percent-encoding characters that some stores don't support.
+#[pyfunction]
+fn normalize(path: &Bound<PyAny>) -> PyResult<String> {
+    let path = extract_path_str(path)?;
+    Ok(Path::from(&*path).to_string())
+}
+
+pub(crate) fn register_path_module(
+    py: Python<'_>,
+    parent_module: &Bound<'_, PyModule>,
+    parent_module_str: &str,
+    sub_module_str: &str,
+) -> PyResult<()> {
+    let full_module_string = format!("{}.{}", parent_module_str, sub_module_str);
+
+    let child_module = PyModule::new(parent_module.py(), sub_module_str)?;
+    child_module.add_wrapped(wrap_pyfunction!(normalize))?;
+
+    // Set the value of `__module__` correctly on each publicly exposed function or class
+    child_module
+        .getattr("normalize")?
+        .setattr(intern!(py, "__module__"), &full_module_string)?;
+
+    // Add the child module to the parent module
+    parent_module.add_submodule(&child_module)?;
+
+    py.import(intern!(py, "sys"))?
+        .getattr(intern!(py, "modules"))?
+        .set_item(&full_module_string, &child_module)?;
+
+    // needs to be set *after* `add_submodule()`
+    child_module.setattr("__name__", full_module_string)?;
+
+    Ok(())
+}
@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{Attribute, GetOptions, GetRange, ObjectMeta, ObjectStore};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath};
+
+use crate::runtime::get_runtime;
+
+pub(crate) struct PyProbeResult {
+    meta: ObjectMeta,
+    content_type: Option<String>,
+    supports_ranges: bool,
+}
+
+impl<'py> IntoPyObject<'py> for PyProbeResult {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(6);
+        dict.insert("path", self.meta.location.as_ref().into_bound_py_any(py)?);
+        dict.insert("size", self.meta.size.into_bound_py_any(py)?);
+        dict.insert("e_tag", self.meta.e_tag.into_bound_py_any(py)?);
+        dict.insert(
+            "last_modified",
+            self.meta.last_modified.into_bound_py_any(py)?,
+        );
+        dict.insert("content_type", self.content_type.into_bound_py_any(py)?);
+        dict.insert(
+            "supports_ranges",
+            self.supports_ranges.into_bound_py_any(py)?,
+        );
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+pub(crate) fn probe(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+) -> PyObjectStoreResult<PyProbeResult> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(probe_inner(store.into_inner(), path.into()))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+pub(crate) fn probe_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = probe_inner(store.into_inner(), path.into()).await?;
+        Ok(out)
+    })
+}
+
+async fn probe_inner(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+) -> PyObjectStoreResult<PyProbeResult> {
+    // A HEAD request returns the metadata and attributes without the body
+    let head = store
+        .get_opts(
+            &path,
+            GetOptions {
+                head: true,
+                ..Default::default()
+            },
+        )
+        .await?;
+    let content_type = head
+        .attributes
+        .get(&Attribute::ContentType)
+        .map(|value| value.as_ref().to_string());
+    let meta = head.meta;
+
+    // Request the first byte of the object. `object_store` errors if a server responds to a
+    // range request with the full object, which means ranges are unsupported, while any other
+    // error is raised. An empty object has no valid range, so we can't tell.
+    let supports_ranges = if meta.size > 0 {
+        let options = GetOptions {
+            if_match: meta.e_tag.clone(),
+            range: Some(GetRange::Bounded(0..1)),
+            ..Default::default()
+        };
+        match store.get_opts(&path, options).await {
+            Ok(result) => result.range == (0..1),
+            Err(err) if is_range_unsupported(&err) => false,
+            Err(err) => return Err(err.into()),
+        }
+    } else {
+        false
+    };
+
+    Ok(PyProbeResult {
+        meta,
+        content_type,
+        supports_ranges,
+    })
+}
+
+/// Whether a range request failed because the server doesn't support ranges.
+///
+/// `HTTPStore` returns `NotSupported`. The other stores don't expose the type of these errors, so
+/// they're recognized by the messages of `object_store`'s errors for a response that isn't
+/// partial, or whose `Content-Range` doesn't match the request, and of a
+/// `416 Range Not Satisfiable` status.
+fn is_range_unsupported(err: &object_store::Error) -> bool {
+    let source = match err {
+        object_store::Error::NotSupported { .. } => return true,
+        object_store::Error::Generic { source, .. } => source,
+        _ => return false,
+    };
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(source.as_ref());
+    while let Some(err) = source {
+        let message = err.to_string();
+        if message.starts_with("Received non-partial response")
+            || message.starts_with("Content-Range header")
+            || message.starts_with("Failed to parse value for CONTENT_RANGE header")
+            || (message.starts_with("Requested ") && message.contains(", got "))
+            || message.contains("status code: 416")
+        {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
@@ -0,0 +1,124 @@
+//! Reporting the progress of transfers to a Python callback.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use pyo3::prelude::*;
+
+/// Default minimum time between two calls of a progress callback.
+pub(crate) const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Default)]
+struct ProgressState {
+    transferred: u64,
+    total: Option<u64>,
+    last_report: Option<Instant>,
+    /// The value of `transferred` at the last report
+    reported: Option<u64>,
+}
+
+/// Calls a Python callback with the number of bytes transferred so far and the total number of
+/// bytes, if known.
+///
+/// Calls are throttled to at most one per `interval`, except for the final call on
+/// [`finish`][Self::finish], which is always made so that the callback sees the final count. The
+/// GIL is only taken for the duration of each call.
+///
+/// Exceptions raised by the callback are reported as unraisable exceptions rather than failing
+/// the transfer.
+#[derive(Debug)]
+pub(crate) struct PyProgress {
+    callback: PyObject,
+    interval: Duration,
+    state: Mutex<ProgressState>,
+}
+
+impl PyProgress {
+    pub(crate) fn new(
+        callback: Option<PyObject>,
+        interval: Duration,
+        total: Option<u64>,
+    ) -> Option<Arc<Self>> {
+        callback.map(|callback| {
+            Arc::new(Self {
+                callback,
+                interval,
+                state: Mutex::new(ProgressState {
+                    total,
+                    ..Default::default()
+                }),
+            })
+        })
+    }
+
+    /// Record that `n` more bytes were transferred.
+    pub(crate) fn update(&self, n: u64) {
+        let report = {
+            let mut state = self.state.lock().unwrap();
+            state.transferred += n;
+            let due = state
+                .last_report
+                .map_or(true, |last| last.elapsed() >= self.interval);
+            if due {
+                state.last_report = Some(Instant::now());
+                state.reported = Some(state.transferred);
+                Some((state.transferred, state.total))
+            } else {
+                None
+            }
+        };
+        if let Some((transferred, total)) = report {
+            self.report(transferred, total);
+        }
+    }
+
+    /// Report the final count, if it hasn't been reported already.
+    pub(crate) fn finish(&self) {
+        let report = {
+            let mut state = self.state.lock().unwrap();
+            if state.reported == Some(state.transferred) {
+                None
+            } else {
+                state.last_report = Some(Instant::now());
+                state.reported = Some(state.transferred);
+                Some((state.transferred, state.total))
+            }
+        };
+        if let Some((transferred, total)) = report {
+            self.report(transferred, total);
+        }
+    }
+
+    fn report(&self, transferred: u64, total: Option<u64>) {
+        Python::with_gil(|py| {
+            if let Err(err) = self.callback.call1(py, (transferred, total)) {
+                err.write_unraisable(py, Some(self.callback.bind(py)));
+            }
+        })
+    }
+}
+
+/// Wrap `stream` so that every chunk it yields is reported to `progress`, and the final count is
+/// reported once the stream is exhausted.
+pub(crate) fn track_stream(
+    stream: BoxStream<'static, object_store::Result<Bytes>>,
+    progress: Option<Arc<PyProgress>>,
+) -> BoxStream<'static, object_store::Result<Bytes>> {
+    let Some(progress) = progress else {
+        return stream;
+    };
+    let finish = progress.clone();
+    stream
+        .inspect_ok(move |bytes| progress.update(bytes.len() as u64))
+        .chain(
+            futures::stream::once(async move {
+                finish.finish();
+                None
+            })
+            .filter_map(futures::future::ready),
+        )
+        .boxed()
+}
@@ -3,24 +3,35 @@ use std::fs::File;
 use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
+use arrow::array::{ArrayRef, RecordBatch, StringArray};
+use arrow::datatypes::{DataType, Field, Schema};
 use bytes::Bytes;
 use indexmap::IndexMap;
 use object_store::path::Path;
 use object_store::{
-    ObjectStore, PutMode, PutMultipartOpts, PutOptions, PutPayload, PutResult, UpdateVersion,
-    WriteMultipart,
+    Attributes, MultipartUpload, ObjectStore, PutMode, PutMultipartOptions, PutOptions, PutPayload,
+    PutPayloadMut, PutResult, UpdateVersion,
 };
-use pyo3::exceptions::{PyStopAsyncIteration, PyStopIteration, PyValueError};
+use pyo3::exceptions::{PyKeyError, PyStopAsyncIteration, PyStopIteration, PyValueError};
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
-use pyo3::types::PyDict;
+use pyo3::types::{PyCapsule, PyDict};
 use pyo3::{intern, IntoPyObjectExt};
+use pyo3_arrow::PyRecordBatch;
 use pyo3_bytes::PyBytes;
 use pyo3_file::PyFileLikeObject;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreResult};
+use pyo3_object_store::{
+    check_keys, PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath, SizeHint,
+};
+use tokio::task::JoinSet;
 
 use crate::attributes::PyAttributes;
+use crate::compression::{compress_payload, set_content_encoding, Codec, Compressor};
+use crate::list::PyRecordBatchWrapper;
+use crate::manifest::PartChecksums;
+use crate::progress::{PyProgress, DEFAULT_PROGRESS_INTERVAL};
 use crate::runtime::get_runtime;
 use crate::tags::PyTagSet;
 
@@ -49,6 +60,7 @@ pub(crate) struct PyUpdateVersion(UpdateVersion);
 
 impl<'py> FromPyObject<'py> for PyUpdateVersion {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        check_keys(ob, "mode", &["e_tag", "version"])?;
         // Update to use derive(FromPyObject) when default is implemented:
         // https://github.com/PyO3/pyo3/issues/4643
         let dict = ob.extract::<HashMap<String, Bound<PyAny>>>()?;
@@ -59,6 +71,194 @@ impl<'py> FromPyObject<'py> for PyUpdateVersion {
     }
 }
 
+/// The maximum number of parts in a multipart upload supported by S3 and GCS.
+const DEFAULT_MAX_PARTS: usize = 10_000;
+
+/// The maximum size of a single part supported by S3.
+const MAX_PART_SIZE: usize = 5 * 1024 * 1024 * 1024;
+
+/// Part sizes in `"auto"` mode are rounded up to a multiple of this.
+const AUTO_PART_SIZE_ALIGNMENT: usize = 1024 * 1024;
+
+/// How the part size grows in `"auto"` mode when the total size is not known up front.
+///
+/// Starting from 5MB parts, doubling the part size ten times over 10,000 parts allows uploads of
+/// up to ~5TB, the maximum object size on S3.
+const AUTO_PART_SIZE_GROWTH: f64 = 2.0;
+
+/// When to use multipart uploads, and how to size their parts.
+#[derive(Debug, Clone)]
+pub(crate) enum PyMultipartPolicy {
+    /// Size parts from the total size of the upload, so that it stays within `DEFAULT_MAX_PARTS`.
+    Auto,
+    /// Use the provided settings, falling back to `chunk_size` where not provided.
+    Manual {
+        threshold: Option<usize>,
+        part_size: Option<usize>,
+        part_size_growth: Option<f64>,
+        max_parts: Option<usize>,
+    },
+}
+
+impl<'py> FromPyObject<'py> for PyMultipartPolicy {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(s) = ob.extract::<PyBackedStr>() {
+            if s.eq_ignore_ascii_case("auto") {
+                return Ok(Self::Auto);
+            }
+            return Err(PyValueError::new_err(format!(
+                "Unexpected input for multipart_policy: {}. Expected 'auto' or a dict.",
+                &*s
+            )));
+        }
+
+        check_keys(
+            ob,
+            "multipart_policy",
+            &["threshold", "part_size", "part_size_growth", "max_parts"],
+        )?;
+        let dict = ob.extract::<HashMap<String, Bound<PyAny>>>()?;
+        let part_size_growth = dict
+            .get("part_size_growth")
+            .map(|x| x.extract::<f64>())
+            .transpose()?;
+        if part_size_growth.is_some_and(|growth| growth < 1.0) {
+            return Err(PyValueError::new_err(
+                "part_size_growth must be greater than or equal to 1.",
+            ));
+        }
+        let max_parts = dict.get("max_parts").map(|x| x.extract()).transpose()?;
+        if max_parts == Some(0) {
+            return Err(PyValueError::new_err("max_parts must be greater than 0."));
+        }
+        Ok(Self::Manual {
+            threshold: dict.get("threshold").map(|x| x.extract()).transpose()?,
+            part_size: dict.get("part_size").map(|x| x.extract()).transpose()?,
+            part_size_growth,
+            max_parts,
+        })
+    }
+}
+
+impl PyMultipartPolicy {
+    /// Resolve this policy into concrete part sizes, given the size of the input if known.
+    ///
+    /// With no policy, this matches the historical behavior of `put`: fixed `chunk_size` parts and
+    /// no limit on the number of parts.
+    fn resolve(
+        policy: Option<&Self>,
+        chunk_size: usize,
+        nbytes: Option<usize>,
+    ) -> PyResult<ResolvedMultipartPolicy> {
+        let resolved = match policy {
+            None => ResolvedMultipartPolicy {
+                threshold: chunk_size,
+                part_size: chunk_size,
+                part_size_growth: 1.0,
+                max_parts: usize::MAX,
+            },
+            Some(Self::Auto) => {
+                let (part_size, part_size_growth) = match nbytes {
+                    Some(nbytes) => {
+                        let part_size = nbytes
+                            .div_ceil(DEFAULT_MAX_PARTS)
+                            .next_multiple_of(AUTO_PART_SIZE_ALIGNMENT);
+                        (part_size.max(chunk_size), 1.0)
+                    }
+                    None => (chunk_size, AUTO_PART_SIZE_GROWTH),
+                };
+                ResolvedMultipartPolicy {
+                    threshold: chunk_size,
+                    part_size,
+                    part_size_growth,
+                    max_parts: DEFAULT_MAX_PARTS,
+                }
+            }
+            Some(Self::Manual {
+                threshold,
+                part_size,
+                part_size_growth,
+                max_parts,
+            }) => {
+                let part_size = part_size.unwrap_or(chunk_size);
+                ResolvedMultipartPolicy {
+                    threshold: threshold.unwrap_or(part_size),
+                    part_size,
+                    part_size_growth: part_size_growth.unwrap_or(1.0),
+                    max_parts: max_parts.unwrap_or(DEFAULT_MAX_PARTS),
+                }
+            }
+        };
+
+        if resolved.part_size == 0 {
+            return Err(PyValueError::new_err("part_size must be greater than 0."));
+        }
+        if let Some(nbytes) = nbytes {
+            if nbytes > resolved.threshold && nbytes > resolved.capacity() {
+                return Err(PyValueError::new_err(format!(
+                    "Upload of {} bytes would need more than {} parts with a starting part size of {} bytes. Increase part_size or use multipart_policy='auto'.",
+                    nbytes, resolved.max_parts, resolved.part_size
+                )));
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+/// A [`PyMultipartPolicy`] with all settings filled in.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResolvedMultipartPolicy {
+    /// Inputs larger than this many bytes use a multipart upload
+    threshold: usize,
+    /// The size of the first part
+    part_size: usize,
+    /// Factor by which the part size grows after every tenth of `max_parts`
+    part_size_growth: f64,
+    /// The maximum number of parts in an upload
+    max_parts: usize,
+}
+
+impl ResolvedMultipartPolicy {
+    /// Whether an input of `nbytes` should use a multipart upload.
+    ///
+    /// We always use multipart uploads for push-based sources because we have no way of knowing
+    /// how large they'll be and we don't want to buffer them into memory.
+    fn use_multipart(&self, nbytes: Option<usize>) -> bool {
+        nbytes.map_or(true, |nbytes| nbytes > self.threshold)
+    }
+
+    /// Number of parts after which the part size grows.
+    fn growth_interval(&self) -> usize {
+        (self.max_parts / 10).max(1)
+    }
+
+    /// The size of the part with (zero-based) index `part_idx`.
+    fn part_size(&self, part_idx: usize) -> usize {
+        if self.part_size_growth == 1.0 {
+            return self.part_size;
+        }
+        let steps = (part_idx / self.growth_interval()).min(i32::MAX as usize) as i32;
+        let size = self.part_size as f64 * self.part_size_growth.powi(steps);
+        (size as usize).clamp(self.part_size, MAX_PART_SIZE.max(self.part_size))
+    }
+
+    /// The largest upload that fits in `max_parts` parts.
+    fn capacity(&self) -> usize {
+        if self.max_parts == usize::MAX {
+            return usize::MAX;
+        }
+        let interval = self.growth_interval();
+        let mut capacity = 0usize;
+        let mut part_idx = 0;
+        while part_idx < self.max_parts {
+            let parts = interval.min(self.max_parts - part_idx);
+            capacity = capacity.saturating_add(self.part_size(part_idx).saturating_mul(parts));
+            part_idx += parts;
+        }
+        capacity
+    }
+}
+
 /// Sources to `put` that are pull-based. I.e. we can pull a specific number of bytes from them.
 pub(crate) enum PullSource {
     File(BufReader<File>),
@@ -74,11 +274,6 @@ impl PullSource {
         self.seek(SeekFrom::Start(origin_pos))?;
         Ok(size.try_into().unwrap())
     }
-
-    /// Whether to use multipart uploads.
-    fn use_multipart(&mut self, chunk_size: usize) -> PyObjectStoreResult<bool> {
-        Ok(self.nbytes()? > chunk_size)
-    }
 }
 
 impl Read for PullSource {
@@ -111,7 +306,7 @@ pub(crate) enum SyncPushSource {
 }
 
 impl SyncPushSource {
-    fn next_chunk(&mut self) -> PyObjectStoreResult<Option<Bytes>> {
+    pub(crate) fn next_chunk(&mut self) -> PyObjectStoreResult<Option<Bytes>> {
         match self {
             Self::Iterator(iter) => {
                 Python::with_gil(|py| match iter.call_method0(py, intern!(py, "__next__")) {
@@ -163,7 +358,7 @@ impl AsyncPushSource {
         Ok(PutPayload::from_iter(buffers))
     }
 
-    async fn next_chunk(&mut self) -> PyObjectStoreResult<Option<Bytes>> {
+    pub(crate) async fn next_chunk(&mut self) -> PyObjectStoreResult<Option<Bytes>> {
         match self {
             Self::AsyncIterator(iter) => {
                 // Note: we have to acquire the GIL once to create the future and a separate time
@@ -205,16 +400,23 @@ pub(crate) enum PutInput {
 
     /// Input that gives us chunks of unknown size, asynchronously
     AsyncPush(AsyncPushSource),
+
+    /// Input whose chunks are all provided up front
+    Slices(Vec<Bytes>),
 }
 
 impl PutInput {
-    /// Whether to use multipart uploads.
-    fn use_multipart(&mut self, chunk_size: usize) -> PyObjectStoreResult<bool> {
+    /// Input made of `buffers`, which are uploaded in order without being copied.
+    fn from_buffers(buffers: Vec<PyBytes>) -> Self {
+        Self::Slices(buffers.into_iter().map(PyBytes::into_inner).collect())
+    }
+
+    /// Number of bytes in the input, if known up front.
+    fn nbytes(&mut self) -> PyObjectStoreResult<Option<usize>> {
         match self {
-            Self::Pull(pull_source) => pull_source.use_multipart(chunk_size),
-            // We always use multipart uploads for push-based sources because we have no way of
-            // knowing how large they'll be and we don't want to buffer them into memory.
-            _ => Ok(true),
+            Self::Pull(pull_source) => Ok(Some(pull_source.nbytes()?)),
+            Self::Slices(slices) => Ok(Some(slices.iter().map(Bytes::len).sum())),
+            _ => Ok(None),
         }
     }
 
@@ -230,6 +432,8 @@ impl PutInput {
             },
             Self::SyncPush(push_source) => push_source.read_all(),
             Self::AsyncPush(push_source) => push_source.read_all().await,
+            // The payload references each slice, instead of concatenating them
+            Self::Slices(slices) => Ok(PutPayload::from_iter(std::mem::take(slices))),
         }
     }
 }
@@ -275,28 +479,124 @@ impl<'py> FromPyObject<'py> for PutInput {
     }
 }
 
-pub(crate) struct PyPutResult(PutResult);
+/// The result of a put request, exposed to Python as `obstore.PutResult`.
+#[pyclass(name = "PutResult", module = "obstore", frozen)]
+pub(crate) struct PyPutResult(pub(crate) PutResult);
+
+/// The keys of the `dict` returned by earlier versions.
+const PUT_RESULT_KEYS: [&str; 2] = ["e_tag", "version"];
+
+impl PyPutResult {
+    fn field(&self, key: &str) -> Option<Option<&str>> {
+        match key {
+            "e_tag" => Some(self.0.e_tag.as_deref()),
+            "version" => Some(self.0.version.as_deref()),
+            _ => None,
+        }
+    }
+
+    fn to_batch(&self) -> RecordBatch {
+        let schema = Schema::new(vec![
+            Field::new("e_tag", DataType::Utf8, true),
+            Field::new("version", DataType::Utf8, true),
+        ]);
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(StringArray::from(vec![self.0.e_tag.clone()])),
+            Arc::new(StringArray::from(vec![self.0.version.clone()])),
+        ];
+        // This unwrap is ok because we know the RecordBatch is valid.
+        RecordBatch::try_new(Arc::new(schema), columns).unwrap()
+    }
+}
+
+#[pymethods]
+impl PyPutResult {
+    /// Look up a field by name, as with the `dict` returned by earlier versions.
+    fn __getitem__(&self, key: &str) -> PyResult<Option<&str>> {
+        self.field(key)
+            .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+    }
+
+    #[pyo3(signature = (key, default=None))]
+    fn get<'py>(
+        &self,
+        py: Python<'py>,
+        key: &str,
+        default: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        match self.field(key) {
+            Some(value) => value.into_bound_py_any(py),
+            None => default.into_bound_py_any(py),
+        }
+    }
+
+    fn keys(&self) -> [&'static str; 2] {
+        PUT_RESULT_KEYS
+    }
+
+    /// Equal to another `PutResult` with the same fields, or to the `dict` returned by earlier
+    /// versions.
+    fn __eq__<'py>(&self, other: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let py = other.py();
+        if let Ok(other) = other.downcast::<PyPutResult>() {
+            (self.0 == other.get().0).into_bound_py_any(py)
+        } else if other.is_instance_of::<PyDict>() {
+            self.to_dict(py)?.eq(other)?.into_bound_py_any(py)
+        } else {
+            Ok(py.NotImplemented().into_bound(py))
+        }
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "PutResult(e_tag={}, version={})",
+            self.0.e_tag.as_deref().into_bound_py_any(py)?.repr()?,
+            self.0.version.as_deref().into_bound_py_any(py)?.repr()?,
+        ))
+    }
+
+    /// Export as an Arrow C Array, as a struct array with one row.
+    #[pyo3(signature = (requested_schema=None))]
+    fn __arrow_c_array__<'py>(
+        &self,
+        py: Python<'py>,
+        requested_schema: Option<Bound<'py, PyCapsule>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        PyRecordBatch::new(self.to_batch())
+            .into_pyobject(py)?
+            .call_method1(intern!(py, "__arrow_c_array__"), (requested_schema,))
+    }
+
+    #[getter]
+    fn e_tag(&self) -> Option<&str> {
+        self.0.e_tag.as_deref()
+    }
+
+    #[getter]
+    fn version(&self) -> Option<&str> {
+        self.0.version.as_deref()
+    }
 
-impl<'py> IntoPyObject<'py> for PyPutResult {
-    type Target = PyDict;
-    type Output = Bound<'py, PyDict>;
-    type Error = PyErr;
+    fn to_arrow(&self) -> PyRecordBatchWrapper {
+        PyRecordBatchWrapper::new(self.to_batch())
+    }
 
-    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
-        let mut dict = IndexMap::with_capacity(2);
-        dict.insert("e_tag", self.0.e_tag.into_bound_py_any(py)?);
-        dict.insert("version", self.0.version.into_bound_py_any(py)?);
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict: IndexMap<_, _> = PUT_RESULT_KEYS
+            .into_iter()
+            .map(|key| (key, self.field(key).flatten()))
+            .collect();
         dict.into_pyobject(py)
     }
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, file, *, attributes=None, tags=None, mode=None, use_multipart=None, chunk_size=5242880, max_concurrency=12))]
+#[pyo3(signature = (store, path, file, *, attributes=None, tags=None, mode=None, use_multipart=None, chunk_size=5242880, max_concurrency=12, multipart_policy=None, manifest=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, timeout=None, compress=None))]
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn put(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     mut file: PutInput,
     attributes: Option<PyAttributes>,
     tags: Option<PyTagSet>,
@@ -304,6 +604,12 @@ pub(crate) fn put(
     use_multipart: Option<bool>,
     chunk_size: usize,
     max_concurrency: usize,
+    multipart_policy: Option<PyMultipartPolicy>,
+    manifest: Option<PyPath>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    timeout: Option<Duration>,
+    compress: Option<Codec>,
 ) -> PyObjectStoreResult<PyPutResult> {
     if matches!(file, PutInput::AsyncPush(_)) {
         return Err(
@@ -311,11 +617,12 @@ pub(crate) fn put(
         );
     }
 
-    let mut use_multipart = if let Some(use_multipart) = use_multipart {
-        use_multipart
-    } else {
-        file.use_multipart(chunk_size)?
-    };
+    let nbytes = file.nbytes()?;
+    let policy = PyMultipartPolicy::resolve(multipart_policy.as_ref(), chunk_size, nbytes)?;
+    let mut use_multipart = use_multipart.unwrap_or_else(|| policy.use_multipart(nbytes));
+    // The size of a compressed upload isn't known up front
+    let total = nbytes.filter(|_| compress.is_none()).map(|n| n as u64);
+    let progress = PyProgress::new(progress, progress_interval, total);
 
     // If mode is provided and not Overwrite, force a non-multipart put
     if let Some(mode) = &mode {
@@ -324,36 +631,49 @@ pub(crate) fn put(
         }
     }
 
+    let manifest = manifest.map(Path::from);
+    let store = store.with_timeout(timeout);
     let runtime = get_runtime(py)?;
-    if use_multipart {
-        runtime.block_on(put_multipart_inner(
-            store.into_inner(),
-            &path.into(),
-            file,
-            chunk_size,
-            max_concurrency,
-            attributes,
-            tags,
-        ))
-    } else {
-        runtime.block_on(put_inner(
-            store.into_inner(),
-            &path.into(),
-            file,
-            attributes,
-            tags,
-            mode,
-        ))
-    }
+    // The GIL is released, as the parts of multipart uploads are uploaded from other threads,
+    // which may need it to call a credential provider
+    py.allow_threads(|| {
+        if use_multipart {
+            runtime.block_on(put_multipart_inner(
+                store.into_inner(),
+                &path.into(),
+                file,
+                nbytes,
+                policy,
+                max_concurrency,
+                attributes,
+                tags,
+                manifest,
+                progress,
+                compress,
+            ))
+        } else {
+            runtime.block_on(put_inner(
+                store.into_inner(),
+                &path.into(),
+                file,
+                attributes,
+                tags,
+                mode,
+                manifest,
+                progress,
+                compress,
+            ))
+        }
+    })
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, path, file, *, attributes=None, tags=None, mode=None, use_multipart=None, chunk_size=5242880, max_concurrency=12))]
+#[pyo3(signature = (store, path, file, *, attributes=None, tags=None, mode=None, use_multipart=None, chunk_size=5242880, max_concurrency=12, multipart_policy=None, manifest=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, timeout=None, compress=None))]
 #[allow(clippy::too_many_arguments)]
 pub(crate) fn put_async(
     py: Python,
     store: PyObjectStore,
-    path: String,
+    path: PyPath,
     mut file: PutInput,
     attributes: Option<PyAttributes>,
     tags: Option<PyTagSet>,
@@ -361,12 +681,19 @@ pub(crate) fn put_async(
     use_multipart: Option<bool>,
     chunk_size: usize,
     max_concurrency: usize,
+    multipart_policy: Option<PyMultipartPolicy>,
+    manifest: Option<PyPath>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    timeout: Option<Duration>,
+    compress: Option<Codec>,
 ) -> PyResult<Bound<PyAny>> {
-    let mut use_multipart = if let Some(use_multipart) = use_multipart {
-        use_multipart
-    } else {
-        file.use_multipart(chunk_size)?
-    };
+    let nbytes = file.nbytes()?;
+    let policy = PyMultipartPolicy::resolve(multipart_policy.as_ref(), chunk_size, nbytes)?;
+    let mut use_multipart = use_multipart.unwrap_or_else(|| policy.use_multipart(nbytes));
+    // The size of a compressed upload isn't known up front
+    let total = nbytes.filter(|_| compress.is_none()).map(|n| n as u64);
+    let progress = PyProgress::new(progress, progress_interval, total);
 
     // If mode is provided and not Overwrite, force a non-multipart put
     if let Some(mode) = &mode {
@@ -375,16 +702,22 @@ pub(crate) fn put_async(
         }
     }
 
+    let manifest = manifest.map(Path::from);
+    let store = store.with_timeout(timeout);
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
         let result = if use_multipart {
             put_multipart_inner(
                 store.into_inner(),
                 &path.into(),
                 file,
-                chunk_size,
+                nbytes,
+                policy,
                 max_concurrency,
                 attributes,
                 tags,
+                manifest,
+                progress,
+                compress,
             )
             .await?
         } else {
@@ -395,6 +728,9 @@ pub(crate) fn put_async(
                 attributes,
                 tags,
                 mode,
+                manifest,
+                progress,
+                compress,
             )
             .await?
         };
@@ -402,6 +738,89 @@ pub(crate) fn put_async(
     })
 }
 
+#[pyfunction]
+#[pyo3(signature = (store, path, buffers, *, attributes=None, tags=None, mode=None, use_multipart=None, chunk_size=5242880, max_concurrency=12, multipart_policy=None, manifest=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, timeout=None, compress=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn put_slices(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    buffers: Vec<PyBytes>,
+    attributes: Option<PyAttributes>,
+    tags: Option<PyTagSet>,
+    mode: Option<PyPutMode>,
+    use_multipart: Option<bool>,
+    chunk_size: usize,
+    max_concurrency: usize,
+    multipart_policy: Option<PyMultipartPolicy>,
+    manifest: Option<PyPath>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    timeout: Option<Duration>,
+    compress: Option<Codec>,
+) -> PyObjectStoreResult<PyPutResult> {
+    put(
+        py,
+        store,
+        path,
+        PutInput::from_buffers(buffers),
+        attributes,
+        tags,
+        mode,
+        use_multipart,
+        chunk_size,
+        max_concurrency,
+        multipart_policy,
+        manifest,
+        progress,
+        progress_interval,
+        timeout,
+        compress,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, buffers, *, attributes=None, tags=None, mode=None, use_multipart=None, chunk_size=5242880, max_concurrency=12, multipart_policy=None, manifest=None, progress=None, progress_interval=DEFAULT_PROGRESS_INTERVAL, timeout=None, compress=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn put_slices_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    buffers: Vec<PyBytes>,
+    attributes: Option<PyAttributes>,
+    tags: Option<PyTagSet>,
+    mode: Option<PyPutMode>,
+    use_multipart: Option<bool>,
+    chunk_size: usize,
+    max_concurrency: usize,
+    multipart_policy: Option<PyMultipartPolicy>,
+    manifest: Option<PyPath>,
+    progress: Option<PyObject>,
+    progress_interval: Duration,
+    timeout: Option<Duration>,
+    compress: Option<Codec>,
+) -> PyResult<Bound<PyAny>> {
+    put_async(
+        py,
+        store,
+        path,
+        PutInput::from_buffers(buffers),
+        attributes,
+        tags,
+        mode,
+        use_multipart,
+        chunk_size,
+        max_concurrency,
+        multipart_policy,
+        manifest,
+        progress,
+        progress_interval,
+        timeout,
+        compress,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
 async fn put_inner(
     store: Arc<dyn ObjectStore>,
     path: &Path,
@@ -409,6 +828,9 @@ async fn put_inner(
     attributes: Option<PyAttributes>,
     tags: Option<PyTagSet>,
     mode: Option<PyPutMode>,
+    manifest: Option<Path>,
+    progress: Option<Arc<PyProgress>>,
+    compress: Option<Codec>,
 ) -> PyObjectStoreResult<PyPutResult> {
     let mut opts = PutOptions::default();
 
@@ -421,21 +843,54 @@ async fn put_inner(
     if let Some(mode) = mode {
         opts.mode = mode.0;
     }
+    let inferred_attributes =
+        compress.is_some_and(|codec| set_content_encoding(&mut opts.attributes, codec));
 
-    let payload = reader.read_all().await?;
-    Ok(PyPutResult(store.put_opts(path, payload, opts).await?))
+    let mut payload = reader.read_all().await?;
+    if let Some(codec) = compress {
+        payload = compress_payload(codec, &payload)?;
+    }
+    let checksums = manifest.is_some().then(|| {
+        let mut checksums = PartChecksums::default();
+        checksums.push(&payload);
+        checksums
+    });
+    let nbytes = payload.content_length() as u64;
+    let result = match store.put_opts(path, payload.clone(), opts.clone()).await {
+        // Stores that don't support attributes still get the compressed data
+        Err(object_store::Error::NotImplemented) if inferred_attributes => {
+            opts.attributes = Attributes::new();
+            store.put_opts(path, payload, opts).await?
+        }
+        result => result?,
+    };
+    if let Some(progress) = progress {
+        progress.update(nbytes);
+        progress.finish();
+    }
+    if let (Some(manifest), Some(checksums)) = (manifest, checksums) {
+        checksums
+            .write_manifest(&store, &manifest, path, &result)
+            .await?;
+    }
+    Ok(PyPutResult(result))
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn put_multipart_inner(
     store: Arc<dyn ObjectStore>,
     path: &Path,
     reader: PutInput,
-    chunk_size: usize,
+    nbytes: Option<usize>,
+    policy: ResolvedMultipartPolicy,
     max_concurrency: usize,
     attributes: Option<PyAttributes>,
     tags: Option<PyTagSet>,
+    manifest: Option<Path>,
+    progress: Option<Arc<PyProgress>>,
+    compress: Option<Codec>,
 ) -> PyObjectStoreResult<PyPutResult> {
-    let mut opts = PutMultipartOpts::default();
+    let mut opts = PutMultipartOptions::default();
 
     if let Some(attributes) = attributes {
         opts.attributes = attributes.into_inner();
@@ -443,13 +898,36 @@ async fn put_multipart_inner(
     if let Some(tags) = tags {
         opts.tags = tags.into_inner();
     }
+    let inferred_attributes =
+        compress.is_some_and(|codec| set_content_encoding(&mut opts.attributes, codec));
+    // Lets stores that support it, such as a LocalStore that preallocates files, allocate space up
+    // front. The size of a compressed upload isn't known until it's done.
+    if let (Some(nbytes), None) = (nbytes, compress) {
+        opts.extensions.insert(SizeHint(nbytes as u64));
+    }
 
-    let upload = store.put_multipart_opts(path, opts).await?;
-    let mut writer = WriteMultipart::new_with_chunk_size(upload, chunk_size);
+    let upload = match store.put_multipart_opts(path, opts.clone()).await {
+        // Stores that don't support attributes still get the compressed data
+        Err(object_store::Error::NotImplemented) if inferred_attributes => {
+            opts.attributes = Attributes::new();
+            store.put_multipart_opts(path, opts).await?
+        }
+        result => result?,
+    };
+    let compressor = compress.map(Compressor::new).transpose()?;
+    let mut writer = PolicyWriter::new(upload, policy, manifest.is_some(), progress, compressor);
 
     // Make sure to call abort if the multipart upload failed for any reason
-    match write_multipart(&mut writer, reader, chunk_size, max_concurrency).await {
-        Ok(()) => Ok(PyPutResult(writer.finish().await?)),
+    match write_multipart(&mut writer, reader, policy.part_size, max_concurrency).await {
+        Ok(()) => {
+            let (result, checksums) = writer.finish().await?;
+            if let (Some(manifest), Some(checksums)) = (manifest, checksums) {
+                checksums
+                    .write_manifest(&store, &manifest, path, &result)
+                    .await?;
+            }
+            Ok(PyPutResult(result))
+        }
         Err(err) => {
             writer.abort().await?;
             Err(err)
@@ -458,7 +936,7 @@ async fn put_multipart_inner(
 }
 
 async fn write_multipart(
-    writer: &mut WriteMultipart,
+    writer: &mut PolicyWriter,
     reader: PutInput,
     chunk_size: usize,
     max_concurrency: usize,
@@ -472,23 +950,192 @@ async fn write_multipart(
                 break;
             } else {
                 writer.wait_for_capacity(max_concurrency).await?;
-                writer.write(&scratch_buffer[0..read_size]);
+                // Copy a short read, rather than keep the whole buffer alive for a few bytes
+                let buf = if read_size == chunk_size {
+                    Bytes::from(scratch_buffer)
+                } else {
+                    Bytes::copy_from_slice(&scratch_buffer[..read_size])
+                };
+                writer.put(buf)?;
             }
         },
         PutInput::SyncPush(push_reader) => {
             for buf in push_reader {
                 writer.wait_for_capacity(max_concurrency).await?;
-                writer.put(buf?);
+                writer.put(buf?)?;
             }
         }
         PutInput::AsyncPush(mut push_reader) => {
             // Note: I believe that only one __anext__ call can happen at a time
             while let Some(buf) = push_reader.next_chunk().await? {
                 writer.wait_for_capacity(max_concurrency).await?;
-                writer.put(buf);
+                writer.put(buf)?;
+            }
+        }
+        PutInput::Slices(slices) => {
+            for slice in slices {
+                writer.wait_for_capacity(max_concurrency).await?;
+                writer.put(slice)?;
             }
         }
     }
 
     Ok(())
 }
+
+/// A multipart writer, like [`object_store::WriteMultipart`], whose part size follows a
+/// [`ResolvedMultipartPolicy`] instead of being fixed.
+struct PolicyWriter {
+    /// The upload, until it's completed or aborted
+    upload: Option<Box<dyn MultipartUpload>>,
+    policy: ResolvedMultipartPolicy,
+    buffer: PutPayloadMut,
+    /// Number of parts started so far
+    parts: usize,
+    /// Checksums of the parts started so far, if a manifest was requested
+    checksums: Option<PartChecksums>,
+    progress: Option<Arc<PyProgress>>,
+    /// In-flight part uploads, each of which resolves to the size of its part
+    tasks: JoinSet<object_store::Result<usize>>,
+    /// The encoder that data is passed through before being buffered, if compressing
+    compressor: Option<Compressor>,
+}
+
+impl PolicyWriter {
+    fn new(
+        upload: Box<dyn MultipartUpload>,
+        policy: ResolvedMultipartPolicy,
+        checksums: bool,
+        progress: Option<Arc<PyProgress>>,
+        compressor: Option<Compressor>,
+    ) -> Self {
+        Self {
+            upload: Some(upload),
+            policy,
+            buffer: PutPayloadMut::new(),
+            parts: 0,
+            checksums: checksums.then(PartChecksums::default),
+            progress,
+            tasks: JoinSet::new(),
+            compressor,
+        }
+    }
+
+    /// Wait until there are less than `max_concurrency` parts in progress
+    async fn wait_for_capacity(&mut self, max_concurrency: usize) -> object_store::Result<()> {
+        while !self.tasks.is_empty() && self.tasks.len() >= max_concurrency {
+            let part_size = self.tasks.join_next().await.unwrap()??;
+            if let Some(progress) = &self.progress {
+                progress.update(part_size as u64);
+            }
+        }
+        Ok(())
+    }
+
+    /// Buffer `bytes`, compressing them if requested, and start an upload for each part that fills
+    /// up
+    fn put(&mut self, bytes: Bytes) -> PyObjectStoreResult<()> {
+        let bytes = match &mut self.compressor {
+            Some(compressor) => compressor.compress(&bytes)?,
+            None => bytes,
+        };
+        self.buffer_bytes(bytes)
+    }
+
+    fn buffer_bytes(&mut self, mut bytes: Bytes) -> PyObjectStoreResult<()> {
+        while !bytes.is_empty() {
+            let remaining = self.policy.part_size(self.parts) - self.buffer.content_length();
+            if bytes.len() < remaining {
+                self.buffer.push(bytes);
+                return Ok(());
+            }
+            self.buffer.push(bytes.split_to(remaining));
+            self.put_part()?;
+        }
+        Ok(())
+    }
+
+    fn put_part(&mut self) -> PyObjectStoreResult<()> {
+        if self.parts >= self.policy.max_parts {
+            return Err(PyValueError::new_err(format!(
+                "Upload exceeded the maximum of {} parts. Increase part_size or part_size_growth, or use multipart_policy='auto'.",
+                self.policy.max_parts
+            ))
+            .into());
+        }
+        let part = PutPayload::from(std::mem::take(&mut self.buffer));
+        if let Some(checksums) = &mut self.checksums {
+            checksums.push(&part);
+        }
+        let part_size = part.content_length();
+        let upload_part = self
+            .upload
+            .as_mut()
+            .expect("upload is in progress")
+            .put_part(part);
+        self.tasks
+            .spawn(async move { upload_part.await.map(|()| part_size) });
+        self.parts += 1;
+        Ok(())
+    }
+
+    async fn abort(mut self) -> object_store::Result<()> {
+        self.tasks.shutdown().await;
+        match self.upload.take() {
+            Some(mut upload) => upload.abort().await,
+            None => Ok(()),
+        }
+    }
+
+    /// Flush the final part, and await completion of all in-flight requests
+    async fn finish(mut self) -> PyObjectStoreResult<(PutResult, Option<PartChecksums>)> {
+        if let Some(compressor) = self.compressor.take() {
+            let flushed = compressor
+                .finish()
+                .map_err(PyObjectStoreError::from)
+                .and_then(|rest| self.buffer_bytes(rest));
+            if let Err(err) = flushed {
+                self.abort().await?;
+                return Err(err);
+            }
+        }
+        if !self.buffer.is_empty() {
+            if let Err(err) = self.put_part() {
+                self.abort().await?;
+                return Err(err);
+            }
+        }
+
+        if let Err(err) = self.wait_for_capacity(0).await {
+            self.abort().await?;
+            return Err(err.into());
+        }
+
+        let mut upload = self.upload.take().expect("upload is in progress");
+        match upload.complete().await {
+            Err(e) => {
+                self.tasks.shutdown().await;
+                upload.abort().await?;
+                Err(e.into())
+            }
+            Ok(result) => {
+                if let Some(progress) = &self.progress {
+                    progress.finish();
+                }
+                Ok((result, self.checksums.take()))
+            }
+        }
+    }
+}
+
+impl Drop for PolicyWriter {
+    /// Abort an upload that was neither completed nor aborted, such as when the `put_async` task
+    /// uploading it is cancelled, so that its parts don't linger in the store.
+    fn drop(&mut self) {
+        if let Some(mut upload) = self.upload.take() {
+            if let Ok(runtime) = tokio::runtime::Handle::try_current() {
+                runtime.spawn(async move { upload.abort().await });
+            }
+        }
+    }
+}
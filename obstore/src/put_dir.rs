@@ -0,0 +1,292 @@
+//! Upload of a local directory tree, optionally recording a manifest of the uploaded objects.
+
+use std::path::{Path as LocalPath, PathBuf};
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
+use object_store::buffered::BufWriter;
+use object_store::path::Path;
+use object_store::{Attribute, Attributes, ObjectStore};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreResult, PyPath};
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use tokio::fs::{self, File};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::runtime::get_runtime;
+
+/// Version of the manifest format written by [`write_manifest`].
+const MANIFEST_VERSION: u64 = 1;
+
+/// Content types guessed from the extension of a file, matched case-insensitively.
+const CONTENT_TYPES: &[(&str, &str)] = &[
+    ("avro", "application/avro"),
+    ("bin", "application/octet-stream"),
+    ("bz2", "application/x-bzip2"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("gif", "image/gif"),
+    ("gz", "application/gzip"),
+    ("htm", "text/html"),
+    ("html", "text/html"),
+    ("jpeg", "image/jpeg"),
+    ("jpg", "image/jpeg"),
+    ("js", "text/javascript"),
+    ("json", "application/json"),
+    ("jsonl", "application/jsonl"),
+    ("md", "text/markdown"),
+    ("ndjson", "application/x-ndjson"),
+    ("parquet", "application/vnd.apache.parquet"),
+    ("pdf", "application/pdf"),
+    ("png", "image/png"),
+    ("svg", "image/svg+xml"),
+    ("tar", "application/x-tar"),
+    ("tif", "image/tiff"),
+    ("tiff", "image/tiff"),
+    ("tsv", "text/tab-separated-values"),
+    ("txt", "text/plain"),
+    ("wasm", "application/wasm"),
+    ("webp", "image/webp"),
+    ("xml", "application/xml"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+    ("zip", "application/zip"),
+    ("zst", "application/zstd"),
+];
+
+/// Guess the content type of a file from its extension.
+fn guess_content_type(local_path: &LocalPath) -> &'static str {
+    local_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(|ext| {
+            CONTENT_TYPES
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(ext))
+        })
+        .map(|(_, content_type)| *content_type)
+        .unwrap_or("application/octet-stream")
+}
+
+/// An object uploaded by [`put_dir`].
+#[derive(Debug)]
+pub(crate) struct PyPutDirEntry {
+    path: Path,
+    size: u64,
+    sha256: String,
+    content_type: &'static str,
+}
+
+impl<'py> IntoPyObject<'py> for PyPutDirEntry {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(4);
+        dict.insert("path", self.path.as_ref().into_bound_py_any(py)?);
+        dict.insert("size", self.size.into_bound_py_any(py)?);
+        dict.insert("sha256", self.sha256.into_bound_py_any(py)?);
+        dict.insert("content_type", self.content_type.into_bound_py_any(py)?);
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, local_dir, prefix=None, *, manifest=None, chunk_size=5242880, max_concurrency=12))]
+pub(crate) fn put_dir(
+    py: Python,
+    store: PyObjectStore,
+    local_dir: PathBuf,
+    prefix: Option<PyPath>,
+    manifest: Option<PyPath>,
+    chunk_size: usize,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<Vec<PyPutDirEntry>> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        runtime.block_on(put_dir_inner(
+            store.into_inner(),
+            local_dir,
+            prefix.map(|s| s.into()),
+            manifest.map(|s| s.into()),
+            chunk_size,
+            max_concurrency,
+        ))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, local_dir, prefix=None, *, manifest=None, chunk_size=5242880, max_concurrency=12))]
+pub(crate) fn put_dir_async(
+    py: Python,
+    store: PyObjectStore,
+    local_dir: PathBuf,
+    prefix: Option<PyPath>,
+    manifest: Option<PyPath>,
+    chunk_size: usize,
+    max_concurrency: usize,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let entries = put_dir_inner(
+            store.into_inner(),
+            local_dir,
+            prefix.map(|s| s.into()),
+            manifest.map(|s| s.into()),
+            chunk_size,
+            max_concurrency,
+        )
+        .await?;
+        Ok(entries)
+    })
+}
+
+/// Upload every file under `local_dir` to the matching path under `prefix`, returning the
+/// uploaded objects sorted by path.
+///
+/// If `manifest_path` is given, a JSON manifest of the uploaded objects is written there once
+/// every file has been uploaded, so that a partial upload never has a manifest.
+async fn put_dir_inner(
+    store: Arc<dyn ObjectStore>,
+    local_dir: PathBuf,
+    prefix: Option<Path>,
+    manifest_path: Option<Path>,
+    chunk_size: usize,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<Vec<PyPutDirEntry>> {
+    let prefix = prefix.unwrap_or_default();
+    let files = walk_dir(&local_dir, &prefix).await?;
+
+    let mut entries = futures::stream::iter(files)
+        .map(|(local_path, path)| put_file(&store, local_path, path, chunk_size))
+        .buffer_unordered(max_concurrency.max(1))
+        .try_collect::<Vec<_>>()
+        .await?;
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    if let Some(manifest_path) = manifest_path {
+        write_manifest(&store, &manifest_path, &prefix, &entries).await?;
+    }
+    Ok(entries)
+}
+
+/// Find every file under `local_dir`, following symlinks, paired with the path it is uploaded to.
+async fn walk_dir(
+    local_dir: &LocalPath,
+    prefix: &Path,
+) -> PyObjectStoreResult<Vec<(PathBuf, Path)>> {
+    if !fs::metadata(local_dir).await?.is_dir() {
+        return Err(
+            PyValueError::new_err(format!("Not a directory: '{}'", local_dir.display())).into(),
+        );
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![(local_dir.to_path_buf(), prefix.clone())];
+    while let Some((dir, dir_path)) = dirs.pop() {
+        let mut entries = fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name();
+            let name = name.to_str().ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "File name is not valid UTF-8: '{}'",
+                    entry.path().display()
+                ))
+            })?;
+            let path = dir_path.child(name);
+            if fs::metadata(entry.path()).await?.is_dir() {
+                dirs.push((entry.path(), path));
+            } else {
+                files.push((entry.path(), path));
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Stream a single file to `path`, hashing it as it is read.
+async fn put_file(
+    store: &Arc<dyn ObjectStore>,
+    local_path: PathBuf,
+    path: Path,
+    chunk_size: usize,
+) -> PyObjectStoreResult<PyPutDirEntry> {
+    let content_type = guess_content_type(&local_path);
+    let mut attributes = Attributes::new();
+    attributes.insert(Attribute::ContentType, content_type.into());
+
+    let mut file = File::open(&local_path).await?;
+    let mut writer = BufWriter::with_capacity(store.clone(), path.clone(), chunk_size)
+        .with_attributes(attributes);
+    let mut hasher = Sha256::new();
+    match copy_file(&mut file, &mut writer, &mut hasher, chunk_size).await {
+        Ok(size) => {
+            writer.shutdown().await?;
+            Ok(PyPutDirEntry {
+                path,
+                size,
+                sha256: format!("{:x}", hasher.finalize()),
+                content_type,
+            })
+        }
+        Err(err) => {
+            writer.abort().await?;
+            Err(err)
+        }
+    }
+}
+
+async fn copy_file(
+    file: &mut File,
+    writer: &mut BufWriter,
+    hasher: &mut Sha256,
+    chunk_size: usize,
+) -> PyObjectStoreResult<u64> {
+    let mut size = 0;
+    let mut buf = vec![0; chunk_size.max(1)];
+    loop {
+        let n = file.read(&mut buf).await?;
+        if n == 0 {
+            return Ok(size);
+        }
+        hasher.update(&buf[..n]);
+        size += n as u64;
+        writer.put(Bytes::copy_from_slice(&buf[..n])).await?;
+    }
+}
+
+/// Write a JSON manifest of the uploaded objects to `manifest_path`.
+async fn write_manifest(
+    store: &Arc<dyn ObjectStore>,
+    manifest_path: &Path,
+    prefix: &Path,
+    entries: &[PyPutDirEntry],
+) -> PyObjectStoreResult<()> {
+    let objects = entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "path": entry.path.as_ref(),
+                "size": entry.size,
+                "sha256": entry.sha256,
+                "content_type": entry.content_type,
+            })
+        })
+        .collect::<Vec<_>>();
+    let manifest = json!({
+        "version": MANIFEST_VERSION,
+        "prefix": prefix.as_ref(),
+        "size": entries.iter().map(|entry| entry.size).sum::<u64>(),
+        "algorithm": "sha256",
+        "objects": objects,
+    });
+    let data = serde_json::to_vec_pretty(&manifest).unwrap();
+    store.put(manifest_path, data.into()).await?;
+    Ok(())
+}
@@ -1,51 +1,84 @@
+use std::time::Duration;
+
+use object_store::path::Path;
 use object_store::ObjectStore;
 use pyo3::prelude::*;
-use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult};
+use pyo3_object_store::{PyObjectStoreError, PyObjectStoreResult, PyPath};
 
+use crate::copy::{copy_inner, deadline, MultipartCopyOptions, PyCopyStore};
 use crate::runtime::get_runtime;
 use crate::utils::PyNone;
 
 #[pyfunction]
-#[pyo3(signature = (store, from_, to, *, overwrite=true))]
+#[pyo3(signature = (store, from_, to, *, overwrite=true, multipart_threshold=None, timeout=None))]
 pub(crate) fn rename(
     py: Python,
-    store: PyObjectStore,
-    from_: String,
-    to: String,
+    store: PyCopyStore,
+    from_: PyPath,
+    to: PyPath,
     overwrite: bool,
+    multipart_threshold: Option<u64>,
+    timeout: Option<Duration>,
 ) -> PyObjectStoreResult<()> {
     let runtime = get_runtime(py)?;
     let from_ = from_.into();
     let to = to.into();
     py.allow_threads(|| {
-        let fut = if overwrite {
-            store.as_ref().rename(&from_, &to)
-        } else {
-            store.as_ref().rename_if_not_exists(&from_, &to)
-        };
-        runtime.block_on(fut)?;
+        runtime.block_on(rename_inner(
+            store,
+            from_,
+            to,
+            overwrite,
+            multipart_threshold,
+            timeout,
+        ))?;
         Ok::<_, PyObjectStoreError>(())
     })
 }
 
 #[pyfunction]
-#[pyo3(signature = (store, from_, to, *, overwrite=true))]
+#[pyo3(signature = (store, from_, to, *, overwrite=true, multipart_threshold=None, timeout=None))]
 pub(crate) fn rename_async(
     py: Python,
-    store: PyObjectStore,
-    from_: String,
-    to: String,
+    store: PyCopyStore,
+    from_: PyPath,
+    to: PyPath,
     overwrite: bool,
+    multipart_threshold: Option<u64>,
+    timeout: Option<Duration>,
 ) -> PyResult<Bound<PyAny>> {
     let from_ = from_.into();
     let to = to.into();
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
-        let fut = if overwrite {
-            store.as_ref().rename(&from_, &to)
-        } else {
-            store.as_ref().rename_if_not_exists(&from_, &to)
-        };
-        fut.await.map_err(PyObjectStoreError::ObjectStoreError)?;
+        rename_inner(store, from_, to, overwrite, multipart_threshold, timeout)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
         Ok(PyNone)
     })
 }
+
+/// Rename `from` to `to`.
+///
+/// `object_store` renames objects in S3 by copying and then deleting them, which we do ourselves
+/// so that large objects can be copied in parts.
+async fn rename_inner(
+    store: PyCopyStore,
+    from: Path,
+    to: Path,
+    overwrite: bool,
+    multipart_threshold: Option<u64>,
+    timeout: Option<Duration>,
+) -> object_store::Result<()> {
+    let deadline = deadline(timeout);
+    let PyCopyStore { store, s3 } = store;
+    let store = store.with_timeout(timeout).into_inner();
+    match s3 {
+        Some(s3) => {
+            let options = MultipartCopyOptions::with_threshold(multipart_threshold);
+            copy_inner(&store, Some(&s3), &from, &to, overwrite, options, deadline).await?;
+            store.delete(&from).await
+        }
+        None if overwrite => store.rename(&from, &to).await,
+        None => store.rename_if_not_exists(&from, &to).await,
+    }
+}
@@ -0,0 +1,209 @@
+//! Requests that `object_store` doesn't expose, which we make ourselves with the credentials of a
+//! store.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::{Method, StatusCode};
+use object_store::aws::{AmazonS3, AwsAuthorizer};
+use object_store::client::{
+    HttpClient, HttpConnector, HttpRequest, HttpRequestBody, ReqwestConnector,
+};
+use object_store::path::Path;
+use object_store::signer::Signer;
+use object_store::{ClientOptions, ObjectMeta, ObjectStore};
+use pyo3_object_store::{LimitedStore, MaybePrefixedStore, PyS3Store};
+use url::Url;
+
+/// The client to make requests with.
+///
+/// The URLs requested are derived from the config of a store, which has already been validated,
+/// so plain HTTP is allowed here.
+pub(crate) fn client() -> object_store::Result<HttpClient> {
+    ReqwestConnector::default().connect(&ClientOptions::new().with_allow_http(true))
+}
+
+pub(crate) fn request_error(store: &'static str, msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store,
+        source: msg.into().into(),
+    }
+}
+
+/// Send `request` for `path`, returning the body of a successful response.
+pub(crate) async fn send(
+    client: &HttpClient,
+    request: HttpRequest,
+    path: &str,
+    store: &'static str,
+) -> object_store::Result<String> {
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|err| request_error(store, err.to_string()))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .bytes()
+        .await
+        .map_err(|err| request_error(store, err.to_string()))?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+
+    match status {
+        StatusCode::NOT_FOUND => Err(object_store::Error::NotFound {
+            path: path.to_string(),
+            source: body.into(),
+        }),
+        StatusCode::PRECONDITION_FAILED => Err(object_store::Error::AlreadyExists {
+            path: path.to_string(),
+            source: body.into(),
+        }),
+        _ if !status.is_success() => Err(request_error(
+            store,
+            format!("Request failed with status {}: {}", status, body),
+        )),
+        _ => Ok(body),
+    }
+}
+
+/// The URL of the object at `path` of an S3 store, without a query. An empty `path` gives the
+/// URL of the bucket.
+///
+/// A presigned URL for the object gives us the endpoint and addressing style of the store.
+async fn s3_object_url(s3: &AmazonS3, path: &Path) -> object_store::Result<Url> {
+    let mut url = s3
+        .signed_url(Method::GET, path, Duration::from_secs(60))
+        .await?;
+    url.set_query(None);
+    Ok(url)
+}
+
+/// The URL of the object at `path` of an S3 store, without a query, and the signing region of
+/// the store. An empty `path` gives the URL of the bucket.
+///
+/// A presigned URL for the object gives us the endpoint, addressing style and region of the
+/// store.
+pub(crate) async fn s3_url(s3: &AmazonS3, path: &Path) -> object_store::Result<(Url, String)> {
+    let mut url = s3
+        .signed_url(Method::GET, path, Duration::from_secs(60))
+        .await?;
+    let region = url
+        .query_pairs()
+        .find(|(key, _)| key == "X-Amz-Credential")
+        .and_then(|(_, value)| value.split('/').nth(2).map(String::from))
+        .ok_or_else(|| request_error("S3", "Could not determine region of S3 store"))?;
+    url.set_query(None);
+    Ok((url, region))
+}
+
+/// Make a request for the object at `path` of an S3 store, signed with the store's credentials.
+///
+/// An empty `path` makes a request for the bucket.
+pub(crate) async fn s3_request(
+    s3: &AmazonS3,
+    client: &HttpClient,
+    method: Method,
+    path: &Path,
+    query: &[(&str, &str)],
+    headers: &[(&str, String)],
+    body: Bytes,
+) -> object_store::Result<String> {
+    let (url, region) = s3_url(s3, path).await?;
+    send_s3(s3, client, &region, url, method, path, query, headers, body).await
+}
+
+/// Sign a request to `url` for the region of an S3 store, and send it.
+#[allow(clippy::too_many_arguments)]
+async fn send_s3(
+    s3: &AmazonS3,
+    client: &HttpClient,
+    region: &str,
+    mut url: Url,
+    method: Method,
+    path: &Path,
+    query: &[(&str, &str)],
+    headers: &[(&str, String)],
+    body: Bytes,
+) -> object_store::Result<String> {
+    if !query.is_empty() {
+        url.query_pairs_mut().extend_pairs(query);
+    }
+
+    let credential = s3.credentials().get_credential().await?;
+    let mut builder = http::Request::builder().method(method).uri(url.as_str());
+    for (name, value) in headers {
+        builder = builder.header(*name, value);
+    }
+    let mut request = builder
+        .body(HttpRequestBody::from(body))
+        .map_err(|err| request_error("S3", err.to_string()))?;
+    AwsAuthorizer::new(&credential, "s3", region).authorize(&mut request, None);
+
+    let body = send(client, request, path.as_ref(), "S3").await?;
+    // Some requests, like CompleteMultipartUpload, can fail after responding with a success status
+    if body.contains("<Error>") {
+        return Err(request_error("S3", format!("Request failed: {}", body)));
+    }
+    Ok(body)
+}
+
+/// An S3 store with the signing region and HTTP client of its config, for making requests that
+/// [`AmazonS3`] doesn't support.
+pub(crate) struct S3Requests {
+    store: Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>,
+    bucket: String,
+    region: String,
+    client: HttpClient,
+}
+
+impl S3Requests {
+    pub(crate) fn new(store: &PyS3Store) -> object_store::Result<Self> {
+        Ok(Self {
+            store: store.as_ref().clone(),
+            bucket: store.bucket().to_string(),
+            region: store.region(),
+            client: store.http_client()?,
+        })
+    }
+
+    pub(crate) fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    /// The metadata of the object at `path` within the store.
+    pub(crate) async fn head(&self, path: &Path) -> object_store::Result<ObjectMeta> {
+        self.store.head(path).await
+    }
+
+    /// The path within the bucket of `path` within the store.
+    pub(crate) fn full_path(&self, path: &Path) -> Path {
+        self.store.inner().full_path(path).into_owned()
+    }
+
+    /// Make a request for the object at `path` within the bucket, returning the body of a
+    /// successful response.
+    pub(crate) async fn request(
+        &self,
+        method: Method,
+        path: &Path,
+        query: &[(&str, &str)],
+        headers: &[(&str, String)],
+        body: Bytes,
+    ) -> object_store::Result<String> {
+        let s3 = self.store.inner().inner();
+        let url = s3_object_url(s3, path).await?;
+        send_s3(
+            s3,
+            &self.client,
+            &self.region,
+            url,
+            method,
+            path,
+            query,
+            headers,
+            body,
+        )
+        .await
+    }
+}
@@ -0,0 +1,231 @@
+//! Streaming the body of a `get` as the response of a web framework.
+//!
+//! Web servers pull the body of a response one chunk at a time, as the client reads it, so a
+//! chunk is returned as soon as any data has arrived rather than once a minimum size has been
+//! buffered, and nothing is read from the store before it's asked for.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt};
+use http::header::{self, HeaderName};
+use indexmap::IndexMap;
+use object_store::{Attribute, GetResult};
+use pyo3::exceptions::{PyStopAsyncIteration, PyStopIteration, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3_object_store::PyObjectStoreError;
+use tokio::sync::Mutex;
+
+use crate::get::PyBytesWrapper;
+use crate::progress::{track_stream, PyProgress};
+use crate::runtime::get_runtime;
+use crate::utils::next_within;
+
+/// 1MB default maximum chunk size of a response
+pub(crate) const DEFAULT_RESPONSE_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// The format of dates in HTTP headers.
+pub(crate) fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// The HTTP header that an attribute of an object is returned as, if any.
+pub(crate) fn attribute_header(attribute: &Attribute) -> Option<HeaderName> {
+    match attribute {
+        Attribute::CacheControl => Some(header::CACHE_CONTROL),
+        Attribute::ContentDisposition => Some(header::CONTENT_DISPOSITION),
+        Attribute::ContentEncoding => Some(header::CONTENT_ENCODING),
+        Attribute::ContentLanguage => Some(header::CONTENT_LANGUAGE),
+        Attribute::ContentType => Some(header::CONTENT_TYPE),
+        _ => None,
+    }
+}
+
+struct ResponseBody {
+    /// `None` once the stream is exhausted, has failed, or the response is closed
+    stream: Option<BoxStream<'static, object_store::Result<Bytes>>>,
+    /// Data received from the stream that wasn't returned in the last chunk
+    pending: Bytes,
+}
+
+impl ResponseBody {
+    fn close(&mut self) {
+        self.pending.clear();
+        // Dropping a streaming body closes its connection
+        self.stream = None;
+    }
+}
+
+#[pyclass(name = "ResponseStream", frozen)]
+pub(crate) struct PyResponseStream {
+    body: Arc<Mutex<ResponseBody>>,
+    chunk_size: usize,
+    chunk_timeout: Option<Duration>,
+    status: u16,
+    headers: IndexMap<String, String>,
+}
+
+impl PyResponseStream {
+    pub(crate) fn new(
+        result: GetResult,
+        progress: Option<Arc<PyProgress>>,
+        chunk_size: usize,
+        chunk_timeout: Option<Duration>,
+    ) -> PyResult<Self> {
+        if chunk_size == 0 {
+            return Err(PyValueError::new_err("chunk_size must be greater than 0."));
+        }
+
+        let range = result.range.clone();
+        let size = result.meta.size;
+        let partial = range.start != 0 || range.end != size;
+        let mut headers = IndexMap::new();
+        headers.insert(
+            header::CONTENT_LENGTH.to_string(),
+            (range.end - range.start).to_string(),
+        );
+        if partial {
+            headers.insert(
+                header::CONTENT_RANGE.to_string(),
+                format!("bytes {}-{}/{}", range.start, range.end - 1, size),
+            );
+        }
+        headers.insert(header::ACCEPT_RANGES.to_string(), "bytes".to_string());
+        headers.insert(
+            header::LAST_MODIFIED.to_string(),
+            http_date(result.meta.last_modified),
+        );
+        if let Some(e_tag) = &result.meta.e_tag {
+            headers.insert(header::ETAG.to_string(), e_tag.clone());
+        }
+        for (attribute, value) in result.attributes.iter() {
+            if let Some(name) = attribute_header(attribute) {
+                headers.insert(name.to_string(), value.to_string());
+            }
+        }
+
+        let body = ResponseBody {
+            stream: Some(track_stream(result.into_stream(), progress)),
+            pending: Bytes::new(),
+        };
+        Ok(Self {
+            body: Arc::new(Mutex::new(body)),
+            chunk_size,
+            chunk_timeout,
+            status: if partial { 206 } else { 200 },
+            headers,
+        })
+    }
+}
+
+/// Return the data that has arrived, up to `chunk_size` bytes, waiting only if none has.
+async fn next_chunk(
+    body: Arc<Mutex<ResponseBody>>,
+    chunk_size: usize,
+    chunk_timeout: Option<Duration>,
+    sync: bool,
+) -> PyResult<PyBytesWrapper> {
+    let mut body = body.lock().await;
+    let body = &mut *body;
+    let mut buffers: Vec<Bytes> = vec![];
+    let mut total_buffer_len = 0;
+    loop {
+        if !body.pending.is_empty() {
+            let len = body.pending.len().min(chunk_size - total_buffer_len);
+            buffers.push(body.pending.split_to(len));
+            total_buffer_len += len;
+            if total_buffer_len == chunk_size {
+                break;
+            }
+        }
+        let Some(stream) = body.stream.as_mut() else {
+            break;
+        };
+        let next = if buffers.is_empty() {
+            next_within(stream, chunk_timeout).await?
+        } else {
+            // Only take what has already arrived, so that the client isn't kept waiting
+            match stream.next().now_or_never() {
+                Some(next) => next,
+                None => break,
+            }
+        };
+        match next {
+            Some(Ok(bytes)) => body.pending = bytes,
+            Some(Err(err)) => {
+                body.stream = None;
+                return Err(PyObjectStoreError::from(err).into());
+            }
+            None => body.stream = None,
+        }
+    }
+
+    if !buffers.is_empty() {
+        Ok(PyBytesWrapper::new_multiple(buffers))
+    } else if sync {
+        Err(PyStopIteration::new_err("stream exhausted"))
+    } else {
+        Err(PyStopAsyncIteration::new_err("stream exhausted"))
+    }
+}
+
+#[pymethods]
+impl PyResponseStream {
+    #[getter]
+    fn status(&self) -> u16 {
+        self.status
+    }
+
+    #[getter]
+    fn headers(&self) -> IndexMap<String, String> {
+        self.headers.clone()
+    }
+
+    /// Abort the download of the rest of the body.
+    fn close(&self, py: Python) -> PyResult<()> {
+        let runtime = get_runtime(py)?;
+        let body = self.body.clone();
+        py.allow_threads(|| runtime.block_on(body.lock()).close());
+        Ok(())
+    }
+
+    fn aclose<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let body = self.body.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            body.lock().await.close();
+            Ok(())
+        })
+    }
+
+    fn __reduce__(&self) -> PyResult<()> {
+        Err(PyTypeError::new_err(
+            "ResponseStream can't be pickled, as it holds the response to a request.",
+        ))
+    }
+
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __iter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let body = self.body.clone();
+        pyo3_async_runtimes::tokio::future_into_py(
+            py,
+            next_chunk(body, self.chunk_size, self.chunk_timeout, false),
+        )
+    }
+
+    fn __next__(&self, py: Python) -> PyResult<PyBytesWrapper> {
+        let runtime = get_runtime(py)?;
+        let body = self.body.clone();
+        let (chunk_size, chunk_timeout) = (self.chunk_size, self.chunk_timeout);
+        py.allow_threads(|| runtime.block_on(next_chunk(body, chunk_size, chunk_timeout, true)))
+    }
+}
@@ -0,0 +1,276 @@
+//! Resumable uploads to GCS, whose session URI can be handed off to another process to upload
+//! the data.
+//!
+//! See <https://cloud.google.com/storage/docs/resumable-uploads>.
+
+use bytes::Bytes;
+use http::{Method, StatusCode};
+use object_store::client::{HttpClient, HttpRequest, HttpRequestBody};
+use object_store::gcp::GoogleCloudStorage;
+use object_store::path::Path;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3_async_runtimes::tokio::future_into_py;
+use pyo3_bytes::PyBytes;
+use pyo3_object_store::{PyGCSStore, PyObjectStoreError, PyObjectStoreResult, PyPath};
+use url::Url;
+
+use crate::requests::{self, request_error};
+use crate::runtime::get_runtime;
+
+const GCS_UPLOAD_URL: &str = "https://storage.googleapis.com/upload/storage/v1/b";
+
+/// Every chunk of a resumable upload but the last must be a multiple of this size.
+const CHUNK_ALIGNMENT: usize = 256 * 1024;
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, content_type=None))]
+pub(crate) fn start_resumable_upload(
+    py: Python,
+    store: PyGCSStore,
+    path: PyPath,
+    content_type: Option<String>,
+) -> PyObjectStoreResult<String> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let session_uri = runtime.block_on(start_upload(&store, path.into(), content_type))?;
+        Ok::<_, PyObjectStoreError>(session_uri)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, content_type=None))]
+pub(crate) fn start_resumable_upload_async(
+    py: Python,
+    store: PyGCSStore,
+    path: PyPath,
+    content_type: Option<String>,
+) -> PyResult<Bound<PyAny>> {
+    future_into_py(py, async move {
+        let session_uri = start_upload(&store, path.into(), content_type)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(session_uri)
+    })
+}
+
+#[pyfunction]
+pub(crate) fn resumable_upload_offset(
+    py: Python,
+    session_uri: String,
+) -> PyObjectStoreResult<Option<u64>> {
+    let runtime = get_runtime(py)?;
+    let session_uri = parse_session_uri(&session_uri)?;
+    py.allow_threads(|| {
+        let offset = runtime.block_on(upload_offset(&session_uri))?;
+        Ok::<_, PyObjectStoreError>(offset)
+    })
+}
+
+#[pyfunction]
+pub(crate) fn resumable_upload_offset_async(
+    py: Python,
+    session_uri: String,
+) -> PyResult<Bound<PyAny>> {
+    let session_uri = parse_session_uri(&session_uri)?;
+    future_into_py(py, async move {
+        let offset = upload_offset(&session_uri)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(offset)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (session_uri, data, offset, *, last=false))]
+pub(crate) fn upload_resumable_chunk(
+    py: Python,
+    session_uri: String,
+    data: PyBytes,
+    offset: u64,
+    last: bool,
+) -> PyObjectStoreResult<Option<u64>> {
+    let runtime = get_runtime(py)?;
+    let session_uri = parse_session_uri(&session_uri)?;
+    let data = data.into_inner();
+    check_chunk(&data, last)?;
+    py.allow_threads(|| {
+        let offset = runtime.block_on(upload_chunk(&session_uri, data, offset, last))?;
+        Ok::<_, PyObjectStoreError>(offset)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (session_uri, data, offset, *, last=false))]
+pub(crate) fn upload_resumable_chunk_async(
+    py: Python,
+    session_uri: String,
+    data: PyBytes,
+    offset: u64,
+    last: bool,
+) -> PyResult<Bound<PyAny>> {
+    let session_uri = parse_session_uri(&session_uri)?;
+    let data = data.into_inner();
+    check_chunk(&data, last)?;
+    future_into_py(py, async move {
+        let offset = upload_chunk(&session_uri, data, offset, last)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(offset)
+    })
+}
+
+fn parse_session_uri(session_uri: &str) -> PyResult<Url> {
+    Url::parse(session_uri)
+        .map_err(|err| PyValueError::new_err(format!("Invalid session URI: {}", err)))
+}
+
+/// Raise a `ValueError` for a chunk that GCS would reject.
+fn check_chunk(data: &Bytes, last: bool) -> PyResult<()> {
+    if !last && (data.is_empty() || data.len() % CHUNK_ALIGNMENT != 0) {
+        return Err(PyValueError::new_err(format!(
+            "Every chunk but the last must be a non-zero multiple of {} bytes, got {} bytes.",
+            CHUNK_ALIGNMENT,
+            data.len()
+        )));
+    }
+    Ok(())
+}
+
+/// Initiate a resumable upload of `path`, returning the session URI.
+async fn start_upload(
+    store: &PyGCSStore,
+    path: Path,
+    content_type: Option<String>,
+) -> object_store::Result<String> {
+    let path = store.as_ref().inner().full_path(&path).into_owned();
+    let gcs: &GoogleCloudStorage = store.as_ref().inner().inner();
+    let credential = gcs.credentials().get_credential().await?;
+
+    let mut url = Url::parse(GCS_UPLOAD_URL).expect("GCS upload URL is valid");
+    url.path_segments_mut()
+        .expect("GCS URL is a base")
+        .extend([store.bucket(), "o"]);
+    url.query_pairs_mut()
+        .append_pair("uploadType", "resumable")
+        .append_pair("name", path.as_ref());
+
+    let mut builder = http::Request::builder()
+        .method(Method::POST)
+        .uri(url.as_str())
+        .header(
+            http::header::AUTHORIZATION,
+            format!("Bearer {}", credential.bearer),
+        )
+        .header(http::header::CONTENT_LENGTH, 0);
+    if let Some(content_type) = content_type {
+        builder = builder.header("X-Upload-Content-Type", content_type);
+    }
+    let request = builder
+        .body(HttpRequestBody::empty())
+        .map_err(|err| request_error("GCS", err.to_string()))?;
+
+    let response = execute(&requests::client()?, request, path.as_ref()).await?;
+    response
+        .headers()
+        .get(http::header::LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(String::from)
+        .ok_or_else(|| request_error("GCS", "Response has no session URI"))
+}
+
+/// The number of bytes persisted by a resumable upload, or `None` if it's complete.
+async fn upload_offset(session_uri: &Url) -> object_store::Result<Option<u64>> {
+    let request = http::Request::builder()
+        .method(Method::PUT)
+        .uri(session_uri.as_str())
+        .header(http::header::CONTENT_RANGE, "bytes */*")
+        .header(http::header::CONTENT_LENGTH, 0)
+        .body(HttpRequestBody::empty())
+        .map_err(|err| request_error("GCS", err.to_string()))?;
+    let response = execute(&requests::client()?, request, session_uri.as_str()).await?;
+    persisted_offset(&response)
+}
+
+/// Upload `data` at `offset` of a resumable upload, returning the number of bytes persisted, or
+/// `None` if this was the last chunk and the upload is complete.
+async fn upload_chunk(
+    session_uri: &Url,
+    data: Bytes,
+    offset: u64,
+    last: bool,
+) -> object_store::Result<Option<u64>> {
+    let len = data.len() as u64;
+    let total = if last {
+        (offset + len).to_string()
+    } else {
+        "*".to_string()
+    };
+    let content_range = if len == 0 {
+        format!("bytes */{}", total)
+    } else {
+        format!("bytes {}-{}/{}", offset, offset + len - 1, total)
+    };
+    let request = http::Request::builder()
+        .method(Method::PUT)
+        .uri(session_uri.as_str())
+        .header(http::header::CONTENT_RANGE, content_range)
+        .header(http::header::CONTENT_LENGTH, len)
+        .body(HttpRequestBody::from(data))
+        .map_err(|err| request_error("GCS", err.to_string()))?;
+    let response = execute(&requests::client()?, request, session_uri.as_str()).await?;
+    persisted_offset(&response)
+}
+
+/// Send `request`, returning the response if it's a success or a `308 Resume Incomplete`.
+async fn execute(
+    client: &HttpClient,
+    request: HttpRequest,
+    path: &str,
+) -> object_store::Result<http::Response<()>> {
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|err| request_error("GCS", err.to_string()))?;
+    let status = response.status();
+    if status.is_success() || status == StatusCode::PERMANENT_REDIRECT {
+        let (parts, _) = response.into_parts();
+        return Ok(http::Response::from_parts(parts, ()));
+    }
+
+    let body = response
+        .into_body()
+        .bytes()
+        .await
+        .map_err(|err| request_error("GCS", err.to_string()))?;
+    let body = String::from_utf8_lossy(&body);
+    match status {
+        StatusCode::NOT_FOUND | StatusCode::GONE => Err(object_store::Error::NotFound {
+            path: path.to_string(),
+            source: format!("Upload session no longer exists: {}", body).into(),
+        }),
+        _ => Err(request_error(
+            "GCS",
+            format!("Request failed with status {}: {}", status, body),
+        )),
+    }
+}
+
+/// The offset of a resumable upload from the `Range` header of a `308 Resume Incomplete`
+/// response, or `None` if the upload is complete.
+fn persisted_offset(response: &http::Response<()>) -> object_store::Result<Option<u64>> {
+    if response.status() != StatusCode::PERMANENT_REDIRECT {
+        return Ok(None);
+    }
+    // No range means that no bytes have been persisted yet
+    let Some(range) = response.headers().get(http::header::RANGE) else {
+        return Ok(Some(0));
+    };
+    range
+        .to_str()
+        .ok()
+        .and_then(|range| range.strip_prefix("bytes=0-"))
+        .and_then(|end| end.parse::<u64>().ok())
+        .map(|end| Some(end + 1))
+        .ok_or_else(|| request_error("GCS", format!("Invalid range header: {:?}", range)))
+}
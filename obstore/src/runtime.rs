@@ -1,10 +1,15 @@
-use pyo3::exceptions::PyValueError;
+use std::sync::OnceLock;
+
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::intern;
 use pyo3::prelude::*;
-use pyo3::sync::GILOnceCell;
+use pyo3::pybacked::PyBackedStr;
 use tokio::runtime::Runtime;
 
-static RUNTIME: GILOnceCell<Runtime> = GILOnceCell::new();
-static PID: GILOnceCell<u32> = GILOnceCell::new();
+// These are not `GILOnceCell`s, which don't guarantee a single initialization on free-threaded
+// Python
+static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+static PID: OnceLock<u32> = OnceLock::new();
 
 /// Construct a tokio runtime for sync requests
 ///
@@ -16,9 +21,9 @@ static PID: GILOnceCell<u32> = GILOnceCell::new();
 ///
 /// Downstream consumers may explicitly want to depend on tokio and add `rt-multi-thread` as a
 /// tokio feature flag to opt-in to the multi-threaded tokio runtime.
-pub fn get_runtime(py: Python<'_>) -> PyResult<&'static Runtime> {
+pub fn get_runtime(_py: Python<'_>) -> PyResult<&'static Runtime> {
     let pid = std::process::id();
-    let runtime_pid = *PID.get_or_init(py, || pid);
+    let runtime_pid = *PID.get_or_init(|| pid);
     if pid != runtime_pid {
         panic!(
             "Forked process detected - current PID is {} but the tokio runtime was created by {}. The tokio \
@@ -29,10 +34,198 @@ pub fn get_runtime(py: Python<'_>) -> PyResult<&'static Runtime> {
         );
     }
 
-    let runtime = RUNTIME.get_or_try_init(py, || {
-        Runtime::new().map_err(|err| {
-            PyValueError::new_err(format!("Could not create tokio runtime. {}", err))
-        })
-    })?;
-    Ok(runtime)
+    if let Some(runtime) = RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = Runtime::new()
+        .map_err(|err| PyValueError::new_err(format!("Could not create tokio runtime. {}", err)))?;
+    // If another thread created a runtime in the meantime, ours is dropped and theirs is used
+    Ok(RUNTIME.get_or_init(|| runtime))
+}
+
+/// One of the two tokio runtimes used by obstore.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PyRuntimeKind {
+    /// The runtime used by synchronous functions, from [`get_runtime`]
+    Sync,
+    /// The runtime used by asynchronous functions, managed by `pyo3-async-runtimes`
+    Async,
+}
+
+impl<'py> FromPyObject<'py> for PyRuntimeKind {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "sync" => Ok(Self::Sync),
+            "async" => Ok(Self::Async),
+            _ => Err(PyValueError::new_err(format!(
+                "Unexpected input for runtime: {}. Expected one of 'sync' or 'async'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// A snapshot of the metrics of a tokio runtime.
+#[derive(IntoPyObject)]
+pub(crate) struct PyRuntimeStats {
+    #[pyo3(item)]
+    workers: usize,
+    #[pyo3(item)]
+    alive_tasks: usize,
+    #[pyo3(item)]
+    global_queue_depth: usize,
+    #[pyo3(item)]
+    blocking_threads: Option<usize>,
+    #[pyo3(item)]
+    idle_blocking_threads: Option<usize>,
+    #[pyo3(item)]
+    worker_utilization: Option<Vec<f64>>,
+}
+
+#[pyfunction]
+#[pyo3(signature = (runtime=PyRuntimeKind::Sync))]
+fn stats(py: Python, runtime: PyRuntimeKind) -> PyResult<PyRuntimeStats> {
+    let handle = match runtime {
+        PyRuntimeKind::Sync => get_runtime(py)?,
+        PyRuntimeKind::Async => pyo3_async_runtimes::tokio::get_runtime(),
+    };
+    let metrics = handle.metrics();
+
+    // Blocking thread counts and busy durations are only available when tokio's unstable metrics
+    // are enabled, by building with `RUSTFLAGS="--cfg tokio_unstable"`.
+    #[cfg(tokio_unstable)]
+    let (blocking_threads, idle_blocking_threads, worker_utilization) = (
+        Some(metrics.num_blocking_threads()),
+        Some(metrics.num_idle_blocking_threads()),
+        unstable::worker_utilization(runtime, &metrics),
+    );
+    #[cfg(not(tokio_unstable))]
+    let (blocking_threads, idle_blocking_threads, worker_utilization) = (None, None, None);
+
+    Ok(PyRuntimeStats {
+        workers: metrics.num_workers(),
+        alive_tasks: metrics.num_alive_tasks(),
+        global_queue_depth: metrics.global_queue_depth(),
+        blocking_threads,
+        idle_blocking_threads,
+        worker_utilization,
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (callback=None))]
+fn enable_tracing(py: Python, callback: Option<PyObject>) -> PyResult<()> {
+    if let Some(callback) = &callback {
+        if !callback.bind(py).is_callable() {
+            return Err(PyTypeError::new_err("Expected callback to be callable."));
+        }
+    }
+    crate::trace::enable(callback);
+    Ok(())
+}
+
+#[pyfunction]
+fn disable_tracing() {
+    crate::trace::disable();
+}
+
+#[pyfunction]
+fn set_strict_keys(strict: bool) {
+    pyo3_object_store::set_strict_keys(strict);
+}
+
+#[pyfunction]
+fn set_object_meta_dicts(dicts: bool) {
+    crate::list::set_object_meta_dicts(dicts);
+}
+
+#[cfg(tokio_unstable)]
+mod unstable {
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use tokio::runtime::RuntimeMetrics;
+
+    use super::PyRuntimeKind;
+
+    /// The total busy duration of each worker at a point in time.
+    struct Sample {
+        at: Instant,
+        busy: Vec<Duration>,
+    }
+
+    /// The previous sample of the sync and async runtimes, respectively.
+    static LAST_SAMPLE: Mutex<[Option<Sample>; 2]> = Mutex::new([None, None]);
+
+    /// The fraction of time each worker was busy since the previous call, or `None` on the first
+    /// call for a runtime.
+    pub(super) fn worker_utilization(
+        runtime: PyRuntimeKind,
+        metrics: &RuntimeMetrics,
+    ) -> Option<Vec<f64>> {
+        let sample = Sample {
+            at: Instant::now(),
+            busy: (0..metrics.num_workers())
+                .map(|worker| metrics.worker_total_busy_duration(worker))
+                .collect(),
+        };
+        let mut last_sample = LAST_SAMPLE.lock().unwrap();
+        let last = last_sample[runtime as usize].replace(sample);
+        let (last, sample) = (last?, last_sample[runtime as usize].as_ref()?);
+        let elapsed = sample.at.duration_since(last.at).as_secs_f64();
+        if elapsed == 0.0 {
+            return None;
+        }
+        Some(
+            sample
+                .busy
+                .iter()
+                .zip(last.busy.iter())
+                .map(|(now, before)| (now.saturating_sub(*before).as_secs_f64() / elapsed).min(1.0))
+                .collect(),
+        )
+    }
+}
+
+/// Export runtime introspection as a submodule named `runtime` within the given parent module
+pub(crate) fn register_runtime_module(
+    py: Python<'_>,
+    parent_module: &Bound<'_, PyModule>,
+    parent_module_str: &str,
+    sub_module_str: &str,
+) -> PyResult<()> {
+    let full_module_string = format!("{}.{}", parent_module_str, sub_module_str);
+
+    let child_module = PyModule::new(parent_module.py(), sub_module_str)?;
+    child_module.add_wrapped(wrap_pyfunction!(disable_tracing))?;
+    child_module.add_wrapped(wrap_pyfunction!(enable_tracing))?;
+    child_module.add_wrapped(wrap_pyfunction!(set_object_meta_dicts))?;
+    child_module.add_wrapped(wrap_pyfunction!(set_strict_keys))?;
+    child_module.add_wrapped(wrap_pyfunction!(stats))?;
+
+    // Set the value of `__module__` correctly on each publicly exposed function or class
+    for name in [
+        "disable_tracing",
+        "enable_tracing",
+        "set_object_meta_dicts",
+        "set_strict_keys",
+        "stats",
+    ] {
+        child_module
+            .getattr(name)?
+            .setattr(intern!(py, "__module__"), &full_module_string)?;
+    }
+
+    // Add the child module to the parent module
+    parent_module.add_submodule(&child_module)?;
+
+    py.import(intern!(py, "sys"))?
+        .getattr(intern!(py, "modules"))?
+        .set_item(&full_module_string, &child_module)?;
+
+    // needs to be set *after* `add_submodule()`
+    child_module.setattr("__name__", full_module_string)?;
+
+    Ok(())
 }
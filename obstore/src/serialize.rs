@@ -0,0 +1,434 @@
+//! Convenience functions for storing Python objects as pickle or JSON.
+//!
+//! Serialization itself happens in Python, via the `pickle` and `json` modules, but the resulting
+//! bytes are compressed, uploaded, downloaded and decompressed through the same Rust code paths
+//! as `put` and `get`.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use futures::{stream, StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{Attribute, Attributes, ObjectStore, PutOptions};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::{intern, IntoPyObjectExt};
+use pyo3_object_store::{PyObjectStore, PyObjectStoreResult, PyPath};
+
+use crate::attributes::PyAttributes;
+use crate::compression::{compress_payload, decompress_stream, Codec};
+use crate::put::PyPutResult;
+use crate::runtime::get_runtime;
+
+/// The first bytes of any gzip stream.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// The first bytes of any Zstandard frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// The size of the chunks that objects are decompressed in.
+const DECOMPRESS_CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Pickle,
+    Json,
+}
+
+impl Format {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Pickle => "application/python-pickle",
+            Self::Json => "application/json",
+        }
+    }
+
+    fn dumps<'py>(
+        &self,
+        obj: &Bound<'py, PyAny>,
+        protocol: Option<i32>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = obj.py();
+        match self {
+            Self::Pickle => py
+                .import(intern!(py, "pickle"))?
+                .call_method1(intern!(py, "dumps"), (obj, protocol)),
+            Self::Json => py
+                .import(intern!(py, "json"))?
+                .call_method1(intern!(py, "dumps"), (obj,))?
+                .call_method0(intern!(py, "encode")),
+        }
+    }
+
+    fn loads<'py>(&self, data: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+        let py = data.py();
+        let module = match self {
+            Self::Pickle => intern!(py, "pickle"),
+            Self::Json => intern!(py, "json"),
+        };
+        py.import(module)?
+            .call_method1(intern!(py, "loads"), (data,))
+    }
+}
+
+/// The codec that `data` was compressed with, if it starts like a compressed stream.
+fn detect_codec(data: &[u8]) -> Option<Codec> {
+    if data.starts_with(&GZIP_MAGIC) {
+        Some(Codec::Gzip)
+    } else if data.starts_with(&ZSTD_MAGIC) {
+        Some(Codec::Zstd)
+    } else {
+        None
+    }
+}
+
+fn too_large(path: &str, size: usize, max_size: usize) -> PyErr {
+    PyValueError::new_err(format!(
+        "Object '{}' is {} bytes, which is larger than max_size of {} bytes",
+        path, size, max_size
+    ))
+}
+
+/// Serialize `obj`, compressing it if requested and enforcing `max_size` on the result.
+fn serialize(
+    obj: &Bound<PyAny>,
+    path: &str,
+    format: Format,
+    protocol: Option<i32>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+) -> PyResult<Bytes> {
+    let mut data = format
+        .dumps(obj, protocol)?
+        .extract::<pyo3_bytes::PyBytes>()?
+        .into_inner();
+    if let Some(codec) = compression {
+        data = compress_payload(codec, &data.into())?.into();
+    }
+    if let Some(max_size) = max_size {
+        if data.len() > max_size {
+            return Err(too_large(path, data.len(), max_size));
+        }
+    }
+    Ok(data)
+}
+
+/// Decompress `data` if it is compressed.
+///
+/// Compression is detected from the content rather than from the `Content-Encoding` attribute, so
+/// that objects can be read back from stores that don't persist attributes, such as `LocalStore`.
+/// The decompressed size is also limited to `max_size`, to guard against decompression bombs.
+async fn decompress(
+    data: Bytes,
+    path: &Path,
+    max_size: Option<usize>,
+) -> PyObjectStoreResult<Bytes> {
+    let Some(codec) = detect_codec(&data) else {
+        return Ok(data);
+    };
+    let mut chunks = decompress_stream(
+        codec,
+        stream::once(async { Ok(data) }).boxed(),
+        DECOMPRESS_CHUNK_SIZE,
+    );
+    let mut buffer = vec![];
+    while let Some(chunk) = chunks.try_next().await? {
+        buffer.extend_from_slice(&chunk);
+        if let Some(max_size) = max_size {
+            if buffer.len() > max_size {
+                return Err(too_large(path.as_ref(), buffer.len(), max_size).into());
+            }
+        }
+    }
+    Ok(buffer.into())
+}
+
+fn deserialize<'py>(py: Python<'py>, data: Bytes, format: Format) -> PyResult<Bound<'py, PyAny>> {
+    format.loads(&pyo3::types::PyBytes::new(py, &data).into_any())
+}
+
+/// The attributes to store a serialized object with.
+///
+/// Any attributes provided by the user take precedence over the inferred `Content-Type` and
+/// `Content-Encoding`.
+fn serialized_attributes(
+    format: Format,
+    compression: Option<Codec>,
+    attributes: Option<PyAttributes>,
+) -> Attributes {
+    let mut attrs = Attributes::new();
+    attrs.insert(Attribute::ContentType, format.content_type().into());
+    if let Some(codec) = compression {
+        attrs.insert(Attribute::ContentEncoding, codec.name().into());
+    }
+    if let Some(attributes) = attributes {
+        for (key, value) in attributes.into_inner().iter() {
+            attrs.insert(key.clone(), value.clone());
+        }
+    }
+    attrs
+}
+
+/// Upload a serialized object.
+///
+/// If the user didn't provide attributes explicitly and the store doesn't support attributes, the
+/// object is stored without the inferred `Content-Type`.
+async fn put_serialized(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    data: Bytes,
+    attributes: Attributes,
+    required_attributes: bool,
+) -> PyObjectStoreResult<PyPutResult> {
+    let opts = PutOptions {
+        attributes,
+        ..Default::default()
+    };
+    match store.put_opts(&path, data.clone().into(), opts).await {
+        Err(object_store::Error::NotImplemented) if !required_attributes => {
+            Ok(PyPutResult(store.put(&path, data.into()).await?))
+        }
+        result => Ok(PyPutResult(result?)),
+    }
+}
+
+/// Download an object for deserialization, enforcing `max_size` before reading the body and
+/// again after decompressing it.
+async fn get_serialized(
+    store: Arc<dyn ObjectStore>,
+    path: Path,
+    max_size: Option<usize>,
+) -> PyObjectStoreResult<Bytes> {
+    let result = store.get(&path).await?;
+    if let Some(max_size) = max_size {
+        let size = result.meta.size as usize;
+        if size > max_size {
+            return Err(too_large(path.as_ref(), size, max_size).into());
+        }
+    }
+    decompress(result.bytes().await?, &path, max_size).await
+}
+
+#[allow(clippy::too_many_arguments)]
+fn put_object(
+    py: Python,
+    store: PyObjectStore,
+    path: Path,
+    obj: &Bound<PyAny>,
+    format: Format,
+    protocol: Option<i32>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+    attributes: Option<PyAttributes>,
+) -> PyObjectStoreResult<PyPutResult> {
+    let runtime = get_runtime(py)?;
+    let data = serialize(obj, path.as_ref(), format, protocol, compression, max_size)?;
+    let required_attributes = attributes.is_some();
+    let attributes = serialized_attributes(format, compression, attributes);
+    py.allow_threads(|| {
+        runtime.block_on(put_serialized(
+            store.into_inner(),
+            path,
+            data,
+            attributes,
+            required_attributes,
+        ))
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn put_object_async<'py>(
+    py: Python<'py>,
+    store: PyObjectStore,
+    path: Path,
+    obj: &Bound<'py, PyAny>,
+    format: Format,
+    protocol: Option<i32>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+    attributes: Option<PyAttributes>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let data = serialize(obj, path.as_ref(), format, protocol, compression, max_size)?;
+    let required_attributes = attributes.is_some();
+    let attributes = serialized_attributes(format, compression, attributes);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        Ok(put_serialized(
+            store.into_inner(),
+            path,
+            data,
+            attributes,
+            required_attributes,
+        )
+        .await?)
+    })
+}
+
+fn get_object(
+    py: Python,
+    store: PyObjectStore,
+    path: Path,
+    format: Format,
+    max_size: Option<usize>,
+) -> PyObjectStoreResult<PyObject> {
+    let runtime = get_runtime(py)?;
+    let data =
+        py.allow_threads(|| runtime.block_on(get_serialized(store.into_inner(), path, max_size)))?;
+    Ok(deserialize(py, data, format)?.unbind())
+}
+
+fn get_object_async(
+    py: Python,
+    store: PyObjectStore,
+    path: Path,
+    format: Format,
+    max_size: Option<usize>,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let data = get_serialized(store.into_inner(), path, max_size).await?;
+        Python::with_gil(|py| deserialize(py, data, format)?.into_py_any(py))
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, obj, *, protocol=None, compression=None, max_size=None, attributes=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn put_pickle(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    obj: Bound<PyAny>,
+    protocol: Option<i32>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+    attributes: Option<PyAttributes>,
+) -> PyObjectStoreResult<PyPutResult> {
+    put_object(
+        py,
+        store,
+        path.into(),
+        &obj,
+        Format::Pickle,
+        protocol,
+        compression,
+        max_size,
+        attributes,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, obj, *, protocol=None, compression=None, max_size=None, attributes=None))]
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn put_pickle_async<'py>(
+    py: Python<'py>,
+    store: PyObjectStore,
+    path: PyPath,
+    obj: Bound<'py, PyAny>,
+    protocol: Option<i32>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+    attributes: Option<PyAttributes>,
+) -> PyResult<Bound<'py, PyAny>> {
+    put_object_async(
+        py,
+        store,
+        path.into(),
+        &obj,
+        Format::Pickle,
+        protocol,
+        compression,
+        max_size,
+        attributes,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, max_size=None))]
+pub(crate) fn get_pickle(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    max_size: Option<usize>,
+) -> PyObjectStoreResult<PyObject> {
+    get_object(py, store, path.into(), Format::Pickle, max_size)
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, max_size=None))]
+pub(crate) fn get_pickle_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    max_size: Option<usize>,
+) -> PyResult<Bound<PyAny>> {
+    get_object_async(py, store, path.into(), Format::Pickle, max_size)
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, obj, *, compression=None, max_size=None, attributes=None))]
+pub(crate) fn put_json(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    obj: Bound<PyAny>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+    attributes: Option<PyAttributes>,
+) -> PyObjectStoreResult<PyPutResult> {
+    put_object(
+        py,
+        store,
+        path.into(),
+        &obj,
+        Format::Json,
+        None,
+        compression,
+        max_size,
+        attributes,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, obj, *, compression=None, max_size=None, attributes=None))]
+pub(crate) fn put_json_async<'py>(
+    py: Python<'py>,
+    store: PyObjectStore,
+    path: PyPath,
+    obj: Bound<'py, PyAny>,
+    compression: Option<Codec>,
+    max_size: Option<usize>,
+    attributes: Option<PyAttributes>,
+) -> PyResult<Bound<'py, PyAny>> {
+    put_object_async(
+        py,
+        store,
+        path.into(),
+        &obj,
+        Format::Json,
+        None,
+        compression,
+        max_size,
+        attributes,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, max_size=None))]
+pub(crate) fn get_json(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    max_size: Option<usize>,
+) -> PyObjectStoreResult<PyObject> {
+    get_object(py, store, path.into(), Format::Json, max_size)
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, *, max_size=None))]
+pub(crate) fn get_json_async(
+    py: Python,
+    store: PyObjectStore,
+    path: PyPath,
+    max_size: Option<usize>,
+) -> PyResult<Bound<PyAny>> {
+    get_object_async(py, store, path.into(), Format::Json, max_size)
+}
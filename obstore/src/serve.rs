@@ -0,0 +1,425 @@
+//! Serving a store over HTTP, so that local tools such as browsers, media players and debuggers
+//! can read remote objects by URL.
+//!
+//! Each request for an object is a single `get` of the store, with the `Range` and conditional
+//! headers of the request passed on to it, and its body is streamed from the store as the client
+//! reads it. Requests for a directory, i.e. the root or a path ending in `/`, return an HTML
+//! index of the directory.
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use http::header::{self, HeaderMap, HeaderName};
+use http::{Method, Request, Response, StatusCode};
+use http_body_util::combinators::UnsyncBoxBody;
+use http_body_util::{BodyExt, Empty, Full, StreamBody};
+use hyper::body::{Frame, Incoming};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper_util::rt::TokioIo;
+use object_store::path::Path;
+use object_store::prefix::PrefixStore;
+use object_store::{GetOptions, GetRange, GetResult, ObjectStore};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, NON_ALPHANUMERIC};
+use pyo3::prelude::*;
+use pyo3_object_store::{PyObjectStore, PyPath};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio::task::{JoinHandle, JoinSet};
+
+use crate::response::{attribute_header, http_date};
+use crate::runtime::get_runtime;
+
+type Body = UnsyncBoxBody<Bytes, object_store::Error>;
+
+#[pyfunction]
+#[pyo3(signature = (store, addr="127.0.0.1:0", *, prefix=None))]
+pub(crate) fn serve(
+    py: Python,
+    store: PyObjectStore,
+    addr: &str,
+    prefix: Option<PyPath>,
+) -> PyResult<PyServer> {
+    let runtime = get_runtime(py)?;
+    let store: Arc<dyn ObjectStore> = match prefix {
+        Some(prefix) => Arc::new(PrefixStore::new(store.into_inner(), prefix)),
+        None => store.into_inner(),
+    };
+
+    let listener = std::net::TcpListener::bind(addr)?;
+    listener.set_nonblocking(true)?;
+    let address = listener.local_addr()?;
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let task = {
+        let _guard = runtime.enter();
+        let listener = TcpListener::from_std(listener)?;
+        runtime.spawn(accept(listener, store, shutdown_rx))
+    };
+    Ok(PyServer {
+        address,
+        shutdown,
+        task: Mutex::new(Some(task)),
+    })
+}
+
+/// A running server, which stops once shut down or garbage collected.
+#[pyclass(name = "Server", frozen)]
+pub(crate) struct PyServer {
+    address: SocketAddr,
+    shutdown: watch::Sender<bool>,
+    /// The task accepting connections, until the server is shut down
+    task: Mutex<Option<JoinHandle<()>>>,
+}
+
+#[pymethods]
+impl PyServer {
+    #[getter]
+    fn address(&self) -> String {
+        self.address.to_string()
+    }
+
+    #[getter]
+    fn port(&self) -> u16 {
+        self.address.port()
+    }
+
+    #[getter]
+    fn url(&self) -> String {
+        format!("http://{}/", self.address)
+    }
+
+    fn shutdown(&self, py: Python) -> PyResult<()> {
+        let _ = self.shutdown.send(true);
+        let task = self.task.lock().unwrap().take();
+        if let Some(task) = task {
+            let runtime = get_runtime(py)?;
+            // The task only fails if it panicked, which has already been reported
+            let _ = py.allow_threads(|| runtime.block_on(task));
+        }
+        Ok(())
+    }
+
+    fn __enter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    #[allow(unused_variables)]
+    #[pyo3(signature = (exc_type, exc_value, traceback))]
+    fn __exit__(
+        &self,
+        py: Python,
+        exc_type: Option<PyObject>,
+        exc_value: Option<PyObject>,
+        traceback: Option<PyObject>,
+    ) -> PyResult<()> {
+        self.shutdown(py)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Server(url={})", self.url())
+    }
+}
+
+/// Accept connections until the server is shut down, which closes the open connections too.
+async fn accept(
+    listener: TcpListener,
+    store: Arc<dyn ObjectStore>,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let mut connections = JoinSet::new();
+    loop {
+        tokio::select! {
+            // This also fails once the `Server` is dropped
+            _ = shutdown.changed() => break,
+            accepted = listener.accept() => {
+                let Ok((stream, _)) = accepted else {
+                    continue;
+                };
+                let store = store.clone();
+                connections.spawn(async move {
+                    let service = service_fn(move |request| {
+                        let store = store.clone();
+                        async move { Ok::<_, Infallible>(respond(store.as_ref(), request).await) }
+                    });
+                    let _ = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await;
+                });
+            }
+            // Reap closed connections, so that the set doesn't grow with every connection
+            Some(_) = connections.join_next(), if !connections.is_empty() => {}
+        }
+    }
+}
+
+async fn respond(store: &dyn ObjectStore, request: Request<Incoming>) -> Response<Body> {
+    let head = match *request.method() {
+        Method::GET => false,
+        Method::HEAD => true,
+        _ => {
+            let mut response = text_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed");
+            response
+                .headers_mut()
+                .insert(header::ALLOW, "GET, HEAD".parse().unwrap());
+            return response;
+        }
+    };
+    let Ok(path) = percent_decode_str(request.uri().path()).decode_utf8() else {
+        return text_response(StatusCode::BAD_REQUEST, "Path is not valid UTF-8");
+    };
+    let path = path.strip_prefix('/').unwrap_or(&path);
+    let Ok(location) = Path::parse(path) else {
+        return text_response(StatusCode::BAD_REQUEST, "Invalid path");
+    };
+    if path.is_empty() || path.ends_with('/') {
+        return index_response(store, &location, head).await;
+    }
+
+    let headers = request.headers();
+    let mut options = GetOptions {
+        if_match: header_str(headers, header::IF_MATCH).map(String::from),
+        if_none_match: header_str(headers, header::IF_NONE_MATCH).map(String::from),
+        if_modified_since: header_date(headers, header::IF_MODIFIED_SINCE),
+        if_unmodified_since: header_date(headers, header::IF_UNMODIFIED_SINCE),
+        head,
+        ..Default::default()
+    };
+    let range = (!head)
+        .then(|| header_str(headers, header::RANGE).and_then(parse_range))
+        .flatten();
+    // With `If-Range`, the range is only served if the object still has that ETag, and the whole
+    // object is served otherwise. An `If-Range` date can't be checked, so the whole object is
+    // served then.
+    let mut if_range = false;
+    match (range, header_str(headers, header::IF_RANGE)) {
+        (Some(range), None) => options.range = Some(range),
+        // A weak ETag can't be compared strongly, as `If-Range` requires
+        (Some(range), Some(e_tag)) if options.if_match.is_none() && e_tag.starts_with('"') => {
+            options.range = Some(range);
+            options.if_match = Some(e_tag.to_string());
+            if_range = true;
+        }
+        _ => {}
+    }
+
+    let result = match store.get_opts(&location, options.clone()).await {
+        Err(object_store::Error::Precondition { .. }) if if_range => {
+            options.range = None;
+            options.if_match = None;
+            store.get_opts(&location, options.clone()).await
+        }
+        result => result,
+    };
+    match result {
+        Ok(result) => object_response(result, options.range.is_some(), head),
+        Err(err) => error_response(store, &location, options.range.as_ref(), err).await,
+    }
+}
+
+fn header_str(headers: &HeaderMap, name: HeaderName) -> Option<&str> {
+    headers.get(name)?.to_str().ok()
+}
+
+fn header_date(headers: &HeaderMap, name: HeaderName) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc2822(header_str(headers, name)?)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Parse a `Range` header with a single range of bytes.
+///
+/// Other ranges, including multiple ranges, are ignored, which serves the whole object as
+/// allowed by RFC 9110.
+fn parse_range(value: &str) -> Option<GetRange> {
+    let spec = value.trim().strip_prefix("bytes=")?;
+    if spec.contains(',') {
+        return None;
+    }
+    let (start, end) = spec.split_once('-')?;
+    match (start.trim(), end.trim()) {
+        ("", suffix) => {
+            let suffix = suffix.parse().ok()?;
+            (suffix > 0).then_some(GetRange::Suffix(suffix))
+        }
+        (start, "") => Some(GetRange::Offset(start.parse().ok()?)),
+        (start, end) => {
+            let (start, end) = (start.parse::<u64>().ok()?, end.parse::<u64>().ok()?);
+            // The end of the header is inclusive
+            (end >= start).then(|| GetRange::Bounded(start..end + 1))
+        }
+    }
+}
+
+fn object_response(result: GetResult, partial: bool, head: bool) -> Response<Body> {
+    let range = result.range.clone();
+    let size = result.meta.size;
+    let mut builder = Response::builder()
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header(header::LAST_MODIFIED, http_date(result.meta.last_modified));
+    if let Some(e_tag) = &result.meta.e_tag {
+        builder = builder.header(header::ETAG, e_tag);
+    }
+    for (attribute, value) in result.attributes.iter() {
+        let Some(name) = attribute_header(attribute) else {
+            continue;
+        };
+        builder = builder.header(name, value.as_ref());
+    }
+    builder = if head {
+        builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, size)
+    } else if partial && !range.is_empty() {
+        builder
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_LENGTH, range.end - range.start)
+            .header(
+                header::CONTENT_RANGE,
+                format!("bytes {}-{}/{}", range.start, range.end - 1, size),
+            )
+    } else {
+        builder
+            .status(StatusCode::OK)
+            .header(header::CONTENT_LENGTH, range.end - range.start)
+    };
+
+    let body = if head {
+        empty_body()
+    } else {
+        StreamBody::new(result.into_stream().map_ok(Frame::data)).boxed_unsync()
+    };
+    builder.body(body).unwrap_or_else(|err| {
+        text_response(
+            StatusCode::BAD_GATEWAY,
+            &format!("Invalid response headers: {}", err),
+        )
+    })
+}
+
+async fn error_response(
+    store: &dyn ObjectStore,
+    location: &Path,
+    range: Option<&GetRange>,
+    err: object_store::Error,
+) -> Response<Body> {
+    match err {
+        object_store::Error::NotFound { .. } => text_response(StatusCode::NOT_FOUND, "Not found"),
+        object_store::Error::NotModified { .. } => empty_response(StatusCode::NOT_MODIFIED),
+        object_store::Error::Precondition { .. } => empty_response(StatusCode::PRECONDITION_FAILED),
+        object_store::Error::InvalidPath { .. } => {
+            text_response(StatusCode::BAD_REQUEST, "Invalid path")
+        }
+        err => {
+            // Stores fail in their own ways for a range beyond the end of the object, so that's
+            // only checked once the request has failed
+            if let (Some(range), Ok(meta)) = (range, store.head(location).await) {
+                let start = match range {
+                    GetRange::Bounded(range) => range.start,
+                    GetRange::Offset(offset) => *offset,
+                    GetRange::Suffix(_) => 0,
+                };
+                if start >= meta.size {
+                    let mut response = empty_response(StatusCode::RANGE_NOT_SATISFIABLE);
+                    response.headers_mut().insert(
+                        header::CONTENT_RANGE,
+                        format!("bytes */{}", meta.size).parse().unwrap(),
+                    );
+                    return response;
+                }
+            }
+            text_response(StatusCode::BAD_GATEWAY, &err.to_string())
+        }
+    }
+}
+
+/// An HTML page listing the directories and objects directly under `prefix`.
+async fn index_response(store: &dyn ObjectStore, prefix: &Path, head: bool) -> Response<Body> {
+    let result = match store.list_with_delimiter(Some(prefix)).await {
+        Ok(result) => result,
+        Err(err) => return text_response(StatusCode::BAD_GATEWAY, &err.to_string()),
+    };
+    if result.common_prefixes.is_empty() && result.objects.is_empty() && !prefix.as_ref().is_empty()
+    {
+        return text_response(StatusCode::NOT_FOUND, "Not found");
+    }
+
+    let title = format!("Index of /{}", prefix);
+    let mut html = format!(
+        concat!(
+            "<!DOCTYPE html>\n<html>\n",
+            "<head><meta charset=\"utf-8\"><title>{0}</title></head>\n",
+            "<body>\n<h1>{0}</h1>\n<ul>\n",
+        ),
+        escape_html(&title)
+    );
+    let directories = result
+        .common_prefixes
+        .iter()
+        .map(|path| (format!("{}/", path.filename().unwrap_or_default()), None));
+    let objects = result.objects.iter().map(|meta| {
+        let name = meta.location.filename().unwrap_or_default().to_string();
+        (name, Some(meta.size))
+    });
+    for (name, size) in directories.chain(objects) {
+        // Links are relative to the directory, whose URL ends with `/`
+        let href = name
+            .split('/')
+            .map(|part| utf8_percent_encode(part, NON_ALPHANUMERIC).to_string())
+            .collect::<Vec<_>>()
+            .join("/");
+        let size = size.map(|size| format!(" ({} bytes)", size));
+        html.push_str(&format!(
+            "<li><a href=\"{}\">{}</a>{}</li>\n",
+            href,
+            escape_html(&name),
+            size.unwrap_or_default()
+        ));
+    }
+    html.push_str("</ul>\n</body>\n</html>\n");
+
+    let length = html.len();
+    let body = if head { empty_body() } else { full_body(html) };
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        .header(header::CONTENT_LENGTH, length)
+        .body(body)
+        .unwrap()
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn empty_body() -> Body {
+    Empty::new().map_err(|never| match never {}).boxed_unsync()
+}
+
+fn full_body(body: String) -> Body {
+    Full::new(Bytes::from(body))
+        .map_err(|never| match never {})
+        .boxed_unsync()
+}
+
+fn empty_response(status: StatusCode) -> Response<Body> {
+    let mut response = Response::new(empty_body());
+    *response.status_mut() = status;
+    response
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(full_body(format!("{}\n", message)));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        "text/plain; charset=utf-8".parse().unwrap(),
+    );
+    response
+}
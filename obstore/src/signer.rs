@@ -1,40 +1,60 @@
 use core::time::Duration;
+use std::collections::BTreeMap;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use chrono::Utc;
 use http::Method;
+use indexmap::IndexMap;
 use object_store::aws::AmazonS3;
 use object_store::azure::MicrosoftAzure;
+use object_store::client::HttpRequestBody;
 use object_store::gcp::GoogleCloudStorage;
 use object_store::path::Path;
 use object_store::signer::Signer;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use pyo3::exceptions::PyValueError;
 use pyo3::intern;
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
+use pyo3::types::PyDict;
 use pyo3_object_store::{
-    MaybePrefixedStore, PyAzureStore, PyGCSStore, PyObjectStoreError, PyObjectStoreResult,
-    PyS3Store, PyUrl,
+    resolve_store_token, LimitedStore, MaybePrefixedStore, PyAzureStore, PyGCSStore,
+    PyObjectStoreError, PyObjectStoreResult, PyPath, PyS3Store, PyUrl,
 };
+use serde_json::{json, Value};
 use url::Url;
 
 use crate::path::PyPaths;
+use crate::requests;
 use crate::runtime::get_runtime;
 
 #[derive(Debug)]
 pub(crate) enum SignCapableStore {
-    S3(Arc<MaybePrefixedStore<AmazonS3>>),
-    Gcs(Arc<MaybePrefixedStore<GoogleCloudStorage>>),
-    Azure(Arc<MaybePrefixedStore<MicrosoftAzure>>),
+    S3(Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>),
+    Gcs {
+        store: Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>>,
+        bucket: String,
+        service_account_key: Option<String>,
+    },
+    Azure(Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>),
 }
 
 impl<'py> FromPyObject<'py> for SignCapableStore {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
         if let Ok(store) = ob.downcast::<PyS3Store>() {
             Ok(Self::S3(store.get().as_ref().clone()))
         } else if let Ok(store) = ob.downcast::<PyGCSStore>() {
-            Ok(Self::Gcs(store.get().as_ref().clone()))
+            let store = store.get();
+            Ok(Self::Gcs {
+                store: store.as_ref().clone(),
+                bucket: store.bucket().to_string(),
+                service_account_key: store.service_account_key(),
+            })
         } else if let Ok(store) = ob.downcast::<PyAzureStore>() {
             Ok(Self::Azure(store.get().as_ref().clone()))
         } else {
@@ -78,9 +98,9 @@ impl Signer for SignCapableStore {
         Self: 'async_trait,
     {
         match self {
-            Self::S3(inner) => inner.as_ref().inner().signed_url(method, path, expires_in),
-            Self::Gcs(inner) => inner.as_ref().inner().signed_url(method, path, expires_in),
-            Self::Azure(inner) => inner.as_ref().inner().signed_url(method, path, expires_in),
+            Self::S3(inner) => inner.inner().inner().signed_url(method, path, expires_in),
+            Self::Gcs { store, .. } => store.inner().inner().signed_url(method, path, expires_in),
+            Self::Azure(inner) => inner.inner().inner().signed_url(method, path, expires_in),
         }
     }
 
@@ -96,18 +116,9 @@ impl Signer for SignCapableStore {
         Self: 'async_trait,
     {
         match self {
-            Self::S3(inner) => inner
-                .as_ref()
-                .inner()
-                .signed_urls(method, paths, expires_in),
-            Self::Gcs(inner) => inner
-                .as_ref()
-                .inner()
-                .signed_urls(method, paths, expires_in),
-            Self::Azure(inner) => inner
-                .as_ref()
-                .inner()
-                .signed_urls(method, paths, expires_in),
+            Self::S3(inner) => inner.inner().inner().signed_urls(method, paths, expires_in),
+            Self::Gcs { store, .. } => store.inner().inner().signed_urls(method, paths, expires_in),
+            Self::Azure(inner) => inner.inner().inner().signed_urls(method, paths, expires_in),
         }
     }
 }
@@ -148,23 +159,28 @@ pub(crate) enum PySignResult {
 }
 
 #[pyfunction]
+#[pyo3(signature = (store, method, paths, expires_in, *, headers=None, query=None))]
 pub(crate) fn sign(
     py: Python,
     store: SignCapableStore,
     method: PyMethod,
     paths: PyPaths,
     expires_in: Duration,
+    headers: Option<IndexMap<String, String>>,
+    query: Option<IndexMap<String, String>>,
 ) -> PyObjectStoreResult<PySignResult> {
     let runtime = get_runtime(py)?;
     let method = method.0;
+    let options = SignOptions::new(&store, headers, query)?;
 
     py.allow_threads(|| match paths {
         PyPaths::One(path) => {
-            let url = runtime.block_on(store.signed_url(method, &path, expires_in))?;
+            let url = runtime.block_on(sign_inner(&store, &method, &path, expires_in, &options))?;
             Ok(PySignResult::One(PyUrl::new(url)))
         }
         PyPaths::Many(paths) => {
-            let urls = runtime.block_on(store.signed_urls(method, &paths, expires_in))?;
+            let urls =
+                runtime.block_on(sign_many(&store, &method, &paths, expires_in, &options))?;
             Ok(PySignResult::Many(PyUrls(
                 urls.into_iter().map(PyUrl::new).collect(),
             )))
@@ -173,26 +189,28 @@ pub(crate) fn sign(
 }
 
 #[pyfunction]
+#[pyo3(signature = (store, method, paths, expires_in, *, headers=None, query=None))]
 pub(crate) fn sign_async(
     py: Python,
     store: SignCapableStore,
     method: PyMethod,
     paths: PyPaths,
     expires_in: Duration,
+    headers: Option<IndexMap<String, String>>,
+    query: Option<IndexMap<String, String>>,
 ) -> PyResult<Bound<PyAny>> {
     let method = method.0;
+    let options = SignOptions::new(&store, headers, query)?;
     pyo3_async_runtimes::tokio::future_into_py(py, async move {
         match paths {
             PyPaths::One(path) => {
-                let url = store
-                    .signed_url(method, &path, expires_in)
+                let url = sign_inner(&store, &method, &path, expires_in, &options)
                     .await
                     .map_err(PyObjectStoreError::ObjectStoreError)?;
                 Ok(PySignResult::One(PyUrl::new(url)))
             }
             PyPaths::Many(paths) => {
-                let urls = store
-                    .signed_urls(method, &paths, expires_in)
+                let urls = sign_many(&store, &method, &paths, expires_in, &options)
                     .await
                     .map_err(PyObjectStoreError::ObjectStoreError)?;
                 Ok(PySignResult::Many(PyUrls(
@@ -202,3 +220,545 @@ pub(crate) fn sign_async(
         }
     })
 }
+
+/// What to sign in addition to the method and path of a URL.
+#[derive(Debug, Default)]
+struct SignOptions {
+    /// Headers that requests using the URL must send, by lowercase name
+    headers: BTreeMap<String, String>,
+    /// Query parameters added to the URL, such as `response-content-disposition`
+    query: Vec<(String, String)>,
+}
+
+impl SignOptions {
+    /// Raise a `ValueError` if `store` can't sign the headers or query parameters.
+    fn new(
+        store: &SignCapableStore,
+        headers: Option<IndexMap<String, String>>,
+        query: Option<IndexMap<String, String>>,
+    ) -> PyResult<Self> {
+        let headers = headers.unwrap_or_default();
+        if !headers.is_empty() && !matches!(store, SignCapableStore::S3(_)) {
+            return Err(PyValueError::new_err(
+                "Signing headers is only supported for S3Store instances.",
+            ));
+        }
+        let query = query.unwrap_or_default();
+        if !query.is_empty() && matches!(store, SignCapableStore::Azure(_)) {
+            return Err(PyValueError::new_err(
+                "Signing query parameters is only supported for S3Store and GCSStore instances.",
+            ));
+        }
+        Ok(Self {
+            headers: headers
+                .into_iter()
+                .map(|(name, value)| (name.to_ascii_lowercase(), value.trim().to_string()))
+                .collect(),
+            query: query.into_iter().collect(),
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.query.is_empty()
+    }
+}
+
+/// Sign a URL for `path`, which with headers in `options` can only be used by requests sending
+/// them.
+async fn sign_inner(
+    store: &SignCapableStore,
+    method: &Method,
+    path: &Path,
+    expires_in: Duration,
+    options: &SignOptions,
+) -> object_store::Result<Url> {
+    match store {
+        SignCapableStore::S3(inner) if !options.is_empty() => {
+            s3_signed_url_with_headers(inner.inner().inner(), method, path, expires_in, options)
+                .await
+        }
+        SignCapableStore::Gcs {
+            store,
+            bucket,
+            service_account_key,
+        } if !options.is_empty() => {
+            gcs_signed_url_with_query(
+                store.inner().inner(),
+                bucket,
+                service_account_key.as_deref(),
+                method,
+                path,
+                expires_in,
+                &options.query,
+            )
+            .await
+        }
+        _ => store.signed_url(method.clone(), path, expires_in).await,
+    }
+}
+
+/// Sign a URL for each of `paths`, as with [`sign_inner`].
+async fn sign_many(
+    store: &SignCapableStore,
+    method: &Method,
+    paths: &[Path],
+    expires_in: Duration,
+    options: &SignOptions,
+) -> object_store::Result<Vec<Url>> {
+    if options.is_empty() {
+        return store.signed_urls(method.clone(), paths, expires_in).await;
+    }
+    futures::future::try_join_all(
+        paths
+            .iter()
+            .map(|path| sign_inner(store, method, path, expires_in, options)),
+    )
+    .await
+}
+
+/// The characters that are percent-encoded in the canonical query of Signature Version 4
+const URI_ENCODE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Presign `path` with Signature Version 4, such that the URL can only be used by requests
+/// sending the headers of `options`, and has its query parameters.
+///
+/// `object_store` only signs the `host` header of presigned URLs, so we sign these ourselves.
+/// See <https://docs.aws.amazon.com/AmazonS3/latest/API/sigv4-query-string-auth.html>.
+async fn s3_signed_url_with_headers(
+    s3: &AmazonS3,
+    method: &Method,
+    path: &Path,
+    expires_in: Duration,
+    options: &SignOptions,
+) -> object_store::Result<Url> {
+    let (mut url, region) = requests::s3_url(s3, path).await?;
+    if !options.query.is_empty() {
+        url.query_pairs_mut().extend_pairs(&options.query);
+    }
+    let credential = s3.credentials().get_credential().await?;
+    let now = Utc::now();
+    let date = now.format("%Y%m%d").to_string();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+
+    let mut headers = options.headers.clone();
+    let host = url[url::Position::BeforeHost..url::Position::AfterPort].to_string();
+    headers.insert("host".to_string(), host);
+    let signed_headers = headers
+        .keys()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(";");
+
+    url.query_pairs_mut()
+        .append_pair("X-Amz-Algorithm", "AWS4-HMAC-SHA256")
+        .append_pair(
+            "X-Amz-Credential",
+            &format!("{}/{}", credential.key_id, scope),
+        )
+        .append_pair("X-Amz-Date", &amz_date)
+        .append_pair("X-Amz-Expires", &expires_in.as_secs().to_string())
+        .append_pair("X-Amz-SignedHeaders", &signed_headers);
+    if let Some(token) = &credential.token {
+        url.query_pairs_mut()
+            .append_pair("X-Amz-Security-Token", token);
+    }
+
+    let canonical_query = canonical_query(&url);
+    let canonical_headers = headers
+        .iter()
+        .map(|(name, value)| format!("{}:{}\n", name, value))
+        .collect::<String>();
+    // The payload is sent by whoever uses the URL, so it isn't signed
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\nUNSIGNED-PAYLOAD",
+        method,
+        url.path(),
+        canonical_query,
+        canonical_headers,
+        signed_headers
+    );
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(ring::digest::digest(&ring::digest::SHA256, canonical_request.as_bytes()).as_ref())
+    );
+    let signing_key = s3_signing_key(&credential.secret_key, &date, &region);
+    let signature = hex(hmac_sha256(signing_key.as_ref(), &string_to_sign).as_ref());
+    url.query_pairs_mut()
+        .append_pair("X-Amz-Signature", &signature);
+    Ok(url)
+}
+
+/// The query of `url` in the canonical form of Signature Version 4: sorted by name, with the names
+/// and values percent-encoded.
+fn canonical_query(url: &Url) -> String {
+    let mut query = url
+        .query_pairs()
+        .map(|(name, value)| {
+            (
+                utf8_percent_encode(&name, URI_ENCODE).to_string(),
+                utf8_percent_encode(&value, URI_ENCODE).to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    query.sort();
+    query
+        .iter()
+        .map(|(name, value)| format!("{}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Presign `path` with GCS V4 signing, with the query parameters `query` added to the URL.
+///
+/// `object_store` doesn't sign extra query parameters, so we sign these URLs ourselves, with the
+/// store's service account key if it has one, and otherwise with the IAM `signBlob` API, like
+/// `object_store`. See <https://cloud.google.com/storage/docs/access-control/signing-urls-manually>.
+async fn gcs_signed_url_with_query(
+    gcs: &GoogleCloudStorage,
+    bucket: &str,
+    service_account_key: Option<&str>,
+    method: &Method,
+    path: &Path,
+    expires_in: Duration,
+    query: &[(String, String)],
+) -> object_store::Result<Url> {
+    let private_key = service_account_key.and_then(GcsPrivateKey::from_json);
+    let email = match &private_key {
+        Some(key) => key.email.clone(),
+        None => gcs
+            .signing_credentials()
+            .get_credential()
+            .await?
+            .email
+            .clone(),
+    };
+
+    let now = Utc::now();
+    let date = now.format("%Y%m%d").to_string();
+    let goog_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let scope = format!("{}/auto/storage/goog4_request", date);
+
+    let encoded_path = std::iter::once(utf8_percent_encode(bucket, URI_ENCODE).to_string())
+        .chain(
+            path.parts()
+                .map(|part| utf8_percent_encode(part.as_ref(), URI_ENCODE).to_string()),
+        )
+        .collect::<Vec<_>>()
+        .join("/");
+    let mut url = Url::parse(&format!("https://{}/{}", GCS_HOST, encoded_path))
+        .map_err(|err| requests::request_error("GCS", err.to_string()))?;
+    url.query_pairs_mut()
+        .extend_pairs(query)
+        .append_pair("X-Goog-Algorithm", "GOOG4-RSA-SHA256")
+        .append_pair("X-Goog-Credential", &format!("{}/{}", email, scope))
+        .append_pair("X-Goog-Date", &goog_date)
+        .append_pair("X-Goog-Expires", &expires_in.as_secs().to_string())
+        .append_pair("X-Goog-SignedHeaders", "host");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD",
+        method,
+        url.path(),
+        canonical_query(&url),
+        GCS_HOST
+    );
+    let string_to_sign = format!(
+        "GOOG4-RSA-SHA256\n{}\n{}\n{}",
+        goog_date,
+        scope,
+        hex(ring::digest::digest(&ring::digest::SHA256, canonical_request.as_bytes()).as_ref())
+    );
+    let signature = match &private_key {
+        Some(key) => key.sign(&string_to_sign)?,
+        None => gcs_sign_blob(gcs, &email, &string_to_sign).await?,
+    };
+    url.query_pairs_mut()
+        .append_pair("X-Goog-Signature", &hex(&signature));
+    Ok(url)
+}
+
+const GCS_HOST: &str = "storage.googleapis.com";
+
+/// The RSA key of a GCS service account.
+struct GcsPrivateKey {
+    email: String,
+    key: ring::signature::RsaKeyPair,
+}
+
+impl GcsPrivateKey {
+    /// Parse the key from a JSON service account key, returning `None` if it isn't one, such as
+    /// for the credentials of a user.
+    fn from_json(json: &str) -> Option<Self> {
+        let json: Value = serde_json::from_str(json).ok()?;
+        let email = json["client_email"].as_str()?.to_string();
+        let pem = json["private_key"].as_str()?;
+        let der = BASE64_STANDARD
+            .decode(
+                pem.lines()
+                    .filter(|line| !line.starts_with("-----"))
+                    .collect::<String>(),
+            )
+            .ok()?;
+        let key = ring::signature::RsaKeyPair::from_pkcs8(&der).ok()?;
+        Some(Self { email, key })
+    }
+
+    fn sign(&self, string_to_sign: &str) -> object_store::Result<Vec<u8>> {
+        let mut signature = vec![0; self.key.public().modulus_len()];
+        self.key
+            .sign(
+                &ring::signature::RSA_PKCS1_SHA256,
+                &ring::rand::SystemRandom::new(),
+                string_to_sign.as_bytes(),
+                &mut signature,
+            )
+            .map_err(|err| requests::request_error("GCS", err.to_string()))?;
+        Ok(signature)
+    }
+}
+
+/// Sign `string_to_sign` as the service account `email` with the IAM `signBlob` API.
+async fn gcs_sign_blob(
+    gcs: &GoogleCloudStorage,
+    email: &str,
+    string_to_sign: &str,
+) -> object_store::Result<Vec<u8>> {
+    let credential = gcs.credentials().get_credential().await?;
+    let url = format!(
+        "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{}:signBlob",
+        email
+    );
+    let body = json!({ "payload": BASE64_STANDARD.encode(string_to_sign) }).to_string();
+    let request = http::Request::builder()
+        .method(Method::POST)
+        .uri(url)
+        .header(
+            http::header::AUTHORIZATION,
+            format!("Bearer {}", credential.bearer),
+        )
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(HttpRequestBody::from(body.into_bytes()))
+        .map_err(|err| requests::request_error("GCS", err.to_string()))?;
+    let body = requests::send(&requests::client()?, request, email, "GCS").await?;
+    let body: Value = serde_json::from_str(&body)
+        .map_err(|err| requests::request_error("GCS", err.to_string()))?;
+    body["signedBlob"]
+        .as_str()
+        .and_then(|blob| BASE64_STANDARD.decode(blob).ok())
+        .ok_or_else(|| requests::request_error("GCS", "Invalid signBlob response"))
+}
+
+/// A condition of an S3 POST policy, that the fields of the upload form must satisfy.
+pub(crate) enum PyPostCondition {
+    /// Fields that must have exactly these values, from a dict
+    Exact(IndexMap<String, String>),
+    /// `["eq" | "starts-with", "$field", value]`
+    Match(String, String, String),
+    /// `["content-length-range", min, max]`
+    ContentLengthRange(u64, u64),
+}
+
+impl<'py> FromPyObject<'py> for PyPostCondition {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.downcast::<PyDict>().is_ok() {
+            return Ok(Self::Exact(ob.extract()?));
+        }
+        if let Ok((name, min, max)) = ob.extract::<(PyBackedStr, u64, u64)>() {
+            if &*name == "content-length-range" {
+                return Ok(Self::ContentLengthRange(min, max));
+            }
+        }
+        if let Ok((op, field, value)) = ob.extract::<(String, String, String)>() {
+            if matches!(op.as_str(), "eq" | "starts-with") && field.starts_with('$') {
+                return Ok(Self::Match(op, field, value));
+            }
+        }
+        Err(PyValueError::new_err(format!(
+            "Expected a POST policy condition as a dict of exact values, [\"eq\" or \"starts-with\", \"$field\", value] or [\"content-length-range\", min, max], got {}",
+            ob.repr()?
+        )))
+    }
+}
+
+impl PyPostCondition {
+    /// The condition in the policy document. Exact values are added to the form fields instead,
+    /// each of which is a condition of the policy.
+    fn to_json(&self) -> Option<Value> {
+        match self {
+            Self::Exact(_) => None,
+            Self::Match(op, field, value) => Some(json!([op, field, value])),
+            Self::ContentLengthRange(min, max) => Some(json!(["content-length-range", min, max])),
+        }
+    }
+}
+
+/// An S3 store, which is the only backend that supports POST policies.
+pub(crate) struct S3PostStore {
+    store: Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>,
+    bucket: String,
+}
+
+impl<'py> FromPyObject<'py> for S3PostStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyS3Store>() {
+            let store = store.get();
+            return Ok(Self {
+                store: store.as_ref().clone(),
+                bucket: store.bucket().to_string(),
+            });
+        }
+
+        let py = ob.py();
+        // Check for object-store instance from other library
+        let cls_name = ob
+            .getattr(intern!(py, "__class__"))?
+            .getattr(intern!(py, "__name__"))?
+            .extract::<PyBackedStr>()?;
+        if &*cls_name == "S3Store" {
+            return Err(PyValueError::new_err("You must use an object store instance exported from **the same library** as this function. They cannot be used across libraries.\nThis is because object store instances are compiled with a specific version of Rust and Python." ));
+        }
+
+        Err(PyValueError::new_err(format!(
+            "Presigned POST uploads are only supported for S3Store instances, got {}",
+            ob.repr()?
+        )))
+    }
+}
+
+/// The URL and form fields of a presigned POST upload.
+#[derive(IntoPyObject)]
+pub(crate) struct PyPresignedPost {
+    #[pyo3(item)]
+    url: PyUrl,
+    #[pyo3(item)]
+    fields: IndexMap<String, String>,
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, expires_in, conditions=None))]
+pub(crate) fn sign_post(
+    py: Python,
+    store: S3PostStore,
+    path: PyPath,
+    expires_in: Duration,
+    conditions: Option<Vec<PyPostCondition>>,
+) -> PyObjectStoreResult<PyPresignedPost> {
+    let runtime = get_runtime(py)?;
+    let path = path.into();
+    let conditions = conditions.unwrap_or_default();
+    py.allow_threads(|| {
+        let post = runtime.block_on(presigned_post(&store, &path, expires_in, &conditions))?;
+        Ok::<_, PyObjectStoreError>(post)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, path, expires_in, conditions=None))]
+pub(crate) fn sign_post_async(
+    py: Python,
+    store: S3PostStore,
+    path: PyPath,
+    expires_in: Duration,
+    conditions: Option<Vec<PyPostCondition>>,
+) -> PyResult<Bound<PyAny>> {
+    let path = path.into();
+    let conditions = conditions.unwrap_or_default();
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let post = presigned_post(&store, &path, expires_in, &conditions)
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(post)
+    })
+}
+
+/// Create a POST policy for uploading to `path`, signed with Signature Version 4.
+///
+/// S3 rejects an upload with a form field that no condition of the policy covers, so every
+/// field returned is also an exact condition of the policy.
+async fn presigned_post(
+    store: &S3PostStore,
+    path: &Path,
+    expires_in: Duration,
+    conditions: &[PyPostCondition],
+) -> object_store::Result<PyPresignedPost> {
+    let key = store.store.inner().full_path(path).to_string();
+    let s3 = store.store.inner().inner();
+    let (url, region) = requests::s3_url(s3, &Path::default()).await?;
+    let credential = s3.credentials().get_credential().await?;
+
+    let now = Utc::now();
+    let expiration = now
+        + chrono::Duration::from_std(expires_in)
+            .map_err(|err| requests::request_error("S3", err.to_string()))?;
+    let date = now.format("%Y%m%d").to_string();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let amz_credential = format!("{}/{}/{}/s3/aws4_request", credential.key_id, date, region);
+
+    let mut fields = IndexMap::new();
+    fields.insert("key".to_string(), key);
+    fields.insert(
+        "x-amz-algorithm".to_string(),
+        "AWS4-HMAC-SHA256".to_string(),
+    );
+    fields.insert("x-amz-credential".to_string(), amz_credential);
+    fields.insert("x-amz-date".to_string(), amz_date);
+    if let Some(token) = &credential.token {
+        fields.insert("x-amz-security-token".to_string(), token.clone());
+    }
+    // Fields with exact values are sent by the form as they are
+    for condition in conditions {
+        if let PyPostCondition::Exact(exact) = condition {
+            fields.extend(
+                exact
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone())),
+            );
+        }
+    }
+
+    let mut policy_conditions = vec![json!({ "bucket": store.bucket })];
+    policy_conditions.extend(fields.iter().map(|(name, value)| json!({ name: value })));
+    policy_conditions.extend(conditions.iter().filter_map(PyPostCondition::to_json));
+    let policy = json!({
+        "expiration": expiration.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(),
+        "conditions": policy_conditions,
+    });
+    let policy = BASE64_STANDARD.encode(policy.to_string());
+
+    let signing_key = s3_signing_key(&credential.secret_key, &date, &region);
+    let signature = hex(hmac_sha256(signing_key.as_ref(), &policy).as_ref());
+    fields.insert("policy".to_string(), policy);
+    fields.insert("x-amz-signature".to_string(), signature);
+
+    Ok(PyPresignedPost {
+        url: PyUrl::new(url),
+        fields,
+    })
+}
+
+/// The key that Signature Version 4 signs with on `date`, as `YYYYMMDD`.
+fn s3_signing_key(secret_key: &str, date: &str, region: &str) -> ring::hmac::Tag {
+    [region, "s3", "aws4_request"].iter().fold(
+        hmac_sha256(format!("AWS4{}", secret_key).as_bytes(), date),
+        |key, data| hmac_sha256(key.as_ref(), data),
+    )
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> ring::hmac::Tag {
+    ring::hmac::sign(
+        &ring::hmac::Key::new(ring::hmac::HMAC_SHA256, key),
+        data.as_bytes(),
+    )
+}
@@ -0,0 +1,336 @@
+//! Ordering list streams by path, modification time or size.
+//!
+//! Stores list in their own order, which for most is the lexicographic order of paths, so any
+//! other order needs the whole listing before the first object can be returned. Listings of
+//! millions of objects don't fit in memory, so this is an external merge sort: objects are
+//! buffered up to a memory budget, each full buffer is sorted and spilled to a temporary file,
+//! and the files are merged as the stream is read.
+
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Seek, Write};
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::ObjectMeta;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use serde_json::{json, Value};
+
+use crate::list::PyObjectMeta;
+
+/// The default memory budget of a sort, in bytes.
+pub(crate) const DEFAULT_SORT_MEMORY_LIMIT: usize = 256 * 1024 * 1024;
+
+/// The fewest objects in a spilled run, so that a tiny memory budget doesn't open a file per
+/// object while merging.
+const MIN_RUN_LEN: usize = 1024;
+
+/// The most objects merged by one blocking task, so that a task isn't spawned per object.
+const MERGE_BATCH_LEN: usize = 1024;
+
+/// The key that a listing is ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PyListOrder {
+    Path,
+    LastModified,
+    Size,
+}
+
+impl<'py> FromPyObject<'py> for PyListOrder {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_lowercase();
+        match s.as_str() {
+            "path" => Ok(Self::Path),
+            "last_modified" => Ok(Self::LastModified),
+            "size" => Ok(Self::Size),
+            _ => Err(PyValueError::new_err(format!(
+                "Unexpected order_by: '{}'. Expected one of 'path', 'last_modified', or 'size'.",
+                s
+            ))),
+        }
+    }
+}
+
+/// The order of a listing, with ties broken by path so that it's the same across runs.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SortOrder {
+    pub(crate) key: PyListOrder,
+    pub(crate) descending: bool,
+}
+
+impl SortOrder {
+    fn compare(&self, a: &ObjectMeta, b: &ObjectMeta) -> Ordering {
+        let ordering = match self.key {
+            PyListOrder::Path => Ordering::Equal,
+            PyListOrder::LastModified => a.last_modified.cmp(&b.last_modified),
+            PyListOrder::Size => a.size.cmp(&b.size),
+        }
+        .then_with(|| a.location.cmp(&b.location));
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+}
+
+/// Read the whole of `stream`, and emit it in `order`.
+///
+/// At most about `memory_limit` bytes of objects are held in memory at once. Beyond that, sorted
+/// runs of objects are spilled to temporary files, which are removed once the stream is dropped.
+/// The files are written and read on blocking threads, so as not to stall the runtime.
+pub(crate) fn sort_stream(
+    stream: BoxStream<'static, object_store::Result<PyObjectMeta>>,
+    order: SortOrder,
+    memory_limit: usize,
+) -> BoxStream<'static, object_store::Result<PyObjectMeta>> {
+    futures::stream::once(async move {
+        let mut stream = stream;
+        let mut buffer = vec![];
+        let mut buffered_size = 0;
+        let mut runs = vec![];
+        while let Some(meta) = stream.try_next().await? {
+            buffered_size += estimated_size(&meta);
+            buffer.push(meta);
+            if buffered_size >= memory_limit && buffer.len() >= MIN_RUN_LEN {
+                let run = std::mem::take(&mut buffer);
+                runs.push(spawn_blocking(move || spill(run, order)).await?);
+                buffered_size = 0;
+            }
+        }
+        buffer.sort_unstable_by(|a, b| order.compare(a.as_ref(), b.as_ref()));
+
+        let sorted = if runs.is_empty() {
+            futures::stream::iter(buffer.into_iter().map(Ok)).boxed()
+        } else {
+            let merge = spawn_blocking(move || Merge::new(runs, buffer, order)).await?;
+            merge_stream(merge)
+        };
+        Ok::<_, object_store::Error>(sorted)
+    })
+    .try_flatten()
+    .boxed()
+}
+
+/// Emit the objects of `merge`, reading its runs in batches on blocking threads.
+fn merge_stream(merge: Merge) -> BoxStream<'static, object_store::Result<PyObjectMeta>> {
+    futures::stream::try_unfold(merge, |mut merge| async move {
+        let (batch, merge) = tokio::task::spawn_blocking(move || {
+            let batch = merge
+                .by_ref()
+                .take(MERGE_BATCH_LEN)
+                .collect::<io::Result<Vec<_>>>();
+            (batch, merge)
+        })
+        .await
+        .map_err(|err| object_store::Error::JoinError { source: err })?;
+        let batch = batch.map_err(sort_error)?;
+        let next =
+            (!batch.is_empty()).then(|| (futures::stream::iter(batch.into_iter().map(Ok)), merge));
+        Ok::<_, object_store::Error>(next)
+    })
+    .try_flatten()
+    .boxed()
+}
+
+async fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> io::Result<T> + Send + 'static,
+) -> object_store::Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| object_store::Error::JoinError { source: err })?
+        .map_err(sort_error)
+}
+
+fn sort_error(err: io::Error) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "sort",
+        source: format!("Failed to sort listing in temporary files: {}", err).into(),
+    }
+}
+
+/// The approximate memory used by an object in the buffer.
+fn estimated_size(meta: &PyObjectMeta) -> usize {
+    let object = meta.as_ref();
+    let metadata = meta.metadata().map_or(0, |metadata| {
+        metadata
+            .iter()
+            .map(|(key, value)| key.len() + value.len())
+            .sum()
+    });
+    std::mem::size_of::<PyObjectMeta>()
+        + object.location.as_ref().len()
+        + object.e_tag.as_ref().map_or(0, String::len)
+        + object.version.as_ref().map_or(0, String::len)
+        + metadata
+}
+
+/// A sorted run of objects spilled to a temporary file, one JSON object per line.
+///
+/// The file is created by [`tempfile::tempfile`], so it has a unique name that can't be taken over
+/// by another user, and the OS removes it once it's closed, even if the process is killed.
+struct Run {
+    reader: BufReader<File>,
+}
+
+impl Run {
+    fn next(&mut self) -> io::Result<Option<PyObjectMeta>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let value = serde_json::from_str(&line)?;
+        from_json(&value)
+            .map(Some)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Malformed sort run"))
+    }
+}
+
+/// Sort `buffer` and write it to a new run.
+fn spill(mut buffer: Vec<PyObjectMeta>, order: SortOrder) -> io::Result<Run> {
+    buffer.sort_unstable_by(|a, b| order.compare(a.as_ref(), b.as_ref()));
+    let mut writer = BufWriter::new(tempfile::tempfile()?);
+    for meta in buffer {
+        serde_json::to_writer(&mut writer, &to_json(&meta))?;
+        writer.write_all(b"\n")?;
+    }
+    let mut file = writer
+        .into_inner()
+        .map_err(io::IntoInnerError::into_error)?;
+    file.rewind()?;
+    Ok(Run {
+        reader: BufReader::new(file),
+    })
+}
+
+fn to_json(meta: &PyObjectMeta) -> Value {
+    let object = meta.as_ref();
+    // Metadata is written as pairs, to keep the order of its keys
+    let metadata = meta.metadata().map(|metadata| {
+        metadata
+            .iter()
+            .map(|(key, value)| json!([key, value]))
+            .collect::<Vec<_>>()
+    });
+    json!({
+        "path": object.location.as_ref(),
+        "last_modified": object.last_modified.to_rfc3339_opts(SecondsFormat::Nanos, true),
+        "size": object.size,
+        "e_tag": object.e_tag,
+        "version": object.version,
+        "metadata": metadata,
+    })
+}
+
+fn from_json(value: &Value) -> Option<PyObjectMeta> {
+    let meta = ObjectMeta {
+        location: Path::parse(value["path"].as_str()?).ok()?,
+        last_modified: DateTime::parse_from_rfc3339(value["last_modified"].as_str()?)
+            .ok()?
+            .with_timezone(&Utc),
+        size: value["size"].as_u64()?,
+        e_tag: value["e_tag"].as_str().map(String::from),
+        version: value["version"].as_str().map(String::from),
+    };
+    match value["metadata"].as_array() {
+        Some(metadata) => {
+            let metadata = metadata
+                .iter()
+                .map(|pair| Some((pair[0].as_str()?.to_string(), pair[1].as_str()?.to_string())))
+                .collect::<Option<IndexMap<_, _>>>()?;
+            Some(PyObjectMeta::with_metadata(meta, metadata))
+        }
+        None => Some(PyObjectMeta::new(meta)),
+    }
+}
+
+/// The next object of a run, ordered so that the [`BinaryHeap`] of a merge pops the first object
+/// in the sort order.
+struct Head {
+    meta: PyObjectMeta,
+    /// The index of the run, or `None` for the objects still in memory
+    run: Option<usize>,
+    order: SortOrder,
+}
+
+impl PartialEq for Head {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Head {}
+
+impl PartialOrd for Head {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Head {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order
+            .compare(other.meta.as_ref(), self.meta.as_ref())
+            .then_with(|| other.run.cmp(&self.run))
+    }
+}
+
+/// A k-way merge of the spilled runs and the sorted objects that were left in memory.
+struct Merge {
+    runs: Vec<Run>,
+    in_memory: std::vec::IntoIter<PyObjectMeta>,
+    heads: BinaryHeap<Head>,
+    order: SortOrder,
+}
+
+impl Merge {
+    fn new(runs: Vec<Run>, in_memory: Vec<PyObjectMeta>, order: SortOrder) -> io::Result<Self> {
+        let mut merge = Self {
+            heads: BinaryHeap::with_capacity(runs.len() + 1),
+            runs,
+            in_memory: in_memory.into_iter(),
+            order,
+        };
+        merge.advance(None)?;
+        for run in 0..merge.runs.len() {
+            merge.advance(Some(run))?;
+        }
+        Ok(merge)
+    }
+
+    /// Push the next object of `run` onto the heap, if it has one.
+    fn advance(&mut self, run: Option<usize>) -> io::Result<()> {
+        let next = match run {
+            Some(index) => self.runs[index].next()?,
+            None => self.in_memory.next(),
+        };
+        if let Some(meta) = next {
+            self.heads.push(Head {
+                meta,
+                run,
+                order: self.order,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for Merge {
+    type Item = io::Result<PyObjectMeta>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let head = self.heads.pop()?;
+        if let Err(err) = self.advance(head.run) {
+            // Nothing more can be merged correctly once a run can't be read
+            self.heads.clear();
+            return Some(Err(err));
+        }
+        Some(Ok(head.meta))
+    }
+}
@@ -0,0 +1,394 @@
+//! Tracing of store operations, enabled with `obstore.runtime.enable_tracing`.
+
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::prelude::*;
+use tracing::field::Empty;
+use tracing::Instrument;
+
+/// The Python callback receiving a record of each finished operation, if any.
+static CALLBACK: RwLock<Option<PyObject>> = RwLock::new(None);
+
+/// Trace every store operation started from now on, reporting finished operations to `callback`
+/// as well as to any `tracing` subscriber.
+pub(crate) fn enable(callback: Option<PyObject>) {
+    *CALLBACK.write().unwrap() = callback;
+    pyo3_object_store::set_store_layer(Some(traced));
+}
+
+/// Stop tracing store operations started from now on.
+pub(crate) fn disable() {
+    pyo3_object_store::set_store_layer(None);
+    *CALLBACK.write().unwrap() = None;
+}
+
+fn traced(store: Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore> {
+    Arc::new(TracingStore { inner: store })
+}
+
+/// The record of a finished operation passed to the Python callback.
+#[derive(IntoPyObject)]
+struct PySpanRecord {
+    #[pyo3(item)]
+    operation: &'static str,
+    #[pyo3(item)]
+    path: String,
+    #[pyo3(item)]
+    bytes: u64,
+    #[pyo3(item)]
+    start: DateTime<Utc>,
+    #[pyo3(item)]
+    duration: Duration,
+    #[pyo3(item)]
+    error: Option<String>,
+}
+
+/// An operation in progress, which is reported once dropped.
+///
+/// Operations returning a stream, such as `get` and `list`, move their span into the stream, so
+/// that they finish once the stream has been consumed or dropped.
+struct OperationSpan {
+    span: tracing::Span,
+    operation: &'static str,
+    path: String,
+    start: DateTime<Utc>,
+    started: Instant,
+    bytes: u64,
+    error: Option<String>,
+}
+
+impl OperationSpan {
+    fn new(operation: &'static str, path: impl Into<String>) -> Self {
+        let path = path.into();
+        let span = tracing::info_span!(
+            target: "obstore",
+            "store_operation",
+            operation,
+            path = path.as_str(),
+            bytes = Empty,
+            error = Empty,
+        );
+        Self {
+            span,
+            operation,
+            path,
+            start: Utc::now(),
+            started: Instant::now(),
+            bytes: 0,
+            error: None,
+        }
+    }
+
+    fn record<T>(&mut self, result: &Result<T>) {
+        if let Err(err) = result {
+            self.error.get_or_insert_with(|| err.to_string());
+        }
+    }
+}
+
+impl std::fmt::Debug for OperationSpan {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OperationSpan")
+            .field("operation", &self.operation)
+            .field("path", &self.path)
+            .finish()
+    }
+}
+
+impl Drop for OperationSpan {
+    fn drop(&mut self) {
+        self.span.record("bytes", self.bytes);
+        if let Some(error) = &self.error {
+            self.span.record("error", error.as_str());
+        }
+        if CALLBACK.read().unwrap().is_none() {
+            return;
+        }
+        Python::with_gil(|py| {
+            let Some(callback) = CALLBACK.read().unwrap().as_ref().map(|cb| cb.clone_ref(py))
+            else {
+                return;
+            };
+            let record = PySpanRecord {
+                operation: self.operation,
+                path: std::mem::take(&mut self.path),
+                bytes: self.bytes,
+                start: self.start,
+                duration: self.started.elapsed(),
+                error: self.error.take(),
+            };
+            if let Err(err) = callback.call1(py, (record,)) {
+                err.write_unraisable(py, None);
+            }
+        });
+    }
+}
+
+/// Yield the entries of `stream`, finishing `span` once the stream is consumed or dropped.
+fn traced_stream<'a, T: Send + 'a>(
+    mut span: OperationSpan,
+    stream: BoxStream<'a, Result<T>>,
+    size: impl Fn(&T) -> u64 + Send + 'a,
+) -> BoxStream<'a, Result<T>> {
+    stream
+        .map(move |item| {
+            match &item {
+                Ok(item) => span.bytes += size(item),
+                Err(_) => span.record(&item),
+            }
+            item
+        })
+        .boxed()
+}
+
+/// Store wrapper reporting each operation as a span.
+#[derive(Debug)]
+struct TracingStore {
+    inner: Arc<dyn ObjectStore>,
+}
+
+impl std::fmt::Display for TracingStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TracingStore({})", self.inner)
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for TracingStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let mut span = OperationSpan::new("put", location.as_ref());
+        span.bytes = payload.content_length() as u64;
+        let result = self
+            .inner
+            .put_opts(location, payload, opts)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let mut span = OperationSpan::new("put_multipart", location.as_ref());
+        let result = self
+            .inner
+            .put_multipart_opts(location, opts)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        Ok(Box::new(TracingUpload {
+            inner: result?,
+            span,
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let operation = if options.head { "head" } else { "get" };
+        let mut span = OperationSpan::new(operation, location.as_ref());
+        let result = self
+            .inner
+            .get_opts(location, options)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        let result = result?;
+        let payload = match result.payload {
+            GetResultPayload::Stream(stream) => {
+                GetResultPayload::Stream(traced_stream(span, stream, |bytes| bytes.len() as u64))
+            }
+            payload => {
+                // A file is read by the caller, so the span only covers opening it
+                span.bytes = result.range.end - result.range.start;
+                payload
+            }
+        };
+        Ok(GetResult { payload, ..result })
+    }
+
+    async fn get_range(
+        &self,
+        location: &Path,
+        range: std::ops::Range<u64>,
+    ) -> Result<bytes::Bytes> {
+        let mut span = OperationSpan::new("get_range", location.as_ref());
+        let result = self
+            .inner
+            .get_range(location, range)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        if let Ok(buffer) = &result {
+            span.bytes = buffer.len() as u64;
+        }
+        result
+    }
+
+    async fn get_ranges(
+        &self,
+        location: &Path,
+        ranges: &[std::ops::Range<u64>],
+    ) -> Result<Vec<bytes::Bytes>> {
+        let mut span = OperationSpan::new("get_ranges", location.as_ref());
+        let result = self
+            .inner
+            .get_ranges(location, ranges)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        if let Ok(buffers) = &result {
+            span.bytes = buffers.iter().map(|buffer| buffer.len() as u64).sum();
+        }
+        result
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let mut span = OperationSpan::new("head", location.as_ref());
+        let result = self
+            .inner
+            .head(location)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let mut span = OperationSpan::new("delete", location.as_ref());
+        let result = self
+            .inner
+            .delete(location)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let span = OperationSpan::new("delete", "");
+        traced_stream(span, self.inner.delete_stream(locations), |_| 0)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let span = OperationSpan::new("list", prefix.map(|p| p.as_ref()).unwrap_or_default());
+        traced_stream(span, self.inner.list(prefix), |_| 0)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        let span = OperationSpan::new("list", prefix.map(|p| p.as_ref()).unwrap_or_default());
+        traced_stream(span, self.inner.list_with_offset(prefix, offset), |_| 0)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut span = OperationSpan::new(
+            "list_with_delimiter",
+            prefix.map(|p| p.as_ref()).unwrap_or_default(),
+        );
+        let result = self
+            .inner
+            .list_with_delimiter(prefix)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut span = OperationSpan::new("copy", from.as_ref());
+        let result = self
+            .inner
+            .copy(from, to)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut span = OperationSpan::new("rename", from.as_ref());
+        let result = self
+            .inner
+            .rename(from, to)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut span = OperationSpan::new("copy", from.as_ref());
+        let result = self
+            .inner
+            .copy_if_not_exists(from, to)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut span = OperationSpan::new("rename", from.as_ref());
+        let result = self
+            .inner
+            .rename_if_not_exists(from, to)
+            .instrument(span.span.clone())
+            .await;
+        span.record(&result);
+        result
+    }
+}
+
+/// A multipart upload reported as a single span, from its creation until it completes or is
+/// aborted.
+#[derive(Debug)]
+struct TracingUpload {
+    inner: Box<dyn MultipartUpload>,
+    span: OperationSpan,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for TracingUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.span.bytes += data.content_length() as u64;
+        self.inner.put_part(data)
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self
+            .inner
+            .complete()
+            .instrument(self.span.span.clone())
+            .await;
+        self.span.record(&result);
+        result
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let result = self.inner.abort().instrument(self.span.span.clone()).await;
+        self.span.error.get_or_insert_with(|| "aborted".to_string());
+        result
+    }
+}
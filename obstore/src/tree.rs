@@ -0,0 +1,429 @@
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use futures::{StreamExt, TryStreamExt};
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyObjectStoreResult, PyPath};
+
+use crate::runtime::get_runtime;
+
+/// A node in the prefix tree, aggregating the number and total size of all objects under it.
+#[derive(Debug)]
+pub(crate) struct PyTreeNode {
+    path: String,
+    count: u64,
+    size: u64,
+    children: BTreeMap<String, PyTreeNode>,
+}
+
+impl PyTreeNode {
+    fn new(path: String) -> Self {
+        Self {
+            path,
+            count: 0,
+            size: 0,
+            children: BTreeMap::new(),
+        }
+    }
+
+    /// Join a child name onto this node's path
+    fn child_path(&self, name: &str) -> String {
+        if self.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}/{}", self.path, name)
+        }
+    }
+
+    /// Record an object in this node and in each of its ancestor directories, up to `depth`
+    /// levels below this node.
+    ///
+    /// `parts` is the path of the object relative to this node, including the file name.
+    fn insert(&mut self, parts: &[String], size: u64, depth: usize) {
+        self.count += 1;
+        self.size += size;
+
+        // The final part is the file name itself, which is not a node of the tree
+        if depth == 0 || parts.len() <= 1 {
+            return;
+        }
+
+        let name = &parts[0];
+        let path = self.child_path(name);
+        self.children
+            .entry(name.clone())
+            .or_insert_with(|| PyTreeNode::new(path))
+            .insert(&parts[1..], size, depth - 1);
+    }
+
+    fn insert_meta(&mut self, root: &Path, meta: &ObjectMeta, depth: usize) {
+        let parts = meta
+            .location
+            .prefix_match(root)
+            .map(|parts| {
+                parts
+                    .map(|part| part.as_ref().to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        self.insert(&parts, meta.size, depth);
+    }
+
+    /// Merge a subtree that was computed for the child directory `name`.
+    fn merge_child(&mut self, name: String, child: PyTreeNode, depth: usize) {
+        self.count += child.count;
+        self.size += child.size;
+        if depth > 0 {
+            self.children.insert(name, child);
+        }
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyTreeNode {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(4);
+        dict.insert("path", self.path.into_bound_py_any(py)?);
+        dict.insert("count", self.count.into_bound_py_any(py)?);
+        dict.insert("size", self.size.into_bound_py_any(py)?);
+        dict.insert("children", self.children.into_bound_py_any(py)?);
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, depth=1, max_concurrency=12))]
+pub(crate) fn tree(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    depth: usize,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<PyTreeNode> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(build_tree(
+            store.into_inner(),
+            prefix.map(|s| s.into()).unwrap_or_default(),
+            depth,
+            max_concurrency,
+        ))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, depth=1, max_concurrency=12))]
+pub(crate) fn tree_async(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    depth: usize,
+    max_concurrency: usize,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = build_tree(
+            store.into_inner(),
+            prefix.map(|s| s.into()).unwrap_or_default(),
+            depth,
+            max_concurrency,
+        )
+        .await?;
+        Ok(out)
+    })
+}
+
+/// Build the tree under `prefix`.
+///
+/// The top level of the prefix is listed with a delimiter, and then each of the top-level
+/// directories is listed recursively and concurrently.
+async fn build_tree(
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    depth: usize,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<PyTreeNode> {
+    let mut root = PyTreeNode::new(prefix.to_string());
+    let list_result = store.list_with_delimiter(Some(&prefix)).await?;
+    for meta in list_result.objects.iter() {
+        root.insert_meta(&prefix, meta, depth);
+    }
+
+    let subtrees = futures::stream::iter(list_result.common_prefixes)
+        .map(|child_prefix| build_subtree(store.clone(), child_prefix, depth.saturating_sub(1)))
+        .buffer_unordered(max_concurrency.max(1))
+        .try_collect::<Vec<_>>()
+        .await?;
+    for (name, subtree) in subtrees {
+        root.merge_child(name, subtree, depth);
+    }
+
+    Ok(root)
+}
+
+async fn build_subtree(
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    depth: usize,
+) -> PyObjectStoreResult<(String, PyTreeNode)> {
+    let name = prefix
+        .filename()
+        .map(|name| name.to_string())
+        .unwrap_or_default();
+    let mut node = PyTreeNode::new(prefix.to_string());
+    let mut stream = store.list(Some(&prefix));
+    while let Some(meta) = stream.try_next().await? {
+        node.insert_meta(&prefix, &meta, depth);
+    }
+    Ok((name, node))
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, depth=1, *, max_concurrency=12))]
+pub(crate) fn distinct_prefixes(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    depth: usize,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<Vec<String>> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(list_distinct_prefixes(
+            store.into_inner(),
+            prefix.map(|s| s.into()).unwrap_or_default(),
+            depth,
+            max_concurrency,
+        ))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, depth=1, *, max_concurrency=12))]
+pub(crate) fn distinct_prefixes_async(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    depth: usize,
+    max_concurrency: usize,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = list_distinct_prefixes(
+            store.into_inner(),
+            prefix.map(|s| s.into()).unwrap_or_default(),
+            depth,
+            max_concurrency,
+        )
+        .await?;
+        Ok(out)
+    })
+}
+
+/// Find the directories up to `depth` levels below `prefix`, in the order of a tree: each
+/// directory comes right before the directories under it.
+///
+/// Unlike [`build_tree`], only directories are listed, with a delimiter, one level at a time. All
+/// the directories of a level are listed concurrently.
+async fn list_distinct_prefixes(
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    depth: usize,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<Vec<String>> {
+    // Keyed by their parts, so that `a/b` sorts right after `a`, before `a-b`
+    let mut prefixes = BTreeMap::new();
+    let mut level = vec![prefix];
+    for _ in 0..depth {
+        if level.is_empty() {
+            break;
+        }
+        let results = futures::stream::iter(level)
+            .map(|prefix| {
+                let store = store.clone();
+                async move { store.list_with_delimiter(Some(&prefix)).await }
+            })
+            .buffer_unordered(max_concurrency.max(1))
+            .try_collect::<Vec<_>>()
+            .await?;
+        level = vec![];
+        for common_prefix in results
+            .into_iter()
+            .flat_map(|result| result.common_prefixes)
+        {
+            let parts = common_prefix
+                .parts()
+                .map(|part| part.as_ref().to_string())
+                .collect::<Vec<_>>();
+            // Stores that list in pages may repeat a prefix across pages
+            if let Entry::Vacant(entry) = prefixes.entry(parts) {
+                entry.insert(common_prefix.to_string());
+                level.push(common_prefix);
+            }
+        }
+    }
+    Ok(prefixes.into_values().collect())
+}
+
+/// The number, total size and range of last modified times of the objects under a prefix.
+#[derive(Debug, Default)]
+pub(crate) struct PyPrefixStats {
+    count: u64,
+    size: u64,
+    min_last_modified: Option<DateTime<Utc>>,
+    max_last_modified: Option<DateTime<Utc>>,
+    /// The statistics of each directory directly under the prefix, if requested
+    directories: Option<BTreeMap<String, PyPrefixStats>>,
+}
+
+impl PyPrefixStats {
+    fn insert(&mut self, meta: &ObjectMeta) {
+        self.count += 1;
+        self.size += meta.size;
+        self.min_last_modified = Some(
+            self.min_last_modified
+                .map_or(meta.last_modified, |min| min.min(meta.last_modified)),
+        );
+        self.max_last_modified = Some(
+            self.max_last_modified
+                .map_or(meta.last_modified, |max| max.max(meta.last_modified)),
+        );
+    }
+
+    fn merge(&mut self, other: &PyPrefixStats) {
+        self.count += other.count;
+        self.size += other.size;
+        self.min_last_modified = self
+            .min_last_modified
+            .into_iter()
+            .chain(other.min_last_modified)
+            .min();
+        self.max_last_modified = self
+            .max_last_modified
+            .into_iter()
+            .chain(other.max_last_modified)
+            .max();
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyPrefixStats {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(5);
+        dict.insert("count", self.count.into_bound_py_any(py)?);
+        dict.insert("size", self.size.into_bound_py_any(py)?);
+        dict.insert(
+            "min_last_modified",
+            self.min_last_modified.into_bound_py_any(py)?,
+        );
+        dict.insert(
+            "max_last_modified",
+            self.max_last_modified.into_bound_py_any(py)?,
+        );
+        if let Some(directories) = self.directories {
+            dict.insert("directories", directories.into_bound_py_any(py)?);
+        }
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, by_directory=false, max_concurrency=12))]
+pub(crate) fn stat_prefix(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    by_directory: bool,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<PyPrefixStats> {
+    let runtime = get_runtime(py)?;
+    py.allow_threads(|| {
+        let out = runtime.block_on(build_stats(
+            store.into_inner(),
+            prefix.map(|s| s.into()).unwrap_or_default(),
+            by_directory,
+            max_concurrency,
+        ))?;
+        Ok::<_, PyObjectStoreError>(out)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, by_directory=false, max_concurrency=12))]
+pub(crate) fn stat_prefix_async(
+    py: Python,
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    by_directory: bool,
+    max_concurrency: usize,
+) -> PyResult<Bound<PyAny>> {
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let out = build_stats(
+            store.into_inner(),
+            prefix.map(|s| s.into()).unwrap_or_default(),
+            by_directory,
+            max_concurrency,
+        )
+        .await?;
+        Ok(out)
+    })
+}
+
+/// Aggregate the statistics of the objects under `prefix`.
+///
+/// As with [`build_tree`], the top-level directories of the prefix are listed concurrently.
+async fn build_stats(
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+    by_directory: bool,
+    max_concurrency: usize,
+) -> PyObjectStoreResult<PyPrefixStats> {
+    let mut root = PyPrefixStats::default();
+    let list_result = store.list_with_delimiter(Some(&prefix)).await?;
+    for meta in list_result.objects.iter() {
+        root.insert(meta);
+    }
+
+    let directories = futures::stream::iter(list_result.common_prefixes)
+        .map(|child_prefix| build_directory_stats(store.clone(), child_prefix))
+        .buffer_unordered(max_concurrency.max(1))
+        .try_collect::<BTreeMap<_, _>>()
+        .await?;
+    for stats in directories.values() {
+        root.merge(stats);
+    }
+    if by_directory {
+        root.directories = Some(directories);
+    }
+
+    Ok(root)
+}
+
+async fn build_directory_stats(
+    store: Arc<dyn ObjectStore>,
+    prefix: Path,
+) -> PyObjectStoreResult<(String, PyPrefixStats)> {
+    let name = prefix
+        .filename()
+        .map(|name| name.to_string())
+        .unwrap_or_default();
+    let mut stats = PyPrefixStats::default();
+    let mut stream = store.list(Some(&prefix));
+    while let Some(meta) = stream.try_next().await? {
+        stats.insert(&meta);
+    }
+    Ok((name, stats))
+}
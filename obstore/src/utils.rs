@@ -1,4 +1,12 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use futures::{Stream, StreamExt};
 use pyo3::prelude::*;
+use pyo3_object_store::StreamTimeoutError;
+use tokio::task::{JoinError, JoinHandle};
 
 /// Returning `()` from `future_into_py` returns an empty tuple instead of None
 /// https://github.com/developmentseed/obstore/issues/240
@@ -13,3 +21,51 @@ impl<'py> IntoPyObject<'py> for PyNone {
         Ok(py.None().bind(py).clone())
     }
 }
+
+/// A spawned task that is aborted once dropped, unlike a [`JoinHandle`], which detaches it.
+///
+/// Futures passed to `future_into_py` are dropped when their Python task is cancelled, so tasks
+/// they spawn should be aborted too, rather than keep making requests in the background.
+pub(crate) struct AbortOnDrop<T>(JoinHandle<T>);
+
+impl<T: Send + 'static> AbortOnDrop<T> {
+    pub(crate) fn spawn(future: impl Future<Output = T> + Send + 'static) -> Self {
+        Self(tokio::spawn(future))
+    }
+}
+
+impl<T> Future for AbortOnDrop<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.0).poll(cx)
+    }
+}
+
+impl<T> Drop for AbortOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Wait for the next item of `stream`, raising a `StreamTimeoutError` if none arrives within
+/// `timeout`.
+///
+/// Waiting for the next item of a stream is cancel-safe, so the stream can still be polled after
+/// a timeout.
+pub(crate) async fn next_within<S: Stream + Unpin>(
+    stream: &mut S,
+    timeout: Option<Duration>,
+) -> PyResult<Option<S::Item>> {
+    let Some(timeout) = timeout else {
+        return Ok(stream.next().await);
+    };
+    tokio::time::timeout(timeout, stream.next())
+        .await
+        .map_err(|_| {
+            StreamTimeoutError::new_err(format!(
+                "No data received from the stream within {:?}",
+                timeout
+            ))
+        })
+}
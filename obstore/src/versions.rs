@@ -0,0 +1,584 @@
+//! Listing and deleting the versions of objects, in buckets and containers with versioning
+//! enabled.
+//!
+//! `object_store` can read a version with `GetOptions::version`, but it has no way to find the
+//! versions of an object or to delete one, so we make those requests ourselves.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use http::Method;
+use object_store::aws::AmazonS3;
+use object_store::azure::{AzureAuthorizer, MicrosoftAzure};
+use object_store::client::{HttpClient, HttpRequestBody};
+use object_store::gcp::GoogleCloudStorage;
+use object_store::path::Path;
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3_object_store::{
+    resolve_store_token, DeadlineExceeded, LimitedStore, MaybePrefixedStore, PyAzureStore,
+    PyGCSStore, PyObjectStoreError, PyObjectStoreResult, PyPath, PyS3Store,
+};
+use serde_json::Value;
+use url::Url;
+
+use crate::copy::deadline;
+use crate::metadata::{unescape_xml, xml_element};
+use crate::requests::{self, request_error};
+use crate::runtime::get_runtime;
+
+/// The base URL of the JSON API of Google Cloud Storage.
+const GCS_BASE_URL: &str = "https://storage.googleapis.com";
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, timeout=None))]
+pub(crate) fn list_versions(
+    py: Python,
+    store: VersionedStore,
+    prefix: Option<PyPath>,
+    timeout: Option<Duration>,
+) -> PyObjectStoreResult<Vec<PyObjectVersion>> {
+    let runtime = get_runtime(py)?;
+    let prefix = prefix.map(Path::from);
+    py.allow_threads(|| {
+        let versions = runtime.block_on(with_deadline(
+            deadline(timeout),
+            store.list_versions(prefix.as_ref()),
+        ))?;
+        Ok::<_, PyObjectStoreError>(versions)
+    })
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, *, timeout=None))]
+pub(crate) fn list_versions_async(
+    py: Python,
+    store: VersionedStore,
+    prefix: Option<PyPath>,
+    timeout: Option<Duration>,
+) -> PyResult<Bound<PyAny>> {
+    let prefix = prefix.map(Path::from);
+    let deadline = deadline(timeout);
+    pyo3_async_runtimes::tokio::future_into_py(py, async move {
+        let versions = with_deadline(deadline, store.list_versions(prefix.as_ref()))
+            .await
+            .map_err(PyObjectStoreError::ObjectStoreError)?;
+        Ok(versions)
+    })
+}
+
+/// Run `fut`, failing if it hasn't completed by `deadline`.
+pub(crate) async fn with_deadline<T>(
+    deadline: Option<tokio::time::Instant>,
+    fut: impl std::future::Future<Output = object_store::Result<T>>,
+) -> object_store::Result<T> {
+    match deadline {
+        Some(deadline) => tokio::time::timeout_at(deadline, fut).await.map_err(|_| {
+            object_store::Error::Generic {
+                store: "versions",
+                source: Box::new(DeadlineExceeded::new("versions")),
+            }
+        })?,
+        None => fut.await,
+    }
+}
+
+/// A version of an object.
+#[derive(Debug, IntoPyObject)]
+pub(crate) struct PyObjectVersion {
+    #[pyo3(item)]
+    path: String,
+    #[pyo3(item)]
+    version: String,
+    #[pyo3(item)]
+    is_latest: bool,
+    #[pyo3(item)]
+    is_delete_marker: bool,
+    #[pyo3(item)]
+    last_modified: DateTime<Utc>,
+    #[pyo3(item)]
+    size: u64,
+    #[pyo3(item)]
+    e_tag: Option<String>,
+}
+
+/// A store whose backend keeps versions of objects: S3, GCS or Azure.
+pub(crate) enum VersionedStore {
+    S3(Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>),
+    Gcs {
+        store: Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>>,
+        bucket: String,
+    },
+    Azure {
+        store: Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>,
+        container_url: Url,
+        account: String,
+    },
+}
+
+impl<'py> FromPyObject<'py> for VersionedStore {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let ob = &resolve_store_token(ob)?;
+        if let Ok(store) = ob.downcast::<PyS3Store>() {
+            return Ok(Self::S3(store.get().as_ref().clone()));
+        }
+        if let Ok(store) = ob.downcast::<PyGCSStore>() {
+            let store = store.get();
+            return Ok(Self::Gcs {
+                store: store.as_ref().clone(),
+                bucket: store.bucket().to_string(),
+            });
+        }
+        if let Ok(store) = ob.downcast::<PyAzureStore>() {
+            let store = store.get();
+            let (container_url, account) = store.container_url()?;
+            return Ok(Self::Azure {
+                store: store.as_ref().clone(),
+                container_url,
+                account,
+            });
+        }
+
+        let py = ob.py();
+        // Check for object-store instance from other library
+        let cls_name = ob
+            .getattr(intern!(py, "__class__"))?
+            .getattr(intern!(py, "__name__"))?
+            .extract::<PyBackedStr>()?;
+        if matches!(cls_name.as_ref(), "S3Store" | "GCSStore" | "AzureStore") {
+            return Err(PyValueError::new_err("You must use an object store instance exported from **the same library** as this function. They cannot be used across libraries.\nThis is because object store instances are compiled with a specific version of Rust and Python." ));
+        }
+
+        Err(PyValueError::new_err(format!(
+            "Object versions are only supported for S3Store, GCSStore and AzureStore instances, got {}",
+            ob.repr()?
+        )))
+    }
+}
+
+impl VersionedStore {
+    /// The path within the bucket of `path` within the store.
+    fn full_path(&self, path: &Path) -> Path {
+        match self {
+            Self::S3(store) => store.inner().full_path(path).into_owned(),
+            Self::Gcs { store, .. } => store.inner().full_path(path).into_owned(),
+            Self::Azure { store, .. } => store.inner().full_path(path).into_owned(),
+        }
+    }
+
+    /// The path within the store of `path` within the bucket, or `None` if it's outside of the
+    /// prefix of the store.
+    fn store_path(&self, path: Path) -> Option<Path> {
+        let prefix = match self {
+            Self::S3(store) => store.inner().prefix(),
+            Self::Gcs { store, .. } => store.inner().prefix(),
+            Self::Azure { store, .. } => store.inner().prefix(),
+        };
+        match prefix {
+            Some(prefix) => Some(path.prefix_match(prefix)?.collect()),
+            None => Some(path),
+        }
+    }
+
+    /// List every version of the objects under `prefix`, including delete markers.
+    async fn list_versions(
+        &self,
+        prefix: Option<&Path>,
+    ) -> object_store::Result<Vec<PyObjectVersion>> {
+        let prefix = self.full_path(prefix.unwrap_or(&Path::default()));
+        // Prefixes are evaluated on a path segment basis, as in `ObjectStore::list`
+        let request_prefix = (!prefix.as_ref().is_empty()).then(|| format!("{}/", prefix));
+        let client = requests::client()?;
+
+        let mut versions = vec![];
+        let mut marker = vec![];
+        loop {
+            let (page, next_marker) = match self {
+                Self::S3(store) => {
+                    list_s3_page(
+                        store.inner().inner(),
+                        &client,
+                        request_prefix.as_deref(),
+                        &marker,
+                    )
+                    .await?
+                }
+                Self::Gcs { store, bucket } => {
+                    list_gcs_page(
+                        store.inner().inner(),
+                        &client,
+                        bucket,
+                        request_prefix.as_deref(),
+                        &marker,
+                    )
+                    .await?
+                }
+                Self::Azure {
+                    store,
+                    container_url,
+                    account,
+                } => {
+                    list_azure_page(
+                        store.inner().inner(),
+                        &client,
+                        container_url,
+                        account,
+                        request_prefix.as_deref(),
+                        &marker,
+                    )
+                    .await?
+                }
+            };
+            versions.extend(page.into_iter().filter_map(|version| {
+                let path = self.store_path(version.location)?;
+                Some(PyObjectVersion {
+                    path: path.to_string(),
+                    version: version.version,
+                    is_latest: version.is_latest,
+                    is_delete_marker: version.is_delete_marker,
+                    last_modified: version.last_modified,
+                    size: version.size,
+                    e_tag: version.e_tag,
+                })
+            }));
+            match next_marker {
+                Some(next_marker) => marker = next_marker,
+                None => return Ok(versions),
+            }
+        }
+    }
+
+    /// Delete a single version of the object at `path`, leaving its other versions in place.
+    pub(crate) async fn delete_version(
+        &self,
+        path: &Path,
+        version: &str,
+    ) -> object_store::Result<()> {
+        let path = self.full_path(path);
+        let client = requests::client()?;
+        match self {
+            Self::S3(store) => {
+                requests::s3_request(
+                    store.inner().inner(),
+                    &client,
+                    Method::DELETE,
+                    &path,
+                    &[("versionId", version)],
+                    &[],
+                    Bytes::new(),
+                )
+                .await?;
+            }
+            Self::Gcs { store, bucket } => {
+                let mut url = gcs_objects_url(bucket);
+                url.path_segments_mut()
+                    .expect("GCS URL is a base")
+                    .push(path.as_ref());
+                url.query_pairs_mut().append_pair("generation", version);
+                let request = gcs_request(store.inner().inner(), Method::DELETE, &url).await?;
+                requests::send(&client, request, path.as_ref(), "GCS").await?;
+            }
+            Self::Azure {
+                store,
+                container_url,
+                account,
+            } => {
+                let mut url = container_url.clone();
+                url.path_segments_mut()
+                    .expect("Azure URL is a base")
+                    .pop_if_empty()
+                    .extend(path.parts());
+                url.query_pairs_mut().append_pair("versionid", version);
+                let request =
+                    azure_request(store.inner().inner(), account, Method::DELETE, &url).await?;
+                requests::send(&client, request, path.as_ref(), "MicrosoftAzure").await?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A version of an object, with its path within the bucket.
+struct ObjectVersion {
+    location: Path,
+    version: String,
+    is_latest: bool,
+    is_delete_marker: bool,
+    last_modified: DateTime<Utc>,
+    size: u64,
+    e_tag: Option<String>,
+}
+
+/// The query parameters that continue a listing from the end of the previous page.
+type Marker = Vec<(&'static str, String)>;
+
+/// A page of versions, and the marker of the next page if there is one.
+type VersionPage = (Vec<ObjectVersion>, Option<Marker>);
+
+/// Make a single `ListObjectVersions` request.
+///
+/// The page is continued from both the key and the version of the last entry of the previous
+/// page, as a key can have more versions than fit in a page.
+async fn list_s3_page(
+    s3: &AmazonS3,
+    client: &HttpClient,
+    prefix: Option<&str>,
+    marker: &Marker,
+) -> object_store::Result<VersionPage> {
+    let mut query = vec![("versions", "")];
+    if let Some(prefix) = prefix {
+        query.push(("prefix", prefix));
+    }
+    query.extend(marker.iter().map(|(name, value)| (*name, value.as_str())));
+    let body = requests::s3_request(
+        s3,
+        client,
+        Method::GET,
+        &Path::default(),
+        &query,
+        &[],
+        Bytes::new(),
+    )
+    .await?;
+
+    let versions = parse_s3_versions(&body)?;
+    let next_marker = if xml_element(&body, "IsTruncated") == Some("true") {
+        let key_marker = xml_element(&body, "NextKeyMarker").map(unescape_xml);
+        let version_id_marker = xml_element(&body, "NextVersionIdMarker").map(unescape_xml);
+        let (key_marker, version_id_marker) =
+            key_marker.zip(version_id_marker).ok_or_else(|| {
+                request_error(
+                    "S3",
+                    "Truncated ListObjectVersions response has no next marker",
+                )
+            })?;
+        Some(vec![
+            ("key-marker", key_marker),
+            ("version-id-marker", version_id_marker),
+        ])
+    } else {
+        None
+    };
+    Ok((versions, next_marker))
+}
+
+/// Parse the `Version` and `DeleteMarker` elements of a `ListObjectVersions` response, which are
+/// interleaved in the order of their keys.
+fn parse_s3_versions(body: &str) -> object_store::Result<Vec<ObjectVersion>> {
+    let mut out = vec![];
+    let mut rest = body;
+    loop {
+        let (start, is_delete_marker) = match (rest.find("<Version>"), rest.find("<DeleteMarker>"))
+        {
+            (Some(version), Some(marker)) if marker < version => (marker, true),
+            (Some(version), _) => (version, false),
+            (None, Some(marker)) => (marker, true),
+            (None, None) => return Ok(out),
+        };
+        let close = if is_delete_marker {
+            "</DeleteMarker>"
+        } else {
+            "</Version>"
+        };
+        let end = start
+            + rest[start..]
+                .find(close)
+                .ok_or_else(|| request_error("S3", "Unterminated element in versions"))?;
+        let entry = &rest[start..end];
+        rest = &rest[end + close.len()..];
+
+        let key = unescape_xml(
+            xml_element(entry, "Key").ok_or_else(|| request_error("S3", "Version has no Key"))?,
+        );
+        let last_modified = xml_element(entry, "LastModified")
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| request_error("S3", format!("Invalid LastModified for {}", key)))?;
+        out.push(ObjectVersion {
+            location: Path::parse(&key)?,
+            version: xml_element(entry, "VersionId")
+                .map(unescape_xml)
+                .unwrap_or_default(),
+            is_latest: xml_element(entry, "IsLatest") == Some("true"),
+            is_delete_marker,
+            last_modified,
+            // Delete markers have no size or ETag
+            size: xml_element(entry, "Size")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            e_tag: xml_element(entry, "ETag").map(unescape_xml),
+        });
+    }
+}
+
+/// The URL of the objects of `bucket` in the JSON API.
+pub(crate) fn gcs_objects_url(bucket: &str) -> Url {
+    let mut url = Url::parse(GCS_BASE_URL).expect("GCS base URL is valid");
+    url.path_segments_mut()
+        .expect("GCS URL is a base")
+        .extend(["storage", "v1", "b", bucket, "o"]);
+    url
+}
+
+async fn gcs_request(
+    gcs: &GoogleCloudStorage,
+    method: Method,
+    url: &Url,
+) -> object_store::Result<object_store::client::HttpRequest> {
+    let credential = gcs.credentials().get_credential().await?;
+    http::Request::builder()
+        .method(method)
+        .uri(url.as_str())
+        .header(
+            http::header::AUTHORIZATION,
+            format!("Bearer {}", credential.bearer),
+        )
+        .body(HttpRequestBody::empty())
+        .map_err(|err| request_error("GCS", err.to_string()))
+}
+
+/// Make a single request to list the objects of a bucket with `versions=true`.
+///
+/// GCS has no delete markers. Deleting the live version of an object makes it noncurrent, which
+/// is shown by it having a `timeDeleted`. The version of an object is its generation.
+async fn list_gcs_page(
+    gcs: &GoogleCloudStorage,
+    client: &HttpClient,
+    bucket: &str,
+    prefix: Option<&str>,
+    marker: &Marker,
+) -> object_store::Result<VersionPage> {
+    let mut url = gcs_objects_url(bucket);
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("versions", "true");
+        if let Some(prefix) = prefix {
+            query.append_pair("prefix", prefix);
+        }
+        query.extend_pairs(marker);
+    }
+    let request = gcs_request(gcs, Method::GET, &url).await?;
+    let body = requests::send(client, request, prefix.unwrap_or_default(), "GCS").await?;
+    let body: Value =
+        serde_json::from_str(&body).map_err(|err| request_error("GCS", err.to_string()))?;
+
+    let mut versions = vec![];
+    for item in body["items"].as_array().into_iter().flatten() {
+        let name = item["name"]
+            .as_str()
+            .ok_or_else(|| request_error("GCS", "Object has no name"))?;
+        let last_modified = item["updated"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| request_error("GCS", format!("Invalid updated time for {}", name)))?;
+        versions.push(ObjectVersion {
+            location: Path::parse(name)?,
+            version: item["generation"].as_str().unwrap_or_default().to_string(),
+            is_latest: item.get("timeDeleted").is_none(),
+            is_delete_marker: false,
+            last_modified,
+            // The JSON API returns sizes as strings
+            size: item["size"]
+                .as_str()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            e_tag: item["etag"].as_str().map(String::from),
+        });
+    }
+    let next_marker = body["nextPageToken"]
+        .as_str()
+        .map(|token| vec![("pageToken", token.to_string())]);
+    Ok((versions, next_marker))
+}
+
+async fn azure_request(
+    azure: &MicrosoftAzure,
+    account: &str,
+    method: Method,
+    url: &Url,
+) -> object_store::Result<object_store::client::HttpRequest> {
+    let credential = azure.credentials().get_credential().await?;
+    let mut request = http::Request::builder()
+        .method(method)
+        .uri(url.as_str())
+        .body(HttpRequestBody::empty())
+        .map_err(|err| request_error("MicrosoftAzure", err.to_string()))?;
+    AzureAuthorizer::new(&credential, account).authorize(&mut request);
+    Ok(request)
+}
+
+/// Make a single List Blobs request with `include=versions`.
+///
+/// Azure has no delete markers. Deleting the current version of a blob leaves its previous
+/// versions, none of which is the current version.
+async fn list_azure_page(
+    azure: &MicrosoftAzure,
+    client: &HttpClient,
+    container_url: &Url,
+    account: &str,
+    prefix: Option<&str>,
+    marker: &Marker,
+) -> object_store::Result<VersionPage> {
+    let mut url = container_url.clone();
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("restype", "container")
+            .append_pair("comp", "list")
+            .append_pair("include", "versions");
+        if let Some(prefix) = prefix {
+            query.append_pair("prefix", prefix);
+        }
+        query.extend_pairs(marker);
+    }
+    let request = azure_request(azure, account, Method::GET, &url).await?;
+    let body = requests::send(
+        client,
+        request,
+        prefix.unwrap_or_default(),
+        "MicrosoftAzure",
+    )
+    .await?;
+
+    let mut versions = vec![];
+    for blob in body.split("<Blob>").skip(1) {
+        let name = unescape_xml(
+            xml_element(blob, "Name")
+                .ok_or_else(|| request_error("MicrosoftAzure", "Blob is missing a Name"))?,
+        );
+        let properties = xml_element(blob, "Properties").unwrap_or_default();
+        // Directories of accounts with a hierarchical namespace aren't versioned
+        let Some(version) = xml_element(blob, "VersionId") else {
+            continue;
+        };
+        let last_modified = xml_element(properties, "Last-Modified")
+            .and_then(|s| DateTime::parse_from_rfc2822(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok_or_else(|| {
+                request_error(
+                    "MicrosoftAzure",
+                    format!("Invalid Last-Modified for blob {}", name),
+                )
+            })?;
+        versions.push(ObjectVersion {
+            location: Path::parse(&name)?,
+            version: unescape_xml(version),
+            is_latest: xml_element(blob, "IsCurrentVersion") == Some("true"),
+            is_delete_marker: false,
+            last_modified,
+            size: xml_element(properties, "Content-Length")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_default(),
+            e_tag: xml_element(properties, "Etag").map(unescape_xml),
+        });
+    }
+    let next_marker = xml_element(&body, "NextMarker")
+        .filter(|marker| !marker.is_empty())
+        .map(|marker| vec![("marker", unescape_xml(marker))]);
+    Ok((versions, next_marker))
+}
@@ -0,0 +1,192 @@
+//! Polling a prefix for changes, as an alternative to the notification services of each cloud.
+//!
+//! Each poll lists the prefix once and compares the listing with the one before it, so changes
+//! between two polls are coalesced: an object that is created and deleted again in between isn't
+//! reported at all.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::TryStreamExt;
+use indexmap::IndexMap;
+use object_store::path::Path;
+use object_store::{ObjectMeta, ObjectStore};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use pyo3::IntoPyObjectExt;
+use pyo3_object_store::{PyObjectStore, PyObjectStoreError, PyPath};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::list::PyObjectMeta;
+
+/// How an object changed between two polls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChangeKind {
+    Created,
+    Modified,
+    Deleted,
+}
+
+impl ChangeKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Created => "created",
+            Self::Modified => "modified",
+            Self::Deleted => "deleted",
+        }
+    }
+}
+
+/// A change to an object, exposed to Python as a `ChangeEvent` dict.
+pub(crate) struct PyChangeEvent {
+    change: ChangeKind,
+    /// The metadata of the object, or its last known metadata if it was deleted
+    meta: ObjectMeta,
+}
+
+impl PyChangeEvent {
+    pub(crate) fn new(change: ChangeKind, meta: ObjectMeta) -> Self {
+        Self { change, meta }
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyChangeEvent {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let mut dict = IndexMap::with_capacity(3);
+        dict.insert("change", self.change.as_str().into_bound_py_any(py)?);
+        dict.insert("path", self.meta.location.as_ref().into_bound_py_any(py)?);
+        dict.insert("meta", PyObjectMeta::new(self.meta).into_bound_py_any(py)?);
+        dict.into_pyobject(py)
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (store, prefix=None, poll_interval=Duration::from_secs(10), *, include_existing=false))]
+pub(crate) fn watch(
+    store: PyObjectStore,
+    prefix: Option<PyPath>,
+    poll_interval: Duration,
+    include_existing: bool,
+) -> PyWatchStream {
+    let watcher = Watcher {
+        store: store.into_inner(),
+        prefix: prefix.map(Path::from),
+        poll_interval,
+        include_existing,
+        known: None,
+        pending: VecDeque::new(),
+        last_poll: None,
+    };
+    PyWatchStream {
+        watcher: Arc::new(Mutex::new(watcher)),
+    }
+}
+
+/// The state of a watch between polls.
+struct Watcher {
+    store: Arc<dyn ObjectStore>,
+    prefix: Option<Path>,
+    poll_interval: Duration,
+    /// Whether the objects found by the first poll are reported as created
+    include_existing: bool,
+    /// The objects found by the last poll, or `None` before the first poll
+    known: Option<HashMap<Path, ObjectMeta>>,
+    /// Changes found by the last poll that haven't been returned yet
+    pending: VecDeque<PyChangeEvent>,
+    last_poll: Option<Instant>,
+}
+
+impl Watcher {
+    /// Wait for the next change, polling as often as `poll_interval` allows.
+    async fn next_event(&mut self) -> object_store::Result<PyChangeEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            if let Some(last_poll) = self.last_poll {
+                tokio::time::sleep_until(last_poll + self.poll_interval).await;
+            }
+            self.last_poll = Some(Instant::now());
+            self.poll().await?;
+        }
+    }
+
+    /// List the prefix, and queue the changes since the last poll, in order of path.
+    async fn poll(&mut self) -> object_store::Result<()> {
+        let mut current = HashMap::new();
+        let mut objects = self.store.list(self.prefix.as_ref());
+        while let Some(meta) = objects.try_next().await? {
+            current.insert(meta.location.clone(), meta);
+        }
+
+        let mut events = vec![];
+        match self.known.take() {
+            None if self.include_existing => events.extend(
+                current
+                    .values()
+                    .map(|meta| PyChangeEvent::new(ChangeKind::Created, meta.clone())),
+            ),
+            None => {}
+            Some(mut known) => {
+                for (path, meta) in current.iter() {
+                    let change = match known.remove(path) {
+                        None => ChangeKind::Created,
+                        Some(previous) if changed(&previous, meta) => ChangeKind::Modified,
+                        Some(_) => continue,
+                    };
+                    events.push(PyChangeEvent::new(change, meta.clone()));
+                }
+                // Whatever is left wasn't found by this poll
+                events.extend(
+                    known
+                        .into_values()
+                        .map(|meta| PyChangeEvent::new(ChangeKind::Deleted, meta)),
+                );
+            }
+        }
+        events.sort_by(|a, b| a.meta.location.cmp(&b.meta.location));
+        self.pending.extend(events);
+        self.known = Some(current);
+        Ok(())
+    }
+}
+
+/// Whether an object was overwritten between two listings.
+///
+/// The ETag identifies the contents where the store provides one. Otherwise, an object is
+/// considered changed when its size or modification time is.
+fn changed(previous: &ObjectMeta, current: &ObjectMeta) -> bool {
+    match (&previous.e_tag, &current.e_tag) {
+        (Some(previous_e_tag), Some(current_e_tag)) => {
+            previous_e_tag != current_e_tag || previous.size != current.size
+        }
+        _ => previous.size != current.size || previous.last_modified != current.last_modified,
+    }
+}
+
+/// An endless async iterator of the changes under a prefix.
+#[pyclass(name = "WatchStream", frozen)]
+pub(crate) struct PyWatchStream {
+    watcher: Arc<Mutex<Watcher>>,
+}
+
+#[pymethods]
+impl PyWatchStream {
+    fn __aiter__(slf: Py<Self>) -> Py<Self> {
+        slf
+    }
+
+    fn __anext__<'py>(&'py self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        let watcher = self.watcher.clone();
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let event = watcher.lock().await.next_event().await;
+            Ok(event.map_err(PyObjectStoreError::from)?)
+        })
+    }
+}
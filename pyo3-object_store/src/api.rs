@@ -1,9 +1,13 @@
 use pyo3::intern;
 use pyo3::prelude::*;
 
+use crate::credentials::{PyChainCredentialProvider, PyCredentialProviderHandle};
 use crate::error::*;
 use crate::{
-    from_url, PyAzureStore, PyGCSStore, PyHttpStore, PyLocalStore, PyMemoryStore, PyS3Store,
+    from_url, PyAzureStore, PyCacheStore, PyChaosStore, PyConsistentStore, PyDeadlineStore,
+    PyDictStore, PyEncryptedStore, PyGCSStore, PyHdfsStore, PyHttpStore, PyLocalStore,
+    PyMemoryStore, PyMirrorStore, PyNegativeCacheStore, PyPrefixStore, PyRangeCacheStore,
+    PyS3Store, PyThrottleStore,
 };
 
 /// Export the default Python API as a submodule named `store` within the given parent module
@@ -50,11 +54,27 @@ pub fn register_store_module(
 
     child_module.add_wrapped(wrap_pyfunction!(from_url))?;
     child_module.add_class::<PyAzureStore>()?;
+    child_module.add_class::<PyCacheStore>()?;
+    child_module.add_class::<PyChainCredentialProvider>()?;
+    child_module.add_class::<PyChaosStore>()?;
+    child_module.add_class::<PyConsistentStore>()?;
+    child_module.add_class::<PyCredentialProviderHandle>()?;
+    child_module.add_class::<PyDeadlineStore>()?;
+    child_module.add_class::<PyDictStore>()?;
+    child_module.add_class::<PyEncryptedStore>()?;
     child_module.add_class::<PyGCSStore>()?;
+    child_module.add_class::<PyHdfsStore>()?;
     child_module.add_class::<PyHttpStore>()?;
     child_module.add_class::<PyLocalStore>()?;
     child_module.add_class::<PyMemoryStore>()?;
+    child_module.add_class::<PyMirrorStore>()?;
+    child_module.add_class::<PyNegativeCacheStore>()?;
+    child_module.add_class::<PyPrefixStore>()?;
+    child_module.add_class::<PyRangeCacheStore>()?;
     child_module.add_class::<PyS3Store>()?;
+    #[cfg(feature = "sftp")]
+    child_module.add_class::<crate::PySFTPStore>()?;
+    child_module.add_class::<PyThrottleStore>()?;
 
     // Set the value of `__module__` correctly on each publicly exposed function or class
     let __module__ = intern!(py, "__module__");
@@ -64,9 +84,36 @@ pub fn register_store_module(
     child_module
         .getattr("AzureStore")?
         .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("CacheStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("ChainCredentialProvider")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("ChaosStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("ConsistentStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("CredentialProviderHandle")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("DeadlineStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("DictStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("EncryptedStore")?
+        .setattr(__module__, &full_module_string)?;
     child_module
         .getattr("GCSStore")?
         .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("HdfsStore")?
+        .setattr(__module__, &full_module_string)?;
     child_module
         .getattr("HTTPStore")?
         .setattr(__module__, &full_module_string)?;
@@ -76,9 +123,28 @@ pub fn register_store_module(
     child_module
         .getattr("MemoryStore")?
         .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("MirrorStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("NegativeCacheStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("PrefixStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("RangeCacheStore")?
+        .setattr(__module__, &full_module_string)?;
     child_module
         .getattr("S3Store")?
         .setattr(__module__, &full_module_string)?;
+    #[cfg(feature = "sftp")]
+    child_module
+        .getattr("SFTPStore")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("ThrottleStore")?
+        .setattr(__module__, &full_module_string)?;
 
     // Add the child module to the parent module
     parent_module.add_submodule(&child_module)?;
@@ -127,6 +193,9 @@ pub fn register_exceptions_module(
         "UnknownConfigurationKeyError",
         py.get_type::<UnknownConfigurationKeyError>(),
     )?;
+    child_module.add("TimeoutError", timeout_error_type(py))?;
+    child_module.add("ConnectError", py.get_type::<ConnectError>())?;
+    child_module.add("StreamTimeoutError", py.get_type::<StreamTimeoutError>())?;
 
     // Set the value of `__module__` correctly on each publicly exposed function or class
     let __module__ = intern!(py, "__module__");
@@ -166,6 +235,15 @@ pub fn register_exceptions_module(
     child_module
         .getattr("UnknownConfigurationKeyError")?
         .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("TimeoutError")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("ConnectError")?
+        .setattr(__module__, &full_module_string)?;
+    child_module
+        .getattr("StreamTimeoutError")?
+        .setattr(__module__, &full_module_string)?;
 
     // Add the child module to the parent module
     parent_module.add_submodule(&child_module)?;
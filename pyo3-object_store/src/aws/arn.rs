@@ -0,0 +1,138 @@
+//! S3 access point and Object Lambda access point ARNs, which can be used in place of a bucket
+//! name.
+//!
+//! <https://docs.aws.amazon.com/AmazonS3/latest/userguide/using-access-points.html>
+
+use object_store::aws::AmazonS3ConfigKey;
+
+/// The service of an access point ARN.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AccessPointService {
+    /// A standard S3 access point, `arn:aws:s3:...`
+    S3,
+    /// An S3 Object Lambda access point, `arn:aws:s3-object-lambda:...`
+    ObjectLambda,
+}
+
+impl AccessPointService {
+    /// The label of the access point's endpoint that identifies the service.
+    fn endpoint_label(&self) -> &'static str {
+        match self {
+            Self::S3 => "s3-accesspoint",
+            Self::ObjectLambda => "s3-object-lambda",
+        }
+    }
+}
+
+/// A parsed access point ARN, of the form
+/// `arn:<partition>:<service>:<region>:<account-id>:accesspoint/<name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct AccessPointArn<'a> {
+    partition: &'a str,
+    service: AccessPointService,
+    region: &'a str,
+    account_id: &'a str,
+    name: &'a str,
+}
+
+impl<'a> AccessPointArn<'a> {
+    /// Parse an access point ARN, returning `None` if `s` isn't one.
+    ///
+    /// The `accesspoint/<name>` resource can also be written `accesspoint:<name>`.
+    pub(crate) fn parse(s: &'a str) -> Option<Self> {
+        let mut parts = s.splitn(6, ':');
+        if parts.next()? != "arn" {
+            return None;
+        }
+        let partition = parts.next()?;
+        let service = match parts.next()? {
+            "s3" => AccessPointService::S3,
+            "s3-object-lambda" => AccessPointService::ObjectLambda,
+            _ => return None,
+        };
+        let region = parts.next()?;
+        let account_id = parts.next()?;
+        let resource = parts.next()?;
+        let name = resource
+            .strip_prefix("accesspoint/")
+            .or_else(|| resource.strip_prefix("accesspoint:"))?;
+        if partition.is_empty()
+            || region.is_empty()
+            || account_id.is_empty()
+            || name.is_empty()
+            || name.contains(['/', ':'])
+        {
+            return None;
+        }
+        Some(Self {
+            partition,
+            service,
+            region,
+            account_id,
+            name,
+        })
+    }
+
+    /// The virtual-hosted endpoint of the access point.
+    pub(crate) fn endpoint(&self) -> String {
+        let domain = match self.partition {
+            "aws-cn" => "amazonaws.com.cn",
+            _ => "amazonaws.com",
+        };
+        format!(
+            "https://{}-{}.{}.{}.{}",
+            self.name,
+            self.account_id,
+            self.service.endpoint_label(),
+            self.region,
+            domain
+        )
+    }
+
+    /// The configuration that sends requests to the access point.
+    ///
+    /// Each value is only a default: it doesn't override a value for the same key passed in
+    /// `config` or `kwargs`.
+    pub(crate) fn config(&self) -> Vec<(AmazonS3ConfigKey, String)> {
+        vec![
+            (AmazonS3ConfigKey::Region, self.region.to_string()),
+            (AmazonS3ConfigKey::Endpoint, self.endpoint()),
+            (
+                AmazonS3ConfigKey::VirtualHostedStyleRequest,
+                "true".to_string(),
+            ),
+        ]
+    }
+}
+
+/// Rewrite an `s3://<access point ARN>/<path>` URL, which isn't a valid URL because of the colons
+/// in the ARN, to the equivalent `https://` URL of the access point's endpoint.
+///
+/// Returns `None` if `url` isn't an access point ARN URL.
+pub(crate) fn rewrite_arn_url(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("s3://")
+        .or_else(|| url.strip_prefix("s3a://"))?;
+    // The name of the access point is followed by the path, if any
+    let (arn, path) = match rest.find("accesspoint") {
+        Some(idx) => {
+            let name_start = idx + "accesspoint".len() + 1;
+            match rest.get(name_start..)?.find('/') {
+                Some(path_start) => rest.split_at(name_start + path_start),
+                None => (rest, ""),
+            }
+        }
+        None => return None,
+    };
+    let arn = AccessPointArn::parse(arn)?;
+    Some(format!("{}{}", arn.endpoint(), path))
+}
+
+/// Whether `host` is the endpoint of an access point, in which case the whole path of a URL is
+/// the path within the store.
+pub(crate) fn is_access_point_host(host: &str) -> bool {
+    matches!(
+        host.split('.').nth(1),
+        Some("s3-accesspoint" | "s3-object-lambda")
+    )
+}
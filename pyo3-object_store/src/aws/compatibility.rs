@@ -0,0 +1,86 @@
+use object_store::aws::AmazonS3ConfigKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+use pyo3::types::PyString;
+
+/// A preset of configuration for an S3-compatible service that doesn't accept the defaults used
+/// for AWS S3.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PyS3Compatibility {
+    /// MinIO
+    Minio,
+    /// Ceph Object Gateway (RGW)
+    Ceph,
+    /// Any other S3-compatible service, using the most widely supported subset of the API
+    Generic,
+}
+
+impl PyS3Compatibility {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Minio => "minio",
+            Self::Ceph => "ceph",
+            Self::Generic => "generic",
+        }
+    }
+
+    /// The configuration applied by this preset.
+    ///
+    /// Each value is only a default: it doesn't override a value for the same key passed in
+    /// `config` or `kwargs`.
+    ///
+    /// The rest of the requests that object_store makes are already accepted by these services:
+    /// they're signed with SigV4 and the SHA-256 of the whole payload, never with a chunked
+    /// streaming signature, and carry no checksum headers unless `checksum_algorithm` is set.
+    pub(crate) fn config(&self) -> Vec<(AmazonS3ConfigKey, &'static str)> {
+        // Every preset uses path-style requests, since on-premises gateways are commonly
+        // deployed on a custom port without wildcard DNS for bucket subdomains.
+        let mut config = vec![(AmazonS3ConfigKey::VirtualHostedStyleRequest, "false")];
+        match self {
+            Self::Minio => {
+                config.push((AmazonS3ConfigKey::ConditionalPut, "etag"));
+            }
+            Self::Ceph => {}
+            Self::Generic => {
+                config.push((AmazonS3ConfigKey::DisableTagging, "true"));
+            }
+        }
+        config
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyS3Compatibility {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?.to_ascii_lowercase();
+        match s.as_str() {
+            "minio" => Ok(Self::Minio),
+            "ceph" => Ok(Self::Ceph),
+            "generic" => Ok(Self::Generic),
+            _ => Err(PyValueError::new_err(format!(
+                "Unexpected input for compatibility: {}. Expected one of 'minio', 'ceph', or 'generic'.",
+                s
+            ))),
+        }
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &PyS3Compatibility {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(PyString::new(py, self.as_str()))
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyS3Compatibility {
+    type Target = PyString;
+    type Output = Bound<'py, PyString>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        (&self).into_pyobject(py)
+    }
+}
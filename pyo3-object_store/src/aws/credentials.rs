@@ -2,15 +2,21 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
 use object_store::aws::AwsCredential;
-use object_store::CredentialProvider;
-use pyo3::exceptions::PyTypeError;
+use object_store::{ClientOptions, CredentialProvider};
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
 use pyo3::types::PyTuple;
 
+use crate::aws::providers::AwsBuiltinProvider;
 use crate::aws::store::PyAmazonS3Config;
-use crate::credentials::{is_awaitable, TemporaryToken, TokenCache};
+use crate::credentials::{
+    background_refresh, chain_providers, fetch_first, is_awaitable, PyCredentialProviderHandle,
+    TemporaryToken, TokenCache,
+};
 
 /// A wrapper around an [AwsCredential] that includes an optional expiry timestamp.
 struct PyAwsCredential {
@@ -55,11 +61,16 @@ impl<'py> FromPyObject<'py> for PyAwsCredential {
 // (so you don't need to access a mutex)
 #[derive(Debug)]
 pub struct PyAWSCredentialProvider {
-    /// The provided user callback to manage credential refresh
+    /// The provided user callback to manage credential refresh, the name of a built-in
+    /// provider, or a chain of providers
     user_callback: PyObject,
-    cache: TokenCache<Arc<AwsCredential>>,
+    /// Where credentials are fetched from, as given by `user_callback`
+    source: Arc<AwsCredentialSource>,
+    cache: Arc<TokenCache<Arc<AwsCredential>>>,
     /// An optional config passed down from the credential provider class
     config: Option<PyAmazonS3Config>,
+    /// The client options of the store, which built-in providers make requests with
+    client_options: Arc<ClientOptions>,
 }
 
 impl PyAWSCredentialProvider {
@@ -68,6 +79,17 @@ impl PyAWSCredentialProvider {
         self.config.as_ref()
     }
 
+    /// Make the requests of built-in providers with the client options of the store.
+    pub(crate) fn with_client_options(mut self, client_options: ClientOptions) -> Self {
+        self.client_options = Arc::new(client_options);
+        self
+    }
+
+    /// The handle returned by the store's `credential_provider` property.
+    pub(crate) fn handle(&self, py: Python) -> PyCredentialProviderHandle {
+        PyCredentialProviderHandle::new(self.user_callback.clone_ref(py), self.cache.clone())
+    }
+
     fn equals(&self, py: Python, other: &Self) -> PyResult<bool> {
         self.user_callback
             .call_method1(py, "__eq__", PyTuple::new(py, vec![&other.user_callback])?)?
@@ -80,8 +102,10 @@ impl Clone for PyAWSCredentialProvider {
         let cloned_callback = Python::with_gil(|py| self.user_callback.clone_ref(py));
         Self {
             user_callback: cloned_callback,
+            source: self.source.clone(),
             cache: self.cache.clone(),
             config: self.config.clone(),
+            client_options: self.client_options.clone(),
         }
     }
 }
@@ -94,15 +118,21 @@ impl PartialEq for PyAWSCredentialProvider {
 
 impl<'py> FromPyObject<'py> for PyAWSCredentialProvider {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
-            return Err(PyTypeError::new_err(
-                "Expected callable object for credential_provider.",
-            ));
+        let source = AwsCredentialSource::extract(ob)?;
+        if !matches!(source, AwsCredentialSource::Callback(_)) {
+            return Ok(Self {
+                user_callback: ob.clone().unbind(),
+                source: Arc::new(source),
+                cache: Default::default(),
+                config: None,
+                client_options: Default::default(),
+            });
         }
         let mut cache = TokenCache::default();
         if let Ok(refresh_threshold) = ob.getattr(intern!(ob.py(), "refresh_threshold")) {
             cache = cache.with_min_ttl(refresh_threshold.extract()?);
         }
+        cache = cache.with_background_refresh(background_refresh(ob)?);
 
         let config = if let Ok(config) = ob.getattr(intern!(ob.py(), "config")) {
             config.extract()?
@@ -113,8 +143,10 @@ impl<'py> FromPyObject<'py> for PyAWSCredentialProvider {
 
         Ok(Self {
             user_callback: ob.clone().unbind(),
-            cache,
+            source: Arc::new(source),
+            cache: Arc::new(cache),
             config,
+            client_options: Default::default(),
         })
     }
 }
@@ -139,6 +171,70 @@ impl<'py> IntoPyObject<'py> for &PyAWSCredentialProvider {
     }
 }
 
+/// Where a credential provider fetches credentials from.
+#[derive(Debug)]
+enum AwsCredentialSource {
+    /// A Python callback
+    Callback(PyObject),
+    /// A built-in provider, selected by name
+    Builtin(AwsBuiltinProvider),
+    /// Sources that are tried in order until one returns a credential
+    Chain(Vec<AwsCredentialSource>),
+}
+
+impl AwsCredentialSource {
+    fn extract(ob: &Bound<PyAny>) -> PyResult<Self> {
+        if let Some(providers) = chain_providers(ob)? {
+            let sources = providers
+                .iter()
+                .map(Self::extract)
+                .collect::<PyResult<_>>()?;
+            return Ok(Self::Chain(sources));
+        }
+        if let Ok(name) = ob.extract::<PyBackedStr>() {
+            let builtin = AwsBuiltinProvider::parse(&name).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Unknown credential_provider: {}. Expected one of 'profile', 'profile:<name>', 'sso', 'sso:<name>', 'imds', or 'web-identity'.",
+                    &*name
+                ))
+            })?;
+            return Ok(Self::Builtin(builtin));
+        }
+        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
+            return Err(PyTypeError::new_err(
+                "Expected callable object for credential_provider.",
+            ));
+        }
+        Ok(Self::Callback(ob.clone().unbind()))
+    }
+
+    fn fetch<'a>(
+        &'a self,
+        client_options: &'a ClientOptions,
+    ) -> BoxFuture<'a, object_store::Result<TemporaryToken<Arc<AwsCredential>>>> {
+        Box::pin(async move {
+            match self {
+                Self::Callback(user_callback) => {
+                    PyAWSCredentialProvider::fetch_token(user_callback).await
+                }
+                Self::Builtin(builtin) => builtin.fetch(client_options).await.map_err(|err| {
+                    object_store::Error::Unauthenticated {
+                        path: format!("AWS {} credential provider", builtin),
+                        source: Box::new(err),
+                    }
+                }),
+                Self::Chain(sources) => {
+                    let attempts = sources
+                        .iter()
+                        .map(|source| source.fetch(client_options))
+                        .collect::<Vec<_>>();
+                    fetch_first(attempts).await
+                }
+            }
+        })
+    }
+}
+
 /// Note: This is copied across providers at the moment
 enum PyCredentialProviderResult {
     Async(PyObject),
@@ -174,9 +270,9 @@ impl PyAWSCredentialProvider {
     /// Call the user-provided callback and extract to a token.
     ///
     /// This is separate from `fetch_token` below so that it can return a `PyResult`.
-    async fn call(&self) -> PyResult<PyAwsCredential> {
+    async fn call(user_callback: &PyObject) -> PyResult<PyAwsCredential> {
         let call_result = Python::with_gil(|py| {
-            self.user_callback
+            user_callback
                 .call0(py)?
                 .extract::<PyCredentialProviderResult>(py)
         })?;
@@ -184,14 +280,15 @@ impl PyAWSCredentialProvider {
     }
 
     /// Call the user-provided callback
-    async fn fetch_token(&self) -> object_store::Result<TemporaryToken<Arc<AwsCredential>>> {
-        let credential = self
-            .call()
-            .await
-            .map_err(|err| object_store::Error::Unauthenticated {
+    async fn fetch_token(
+        user_callback: &PyObject,
+    ) -> object_store::Result<TemporaryToken<Arc<AwsCredential>>> {
+        let credential = Self::call(user_callback).await.map_err(|err| {
+            object_store::Error::Unauthenticated {
                 path: "External AWS credential provider".to_string(),
                 source: Box::new(err),
-            })?;
+            }
+        })?;
 
         Ok(TemporaryToken {
             token: Arc::new(credential.credential),
@@ -205,6 +302,19 @@ impl CredentialProvider for PyAWSCredentialProvider {
     type Credential = AwsCredential;
 
     async fn get_credential(&self) -> object_store::Result<Arc<Self::Credential>> {
-        self.cache.get_or_insert_with(|| self.fetch_token()).await
+        let credential = self
+            .cache
+            .get_or_insert_with(|| self.source.fetch(&self.client_options))
+            .await?;
+        if self.cache.needs_refresh_task() {
+            let source = self.source.clone();
+            let client_options = self.client_options.clone();
+            self.cache.spawn_refresh(move || {
+                let source = source.clone();
+                let client_options = client_options.clone();
+                async move { source.fetch(&client_options).await }
+            });
+        }
+        Ok(credential)
     }
 }
@@ -1,4 +1,9 @@
+mod arn;
+mod compatibility;
 mod credentials;
+mod providers;
+mod sse;
 mod store;
 
+pub(crate) use arn::rewrite_arn_url;
 pub use store::PyS3Store;
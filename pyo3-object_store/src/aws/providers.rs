@@ -0,0 +1,740 @@
+//! Credential providers implemented in Rust, which can be selected by name instead of passing a
+//! Python callback as `credential_provider`.
+//!
+//! These cover common setups that `AmazonS3Builder::from_env` doesn't: explicit profiles of the
+//! shared config files (including role chaining and `credential_process`), SSO sessions cached
+//! by `aws sso login`, and forcing the use of IMDSv2 or a web identity token.
+//!
+//! <https://docs.aws.amazon.com/sdkref/latest/guide/file-format.html>
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use chrono::{DateTime, TimeZone, Utc};
+use http::Method;
+use object_store::aws::{AwsAuthorizer, AwsCredential};
+use object_store::client::{HttpClient, HttpConnector, HttpRequestBody, ReqwestConnector};
+use object_store::ClientOptions;
+use serde::Deserialize;
+use url::Url;
+
+use crate::credentials::TemporaryToken;
+
+type Result<T, E = object_store::Error> = std::result::Result<T, E>;
+
+const DEFAULT_IMDS_ENDPOINT: &str = "http://169.254.169.254";
+
+/// The lifetime requested for IMDSv2 session tokens
+const IMDS_TOKEN_TTL_SECONDS: u32 = 21600;
+
+/// The region of STS requests when neither the profile nor the environment sets one
+const DEFAULT_STS_REGION: &str = "us-east-1";
+
+/// The maximum number of `source_profile` links followed when chaining roles
+const MAX_PROFILE_DEPTH: usize = 8;
+
+/// A credential provider selected by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AwsBuiltinProvider {
+    /// `"profile"` or `"profile:<name>"`: a profile of the shared config and credentials files.
+    /// `None` is the profile named by `AWS_PROFILE`, or the default profile.
+    Profile(Option<String>),
+    /// `"sso"` or `"sso:<name>"`: the SSO session of a profile, which must have been started with
+    /// `aws sso login`.
+    Sso(Option<String>),
+    /// `"imds"`: the role of the EC2 instance, from the instance metadata service with IMDSv2.
+    Imds,
+    /// `"web-identity"`: a web identity token exchanged for the role in `AWS_ROLE_ARN`.
+    WebIdentity,
+}
+
+impl AwsBuiltinProvider {
+    /// Parse the name of a provider, returning `None` if `s` isn't one.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let (kind, profile) = match s.split_once(':') {
+            Some((kind, profile)) => (kind, Some(profile.to_string())),
+            None => (s, None),
+        };
+        match (kind, profile) {
+            ("profile", profile) => Some(Self::Profile(profile)),
+            ("sso", profile) => Some(Self::Sso(profile)),
+            ("imds", None) => Some(Self::Imds),
+            ("web-identity", None) => Some(Self::WebIdentity),
+            _ => None,
+        }
+    }
+
+    /// Fetch a credential, making requests with the client options of the store.
+    pub(crate) async fn fetch(
+        &self,
+        client_options: &ClientOptions,
+    ) -> Result<TemporaryToken<Arc<AwsCredential>>> {
+        let client = ReqwestConnector::default().connect(client_options)?;
+        let credential = match self {
+            Self::Profile(name) => {
+                let files = ProfileFiles::load()?;
+                let name = profile_name(name.as_deref());
+                let credential = files.credential(&client, &name, 0).await?;
+                credential
+            }
+            Self::Sso(name) => {
+                let files = ProfileFiles::load()?;
+                let name = profile_name(name.as_deref());
+                let profile = files.profile(&name)?;
+                if !is_sso_profile(profile) {
+                    return Err(error(format!("Profile {name} is not an SSO profile")));
+                }
+                let credential = sso_credential(&client, &files, profile).await?;
+                credential
+            }
+            Self::Imds => {
+                // IMDS is only served over plain HTTP
+                let client = ReqwestConnector::default()
+                    .connect(&client_options.clone().with_allow_http(true))?;
+                imds_credential(&client).await?
+            }
+            Self::WebIdentity => {
+                let token_file = env_var("AWS_WEB_IDENTITY_TOKEN_FILE")
+                    .ok_or_else(|| error("AWS_WEB_IDENTITY_TOKEN_FILE is not set"))?;
+                let role_arn =
+                    env_var("AWS_ROLE_ARN").ok_or_else(|| error("AWS_ROLE_ARN is not set"))?;
+                let session_name = env_var("AWS_ROLE_SESSION_NAME");
+                web_identity_credential(
+                    &client,
+                    &env_region(),
+                    &token_file,
+                    &role_arn,
+                    session_name.as_deref(),
+                )
+                .await?
+            }
+        };
+        Ok(credential.into_token())
+    }
+}
+
+impl fmt::Display for AwsBuiltinProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Profile(None) => write!(f, "profile"),
+            Self::Profile(Some(name)) => write!(f, "profile:{name}"),
+            Self::Sso(None) => write!(f, "sso"),
+            Self::Sso(Some(name)) => write!(f, "sso:{name}"),
+            Self::Imds => write!(f, "imds"),
+            Self::WebIdentity => write!(f, "web-identity"),
+        }
+    }
+}
+
+fn error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "S3",
+        source: msg.into().into(),
+    }
+}
+
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+fn env_region() -> String {
+    env_var("AWS_REGION")
+        .or_else(|| env_var("AWS_DEFAULT_REGION"))
+        .unwrap_or_else(|| DEFAULT_STS_REGION.to_string())
+}
+
+fn profile_name(name: Option<&str>) -> String {
+    name.map(String::from)
+        .or_else(|| env_var("AWS_PROFILE"))
+        .unwrap_or_else(|| "default".to_string())
+}
+
+fn home_dir() -> Option<PathBuf> {
+    env_var("HOME")
+        .or_else(|| env_var("USERPROFILE"))
+        .map(PathBuf::from)
+}
+
+/// A credential fetched by a provider, with its expiry if it's temporary.
+struct Credential {
+    credential: AwsCredential,
+    expiry: Option<DateTime<Utc>>,
+}
+
+impl Credential {
+    fn into_token(self) -> TemporaryToken<Arc<AwsCredential>> {
+        TemporaryToken {
+            token: Arc::new(self.credential),
+            expiry: self.expiry,
+        }
+    }
+}
+
+/// The sections of an INI file, keyed by name, e.g. `default`, `profile dev`, or
+/// `sso-session my-sso`.
+type Sections = HashMap<String, HashMap<String, String>>;
+
+/// Parse the INI format of the shared config and credentials files.
+fn parse_ini(contents: &str) -> Sections {
+    let mut sections = Sections::new();
+    let mut current = None;
+    for line in contents.lines() {
+        // Indented lines are nested properties, like those of `s3 =`, which aren't used here
+        if line.starts_with([' ', '\t']) {
+            continue;
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+            sections.entry(name.clone()).or_default();
+            current = Some(name);
+        } else if let (Some(section), Some((key, value))) = (&current, line.split_once('=')) {
+            sections
+                .entry(section.clone())
+                .or_default()
+                .insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+    sections
+}
+
+/// Read an INI file, which is treated as empty if it doesn't exist.
+fn read_ini(env_key: &str, default_name: &str) -> Result<Sections> {
+    let Some(path) = env_var(env_key)
+        .map(PathBuf::from)
+        .or_else(|| home_dir().map(|home| home.join(".aws").join(default_name)))
+    else {
+        return Ok(Sections::new());
+    };
+    match std::fs::read_to_string(&path) {
+        Ok(contents) => Ok(parse_ini(&contents)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Sections::new()),
+        Err(err) => Err(error(format!("Failed to read {}: {err}", path.display()))),
+    }
+}
+
+/// The shared config (`~/.aws/config`) and credentials (`~/.aws/credentials`) files.
+struct ProfileFiles {
+    config: Sections,
+    credentials: Sections,
+    /// Profiles merged from both files, with the credentials file taking precedence
+    profiles: Sections,
+}
+
+impl ProfileFiles {
+    fn load() -> Result<Self> {
+        let config = read_ini("AWS_CONFIG_FILE", "config")?;
+        let credentials = read_ini("AWS_SHARED_CREDENTIALS_FILE", "credentials")?;
+
+        let mut profiles = Sections::new();
+        for (section, properties) in &config {
+            let name = match section.strip_prefix("profile ") {
+                Some(name) => name,
+                // The default profile is the only one that can omit the `profile` prefix
+                None if section == "default" => section,
+                None => continue,
+            };
+            profiles
+                .entry(name.to_string())
+                .or_default()
+                .extend(properties.clone());
+        }
+        for (name, properties) in &credentials {
+            profiles
+                .entry(name.clone())
+                .or_default()
+                .extend(properties.clone());
+        }
+        Ok(Self {
+            config,
+            credentials,
+            profiles,
+        })
+    }
+
+    fn profile(&self, name: &str) -> Result<&HashMap<String, String>> {
+        self.profiles.get(name).ok_or_else(|| {
+            if self.config.is_empty() && self.credentials.is_empty() {
+                error(format!(
+                    "Profile {name} not found: no AWS config or credentials file"
+                ))
+            } else {
+                error(format!("Profile {name} not found"))
+            }
+        })
+    }
+
+    /// Resolve the credential of a profile. `depth` is the number of `source_profile` links
+    /// followed so far.
+    fn credential<'a>(
+        &'a self,
+        client: &'a HttpClient,
+        name: &'a str,
+        depth: usize,
+    ) -> futures::future::BoxFuture<'a, Result<Credential>> {
+        Box::pin(async move {
+            if depth > MAX_PROFILE_DEPTH {
+                return Err(error(format!(
+                    "Too many source_profile links while resolving profile {name}"
+                )));
+            }
+            let profile = self.profile(name)?;
+            let region = profile.get("region").cloned().unwrap_or_else(env_region);
+
+            if let Some(role_arn) = profile.get("role_arn") {
+                let session_name = profile.get("role_session_name").map(String::as_str);
+                if let Some(token_file) = profile.get("web_identity_token_file") {
+                    return web_identity_credential(
+                        client,
+                        &region,
+                        token_file,
+                        role_arn,
+                        session_name,
+                    )
+                    .await;
+                }
+                let source = if let Some(source_profile) = profile.get("source_profile") {
+                    // A profile can use its own static keys as the source of its role
+                    if source_profile == name {
+                        static_credential(profile).ok_or_else(|| {
+                            error(format!("Profile {name} has no static credentials"))
+                        })?
+                    } else {
+                        self.credential(client, source_profile, depth + 1).await?
+                    }
+                } else if let Some(credential_source) = profile.get("credential_source") {
+                    match credential_source.as_str() {
+                        "Ec2InstanceMetadata" => imds_credential(client).await?,
+                        "Environment" => env_credential()?,
+                        other => {
+                            return Err(error(format!("Unsupported credential_source: {other}")))
+                        }
+                    }
+                } else {
+                    return Err(error(format!(
+                        "Profile {name} sets role_arn without source_profile or credential_source"
+                    )));
+                };
+                return assume_role(
+                    client,
+                    &region,
+                    &source.credential,
+                    role_arn,
+                    session_name,
+                    profile.get("external_id").map(String::as_str),
+                )
+                .await;
+            }
+
+            if is_sso_profile(profile) {
+                return sso_credential(client, self, profile).await;
+            }
+            if let Some(command) = profile.get("credential_process") {
+                return process_credential(command).await;
+            }
+            static_credential(profile)
+                .ok_or_else(|| error(format!("Profile {name} has no credentials")))
+        })
+    }
+
+    fn sso_session(&self, name: &str) -> Result<&HashMap<String, String>> {
+        self.config
+            .get(&format!("sso-session {name}"))
+            .ok_or_else(|| error(format!("SSO session {name} not found")))
+    }
+}
+
+fn is_sso_profile(profile: &HashMap<String, String>) -> bool {
+    profile.contains_key("sso_session") || profile.contains_key("sso_start_url")
+}
+
+fn static_credential(properties: &HashMap<String, String>) -> Option<Credential> {
+    Some(Credential {
+        credential: AwsCredential {
+            key_id: properties.get("aws_access_key_id")?.clone(),
+            secret_key: properties.get("aws_secret_access_key")?.clone(),
+            token: properties.get("aws_session_token").cloned(),
+        },
+        expiry: None,
+    })
+}
+
+fn env_credential() -> Result<Credential> {
+    Ok(Credential {
+        credential: AwsCredential {
+            key_id: env_var("AWS_ACCESS_KEY_ID")
+                .ok_or_else(|| error("AWS_ACCESS_KEY_ID is not set"))?,
+            secret_key: env_var("AWS_SECRET_ACCESS_KEY")
+                .ok_or_else(|| error("AWS_SECRET_ACCESS_KEY is not set"))?,
+            token: env_var("AWS_SESSION_TOKEN"),
+        },
+        expiry: None,
+    })
+}
+
+/// Send `request`, returning the body of a successful response.
+async fn send(client: &HttpClient, request: http::Request<HttpRequestBody>) -> Result<String> {
+    let uri = request.uri().clone();
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|err| error(format!("Request to {uri} failed: {err}")))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .bytes()
+        .await
+        .map_err(|err| error(format!("Request to {uri} failed: {err}")))?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if !status.is_success() {
+        return Err(error(format!(
+            "Request to {uri} failed with status {status}: {body}"
+        )));
+    }
+    Ok(body)
+}
+
+/// The STS endpoint of `region`, which can be overridden with `AWS_ENDPOINT_URL_STS`.
+fn sts_url(region: &str) -> Result<Url> {
+    let endpoint = env_var("AWS_ENDPOINT_URL_STS")
+        .unwrap_or_else(|| format!("https://sts.{region}.amazonaws.com"));
+    Url::parse(&endpoint).map_err(|err| error(format!("Invalid STS endpoint {endpoint}: {err}")))
+}
+
+fn default_session_name() -> String {
+    format!("obstore-{}", Utc::now().timestamp_millis())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AssumeRoleResponse {
+    #[serde(alias = "AssumeRoleWithWebIdentityResult")]
+    assume_role_result: AssumeRoleResult,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct AssumeRoleResult {
+    credentials: StsCredentials,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct StsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: String,
+    expiration: String,
+}
+
+/// Parse an RFC 3339 timestamp. Older versions of the AWS CLI write `UTC` instead of `Z`.
+fn parse_timestamp(s: &str) -> Result<DateTime<Utc>> {
+    let normalized = match s.strip_suffix("UTC") {
+        Some(s) => format!("{s}Z"),
+        None => s.to_string(),
+    };
+    DateTime::parse_from_rfc3339(&normalized)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|err| error(format!("Invalid timestamp {s}: {err}")))
+}
+
+fn parse_sts_response(body: &str) -> Result<Credential> {
+    let response: AssumeRoleResponse = quick_xml::de::from_str(body)
+        .map_err(|err| error(format!("Invalid STS response: {err}")))?;
+    let credentials = response.assume_role_result.credentials;
+    Ok(Credential {
+        credential: AwsCredential {
+            key_id: credentials.access_key_id,
+            secret_key: credentials.secret_access_key,
+            token: Some(credentials.session_token),
+        },
+        expiry: Some(parse_timestamp(&credentials.expiration)?),
+    })
+}
+
+/// Assume `role_arn` with the `source` credential.
+async fn assume_role(
+    client: &HttpClient,
+    region: &str,
+    source: &AwsCredential,
+    role_arn: &str,
+    session_name: Option<&str>,
+    external_id: Option<&str>,
+) -> Result<Credential> {
+    let mut url = sts_url(region)?;
+    {
+        let mut query = url.query_pairs_mut();
+        query
+            .append_pair("Action", "AssumeRole")
+            .append_pair("Version", "2011-06-15")
+            .append_pair("RoleArn", role_arn)
+            .append_pair(
+                "RoleSessionName",
+                session_name.unwrap_or(&default_session_name()),
+            );
+        if let Some(external_id) = external_id {
+            query.append_pair("ExternalId", external_id);
+        }
+    }
+    let mut request = http::Request::builder()
+        .method(Method::GET)
+        .uri(url.as_str())
+        .body(HttpRequestBody::empty())
+        .map_err(|err| error(err.to_string()))?;
+    AwsAuthorizer::new(source, "sts", region).authorize(&mut request, None);
+    parse_sts_response(&send(client, request).await?)
+}
+
+/// Exchange the web identity token in `token_file` for a credential of `role_arn`.
+async fn web_identity_credential(
+    client: &HttpClient,
+    region: &str,
+    token_file: &str,
+    role_arn: &str,
+    session_name: Option<&str>,
+) -> Result<Credential> {
+    let token = std::fs::read_to_string(token_file)
+        .map_err(|err| error(format!("Failed to read {token_file}: {err}")))?;
+    // The token is sent in the body rather than the query string, which can end up in logs
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("Action", "AssumeRoleWithWebIdentity")
+        .append_pair("Version", "2011-06-15")
+        .append_pair("RoleArn", role_arn)
+        .append_pair(
+            "RoleSessionName",
+            session_name.unwrap_or(&default_session_name()),
+        )
+        .append_pair("WebIdentityToken", token.trim())
+        .finish();
+    let request = http::Request::builder()
+        .method(Method::POST)
+        .uri(sts_url(region)?.as_str())
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .body(HttpRequestBody::from(body))
+        .map_err(|err| error(err.to_string()))?;
+    parse_sts_response(&send(client, request).await?)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ImdsCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    token: String,
+    expiration: String,
+}
+
+/// Fetch the credential of the instance's role from the instance metadata service, using a
+/// session token as required by IMDSv2.
+async fn imds_credential(client: &HttpClient) -> Result<Credential> {
+    let endpoint = env_var("AWS_EC2_METADATA_SERVICE_ENDPOINT")
+        .unwrap_or_else(|| DEFAULT_IMDS_ENDPOINT.to_string());
+    let endpoint = endpoint.trim_end_matches('/');
+
+    let request = http::Request::builder()
+        .method(Method::PUT)
+        .uri(format!("{endpoint}/latest/api/token"))
+        .header(
+            "X-aws-ec2-metadata-token-ttl-seconds",
+            IMDS_TOKEN_TTL_SECONDS,
+        )
+        .body(HttpRequestBody::empty())
+        .map_err(|err| error(err.to_string()))?;
+    let token = send(client, request).await?;
+
+    let get = |path: String| {
+        http::Request::builder()
+            .method(Method::GET)
+            .uri(format!("{endpoint}{path}"))
+            .header("X-aws-ec2-metadata-token", &token)
+            .body(HttpRequestBody::empty())
+            .map_err(|err| error(err.to_string()))
+    };
+    let roles = send(
+        client,
+        get("/latest/meta-data/iam/security-credentials/".to_string())?,
+    )
+    .await?;
+    let role = roles
+        .lines()
+        .next()
+        .filter(|role| !role.is_empty())
+        .ok_or_else(|| error("The instance has no IAM role"))?;
+    let body = send(
+        client,
+        get(format!("/latest/meta-data/iam/security-credentials/{role}"))?,
+    )
+    .await?;
+    let credentials: ImdsCredentials = serde_json::from_str(&body)
+        .map_err(|err| error(format!("Invalid instance metadata response: {err}")))?;
+    Ok(Credential {
+        credential: AwsCredential {
+            key_id: credentials.access_key_id,
+            secret_key: credentials.secret_access_key,
+            token: Some(credentials.token),
+        },
+        expiry: Some(parse_timestamp(&credentials.expiration)?),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoCachedToken {
+    access_token: String,
+    expires_at: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoResponse {
+    role_credentials: SsoRoleCredentials,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SsoRoleCredentials {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: String,
+    /// Milliseconds since the epoch
+    expiration: i64,
+}
+
+/// Exchange the token cached by `aws sso login` for a credential of the profile's role.
+async fn sso_credential(
+    client: &HttpClient,
+    files: &ProfileFiles,
+    profile: &HashMap<String, String>,
+) -> Result<Credential> {
+    let property = |key: &str| {
+        profile
+            .get(key)
+            .ok_or_else(|| error(format!("SSO profile is missing {key}")))
+    };
+    // The token is cached under the name of the session, or the start URL for profiles that
+    // predate `sso-session` sections
+    let (cache_key, region) = match profile.get("sso_session") {
+        Some(session_name) => {
+            let session = files.sso_session(session_name)?;
+            let region = session.get("sso_region").ok_or_else(|| {
+                error(format!("SSO session {session_name} is missing sso_region"))
+            })?;
+            (session_name, region)
+        }
+        None => (property("sso_start_url")?, property("sso_region")?),
+    };
+    let account_id = property("sso_account_id")?;
+    let role_name = property("sso_role_name")?;
+
+    let digest = ring::digest::digest(
+        &ring::digest::SHA1_FOR_LEGACY_USE_ONLY,
+        cache_key.as_bytes(),
+    );
+    let file_name = digest
+        .as_ref()
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    let path = home_dir()
+        .ok_or_else(|| error("Cannot find the home directory"))?
+        .join(".aws")
+        .join("sso")
+        .join("cache")
+        .join(format!("{file_name}.json"));
+    let login_hint = "run `aws sso login` to start a new session";
+    let contents = std::fs::read_to_string(&path).map_err(|err| {
+        error(format!(
+            "Failed to read cached SSO token {}: {err}; {login_hint}",
+            path.display()
+        ))
+    })?;
+    let cached: SsoCachedToken = serde_json::from_str(&contents)
+        .map_err(|err| error(format!("Invalid cached SSO token: {err}")))?;
+    if parse_timestamp(&cached.expires_at)? <= Utc::now() {
+        return Err(error(format!("The SSO session has expired; {login_hint}")));
+    }
+
+    let mut url = Url::parse(&format!("https://portal.sso.{region}.amazonaws.com"))
+        .map_err(|err| error(format!("Invalid SSO region {region}: {err}")))?;
+    url.set_path("/federation/credentials");
+    url.query_pairs_mut()
+        .append_pair("account_id", account_id)
+        .append_pair("role_name", role_name);
+    let request = http::Request::builder()
+        .method(Method::GET)
+        .uri(url.as_str())
+        .header("x-amz-sso_bearer_token", &cached.access_token)
+        .body(HttpRequestBody::empty())
+        .map_err(|err| error(err.to_string()))?;
+    let body = send(client, request)
+        .await
+        .map_err(|err| error(format!("{err}; if the session was revoked, {login_hint}")))?;
+    let response: SsoResponse =
+        serde_json::from_str(&body).map_err(|err| error(format!("Invalid SSO response: {err}")))?;
+    let credentials = response.role_credentials;
+    Ok(Credential {
+        credential: AwsCredential {
+            key_id: credentials.access_key_id,
+            secret_key: credentials.secret_access_key,
+            token: Some(credentials.session_token),
+        },
+        expiry: Utc.timestamp_millis_opt(credentials.expiration).single(),
+    })
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ProcessOutput {
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    expiration: Option<String>,
+}
+
+/// Run the `credential_process` of a profile, which prints the credential as JSON.
+async fn process_credential(command: &str) -> Result<Credential> {
+    let command = command.to_string();
+    let output = tokio::task::spawn_blocking(move || {
+        #[cfg(windows)]
+        let output = std::process::Command::new("cmd")
+            .args(["/C", &command])
+            .output();
+        #[cfg(not(windows))]
+        let output = std::process::Command::new("sh")
+            .args(["-c", &command])
+            .output();
+        output
+    })
+    .await
+    .map_err(|err| error(err.to_string()))?
+    .map_err(|err| error(format!("Failed to run credential_process: {err}")))?;
+    if !output.status.success() {
+        return Err(error(format!(
+            "credential_process failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let credentials: ProcessOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|err| error(format!("Invalid credential_process output: {err}")))?;
+    Ok(Credential {
+        credential: AwsCredential {
+            key_id: credentials.access_key_id,
+            secret_key: credentials.secret_access_key,
+            token: credentials.session_token,
+        },
+        expiry: credentials
+            .expiration
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+    })
+}
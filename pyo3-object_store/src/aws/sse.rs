@@ -0,0 +1,89 @@
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use md5::{Digest, Md5};
+use object_store::aws::AmazonS3ConfigKey;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::{PyBackedBytes, PyBackedStr};
+
+/// The length in bytes of an SSE-C key: S3 only accepts AES-256 keys.
+const KEY_LEN: usize = 32;
+
+/// The config key of the server-side encryption type.
+///
+/// The encryption config keys are variants of `S3EncryptionConfigKey`, which `object_store`
+/// doesn't export, so they're parsed from their names.
+const SSE_TYPE_CONFIG_KEY: &str = "aws_server_side_encryption";
+
+/// The config key of the base64-encoded SSE-C key.
+pub(crate) const SSE_CUSTOMER_KEY_CONFIG_KEY: &str = "aws_sse_customer_key_base64";
+
+fn encryption_config_key(name: &str) -> AmazonS3ConfigKey {
+    name.parse()
+        .expect("encryption config keys are valid AmazonS3ConfigKeys")
+}
+
+/// A customer-provided key for S3 server-side encryption (SSE-C).
+///
+/// Accepts either the 32 raw key bytes or the key encoded as base64.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PySseCustomerKey([u8; KEY_LEN]);
+
+impl PySseCustomerKey {
+    /// The key encoded as base64, as expected by `aws_sse_customer_key_base64`.
+    pub(crate) fn to_base64(&self) -> String {
+        BASE64_STANDARD.encode(self.0)
+    }
+
+    /// The base64-encoded MD5 digest of the key, as sent in the
+    /// `x-amz-server-side-encryption-customer-key-MD5` header.
+    pub(crate) fn md5_base64(&self) -> String {
+        BASE64_STANDARD.encode(Md5::digest(self.0))
+    }
+
+    /// Check a user-provided MD5 digest against the key.
+    ///
+    /// S3 uses the digest to check that the key wasn't corrupted in transit, so a digest that
+    /// doesn't match would make every request fail.
+    pub(crate) fn validate_md5(&self, key_md5: &str) -> PyResult<()> {
+        if key_md5 != self.md5_base64() {
+            return Err(PyValueError::new_err(
+                "key_md5 is not the base64-encoded MD5 digest of the SSE-C key.",
+            ));
+        }
+        Ok(())
+    }
+
+    /// The configuration that makes a store use this key for every request.
+    pub(crate) fn config(&self) -> Vec<(AmazonS3ConfigKey, String)> {
+        vec![
+            (
+                encryption_config_key(SSE_TYPE_CONFIG_KEY),
+                "sse-c".to_string(),
+            ),
+            (
+                encryption_config_key(SSE_CUSTOMER_KEY_CONFIG_KEY),
+                self.to_base64(),
+            ),
+        ]
+    }
+}
+
+impl<'py> FromPyObject<'py> for PySseCustomerKey {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let key = if let Ok(s) = ob.extract::<PyBackedStr>() {
+            BASE64_STANDARD
+                .decode(s.as_bytes())
+                .map_err(|err| PyValueError::new_err(format!("Invalid base64 SSE-C key: {err}")))?
+        } else {
+            ob.extract::<PyBackedBytes>()?.to_vec()
+        };
+        let key: [u8; KEY_LEN] = key.try_into().map_err(|key: Vec<u8>| {
+            PyValueError::new_err(format!(
+                "SSE-C key must be {KEY_LEN} bytes long, got {} bytes.",
+                key.len()
+            ))
+        })?;
+        Ok(Self(key))
+    }
+}
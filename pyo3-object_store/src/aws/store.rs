@@ -3,20 +3,33 @@ use std::sync::Arc;
 
 use itertools::Itertools;
 use object_store::aws::{AmazonS3, AmazonS3Builder, AmazonS3ConfigKey};
+use object_store::client::{HttpClient, HttpConnector};
+use object_store::path::Path;
 use object_store::ObjectStoreScheme;
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyDict, PyString, PyTuple, PyType};
-use pyo3::{intern, IntoPyObjectExt};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
 use url::Url;
 
+use crate::aws::arn::{is_access_point_host, AccessPointArn};
+use crate::aws::compatibility::PyS3Compatibility;
 use crate::aws::credentials::PyAWSCredentialProvider;
-use crate::client::PyClientOptions;
+use crate::aws::sse::{PySseCustomerKey, SSE_CUSTOMER_KEY_CONFIG_KEY};
+use crate::client::{client_options_from_builder, PyClientOptions};
 use crate::config::PyConfigValue;
+use crate::connector::StoreConnector;
+use crate::credentials::PyCredentialProviderHandle;
 use crate::error::{GenericError, ParseUrlError, PyObjectStoreError, PyObjectStoreResult};
+use crate::headers::RequestHeaders;
+use crate::limit::{LimitedStore, PyLimitConfig};
 use crate::path::PyPath;
+use crate::pool::ConnectionPool;
 use crate::prefix::MaybePrefixedStore;
+use crate::resolver::PyResolver;
 use crate::retry::PyRetryConfig;
+use crate::tags::{PyRequestTags, TagStyle};
+use crate::token::{encode_token, store_from_token};
 use crate::PyUrl;
 
 #[derive(Debug, Clone, PartialEq)]
@@ -25,7 +38,12 @@ struct S3Config {
     config: PyAmazonS3Config,
     client_options: Option<PyClientOptions>,
     retry_config: Option<PyRetryConfig>,
+    limit_config: Option<PyLimitConfig>,
+    compatibility: Option<PyS3Compatibility>,
     credential_provider: Option<PyAWSCredentialProvider>,
+    resolver: Option<PyResolver>,
+    request_tags: Option<PyRequestTags>,
+    extra_headers: Option<RequestHeaders>,
 }
 
 impl S3Config {
@@ -37,6 +55,28 @@ impl S3Config {
             .as_ref()
     }
 
+    /// The config without credentials, encryption keys or authorization headers, for `to_token`.
+    fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config.config.0.retain(|key, _| {
+            !(matches!(
+                key.0,
+                AmazonS3ConfigKey::AccessKeyId
+                    | AmazonS3ConfigKey::SecretAccessKey
+                    | AmazonS3ConfigKey::Token
+            ) || key.0.as_ref() == SSE_CUSTOMER_KEY_CONFIG_KEY)
+        });
+        config.client_options = config
+            .client_options
+            .as_ref()
+            .map(PyClientOptions::without_secrets);
+        config.extra_headers = config
+            .extra_headers
+            .as_ref()
+            .map(RequestHeaders::without_secrets);
+        config
+    }
+
     fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
         let args = PyTuple::empty(py).into_py_any(py)?;
         let kwargs = PyDict::new(py);
@@ -51,9 +91,24 @@ impl S3Config {
         if let Some(retry_config) = &self.retry_config {
             kwargs.set_item(intern!(py, "retry_config"), retry_config)?;
         }
+        if let Some(limit_config) = &self.limit_config {
+            kwargs.set_item(intern!(py, "limit_config"), limit_config)?;
+        }
+        if let Some(compatibility) = &self.compatibility {
+            kwargs.set_item(intern!(py, "compatibility"), compatibility)?;
+        }
         if let Some(credential_provider) = &self.credential_provider {
             kwargs.set_item("credential_provider", credential_provider)?;
         }
+        if let Some(resolver) = &self.resolver {
+            kwargs.set_item(intern!(py, "resolver"), resolver)?;
+        }
+        if let Some(request_tags) = &self.request_tags {
+            kwargs.set_item(intern!(py, "request_tags"), request_tags)?;
+        }
+        if let Some(extra_headers) = &self.extra_headers {
+            kwargs.set_item(intern!(py, "extra_headers"), extra_headers)?;
+        }
 
         PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
     }
@@ -63,36 +118,89 @@ impl S3Config {
 #[derive(Debug, Clone)]
 #[pyclass(name = "S3Store", frozen, subclass)]
 pub struct PyS3Store {
-    store: Arc<MaybePrefixedStore<AmazonS3>>,
+    store: Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>,
     /// A config used for pickling. This must stay in sync with the underlying store's config.
     config: S3Config,
+    /// The connections of the store, reset with `reset_connections`.
+    pool: ConnectionPool,
 }
 
-impl AsRef<Arc<MaybePrefixedStore<AmazonS3>>> for PyS3Store {
-    fn as_ref(&self) -> &Arc<MaybePrefixedStore<AmazonS3>> {
+impl AsRef<Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>>> for PyS3Store {
+    fn as_ref(&self) -> &Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>> {
         &self.store
     }
 }
 
 impl PyS3Store {
     /// Consume self and return the underlying [`AmazonS3`].
-    pub fn into_inner(self) -> Arc<MaybePrefixedStore<AmazonS3>> {
+    pub fn into_inner(self) -> Arc<LimitedStore<MaybePrefixedStore<AmazonS3>>> {
         self.store
     }
+
+    /// The bucket of the store, for making requests that [`AmazonS3`] doesn't support.
+    pub fn bucket(&self) -> &str {
+        self.config.bucket()
+    }
+
+    /// A builder configured from the environment and the config of the store, in the same way as
+    /// the builder of the store, to read back the config that [`AmazonS3`] doesn't expose.
+    fn builder(&self) -> AmazonS3Builder {
+        let mut builder = AmazonS3Builder::from_env();
+        if let Some(client_options) = self.config.client_options.clone() {
+            builder = builder.with_client_options(client_options.into());
+        }
+        self.config.config.clone().apply_config(builder)
+    }
+
+    /// The region that requests to the store are signed for.
+    pub fn region(&self) -> String {
+        self.builder()
+            .get_config_value(&AmazonS3ConfigKey::Region)
+            // The default of `AmazonS3Builder`
+            .unwrap_or_else(|| "us-east-1".to_string())
+    }
+
+    /// An HTTP client with the client options, connections and request behavior of the store,
+    /// for making requests that [`AmazonS3`] doesn't support.
+    ///
+    /// Requests are retried with the retry config of the store, as `object_store` retries its own.
+    pub fn http_client(&self) -> object_store::Result<HttpClient> {
+        let builder = self.builder();
+        let options = client_options_from_builder(|key| {
+            builder.get_config_value(&AmazonS3ConfigKey::Client(key))
+        });
+        let client = StoreConnector::new(
+            &self.pool,
+            self.config.client_options.as_ref(),
+            self.config.retry_config.as_ref(),
+            self.config.resolver.as_ref(),
+            self.config.request_tags.as_ref(),
+            TagStyle::UserAgent,
+        )
+        .with_extra_headers(self.config.extra_headers.as_ref())
+        .connect(&options)?;
+        Ok(PyRetryConfig::request_policy(self.config.retry_config.as_ref()).wrap(client))
+    }
 }
 
 #[pymethods]
 impl PyS3Store {
     // Create from parameters
     #[new]
-    #[pyo3(signature = (bucket=None, *, prefix=None, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+    #[pyo3(signature = (bucket=None, *, prefix=None, config=None, client_options=None, retry_config=None, limit_config=None, compatibility=None, credential_provider=None, resolver=None, request_tags=None, extra_headers=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         bucket: Option<String>,
         prefix: Option<PyPath>,
         config: Option<PyAmazonS3Config>,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
+        compatibility: Option<PyS3Compatibility>,
         credential_provider: Option<PyAWSCredentialProvider>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
+        extra_headers: Option<RequestHeaders>,
         kwargs: Option<PyAmazonS3Config>,
     ) -> PyObjectStoreResult<Self> {
         let mut builder = AmazonS3Builder::from_env();
@@ -109,6 +217,18 @@ impl PyS3Store {
         if let Some(client_options) = client_options.clone() {
             builder = builder.with_client_options(client_options.into())
         }
+        let pool = ConnectionPool::default();
+        builder = builder.with_http_connector(
+            StoreConnector::new(
+                &pool,
+                client_options.as_ref(),
+                retry_config.as_ref(),
+                resolver.as_ref(),
+                request_tags.as_ref(),
+                TagStyle::UserAgent,
+            )
+            .with_extra_headers(extra_headers.as_ref()),
+        );
         if let Some(retry_config) = retry_config.clone() {
             builder = builder.with_retry(retry_config.into())
         }
@@ -121,44 +241,101 @@ impl PyS3Store {
                     combined_config.insert_if_not_exists(key.clone(), val.clone());
                 }
             }
-            builder = builder.with_credentials(Arc::new(credential_provider));
+        }
+
+        if let Some(compatibility) = compatibility {
+            // Give precedence to passed-in config values
+            for (key, val) in compatibility.config() {
+                combined_config.insert_if_not_exists(key, val);
+            }
+        }
+
+        // An access point ARN can be used in place of the bucket name
+        let arn_config = combined_config
+            .0
+            .get(&PyAmazonS3ConfigKey(AmazonS3ConfigKey::Bucket))
+            .and_then(|bucket| AccessPointArn::parse(bucket.as_ref()))
+            .map(|arn| arn.config());
+        if let Some(arn_config) = arn_config {
+            // Give precedence to passed-in config values
+            for (key, val) in arn_config {
+                combined_config.insert_if_not_exists(key, val);
+            }
         }
 
         builder = combined_config.clone().apply_config(builder);
 
+        if let Some(credential_provider) = credential_provider.clone() {
+            // Built-in providers make requests with the client options of the store, which
+            // are only complete once the config has been applied
+            let client_options = client_options_from_builder(|key| {
+                builder.get_config_value(&AmazonS3ConfigKey::Client(key))
+            });
+            builder = builder.with_credentials(Arc::new(
+                credential_provider.with_client_options(client_options),
+            ));
+        }
+
         Ok(Self {
-            store: Arc::new(MaybePrefixedStore::new(builder.build()?, prefix.clone())),
+            pool,
+            store: Arc::new(LimitedStore::new(
+                MaybePrefixedStore::new(builder.build()?, prefix.clone()),
+                limit_config.as_ref(),
+            )),
             config: S3Config {
                 prefix,
                 config: combined_config,
                 client_options,
                 retry_config,
+                limit_config,
+                compatibility,
                 credential_provider,
+                resolver,
+                request_tags,
+                extra_headers,
             },
         })
     }
 
     #[classmethod]
-    #[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+    #[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, limit_config=None, compatibility=None, credential_provider=None, resolver=None, request_tags=None, extra_headers=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_url(
         cls: &Bound<PyType>,
         url: PyUrl,
         config: Option<PyAmazonS3Config>,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
+        compatibility: Option<PyS3Compatibility>,
         credential_provider: Option<PyAWSCredentialProvider>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
+        extra_headers: Option<RequestHeaders>,
         kwargs: Option<PyAmazonS3Config>,
     ) -> PyObjectStoreResult<PyObject> {
         // We manually parse the URL to find the prefix because `with_url` does not apply the
         // prefix.
-        let (_, prefix) =
+        let (scheme, prefix) =
             ObjectStoreScheme::parse(url.as_ref()).map_err(object_store::Error::from)?;
+        // With a compatibility preset, any other HTTP URL is a path-style URL of an S3-compatible
+        // service, such as `http://minio.internal:9000/bucket/prefix`.
+        let compatible_endpoint = compatibility.is_some() && scheme == ObjectStoreScheme::Http;
+        // The whole path of an access point URL is within the store, even when the name of the
+        // access point starts with "s3", which would otherwise be parsed as a path-style URL.
+        let prefix = match url.as_ref().host_str() {
+            Some(host) if is_access_point_host(host) => {
+                Path::from_url_path(url.as_ref().path()).map_err(object_store::Error::from)?
+            }
+            _ if compatible_endpoint => prefix.parts().skip(1).collect(),
+            _ => prefix,
+        };
         let prefix: Option<String> = if prefix.parts().count() != 0 {
             Some(prefix.into())
         } else {
             None
         };
-        let config = parse_url(config, url.as_ref())?;
+        let config = parse_url(config, url.as_ref(), compatible_endpoint)?;
 
         // Note: we pass **back** through Python so that if cls is a subclass, we instantiate the
         // subclass
@@ -167,7 +344,12 @@ impl PyS3Store {
         kwargs.set_item("config", config)?;
         kwargs.set_item("client_options", client_options)?;
         kwargs.set_item("retry_config", retry_config)?;
+        kwargs.set_item("limit_config", limit_config)?;
+        kwargs.set_item("compatibility", compatibility)?;
         kwargs.set_item("credential_provider", credential_provider)?;
+        kwargs.set_item("resolver", resolver)?;
+        kwargs.set_item("request_tags", request_tags)?;
+        kwargs.set_item("extra_headers", extra_headers.as_ref())?;
         Ok(cls.call((), Some(&kwargs))?.unbind())
     }
 
@@ -183,6 +365,55 @@ impl PyS3Store {
         self.config.__getnewargs_ex__(py)
     }
 
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        let config = if allow_secrets {
+            self.config.clone()
+        } else {
+            self.config.without_secrets()
+        };
+        encode_token(py, Self::NAME, config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
+    fn reset_connections(&self) {
+        self.pool.reset();
+    }
+
+    #[pyo3(signature = (key, *, key_md5=None))]
+    fn with_sse_customer_key<'py>(
+        slf: &Bound<'py, Self>,
+        key: PySseCustomerKey,
+        key_md5: Option<String>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let py = slf.py();
+        if let Some(key_md5) = key_md5 {
+            key.validate_md5(&key_md5)?;
+        }
+
+        // SSE-C headers must be signed along with the request, so they can't be attached per
+        // request after signing. Instead we build a new store with the key in its config, keeping
+        // every other parameter of this store.
+        let mut config = slf.get().config.config.clone();
+        for (config_key, val) in key.config() {
+            config.insert(config_key, val);
+        }
+
+        // Note: we pass **back** through Python so that if the class is a subclass, we
+        // instantiate the subclass
+        let (args, kwargs) = slf
+            .get()
+            .config
+            .__getnewargs_ex__(py)?
+            .extract::<(Bound<PyTuple>, Bound<PyDict>)>(py)?;
+        kwargs.set_item(intern!(py, "config"), config)?;
+        slf.get_type().call(args, Some(&kwargs))
+    }
+
     fn __repr__(&self) -> String {
         let bucket = self.config.bucket();
         if let Some(prefix) = &self.config.prefix {
@@ -212,14 +443,42 @@ impl PyS3Store {
     }
 
     #[getter]
-    fn credential_provider(&self) -> Option<&PyAWSCredentialProvider> {
-        self.config.credential_provider.as_ref()
+    fn credential_provider(&self, py: Python) -> Option<PyCredentialProviderHandle> {
+        self.config
+            .credential_provider
+            .as_ref()
+            .map(|provider| provider.handle(py))
     }
 
     #[getter]
     fn retry_config(&self) -> Option<&PyRetryConfig> {
         self.config.retry_config.as_ref()
     }
+
+    #[getter]
+    fn resolver(&self) -> Option<&PyResolver> {
+        self.config.resolver.as_ref()
+    }
+
+    #[getter]
+    fn request_tags(&self) -> Option<&PyRequestTags> {
+        self.config.request_tags.as_ref()
+    }
+
+    #[getter]
+    fn extra_headers(&self) -> Option<&RequestHeaders> {
+        self.config.extra_headers.as_ref()
+    }
+
+    #[getter]
+    fn limit_config(&self) -> Option<&PyLimitConfig> {
+        self.config.limit_config.as_ref()
+    }
+
+    #[getter]
+    fn compatibility(&self) -> Option<PyS3Compatibility> {
+        self.config.compatibility
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -334,6 +593,11 @@ impl PyAmazonS3Config {
         Ok(())
     }
 
+    /// Insert a key, overriding any existing value.
+    fn insert(&mut self, key: impl Into<PyAmazonS3ConfigKey>, val: impl Into<String>) {
+        self.0.insert(key.into(), PyConfigValue::new(val));
+    }
+
     /// Insert a key only if it does not already exist.
     ///
     /// This is used for URL parsing, where any parts of the URL **do not** override any
@@ -367,9 +631,12 @@ fn combine_config_kwargs(
 /// underlying ObjectStore builder. Passing the URL on verbatim makes it hard because the URL
 /// parsing only happens in `build()`. Then the config parameters we have don't include any config
 /// applied from the URL.
+/// Parse the config of `parsed`, which with `compatible_endpoint` is a path-style URL of an
+/// S3-compatible service, whose origin is the endpoint and first path segment is the bucket.
 fn parse_url(
     config: Option<PyAmazonS3Config>,
     parsed: &Url,
+    compatible_endpoint: bool,
 ) -> object_store::Result<PyAmazonS3Config> {
     let host = parsed
         .host_str()
@@ -378,6 +645,17 @@ fn parse_url(
         })?;
     let mut config = config.unwrap_or_default();
 
+    if compatible_endpoint {
+        // The origin keeps a custom port, such as MinIO's 9000
+        let endpoint = parsed.origin().ascii_serialization();
+        config.insert_if_not_exists(AmazonS3ConfigKey::Endpoint, endpoint);
+        let bucket = parsed.path_segments().into_iter().flatten().next();
+        if let Some(bucket) = bucket.filter(|bucket| !bucket.is_empty()) {
+            config.insert_if_not_exists(AmazonS3ConfigKey::Bucket, bucket);
+        }
+        return Ok(config);
+    }
+
     match parsed.scheme() {
         "s3" | "s3a" => {
             config.insert_if_not_exists(AmazonS3ConfigKey::Bucket, host);
@@ -395,6 +673,18 @@ fn parse_url(
                 config.insert_if_not_exists(AmazonS3ConfigKey::Region, region);
                 config.insert_if_not_exists(AmazonS3ConfigKey::VirtualHostedStyleRequest, "true");
             }
+            Some((
+                alias,
+                "s3-accesspoint" | "s3-object-lambda",
+                region,
+                "amazonaws.com" | "amazonaws.com.cn",
+            )) => {
+                // The endpoint of an access point, whose alias takes the place of the bucket
+                config.insert_if_not_exists(AmazonS3ConfigKey::Bucket, alias);
+                config.insert_if_not_exists(AmazonS3ConfigKey::Region, region);
+                config.insert_if_not_exists(AmazonS3ConfigKey::Endpoint, format!("https://{host}"));
+                config.insert_if_not_exists(AmazonS3ConfigKey::VirtualHostedStyleRequest, "true");
+            }
             Some((account, "r2", "cloudflarestorage", "com")) => {
                 config.insert_if_not_exists(AmazonS3ConfigKey::Region, "auto");
                 let endpoint = format!("https://{account}.r2.cloudflarestorage.com");
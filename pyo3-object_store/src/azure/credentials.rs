@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
+use futures::future::BoxFuture;
 use object_store::azure::{AzureAccessKey, AzureCredential};
 use object_store::CredentialProvider;
 use percent_encoding::percent_decode_str;
@@ -12,8 +13,12 @@ use pyo3::pybacked::PyBackedStr;
 use pyo3::types::PyTuple;
 
 use crate::azure::error::Error;
+use crate::azure::providers::AzureBuiltinProvider;
 use crate::azure::store::PyAzureConfig;
-use crate::credentials::{is_awaitable, TemporaryToken, TokenCache};
+use crate::credentials::{
+    background_refresh, chain_providers, fetch_first, is_awaitable, PyCredentialProviderHandle,
+    TemporaryToken, TokenCache,
+};
 use crate::path::PyPath;
 use crate::PyObjectStoreError;
 
@@ -137,9 +142,12 @@ fn split_sas(sas: &str) -> Result<Vec<(String, String)>, object_store::Error> {
 
 #[derive(Debug)]
 pub struct PyAzureCredentialProvider {
-    /// The provided user callback to manage credential refresh
+    /// The provided user callback to manage credential refresh, the name of a built-in
+    /// provider, or a chain of providers
     user_callback: PyObject,
-    cache: TokenCache<Arc<AzureCredential>>,
+    /// Where credentials are fetched from, as given by `user_callback`
+    source: Arc<AzureCredentialSource>,
+    cache: Arc<TokenCache<Arc<AzureCredential>>>,
     /// An optional config passed down from the credential provider class
     config: Option<PyAzureConfig>,
     /// An optional prefix passed down from the credential provider class
@@ -157,6 +165,11 @@ impl PyAzureCredentialProvider {
         self.prefix.as_ref()
     }
 
+    /// The handle returned by the store's `credential_provider` property.
+    pub(crate) fn handle(&self, py: Python) -> PyCredentialProviderHandle {
+        PyCredentialProviderHandle::new(self.user_callback.clone_ref(py), self.cache.clone())
+    }
+
     fn equals(&self, py: Python, other: &Self) -> PyResult<bool> {
         self.user_callback
             .call_method1(py, "__eq__", PyTuple::new(py, vec![&other.user_callback])?)?
@@ -169,6 +182,7 @@ impl Clone for PyAzureCredentialProvider {
         let cloned_callback = Python::with_gil(|py| self.user_callback.clone_ref(py));
         Self {
             user_callback: cloned_callback,
+            source: self.source.clone(),
             cache: self.cache.clone(),
             config: self.config.clone(),
             prefix: self.prefix.clone(),
@@ -184,16 +198,22 @@ impl PartialEq for PyAzureCredentialProvider {
 
 impl<'py> FromPyObject<'py> for PyAzureCredentialProvider {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
-            return Err(PyTypeError::new_err(
-                "Expected callable object for credential_provider.",
-            ));
+        let source = AzureCredentialSource::extract(ob)?;
+        if !matches!(source, AzureCredentialSource::Callback(_)) {
+            return Ok(Self {
+                user_callback: ob.clone().unbind(),
+                source: Arc::new(source),
+                cache: Default::default(),
+                config: None,
+                prefix: None,
+            });
         }
 
         let mut cache = TokenCache::default();
         if let Ok(refresh_threshold) = ob.getattr(intern!(ob.py(), "refresh_threshold")) {
             cache = cache.with_min_ttl(refresh_threshold.extract()?);
         }
+        cache = cache.with_background_refresh(background_refresh(ob)?);
 
         let config = if let Ok(config) = ob.getattr(intern!(ob.py(), "config")) {
             config.extract()?
@@ -211,7 +231,8 @@ impl<'py> FromPyObject<'py> for PyAzureCredentialProvider {
 
         Ok(Self {
             user_callback: ob.clone().unbind(),
-            cache,
+            source: Arc::new(source),
+            cache: Arc::new(cache),
             config,
             prefix,
         })
@@ -238,6 +259,64 @@ impl<'py> IntoPyObject<'py> for PyAzureCredentialProvider {
     }
 }
 
+/// Where a credential provider fetches credentials from.
+#[derive(Debug)]
+enum AzureCredentialSource {
+    /// A Python callback
+    Callback(PyObject),
+    /// A built-in provider, selected by name
+    Builtin(AzureBuiltinProvider),
+    /// Sources that are tried in order until one returns a credential
+    Chain(Vec<AzureCredentialSource>),
+}
+
+impl AzureCredentialSource {
+    fn extract(ob: &Bound<PyAny>) -> PyResult<Self> {
+        if let Some(providers) = chain_providers(ob)? {
+            let sources = providers
+                .iter()
+                .map(Self::extract)
+                .collect::<PyResult<_>>()?;
+            return Ok(Self::Chain(sources));
+        }
+        if let Ok(name) = ob.extract::<PyBackedStr>() {
+            let builtin = AzureBuiltinProvider::parse(&name).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Unknown credential_provider: {}. Expected one of 'cli', 'managed-identity', 'managed-identity:<client_id>', or 'workload-identity'.",
+                    &*name
+                ))
+            })?;
+            return Ok(Self::Builtin(builtin));
+        }
+        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
+            return Err(PyTypeError::new_err(
+                "Expected callable object for credential_provider.",
+            ));
+        }
+        Ok(Self::Callback(ob.clone().unbind()))
+    }
+
+    fn fetch(&self) -> BoxFuture<'_, object_store::Result<TemporaryToken<Arc<AzureCredential>>>> {
+        Box::pin(async move {
+            match self {
+                Self::Callback(user_callback) => {
+                    PyAzureCredentialProvider::fetch_token(user_callback).await
+                }
+                Self::Builtin(builtin) => {
+                    builtin
+                        .fetch()
+                        .await
+                        .map_err(|err| object_store::Error::Unauthenticated {
+                            path: format!("Azure {} credential provider", builtin),
+                            source: Box::new(err),
+                        })
+                }
+                Self::Chain(sources) => fetch_first(sources.iter().map(Self::fetch)).await,
+            }
+        })
+    }
+}
+
 enum PyCredentialProviderResult {
     Async(PyObject),
     Sync(PyAzureCredential),
@@ -269,9 +348,9 @@ impl<'py> FromPyObject<'py> for PyCredentialProviderResult {
 }
 
 impl PyAzureCredentialProvider {
-    async fn call(&self) -> PyResult<PyAzureCredential> {
+    async fn call(user_callback: &PyObject) -> PyResult<PyAzureCredential> {
         let call_result = Python::with_gil(|py| {
-            self.user_callback
+            user_callback
                 .call0(py)?
                 .extract::<PyCredentialProviderResult>(py)
         })?;
@@ -279,14 +358,15 @@ impl PyAzureCredentialProvider {
     }
 
     /// Call the user-provided callback
-    async fn fetch_token(&self) -> object_store::Result<TemporaryToken<Arc<AzureCredential>>> {
-        let credential = self
-            .call()
-            .await
-            .map_err(|err| object_store::Error::Unauthenticated {
+    async fn fetch_token(
+        user_callback: &PyObject,
+    ) -> object_store::Result<TemporaryToken<Arc<AzureCredential>>> {
+        let credential = Self::call(user_callback).await.map_err(|err| {
+            object_store::Error::Unauthenticated {
                 path: "External Azure credential provider".to_string(),
                 source: Box::new(err),
-            })?;
+            }
+        })?;
 
         Ok(credential.into_temporary_token())
     }
@@ -298,6 +378,17 @@ impl CredentialProvider for PyAzureCredentialProvider {
     type Credential = AzureCredential;
 
     async fn get_credential(&self) -> object_store::Result<Arc<Self::Credential>> {
-        self.cache.get_or_insert_with(|| self.fetch_token()).await
+        let credential = self
+            .cache
+            .get_or_insert_with(|| self.source.fetch())
+            .await?;
+        if self.cache.needs_refresh_task() {
+            let source = self.source.clone();
+            self.cache.spawn_refresh(move || {
+                let source = source.clone();
+                async move { source.fetch().await }
+            });
+        }
+        Ok(credential)
     }
 }
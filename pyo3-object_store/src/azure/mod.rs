@@ -1,5 +1,9 @@
 mod credentials;
 mod error;
+mod providers;
 mod store;
+mod tier;
 
 pub use store::PyAzureStore;
+pub(crate) use tier::TieredAzureStore;
+pub use tier::{set_blob_tier, AccessTier};
@@ -0,0 +1,275 @@
+//! Credential providers implemented in Rust, which can be selected by name instead of passing a
+//! Python callback as `credential_provider`.
+//!
+//! Each provider fetches a bearer token for Azure Storage from a different source: the Azure
+//! CLI, a managed identity, or a federated token from workload identity on Kubernetes.
+
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Local, NaiveDateTime, TimeDelta, TimeZone, Utc};
+use http::Method;
+use object_store::azure::AzureCredential;
+use object_store::client::{HttpClient, HttpConnector, HttpRequestBody, ReqwestConnector};
+use object_store::ClientOptions;
+use serde::Deserialize;
+use url::Url;
+
+use crate::credentials::TemporaryToken;
+
+type Result<T, E = object_store::Error> = std::result::Result<T, E>;
+
+/// The resource that tokens are requested for
+const STORAGE_RESOURCE: &str = "https://storage.azure.com";
+
+const DEFAULT_IMDS_ENDPOINT: &str = "http://169.254.169.254/metadata/identity/oauth2/token";
+
+const DEFAULT_AUTHORITY_HOST: &str = "https://login.microsoftonline.com";
+
+/// A credential provider selected by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AzureBuiltinProvider {
+    /// `"cli"`: the account signed in to the Azure CLI with `az login`.
+    Cli,
+    /// `"managed-identity"` or `"managed-identity:<client_id>"`: the system-assigned managed
+    /// identity, or the user-assigned identity with the given client ID.
+    ManagedIdentity(Option<String>),
+    /// `"workload-identity"`: the federated token that workload identity mounts into Kubernetes
+    /// pods, exchanged for a token of the application in `AZURE_CLIENT_ID`.
+    WorkloadIdentity,
+}
+
+impl AzureBuiltinProvider {
+    /// Parse the name of a provider, returning `None` if `s` isn't one.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        match s.split_once(':') {
+            None if s == "cli" => Some(Self::Cli),
+            None if s == "managed-identity" => Some(Self::ManagedIdentity(None)),
+            Some(("managed-identity", client_id)) if !client_id.is_empty() => {
+                Some(Self::ManagedIdentity(Some(client_id.to_string())))
+            }
+            None if s == "workload-identity" => Some(Self::WorkloadIdentity),
+            _ => None,
+        }
+    }
+
+    pub(crate) async fn fetch(&self) -> Result<TemporaryToken<Arc<AzureCredential>>> {
+        let (token, expiry) = match self {
+            Self::Cli => cli_token().await?,
+            Self::ManagedIdentity(client_id) => {
+                managed_identity_token(&client()?, client_id.as_deref()).await?
+            }
+            Self::WorkloadIdentity => workload_identity_token(&client()?).await?,
+        };
+        Ok(TemporaryToken {
+            token: Arc::new(AzureCredential::BearerToken(token)),
+            expiry: Some(expiry),
+        })
+    }
+}
+
+impl fmt::Display for AzureBuiltinProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cli => write!(f, "cli"),
+            Self::ManagedIdentity(None) => write!(f, "managed-identity"),
+            Self::ManagedIdentity(Some(client_id)) => write!(f, "managed-identity:{client_id}"),
+            Self::WorkloadIdentity => write!(f, "workload-identity"),
+        }
+    }
+}
+
+fn error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "MicrosoftAzure",
+        source: msg.into().into(),
+    }
+}
+
+/// The client to make requests with. IMDS is only served over plain HTTP.
+fn client() -> Result<HttpClient> {
+    ReqwestConnector::default().connect(&ClientOptions::new().with_allow_http(true))
+}
+
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// Send `request`, returning the body of a successful response.
+async fn send(client: &HttpClient, request: http::Request<HttpRequestBody>) -> Result<String> {
+    let uri = request.uri().clone();
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|err| error(format!("Request to {uri} failed: {err}")))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .bytes()
+        .await
+        .map_err(|err| error(format!("Request to {uri} failed: {err}")))?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if !status.is_success() {
+        return Err(error(format!(
+            "Request to {uri} failed with status {status}: {body}"
+        )));
+    }
+    Ok(body)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CliToken {
+    access_token: String,
+    /// Seconds since the epoch, only output by Azure CLI 2.54 and later
+    #[serde(rename = "expires_on")]
+    expires_on_timestamp: Option<i64>,
+    /// Local time, e.g. `2024-01-01 12:00:00.000000`
+    expires_on: String,
+}
+
+/// Fetch a token from the Azure CLI.
+async fn cli_token() -> Result<(String, DateTime<Utc>)> {
+    let args = [
+        "account",
+        "get-access-token",
+        "--resource",
+        STORAGE_RESOURCE,
+        "--output",
+        "json",
+    ];
+    let output = tokio::task::spawn_blocking(move || {
+        // `az` is a batch script on Windows
+        #[cfg(windows)]
+        let output = std::process::Command::new("cmd")
+            .args(["/C", "az"])
+            .args(args)
+            .output();
+        #[cfg(not(windows))]
+        let output = std::process::Command::new("az").args(args).output();
+        output
+    })
+    .await
+    .map_err(|err| error(err.to_string()))?
+    .map_err(|err| error(format!("Failed to run the Azure CLI: {err}")))?;
+    if !output.status.success() {
+        return Err(error(format!(
+            "The Azure CLI failed with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    let token: CliToken = serde_json::from_slice(&output.stdout)
+        .map_err(|err| error(format!("Invalid Azure CLI output: {err}")))?;
+    let expiry = match token.expires_on_timestamp {
+        Some(timestamp) => Utc.timestamp_opt(timestamp, 0).single(),
+        None => NaiveDateTime::parse_from_str(&token.expires_on, "%Y-%m-%d %H:%M:%S%.f")
+            .ok()
+            .and_then(|expiry| Local.from_local_datetime(&expiry).earliest())
+            .map(|expiry| expiry.with_timezone(&Utc)),
+    }
+    .ok_or_else(|| error(format!("Invalid token expiry: {}", token.expires_on)))?;
+    Ok((token.access_token, expiry))
+}
+
+#[derive(Deserialize)]
+struct ManagedIdentityToken {
+    access_token: String,
+    /// Seconds since the epoch, as a string or a number depending on the endpoint
+    expires_on: serde_json::Value,
+}
+
+/// Fetch a token of a managed identity.
+///
+/// On App Service and Azure Functions, the identity endpoint is given by `IDENTITY_ENDPOINT`
+/// and `IDENTITY_HEADER`. Elsewhere, the instance metadata service is used.
+async fn managed_identity_token(
+    client: &HttpClient,
+    client_id: Option<&str>,
+) -> Result<(String, DateTime<Utc>)> {
+    let identity_endpoint = env_var("IDENTITY_ENDPOINT").zip(env_var("IDENTITY_HEADER"));
+    let endpoint = identity_endpoint
+        .as_ref()
+        .map(|(endpoint, _)| endpoint.as_str())
+        .unwrap_or(DEFAULT_IMDS_ENDPOINT);
+    let mut url = Url::parse(endpoint)
+        .map_err(|err| error(format!("Invalid identity endpoint {endpoint}: {err}")))?;
+    url.query_pairs_mut()
+        .append_pair("api-version", "2019-08-01")
+        .append_pair("resource", STORAGE_RESOURCE);
+    if let Some(client_id) = client_id {
+        url.query_pairs_mut().append_pair("client_id", client_id);
+    }
+
+    let mut builder = http::Request::builder()
+        .method(Method::GET)
+        .uri(url.as_str());
+    builder = match &identity_endpoint {
+        Some((_, header)) => builder.header("X-IDENTITY-HEADER", header),
+        None => builder.header("Metadata", "true"),
+    };
+    let request = builder
+        .body(HttpRequestBody::empty())
+        .map_err(|err| error(err.to_string()))?;
+    let body = send(client, request).await?;
+    let token: ManagedIdentityToken = serde_json::from_str(&body)
+        .map_err(|err| error(format!("Invalid managed identity response: {err}")))?;
+    let expiry = match &token.expires_on {
+        serde_json::Value::Number(n) => n.as_i64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+    .and_then(|timestamp| Utc.timestamp_opt(timestamp, 0).single())
+    .ok_or_else(|| error(format!("Invalid token expiry: {}", token.expires_on)))?;
+    Ok((token.access_token, expiry))
+}
+
+#[derive(Deserialize)]
+struct OAuthToken {
+    access_token: String,
+    /// Seconds from now
+    expires_in: i64,
+}
+
+/// Exchange the federated token of workload identity for a token of the application, using the
+/// environment variables set by the workload identity webhook.
+async fn workload_identity_token(client: &HttpClient) -> Result<(String, DateTime<Utc>)> {
+    let var = |key: &str| env_var(key).ok_or_else(|| error(format!("{key} is not set")));
+    let client_id = var("AZURE_CLIENT_ID")?;
+    let tenant_id = var("AZURE_TENANT_ID")?;
+    let token_file = var("AZURE_FEDERATED_TOKEN_FILE")?;
+    let authority_host =
+        env_var("AZURE_AUTHORITY_HOST").unwrap_or_else(|| DEFAULT_AUTHORITY_HOST.to_string());
+
+    let assertion = std::fs::read_to_string(&token_file)
+        .map_err(|err| error(format!("Failed to read {token_file}: {err}")))?;
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair("client_id", &client_id)
+        .append_pair("scope", &format!("{STORAGE_RESOURCE}/.default"))
+        .append_pair(
+            "client_assertion_type",
+            "urn:ietf:params:oauth:client-assertion-type:jwt-bearer",
+        )
+        .append_pair("client_assertion", assertion.trim())
+        .append_pair("grant_type", "client_credentials")
+        .finish();
+    let url = format!(
+        "{}/{}/oauth2/v2.0/token",
+        authority_host.trim_end_matches('/'),
+        tenant_id
+    );
+    let request = http::Request::builder()
+        .method(Method::POST)
+        .uri(&url)
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .body(HttpRequestBody::from(body.into_bytes()))
+        .map_err(|err| error(err.to_string()))?;
+    let body = send(client, request).await?;
+    let token: OAuthToken = serde_json::from_str(&body)
+        .map_err(|err| error(format!("Invalid token response: {err}")))?;
+    let expiry = Utc::now() + TimeDelta::seconds(token.expires_in);
+    Ok((token.access_token, expiry))
+}
@@ -2,19 +2,27 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use object_store::azure::{AzureConfigKey, MicrosoftAzure, MicrosoftAzureBuilder};
+use object_store::client::{HttpClient, HttpConnector};
 use object_store::ObjectStoreScheme;
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyDict, PyString, PyTuple, PyType};
-use pyo3::{intern, IntoPyObjectExt};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
 use url::Url;
 
 use crate::azure::credentials::PyAzureCredentialProvider;
-use crate::client::PyClientOptions;
+use crate::client::{client_options_from_builder, PyClientOptions};
 use crate::config::PyConfigValue;
+use crate::connector::StoreConnector;
+use crate::credentials::PyCredentialProviderHandle;
 use crate::error::{GenericError, ParseUrlError, PyObjectStoreError, PyObjectStoreResult};
+use crate::limit::{LimitedStore, PyLimitConfig};
 use crate::path::PyPath;
+use crate::pool::ConnectionPool;
+use crate::resolver::PyResolver;
 use crate::retry::PyRetryConfig;
+use crate::tags::{PyRequestTags, TagStyle};
+use crate::token::{encode_token, store_from_token};
 use crate::{MaybePrefixedStore, PyUrl};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,7 +31,10 @@ struct AzureConfig {
     config: PyAzureConfig,
     client_options: Option<PyClientOptions>,
     retry_config: Option<PyRetryConfig>,
+    limit_config: Option<PyLimitConfig>,
     credential_provider: Option<PyAzureCredentialProvider>,
+    resolver: Option<PyResolver>,
+    request_tags: Option<PyRequestTags>,
 }
 
 impl AzureConfig {
@@ -43,6 +54,26 @@ impl AzureConfig {
             .as_ref()
     }
 
+    /// The config without credentials, for `to_token`.
+    fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config.config.0.retain(|key, _| {
+            !matches!(
+                key.0,
+                AzureConfigKey::AccessKey
+                    | AzureConfigKey::ClientSecret
+                    | AzureConfigKey::SasKey
+                    | AzureConfigKey::Token
+                    | AzureConfigKey::FabricSessionToken
+            )
+        });
+        config.client_options = config
+            .client_options
+            .as_ref()
+            .map(PyClientOptions::without_secrets);
+        config
+    }
+
     fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
         let args = PyTuple::empty(py).into_py_any(py)?;
         let kwargs = PyDict::new(py);
@@ -57,9 +88,18 @@ impl AzureConfig {
         if let Some(retry_config) = &self.retry_config {
             kwargs.set_item(intern!(py, "retry_config"), retry_config)?;
         }
+        if let Some(limit_config) = &self.limit_config {
+            kwargs.set_item(intern!(py, "limit_config"), limit_config)?;
+        }
         if let Some(credential_provider) = &self.credential_provider {
             kwargs.set_item("credential_provider", credential_provider)?;
         }
+        if let Some(resolver) = &self.resolver {
+            kwargs.set_item(intern!(py, "resolver"), resolver)?;
+        }
+        if let Some(request_tags) = &self.request_tags {
+            kwargs.set_item(intern!(py, "request_tags"), request_tags)?;
+        }
 
         PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
     }
@@ -69,36 +109,121 @@ impl AzureConfig {
 #[derive(Debug, Clone)]
 #[pyclass(name = "AzureStore", frozen, subclass)]
 pub struct PyAzureStore {
-    store: Arc<MaybePrefixedStore<MicrosoftAzure>>,
+    store: Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>,
     /// A config used for pickling. This must stay in sync with the underlying store's config.
     config: AzureConfig,
+    /// The connections of the store, reset with `reset_connections`.
+    pool: ConnectionPool,
 }
 
-impl AsRef<Arc<MaybePrefixedStore<MicrosoftAzure>>> for PyAzureStore {
-    fn as_ref(&self) -> &Arc<MaybePrefixedStore<MicrosoftAzure>> {
+impl AsRef<Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>>> for PyAzureStore {
+    fn as_ref(&self) -> &Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>> {
         &self.store
     }
 }
 
 impl PyAzureStore {
     /// Consume self and return the underlying [`MicrosoftAzure`].
-    pub fn into_inner(self) -> Arc<MaybePrefixedStore<MicrosoftAzure>> {
+    pub fn into_inner(self) -> Arc<LimitedStore<MaybePrefixedStore<MicrosoftAzure>>> {
         self.store
     }
+
+    /// The URL of the container and the name of the storage account used by the store.
+    ///
+    /// [`MicrosoftAzure`] doesn't expose these, so we resolve them from the store's config and
+    /// the environment in the same way as [`MicrosoftAzureBuilder::build`]. They can be used to
+    /// make requests that [`MicrosoftAzure`] doesn't support, authorized with an
+    /// [`AzureAuthorizer`][object_store::azure::AzureAuthorizer].
+    pub fn container_url(&self) -> PyObjectStoreResult<(Url, String)> {
+        let builder = self
+            .config
+            .config
+            .clone()
+            .apply_config(MicrosoftAzureBuilder::from_env());
+        let is_truthy = |key: AzureConfigKey| {
+            builder.get_config_value(&key).is_some_and(|value| {
+                matches!(
+                    value.to_ascii_lowercase().as_str(),
+                    "1" | "true" | "on" | "yes" | "y"
+                )
+            })
+        };
+        let container = builder
+            .get_config_value(&AzureConfigKey::ContainerName)
+            .ok_or_else(|| GenericError::new_err("Container name must be specified"))?;
+
+        let (url, account, is_emulator) = if is_truthy(AzureConfigKey::UseEmulator) {
+            let account = builder
+                .get_config_value(&AzureConfigKey::AccountName)
+                .unwrap_or_else(|| "devstoreaccount1".to_string());
+            let url = std::env::var("AZURITE_BLOB_STORAGE_URL")
+                .unwrap_or_else(|_| "http://127.0.0.1:10000".to_string());
+            (url, account, true)
+        } else {
+            let account = builder
+                .get_config_value(&AzureConfigKey::AccountName)
+                .ok_or_else(|| GenericError::new_err("Account name must be specified"))?;
+            let url = match builder.get_config_value(&AzureConfigKey::Endpoint) {
+                Some(endpoint) => endpoint,
+                None if is_truthy(AzureConfigKey::UseFabricEndpoint) => {
+                    format!("https://{}.blob.fabric.microsoft.com", account)
+                }
+                None => format!("https://{}.blob.core.windows.net", account),
+            };
+            (url, account, false)
+        };
+        let mut url = Url::parse(&url).map_err(|err| GenericError::new_err(err.to_string()))?;
+        url.path_segments_mut()
+            .map_err(|_| GenericError::new_err("Endpoint URL cannot be a base"))?
+            .pop_if_empty()
+            .extend(is_emulator.then_some(account.as_str()))
+            .push(&container);
+        Ok((url, account))
+    }
+
+    /// An HTTP client with the client options, connections and request behavior of the store,
+    /// for making requests that [`MicrosoftAzure`] doesn't support.
+    ///
+    /// Requests are retried with the retry config of the store, as `object_store` retries its own.
+    pub fn http_client(&self) -> object_store::Result<HttpClient> {
+        // Configured in the same order as the builder of the store
+        let mut builder = MicrosoftAzureBuilder::from_env();
+        if let Some(client_options) = self.config.client_options.clone() {
+            builder = builder.with_client_options(client_options.into());
+        }
+        let builder = self.config.config.clone().apply_config(builder);
+        let options = client_options_from_builder(|key| {
+            builder.get_config_value(&AzureConfigKey::Client(key))
+        });
+        let client = StoreConnector::new(
+            &self.pool,
+            self.config.client_options.as_ref(),
+            self.config.retry_config.as_ref(),
+            self.config.resolver.as_ref(),
+            self.config.request_tags.as_ref(),
+            TagStyle::UserAgent,
+        )
+        .connect(&options)?;
+        Ok(PyRetryConfig::request_policy(self.config.retry_config.as_ref()).wrap(client))
+    }
 }
 
 #[pymethods]
 impl PyAzureStore {
     // Create from parameters
     #[new]
-    #[pyo3(signature = (container_name=None, *, prefix=None, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+    #[pyo3(signature = (container_name=None, *, prefix=None, config=None, client_options=None, retry_config=None, limit_config=None, credential_provider=None, resolver=None, request_tags=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         container_name: Option<String>,
         mut prefix: Option<PyPath>,
         config: Option<PyAzureConfig>,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
         credential_provider: Option<PyAzureCredentialProvider>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
         kwargs: Option<PyAzureConfig>,
     ) -> PyObjectStoreResult<Self> {
         let mut builder = MicrosoftAzureBuilder::from_env();
@@ -115,6 +240,15 @@ impl PyAzureStore {
         if let Some(client_options) = client_options.clone() {
             builder = builder.with_client_options(client_options.into())
         }
+        let pool = ConnectionPool::default();
+        builder = builder.with_http_connector(StoreConnector::new(
+            &pool,
+            client_options.as_ref(),
+            retry_config.as_ref(),
+            resolver.as_ref(),
+            request_tags.as_ref(),
+            TagStyle::UserAgent,
+        ));
         if let Some(retry_config) = retry_config.clone() {
             builder = builder.with_retry(retry_config.into())
         }
@@ -144,26 +278,37 @@ impl PyAzureStore {
         builder = combined_config.clone().apply_config(builder);
 
         Ok(Self {
-            store: Arc::new(MaybePrefixedStore::new(builder.build()?, prefix.clone())),
+            pool,
+            store: Arc::new(LimitedStore::new(
+                MaybePrefixedStore::new(builder.build()?, prefix.clone()),
+                limit_config.as_ref(),
+            )),
             config: AzureConfig {
                 prefix,
                 config: combined_config,
                 client_options,
                 retry_config,
+                limit_config,
                 credential_provider,
+                resolver,
+                request_tags,
             },
         })
     }
 
     #[classmethod]
-    #[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+    #[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, limit_config=None, credential_provider=None, resolver=None, request_tags=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_url(
         cls: &Bound<PyType>,
         url: PyUrl,
         config: Option<PyAzureConfig>,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
         credential_provider: Option<PyAzureCredentialProvider>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
         kwargs: Option<PyAzureConfig>,
     ) -> PyObjectStoreResult<PyObject> {
         // We manually parse the URL to find the prefix because `parse_url` does not apply the
@@ -184,7 +329,10 @@ impl PyAzureStore {
         kwargs.set_item("config", config)?;
         kwargs.set_item("client_options", client_options)?;
         kwargs.set_item("retry_config", retry_config)?;
+        kwargs.set_item("limit_config", limit_config)?;
         kwargs.set_item("credential_provider", credential_provider)?;
+        kwargs.set_item("resolver", resolver)?;
+        kwargs.set_item("request_tags", request_tags)?;
         Ok(cls.call((), Some(&kwargs))?.unbind())
     }
 
@@ -201,6 +349,25 @@ impl PyAzureStore {
         self.config.__getnewargs_ex__(py)
     }
 
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        let config = if allow_secrets {
+            self.config.clone()
+        } else {
+            self.config.without_secrets()
+        };
+        encode_token(py, Self::NAME, config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
+    fn reset_connections(&self) {
+        self.pool.reset();
+    }
+
     fn __repr__(&self) -> String {
         let account_name = self.config.account_name();
         let container_name = self.config.container_name();
@@ -235,14 +402,32 @@ impl PyAzureStore {
     }
 
     #[getter]
-    fn credential_provider(&self) -> Option<&PyAzureCredentialProvider> {
-        self.config.credential_provider.as_ref()
+    fn credential_provider(&self, py: Python) -> Option<PyCredentialProviderHandle> {
+        self.config
+            .credential_provider
+            .as_ref()
+            .map(|provider| provider.handle(py))
     }
 
     #[getter]
     fn retry_config(&self) -> Option<&PyRetryConfig> {
         self.config.retry_config.as_ref()
     }
+
+    #[getter]
+    fn resolver(&self) -> Option<&PyResolver> {
+        self.config.resolver.as_ref()
+    }
+
+    #[getter]
+    fn request_tags(&self) -> Option<&PyRequestTags> {
+        self.config.request_tags.as_ref()
+    }
+
+    #[getter]
+    fn limit_config(&self) -> Option<&PyLimitConfig> {
+        self.config.limit_config.as_ref()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
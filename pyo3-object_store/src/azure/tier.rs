@@ -0,0 +1,292 @@
+//! Access tiers of block blobs, which `object_store` doesn't support setting.
+//!
+//! See <https://learn.microsoft.com/en-us/azure/storage/blobs/access-tiers-overview>.
+
+use std::sync::Arc;
+
+use futures::stream::BoxStream;
+use http::{Method, StatusCode};
+use object_store::azure::AzureAuthorizer;
+use object_store::client::{HttpConnector, HttpRequestBody, ReqwestConnector};
+use object_store::path::Path;
+use object_store::{
+    Attribute, ClientOptions, Error, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
+    UploadPart,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
+
+use crate::PyAzureStore;
+
+/// The attribute that sets the access tier of a blob on `put`, which is otherwise treated as
+/// user-defined metadata. Attribute keys are lowercased when parsed from Python.
+const ACCESS_TIER_ATTRIBUTE: &str = "access-tier";
+
+/// The access tier of a block blob.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessTier {
+    /// Frequently accessed data
+    Hot,
+    /// Infrequently accessed data, stored for at least 30 days
+    Cool,
+    /// Rarely accessed data, stored for at least 90 days
+    Cold,
+    /// Offline data, which must be rehydrated to another tier before it can be read
+    Archive,
+}
+
+impl AccessTier {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hot => "Hot",
+            Self::Cool => "Cool",
+            Self::Cold => "Cold",
+            Self::Archive => "Archive",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "hot" => Some(Self::Hot),
+            "cool" => Some(Self::Cool),
+            "cold" => Some(Self::Cold),
+            "archive" => Some(Self::Archive),
+            _ => None,
+        }
+    }
+
+    /// Remove the access tier attribute from `attributes`, if any.
+    fn take(attributes: &mut object_store::Attributes) -> Result<Option<Self>> {
+        let Some(value) = attributes.remove(&Attribute::Metadata(ACCESS_TIER_ATTRIBUTE.into()))
+        else {
+            return Ok(None);
+        };
+        Self::parse(value.as_ref())
+            .map(Some)
+            .ok_or_else(|| invalid_tier(value.as_ref()))
+    }
+}
+
+fn invalid_tier(tier: &str) -> Error {
+    Error::Generic {
+        store: "MicrosoftAzure",
+        source: format!(
+            "Unexpected access tier: {}. Expected one of 'Hot', 'Cool', 'Cold', or 'Archive'.",
+            tier
+        )
+        .into(),
+    }
+}
+
+impl<'py> FromPyObject<'py> for AccessTier {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?;
+        Self::parse(&s).ok_or_else(|| {
+            PyValueError::new_err(format!(
+                "Unexpected input for tier: {}. Expected one of 'Hot', 'Cool', 'Cold', or 'Archive'.",
+                &*s
+            ))
+        })
+    }
+}
+
+/// Set the access tier of the blob at `location` with a Set Blob Tier request.
+pub async fn set_blob_tier(store: &PyAzureStore, location: &Path, tier: AccessTier) -> Result<()> {
+    let azure_error = |msg: String| Error::Generic {
+        store: "MicrosoftAzure",
+        source: msg.into(),
+    };
+    let path = store.as_ref().inner().full_path(location).into_owned();
+    let (mut url, account) = store
+        .container_url()
+        .map_err(|err| azure_error(err.to_string()))?;
+    url.path_segments_mut()
+        .map_err(|_| azure_error("Container URL cannot be a base".to_string()))?
+        .extend(path.parts());
+    url.query_pairs_mut().append_pair("comp", "tier");
+
+    let credential = store
+        .as_ref()
+        .inner()
+        .inner()
+        .credentials()
+        .get_credential()
+        .await?;
+    let mut request = http::Request::builder()
+        .method(Method::PUT)
+        .uri(url.as_str())
+        .header("x-ms-access-tier", tier.as_str())
+        .header(http::header::CONTENT_LENGTH, 0)
+        .body(HttpRequestBody::empty())
+        .map_err(|err| azure_error(err.to_string()))?;
+    AzureAuthorizer::new(&credential, &account).authorize(&mut request);
+
+    // The emulator is served over plain HTTP
+    let options = ClientOptions::new().with_allow_http(url.scheme() == "http");
+    let client = ReqwestConnector::default().connect(&options)?;
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|err| azure_error(err.to_string()))?;
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let body = response
+        .into_body()
+        .bytes()
+        .await
+        .map_err(|err| azure_error(err.to_string()))?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if status == StatusCode::NOT_FOUND {
+        return Err(Error::NotFound {
+            path: path.to_string(),
+            source: body.into(),
+        });
+    }
+    Err(azure_error(format!(
+        "Set Blob Tier request failed with status {}: {}",
+        status, body
+    )))
+}
+
+/// An Azure store that sets the access tier of blobs given in the `Access-Tier` attribute of
+/// `put`, after the blob has been written.
+///
+/// Setting the tier in the request that writes the blob would need the `x-ms-access-tier` header
+/// to be signed, which [`MicrosoftAzure`][object_store::azure::MicrosoftAzure] doesn't do.
+#[derive(Debug)]
+pub(crate) struct TieredAzureStore {
+    inner: Arc<dyn ObjectStore>,
+    /// The store, for making Set Blob Tier requests
+    store: Arc<PyAzureStore>,
+}
+
+impl TieredAzureStore {
+    pub(crate) fn new(store: PyAzureStore) -> Self {
+        Self {
+            inner: store.as_ref().clone(),
+            store: Arc::new(store),
+        }
+    }
+
+    fn inner(&self) -> &dyn ObjectStore {
+        self.inner.as_ref()
+    }
+}
+
+impl std::fmt::Display for TieredAzureStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.inner())
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for TieredAzureStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        mut opts: PutOptions,
+    ) -> Result<PutResult> {
+        let tier = AccessTier::take(&mut opts.attributes)?;
+        let result = self.inner().put_opts(location, payload, opts).await?;
+        if let Some(tier) = tier {
+            set_blob_tier(&self.store, location, tier).await?;
+        }
+        Ok(result)
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        mut opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let tier = AccessTier::take(&mut opts.attributes)?;
+        let upload = self.inner().put_multipart_opts(location, opts).await?;
+        match tier {
+            Some(tier) => Ok(Box::new(TieredUpload {
+                inner: upload,
+                store: self.store.clone(),
+                location: location.clone(),
+                tier,
+            })),
+            None => Ok(upload),
+        }
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner().get_opts(location, options).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner().delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.inner().delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner().list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner().list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner().list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner().rename_if_not_exists(from, to).await
+    }
+}
+
+/// A multipart upload that sets the access tier of the blob once it's complete.
+#[derive(Debug)]
+struct TieredUpload {
+    inner: Box<dyn MultipartUpload>,
+    store: Arc<PyAzureStore>,
+    location: Path,
+    tier: AccessTier,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for TieredUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.inner.put_part(data)
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self.inner.complete().await?;
+        set_blob_tier(&self.store, &self.location, self.tier).await?;
+        Ok(result)
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
@@ -0,0 +1,253 @@
+//! Observation of the failed requests of a store, with an optional circuit breaker failing new
+//! requests fast once too many requests have failed in a row.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use http::{Method, StatusCode, Uri};
+use object_store::client::{
+    HttpClient, HttpError, HttpErrorKind, HttpRequest, HttpResponse, HttpService,
+};
+use pyo3::exceptions::PyTypeError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyTuple;
+
+/// A user-provided callback called with each failed request attempt that is retryable.
+#[derive(Debug)]
+pub struct PyRetryCallback(PyObject);
+
+impl PyRetryCallback {
+    fn equals(&self, py: Python, other: &Self) -> PyResult<bool> {
+        self.0
+            .call_method1(py, "__eq__", PyTuple::new(py, vec![&other.0])?)?
+            .extract(py)
+    }
+}
+
+impl Clone for PyRetryCallback {
+    fn clone(&self) -> Self {
+        Python::with_gil(|py| Self(self.0.clone_ref(py)))
+    }
+}
+
+impl PartialEq for PyRetryCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Python::with_gil(|py| self.equals(py, other)).unwrap_or(false)
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyRetryCallback {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
+            return Err(PyTypeError::new_err(
+                "Expected on_retry to be a callable object.",
+            ));
+        }
+        Ok(Self(ob.clone().unbind()))
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyRetryCallback {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        (&self).into_pyobject(py)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &PyRetryCallback {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.0.bind(py).clone())
+    }
+}
+
+/// A failed request attempt, passed to the `on_retry` callback.
+#[derive(IntoPyObject)]
+struct PyRetryEvent {
+    #[pyo3(item)]
+    method: String,
+    #[pyo3(item)]
+    url: String,
+    #[pyo3(item)]
+    status: Option<u16>,
+    #[pyo3(item)]
+    error: Option<String>,
+    #[pyo3(item)]
+    consecutive_failures: usize,
+}
+
+/// Whether a response status is an error that is retried, by `object_store` for server errors or
+/// by the throttle retry policy for throttled responses.
+fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Whether a request error is one that `object_store` may retry.
+fn is_retryable_error(err: &HttpError) -> bool {
+    matches!(
+        err.kind(),
+        HttpErrorKind::Connect
+            | HttpErrorKind::Request
+            | HttpErrorKind::Timeout
+            | HttpErrorKind::Interrupted
+    )
+}
+
+/// The URL of a request without its query string, which may hold credentials such as SAS tokens.
+fn redacted_url(uri: &Uri) -> String {
+    match (uri.scheme_str(), uri.authority()) {
+        (Some(scheme), Some(authority)) => format!("{}://{}{}", scheme, authority, uri.path()),
+        _ => uri.path().to_string(),
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: usize,
+    opened_at: Option<Instant>,
+}
+
+/// The failures of the requests of a store, shared by all the HTTP clients of the store.
+#[derive(Debug)]
+pub(crate) struct RetryMonitor {
+    on_retry: Option<PyRetryCallback>,
+    fail_fast_after: Option<usize>,
+    fail_fast_cooldown: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl RetryMonitor {
+    pub(crate) fn new(
+        on_retry: Option<PyRetryCallback>,
+        fail_fast_after: Option<usize>,
+        fail_fast_cooldown: Duration,
+    ) -> Self {
+        Self {
+            on_retry,
+            fail_fast_after,
+            fail_fast_cooldown,
+            state: Default::default(),
+        }
+    }
+
+    /// Wrap `client` so that its failed requests are reported to this monitor.
+    pub(crate) fn wrap(&self, client: HttpClient) -> HttpClient {
+        HttpClient::new(RetryMonitorService {
+            client,
+            on_retry: self.on_retry.clone(),
+            fail_fast_after: self.fail_fast_after,
+            fail_fast_cooldown: self.fail_fast_cooldown,
+            state: self.state.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct RetryMonitorService {
+    client: HttpClient,
+    on_retry: Option<PyRetryCallback>,
+    fail_fast_after: Option<usize>,
+    fail_fast_cooldown: Duration,
+    state: Arc<Mutex<BreakerState>>,
+}
+
+impl RetryMonitorService {
+    /// Fail if the circuit breaker is open.
+    ///
+    /// Once the cooldown has passed, requests are let through again, but the next failure opens
+    /// the breaker right away.
+    fn check_breaker(&self) -> Result<(), HttpError> {
+        let Some(fail_fast_after) = self.fail_fast_after else {
+            return Ok(());
+        };
+        let mut state = self.state.lock().unwrap();
+        let Some(opened_at) = state.opened_at else {
+            return Ok(());
+        };
+        let elapsed = opened_at.elapsed();
+        if elapsed >= self.fail_fast_cooldown {
+            state.opened_at = None;
+            state.consecutive_failures = fail_fast_after.saturating_sub(1);
+            return Ok(());
+        }
+        // Not retried by object_store, so that the request fails right away
+        Err(HttpError::new(
+            HttpErrorKind::Unknown,
+            std::io::Error::other(format!(
+                "Failing fast after {} consecutive failed requests, for another {:?}",
+                state.consecutive_failures,
+                self.fail_fast_cooldown - elapsed
+            )),
+        ))
+    }
+
+    fn record_success(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.consecutive_failures = 0;
+        state.opened_at = None;
+    }
+
+    fn record_failure(
+        &self,
+        method: &Method,
+        uri: &Uri,
+        status: Option<StatusCode>,
+        error: Option<String>,
+    ) {
+        let consecutive_failures = {
+            let mut state = self.state.lock().unwrap();
+            state.consecutive_failures += 1;
+            if self
+                .fail_fast_after
+                .is_some_and(|max| state.consecutive_failures >= max)
+            {
+                state.opened_at.get_or_insert_with(Instant::now);
+            }
+            state.consecutive_failures
+        };
+        if let Some(on_retry) = &self.on_retry {
+            let event = PyRetryEvent {
+                method: method.to_string(),
+                url: redacted_url(uri),
+                status: status.map(|status| status.as_u16()),
+                error,
+                consecutive_failures,
+            };
+            Python::with_gil(|py| {
+                if let Err(err) = on_retry.0.call1(py, (event,)) {
+                    err.write_unraisable(py, None);
+                }
+            });
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpService for RetryMonitorService {
+    async fn call(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        self.check_breaker()?;
+        let (method, uri) = (req.method().clone(), req.uri().clone());
+        match self.client.execute(req).await {
+            Ok(response) if is_retryable_status(response.status()) => {
+                self.record_failure(&method, &uri, Some(response.status()), None);
+                Ok(response)
+            }
+            Ok(response) => {
+                self.record_success();
+                Ok(response)
+            }
+            Err(err) if is_retryable_error(&err) => {
+                self.record_failure(&method, &uri, None, Some(err.to_string()));
+                Err(err)
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
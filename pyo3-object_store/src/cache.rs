@@ -0,0 +1,664 @@
+//! An object store wrapper that caches whole objects on local disk.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    Attribute, AttributeValue, Attributes, GetOptions, GetRange, GetResult, GetResultPayload,
+    ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions,
+    PutPayload, PutResult, Result,
+};
+use pyo3::prelude::*;
+use serde_json::{json, Value};
+
+use crate::{PyObjectStore, PyObjectStoreResult};
+
+const STORE: &str = "CacheStore";
+
+fn cache_error(source: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: source.into(),
+    }
+}
+
+/// Run the blocking `f` on a thread for blocking work.
+async fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| object_store::Error::JoinError { source: err })?
+}
+
+/// The key of a path in the cache, which names its files in the cache directory.
+///
+/// This is the 64-bit FNV-1a hash of the path. Since distinct paths may collide, each entry
+/// records its full path, which is checked on lookup.
+fn cache_key(location: &Path) -> u64 {
+    location
+        .as_ref()
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+fn attribute_name(attribute: &Attribute) -> Option<String> {
+    let name = match attribute {
+        Attribute::ContentDisposition => "Content-Disposition".to_string(),
+        Attribute::ContentEncoding => "Content-Encoding".to_string(),
+        Attribute::ContentLanguage => "Content-Language".to_string(),
+        Attribute::ContentType => "Content-Type".to_string(),
+        Attribute::CacheControl => "Cache-Control".to_string(),
+        Attribute::Metadata(key) => format!("Metadata:{}", key),
+        _ => return None,
+    };
+    Some(name)
+}
+
+fn attribute_from_name(name: &str) -> Option<Attribute> {
+    let attribute = match name {
+        "Content-Disposition" => Attribute::ContentDisposition,
+        "Content-Encoding" => Attribute::ContentEncoding,
+        "Content-Language" => Attribute::ContentLanguage,
+        "Content-Type" => Attribute::ContentType,
+        "Cache-Control" => Attribute::CacheControl,
+        _ => Attribute::Metadata(name.strip_prefix("Metadata:")?.to_string().into()),
+    };
+    Some(attribute)
+}
+
+/// An object stored in the cache directory.
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    meta: ObjectMeta,
+    attributes: Attributes,
+}
+
+impl CacheEntry {
+    fn to_json(&self) -> Value {
+        let attributes = self
+            .attributes
+            .iter()
+            .filter_map(|(attribute, value)| {
+                Some((attribute_name(attribute)?, Value::from(value.as_ref())))
+            })
+            .collect::<serde_json::Map<_, _>>();
+        json!({
+            "path": self.meta.location.as_ref(),
+            "last_modified": self.meta.last_modified.to_rfc3339(),
+            "size": self.meta.size,
+            "e_tag": self.meta.e_tag,
+            "version": self.meta.version,
+            "attributes": attributes,
+        })
+    }
+
+    fn from_json(value: &Value) -> Option<Self> {
+        let optional_string = |key: &str| value.get(key)?.as_str().map(String::from);
+        let meta = ObjectMeta {
+            location: Path::parse(value.get("path")?.as_str()?).ok()?,
+            last_modified: DateTime::parse_from_rfc3339(value.get("last_modified")?.as_str()?)
+                .ok()?
+                .with_timezone(&Utc),
+            size: value.get("size")?.as_u64()?,
+            e_tag: optional_string("e_tag"),
+            version: optional_string("version"),
+        };
+        let attributes = value
+            .get("attributes")?
+            .as_object()?
+            .iter()
+            .filter_map(|(name, value)| {
+                Some((
+                    attribute_from_name(name)?,
+                    AttributeValue::from(value.as_str()?.to_string()),
+                ))
+            })
+            .collect();
+        Some(Self { meta, attributes })
+    }
+}
+
+/// The entries of the cache, in order of last use.
+#[derive(Debug, Default)]
+struct CacheIndex {
+    /// The entry of each key, and its position in `lru`
+    entries: HashMap<u64, (CacheEntry, u64)>,
+    /// The key of each entry, from least to most recently used
+    lru: BTreeMap<u64, u64>,
+    /// The total size of all entries
+    size: u64,
+    tick: u64,
+}
+
+impl CacheIndex {
+    /// Look up the entry for `location`, marking it as most recently used.
+    fn get(&mut self, key: u64, location: &Path) -> Option<CacheEntry> {
+        let tick = self.next_tick();
+        let (entry, last_used) = self.entries.get_mut(&key)?;
+        if entry.meta.location != *location {
+            return None;
+        }
+        self.lru.remove(&*last_used);
+        self.lru.insert(tick, key);
+        *last_used = tick;
+        Some(entry.clone())
+    }
+
+    /// Insert an entry as the most recently used, then evict the least recently used entries
+    /// until the total size is at most `max_size`. Returns the keys of the evicted entries.
+    fn insert(&mut self, key: u64, entry: CacheEntry, max_size: u64) -> Vec<u64> {
+        self.remove(key);
+        let tick = self.next_tick();
+        self.size += entry.meta.size;
+        self.entries.insert(key, (entry, tick));
+        self.lru.insert(tick, key);
+
+        let mut evicted = vec![];
+        while self.size > max_size {
+            let Some((_, oldest)) = self.lru.first_key_value() else {
+                break;
+            };
+            let oldest = *oldest;
+            self.remove(oldest);
+            evicted.push(oldest);
+        }
+        evicted
+    }
+
+    fn remove(&mut self, key: u64) -> bool {
+        match self.entries.remove(&key) {
+            Some((entry, last_used)) => {
+                self.lru.remove(&last_used);
+                self.size -= entry.meta.size;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn keys(&self) -> Vec<u64> {
+        self.entries.keys().copied().collect()
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+}
+
+/// Store wrapper that caches whole objects on local disk, evicting the least recently used
+/// objects once the cache exceeds a maximum size.
+///
+/// Every read of a cached object is validated against the wrapped store with a conditional
+/// request on its e-tag, so only the metadata is transferred while the cached copy is current.
+/// Objects are only cached by full reads. Ranged reads are served from the cached object if
+/// there is a current one, and are otherwise passed through, so that reading a small range of a
+/// large object doesn't download all of it. Objects larger than the cache, or without an e-tag,
+/// are never cached.
+///
+/// Writes through this store invalidate the cached copies of the affected paths. The contents of
+/// the cache directory persist, so that later instances using the same directory start warm.
+#[derive(Debug)]
+pub struct CacheStore {
+    inner: Arc<dyn ObjectStore>,
+    dir: PathBuf,
+    max_size: u64,
+    index: Mutex<CacheIndex>,
+    /// Counter for naming temporary files
+    tmp_counter: AtomicU64,
+}
+
+impl std::fmt::Display for CacheStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CacheStore({}, {})", self.inner, self.dir.display())
+    }
+}
+
+impl CacheStore {
+    /// Create a new [`CacheStore`] wrapping `inner`, using `dir` as its cache directory.
+    ///
+    /// The directory is created if it doesn't exist. Any objects cached in it by a previous
+    /// instance are reused, evicting the oldest of them if they exceed `max_size`.
+    pub fn new(inner: Arc<dyn ObjectStore>, dir: PathBuf, max_size: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        let store = Self {
+            inner,
+            dir,
+            max_size,
+            index: Mutex::new(CacheIndex::default()),
+            tmp_counter: AtomicU64::new(0),
+        };
+        store.load()?;
+        Ok(store)
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// Remove every cached object.
+    pub fn clear(&self) {
+        let keys = self.index.lock().unwrap().keys();
+        for key in keys {
+            if self.index.lock().unwrap().remove(key) {
+                self.remove_files(key);
+            }
+        }
+    }
+
+    fn data_path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}", key))
+    }
+
+    fn meta_path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.json", key))
+    }
+
+    fn tmp_path(&self, key: u64) -> PathBuf {
+        let n = self.tmp_counter.fetch_add(1, Ordering::Relaxed);
+        self.dir
+            .join(format!("{:016x}.{}.{}.tmp", key, std::process::id(), n))
+    }
+
+    /// Index the objects already in the cache directory, from least to most recently modified.
+    fn load(&self) -> std::io::Result<()> {
+        let mut found = vec![];
+        for dir_entry in std::fs::read_dir(&self.dir)? {
+            let meta_path = dir_entry?.path();
+            let Some(key) = meta_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_suffix(".json"))
+                .and_then(|name| u64::from_str_radix(name, 16).ok())
+            else {
+                continue;
+            };
+            let entry = std::fs::read(&meta_path)
+                .ok()
+                .and_then(|contents| serde_json::from_slice::<Value>(&contents).ok())
+                .and_then(|value| CacheEntry::from_json(&value));
+            let modified = std::fs::metadata(self.data_path(key))
+                .ok()
+                .filter(|data| entry.as_ref().map(|entry| entry.meta.size) == Some(data.len()))
+                .and_then(|data| data.modified().ok());
+            match (entry, modified) {
+                (Some(entry), Some(modified)) => found.push((modified, key, entry)),
+                // The entry is incomplete or corrupt
+                _ => self.remove_files(key),
+            }
+        }
+        found.sort_by_key(|(modified, _, _)| *modified);
+
+        let mut index = self.index.lock().unwrap();
+        let mut evicted = vec![];
+        for (_, key, entry) in found {
+            evicted.extend(index.insert(key, entry, self.max_size));
+        }
+        drop(index);
+        for key in evicted {
+            self.remove_files(key);
+        }
+        Ok(())
+    }
+
+    fn remove_files(&self, key: u64) {
+        // A reader that already opened the data file keeps reading it on Unix, and on Windows
+        // the removal fails and the files are replaced on the next write instead.
+        let _ = std::fs::remove_file(self.meta_path(key));
+        let _ = std::fs::remove_file(self.data_path(key));
+    }
+
+    /// Remove the files of `keys` on a thread for blocking work.
+    async fn remove_files_async(&self, keys: Vec<u64>) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+        let paths: Vec<_> = keys
+            .into_iter()
+            .flat_map(|key| [self.meta_path(key), self.data_path(key)])
+            .collect();
+        spawn_blocking(move || {
+            for path in paths {
+                let _ = std::fs::remove_file(path);
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn invalidate_key(&self, key: u64) -> Result<()> {
+        let removed = self.index.lock().unwrap().remove(key);
+        if removed {
+            self.remove_files_async(vec![key]).await?;
+        }
+        Ok(())
+    }
+
+    async fn invalidate(&self, location: &Path) -> Result<()> {
+        self.invalidate_key(cache_key(location)).await
+    }
+
+    /// Write the body of `result` to the cache directory and index it.
+    async fn fill(&self, key: u64, result: GetResult) -> Result<CacheEntry> {
+        let entry = CacheEntry {
+            meta: result.meta.clone(),
+            attributes: result.attributes.clone(),
+        };
+        let tmp_path = self.tmp_path(key);
+        let written = write_file(tmp_path.clone(), result.into_stream()).await;
+        if let Err(err) = written {
+            spawn_blocking(move || {
+                let _ = std::fs::remove_file(&tmp_path);
+                Ok(())
+            })
+            .await?;
+            return Err(err);
+        }
+
+        // The metadata file is written last, so that an entry is only loaded by a later instance
+        // once its data is complete.
+        let meta_path = self.meta_path(key);
+        let data_path = self.data_path(key);
+        let meta_tmp_path = self.tmp_path(key);
+        let meta_json = entry.to_json().to_string();
+        spawn_blocking(move || {
+            let _ = std::fs::remove_file(&meta_path);
+            std::fs::rename(&tmp_path, data_path).map_err(cache_error)?;
+            std::fs::write(&meta_tmp_path, meta_json).map_err(cache_error)?;
+            std::fs::rename(&meta_tmp_path, meta_path).map_err(cache_error)
+        })
+        .await?;
+
+        let evicted = self
+            .index
+            .lock()
+            .unwrap()
+            .insert(key, entry.clone(), self.max_size);
+        self.remove_files_async(evicted).await?;
+        Ok(entry)
+    }
+
+    /// Serve `range` of a cached object from disk.
+    async fn open(
+        &self,
+        key: u64,
+        entry: &CacheEntry,
+        range: Option<&GetRange>,
+    ) -> Result<GetResult> {
+        let range = match range {
+            Some(range) => range.as_range(entry.meta.size).map_err(cache_error)?,
+            None => 0..entry.meta.size,
+        };
+        let path = self.data_path(key);
+        let (file, path) = spawn_blocking(move || {
+            let file = std::fs::File::open(&path).map_err(cache_error)?;
+            Ok((file, path))
+        })
+        .await?;
+        Ok(GetResult {
+            payload: GetResultPayload::File(file, path),
+            meta: entry.meta.clone(),
+            range,
+            attributes: entry.attributes.clone(),
+        })
+    }
+
+    /// Serve a ranged read, from the cached object if it's current, and otherwise from the
+    /// wrapped store without caching.
+    async fn get_range_opts(
+        &self,
+        key: u64,
+        location: &Path,
+        cached: Option<CacheEntry>,
+        options: GetOptions,
+    ) -> Result<GetResult> {
+        let Some(entry) = cached else {
+            return self.inner.get_opts(location, options).await;
+        };
+        let request = GetOptions {
+            if_none_match: entry.meta.e_tag.clone(),
+            head: true,
+            extensions: options.extensions.clone(),
+            ..Default::default()
+        };
+        match self.inner.get_opts(location, request).await {
+            Err(object_store::Error::NotModified { .. }) => {}
+            Err(err) => {
+                if matches!(err, object_store::Error::NotFound { .. }) {
+                    self.invalidate_key(key).await?;
+                }
+                return Err(err);
+            }
+            Ok(_) => {
+                self.invalidate_key(key).await?;
+                return self.inner.get_opts(location, options).await;
+            }
+        }
+
+        match self.open(key, &entry, options.range.as_ref()).await {
+            Ok(result) => Ok(result),
+            // The entry was evicted in the meantime, or the range is invalid, in which case the
+            // wrapped store raises the appropriate error
+            Err(_) => self.inner.get_opts(location, options).await,
+        }
+    }
+}
+
+/// Write `stream` to a new file at `path`.
+async fn write_file(
+    path: PathBuf,
+    mut stream: BoxStream<'static, Result<bytes::Bytes>>,
+) -> Result<()> {
+    let mut file =
+        spawn_blocking(move || std::fs::File::create(&path).map_err(cache_error)).await?;
+    while let Some(chunk) = stream.try_next().await? {
+        file = tokio::task::spawn_blocking(move || {
+            file.write_all(&chunk)?;
+            Ok::<_, std::io::Error>(file)
+        })
+        .await
+        .map_err(cache_error)?
+        .map_err(cache_error)?;
+    }
+    Ok(())
+}
+
+/// Whether the response to a request with `options` can be served from the cache.
+///
+/// Only plain reads, optionally of a range, are cached. Any other request is passed through.
+fn is_cacheable(options: &GetOptions) -> bool {
+    !options.head
+        && options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for CacheStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.invalidate(location).await?;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        // A read that caches the previous version before the upload completes is caught by the
+        // e-tag validation of the next read.
+        self.invalidate(location).await?;
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        if !is_cacheable(&options) {
+            return self.inner.get_opts(location, options).await;
+        }
+
+        let key = cache_key(location);
+        let cached = self.index.lock().unwrap().get(key, location);
+        if options.range.is_some() {
+            return self.get_range_opts(key, location, cached, options).await;
+        }
+
+        let request = GetOptions {
+            if_none_match: cached.as_ref().and_then(|entry| entry.meta.e_tag.clone()),
+            extensions: options.extensions.clone(),
+            ..Default::default()
+        };
+        let entry = match (cached, self.inner.get_opts(location, request).await) {
+            (Some(entry), Err(object_store::Error::NotModified { .. })) => entry,
+            (_, Err(err)) => {
+                if matches!(err, object_store::Error::NotFound { .. }) {
+                    self.invalidate_key(key).await?;
+                }
+                return Err(err);
+            }
+            (_, Ok(result)) => {
+                if result.meta.e_tag.is_none() || result.meta.size > self.max_size {
+                    self.invalidate_key(key).await?;
+                    return Ok(result);
+                }
+                self.fill(key, result).await?
+            }
+        };
+
+        match self.open(key, &entry, None).await {
+            Ok(result) => Ok(result),
+            // The entry was evicted in the meantime
+            Err(_) => self.inner.get_opts(location, options).await,
+        }
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.invalidate(location).await?;
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let locations = locations
+            .and_then(move |location| async move {
+                self.invalidate(&location).await?;
+                Ok(location)
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(to).await?;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(from).await?;
+        self.invalidate(to).await?;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(to).await?;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(from).await?;
+        self.invalidate(to).await?;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+/// A Python-facing wrapper around a [`CacheStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "CacheStore", frozen, subclass)]
+pub struct PyCacheStore(Arc<CacheStore>);
+
+impl AsRef<Arc<CacheStore>> for PyCacheStore {
+    fn as_ref(&self) -> &Arc<CacheStore> {
+        &self.0
+    }
+}
+
+impl PyCacheStore {
+    /// Consume self and return the underlying [`CacheStore`].
+    pub fn into_inner(self) -> Arc<CacheStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyCacheStore {
+    #[new]
+    fn py_new(
+        store: PyObjectStore,
+        cache_dir: PathBuf,
+        max_size: u64,
+    ) -> PyObjectStoreResult<Self> {
+        Ok(Self(Arc::new(CacheStore::new(
+            store.into_dyn(),
+            cache_dir,
+            max_size,
+        )?)))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[getter]
+    fn cache_dir(&self) -> PathBuf {
+        self.0.dir.clone()
+    }
+
+    #[getter]
+    fn max_size(&self) -> u64 {
+        self.0.max_size
+    }
+
+    #[getter]
+    fn size(&self) -> u64 {
+        self.0.index.lock().unwrap().size
+    }
+
+    fn clear(&self) {
+        self.0.clear()
+    }
+}
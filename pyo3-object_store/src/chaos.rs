@@ -0,0 +1,261 @@
+//! An object store wrapper that injects latency and random failures, for testing.
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::PyObjectStore;
+
+/// Decides the latency and outcome of each call.
+#[derive(Debug)]
+struct Chaos {
+    failure_rate: f64,
+    latency: Duration,
+    rng: Mutex<StdRng>,
+}
+
+impl Chaos {
+    /// Sleep for the configured latency, then fail the call at random.
+    async fn call(&self, operation: &str) -> Result<()> {
+        if !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        let fail =
+            self.failure_rate > 0.0 && self.rng.lock().unwrap().random::<f64>() < self.failure_rate;
+        if fail {
+            return Err(object_store::Error::Generic {
+                store: "ChaosStore",
+                source: format!("Injected failure in {}", operation).into(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Store wrapper that delays every call by a fixed latency and fails a random fraction of calls.
+///
+/// Streams returned by `list` fail before their first entry. Each part of a multipart upload may
+/// fail independently.
+#[derive(Debug)]
+pub struct ChaosStore {
+    inner: Arc<dyn ObjectStore>,
+    chaos: Arc<Chaos>,
+}
+
+impl std::fmt::Display for ChaosStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ChaosStore({})", self.inner)
+    }
+}
+
+impl ChaosStore {
+    /// Create a new [`ChaosStore`] wrapping `inner`. Pass a `seed` to make the sequence of
+    /// failures reproducible.
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        failure_rate: f64,
+        latency: Duration,
+        seed: Option<u64>,
+    ) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_os_rng(),
+        };
+        Self {
+            inner,
+            chaos: Arc::new(Chaos {
+                failure_rate,
+                latency,
+                rng: Mutex::new(rng),
+            }),
+        }
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// Apply chaos before the first entry of `stream`.
+    fn chaos_stream<'a, T: Send + 'a>(
+        &self,
+        operation: &'static str,
+        stream: BoxStream<'a, Result<T>>,
+    ) -> BoxStream<'a, Result<T>> {
+        let chaos = self.chaos.clone();
+        futures::stream::once(async move {
+            match chaos.call(operation).await {
+                Ok(()) => stream,
+                Err(err) => futures::stream::once(async move { Err(err) }).boxed(),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for ChaosStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.chaos.call("put").await?;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.chaos.call("put_multipart").await?;
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(ChaosUpload {
+            inner: upload,
+            chaos: self.chaos.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.chaos.call("get").await?;
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.chaos.call("delete").await?;
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.chaos_stream("delete", self.inner.delete_stream(locations))
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.chaos_stream("list", self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.chaos_stream("list", self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.chaos.call("list_with_delimiter").await?;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.chaos.call("copy").await?;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.chaos.call("rename").await?;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.chaos.call("copy").await?;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.chaos.call("rename").await?;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+/// A multipart upload whose parts are subject to the chaos of the store.
+#[derive(Debug)]
+struct ChaosUpload {
+    inner: Box<dyn MultipartUpload>,
+    chaos: Arc<Chaos>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for ChaosUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let part = self.inner.put_part(data);
+        let chaos = self.chaos.clone();
+        Box::pin(async move {
+            chaos.call("put_part").await?;
+            part.await
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        self.chaos.call("complete_multipart").await?;
+        self.inner.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
+
+/// A Python-facing wrapper around a [`ChaosStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "ChaosStore", frozen, subclass)]
+pub struct PyChaosStore(Arc<ChaosStore>);
+
+impl AsRef<Arc<ChaosStore>> for PyChaosStore {
+    fn as_ref(&self) -> &Arc<ChaosStore> {
+        &self.0
+    }
+}
+
+impl PyChaosStore {
+    /// Consume self and return the underlying [`ChaosStore`].
+    pub fn into_inner(self) -> Arc<ChaosStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyChaosStore {
+    #[new]
+    #[pyo3(signature = (store, *, failure_rate=0.0, latency=Duration::ZERO, seed=None))]
+    fn py_new(
+        store: PyObjectStore,
+        failure_rate: f64,
+        latency: Duration,
+        seed: Option<u64>,
+    ) -> PyResult<Self> {
+        if !(0.0..=1.0).contains(&failure_rate) {
+            return Err(PyValueError::new_err(format!(
+                "failure_rate must be between 0 and 1, got {}",
+                failure_rate
+            )));
+        }
+        Ok(Self(Arc::new(ChaosStore::new(
+            store.into_dyn(),
+            failure_rate,
+            latency,
+            seed,
+        ))))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
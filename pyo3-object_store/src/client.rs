@@ -1,9 +1,12 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use object_store::{ClientConfigKey, ClientOptions};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
-use pyo3::types::PyString;
+use pyo3::types::{PyDict, PyString};
+use url::Url;
 
 use crate::config::PyConfigValue;
 use crate::error::PyObjectStoreError;
@@ -40,16 +43,164 @@ impl<'py> IntoPyObject<'py> for &PyClientConfigKey {
     }
 }
 
+/// The client option enabling the logging of every request, which isn't an option of
+/// `object_store`.
+const LOG_REQUESTS: &str = "log_requests";
+
+/// The client option bounding how long connections are reused, which isn't an option of
+/// `object_store`.
+const CONNECTION_MAX_LIFETIME: &str = "connection_max_lifetime";
+
 /// A wrapper around `ClientOptions` that implements [`FromPyObject`].
-#[derive(Clone, Debug, FromPyObject, IntoPyObject, IntoPyObjectRef, PartialEq)]
-pub struct PyClientOptions(HashMap<PyClientConfigKey, PyConfigValue>);
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PyClientOptions {
+    options: HashMap<PyClientConfigKey, PyConfigValue>,
+    log_requests: bool,
+    connection_max_lifetime: Option<Duration>,
+}
+
+impl PyClientOptions {
+    /// Whether every request of the store should be logged.
+    pub(crate) fn log_requests(&self) -> bool {
+        self.log_requests
+    }
+
+    /// How long the connections of the store may be reused before they're replaced.
+    pub(crate) fn connection_max_lifetime(&self) -> Option<Duration> {
+        self.connection_max_lifetime
+    }
+
+    /// The `connect_timeout` option, for stores that open their own connections.
+    #[cfg(feature = "sftp")]
+    pub(crate) fn connect_timeout(&self) -> PyResult<Option<Duration>> {
+        self.options
+            .get(&PyClientConfigKey(ClientConfigKey::ConnectTimeout))
+            .map(|value| {
+                humantime::parse_duration(value.as_ref()).map_err(|err| {
+                    PyValueError::new_err(format!(
+                        "Invalid value for {}: {}",
+                        ClientConfigKey::ConnectTimeout.as_ref(),
+                        err
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// The options without the password of the proxy URL, for `to_token`.
+    pub(crate) fn without_secrets(&self) -> Self {
+        let mut options = self.clone();
+        if let Some(proxy_url) = options
+            .options
+            .get_mut(&PyClientConfigKey(ClientConfigKey::ProxyUrl))
+        {
+            if let Ok(mut url) = Url::parse(proxy_url.as_ref()) {
+                if url.password().is_some() {
+                    let _ = url.set_password(None);
+                    *proxy_url = PyConfigValue::new(url);
+                }
+            }
+        }
+        options
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyClientOptions {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let mut client_options = Self::default();
+        for (key, value) in ob.downcast::<PyDict>()?.iter() {
+            let key_str = key.extract::<PyBackedStr>()?;
+            if key_str.eq_ignore_ascii_case(LOG_REQUESTS) {
+                client_options.log_requests = value.extract()?;
+            } else if key_str.eq_ignore_ascii_case(CONNECTION_MAX_LIFETIME) {
+                let value = value.extract::<PyConfigValue>()?;
+                let lifetime = humantime::parse_duration(value.as_ref()).map_err(|err| {
+                    PyValueError::new_err(format!(
+                        "Invalid value for {}: {}",
+                        CONNECTION_MAX_LIFETIME, err
+                    ))
+                })?;
+                client_options.connection_max_lifetime = Some(lifetime);
+            } else {
+                client_options
+                    .options
+                    .insert(key.extract()?, value.extract()?);
+            }
+        }
+        Ok(client_options)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &PyClientOptions {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let dict = (&self.options).into_pyobject(py)?;
+        if self.log_requests {
+            dict.set_item(LOG_REQUESTS, true)?;
+        }
+        if let Some(lifetime) = self.connection_max_lifetime {
+            dict.set_item(CONNECTION_MAX_LIFETIME, PyConfigValue::from(lifetime))?;
+        }
+        Ok(dict)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyClientOptions {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        (&self).into_pyobject(py)
+    }
+}
 
 impl From<PyClientOptions> for ClientOptions {
     fn from(value: PyClientOptions) -> Self {
         let mut options = ClientOptions::new();
-        for (key, value) in value.0.into_iter() {
+        for (key, value) in value.options.into_iter() {
             options = options.with_config(key.0, value.0);
         }
         options
     }
 }
+
+/// Every option of [`ClientOptions`] that can be set from a config value.
+const CLIENT_CONFIG_KEYS: [ClientConfigKey; 18] = [
+    ClientConfigKey::AllowHttp,
+    ClientConfigKey::AllowInvalidCertificates,
+    ClientConfigKey::ConnectTimeout,
+    ClientConfigKey::DefaultContentType,
+    ClientConfigKey::Http1Only,
+    ClientConfigKey::Http2KeepAliveInterval,
+    ClientConfigKey::Http2KeepAliveTimeout,
+    ClientConfigKey::Http2KeepAliveWhileIdle,
+    ClientConfigKey::Http2MaxFrameSize,
+    ClientConfigKey::Http2Only,
+    ClientConfigKey::PoolIdleTimeout,
+    ClientConfigKey::PoolMaxIdlePerHost,
+    ClientConfigKey::ProxyUrl,
+    ClientConfigKey::ProxyCaCertificate,
+    ClientConfigKey::ProxyExcludes,
+    ClientConfigKey::RandomizeAddresses,
+    ClientConfigKey::Timeout,
+    ClientConfigKey::UserAgent,
+];
+
+/// The [`ClientOptions`] of a store, read back from its builder with `value`.
+///
+/// Builders don't expose their client options, only the config value of each of them, which
+/// combines the environment, the client options and the config of the store.
+pub(crate) fn client_options_from_builder(
+    value: impl Fn(ClientConfigKey) -> Option<String>,
+) -> ClientOptions {
+    CLIENT_CONFIG_KEYS
+        .into_iter()
+        .fold(ClientOptions::new(), |options, key| match value(key) {
+            Some(value) => options.with_config(key, value),
+            None => options,
+        })
+}
@@ -0,0 +1,92 @@
+//! The HTTP connector of a store, for the client behavior that `object_store` doesn't support.
+
+use std::time::Duration;
+
+use object_store::client::{HttpClient, HttpConnector};
+use object_store::ClientOptions;
+
+use crate::breaker::RetryMonitor;
+use crate::client::PyClientOptions;
+use crate::headers::{headers_client, RequestHeaders};
+use crate::pool::{ConnectionPool, RecyclingClient};
+use crate::request_log::logging_client;
+use crate::resolver::PyResolver;
+use crate::retry::{PyRetryConfig, ServerErrorRetryPolicy, ThrottleRetryPolicy};
+use crate::tags::{PyRequestTags, TagStyle};
+
+/// An [`HttpConnector`] whose clients resolve hostnames with a user-provided resolver, recycle
+/// their connections, send the extra headers of the store and of each request, log and report
+/// failed requests, tag every request, and retry error responses, throttled ones with their own
+/// policy.
+#[derive(Debug)]
+pub(crate) struct StoreConnector {
+    resolver: Option<PyResolver>,
+    pool: ConnectionPool,
+    connection_max_lifetime: Option<Duration>,
+    log_requests: bool,
+    retry_monitor: Option<RetryMonitor>,
+    request_tags: Option<(PyRequestTags, TagStyle)>,
+    throttle_policy: Option<ThrottleRetryPolicy>,
+    server_error_policy: ServerErrorRetryPolicy,
+    extra_headers: Option<RequestHeaders>,
+}
+
+impl StoreConnector {
+    /// The connector for a store, whose connections are reset through `pool`.
+    pub(crate) fn new(
+        pool: &ConnectionPool,
+        client_options: Option<&PyClientOptions>,
+        retry_config: Option<&PyRetryConfig>,
+        resolver: Option<&PyResolver>,
+        request_tags: Option<&PyRequestTags>,
+        tag_style: TagStyle,
+    ) -> Self {
+        Self {
+            resolver: resolver.cloned(),
+            pool: pool.clone(),
+            connection_max_lifetime: client_options
+                .and_then(|options| options.connection_max_lifetime()),
+            log_requests: client_options.is_some_and(|options| options.log_requests()),
+            retry_monitor: retry_config.and_then(|config| config.retry_monitor()),
+            request_tags: request_tags.map(|tags| (tags.clone(), tag_style)),
+            throttle_policy: retry_config.and_then(|config| config.throttle_policy()),
+            server_error_policy: PyRetryConfig::server_error_policy(retry_config),
+            extra_headers: None,
+        }
+    }
+
+    /// Send `extra_headers` with every request of the store.
+    pub(crate) fn with_extra_headers(mut self, extra_headers: Option<&RequestHeaders>) -> Self {
+        self.extra_headers = extra_headers.cloned();
+        self
+    }
+}
+
+impl HttpConnector for StoreConnector {
+    fn connect(&self, options: &ClientOptions) -> object_store::Result<HttpClient> {
+        let mut client = HttpClient::new(RecyclingClient::new(
+            options,
+            self.resolver.as_ref(),
+            self.connection_max_lifetime,
+            self.pool.clone(),
+        )?);
+        client = headers_client(client, self.extra_headers.clone());
+        // Innermost, so that requests retried by the throttle policy are logged and reported too
+        if self.log_requests {
+            client = logging_client(client);
+        }
+        if let Some(monitor) = &self.retry_monitor {
+            client = monitor.wrap(client);
+        }
+        if let Some((tags, style)) = &self.request_tags {
+            client = tags.wrap(*style, options, client);
+        }
+        if let Some(policy) = &self.throttle_policy {
+            client = policy.wrap(client);
+        }
+        // Outermost, so that `object_store` only sees the error of a request once its retries are
+        // exhausted, with the details of its last response
+        client = self.server_error_policy.wrap(client);
+        Ok(client)
+    }
+}
@@ -0,0 +1,376 @@
+//! An object store wrapper that provides read-after-write consistency for its own writes.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::prelude::*;
+
+use crate::PyObjectStore;
+
+/// The longest time to wait between two retries of a read.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// What a read of a path is expected to observe after a write through this store.
+#[derive(Debug, Clone)]
+enum Expected {
+    /// The object exists. If known, it has the given e-tag and version.
+    Written {
+        e_tag: Option<String>,
+        version: Option<String>,
+    },
+    /// The object was deleted.
+    Deleted,
+}
+
+impl Expected {
+    fn from_put(result: &PutResult) -> Self {
+        Self::Written {
+            e_tag: result.e_tag.clone(),
+            version: result.version.clone(),
+        }
+    }
+
+    /// An object exists, but its e-tag and version are unknown, e.g. after a copy.
+    fn exists() -> Self {
+        Self::Written {
+            e_tag: None,
+            version: None,
+        }
+    }
+
+    /// Whether `result` reflects the write.
+    fn is_observed(&self, result: &Result<GetResult>) -> bool {
+        match (self, result) {
+            (Self::Written { e_tag, version }, Ok(result)) => match (version, &result.meta.version)
+            {
+                (Some(expected), Some(actual)) => expected == actual,
+                _ => e_tag.is_none() || *e_tag == result.meta.e_tag,
+            },
+            (Self::Written { .. }, Err(object_store::Error::NotFound { .. })) => false,
+            (Self::Deleted, Ok(_)) => false,
+            // Any other error is returned as is
+            (_, Err(_)) => true,
+        }
+    }
+}
+
+/// The writes made through a [`ConsistentStore`], each of which is forgotten after a fixed time
+/// to live.
+#[derive(Debug)]
+struct WriteLog {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<Path, (Expected, Instant)>>,
+}
+
+impl WriteLog {
+    fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, location: &Path) -> Option<Expected> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(location) {
+            Some((expected, inserted)) if inserted.elapsed() < self.ttl => Some(expected.clone()),
+            Some(_) => {
+                entries.remove(location);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, location: &Path, expected: Expected) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(location) {
+            entries.retain(|_, (_, inserted)| inserted.elapsed() < self.ttl);
+        }
+        // If the log is still full of live entries, skip tracking rather than evicting them
+        if entries.len() < self.max_entries || entries.contains_key(location) {
+            entries.insert(location.clone(), (expected, Instant::now()));
+        }
+    }
+
+    /// Record the outcome of a write to `location`.
+    ///
+    /// If the write failed, its effect is unknown and the path is no longer tracked.
+    fn record<T>(
+        &self,
+        location: &Path,
+        result: &Result<T>,
+        expected: impl FnOnce(&T) -> Expected,
+    ) {
+        match result {
+            Ok(value) => self.insert(location, expected(value)),
+            Err(_) => self.remove(location),
+        }
+    }
+
+    fn remove(&self, location: &Path) {
+        self.entries.lock().unwrap().remove(location);
+    }
+
+    fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+
+/// Store wrapper that provides read-after-write consistency for writes made through it.
+///
+/// The e-tag and version of each object written through this store are remembered for a fixed
+/// time. A read of such an object that returns an older version, or reports that the object
+/// doesn't exist, is retried with exponential backoff until the written version is observed or
+/// the retries are exhausted, in which case the last response is returned. Likewise, a read of an
+/// object deleted through this store is retried until the store reports it as not found.
+///
+/// Listing is not affected.
+#[derive(Debug)]
+pub struct ConsistentStore {
+    inner: Arc<dyn ObjectStore>,
+    log: Arc<WriteLog>,
+    max_retries: usize,
+    retry_delay: Duration,
+}
+
+impl std::fmt::Display for ConsistentStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ConsistentStore({})", self.inner)
+    }
+}
+
+impl ConsistentStore {
+    /// Create a new [`ConsistentStore`] wrapping `inner`
+    pub fn new(
+        inner: Arc<dyn ObjectStore>,
+        max_retries: usize,
+        retry_delay: Duration,
+        ttl: Duration,
+        max_entries: usize,
+    ) -> Self {
+        Self {
+            inner,
+            log: Arc::new(WriteLog::new(ttl, max_entries)),
+            max_retries,
+            retry_delay,
+        }
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for ConsistentStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let result = self.inner.put_opts(location, payload, opts).await;
+        self.log.record(location, &result, Expected::from_put);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(TrackedUpload {
+            inner: upload,
+            log: self.log.clone(),
+            location: location.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        // Requests for a specific version already get that version
+        let expected = match options.version {
+            None => self.log.get(location),
+            Some(_) => None,
+        };
+        let Some(expected) = expected else {
+            return self.inner.get_opts(location, options).await;
+        };
+
+        let mut delay = self.retry_delay;
+        let mut retries = 0;
+        loop {
+            let result = self.inner.get_opts(location, options.clone()).await;
+            if retries >= self.max_retries || expected.is_observed(&result) {
+                return result;
+            }
+            tokio::time::sleep(delay).await;
+            delay = delay.saturating_mul(2).min(MAX_RETRY_DELAY);
+            retries += 1;
+        }
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let result = self.inner.delete(location).await;
+        match result {
+            // Deleting a missing object still leaves it deleted
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {
+                self.log.insert(location, Expected::Deleted)
+            }
+            Err(_) => self.log.remove(location),
+        }
+        result
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let log = self.log.clone();
+        self.inner
+            .delete_stream(locations)
+            .inspect(move |result| {
+                if let Ok(location) = result {
+                    log.insert(location, Expected::Deleted);
+                }
+            })
+            .boxed()
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.copy(from, to).await;
+        self.log.record(to, &result, |_| Expected::exists());
+        result
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.rename(from, to).await;
+        self.log.record(from, &result, |_| Expected::Deleted);
+        self.log.record(to, &result, |_| Expected::exists());
+        result
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.copy_if_not_exists(from, to).await;
+        self.log.record(to, &result, |_| Expected::exists());
+        result
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.rename_if_not_exists(from, to).await;
+        self.log.record(from, &result, |_| Expected::Deleted);
+        self.log.record(to, &result, |_| Expected::exists());
+        result
+    }
+}
+
+/// A multipart upload that records the written version of its path on completion.
+#[derive(Debug)]
+struct TrackedUpload {
+    inner: Box<dyn MultipartUpload>,
+    log: Arc<WriteLog>,
+    location: Path,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for TrackedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.inner.put_part(data)
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self.inner.complete().await;
+        self.log.record(&self.location, &result, Expected::from_put);
+        result
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
+
+/// A Python-facing wrapper around a [`ConsistentStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "ConsistentStore", frozen, subclass)]
+pub struct PyConsistentStore(Arc<ConsistentStore>);
+
+impl AsRef<Arc<ConsistentStore>> for PyConsistentStore {
+    fn as_ref(&self) -> &Arc<ConsistentStore> {
+        &self.0
+    }
+}
+
+impl PyConsistentStore {
+    /// Consume self and return the underlying [`ConsistentStore`].
+    pub fn into_inner(self) -> Arc<ConsistentStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyConsistentStore {
+    #[new]
+    #[pyo3(signature = (
+        store,
+        *,
+        max_retries=10,
+        retry_delay=Duration::from_millis(50),
+        ttl=Duration::from_secs(60),
+        max_entries=10_000,
+    ))]
+    fn py_new(
+        store: PyObjectStore,
+        max_retries: usize,
+        retry_delay: Duration,
+        ttl: Duration,
+        max_entries: usize,
+    ) -> Self {
+        Self(Arc::new(ConsistentStore::new(
+            store.into_dyn(),
+            max_retries,
+            retry_delay,
+            ttl,
+            max_entries,
+        )))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[pyo3(signature = (path=None))]
+    fn forget(&self, path: Option<String>) {
+        match path {
+            Some(path) => self.0.log.remove(&path.into()),
+            None => self.0.log.clear(),
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use chrono::Utc;
 use chrono::{DateTime, TimeDelta};
+use pyo3::exceptions::PyValueError;
 use pyo3::intern;
 use pyo3::prelude::*;
-use pyo3::types::PyTuple;
+use pyo3::types::{PyDict, PyList, PyTuple};
 use std::future::Future;
+use std::sync::{Arc, Weak};
 use tokio::sync::Mutex;
+use tokio::task::AbortHandle;
 
 /// A temporary authentication token with an associated expiry
 #[derive(Debug, Clone)]
@@ -26,6 +29,11 @@ pub(crate) struct TokenCache<T> {
     /// How long to wait before re-attempting a token fetch after receiving one that
     /// is still within the min-ttl
     fetch_backoff: TimeDelta,
+    /// Whether to fetch a new token in the background `min_ttl` before the cached one expires,
+    /// instead of when the next request finds it expiring
+    background_refresh: bool,
+    /// The task refreshing the token in the background, if any
+    refresh_task: std::sync::Mutex<Option<AbortHandle>>,
 }
 
 impl<T> Default for TokenCache<T> {
@@ -34,6 +42,8 @@ impl<T> Default for TokenCache<T> {
             cache: Default::default(),
             min_ttl: TimeDelta::seconds(300),
             fetch_backoff: TimeDelta::milliseconds(100),
+            background_refresh: false,
+            refresh_task: Default::default(),
         }
     }
 }
@@ -45,14 +55,43 @@ impl<T: Clone> Clone for TokenCache<T> {
             cache: Default::default(),
             min_ttl: self.min_ttl,
             fetch_backoff: self.fetch_backoff,
+            background_refresh: self.background_refresh,
+            refresh_task: Default::default(),
+        }
+    }
+}
+
+impl<T> Drop for TokenCache<T> {
+    fn drop(&mut self) {
+        if let Ok(task) = self.refresh_task.get_mut() {
+            if let Some(task) = task.take() {
+                task.abort();
+            }
         }
     }
 }
 
 impl<T: Clone + Send> TokenCache<T> {
     /// Override the minimum remaining TTL for a cached token to be used
-    pub(crate) fn with_min_ttl(self, min_ttl: TimeDelta) -> Self {
-        Self { min_ttl, ..self }
+    pub(crate) fn with_min_ttl(mut self, min_ttl: TimeDelta) -> Self {
+        self.min_ttl = min_ttl;
+        self
+    }
+
+    /// Refresh the token in the background `min_ttl` before it expires
+    pub(crate) fn with_background_refresh(mut self, background_refresh: bool) -> Self {
+        self.background_refresh = background_refresh;
+        self
+    }
+
+    /// The expiry of the cached token, if any.
+    async fn expiry(&self) -> Option<DateTime<Utc>> {
+        let locked = self.cache.lock().await;
+        locked.as_ref().and_then(|(cached, _)| cached.expiry)
+    }
+
+    async fn insert(&self, token: TemporaryToken<T>) {
+        *self.cache.lock().await = Some((token, Utc::now()));
     }
 
     pub(crate) async fn get_or_insert_with<F, Fut, E>(&self, f: F) -> Result<T, E>
@@ -91,6 +130,271 @@ impl<T: Clone + Send> TokenCache<T> {
     }
 }
 
+impl<T: Clone + Send + Sync + 'static> TokenCache<T> {
+    /// Whether a background refresh task should be started with [`Self::spawn_refresh`].
+    pub(crate) fn needs_refresh_task(&self) -> bool {
+        self.background_refresh
+            && self
+                .refresh_task
+                .lock()
+                .map(|task| task.as_ref().map_or(true, |task| task.is_finished()))
+                .unwrap_or(false)
+    }
+
+    /// Start a task that fetches a new token with `fetch` `min_ttl` before the cached one
+    /// expires, for as long as the cache is alive.
+    ///
+    /// The task stops when a fetch fails or returns a token that doesn't expire, in which case
+    /// the token is refreshed by the next request as usual.
+    pub(crate) fn spawn_refresh<F, Fut, E>(self: &Arc<Self>, fetch: F)
+    where
+        F: Fn() -> Fut + Send + 'static,
+        Fut: Future<Output = Result<TemporaryToken<T>, E>> + Send + 'static,
+        E: 'static,
+    {
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let Ok(mut task) = self.refresh_task.lock() else {
+            return;
+        };
+        if task.as_ref().is_some_and(|task| !task.is_finished()) {
+            return;
+        }
+        let cache = Arc::downgrade(self);
+        *task = Some(runtime.spawn(refresh_loop(cache, fetch)).abort_handle());
+    }
+}
+
+async fn refresh_loop<T, F, Fut, E>(cache: Weak<TokenCache<T>>, fetch: F)
+where
+    T: Clone + Send,
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<TemporaryToken<T>, E>>,
+{
+    loop {
+        // Don't keep the cache alive while waiting
+        let refresh_at = {
+            let Some(cache) = cache.upgrade() else {
+                return;
+            };
+            let Some(expiry) = cache.expiry().await else {
+                return;
+            };
+            expiry - cache.min_ttl
+        };
+        if let Ok(wait) = (refresh_at - Utc::now()).to_std() {
+            tokio::time::sleep(wait).await;
+        }
+
+        let Some(cache) = cache.upgrade() else {
+            return;
+        };
+        let Ok(token) = fetch().await else {
+            return;
+        };
+        // A token that is already within the min-ttl would be refreshed again straight away
+        let expiring = token
+            .expiry
+            .is_some_and(|expiry| expiry - Utc::now() <= cache.min_ttl);
+        cache.insert(token).await;
+        if expiring {
+            return;
+        }
+    }
+}
+
+/// Access to a token cache of any credential type, for [`PyCredentialProviderHandle`].
+pub(crate) trait CredentialCache: Send + Sync {
+    /// Drop the cached token, so that the next request fetches a new one.
+    ///
+    /// This must not be called from within the tokio runtime.
+    fn invalidate(&self);
+
+    /// The expiry of the cached token, if any.
+    ///
+    /// This must not be called from within the tokio runtime.
+    fn current_expiry(&self) -> Option<DateTime<Utc>>;
+}
+
+impl<T: Send + Sync> CredentialCache for TokenCache<T> {
+    fn invalidate(&self) {
+        *self.cache.blocking_lock() = None;
+    }
+
+    fn current_expiry(&self) -> Option<DateTime<Utc>> {
+        let locked = self.cache.blocking_lock();
+        locked.as_ref().and_then(|(cached, _)| cached.expiry)
+    }
+}
+
+/// The credential provider of a store, as returned by its `credential_provider` property.
+///
+/// This behaves like the user-provided callback, and gives access to the tokens the store has
+/// cached from it.
+#[pyclass(name = "CredentialProviderHandle", frozen)]
+pub struct PyCredentialProviderHandle {
+    user_callback: PyObject,
+    cache: Arc<dyn CredentialCache>,
+}
+
+impl PyCredentialProviderHandle {
+    pub(crate) fn new(user_callback: PyObject, cache: Arc<dyn CredentialCache>) -> Self {
+        Self {
+            user_callback,
+            cache,
+        }
+    }
+}
+
+#[pymethods]
+impl PyCredentialProviderHandle {
+    #[pyo3(signature = (*args, **kwargs))]
+    fn __call__(
+        &self,
+        py: Python,
+        args: &Bound<PyTuple>,
+        kwargs: Option<&Bound<PyDict>>,
+    ) -> PyResult<PyObject> {
+        self.user_callback.call(py, args, kwargs)
+    }
+
+    fn __getattr__(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        self.user_callback.getattr(py, name)
+    }
+
+    fn __eq__(&self, py: Python, other: &Bound<PyAny>) -> PyResult<bool> {
+        let other = match other.downcast::<Self>() {
+            Ok(other) => other.get().user_callback.bind(py).clone(),
+            Err(_) => other.clone(),
+        };
+        self.user_callback.bind(py).eq(other)
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "CredentialProviderHandle({})",
+            self.user_callback.bind(py).repr()?
+        ))
+    }
+
+    #[getter]
+    fn callback(&self, py: Python) -> PyObject {
+        self.user_callback.clone_ref(py)
+    }
+
+    fn current_expiry(&self, py: Python) -> Option<DateTime<Utc>> {
+        py.allow_threads(|| self.cache.current_expiry())
+    }
+
+    fn invalidate(&self, py: Python) {
+        py.allow_threads(|| self.cache.invalidate())
+    }
+}
+
+/// A credential provider that tries each of its providers in order until one returns a
+/// credential.
+///
+/// Each provider may be a callback or the name of a built-in provider of the store, as accepted
+/// by `credential_provider`.
+#[pyclass(name = "ChainCredentialProvider", frozen)]
+pub struct PyChainCredentialProvider {
+    providers: Vec<PyObject>,
+}
+
+#[pymethods]
+impl PyChainCredentialProvider {
+    #[new]
+    fn new(providers: Vec<PyObject>) -> PyResult<Self> {
+        if providers.is_empty() {
+            return Err(PyValueError::new_err(
+                "ChainCredentialProvider requires at least one provider.",
+            ));
+        }
+        Ok(Self { providers })
+    }
+
+    fn __getnewargs__(&self, py: Python) -> (Vec<PyObject>,) {
+        (self.providers(py),)
+    }
+
+    fn __eq__(&self, py: Python, other: &Bound<PyAny>) -> PyResult<bool> {
+        let Ok(other) = other.downcast::<Self>() else {
+            return Ok(false);
+        };
+        PyList::new(py, &self.providers)?.eq(PyList::new(py, &other.get().providers)?)
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "ChainCredentialProvider({})",
+            PyList::new(py, &self.providers)?.repr()?
+        ))
+    }
+
+    #[getter]
+    fn providers(&self, py: Python) -> Vec<PyObject> {
+        self.providers.iter().map(|p| p.clone_ref(py)).collect()
+    }
+}
+
+/// The providers of a chain, if `ob` is a [`PyChainCredentialProvider`] or a list or tuple of
+/// providers.
+pub(crate) fn chain_providers<'py>(
+    ob: &Bound<'py, PyAny>,
+) -> PyResult<Option<Vec<Bound<'py, PyAny>>>> {
+    if let Ok(chain) = ob.downcast::<PyChainCredentialProvider>() {
+        let providers = chain.get().providers.iter();
+        return Ok(Some(providers.map(|p| p.bind(ob.py()).clone()).collect()));
+    }
+    if !(ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>()) {
+        return Ok(None);
+    }
+    let providers: Vec<Bound<PyAny>> = ob.extract()?;
+    if providers.is_empty() {
+        return Err(PyValueError::new_err(
+            "Expected at least one provider in credential_provider chain.",
+        ));
+    }
+    Ok(Some(providers))
+}
+
+/// Await each of `attempts` in turn, returning the first token fetched.
+///
+/// If every attempt fails, the returned error lists the error of each provider in the chain.
+pub(crate) async fn fetch_first<T, Fut>(
+    attempts: impl IntoIterator<Item = Fut>,
+) -> object_store::Result<TemporaryToken<T>>
+where
+    Fut: Future<Output = object_store::Result<TemporaryToken<T>>>,
+{
+    let mut errors = vec![];
+    for attempt in attempts {
+        match attempt.await {
+            Ok(token) => return Ok(token),
+            Err(err) => errors.push(format!("  {}: {}", errors.len() + 1, err)),
+        }
+    }
+    Err(object_store::Error::Unauthenticated {
+        path: "Chained credential provider".to_string(),
+        source: format!(
+            "No credential provider in the chain returned a credential:\n{}",
+            errors.join("\n")
+        )
+        .into(),
+    })
+}
+
+/// Whether a credential provider asks for its tokens to be refreshed in the background, from its
+/// optional `background_refresh` attribute.
+pub(crate) fn background_refresh(ob: &Bound<PyAny>) -> PyResult<bool> {
+    if let Ok(background_refresh) = ob.getattr(intern!(ob.py(), "background_refresh")) {
+        background_refresh.extract()
+    } else {
+        Ok(false)
+    }
+}
+
 /// Check whether a Python object is awaitable
 pub(crate) fn is_awaitable(ob: &Bound<PyAny>) -> PyResult<bool> {
     let py = ob.py();
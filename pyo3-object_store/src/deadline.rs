@@ -0,0 +1,319 @@
+//! An object store wrapper that fails every call once a deadline has passed.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    Error, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use tokio::time::Instant;
+
+use crate::PyObjectStore;
+
+/// The source of the error of an operation that didn't complete by its deadline, which is raised
+/// as a `TimeoutError` in Python.
+#[derive(Debug, thiserror::Error)]
+#[error("Deadline exceeded in {operation}")]
+pub struct DeadlineExceeded {
+    operation: String,
+}
+
+impl DeadlineExceeded {
+    /// Create the error of `operation` not completing by its deadline.
+    pub fn new(operation: impl Into<String>) -> Self {
+        Self {
+            operation: operation.into(),
+        }
+    }
+}
+
+fn deadline_exceeded(operation: &str) -> Error {
+    Error::Generic {
+        store: "DeadlineStore",
+        source: Box::new(DeadlineExceeded::new(operation)),
+    }
+}
+
+/// Run `fut`, failing if it hasn't completed by `deadline`.
+///
+/// `fut` isn't polled at all once the deadline has passed, as `timeout_at` would still return the
+/// result of a future that completes on its first poll.
+async fn with_deadline<T>(
+    deadline: Option<Instant>,
+    operation: &'static str,
+    fut: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    match deadline {
+        Some(deadline) if Instant::now() >= deadline => Err(deadline_exceeded(operation)),
+        Some(deadline) => tokio::time::timeout_at(deadline, fut)
+            .await
+            .map_err(|_| deadline_exceeded(operation))?,
+        None => fut.await,
+    }
+}
+
+/// Yield the entries of `stream`, failing with a final error once `deadline` has passed.
+fn deadline_stream<'a, T: Send + 'a>(
+    deadline: Option<Instant>,
+    operation: &'static str,
+    stream: BoxStream<'a, Result<T>>,
+) -> BoxStream<'a, Result<T>> {
+    let Some(deadline) = deadline else {
+        return stream;
+    };
+    futures::stream::unfold(Some(stream), move |stream| async move {
+        let mut stream = stream?;
+        if Instant::now() >= deadline {
+            return Some((Err(deadline_exceeded(operation)), None));
+        }
+        match tokio::time::timeout_at(deadline, stream.next()).await {
+            Ok(Some(item)) => Some((item, Some(stream))),
+            Ok(None) => None,
+            Err(_) => Some((Err(deadline_exceeded(operation)), None)),
+        }
+    })
+    .boxed()
+}
+
+/// Store wrapper that stops every call to the underlying store at a fixed deadline.
+///
+/// Calls made after the deadline fail immediately, and calls still in progress at the deadline
+/// are cancelled. This includes streaming the body of a `get` and each entry of a `list`, so
+/// that no work carries on in the background after the caller has given up.
+#[derive(Debug)]
+pub struct DeadlineStore {
+    inner: Arc<dyn ObjectStore>,
+    deadline: Option<Instant>,
+}
+
+impl std::fmt::Display for DeadlineStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DeadlineStore({})", self.inner)
+    }
+}
+
+impl DeadlineStore {
+    /// Create a new [`DeadlineStore`] wrapping `inner`. If `deadline` is `None`, calls are passed
+    /// through unchanged.
+    pub fn new(inner: Arc<dyn ObjectStore>, deadline: Option<Instant>) -> Self {
+        Self { inner, deadline }
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// The time remaining until the deadline, or `None` if there is no deadline.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for DeadlineStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        with_deadline(
+            self.deadline,
+            "put",
+            self.inner.put_opts(location, payload, opts),
+        )
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload = with_deadline(
+            self.deadline,
+            "put_multipart",
+            self.inner.put_multipart_opts(location, opts),
+        )
+        .await?;
+        Ok(Box::new(DeadlineUpload {
+            inner: upload,
+            deadline: self.deadline,
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let result =
+            with_deadline(self.deadline, "get", self.inner.get_opts(location, options)).await?;
+        let payload = match result.payload {
+            GetResultPayload::Stream(stream) => {
+                GetResultPayload::Stream(deadline_stream(self.deadline, "get", stream))
+            }
+            payload => payload,
+        };
+        Ok(GetResult { payload, ..result })
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        with_deadline(self.deadline, "delete", self.inner.delete(location)).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        deadline_stream(self.deadline, "delete", self.inner.delete_stream(locations))
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        deadline_stream(self.deadline, "list", self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        deadline_stream(
+            self.deadline,
+            "list",
+            self.inner.list_with_offset(prefix, offset),
+        )
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        with_deadline(
+            self.deadline,
+            "list_with_delimiter",
+            self.inner.list_with_delimiter(prefix),
+        )
+        .await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        with_deadline(self.deadline, "copy", self.inner.copy(from, to)).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        with_deadline(self.deadline, "rename", self.inner.rename(from, to)).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        with_deadline(
+            self.deadline,
+            "copy",
+            self.inner.copy_if_not_exists(from, to),
+        )
+        .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        with_deadline(
+            self.deadline,
+            "rename",
+            self.inner.rename_if_not_exists(from, to),
+        )
+        .await
+    }
+}
+
+/// A multipart upload whose parts are cancelled at the deadline of the store.
+#[derive(Debug)]
+struct DeadlineUpload {
+    inner: Box<dyn MultipartUpload>,
+    deadline: Option<Instant>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for DeadlineUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let part = self.inner.put_part(data);
+        let deadline = self.deadline;
+        Box::pin(async move { with_deadline(deadline, "put_part", part).await })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        with_deadline(self.deadline, "complete_multipart", self.inner.complete()).await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        // Always clean up, even after the deadline
+        self.inner.abort().await
+    }
+}
+
+/// A deadline given either as the time remaining or as an `asyncio.Timeout`.
+struct PyDeadline(Option<Instant>);
+
+impl<'py> FromPyObject<'py> for PyDeadline {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let py = ob.py();
+        if let Ok(remaining) = ob.extract::<Duration>() {
+            return Ok(Self(Instant::now().checked_add(remaining)));
+        }
+        if ob.hasattr(intern!(py, "when"))? {
+            // The deadline of an `asyncio.Timeout` is measured on the clock of the event loop
+            let when = ob.call_method0(intern!(py, "when"))?;
+            if when.is_none() {
+                return Ok(Self(None));
+            }
+            let now = py
+                .import(intern!(py, "asyncio"))?
+                .call_method0(intern!(py, "get_running_loop"))?
+                .call_method0(intern!(py, "time"))?
+                .extract::<f64>()?;
+            let remaining = (when.extract::<f64>()? - now).max(0.0);
+            return Ok(Self(
+                Instant::now().checked_add(Duration::from_secs_f64(remaining)),
+            ));
+        }
+        Err(PyValueError::new_err(
+            "Expected deadline to be a timedelta or an asyncio.Timeout",
+        ))
+    }
+}
+
+/// A Python-facing wrapper around a [`DeadlineStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "DeadlineStore", frozen, subclass)]
+pub struct PyDeadlineStore(Arc<DeadlineStore>);
+
+impl AsRef<Arc<DeadlineStore>> for PyDeadlineStore {
+    fn as_ref(&self) -> &Arc<DeadlineStore> {
+        &self.0
+    }
+}
+
+impl PyDeadlineStore {
+    /// Consume self and return the underlying [`DeadlineStore`].
+    pub fn into_inner(self) -> Arc<DeadlineStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyDeadlineStore {
+    #[new]
+    fn py_new(store: PyObjectStore, deadline: PyDeadline) -> Self {
+        Self(Arc::new(DeadlineStore::new(store.into_dyn(), deadline.0)))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[getter]
+    fn remaining(&self) -> Option<Duration> {
+        self.0.remaining()
+    }
+}
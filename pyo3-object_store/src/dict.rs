@@ -0,0 +1,417 @@
+//! A store backed by a user-provided Python mapping of paths to bytes.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    Attributes, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMode, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
+    UploadPart,
+};
+use pyo3::exceptions::{PyKeyError, PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyTuple};
+use pyo3::IntoPyObjectExt;
+
+const STORE: &str = "Dict";
+
+fn mapping_error(err: PyErr) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: Box::new(err),
+    }
+}
+
+/// The value of the mapping at `key` as bytes, or `None` if the mapping has no such key.
+fn get_item(mapping: &Bound<PyAny>, key: &str) -> PyResult<Option<Bytes>> {
+    let value = match mapping.get_item(key) {
+        Ok(value) => value,
+        Err(err) if err.is_instance_of::<PyKeyError>(mapping.py()) => return Ok(None),
+        Err(err) => return Err(err),
+    };
+    // Any object supporting the buffer protocol is accepted, as `bytes()` accepts it
+    let value = match value.downcast::<PyBytes>() {
+        Ok(value) => value.clone(),
+        Err(_) => mapping
+            .py()
+            .get_type::<PyBytes>()
+            .call1((value,))?
+            .downcast_into::<PyBytes>()?,
+    };
+    Ok(Some(Bytes::copy_from_slice(value.as_bytes())))
+}
+
+/// An [`ObjectStore`] whose objects are the values of a Python mapping, keyed by their paths.
+///
+/// Every call takes the GIL, on a blocking thread, to access the mapping. The mapping only holds
+/// the contents of the objects, so an object's last modified time is the time this store last
+/// wrote it, or the time the store was created if it didn't.
+#[derive(Debug, Clone)]
+pub struct DictStore {
+    mapping: Arc<PyObject>,
+    created: DateTime<Utc>,
+    modified: Arc<Mutex<HashMap<Path, DateTime<Utc>>>>,
+}
+
+impl std::fmt::Display for DictStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DictStore")
+    }
+}
+
+impl DictStore {
+    /// Create a store backed by `mapping`.
+    pub fn new(mapping: PyObject) -> Self {
+        Self {
+            mapping: Arc::new(mapping),
+            created: Utc::now(),
+            modified: Default::default(),
+        }
+    }
+
+    /// The mapping backing this store.
+    pub fn mapping(&self) -> &PyObject {
+        &self.mapping
+    }
+
+    /// Call `f` with the mapping, holding the GIL on a blocking thread.
+    async fn with_mapping<T: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Bound<PyAny>) -> PyResult<T> + Send + 'static,
+    ) -> Result<T> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| f(store.mapping.bind(py)).map_err(mapping_error))
+        })
+        .await
+        .map_err(|err| object_store::Error::JoinError { source: err })?
+    }
+
+    fn object_meta(&self, location: Path, size: usize) -> ObjectMeta {
+        let last_modified = self
+            .modified
+            .lock()
+            .unwrap()
+            .get(&location)
+            .copied()
+            .unwrap_or(self.created);
+        ObjectMeta {
+            location,
+            last_modified,
+            size: size as u64,
+            e_tag: None,
+            version: None,
+        }
+    }
+
+    fn not_found(location: &Path) -> object_store::Error {
+        object_store::Error::NotFound {
+            path: location.to_string(),
+            source: "Key not found in mapping".into(),
+        }
+    }
+
+    /// All the objects of the mapping under `prefix`, sorted by path.
+    async fn objects(&self, prefix: Option<&Path>) -> Result<Vec<ObjectMeta>> {
+        let prefix = prefix.cloned().unwrap_or_default();
+        let entries = self
+            .with_mapping(move |mapping| {
+                let mut entries = vec![];
+                for key in mapping.try_iter()? {
+                    let key = key?;
+                    let key = key.extract::<String>().map_err(|_| {
+                        PyTypeError::new_err(format!(
+                            "Expected the keys of the mapping to be str, got {}",
+                            key
+                        ))
+                    })?;
+                    let location =
+                        Path::parse(&key).map_err(|err| PyValueError::new_err(err.to_string()))?;
+                    if !location.prefix_matches(&prefix) {
+                        continue;
+                    }
+                    if let Some(value) = get_item(mapping, &key)? {
+                        entries.push((location, value.len()));
+                    }
+                }
+                Ok(entries)
+            })
+            .await?;
+        let mut objects = entries
+            .into_iter()
+            .map(|(location, size)| self.object_meta(location, size))
+            .collect::<Vec<_>>();
+        objects.sort_by(|a, b| a.location.cmp(&b.location));
+        Ok(objects)
+    }
+
+    async fn write(&self, location: &Path, data: Bytes, mode: PutMode) -> Result<()> {
+        let key = location.to_string();
+        let exists = self
+            .with_mapping(move |mapping| {
+                let py = mapping.py();
+                if matches!(mode, PutMode::Create) && mapping.contains(&key)? {
+                    return Ok(true);
+                }
+                mapping.set_item(key, PyBytes::new(py, &data))?;
+                Ok(false)
+            })
+            .await?;
+        if exists {
+            return Err(object_store::Error::AlreadyExists {
+                path: location.to_string(),
+                source: "Key already exists in mapping".into(),
+            });
+        }
+        self.modified
+            .lock()
+            .unwrap()
+            .insert(location.clone(), Utc::now());
+        Ok(())
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, mode: PutMode) -> Result<()> {
+        let key = from.to_string();
+        let data = self
+            .with_mapping(move |mapping| get_item(mapping, &key))
+            .await?
+            .ok_or_else(|| Self::not_found(from))?;
+        self.write(to, data, mode).await
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for DictStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        if matches!(opts.mode, PutMode::Update(_)) {
+            return Err(object_store::Error::NotImplemented);
+        }
+        self.write(location, payload.into(), opts.mode).await?;
+        Ok(PutResult {
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        Ok(Box::new(DictUpload {
+            store: self.clone(),
+            location: location.clone(),
+            parts: vec![],
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let key = location.to_string();
+        let data = self
+            .with_mapping(move |mapping| get_item(mapping, &key))
+            .await?
+            .ok_or_else(|| DictStore::not_found(location))?;
+        let meta = self.object_meta(location.clone(), data.len());
+        options.check_preconditions(&meta)?;
+
+        let range = match &options.range {
+            Some(range) => {
+                range
+                    .as_range(meta.size)
+                    .map_err(|err| object_store::Error::Generic {
+                        store: STORE,
+                        source: Box::new(err),
+                    })?
+            }
+            None => 0..meta.size,
+        };
+        let data = data.slice(range.start as usize..range.end as usize);
+        let stream = futures::stream::once(futures::future::ready(Ok(data)));
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream.boxed()),
+            meta,
+            range,
+            attributes: Attributes::default(),
+        })
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let key = location.to_string();
+        self.with_mapping(move |mapping| match mapping.del_item(key) {
+            // Deleting an object that doesn't exist succeeds
+            Err(err) if err.is_instance_of::<PyKeyError>(mapping.py()) => Ok(()),
+            result => result,
+        })
+        .await?;
+        self.modified.lock().unwrap().remove(location);
+        Ok(())
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let store = self.clone();
+        let prefix = prefix.cloned();
+        futures::stream::once(async move { store.objects(prefix.as_ref()).await })
+            .map(|objects| match objects {
+                Ok(objects) => futures::stream::iter(objects.into_iter().map(Ok)).boxed(),
+                Err(err) => futures::stream::once(futures::future::ready(Err(err))).boxed(),
+            })
+            .flatten()
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let root = Path::default();
+        let prefix = prefix.unwrap_or(&root);
+        let mut common_prefixes = BTreeSet::new();
+        let mut objects = vec![];
+        for object in self.objects(Some(prefix)).await? {
+            // The parts borrow the object, so they're dropped before it's moved
+            let common_prefix = {
+                let Some(mut parts) = object.location.prefix_match(prefix) else {
+                    continue;
+                };
+                let Some(common_prefix) = parts.next() else {
+                    continue;
+                };
+                parts.next().map(|_| prefix.child(common_prefix))
+            };
+            match common_prefix {
+                Some(common_prefix) => {
+                    common_prefixes.insert(common_prefix);
+                }
+                None => objects.push(object),
+            }
+        }
+        Ok(ListResult {
+            common_prefixes: common_prefixes.into_iter().collect(),
+            objects,
+        })
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, PutMode::Overwrite).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, PutMode::Create).await
+    }
+}
+
+/// A multipart upload to a [`DictStore`], whose parts are buffered until it completes.
+#[derive(Debug)]
+struct DictUpload {
+    store: DictStore,
+    location: Path,
+    parts: Vec<PutPayload>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for DictUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.parts.push(data);
+        Box::pin(futures::future::ready(Ok(())))
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let data = std::mem::take(&mut self.parts)
+            .iter()
+            .flat_map(|part| part.iter())
+            .flatten()
+            .copied()
+            .collect::<Vec<u8>>();
+        self.store
+            .write(&self.location, data.into(), PutMode::Overwrite)
+            .await?;
+        Ok(PutResult {
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.parts.clear();
+        Ok(())
+    }
+}
+
+/// A Python-facing wrapper around a [`DictStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "DictStore", frozen, subclass)]
+pub struct PyDictStore(Arc<DictStore>);
+
+impl AsRef<Arc<DictStore>> for PyDictStore {
+    fn as_ref(&self) -> &Arc<DictStore> {
+        &self.0
+    }
+}
+
+impl PyDictStore {
+    /// Consume self and return the underlying [`DictStore`].
+    pub fn into_inner(self) -> Arc<DictStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyDictStore {
+    #[new]
+    #[pyo3(signature = (mapping=None))]
+    fn py_new(py: Python, mapping: Option<Bound<PyAny>>) -> PyResult<Self> {
+        let mapping = match mapping {
+            Some(mapping) => {
+                for method in [
+                    "__getitem__",
+                    "__setitem__",
+                    "__delitem__",
+                    "__iter__",
+                    "keys",
+                ] {
+                    if !mapping.hasattr(method)? {
+                        return Err(PyTypeError::new_err(format!(
+                            "Expected a mutable mapping, got {}",
+                            mapping.get_type().name()?
+                        )));
+                    }
+                }
+                mapping.unbind()
+            }
+            None => PyDict::new(py).into_any().unbind(),
+        };
+        Ok(Self(Arc::new(DictStore::new(mapping))))
+    }
+
+    fn __eq__(&self, other: &Bound<PyAny>) -> bool {
+        // Two dict stores are equal only if they are backed by the same mapping
+        other
+            .downcast::<PyDictStore>()
+            .map(|other| self.0.mapping().is(other.get().0.mapping()))
+            .unwrap_or(false)
+    }
+
+    fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
+        let args = PyTuple::new(py, [self.0.mapping().clone_ref(py)])?.into_py_any(py)?;
+        PyTuple::new(py, [args, PyDict::new(py).into_py_any(py)?])?.into_py_any(py)
+    }
+
+    fn __repr__(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "DictStore({})",
+            self.0.mapping().bind(py).get_type().name()?
+        ))
+    }
+
+    #[getter]
+    fn mapping(&self, py: Python) -> PyObject {
+        self.0.mapping().clone_ref(py)
+    }
+}
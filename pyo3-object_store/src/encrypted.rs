@@ -0,0 +1,626 @@
+//! A store wrapper that encrypts objects on the client with envelope encryption.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use base64::prelude::BASE64_STANDARD;
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    Attribute, Attributes, GetOptions, GetRange, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, Result, UploadPart,
+};
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::PyBytes;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::PyObjectStore;
+
+const STORE: &str = "EncryptedStore";
+
+/// The size of the plaintext of each encrypted block, except the last, which may be shorter.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// The size of the authentication tag appended to each encrypted block.
+const TAG_LEN: usize = 16;
+
+const ENCRYPTED_BLOCK_SIZE: usize = BLOCK_SIZE + TAG_LEN;
+
+/// The size of content and master keys.
+const KEY_LEN: usize = 32;
+
+/// The minimum size of the parts of a multipart upload, except the last, as required by S3.
+const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+/// The metadata key holding the encryption scheme of an object.
+const SCHEME_KEY: &str = "obstore-encryption";
+
+/// The encryption scheme: AES-256-GCM over blocks of 64 KiB.
+const SCHEME: &str = "aes-256-gcm-64k";
+
+/// The metadata key holding the wrapped content key of an object, encoded as base64.
+const WRAPPED_KEY_KEY: &str = "obstore-encryption-key";
+
+fn generic_error(
+    source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: source.into(),
+    }
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    let mut bytes = [0; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| generic_error("Failed to generate random bytes"))?;
+    Ok(bytes)
+}
+
+fn aead_key(key: &[u8]) -> Result<LessSafeKey> {
+    let key = UnboundKey::new(&AES_256_GCM, key)
+        .map_err(|_| generic_error(format!("Expected a key of {} bytes", KEY_LEN)))?;
+    Ok(LessSafeKey::new(key))
+}
+
+/// The nonce of a block. Each object has its own content key, so a counter is unique.
+fn block_nonce(block: u64) -> Nonce {
+    let mut nonce = [0; NONCE_LEN];
+    nonce[NONCE_LEN - 8..].copy_from_slice(&block.to_be_bytes());
+    Nonce::assume_unique_for_key(nonce)
+}
+
+/// The associated data of a block, binding it to its position so that blocks can't be
+/// reordered, and marking the last block so that objects can't be truncated.
+fn block_aad(block: u64, last: bool) -> [u8; 9] {
+    let mut aad = [0; 9];
+    aad[..8].copy_from_slice(&block.to_be_bytes());
+    aad[8] = last as u8;
+    aad
+}
+
+fn seal_block(key: &LessSafeKey, block: u64, last: bool, plaintext: &[u8]) -> Vec<u8> {
+    let mut buffer = Vec::with_capacity(plaintext.len() + TAG_LEN);
+    buffer.extend_from_slice(plaintext);
+    key.seal_in_place_append_tag(
+        block_nonce(block),
+        Aad::from(block_aad(block, last)),
+        &mut buffer,
+    )
+    // Sealing only fails for inputs far larger than a block
+    .expect("block is within AES-GCM limits");
+    buffer
+}
+
+fn open_block(key: &LessSafeKey, block: u64, last: bool, ciphertext: &[u8]) -> Option<Bytes> {
+    let mut buffer = ciphertext.to_vec();
+    let len = key
+        .open_in_place(
+            block_nonce(block),
+            Aad::from(block_aad(block, last)),
+            &mut buffer,
+        )
+        .ok()?
+        .len();
+    buffer.truncate(len);
+    Some(buffer.into())
+}
+
+/// Encrypt a whole object. An empty object is encrypted as a single empty block.
+fn encrypt(key: &LessSafeKey, plaintext: &[u8]) -> Bytes {
+    let blocks = if plaintext.is_empty() {
+        vec![plaintext]
+    } else {
+        plaintext.chunks(BLOCK_SIZE).collect()
+    };
+    let last = blocks.len() - 1;
+    let mut ciphertext = Vec::with_capacity(plaintext.len() + blocks.len() * TAG_LEN);
+    for (i, block) in blocks.into_iter().enumerate() {
+        ciphertext.extend(seal_block(key, i as u64, i == last, block));
+    }
+    ciphertext.into()
+}
+
+/// The number of blocks of an encrypted object of `encrypted_size` bytes.
+fn num_blocks(encrypted_size: u64) -> u64 {
+    encrypted_size.div_ceil(ENCRYPTED_BLOCK_SIZE as u64).max(1)
+}
+
+/// The size of the plaintext of an encrypted object of `encrypted_size` bytes.
+fn plaintext_size(encrypted_size: u64) -> u64 {
+    encrypted_size.saturating_sub(num_blocks(encrypted_size) * TAG_LEN as u64)
+}
+
+fn plaintext_meta(meta: ObjectMeta) -> ObjectMeta {
+    ObjectMeta {
+        size: plaintext_size(meta.size),
+        ..meta
+    }
+}
+
+/// Remove the encryption metadata from the attributes returned to the caller.
+fn strip_attributes(attributes: &mut Attributes) {
+    attributes.remove(&Attribute::Metadata(SCHEME_KEY.into()));
+    attributes.remove(&Attribute::Metadata(WRAPPED_KEY_KEY.into()));
+}
+
+/// Wraps and unwraps the content keys of objects.
+pub enum KeyProvider {
+    /// A 256-bit master key, wrapping content keys with AES-256-GCM.
+    MasterKey([u8; KEY_LEN]),
+    /// A Python object with `wrap_key` and `unwrap_key` methods, such as a client of a key
+    /// management service.
+    Callback(Arc<PyObject>),
+}
+
+impl std::fmt::Debug for KeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the master key
+        match self {
+            Self::MasterKey(_) => write!(f, "MasterKey"),
+            Self::Callback(callback) => write!(f, "Callback({:?})", callback),
+        }
+    }
+}
+
+impl KeyProvider {
+    /// Call `method` of the Python key provider with `key`, holding the GIL on a blocking thread.
+    async fn call(callback: &Arc<PyObject>, method: &'static str, key: &[u8]) -> Result<Vec<u8>> {
+        let callback = callback.clone();
+        let key = key.to_vec();
+        tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| {
+                callback
+                    .bind(py)
+                    .call_method1(method, (PyBytes::new(py, &key),))?
+                    .extract::<Vec<u8>>()
+            })
+            .map_err(generic_error)
+        })
+        .await
+        .map_err(|err| object_store::Error::JoinError { source: err })?
+    }
+
+    async fn wrap(&self, key: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::MasterKey(master_key) => {
+                let nonce = random_bytes::<NONCE_LEN>()?;
+                let mut wrapped = key.to_vec();
+                aead_key(master_key)?
+                    .seal_in_place_append_tag(
+                        Nonce::assume_unique_for_key(nonce),
+                        Aad::empty(),
+                        &mut wrapped,
+                    )
+                    .map_err(|_| generic_error("Failed to wrap content key"))?;
+                Ok([nonce.as_slice(), &wrapped].concat())
+            }
+            Self::Callback(callback) => Self::call(callback, "wrap_key", key).await,
+        }
+    }
+
+    async fn unwrap(&self, wrapped: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::MasterKey(master_key) => {
+                if wrapped.len() < NONCE_LEN {
+                    return Err(generic_error("Wrapped content key is too short"));
+                }
+                let (nonce, wrapped) = wrapped.split_at(NONCE_LEN);
+                let mut key = wrapped.to_vec();
+                let len = aead_key(master_key)?
+                    .open_in_place(
+                        // The length was checked above
+                        Nonce::try_assume_unique_for_key(nonce).unwrap(),
+                        Aad::empty(),
+                        &mut key,
+                    )
+                    .map_err(|_| generic_error("Failed to unwrap content key: wrong master key?"))?
+                    .len();
+                key.truncate(len);
+                Ok(key)
+            }
+            Self::Callback(callback) => Self::call(callback, "unwrap_key", wrapped).await,
+        }
+    }
+}
+
+/// The state of the stream decrypting the body of a get request.
+struct DecryptStream {
+    stream: BoxStream<'static, Result<Bytes>>,
+    buffer: BytesMut,
+    key: Arc<LessSafeKey>,
+    location: Path,
+    /// The index of the next block.
+    block: u64,
+    num_blocks: u64,
+    /// The number of bytes to skip at the start of the next block.
+    skip: usize,
+    /// The number of bytes left to return.
+    remaining: u64,
+}
+
+impl DecryptStream {
+    fn into_stream(self) -> BoxStream<'static, Result<Bytes>> {
+        futures::stream::try_unfold(self, |mut state| async move {
+            if state.remaining == 0 {
+                return Ok(None);
+            }
+            while state.buffer.len() < ENCRYPTED_BLOCK_SIZE {
+                match state.stream.try_next().await? {
+                    Some(chunk) => state.buffer.extend_from_slice(&chunk),
+                    None => break,
+                }
+            }
+            if state.buffer.is_empty() {
+                return Err(generic_error(format!(
+                    "Encrypted object {} ended unexpectedly",
+                    state.location
+                )));
+            }
+            let ciphertext = state
+                .buffer
+                .split_to(state.buffer.len().min(ENCRYPTED_BLOCK_SIZE));
+            let last = state.block + 1 == state.num_blocks;
+            let plaintext =
+                open_block(&state.key, state.block, last, &ciphertext).ok_or_else(|| {
+                    generic_error(format!(
+                        "Failed to decrypt block {} of {}",
+                        state.block, state.location
+                    ))
+                })?;
+            state.block += 1;
+
+            let skip = std::mem::take(&mut state.skip).min(plaintext.len());
+            let len = (plaintext.len() - skip).min(state.remaining as usize);
+            state.remaining -= len as u64;
+            Ok(Some((plaintext.slice(skip..skip + len), state)))
+        })
+        .boxed()
+    }
+}
+
+/// Store wrapper that encrypts objects on the client before they're uploaded.
+///
+/// Each object is encrypted with its own random content key, using AES-256-GCM over blocks of
+/// 64 KiB. The content key is wrapped by the [`KeyProvider`] and stored in the metadata of the
+/// object, next to the encryption scheme.
+///
+/// Sizes reported by `head` and `list` are those of the plaintext, derived from the size of the
+/// ciphertext. Ranged reads fetch the blocks covering the range, which are decrypted and trimmed.
+#[derive(Debug)]
+pub struct EncryptedStore {
+    inner: Arc<dyn ObjectStore>,
+    provider: KeyProvider,
+}
+
+impl std::fmt::Display for EncryptedStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EncryptedStore({})", self.inner)
+    }
+}
+
+impl EncryptedStore {
+    /// Create a new [`EncryptedStore`] wrapping `inner`.
+    pub fn new(inner: Arc<dyn ObjectStore>, provider: KeyProvider) -> Self {
+        Self { inner, provider }
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// Generate a content key, adding it, wrapped, to `attributes`.
+    async fn new_content_key(&self, attributes: &mut Attributes) -> Result<LessSafeKey> {
+        let key = random_bytes::<KEY_LEN>()?;
+        let wrapped = self.provider.wrap(&key).await?;
+        attributes.insert(Attribute::Metadata(SCHEME_KEY.into()), SCHEME.into());
+        attributes.insert(
+            Attribute::Metadata(WRAPPED_KEY_KEY.into()),
+            BASE64_STANDARD.encode(wrapped).into(),
+        );
+        aead_key(&key)
+    }
+
+    /// Unwrap the content key of the object at `location` from its `attributes`.
+    async fn content_key(&self, location: &Path, attributes: &Attributes) -> Result<LessSafeKey> {
+        match attributes.get(&Attribute::Metadata(SCHEME_KEY.into())) {
+            Some(scheme) if scheme.as_ref() == SCHEME => {}
+            Some(scheme) => {
+                return Err(generic_error(format!(
+                    "Unsupported encryption scheme of {}: {}",
+                    location,
+                    scheme.as_ref()
+                )))
+            }
+            None => {
+                return Err(generic_error(format!(
+                    "Object {} is not encrypted, or the store doesn't return its metadata",
+                    location
+                )))
+            }
+        }
+        let wrapped = attributes
+            .get(&Attribute::Metadata(WRAPPED_KEY_KEY.into()))
+            .and_then(|wrapped| BASE64_STANDARD.decode(wrapped.as_ref()).ok())
+            .ok_or_else(|| generic_error(format!("Missing content key of {}", location)))?;
+        aead_key(&self.provider.unwrap(&wrapped).await?)
+    }
+
+    /// The range of the plaintext requested by `range`, which may end past the end of the object.
+    async fn plaintext_range(&self, location: &Path, range: GetRange) -> Result<Range<u64>> {
+        match range {
+            GetRange::Bounded(range) => Ok(range),
+            GetRange::Offset(offset) => Ok(offset..u64::MAX),
+            GetRange::Suffix(suffix) => {
+                let size = plaintext_size(self.inner.head(location).await?.size);
+                Ok(size.saturating_sub(suffix)..size)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for EncryptedStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        mut opts: PutOptions,
+    ) -> Result<PutResult> {
+        let key = self.new_content_key(&mut opts.attributes).await?;
+        // Encrypt on a blocking thread, as payloads may be large
+        let ciphertext = tokio::task::spawn_blocking(move || encrypt(&key, &Bytes::from(payload)))
+            .await
+            .map_err(|err| object_store::Error::JoinError { source: err })?;
+        self.inner.put_opts(location, ciphertext.into(), opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        mut opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let key = self.new_content_key(&mut opts.attributes).await?;
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(EncryptedUpload {
+            inner: upload,
+            key,
+            block: 0,
+            plaintext: BytesMut::new(),
+            ciphertext: Vec::new(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, mut options: GetOptions) -> Result<GetResult> {
+        if options.head {
+            let mut result = self.inner.get_opts(location, options).await?;
+            result.meta = plaintext_meta(result.meta);
+            result.range = 0..result.meta.size;
+            strip_attributes(&mut result.attributes);
+            return Ok(result);
+        }
+
+        let requested = match options.range.take() {
+            Some(range) => Some(self.plaintext_range(location, range).await?),
+            None => None,
+        };
+        // Fetch the blocks covering the requested range
+        let first_block = requested
+            .as_ref()
+            .map_or(0, |range| range.start / BLOCK_SIZE as u64);
+        if let Some(range) = &requested {
+            let start = first_block * ENCRYPTED_BLOCK_SIZE as u64;
+            options.range = Some(if range.end == u64::MAX {
+                GetRange::Offset(start)
+            } else {
+                let end = range.end.div_ceil(BLOCK_SIZE as u64);
+                GetRange::Bounded(start..end.saturating_mul(ENCRYPTED_BLOCK_SIZE as u64))
+            });
+        }
+
+        let result = self.inner.get_opts(location, options).await?;
+        let key = self.content_key(location, &result.attributes).await?;
+        let encrypted_size = result.meta.size;
+        let meta = plaintext_meta(result.meta.clone());
+        let range = match requested {
+            Some(range) if range.start > meta.size => {
+                return Err(generic_error(format!(
+                    "Range start {} is past the end of {} ({} bytes)",
+                    range.start, location, meta.size
+                )))
+            }
+            Some(range) => range.start..range.end.min(meta.size),
+            None => 0..meta.size,
+        };
+        let mut attributes = result.attributes.clone();
+        strip_attributes(&mut attributes);
+
+        let stream = DecryptStream {
+            stream: result.into_stream(),
+            buffer: BytesMut::new(),
+            key: Arc::new(key),
+            location: location.clone(),
+            block: first_block,
+            num_blocks: num_blocks(encrypted_size),
+            skip: (range.start - first_block * BLOCK_SIZE as u64) as usize,
+            remaining: range.end - range.start,
+        };
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream.into_stream()),
+            meta,
+            range,
+            attributes,
+        })
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        Ok(plaintext_meta(self.inner.head(location).await?))
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list(prefix).map_ok(plaintext_meta).boxed()
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner
+            .list_with_offset(prefix, offset)
+            .map_ok(plaintext_meta)
+            .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let result = self.inner.list_with_delimiter(prefix).await?;
+        Ok(ListResult {
+            common_prefixes: result.common_prefixes,
+            objects: result.objects.into_iter().map(plaintext_meta).collect(),
+        })
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+/// A multipart upload encrypting its parts.
+///
+/// Parts are encrypted in whole blocks, the remainder being carried over to the next part.
+/// Encrypted parts are only uploaded once they reach the minimum part size, so that the
+/// upload is valid whatever the size of the parts passed to it.
+#[derive(Debug)]
+struct EncryptedUpload {
+    inner: Box<dyn MultipartUpload>,
+    key: LessSafeKey,
+    /// The index of the next block.
+    block: u64,
+    plaintext: BytesMut,
+    ciphertext: Vec<u8>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for EncryptedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.plaintext.extend_from_slice(&Bytes::from(data));
+        // Keep the last block buffered, as it can only be sealed once it's known to be the last
+        while self.plaintext.len() > BLOCK_SIZE {
+            let block = self.plaintext.split_to(BLOCK_SIZE);
+            self.ciphertext
+                .extend(seal_block(&self.key, self.block, false, &block));
+            self.block += 1;
+        }
+        if self.ciphertext.len() >= MIN_PART_SIZE {
+            let part = Bytes::from(std::mem::take(&mut self.ciphertext));
+            self.inner.put_part(part.into())
+        } else {
+            Box::pin(futures::future::ready(Ok(())))
+        }
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let block = self.plaintext.split();
+        self.ciphertext
+            .extend(seal_block(&self.key, self.block, true, &block));
+        self.block += 1;
+        let part = Bytes::from(std::mem::take(&mut self.ciphertext));
+        self.inner.put_part(part.into()).await?;
+        self.inner.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
+
+/// A key provider given either as a master key or as a Python object wrapping keys.
+struct PyKeyProvider(KeyProvider);
+
+impl<'py> FromPyObject<'py> for PyKeyProvider {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let py = ob.py();
+        if ob.is_instance_of::<PyBytes>() {
+            let key = ob.extract::<Vec<u8>>()?;
+            let key = <[u8; KEY_LEN]>::try_from(key.as_slice()).map_err(|_| {
+                PyValueError::new_err(format!(
+                    "Expected a master key of {} bytes, got {} bytes",
+                    KEY_LEN,
+                    key.len()
+                ))
+            })?;
+            return Ok(Self(KeyProvider::MasterKey(key)));
+        }
+        if ob.hasattr(intern!(py, "wrap_key"))? && ob.hasattr(intern!(py, "unwrap_key"))? {
+            return Ok(Self(KeyProvider::Callback(Arc::new(ob.clone().unbind()))));
+        }
+        Err(PyTypeError::new_err(
+            "Expected key_provider to be bytes or an object with wrap_key and unwrap_key methods",
+        ))
+    }
+}
+
+/// A Python-facing wrapper around an [`EncryptedStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "EncryptedStore", frozen, subclass)]
+pub struct PyEncryptedStore(Arc<EncryptedStore>);
+
+impl AsRef<Arc<EncryptedStore>> for PyEncryptedStore {
+    fn as_ref(&self) -> &Arc<EncryptedStore> {
+        &self.0
+    }
+}
+
+impl PyEncryptedStore {
+    /// Consume self and return the underlying [`EncryptedStore`].
+    pub fn into_inner(self) -> Arc<EncryptedStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyEncryptedStore {
+    #[new]
+    fn py_new(store: PyObjectStore, key_provider: PyKeyProvider) -> Self {
+        Self(Arc::new(EncryptedStore::new(
+            store.into_dyn(),
+            key_provider.0,
+        )))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
@@ -1,11 +1,19 @@
 //! Contains the [`PyObjectStoreError`], the error enum returned by all fallible functions in this
 //! crate.
 
-use pyo3::exceptions::{PyFileNotFoundError, PyIOError, PyNotImplementedError, PyValueError};
+use object_store::client::{HttpError, HttpErrorKind};
+use pyo3::exceptions::{
+    PyFileNotFoundError, PyIOError, PyNotImplementedError, PyTimeoutError, PyValueError,
+};
 use pyo3::prelude::*;
-use pyo3::{create_exception, DowncastError};
+use pyo3::sync::GILOnceCell;
+use pyo3::types::{PyDict, PyTuple, PyType};
+use pyo3::{create_exception, intern, DowncastError};
 use thiserror::Error;
 
+use crate::retry::ResponseError;
+use crate::DeadlineExceeded;
+
 // Base exception
 // Note that this is named `BaseError` instead of `ObstoreError` to not leak the name "obstore" to
 // other Rust-Python libraries using pyo3-object_store.
@@ -83,6 +91,52 @@ create_exception!(
     BaseError,
     "A Python-facing exception wrapping [object_store::Error::UnknownConfigurationKey]."
 );
+create_exception!(
+    pyo3_object_store,
+    ConnectError,
+    BaseError,
+    "A Python-facing exception raised when a connection to the server can't be established."
+);
+
+// Subclasses the builtin `TimeoutError`, like the error raised by `asyncio.wait_for`
+create_exception!(
+    pyo3_object_store,
+    StreamTimeoutError,
+    pyo3::exceptions::PyTimeoutError,
+    "A Python-facing exception raised when a stream receives no data within its chunk timeout."
+);
+
+static TIMEOUT_ERROR: GILOnceCell<Py<PyType>> = GILOnceCell::new();
+
+/// A Python-facing exception raised when a request times out.
+///
+/// This subclasses both [`BaseError`] and the builtin `TimeoutError`, like
+/// [`StreamTimeoutError`], so it can't be created with `create_exception!`.
+pub(crate) fn timeout_error_type(py: Python<'_>) -> &Bound<'_, PyType> {
+    TIMEOUT_ERROR
+        .get_or_init(py, || {
+            let bases = PyTuple::new(
+                py,
+                [py.get_type::<BaseError>(), py.get_type::<PyTimeoutError>()],
+            )
+            .expect("Failed to create the bases of TimeoutError");
+            let dict = PyDict::new(py);
+            dict.set_item("__module__", "pyo3_object_store")
+                .and_then(|_| {
+                    dict.set_item(
+                        "__doc__",
+                        "A Python-facing exception raised when a request times out.",
+                    )
+                })
+                .expect("Failed to create the namespace of TimeoutError");
+            py.get_type::<PyType>()
+                .call1(("TimeoutError", bases, dict))
+                .and_then(|ty| Ok(ty.downcast_into::<PyType>()?))
+                .expect("Failed to initialize new exception type.")
+                .unbind()
+        })
+        .bind(py)
+}
 
 /// The Error variants returned by this crate.
 #[derive(Error, Debug)]
@@ -105,51 +159,131 @@ impl From<PyObjectStoreError> for PyErr {
     fn from(error: PyObjectStoreError) -> Self {
         match error {
             PyObjectStoreError::PyErr(err) => err,
-            PyObjectStoreError::ObjectStoreError(ref err) => match err {
-                object_store::Error::Generic {
-                    store: _,
-                    source: _,
-                } => GenericError::new_err(print_with_debug(err)),
-                object_store::Error::NotFound { path: _, source: _ } => {
-                    PyFileNotFoundError::new_err(print_with_debug(err))
-                }
-                object_store::Error::InvalidPath { source: _ } => {
-                    InvalidPathError::new_err(print_with_debug(err))
-                }
-                object_store::Error::JoinError { source: _ } => {
-                    JoinError::new_err(print_with_debug(err))
-                }
-                object_store::Error::NotSupported { source: _ } => {
-                    NotSupportedError::new_err(print_with_debug(err))
-                }
-                object_store::Error::AlreadyExists { path: _, source: _ } => {
-                    AlreadyExistsError::new_err(print_with_debug(err))
-                }
-                object_store::Error::Precondition { path: _, source: _ } => {
-                    PreconditionError::new_err(print_with_debug(err))
-                }
-                object_store::Error::NotModified { path: _, source: _ } => {
-                    NotModifiedError::new_err(print_with_debug(err))
-                }
-                object_store::Error::NotImplemented => {
-                    PyNotImplementedError::new_err(print_with_debug(err))
-                }
-                object_store::Error::PermissionDenied { path: _, source: _ } => {
-                    PermissionDeniedError::new_err(print_with_debug(err))
-                }
-                object_store::Error::Unauthenticated { path: _, source: _ } => {
-                    UnauthenticatedError::new_err(print_with_debug(err))
-                }
-                object_store::Error::UnknownConfigurationKey { store: _, key: _ } => {
-                    UnknownConfigurationKeyError::new_err(print_with_debug(err))
-                }
-                _ => GenericError::new_err(print_with_debug(err)),
-            },
+            PyObjectStoreError::ObjectStoreError(ref err) => {
+                let py_err = object_store_error(err);
+                RequestDetails::from_error(err).annotate(&py_err);
+                py_err
+            }
             PyObjectStoreError::IOError(err) => PyIOError::new_err(err),
         }
     }
 }
 
+fn object_store_error(err: &object_store::Error) -> PyErr {
+    match err {
+        object_store::Error::Generic {
+            store: _,
+            source: _,
+        } => match network_error_kind(err) {
+            Some(HttpErrorKind::Timeout) => Python::with_gil(|py| {
+                PyErr::from_type(timeout_error_type(py).clone(), print_with_debug(err))
+            }),
+            Some(HttpErrorKind::Connect) => ConnectError::new_err(print_with_debug(err)),
+            _ => GenericError::new_err(print_with_debug(err)),
+        },
+        object_store::Error::NotFound { path: _, source: _ } => {
+            PyFileNotFoundError::new_err(print_with_debug(err))
+        }
+        object_store::Error::InvalidPath { source: _ } => {
+            InvalidPathError::new_err(print_with_debug(err))
+        }
+        object_store::Error::JoinError { source: _ } => JoinError::new_err(print_with_debug(err)),
+        object_store::Error::NotSupported { source: _ } => {
+            NotSupportedError::new_err(print_with_debug(err))
+        }
+        object_store::Error::AlreadyExists { path: _, source: _ } => {
+            AlreadyExistsError::new_err(print_with_debug(err))
+        }
+        object_store::Error::Precondition { path: _, source: _ } => {
+            PreconditionError::new_err(print_with_debug(err))
+        }
+        object_store::Error::NotModified { path: _, source: _ } => {
+            NotModifiedError::new_err(print_with_debug(err))
+        }
+        object_store::Error::NotImplemented => {
+            PyNotImplementedError::new_err(print_with_debug(err))
+        }
+        object_store::Error::PermissionDenied { path: _, source: _ } => {
+            PermissionDeniedError::new_err(print_with_debug(err))
+        }
+        object_store::Error::Unauthenticated { path: _, source: _ } => {
+            UnauthenticatedError::new_err(print_with_debug(err))
+        }
+        object_store::Error::UnknownConfigurationKey { store: _, key: _ } => {
+            UnknownConfigurationKeyError::new_err(print_with_debug(err))
+        }
+        _ => GenericError::new_err(print_with_debug(err)),
+    }
+}
+
+/// Details of the failed HTTP request behind an error, exposed as the `status`, `body` and
+/// `retries` attributes of the Python exception.
+///
+/// They're taken from the [`ResponseError`] in the sources of an error, which the retry policies
+/// of a store return for a request that got an error response once its retries were exhausted.
+#[derive(Debug, Default)]
+struct RequestDetails {
+    /// The status of the last response
+    status: Option<u16>,
+    /// The body of the last response
+    body: Option<String>,
+    /// How many times the request was retried
+    retries: Option<usize>,
+}
+
+impl RequestDetails {
+    fn from_error(err: &object_store::Error) -> Self {
+        let mut source = std::error::Error::source(err);
+        while let Some(err) = source {
+            if let Some(err) = err.downcast_ref::<ResponseError>() {
+                return Self {
+                    status: Some(err.status().as_u16()),
+                    body: err.body().map(String::from),
+                    retries: Some(err.retries()),
+                };
+            }
+            source = err.source();
+        }
+        Self::default()
+    }
+
+    /// Set the details as attributes of `err`.
+    fn annotate(self, err: &PyErr) {
+        Python::with_gil(|py| {
+            let value = err.value(py);
+            // Setting attributes only fails on exceptions with `__slots__`
+            let _ = value.setattr(intern!(py, "status"), self.status);
+            let _ = value.setattr(intern!(py, "body"), self.body);
+            let _ = value.setattr(intern!(py, "retries"), self.retries);
+        })
+    }
+}
+
+/// The kind of the HTTP error that caused `err`, if any.
+///
+/// An operation that didn't complete by its deadline is treated as a timeout.
+fn network_error_kind(err: &object_store::Error) -> Option<HttpErrorKind> {
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if err.is::<DeadlineExceeded>() {
+            return Some(HttpErrorKind::Timeout);
+        }
+        if let Some(err) = err.downcast_ref::<HttpError>() {
+            return Some(err.kind());
+        }
+        if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            if err.is_timeout() {
+                return Some(HttpErrorKind::Timeout);
+            }
+            if err.is_connect() {
+                return Some(HttpErrorKind::Connect);
+            }
+        }
+        source = err.source();
+    }
+    None
+}
+
 fn print_with_debug(err: &object_store::Error) -> String {
     // #? gives "pretty-printing" for debug
     // https://doc.rust-lang.org/std/fmt/trait.Debug.html
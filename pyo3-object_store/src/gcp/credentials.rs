@@ -2,14 +2,20 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use chrono::{DateTime, TimeDelta, Utc};
+use futures::future::BoxFuture;
 use object_store::gcp::GcpCredential;
 use object_store::CredentialProvider;
-use pyo3::exceptions::PyTypeError;
+use pyo3::exceptions::{PyTypeError, PyValueError};
 use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
 use pyo3::types::PyTuple;
 
-use crate::credentials::{is_awaitable, TemporaryToken, TokenCache};
+use crate::credentials::{
+    background_refresh, chain_providers, fetch_first, is_awaitable, PyCredentialProviderHandle,
+    TemporaryToken, TokenCache,
+};
+use crate::gcp::providers::GcpBuiltinProvider;
 
 /// Ref https://github.com/apache/arrow-rs/pull/6638
 const DEFAULT_GCP_MIN_TTL: TimeDelta = TimeDelta::minutes(4);
@@ -45,13 +51,20 @@ impl<'py> FromPyObject<'py> for PyGcpCredential {
 // (so you don't need to access a mutex)
 #[derive(Debug)]
 pub struct PyGcpCredentialProvider {
-    /// The provided user callback to manage credential refresh
+    /// The provided user callback to manage credential refresh, the name of a built-in
+    /// provider, or a chain of providers
     user_callback: PyObject,
-    /// The provided user callback to manage credential refresh
-    cache: TokenCache<Arc<GcpCredential>>,
+    /// Where credentials are fetched from, as given by `user_callback`
+    source: Arc<GcpCredentialSource>,
+    cache: Arc<TokenCache<Arc<GcpCredential>>>,
 }
 
 impl PyGcpCredentialProvider {
+    /// The handle returned by the store's `credential_provider` property.
+    pub(crate) fn handle(&self, py: Python) -> PyCredentialProviderHandle {
+        PyCredentialProviderHandle::new(self.user_callback.clone_ref(py), self.cache.clone())
+    }
+
     fn equals(&self, py: Python, other: &Self) -> PyResult<bool> {
         self.user_callback
             .call_method1(py, "__eq__", PyTuple::new(py, vec![&other.user_callback])?)?
@@ -64,6 +77,7 @@ impl Clone for PyGcpCredentialProvider {
         let cloned_callback = Python::with_gil(|py| self.user_callback.clone_ref(py));
         Self {
             user_callback: cloned_callback,
+            source: self.source.clone(),
             cache: self.cache.clone(),
         }
     }
@@ -77,10 +91,13 @@ impl PartialEq for PyGcpCredentialProvider {
 
 impl<'py> FromPyObject<'py> for PyGcpCredentialProvider {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
-            return Err(PyTypeError::new_err(
-                "Expected callable object for credential_provider.",
-            ));
+        let source = GcpCredentialSource::extract(ob)?;
+        if !matches!(source, GcpCredentialSource::Callback(_)) {
+            return Ok(Self {
+                user_callback: ob.clone().unbind(),
+                source: Arc::new(source),
+                cache: Arc::new(TokenCache::default().with_min_ttl(DEFAULT_GCP_MIN_TTL)),
+            });
         }
         let min_ttl =
             if let Ok(refresh_threshold) = ob.getattr(intern!(ob.py(), "refresh_threshold")) {
@@ -88,10 +105,13 @@ impl<'py> FromPyObject<'py> for PyGcpCredentialProvider {
             } else {
                 DEFAULT_GCP_MIN_TTL
             };
-        let cache = TokenCache::default().with_min_ttl(min_ttl);
+        let cache = TokenCache::default()
+            .with_min_ttl(min_ttl)
+            .with_background_refresh(background_refresh(ob)?);
         Ok(Self {
             user_callback: ob.clone().unbind(),
-            cache,
+            source: Arc::new(source),
+            cache: Arc::new(cache),
         })
     }
 }
@@ -116,6 +136,64 @@ impl<'py> IntoPyObject<'py> for PyGcpCredentialProvider {
     }
 }
 
+/// Where a credential provider fetches credentials from.
+#[derive(Debug)]
+enum GcpCredentialSource {
+    /// A Python callback
+    Callback(PyObject),
+    /// A built-in provider, selected by name
+    Builtin(GcpBuiltinProvider),
+    /// Sources that are tried in order until one returns a credential
+    Chain(Vec<GcpCredentialSource>),
+}
+
+impl GcpCredentialSource {
+    fn extract(ob: &Bound<PyAny>) -> PyResult<Self> {
+        if let Some(providers) = chain_providers(ob)? {
+            let sources = providers
+                .iter()
+                .map(Self::extract)
+                .collect::<PyResult<_>>()?;
+            return Ok(Self::Chain(sources));
+        }
+        if let Ok(name) = ob.extract::<PyBackedStr>() {
+            let builtin = GcpBuiltinProvider::parse(&name).ok_or_else(|| {
+                PyValueError::new_err(format!(
+                    "Unknown credential_provider: {}. Expected one of 'adc', 'metadata', 'metadata:<scopes>', or 'impersonate:<service accounts>'.",
+                    &*name
+                ))
+            })?;
+            return Ok(Self::Builtin(builtin));
+        }
+        if !ob.hasattr(intern!(ob.py(), "__call__"))? {
+            return Err(PyTypeError::new_err(
+                "Expected callable object for credential_provider.",
+            ));
+        }
+        Ok(Self::Callback(ob.clone().unbind()))
+    }
+
+    fn fetch(&self) -> BoxFuture<'_, object_store::Result<TemporaryToken<Arc<GcpCredential>>>> {
+        Box::pin(async move {
+            match self {
+                Self::Callback(user_callback) => {
+                    PyGcpCredentialProvider::fetch_token(user_callback).await
+                }
+                Self::Builtin(builtin) => {
+                    builtin
+                        .fetch()
+                        .await
+                        .map_err(|err| object_store::Error::Unauthenticated {
+                            path: format!("GCP {} credential provider", builtin),
+                            source: Box::new(err),
+                        })
+                }
+                Self::Chain(sources) => fetch_first(sources.iter().map(Self::fetch)).await,
+            }
+        })
+    }
+}
+
 /// Note: This is copied across providers at the moment
 enum PyCredentialProviderResult {
     Async(PyObject),
@@ -151,9 +229,9 @@ impl PyGcpCredentialProvider {
     /// Call the user-provided callback and extract to a token.
     ///
     /// This is separate from `fetch_token` below so that it can return a `PyResult`.
-    async fn call(&self) -> PyResult<PyGcpCredential> {
+    async fn call(user_callback: &PyObject) -> PyResult<PyGcpCredential> {
         let call_result = Python::with_gil(|py| {
-            self.user_callback
+            user_callback
                 .call0(py)?
                 .extract::<PyCredentialProviderResult>(py)
         })?;
@@ -161,14 +239,15 @@ impl PyGcpCredentialProvider {
     }
 
     /// Call the user-provided callback
-    async fn fetch_token(&self) -> object_store::Result<TemporaryToken<Arc<GcpCredential>>> {
-        let credential = self
-            .call()
-            .await
-            .map_err(|err| object_store::Error::Unauthenticated {
+    async fn fetch_token(
+        user_callback: &PyObject,
+    ) -> object_store::Result<TemporaryToken<Arc<GcpCredential>>> {
+        let credential = Self::call(user_callback).await.map_err(|err| {
+            object_store::Error::Unauthenticated {
                 path: "External GCP credential provider".to_string(),
                 source: Box::new(err),
-            })?;
+            }
+        })?;
 
         Ok(TemporaryToken {
             token: Arc::new(credential.credential),
@@ -182,6 +261,17 @@ impl CredentialProvider for PyGcpCredentialProvider {
     type Credential = GcpCredential;
 
     async fn get_credential(&self) -> object_store::Result<Arc<Self::Credential>> {
-        self.cache.get_or_insert_with(|| self.fetch_token()).await
+        let credential = self
+            .cache
+            .get_or_insert_with(|| self.source.fetch())
+            .await?;
+        if self.cache.needs_refresh_task() {
+            let source = self.source.clone();
+            self.cache.spawn_refresh(move || {
+                let source = source.clone();
+                async move { source.fetch().await }
+            });
+        }
+        Ok(credential)
     }
 }
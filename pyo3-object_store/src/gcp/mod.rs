@@ -1,4 +1,5 @@
 mod credentials;
+mod providers;
 mod store;
 
 pub use store::PyGCSStore;
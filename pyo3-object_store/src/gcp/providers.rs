@@ -0,0 +1,422 @@
+//! Credential providers implemented in Rust, which can be selected by name instead of passing a
+//! Python callback as `credential_provider`.
+//!
+//! These fetch OAuth access tokens from application default credentials, the metadata server,
+//! or by impersonating a service account.
+//!
+//! <https://cloud.google.com/docs/authentication/application-default-credentials>
+
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, TimeDelta, Utc};
+use futures::future::BoxFuture;
+use http::Method;
+use object_store::client::{HttpClient, HttpConnector, HttpRequestBody, ReqwestConnector};
+use object_store::gcp::GcpCredential;
+use object_store::ClientOptions;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::credentials::TemporaryToken;
+
+type Result<T, E = object_store::Error> = std::result::Result<T, E>;
+
+/// The scope of tokens, unless the metadata server is asked for others
+const DEFAULT_SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+
+const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+
+const DEFAULT_METADATA_HOST: &str = "metadata.google.internal";
+
+/// The lifetime of tokens requested from service account keys and impersonation
+const TOKEN_LIFETIME: TimeDelta = TimeDelta::hours(1);
+
+/// The maximum number of nested impersonations in an ADC file
+const MAX_ADC_DEPTH: usize = 8;
+
+/// A credential provider selected by name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GcpBuiltinProvider {
+    /// `"adc"`: application default credentials, from `GOOGLE_APPLICATION_CREDENTIALS`, the file
+    /// written by `gcloud auth application-default login`, or the metadata server.
+    Adc,
+    /// `"metadata"` or `"metadata:<scope>,<scope>"`: the service account of the instance, from
+    /// the metadata server, with the given scopes.
+    Metadata(Vec<String>),
+    /// `"impersonate:<service account>"` or `"impersonate:<delegate>,...,<service account>"`:
+    /// the last service account of the chain, impersonated with application default credentials
+    /// through each delegate in turn.
+    Impersonate(Vec<String>),
+}
+
+impl GcpBuiltinProvider {
+    /// Parse the name of a provider, returning `None` if `s` isn't one.
+    pub(crate) fn parse(s: &str) -> Option<Self> {
+        let split = |list: &str| -> Vec<String> {
+            list.split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(String::from)
+                .collect()
+        };
+        match s.split_once(':') {
+            None if s == "adc" => Some(Self::Adc),
+            None if s == "metadata" => Some(Self::Metadata(vec![])),
+            Some(("metadata", scopes)) if !split(scopes).is_empty() => {
+                Some(Self::Metadata(split(scopes)))
+            }
+            Some(("impersonate", chain)) if !split(chain).is_empty() => {
+                Some(Self::Impersonate(split(chain)))
+            }
+            _ => None,
+        }
+    }
+
+    pub(crate) async fn fetch(&self) -> Result<TemporaryToken<Arc<GcpCredential>>> {
+        let client = client()?;
+        let token = match self {
+            Self::Adc => adc_token(&client).await?,
+            Self::Metadata(scopes) => metadata_token(&client, scopes).await?,
+            Self::Impersonate(chain) => {
+                let source = adc_token(&client).await?;
+                let (target, delegates) = chain.split_last().expect("chain is not empty");
+                impersonate(&client, &source.token, target, delegates).await?
+            }
+        };
+        Ok(TemporaryToken {
+            token: Arc::new(GcpCredential {
+                bearer: token.token,
+            }),
+            expiry: Some(token.expiry),
+        })
+    }
+}
+
+impl fmt::Display for GcpBuiltinProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Adc => write!(f, "adc"),
+            Self::Metadata(scopes) if scopes.is_empty() => write!(f, "metadata"),
+            Self::Metadata(scopes) => write!(f, "metadata:{}", scopes.join(",")),
+            Self::Impersonate(chain) => write!(f, "impersonate:{}", chain.join(",")),
+        }
+    }
+}
+
+fn error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "GCS",
+        source: msg.into().into(),
+    }
+}
+
+/// The client to make requests with. The metadata server is only served over plain HTTP.
+fn client() -> Result<HttpClient> {
+    ReqwestConnector::default().connect(&ClientOptions::new().with_allow_http(true))
+}
+
+fn env_var(key: &str) -> Option<String> {
+    std::env::var(key).ok().filter(|value| !value.is_empty())
+}
+
+/// An access token and its expiry.
+struct Token {
+    token: String,
+    expiry: DateTime<Utc>,
+}
+
+/// Send `request`, returning the body of a successful response.
+async fn send(client: &HttpClient, request: http::Request<HttpRequestBody>) -> Result<String> {
+    let uri = request.uri().clone();
+    let response = client
+        .execute(request)
+        .await
+        .map_err(|err| error(format!("Request to {uri} failed: {err}")))?;
+    let status = response.status();
+    let body = response
+        .into_body()
+        .bytes()
+        .await
+        .map_err(|err| error(format!("Request to {uri} failed: {err}")))?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    if !status.is_success() {
+        return Err(error(format!(
+            "Request to {uri} failed with status {status}: {body}"
+        )));
+    }
+    Ok(body)
+}
+
+#[derive(Deserialize)]
+struct OAuthToken {
+    access_token: String,
+    /// Seconds from now
+    expires_in: i64,
+}
+
+impl OAuthToken {
+    fn parse(body: &str) -> Result<Token> {
+        let token: Self = serde_json::from_str(body)
+            .map_err(|err| error(format!("Invalid token response: {err}")))?;
+        Ok(Token {
+            token: token.access_token,
+            expiry: Utc::now() + TimeDelta::seconds(token.expires_in),
+        })
+    }
+}
+
+/// POST a form to the OAuth token endpoint.
+async fn request_token(
+    client: &HttpClient,
+    token_uri: &str,
+    form: &[(&str, &str)],
+) -> Result<Token> {
+    let body = url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(form)
+        .finish();
+    let request = http::Request::builder()
+        .method(Method::POST)
+        .uri(token_uri)
+        .header(
+            http::header::CONTENT_TYPE,
+            "application/x-www-form-urlencoded",
+        )
+        .body(HttpRequestBody::from(body.into_bytes()))
+        .map_err(|err| error(err.to_string()))?;
+    OAuthToken::parse(&send(client, request).await?)
+}
+
+/// The path of the ADC file, if any: `GOOGLE_APPLICATION_CREDENTIALS`, or the file written by
+/// `gcloud auth application-default login`.
+fn adc_path() -> Option<PathBuf> {
+    if let Some(path) = env_var("GOOGLE_APPLICATION_CREDENTIALS") {
+        return Some(PathBuf::from(path));
+    }
+    let config_dir = if cfg!(windows) {
+        PathBuf::from(env_var("APPDATA")?).join("gcloud")
+    } else {
+        PathBuf::from(env_var("HOME")?)
+            .join(".config")
+            .join("gcloud")
+    };
+    let path = config_dir.join("application_default_credentials.json");
+    path.exists().then_some(path)
+}
+
+/// Fetch a token with application default credentials, falling back to the metadata server if
+/// there's no ADC file.
+async fn adc_token(client: &HttpClient) -> Result<Token> {
+    let Some(path) = adc_path() else {
+        return metadata_token(client, &[]).await;
+    };
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|err| error(format!("Failed to read {}: {err}", path.display())))?;
+    let json: Value = serde_json::from_str(&contents).map_err(|err| {
+        error(format!(
+            "Invalid credentials file {}: {err}",
+            path.display()
+        ))
+    })?;
+    json_token(client, &json, 0).await
+}
+
+/// Fetch a token with the credentials of an ADC file. `depth` is the number of nested
+/// `source_credentials` followed so far.
+fn json_token<'a>(
+    client: &'a HttpClient,
+    json: &'a Value,
+    depth: usize,
+) -> BoxFuture<'a, Result<Token>> {
+    Box::pin(async move {
+        if depth > MAX_ADC_DEPTH {
+            return Err(error("Too many nested source_credentials"));
+        }
+        let field = |key: &str| {
+            json[key]
+                .as_str()
+                .ok_or_else(|| error(format!("Credentials file is missing {key}")))
+        };
+        match field("type")? {
+            "authorized_user" => {
+                let token_uri = json["token_uri"].as_str().unwrap_or(DEFAULT_TOKEN_URI);
+                request_token(
+                    client,
+                    token_uri,
+                    &[
+                        ("grant_type", "refresh_token"),
+                        ("client_id", field("client_id")?),
+                        ("client_secret", field("client_secret")?),
+                        ("refresh_token", field("refresh_token")?),
+                    ],
+                )
+                .await
+            }
+            "service_account" => {
+                let token_uri = json["token_uri"].as_str().unwrap_or(DEFAULT_TOKEN_URI);
+                let assertion = service_account_jwt(
+                    field("client_email")?,
+                    field("private_key")?,
+                    json["private_key_id"].as_str(),
+                    token_uri,
+                )?;
+                request_token(
+                    client,
+                    token_uri,
+                    &[
+                        ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                        ("assertion", &assertion),
+                    ],
+                )
+                .await
+            }
+            "impersonated_service_account" => {
+                let source = json_token(client, &json["source_credentials"], depth + 1).await?;
+                // e.g. https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/<email>:generateAccessToken
+                let url = field("service_account_impersonation_url")?;
+                let target = url
+                    .rsplit('/')
+                    .next()
+                    .and_then(|name| name.strip_suffix(":generateAccessToken"))
+                    .ok_or_else(|| {
+                        error(format!("Invalid service_account_impersonation_url: {url}"))
+                    })?;
+                let delegates = json["delegates"]
+                    .as_array()
+                    .map(|delegates| {
+                        delegates
+                            .iter()
+                            .filter_map(|delegate| delegate.as_str())
+                            // Delegates are written as full resource names
+                            .map(|delegate| delegate.rsplit('/').next().unwrap_or(delegate))
+                            .map(String::from)
+                            .collect::<Vec<_>>()
+                    })
+                    .unwrap_or_default();
+                impersonate(client, &source.token, target, &delegates).await
+            }
+            other => Err(error(format!("Unsupported credentials type: {other}"))),
+        }
+    })
+}
+
+/// Create the signed JWT that a service account key exchanges for a token.
+fn service_account_jwt(
+    email: &str,
+    private_key: &str,
+    key_id: Option<&str>,
+    token_uri: &str,
+) -> Result<String> {
+    let der = base64::prelude::BASE64_STANDARD
+        .decode(
+            private_key
+                .lines()
+                .filter(|line| !line.starts_with("-----"))
+                .collect::<String>(),
+        )
+        .map_err(|err| error(format!("Invalid private key: {err}")))?;
+    let key = ring::signature::RsaKeyPair::from_pkcs8(&der)
+        .map_err(|err| error(format!("Invalid private key: {err}")))?;
+
+    let now = Utc::now();
+    let mut header = json!({ "alg": "RS256", "typ": "JWT" });
+    if let Some(key_id) = key_id {
+        header["kid"] = json!(key_id);
+    }
+    let claims = json!({
+        "iss": email,
+        "scope": DEFAULT_SCOPE,
+        "aud": token_uri,
+        "iat": now.timestamp(),
+        "exp": (now + TOKEN_LIFETIME).timestamp(),
+    });
+    let message = format!(
+        "{}.{}",
+        BASE64_URL_SAFE_NO_PAD.encode(header.to_string()),
+        BASE64_URL_SAFE_NO_PAD.encode(claims.to_string())
+    );
+    let mut signature = vec![0; key.public().modulus_len()];
+    key.sign(
+        &ring::signature::RSA_PKCS1_SHA256,
+        &ring::rand::SystemRandom::new(),
+        message.as_bytes(),
+        &mut signature,
+    )
+    .map_err(|err| error(format!("Failed to sign JWT: {err}")))?;
+    Ok(format!(
+        "{}.{}",
+        message,
+        BASE64_URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// Fetch a token of the instance's service account from the metadata server.
+async fn metadata_token(client: &HttpClient, scopes: &[String]) -> Result<Token> {
+    let host = env_var("GCE_METADATA_HOST").unwrap_or_else(|| DEFAULT_METADATA_HOST.to_string());
+    let mut url = Url::parse(&format!(
+        "http://{host}/computeMetadata/v1/instance/service-accounts/default/token"
+    ))
+    .map_err(|err| error(format!("Invalid metadata host {host}: {err}")))?;
+    if !scopes.is_empty() {
+        url.query_pairs_mut()
+            .append_pair("scopes", &scopes.join(","));
+    }
+    let request = http::Request::builder()
+        .method(Method::GET)
+        .uri(url.as_str())
+        .header("Metadata-Flavor", "Google")
+        .body(HttpRequestBody::empty())
+        .map_err(|err| error(err.to_string()))?;
+    OAuthToken::parse(&send(client, request).await?)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ImpersonatedToken {
+    access_token: String,
+    expire_time: String,
+}
+
+/// Impersonate the service account `target` with the `source` token, through each of
+/// `delegates` in turn.
+///
+/// <https://cloud.google.com/iam/docs/reference/credentials/rest/v1/projects.serviceAccounts/generateAccessToken>
+async fn impersonate(
+    client: &HttpClient,
+    source: &str,
+    target: &str,
+    delegates: &[String],
+) -> Result<Token> {
+    let url = format!(
+        "https://iamcredentials.googleapis.com/v1/projects/-/serviceAccounts/{target}:generateAccessToken"
+    );
+    let body = json!({
+        "delegates": delegates
+            .iter()
+            .map(|delegate| format!("projects/-/serviceAccounts/{delegate}"))
+            .collect::<Vec<_>>(),
+        "scope": [DEFAULT_SCOPE],
+        "lifetime": format!("{}s", TOKEN_LIFETIME.num_seconds()),
+    });
+    let request = http::Request::builder()
+        .method(Method::POST)
+        .uri(url)
+        .header(http::header::AUTHORIZATION, format!("Bearer {source}"))
+        .header(http::header::CONTENT_TYPE, "application/json")
+        .body(HttpRequestBody::from(body.to_string().into_bytes()))
+        .map_err(|err| error(err.to_string()))?;
+    let body = send(client, request).await?;
+    let token: ImpersonatedToken = serde_json::from_str(&body)
+        .map_err(|err| error(format!("Invalid impersonation response: {err}")))?;
+    let expiry = DateTime::parse_from_rfc3339(&token.expire_time)
+        .map_err(|err| error(format!("Invalid token expiry {}: {err}", token.expire_time)))?
+        .with_timezone(&Utc);
+    Ok(Token {
+        token: token.access_token,
+        expiry,
+    })
+}
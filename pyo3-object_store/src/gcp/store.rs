@@ -1,20 +1,28 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use object_store::client::{HttpClient, HttpConnector};
 use object_store::gcp::{GoogleCloudStorage, GoogleCloudStorageBuilder, GoogleConfigKey};
 use object_store::ObjectStoreScheme;
 use pyo3::prelude::*;
 use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyDict, PyString, PyTuple, PyType};
-use pyo3::{intern, IntoPyObjectExt};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
 use url::Url;
 
-use crate::client::PyClientOptions;
+use crate::client::{client_options_from_builder, PyClientOptions};
 use crate::config::PyConfigValue;
+use crate::connector::StoreConnector;
+use crate::credentials::PyCredentialProviderHandle;
 use crate::error::{GenericError, ParseUrlError, PyObjectStoreError, PyObjectStoreResult};
 use crate::gcp::credentials::PyGcpCredentialProvider;
+use crate::limit::{LimitedStore, PyLimitConfig};
 use crate::path::PyPath;
+use crate::pool::ConnectionPool;
+use crate::resolver::PyResolver;
 use crate::retry::PyRetryConfig;
+use crate::tags::{PyRequestTags, TagStyle};
+use crate::token::{encode_token, store_from_token};
 use crate::{MaybePrefixedStore, PyUrl};
 
 #[derive(Debug, Clone, PartialEq)]
@@ -23,7 +31,10 @@ struct GCSConfig {
     config: PyGoogleConfig,
     client_options: Option<PyClientOptions>,
     retry_config: Option<PyRetryConfig>,
+    limit_config: Option<PyLimitConfig>,
     credential_provider: Option<PyGcpCredentialProvider>,
+    resolver: Option<PyResolver>,
+    request_tags: Option<PyRequestTags>,
 }
 
 impl GCSConfig {
@@ -35,6 +46,20 @@ impl GCSConfig {
             .as_ref()
     }
 
+    /// The config without the service account key, for `to_token`.
+    fn without_secrets(&self) -> Self {
+        let mut config = self.clone();
+        config
+            .config
+            .0
+            .retain(|key, _| key.0 != GoogleConfigKey::ServiceAccountKey);
+        config.client_options = config
+            .client_options
+            .as_ref()
+            .map(PyClientOptions::without_secrets);
+        config
+    }
+
     fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
         let args = PyTuple::empty(py).into_py_any(py)?;
         let kwargs = PyDict::new(py);
@@ -49,9 +74,18 @@ impl GCSConfig {
         if let Some(retry_config) = &self.retry_config {
             kwargs.set_item(intern!(py, "retry_config"), retry_config)?;
         }
+        if let Some(limit_config) = &self.limit_config {
+            kwargs.set_item(intern!(py, "limit_config"), limit_config)?;
+        }
         if let Some(credential_provider) = &self.credential_provider {
             kwargs.set_item("credential_provider", credential_provider)?;
         }
+        if let Some(resolver) = &self.resolver {
+            kwargs.set_item(intern!(py, "resolver"), resolver)?;
+        }
+        if let Some(request_tags) = &self.request_tags {
+            kwargs.set_item(intern!(py, "request_tags"), request_tags)?;
+        }
 
         PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
     }
@@ -61,36 +95,108 @@ impl GCSConfig {
 #[derive(Debug, Clone)]
 #[pyclass(name = "GCSStore", frozen, subclass)]
 pub struct PyGCSStore {
-    store: Arc<MaybePrefixedStore<GoogleCloudStorage>>,
+    store: Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>>,
     /// A config used for pickling. This must stay in sync with the underlying store's config.
     config: GCSConfig,
+    /// The connections of the store, reset with `reset_connections`.
+    pool: ConnectionPool,
 }
 
-impl AsRef<Arc<MaybePrefixedStore<GoogleCloudStorage>>> for PyGCSStore {
-    fn as_ref(&self) -> &Arc<MaybePrefixedStore<GoogleCloudStorage>> {
+impl AsRef<Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>>> for PyGCSStore {
+    fn as_ref(&self) -> &Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>> {
         &self.store
     }
 }
 
 impl PyGCSStore {
     /// Consume self and return the underlying [`GoogleCloudStorage`].
-    pub fn into_inner(self) -> Arc<MaybePrefixedStore<GoogleCloudStorage>> {
+    pub fn into_inner(self) -> Arc<LimitedStore<MaybePrefixedStore<GoogleCloudStorage>>> {
         self.store
     }
+
+    /// The bucket of the store, for making requests that [`GoogleCloudStorage`] doesn't support.
+    pub fn bucket(&self) -> &str {
+        self.config.bucket()
+    }
+
+    /// The JSON service account key of the store, if it's configured with one, for signing
+    /// requests that [`GoogleCloudStorage`] doesn't support.
+    ///
+    /// This looks for the key in the same config values and environment variables as
+    /// [`GoogleCloudStorageBuilder`], reading it from a file if the config gives its path.
+    pub fn service_account_key(&self) -> Option<String> {
+        let config_value = |key| {
+            self.config
+                .config
+                .0
+                .get(&PyGoogleConfigKey(key))
+                .map(|value| value.as_ref().to_string())
+        };
+        let env_value = |names: &[&str]| names.iter().find_map(|name| std::env::var(name).ok());
+
+        if let Some(key) = config_value(GoogleConfigKey::ServiceAccountKey)
+            .or_else(|| env_value(&["GOOGLE_SERVICE_ACCOUNT_KEY"]))
+        {
+            return Some(key);
+        }
+        let path = config_value(GoogleConfigKey::ServiceAccount)
+            .or_else(|| config_value(GoogleConfigKey::ApplicationCredentials))
+            .or_else(|| {
+                env_value(&[
+                    "GOOGLE_SERVICE_ACCOUNT",
+                    "GOOGLE_SERVICE_ACCOUNT_PATH",
+                    "GOOGLE_APPLICATION_CREDENTIALS",
+                ])
+            })?;
+        std::fs::read_to_string(path).ok()
+    }
+
+    /// An HTTP client with the client options, connections and request behavior of the store,
+    /// for making requests that [`GoogleCloudStorage`] doesn't support.
+    ///
+    /// Requests are retried with the retry config of the store, as `object_store` retries its own.
+    pub fn http_client(&self) -> object_store::Result<HttpClient> {
+        // Configured in the same order as the builder of the store
+        let mut builder = self
+            .config
+            .config
+            .clone()
+            .apply_config(GoogleCloudStorageBuilder::from_env());
+        if let Some(client_options) = self.config.client_options.clone() {
+            builder = builder.with_client_options(client_options.into());
+        }
+        let options = client_options_from_builder(|key| {
+            builder.get_config_value(&GoogleConfigKey::Client(key))
+        });
+        let client = StoreConnector::new(
+            &self.pool,
+            self.config.client_options.as_ref(),
+            self.config.retry_config.as_ref(),
+            self.config.resolver.as_ref(),
+            self.config.request_tags.as_ref(),
+            TagStyle::GcsAuditHeaders,
+        )
+        .connect(&options)?;
+        Ok(PyRetryConfig::request_policy(self.config.retry_config.as_ref()).wrap(client))
+    }
 }
 
 #[pymethods]
 impl PyGCSStore {
     // Create from parameters
     #[new]
-    #[pyo3(signature = (bucket=None, *, prefix=None, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+    #[pyo3(signature = (bucket=None, *, prefix=None, config=None, client_options=None, retry_config=None, limit_config=None, credential_provider=None, resolver=None, request_tags=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
     fn new(
         bucket: Option<String>,
         prefix: Option<PyPath>,
         config: Option<PyGoogleConfig>,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
         credential_provider: Option<PyGcpCredentialProvider>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
         kwargs: Option<PyGoogleConfig>,
     ) -> PyObjectStoreResult<Self> {
         let mut builder = GoogleCloudStorageBuilder::from_env();
@@ -105,6 +211,18 @@ impl PyGCSStore {
         if let Some(client_options) = client_options.clone() {
             builder = builder.with_client_options(client_options.into())
         }
+        if let Some(request_tags) = &request_tags {
+            request_tags.validate(TagStyle::GcsAuditHeaders)?;
+        }
+        let pool = ConnectionPool::default();
+        builder = builder.with_http_connector(StoreConnector::new(
+            &pool,
+            client_options.as_ref(),
+            retry_config.as_ref(),
+            resolver.as_ref(),
+            request_tags.as_ref(),
+            TagStyle::GcsAuditHeaders,
+        ));
         if let Some(retry_config) = retry_config.clone() {
             builder = builder.with_retry(retry_config.into())
         }
@@ -112,26 +230,37 @@ impl PyGCSStore {
             builder = builder.with_credentials(Arc::new(credential_provider));
         }
         Ok(Self {
-            store: Arc::new(MaybePrefixedStore::new(builder.build()?, prefix.clone())),
+            pool,
+            store: Arc::new(LimitedStore::new(
+                MaybePrefixedStore::new(builder.build()?, prefix.clone()),
+                limit_config.as_ref(),
+            )),
             config: GCSConfig {
                 prefix,
                 config: combined_config,
                 client_options,
                 retry_config,
+                limit_config,
                 credential_provider,
+                resolver,
+                request_tags,
             },
         })
     }
 
     #[classmethod]
-    #[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+    #[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, limit_config=None, credential_provider=None, resolver=None, request_tags=None, **kwargs))]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_url(
         cls: &Bound<PyType>,
         url: PyUrl,
         config: Option<PyGoogleConfig>,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
         credential_provider: Option<PyGcpCredentialProvider>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
         kwargs: Option<PyGoogleConfig>,
     ) -> PyObjectStoreResult<PyObject> {
         // We manually parse the URL to find the prefix because `parse_url` does not apply the
@@ -152,7 +281,10 @@ impl PyGCSStore {
         kwargs.set_item("config", config)?;
         kwargs.set_item("client_options", client_options)?;
         kwargs.set_item("retry_config", retry_config)?;
+        kwargs.set_item("limit_config", limit_config)?;
         kwargs.set_item("credential_provider", credential_provider)?;
+        kwargs.set_item("resolver", resolver)?;
+        kwargs.set_item("request_tags", request_tags)?;
         Ok(cls.call((), Some(&kwargs))?.unbind())
     }
 
@@ -169,6 +301,25 @@ impl PyGCSStore {
         self.config.__getnewargs_ex__(py)
     }
 
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        let config = if allow_secrets {
+            self.config.clone()
+        } else {
+            self.config.without_secrets()
+        };
+        encode_token(py, Self::NAME, config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
+    fn reset_connections(&self) {
+        self.pool.reset();
+    }
+
     fn __repr__(&self) -> String {
         let bucket = self.config.bucket();
         if let Some(prefix) = &self.config.prefix {
@@ -198,14 +349,32 @@ impl PyGCSStore {
     }
 
     #[getter]
-    fn credential_provider(&self) -> Option<&PyGcpCredentialProvider> {
-        self.config.credential_provider.as_ref()
+    fn credential_provider(&self, py: Python) -> Option<PyCredentialProviderHandle> {
+        self.config
+            .credential_provider
+            .as_ref()
+            .map(|provider| provider.handle(py))
     }
 
     #[getter]
     fn retry_config(&self) -> Option<&PyRetryConfig> {
         self.config.retry_config.as_ref()
     }
+
+    #[getter]
+    fn resolver(&self) -> Option<&PyResolver> {
+        self.config.resolver.as_ref()
+    }
+
+    #[getter]
+    fn request_tags(&self) -> Option<&PyRequestTags> {
+        self.config.request_tags.as_ref()
+    }
+
+    #[getter]
+    fn limit_config(&self) -> Option<&PyLimitConfig> {
+        self.config.limit_config.as_ref()
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
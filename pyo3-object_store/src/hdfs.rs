@@ -0,0 +1,878 @@
+//! An object store for HDFS, through the WebHDFS REST API of a namenode or an HttpFS gateway.
+//!
+//! <https://hadoop.apache.org/docs/stable/hadoop-project-dist/hadoop-hdfs/WebHDFS.html>
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Instant;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::future::{BoxFuture, Shared};
+use futures::stream::BoxStream;
+use futures::{FutureExt, StreamExt, TryStreamExt};
+use http::header::CONTENT_TYPE;
+use http::{Method, StatusCode};
+use object_store::client::{
+    HttpClient, HttpConnector, HttpErrorKind, HttpRequestBody, HttpResponse,
+};
+use object_store::path::Path;
+use object_store::{
+    Attributes, ClientOptions, GetOptions, GetResult, GetResultPayload, ListResult,
+    MultipartUpload, ObjectMeta, ObjectStore, PutMode, PutMultipartOptions, PutOptions, PutPayload,
+    PutResult, Result, RetryConfig, UploadPart,
+};
+use percent_encoding::percent_decode_str;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple, PyType};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
+use serde::Deserialize;
+use url::Url;
+
+use crate::connector::StoreConnector;
+use crate::error::PyObjectStoreResult;
+use crate::limit::{LimitedStore, PyLimitConfig};
+use crate::pool::ConnectionPool;
+use crate::retry::{Backoff, PyRetryConfig};
+use crate::staging::{is_staging, staging_path};
+use crate::tags::TagStyle;
+use crate::token::{encode_token, store_from_token};
+use crate::{PyClientOptions, PyRequestTags, PyResolver, PyUrl};
+
+const STORE: &str = "HDFS";
+
+fn generic_error(err: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: err.into(),
+    }
+}
+
+/// The body of an error response.
+#[derive(Debug, Deserialize)]
+struct RemoteExceptionResponse {
+    #[serde(rename = "RemoteException")]
+    remote_exception: RemoteException,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteException {
+    exception: String,
+    #[serde(default)]
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileStatusResponse {
+    #[serde(rename = "FileStatus")]
+    file_status: FileStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileStatusesResponse {
+    #[serde(rename = "FileStatuses")]
+    file_statuses: FileStatuses,
+}
+
+#[derive(Debug, Deserialize)]
+struct FileStatuses {
+    #[serde(rename = "FileStatus")]
+    file_status: Vec<FileStatus>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FileStatus {
+    /// The name of the entry within its directory. Empty for the status of a path itself.
+    path_suffix: String,
+    length: u64,
+    /// Milliseconds since the epoch.
+    modification_time: i64,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BooleanResponse {
+    boolean: bool,
+}
+
+impl FileStatus {
+    fn is_dir(&self) -> bool {
+        self.kind == "DIRECTORY"
+    }
+
+    fn object_meta(&self, location: Path) -> ObjectMeta {
+        ObjectMeta {
+            location,
+            last_modified: DateTime::<Utc>::from_timestamp_millis(self.modification_time)
+                .unwrap_or_default(),
+            size: self.length,
+            e_tag: Some(format!("{:x}-{:x}", self.modification_time, self.length)),
+            version: None,
+        }
+    }
+}
+
+/// How requests are authenticated.
+#[derive(Debug, Clone)]
+enum Auth {
+    /// Pseudo authentication, as the given user.
+    User(String),
+    /// A delegation token, such as one obtained from a cluster secured with Kerberos.
+    DelegationToken(String),
+    /// No authentication, leaving the user to the cluster.
+    None,
+}
+
+/// An [`ObjectStore`] for HDFS, through the WebHDFS REST API.
+///
+/// Writes follow the redirects of a namenode to the datanodes, and are sent with `data=true` so
+/// that an HttpFS gateway accepts them directly.
+#[derive(Debug, Clone)]
+pub struct HdfsStore {
+    client: HttpClient,
+    /// The URL of the root directory of the store, under `/webhdfs/v1`.
+    url: Url,
+    /// The absolute path in HDFS of the root directory of the store, without a trailing `/`.
+    root: String,
+    auth: Auth,
+    retry_config: RetryConfig,
+}
+
+impl std::fmt::Display for HdfsStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "HdfsStore({})", self.url)
+    }
+}
+
+impl HdfsStore {
+    /// The URL of operation `op` on the file or directory at `path`, relative to the root.
+    fn op_url(&self, path: &str, op: &str, params: &[(&str, &str)]) -> Url {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            // The URL was checked to be an HTTP URL on creation
+            .unwrap()
+            .pop_if_empty()
+            .extend(path.split('/').filter(|part| !part.is_empty()));
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("op", op);
+            match &self.auth {
+                Auth::User(user) => {
+                    query.append_pair("user.name", user);
+                }
+                Auth::DelegationToken(token) => {
+                    query.append_pair("delegation", token);
+                }
+                Auth::None => {}
+            }
+            query.extend_pairs(params);
+        }
+        url
+    }
+
+    /// Send a request for operation `op` on `path`, returning a successful response.
+    ///
+    /// Reads are retried on connection errors, as configured by the retry config of the store.
+    /// Server error responses are retried by the client of the store.
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        op: &str,
+        params: &[(&str, &str)],
+        body: Option<Bytes>,
+    ) -> Result<HttpResponse> {
+        let url = self.op_url(path, op, params);
+        let idempotent = method == Method::GET;
+        let start = Instant::now();
+        let mut backoff = Backoff::new(&self.retry_config.backoff);
+        let mut retries = 0;
+        loop {
+            let mut builder = http::Request::builder()
+                .method(method.clone())
+                .uri(url.as_str());
+            if body.is_some() {
+                builder = builder.header(CONTENT_TYPE, "application/octet-stream");
+            }
+            let request = builder
+                .body(HttpRequestBody::from(body.clone().unwrap_or_default()))
+                .map_err(generic_error)?;
+            let result = self.client.execute(request).await;
+            // The client gives up on error responses with an error of unknown kind
+            let retryable = result.as_ref().is_err_and(|err| {
+                !matches!(err.kind(), HttpErrorKind::Unknown | HttpErrorKind::Decode)
+            });
+            let delay = backoff.next_delay();
+            if idempotent
+                && retryable
+                && retries < self.retry_config.max_retries
+                && start.elapsed() + delay <= self.retry_config.retry_timeout
+            {
+                retries += 1;
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+            let response = result.map_err(generic_error)?;
+            if response.status().is_success() {
+                return Ok(response);
+            }
+            return Err(response_error(response, path).await);
+        }
+    }
+
+    async fn send_json<T: serde::de::DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        op: &str,
+        params: &[(&str, &str)],
+    ) -> Result<T> {
+        let response = self.send(method, path, op, params, None).await?;
+        let body = response.into_body().bytes().await.map_err(generic_error)?;
+        serde_json::from_slice(&body).map_err(generic_error)
+    }
+
+    /// The absolute path in HDFS of `path`, relative to the root.
+    fn hdfs_path(&self, path: &str) -> String {
+        format!("{}/{}", self.root, path)
+    }
+
+    async fn file_status(&self, location: &Path) -> Result<FileStatus> {
+        let response: FileStatusResponse = self
+            .send_json(Method::GET, location.as_ref(), "GETFILESTATUS", &[])
+            .await?;
+        Ok(response.file_status)
+    }
+
+    /// The objects and directories directly under `prefix`.
+    async fn list_status(&self, prefix: &Path) -> Result<Vec<(Path, FileStatus)>> {
+        let response: FileStatusesResponse = match self
+            .send_json(Method::GET, prefix.as_ref(), "LISTSTATUS", &[])
+            .await
+        {
+            Ok(response) => response,
+            // A prefix that doesn't exist has no entries
+            Err(object_store::Error::NotFound { .. }) => return Ok(vec![]),
+            Err(err) => return Err(err),
+        };
+        Ok(response
+            .file_statuses
+            .file_status
+            .into_iter()
+            // Listing a file gives the status of the file itself, without a suffix
+            .filter(|status| !status.path_suffix.is_empty() && !is_staging(&status.path_suffix))
+            .map(|status| (prefix.child(status.path_suffix.as_str()), status))
+            .collect())
+    }
+
+    /// Write `data` to the file at `path`, creating its parent directories.
+    async fn create(&self, path: &str, data: Bytes, overwrite: bool) -> Result<()> {
+        let overwrite = if overwrite { "true" } else { "false" };
+        self.send(
+            Method::PUT,
+            path,
+            "CREATE",
+            &[("overwrite", overwrite), ("data", "true")],
+            Some(data),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Move `from` to `to`, replacing any file at `to` if `overwrite` is set.
+    async fn rename_path(&self, from: &str, to: &str, overwrite: bool) -> Result<()> {
+        if let Some((parent, _)) = to.rsplit_once('/') {
+            self.send(Method::PUT, parent, "MKDIRS", &[], None).await?;
+        }
+        if overwrite {
+            // A rename onto an existing file fails
+            self.send(Method::DELETE, to, "DELETE", &[], None).await?;
+        }
+        let destination = self.hdfs_path(to);
+        let response: BooleanResponse = self
+            .send_json(
+                Method::PUT,
+                from,
+                "RENAME",
+                &[("destination", destination.as_str())],
+            )
+            .await?;
+        if !response.boolean {
+            return Err(generic_error(format!(
+                "Could not rename {} to {}",
+                from, to
+            )));
+        }
+        Ok(())
+    }
+
+    async fn copy_opts(&self, from: &Path, to: &Path, overwrite: bool) -> Result<()> {
+        let data = self.get(from).await?.bytes().await?;
+        self.create(to.as_ref(), data, overwrite).await
+    }
+}
+
+/// The error of a failed response for `path`.
+async fn response_error(response: HttpResponse, path: &str) -> object_store::Error {
+    let status = response.status();
+    let body = match response.into_body().bytes().await {
+        Ok(body) => String::from_utf8_lossy(&body).into_owned(),
+        Err(err) => return generic_error(err),
+    };
+    let exception = serde_json::from_str::<RemoteExceptionResponse>(&body)
+        .map(|response| response.remote_exception)
+        .ok();
+    let source = match &exception {
+        Some(exception) => format!("{}: {}", exception.exception, exception.message),
+        None => format!("Request failed with status {}: {}", status, body),
+    };
+    let path = path.to_string();
+    match exception
+        .as_ref()
+        .map(|exception| exception.exception.as_str())
+    {
+        Some("FileNotFoundException") => object_store::Error::NotFound {
+            path,
+            source: source.into(),
+        },
+        Some("FileAlreadyExistsException") => object_store::Error::AlreadyExists {
+            path,
+            source: source.into(),
+        },
+        Some("AccessControlException" | "SecurityException") => {
+            object_store::Error::PermissionDenied {
+                path,
+                source: source.into(),
+            }
+        }
+        _ => match status {
+            StatusCode::NOT_FOUND => object_store::Error::NotFound {
+                path,
+                source: source.into(),
+            },
+            StatusCode::UNAUTHORIZED => object_store::Error::Unauthenticated {
+                path,
+                source: source.into(),
+            },
+            StatusCode::FORBIDDEN => object_store::Error::PermissionDenied {
+                path,
+                source: source.into(),
+            },
+            _ => generic_error(source),
+        },
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for HdfsStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let overwrite = match opts.mode {
+            PutMode::Overwrite => true,
+            PutMode::Create => false,
+            PutMode::Update(_) => return Err(object_store::Error::NotImplemented),
+        };
+        // A single buffer, so that the body can be sent again when following a redirect
+        self.create(location.as_ref(), Bytes::from(payload), overwrite)
+            .await?;
+        Ok(PutResult {
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let staging = staging_path(location.as_ref());
+        self.create(&staging, Bytes::new(), true).await?;
+        Ok(Box::new(HdfsUpload {
+            store: self.clone(),
+            location: location.clone(),
+            staging,
+            previous: None,
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let status = self.file_status(location).await?;
+        if status.is_dir() {
+            return Err(object_store::Error::NotFound {
+                path: location.to_string(),
+                source: "Path is a directory".into(),
+            });
+        }
+        let meta = status.object_meta(location.clone());
+        options.check_preconditions(&meta)?;
+
+        let range = match &options.range {
+            Some(range) => range.as_range(meta.size).map_err(generic_error)?,
+            None => 0..meta.size,
+        };
+        let stream = if options.head || range.is_empty() {
+            futures::stream::empty().boxed()
+        } else {
+            let offset = range.start.to_string();
+            let length = (range.end - range.start).to_string();
+            self.send(
+                Method::GET,
+                location.as_ref(),
+                "OPEN",
+                &[("offset", &offset), ("length", &length)],
+                None,
+            )
+            .await?
+            .into_body()
+            .bytes_stream()
+            .map_err(generic_error)
+            .boxed()
+        };
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream),
+            meta,
+            range,
+            attributes: Attributes::default(),
+        })
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        // Deleting a file that doesn't exist succeeds, with a `false` result
+        self.send(Method::DELETE, location.as_ref(), "DELETE", &[], None)
+            .await?;
+        Ok(())
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let pending = VecDeque::from([prefix.cloned().unwrap_or_default()]);
+        futures::stream::try_unfold((self.clone(), pending), |(store, mut pending)| async move {
+            let Some(prefix) = pending.pop_front() else {
+                return Ok(None);
+            };
+            let mut objects = vec![];
+            for (location, status) in store.list_status(&prefix).await? {
+                if status.is_dir() {
+                    pending.push_back(location);
+                } else {
+                    objects.push(Ok(status.object_meta(location)));
+                }
+            }
+            let objects = futures::stream::iter(objects);
+            Ok::<_, object_store::Error>(Some((objects, (store, pending))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut result = ListResult {
+            common_prefixes: vec![],
+            objects: vec![],
+        };
+        let prefix = prefix.cloned().unwrap_or_default();
+        for (location, status) in self.list_status(&prefix).await? {
+            if status.is_dir() {
+                result.common_prefixes.push(location);
+            } else {
+                result.objects.push(status.object_meta(location));
+            }
+        }
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, true).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, false).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.rename_path(from.as_ref(), to.as_ref(), true).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        match self.file_status(to).await {
+            Ok(_) => Err(object_store::Error::AlreadyExists {
+                path: to.to_string(),
+                source: "Object already exists".into(),
+            }),
+            Err(object_store::Error::NotFound { .. }) => {
+                self.rename_path(from.as_ref(), to.as_ref(), false).await
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// The result of appending a part, shared with the append of the next part.
+type AppendFuture = Shared<BoxFuture<'static, Result<(), Arc<object_store::Error>>>>;
+
+/// A multipart upload to an [`HdfsStore`], whose parts are appended in order to a staging file
+/// that is moved into place on completion.
+#[derive(Debug)]
+struct HdfsUpload {
+    store: HdfsStore,
+    location: Path,
+    staging: String,
+    /// The append of the previous part, which the next part waits for.
+    previous: Option<AppendFuture>,
+}
+
+impl HdfsUpload {
+    /// Wait for all the parts put so far to be appended.
+    async fn wait_for_parts(&self) -> Result<()> {
+        match self.previous.clone() {
+            Some(previous) => previous.await.map_err(|err| generic_error(err.to_string())),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for HdfsUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let store = self.store.clone();
+        let staging = self.staging.clone();
+        let previous = self.previous.take();
+        let append = async move {
+            if let Some(previous) = previous {
+                previous.await?;
+            }
+            store
+                .send(
+                    Method::POST,
+                    &staging,
+                    "APPEND",
+                    &[("data", "true")],
+                    Some(Bytes::from(data)),
+                )
+                .await
+                .map_err(Arc::new)?;
+            Ok(())
+        }
+        .boxed()
+        .shared();
+        self.previous = Some(append.clone());
+        Box::pin(async move { append.await.map_err(|err| generic_error(err.to_string())) })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        self.wait_for_parts().await?;
+        self.store
+            .rename_path(&self.staging, self.location.as_ref(), true)
+            .await?;
+        Ok(PutResult {
+            e_tag: None,
+            version: None,
+        })
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        // Let any append in progress finish before deleting the file it appends to
+        let _ = self.wait_for_parts().await;
+        self.store
+            .send(Method::DELETE, &self.staging, "DELETE", &[], None)
+            .await?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HdfsConfig {
+    url: PyUrl,
+    user: Option<String>,
+    delegation_token: Option<String>,
+    client_options: Option<PyClientOptions>,
+    retry_config: Option<PyRetryConfig>,
+    limit_config: Option<PyLimitConfig>,
+    resolver: Option<PyResolver>,
+    request_tags: Option<PyRequestTags>,
+}
+
+impl HdfsConfig {
+    /// The config without the delegation token, for `to_token`.
+    fn without_secrets(&self) -> Self {
+        Self {
+            delegation_token: None,
+            client_options: self
+                .client_options
+                .as_ref()
+                .map(PyClientOptions::without_secrets),
+            ..self.clone()
+        }
+    }
+
+    fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
+        let args = PyTuple::new(py, vec![self.url.clone()])?.into_py_any(py)?;
+        let kwargs = PyDict::new(py);
+
+        if let Some(user) = &self.user {
+            kwargs.set_item(intern!(py, "user"), user)?;
+        }
+        if let Some(delegation_token) = &self.delegation_token {
+            kwargs.set_item(intern!(py, "delegation_token"), delegation_token)?;
+        }
+        if let Some(client_options) = &self.client_options {
+            kwargs.set_item(intern!(py, "client_options"), client_options.clone())?;
+        }
+        if let Some(retry_config) = &self.retry_config {
+            kwargs.set_item(intern!(py, "retry_config"), retry_config.clone())?;
+        }
+        if let Some(limit_config) = &self.limit_config {
+            kwargs.set_item(intern!(py, "limit_config"), limit_config.clone())?;
+        }
+        if let Some(resolver) = &self.resolver {
+            kwargs.set_item(intern!(py, "resolver"), resolver)?;
+        }
+        if let Some(request_tags) = &self.request_tags {
+            kwargs.set_item(intern!(py, "request_tags"), request_tags)?;
+        }
+
+        PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
+    }
+}
+
+/// The WebHDFS URL of the root directory of a store at `url`, and the absolute path of the
+/// directory in HDFS.
+///
+/// `webhdfs://` and `swebhdfs://` URLs use the default HTTP and HTTPS ports of a namenode.
+fn webhdfs_url(url: &Url) -> PyResult<(Url, String)> {
+    let (scheme, default_port) = match url.scheme() {
+        "http" => ("http", None),
+        "https" => ("https", None),
+        "webhdfs" => ("http", Some(9870)),
+        "swebhdfs" => ("https", Some(9871)),
+        scheme => {
+            return Err(PyValueError::new_err(format!(
+                "Expected an http, https, webhdfs or swebhdfs URL, got {}",
+                scheme
+            )))
+        }
+    };
+    let host = url
+        .host_str()
+        .ok_or_else(|| PyValueError::new_err("The URL has no host"))?;
+    let port = match url.port().or(default_port) {
+        Some(port) => format!(":{}", port),
+        None => String::new(),
+    };
+    // The path of a namenode URL may already include the REST prefix
+    let path = url.path().trim_end_matches('/');
+    let path = path.strip_prefix("/webhdfs/v1").unwrap_or(path);
+    let webhdfs_url = Url::parse(&format!(
+        "{}://{}{}/webhdfs/v1{}/",
+        scheme, host, port, path
+    ))
+    .map_err(|err| PyValueError::new_err(err.to_string()))?;
+    let root = percent_decode_str(path)
+        .decode_utf8()
+        .map_err(|err| PyValueError::new_err(err.to_string()))?
+        .into_owned();
+    Ok((webhdfs_url, root))
+}
+
+/// A Python-facing wrapper around an [`HdfsStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "HdfsStore", frozen, subclass)]
+pub struct PyHdfsStore {
+    store: Arc<LimitedStore<HdfsStore>>,
+    /// A config used for pickling. This must stay in sync with the underlying store's config.
+    config: HdfsConfig,
+    /// The connections of the store, reset with `reset_connections`.
+    pool: ConnectionPool,
+}
+
+impl AsRef<Arc<LimitedStore<HdfsStore>>> for PyHdfsStore {
+    fn as_ref(&self) -> &Arc<LimitedStore<HdfsStore>> {
+        &self.store
+    }
+}
+
+impl PyHdfsStore {
+    /// Consume self and return the underlying [`HdfsStore`].
+    pub fn into_inner(self) -> Arc<LimitedStore<HdfsStore>> {
+        self.store
+    }
+}
+
+#[pymethods]
+impl PyHdfsStore {
+    #[new]
+    #[pyo3(signature = (url, *, user=None, delegation_token=None, client_options=None, retry_config=None, limit_config=None, resolver=None, request_tags=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        url: PyUrl,
+        user: Option<String>,
+        delegation_token: Option<String>,
+        client_options: Option<PyClientOptions>,
+        retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
+    ) -> PyObjectStoreResult<Self> {
+        let (webhdfs_url, root) = webhdfs_url(url.as_ref())?;
+        // A delegation token identifies the user, which can't be given as well
+        let auth = match (&user, &delegation_token) {
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "Only one of user and delegation_token can be given.",
+                )
+                .into())
+            }
+            (Some(user), None) => Auth::User(user.clone()),
+            (None, Some(token)) => Auth::DelegationToken(token.clone()),
+            (None, None) => Auth::None,
+        };
+
+        let pool = ConnectionPool::default();
+        let mut options = client_options
+            .clone()
+            .map(ClientOptions::from)
+            .unwrap_or_default();
+        // Namenodes serve WebHDFS over plain HTTP unless configured otherwise, so a `webhdfs://`
+        // or `http://` URL is taken as asking for it
+        if webhdfs_url.scheme() == "http" {
+            options = options.with_allow_http(true);
+        }
+        let client = StoreConnector::new(
+            &pool,
+            client_options.as_ref(),
+            retry_config.as_ref(),
+            resolver.as_ref(),
+            request_tags.as_ref(),
+            TagStyle::UserAgent,
+        )
+        .connect(&options)?;
+        let store = HdfsStore {
+            client,
+            url: webhdfs_url,
+            root,
+            auth,
+            retry_config: retry_config.clone().map(Into::into).unwrap_or_default(),
+        };
+        Ok(Self {
+            pool,
+            store: Arc::new(LimitedStore::new(store, limit_config.as_ref())),
+            config: HdfsConfig {
+                url,
+                user,
+                delegation_token,
+                client_options,
+                retry_config,
+                limit_config,
+                resolver,
+                request_tags,
+            },
+        })
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (url, *, user=None, delegation_token=None, client_options=None, retry_config=None, limit_config=None, resolver=None, request_tags=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_url(
+        cls: &Bound<PyType>,
+        py: Python,
+        url: PyUrl,
+        user: Option<String>,
+        delegation_token: Option<String>,
+        client_options: Option<PyClientOptions>,
+        retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
+    ) -> PyObjectStoreResult<PyObject> {
+        // Note: we pass **back** through Python so that if cls is a subclass, we instantiate the
+        // subclass
+        let kwargs = PyDict::new(py);
+        kwargs.set_item("url", url)?;
+        kwargs.set_item("user", user)?;
+        kwargs.set_item("delegation_token", delegation_token)?;
+        kwargs.set_item("client_options", client_options)?;
+        kwargs.set_item("retry_config", retry_config)?;
+        kwargs.set_item("limit_config", limit_config)?;
+        kwargs.set_item("resolver", resolver)?;
+        kwargs.set_item("request_tags", request_tags)?;
+        Ok(cls.call((), Some(&kwargs))?.unbind())
+    }
+
+    fn __eq__(&self, other: &Bound<PyAny>) -> bool {
+        // Ensure we never error on __eq__ by returning false if the other object is not the same
+        // type
+        other
+            .downcast::<PyHdfsStore>()
+            .map(|other| self.config == other.get().config)
+            .unwrap_or(false)
+    }
+
+    fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
+        self.config.__getnewargs_ex__(py)
+    }
+
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        let config = if allow_secrets {
+            self.config.clone()
+        } else {
+            self.config.without_secrets()
+        };
+        encode_token(py, Self::NAME, config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
+    fn reset_connections(&self) {
+        self.pool.reset();
+    }
+
+    fn __repr__(&self) -> String {
+        format!("HdfsStore(\"{}\")", &self.config.url.as_ref())
+    }
+
+    #[getter]
+    fn url(&self) -> &PyUrl {
+        &self.config.url
+    }
+
+    #[getter]
+    fn user(&self) -> Option<&str> {
+        self.config.user.as_deref()
+    }
+
+    #[getter]
+    fn client_options(&self) -> Option<PyClientOptions> {
+        self.config.client_options.clone()
+    }
+
+    #[getter]
+    fn retry_config(&self) -> Option<PyRetryConfig> {
+        self.config.retry_config.clone()
+    }
+
+    #[getter]
+    fn limit_config(&self) -> Option<PyLimitConfig> {
+        self.config.limit_config.clone()
+    }
+
+    #[getter]
+    fn resolver(&self) -> Option<PyResolver> {
+        self.config.resolver.clone()
+    }
+
+    #[getter]
+    fn request_tags(&self) -> Option<PyRequestTags> {
+        self.config.request_tags.clone()
+    }
+}
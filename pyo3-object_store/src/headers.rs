@@ -0,0 +1,115 @@
+//! Extra headers of individual requests, such as an `Authorization` header for a URL that is
+//! authorized out of band.
+//!
+//! The headers are passed to the HTTP client of the store in the extensions of the request, as
+//! with `GetOptions::extensions`, and added to the request as it's sent. A store can also have
+//! extra headers that it sends with every request.
+
+use std::collections::HashMap;
+
+use http::header::{AUTHORIZATION, COOKIE, PROXY_AUTHORIZATION};
+use http::{HeaderMap, HeaderName, HeaderValue};
+use object_store::client::{HttpClient, HttpError, HttpRequest, HttpResponse, HttpService};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+/// Headers that carry credentials, which are left out of tokens unless secrets are allowed.
+const SECRET_HEADERS: [&str; 2] = ["x-api-key", "x-amz-security-token"];
+
+/// Headers to add to the requests of a single operation.
+///
+/// These are added after the request is signed, so stores that sign their requests, such as S3,
+/// reject headers that they require to be signed.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestHeaders(HeaderMap);
+
+impl RequestHeaders {
+    /// Add these headers to the extensions of a request.
+    pub fn into_extensions(self) -> http::Extensions {
+        let mut extensions = http::Extensions::new();
+        extensions.insert(self);
+        extensions
+    }
+
+    /// The headers without those that carry credentials, for `to_token`.
+    pub(crate) fn without_secrets(&self) -> Self {
+        let mut headers = self.0.clone();
+        for name in [AUTHORIZATION, PROXY_AUTHORIZATION, COOKIE] {
+            headers.remove(name);
+        }
+        for name in SECRET_HEADERS {
+            headers.remove(name);
+        }
+        Self(headers)
+    }
+}
+
+impl<'py> FromPyObject<'py> for RequestHeaders {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let headers = ob.extract::<HashMap<String, String>>()?;
+        let mut out = HeaderMap::with_capacity(headers.len());
+        for (name, value) in headers {
+            let name = HeaderName::try_from(name.as_str()).map_err(|err| {
+                PyValueError::new_err(format!("Invalid header name {}: {}", name, err))
+            })?;
+            let value = HeaderValue::try_from(value).map_err(|err| {
+                PyValueError::new_err(format!("Invalid value for header {}: {}", name, err))
+            })?;
+            out.insert(name, value);
+        }
+        Ok(Self(out))
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &RequestHeaders {
+    type Target = PyDict;
+    type Output = Bound<'py, PyDict>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        let dict = PyDict::new(py);
+        for (name, value) in self.0.iter() {
+            dict.set_item(name.as_str(), String::from_utf8_lossy(value.as_bytes()))?;
+        }
+        Ok(dict)
+    }
+}
+
+/// Wrap `client` so that the [`RequestHeaders`] in the extensions of a request are sent with it.
+///
+/// `extra_headers` are sent with every request, unless the request sets the same header itself.
+pub(crate) fn headers_client(
+    client: HttpClient,
+    extra_headers: Option<RequestHeaders>,
+) -> HttpClient {
+    HttpClient::new(HeadersService {
+        client,
+        extra_headers,
+    })
+}
+
+#[derive(Debug)]
+struct HeadersService {
+    client: HttpClient,
+    extra_headers: Option<RequestHeaders>,
+}
+
+#[async_trait::async_trait]
+impl HttpService for HeadersService {
+    async fn call(&self, mut req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        if let Some(RequestHeaders(headers)) = &self.extra_headers {
+            for (name, value) in headers.iter() {
+                if !req.headers().contains_key(name) {
+                    req.headers_mut().insert(name.clone(), value.clone());
+                }
+            }
+        }
+        if let Some(RequestHeaders(headers)) = req.extensions().get::<RequestHeaders>().cloned() {
+            for (name, value) in headers.iter() {
+                req.headers_mut().insert(name.clone(), value.clone());
+            }
+        }
+        self.client.execute(req).await
+    }
+}
@@ -1,22 +1,45 @@
 use std::sync::Arc;
 
-use object_store::http::{HttpBuilder, HttpStore};
+use object_store::client::HttpConnector;
+use object_store::http::HttpBuilder;
+use object_store::ClientOptions;
 use pyo3::prelude::*;
 use pyo3::types::{PyDict, PyTuple, PyType};
-use pyo3::{intern, IntoPyObjectExt};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
 
+use crate::connector::StoreConnector;
 use crate::error::PyObjectStoreResult;
+use crate::limit::{LimitedStore, PyLimitConfig};
+use crate::pool::ConnectionPool;
 use crate::retry::PyRetryConfig;
-use crate::{PyClientOptions, PyUrl};
+use crate::tags::TagStyle;
+use crate::token::{encode_token, store_from_token};
+use crate::webdav::WebDavStore;
+use crate::{PyClientOptions, PyRequestTags, PyResolver, PyUrl};
 
 #[derive(Debug, Clone, PartialEq)]
 struct HTTPConfig {
     url: PyUrl,
     client_options: Option<PyClientOptions>,
     retry_config: Option<PyRetryConfig>,
+    limit_config: Option<PyLimitConfig>,
+    resolver: Option<PyResolver>,
+    request_tags: Option<PyRequestTags>,
+    webdav: bool,
 }
 
 impl HTTPConfig {
+    /// The config without the password of the proxy URL, for `to_token`.
+    fn without_secrets(&self) -> Self {
+        Self {
+            client_options: self
+                .client_options
+                .as_ref()
+                .map(PyClientOptions::without_secrets),
+            ..self.clone()
+        }
+    }
+
     fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
         let args = PyTuple::new(py, vec![self.url.clone()])?.into_py_any(py)?;
         let kwargs = PyDict::new(py);
@@ -27,31 +50,45 @@ impl HTTPConfig {
         if let Some(retry_config) = &self.retry_config {
             kwargs.set_item(intern!(py, "retry_config"), retry_config.clone())?;
         }
+        if let Some(limit_config) = &self.limit_config {
+            kwargs.set_item(intern!(py, "limit_config"), limit_config.clone())?;
+        }
+        if let Some(resolver) = &self.resolver {
+            kwargs.set_item(intern!(py, "resolver"), resolver)?;
+        }
+        if let Some(request_tags) = &self.request_tags {
+            kwargs.set_item(intern!(py, "request_tags"), request_tags)?;
+        }
+        if self.webdav {
+            kwargs.set_item(intern!(py, "webdav"), self.webdav)?;
+        }
 
         PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
     }
 }
 
-/// A Python-facing wrapper around a [`HttpStore`].
+/// A Python-facing wrapper around a [`WebDavStore`].
 #[derive(Debug, Clone)]
 #[pyclass(name = "HTTPStore", frozen, subclass)]
 pub struct PyHttpStore {
     // Note: we don't need to wrap this in a MaybePrefixedStore because the HttpStore manages its
     // own prefix.
-    store: Arc<HttpStore>,
+    store: Arc<LimitedStore<WebDavStore>>,
     /// A config used for pickling. This must stay in sync with the underlying store's config.
     config: HTTPConfig,
+    /// The connections of the store, reset with `reset_connections`.
+    pool: ConnectionPool,
 }
 
-impl AsRef<Arc<HttpStore>> for PyHttpStore {
-    fn as_ref(&self) -> &Arc<HttpStore> {
+impl AsRef<Arc<LimitedStore<WebDavStore>>> for PyHttpStore {
+    fn as_ref(&self) -> &Arc<LimitedStore<WebDavStore>> {
         &self.store
     }
 }
 
 impl PyHttpStore {
-    /// Consume self and return the underlying [`HttpStore`].
-    pub fn into_inner(self) -> Arc<HttpStore> {
+    /// Consume self and return the underlying [`WebDavStore`].
+    pub fn into_inner(self) -> Arc<LimitedStore<WebDavStore>> {
         self.store
     }
 }
@@ -59,37 +96,74 @@ impl PyHttpStore {
 #[pymethods]
 impl PyHttpStore {
     #[new]
-    #[pyo3(signature = (url, *, client_options=None, retry_config=None))]
+    #[pyo3(signature = (url, *, client_options=None, retry_config=None, limit_config=None, resolver=None, request_tags=None, webdav=false))]
     fn new(
         url: PyUrl,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
+        webdav: bool,
     ) -> PyObjectStoreResult<Self> {
         let mut builder = HttpBuilder::new().with_url(url.clone());
         if let Some(client_options) = client_options.clone() {
             builder = builder.with_client_options(client_options.into())
         }
+        let pool = ConnectionPool::default();
+        let connector = || {
+            StoreConnector::new(
+                &pool,
+                client_options.as_ref(),
+                retry_config.as_ref(),
+                resolver.as_ref(),
+                request_tags.as_ref(),
+                TagStyle::UserAgent,
+            )
+        };
+        builder = builder.with_http_connector(connector());
         if let Some(retry_config) = retry_config.clone() {
             builder = builder.with_retry(retry_config.into())
         }
+        // WebDAV listing makes its own requests, with a client configured like the store's
+        let webdav_client = if webdav {
+            let options = client_options
+                .clone()
+                .map(ClientOptions::from)
+                .unwrap_or_default();
+            Some(connector().connect(&options)?)
+        } else {
+            None
+        };
+        let store = WebDavStore::new(builder.build()?, url.as_ref().clone(), webdav_client);
         Ok(Self {
-            store: Arc::new(builder.build()?),
+            pool,
+            store: Arc::new(LimitedStore::new(store, limit_config.as_ref())),
             config: HTTPConfig {
                 url,
                 client_options,
                 retry_config,
+                limit_config,
+                resolver,
+                request_tags,
+                webdav,
             },
         })
     }
 
     #[classmethod]
-    #[pyo3(signature = (url, *, client_options=None, retry_config=None))]
+    #[pyo3(signature = (url, *, client_options=None, retry_config=None, limit_config=None, resolver=None, request_tags=None, webdav=false))]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_url(
         cls: &Bound<PyType>,
         py: Python,
         url: PyUrl,
         client_options: Option<PyClientOptions>,
         retry_config: Option<PyRetryConfig>,
+        limit_config: Option<PyLimitConfig>,
+        resolver: Option<PyResolver>,
+        request_tags: Option<PyRequestTags>,
+        webdav: bool,
     ) -> PyObjectStoreResult<PyObject> {
         // Note: we pass **back** through Python so that if cls is a subclass, we instantiate the
         // subclass
@@ -97,6 +171,10 @@ impl PyHttpStore {
         kwargs.set_item("url", url)?;
         kwargs.set_item("client_options", client_options)?;
         kwargs.set_item("retry_config", retry_config)?;
+        kwargs.set_item("limit_config", limit_config)?;
+        kwargs.set_item("resolver", resolver)?;
+        kwargs.set_item("request_tags", request_tags)?;
+        kwargs.set_item("webdav", webdav)?;
         Ok(cls.call((), Some(&kwargs))?.unbind())
     }
 
@@ -113,6 +191,25 @@ impl PyHttpStore {
         self.config.__getnewargs_ex__(py)
     }
 
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        let config = if allow_secrets {
+            self.config.clone()
+        } else {
+            self.config.without_secrets()
+        };
+        encode_token(py, Self::NAME, config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
+    fn reset_connections(&self) {
+        self.pool.reset();
+    }
+
     fn __repr__(&self) -> String {
         format!("HTTPStore(\"{}\")", &self.config.url.as_ref())
     }
@@ -131,4 +228,24 @@ impl PyHttpStore {
     fn retry_config(&self) -> Option<PyRetryConfig> {
         self.config.retry_config.clone()
     }
+
+    #[getter]
+    fn limit_config(&self) -> Option<PyLimitConfig> {
+        self.config.limit_config.clone()
+    }
+
+    #[getter]
+    fn resolver(&self) -> Option<PyResolver> {
+        self.config.resolver.clone()
+    }
+
+    #[getter]
+    fn request_tags(&self) -> Option<PyRequestTags> {
+        self.config.request_tags.clone()
+    }
+
+    #[getter]
+    fn webdav(&self) -> bool {
+        self.config.webdav
+    }
 }
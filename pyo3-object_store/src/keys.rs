@@ -0,0 +1,80 @@
+//! Validation of the keys of option dicts, so that misspelled keys aren't silently ignored.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+static STRICT_KEYS: AtomicBool = AtomicBool::new(true);
+
+/// Set whether unknown keys in option dicts raise an error, which they do by default.
+pub fn set_strict_keys(strict: bool) {
+    STRICT_KEYS.store(strict, Ordering::Relaxed);
+}
+
+/// Whether unknown keys in option dicts raise an error.
+pub fn strict_keys() -> bool {
+    STRICT_KEYS.load(Ordering::Relaxed)
+}
+
+/// The edit distance between `a` and `b`.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a_char != *b_char);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// The expected key closest to `key`, if it's close enough to be a likely misspelling.
+fn nearest_key<'a>(key: &str, expected: &[&'a str]) -> Option<&'a str> {
+    let key = key.to_lowercase();
+    expected
+        .iter()
+        .map(|candidate| (levenshtein(&key, candidate), *candidate))
+        .filter(|(distance, candidate)| *distance <= (candidate.len() / 3).max(2))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Raise a `ValueError` if the dict `ob` has keys other than `expected`, naming the nearest
+/// expected key of each.
+///
+/// `name` describes the dict in the error, such as `"retry_config"`. Objects other than dicts
+/// are not checked, nor is any dict when strict keys are disabled with [`set_strict_keys`].
+pub fn check_keys(ob: &Bound<PyAny>, name: &str, expected: &[&str]) -> PyResult<()> {
+    if !strict_keys() {
+        return Ok(());
+    }
+    let Ok(dict) = ob.downcast::<PyDict>() else {
+        return Ok(());
+    };
+    let mut unknown = vec![];
+    for key in dict.keys() {
+        let key = key.str()?.to_string();
+        if !expected.contains(&key.as_str()) {
+            unknown.push(match nearest_key(&key, expected) {
+                Some(nearest) => format!("'{}' (did you mean '{}'?)", key, nearest),
+                None => format!("'{}'", key),
+            });
+        }
+    }
+    if unknown.is_empty() {
+        return Ok(());
+    }
+    Err(PyValueError::new_err(format!(
+        "Unknown {} in {}: {}. Expected keys are: {}.",
+        if unknown.len() == 1 { "key" } else { "keys" },
+        name,
+        unknown.join(", "),
+        expected.join(", ")
+    )))
+}
@@ -4,31 +4,82 @@
 mod api;
 mod aws;
 mod azure;
+mod breaker;
+mod cache;
+mod chaos;
 mod client;
 mod config;
+mod connector;
+mod consistent;
 mod credentials;
+mod deadline;
+mod dict;
+mod encrypted;
 pub(crate) mod error;
 mod gcp;
+mod hdfs;
+mod headers;
 mod http;
+mod keys;
+mod limit;
 mod local;
+mod local_write;
 mod memory;
+mod mirror;
+mod negative_cache;
 mod path;
+mod pool;
 mod prefix;
+mod range_cache;
+mod request_log;
+mod resolver;
 mod retry;
+#[cfg(feature = "sftp")]
+mod sftp;
 mod simple;
+mod staging;
 mod store;
+mod tags;
+mod throttle;
+mod token;
 mod url;
+mod webdav;
 
 pub use api::{register_exceptions_module, register_store_module};
 pub use aws::PyS3Store;
-pub use azure::PyAzureStore;
+pub use azure::{set_blob_tier, AccessTier, PyAzureStore};
+pub use cache::{CacheStore, PyCacheStore};
+pub use chaos::{ChaosStore, PyChaosStore};
 pub use client::{PyClientConfigKey, PyClientOptions};
-pub use error::{PyObjectStoreError, PyObjectStoreResult};
+pub use consistent::{ConsistentStore, PyConsistentStore};
+pub use deadline::{DeadlineExceeded, DeadlineStore, PyDeadlineStore};
+pub use dict::{DictStore, PyDictStore};
+pub use encrypted::{EncryptedStore, KeyProvider, PyEncryptedStore};
+pub use error::{PyObjectStoreError, PyObjectStoreResult, StreamTimeoutError};
 pub use gcp::PyGCSStore;
+pub use hdfs::{HdfsStore, PyHdfsStore};
+pub use headers::RequestHeaders;
 pub use http::PyHttpStore;
-pub use local::PyLocalStore;
+pub use keys::{check_keys, set_strict_keys, strict_keys};
+pub use limit::{LimitedStore, PyLimitConfig};
+pub use local::{LocalStore, PyLocalStore, SymlinkPolicy};
+pub use local_write::SizeHint;
 pub use memory::PyMemoryStore;
-pub use prefix::MaybePrefixedStore;
+pub use mirror::{MirrorStore, PyMirrorStore};
+pub use negative_cache::{NegativeCacheStore, PyNegativeCacheStore};
+pub use path::{extract_path_str, PyPath};
+pub use prefix::{MaybePrefixedStore, PyPrefixStore};
+pub use range_cache::{PyRangeCacheStore, RangeCacheStore};
+pub use resolver::PyResolver;
+pub use retry::{Backoff, PyRetryConfig};
+#[cfg(feature = "sftp")]
+pub use sftp::{PySFTPStore, SftpStore};
 pub use simple::from_url;
-pub use store::{AnyObjectStore, PyExternalObjectStore, PyObjectStore};
+pub use store::{
+    set_store_layer, AnyObjectStore, PyExternalObjectStore, PyObjectStore, StoreLayer,
+};
+pub use tags::PyRequestTags;
+pub use throttle::{PyThrottleConfig, PyThrottleStore};
+pub use token::resolve_store_token;
 pub use url::PyUrl;
+pub use webdav::WebDavStore;
@@ -0,0 +1,410 @@
+//! An object store wrapper limiting the bandwidth and the number of concurrent requests of the
+//! store.
+
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::multipart::{MultipartStore, PartId};
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, GetResultPayload, ListResult, MultipartId, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::intern;
+use pyo3::prelude::*;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+use crate::check_keys;
+
+/// Limits shared by all operations through a store.
+#[derive(Clone, Debug, Default, PartialEq, IntoPyObject, IntoPyObjectRef)]
+pub struct PyLimitConfig {
+    #[pyo3(item)]
+    max_bytes_per_second: Option<u64>,
+    #[pyo3(item)]
+    max_concurrent_requests: Option<usize>,
+}
+
+impl<'py> FromPyObject<'py> for PyLimitConfig {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        check_keys(
+            ob,
+            "limit_config",
+            &["max_bytes_per_second", "max_concurrent_requests"],
+        )?;
+        let mut limit_config = PyLimitConfig::default();
+        let py = ob.py();
+        if let Ok(max_bytes_per_second) = ob.get_item(intern!(py, "max_bytes_per_second")) {
+            limit_config.max_bytes_per_second = max_bytes_per_second.extract()?;
+        }
+        if let Ok(max_concurrent_requests) = ob.get_item(intern!(py, "max_concurrent_requests")) {
+            limit_config.max_concurrent_requests = max_concurrent_requests.extract()?;
+        }
+        if limit_config.max_bytes_per_second == Some(0) {
+            return Err(PyValueError::new_err(
+                "max_bytes_per_second must be greater than 0",
+            ));
+        }
+        if limit_config.max_concurrent_requests == Some(0) {
+            return Err(PyValueError::new_err(
+                "max_concurrent_requests must be greater than 0",
+            ));
+        }
+        Ok(limit_config)
+    }
+}
+
+/// A token bucket holding up to one second's worth of bytes.
+///
+/// A transfer larger than the bucket is let through once the bucket is drained, so that the
+/// average rate still matches the limit.
+#[derive(Debug)]
+struct Bandwidth {
+    bytes_per_second: f64,
+    /// The bytes available for transfer, and when that was last computed. This is negative while
+    /// earlier transfers are still being paid off.
+    state: Mutex<(f64, Instant)>,
+}
+
+impl Bandwidth {
+    fn new(bytes_per_second: u64) -> Self {
+        let bytes_per_second = bytes_per_second as f64;
+        Self {
+            bytes_per_second,
+            state: Mutex::new((bytes_per_second, Instant::now())),
+        }
+    }
+
+    async fn transfer(&self, n: u64) {
+        let wait = {
+            let mut state = self.state.lock().unwrap();
+            let (available, last) = &mut *state;
+            let now = Instant::now();
+            *available = (*available
+                + now.duration_since(*last).as_secs_f64() * self.bytes_per_second)
+                .min(self.bytes_per_second);
+            *last = now;
+            *available -= n as f64;
+            if *available < 0.0 {
+                Duration::from_secs_f64(-*available / self.bytes_per_second)
+            } else {
+                Duration::ZERO
+            }
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Limiter {
+    requests: Option<Arc<Semaphore>>,
+    bandwidth: Option<Bandwidth>,
+}
+
+impl Limiter {
+    fn new(config: &PyLimitConfig) -> Option<Arc<Self>> {
+        if config.max_bytes_per_second.is_none() && config.max_concurrent_requests.is_none() {
+            return None;
+        }
+        Some(Arc::new(Self {
+            requests: config
+                .max_concurrent_requests
+                .map(|n| Arc::new(Semaphore::new(n))),
+            bandwidth: config.max_bytes_per_second.map(Bandwidth::new),
+        }))
+    }
+
+    /// Wait until another request may be made. The request counts as in flight until the returned
+    /// permit is dropped.
+    async fn request(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.requests {
+            Some(requests) => Some(
+                requests
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("semaphore is never closed"),
+            ),
+            None => None,
+        }
+    }
+
+    /// Wait until `n` more bytes may be transferred.
+    async fn transfer(&self, n: u64) {
+        if let Some(bandwidth) = &self.bandwidth {
+            bandwidth.transfer(n).await;
+        }
+    }
+}
+
+/// Hold `permit` until `stream` is dropped, and throttle the bytes it yields.
+fn limit_bytes_stream(
+    stream: BoxStream<'static, Result<Bytes>>,
+    limiter: Arc<Limiter>,
+    permit: Option<OwnedSemaphorePermit>,
+) -> BoxStream<'static, Result<Bytes>> {
+    stream
+        .and_then(move |bytes| {
+            let limiter = limiter.clone();
+            async move {
+                limiter.transfer(bytes.len() as u64).await;
+                Ok(bytes)
+            }
+        })
+        .map(move |x| {
+            let _permit = &permit;
+            x
+        })
+        .boxed()
+}
+
+/// Store wrapper that limits the bandwidth and number of concurrent requests of a store.
+///
+/// Uploads are throttled before each request or part is sent, and downloads as the response body
+/// is read. A download counts as a request in flight until its response body is consumed or
+/// dropped, and a listing until its stream is exhausted or dropped.
+#[derive(Debug, Clone)]
+pub struct LimitedStore<T: ObjectStore> {
+    inner: T,
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl<T: ObjectStore> std::fmt::Display for LimitedStore<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.inner, f)
+    }
+}
+
+impl<T: ObjectStore> LimitedStore<T> {
+    /// Create a new instance of [`LimitedStore`]. Without a `limit_config`, all operations are
+    /// passed through unchanged.
+    pub fn new(store: T, limit_config: Option<&PyLimitConfig>) -> Self {
+        Self {
+            inner: store,
+            limiter: limit_config.and_then(Limiter::new),
+        }
+    }
+
+    /// Access the underlying T under the LimitedStore
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    async fn request(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.limiter {
+            Some(limiter) => limiter.request().await,
+            None => None,
+        }
+    }
+
+    /// Hold a request permit for the lifetime of `stream`.
+    fn limit_stream<'a, S: Send + 'a>(
+        &self,
+        stream: BoxStream<'a, Result<S>>,
+    ) -> BoxStream<'a, Result<S>> {
+        let Some(limiter) = self.limiter.clone() else {
+            return stream;
+        };
+        futures::stream::once(async move {
+            let permit = limiter.request().await;
+            stream.map(move |x| {
+                let _permit = &permit;
+                x
+            })
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: ObjectStore> ObjectStore for LimitedStore<T> {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let Some(limiter) = &self.limiter else {
+            return self.inner.put_opts(location, payload, opts).await;
+        };
+        let _permit = limiter.request().await;
+        limiter.transfer(payload.content_length() as u64).await;
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let Some(limiter) = &self.limiter else {
+            return self.inner.put_multipart_opts(location, opts).await;
+        };
+        let upload = {
+            let _permit = limiter.request().await;
+            self.inner.put_multipart_opts(location, opts).await?
+        };
+        Ok(Box::new(LimitedUpload {
+            inner: upload,
+            limiter: limiter.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let Some(limiter) = &self.limiter else {
+            return self.inner.get_opts(location, options).await;
+        };
+        let permit = limiter.request().await;
+        let result = self.inner.get_opts(location, options).await?;
+        let payload = match result.payload {
+            GetResultPayload::Stream(stream) => {
+                GetResultPayload::Stream(limit_bytes_stream(stream, limiter.clone(), permit))
+            }
+            payload => payload,
+        };
+        Ok(GetResult { payload, ..result })
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<u64>) -> Result<Bytes> {
+        let Some(limiter) = &self.limiter else {
+            return self.inner.get_range(location, range).await;
+        };
+        let bytes = {
+            let _permit = limiter.request().await;
+            self.inner.get_range(location, range).await?
+        };
+        limiter.transfer(bytes.len() as u64).await;
+        Ok(bytes)
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        let _permit = self.request().await;
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let _permit = self.request().await;
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.limit_stream(self.inner.delete_stream(locations))
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.limit_stream(self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.limit_stream(self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let _permit = self.request().await;
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.request().await;
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.request().await;
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.request().await;
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let _permit = self.request().await;
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+#[async_trait::async_trait]
+impl<T: ObjectStore + MultipartStore> MultipartStore for LimitedStore<T> {
+    async fn create_multipart(&self, path: &Path) -> Result<MultipartId> {
+        let _permit = self.request().await;
+        self.inner.create_multipart(path).await
+    }
+
+    async fn put_part(
+        &self,
+        path: &Path,
+        id: &MultipartId,
+        part_idx: usize,
+        data: PutPayload,
+    ) -> Result<PartId> {
+        let Some(limiter) = &self.limiter else {
+            return self.inner.put_part(path, id, part_idx, data).await;
+        };
+        let _permit = limiter.request().await;
+        limiter.transfer(data.content_length() as u64).await;
+        self.inner.put_part(path, id, part_idx, data).await
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &Path,
+        id: &MultipartId,
+        parts: Vec<PartId>,
+    ) -> Result<PutResult> {
+        let _permit = self.request().await;
+        self.inner.complete_multipart(path, id, parts).await
+    }
+
+    async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> Result<()> {
+        let _permit = self.request().await;
+        self.inner.abort_multipart(path, id).await
+    }
+}
+
+/// A multipart upload whose parts count towards the limits of the store.
+#[derive(Debug)]
+struct LimitedUpload {
+    inner: Box<dyn MultipartUpload>,
+    limiter: Arc<Limiter>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for LimitedUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let n = data.content_length() as u64;
+        let part = self.inner.put_part(data);
+        let limiter = self.limiter.clone();
+        Box::pin(async move {
+            let _permit = limiter.request().await;
+            limiter.transfer(n).await;
+            part.await
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let _permit = self.limiter.request().await;
+        self.inner.complete().await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let _permit = self.limiter.request().await;
+        self.inner.abort().await
+    }
+}
@@ -1,21 +1,460 @@
 use std::fs::create_dir_all;
+use std::io::ErrorKind;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
 use object_store::local::LocalFileSystem;
-use object_store::ObjectStoreScheme;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, ObjectStoreScheme,
+    PutMode, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
 use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyDict, PyTuple, PyType};
-use pyo3::{intern, IntoPyObjectExt};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
 
 use crate::error::PyObjectStoreResult;
+use crate::local_write::{SizeHint, StagedFile, WriteOptions};
+use crate::staging::staging_path;
+use crate::token::{encode_token, store_from_token};
 use crate::PyUrl;
 
+const STORE: &str = "LocalFileSystem";
+
+/// The OS error of a rename or hard link between different filesystems.
+#[cfg(unix)]
+const CROSS_DEVICE_ERROR: i32 = 18; // EXDEV
+#[cfg(windows)]
+const CROSS_DEVICE_ERROR: i32 = 17; // ERROR_NOT_SAME_DEVICE
+
+/// What listing and reading do with symbolic links.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Follow symbolic links, as if they were the files and directories they point to.
+    #[default]
+    Follow,
+    /// Leave symbolic links out of listings, and fail to read them as if they didn't exist.
+    Skip,
+    /// Fail listing and reading on symbolic links.
+    Error,
+}
+
+impl<'py> FromPyObject<'py> for SymlinkPolicy {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let s = ob.extract::<PyBackedStr>()?;
+        match s.as_ref() {
+            "follow" => Ok(Self::Follow),
+            "skip" => Ok(Self::Skip),
+            "error" => Ok(Self::Error),
+            _ => Err(PyValueError::new_err(format!(
+                "Expected symlinks to be one of 'follow', 'skip' or 'error', got '{}'",
+                s
+            ))),
+        }
+    }
+}
+
+impl<'py> IntoPyObject<'py> for SymlinkPolicy {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    // `Self::Error` would be ambiguous with the `Error` variant
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, PyErr> {
+        let s = match self {
+            Self::Follow => intern!(py, "follow"),
+            Self::Skip => intern!(py, "skip"),
+            Self::Error => intern!(py, "error"),
+        };
+        Ok(s.clone().into_any())
+    }
+}
+
+/// Whether `err` is from renaming or linking a file to another filesystem.
+fn is_cross_device(err: &object_store::Error) -> bool {
+    let mut source = std::error::Error::source(err);
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            return err.raw_os_error() == Some(CROSS_DEVICE_ERROR);
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Copy the contents of the file at `from` to `to`, which are on different filesystems and so
+/// can't be renamed or hard linked to each other.
+///
+/// The file is copied to a staging file next to `to`, which is then moved into place, or only
+/// linked to `to` if `to` doesn't exist unless `overwrite` is set.
+fn copy_contents(from: &std::path::Path, to: &std::path::Path, overwrite: bool) -> Result<()> {
+    let io_error = |source: std::io::Error| match source.kind() {
+        ErrorKind::NotFound => object_store::Error::NotFound {
+            path: from.display().to_string(),
+            source: source.into(),
+        },
+        ErrorKind::AlreadyExists => object_store::Error::AlreadyExists {
+            path: to.display().to_string(),
+            source: source.into(),
+        },
+        _ => object_store::Error::Generic {
+            store: STORE,
+            source: format!(
+                "Unable to copy file from {} to {}: {}",
+                from.display(),
+                to.display(),
+                source
+            )
+            .into(),
+        },
+    };
+    if let Some(parent) = to.parent() {
+        create_dir_all(parent).map_err(io_error)?;
+    }
+    let staged = PathBuf::from(staging_path(&to.to_string_lossy()));
+    std::fs::copy(from, &staged).map_err(io_error)?;
+    let result = if overwrite {
+        std::fs::rename(&staged, to)
+    } else {
+        std::fs::hard_link(&staged, to)
+    };
+    if !overwrite || result.is_err() {
+        let _ = std::fs::remove_file(&staged);
+    }
+    result.map_err(io_error)
+}
+
+/// Run the blocking `f` on a thread for blocking work.
+async fn spawn_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|err| object_store::Error::JoinError { source: err })?
+}
+
+/// A multipart upload to a [`StagedFile`], whose parts are written at their offsets as they
+/// arrive.
+#[derive(Debug)]
+struct LocalUpload {
+    /// The staging file, until the upload is completed or aborted
+    staged: Option<StagedFile>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for LocalUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let Some(staged) = &mut self.staged else {
+            return Box::pin(futures::future::ready(Err(object_store::Error::Generic {
+                store: STORE,
+                source: "Upload was already completed or aborted".into(),
+            })));
+        };
+        let file = staged.file();
+        let chunks = data
+            .into_iter()
+            .flat_map(|bytes| staged.push(bytes))
+            .collect::<Vec<_>>();
+        Box::pin(spawn_blocking(move || {
+            for chunk in chunks {
+                chunk
+                    .write(&file)
+                    .map_err(|err| object_store::Error::Generic {
+                        store: STORE,
+                        source: err.into(),
+                    })?;
+            }
+            Ok(())
+        }))
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let staged = self
+            .staged
+            .take()
+            .ok_or_else(|| object_store::Error::Generic {
+                store: STORE,
+                source: "Upload was already completed or aborted".into(),
+            })?;
+        let e_tag = spawn_blocking(move || staged.finish(PutMode::Overwrite)).await?;
+        Ok(PutResult {
+            e_tag: Some(e_tag),
+            version: None,
+        })
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        if let Some(staged) = self.staged.take() {
+            spawn_blocking(move || {
+                staged.abort();
+                Ok(())
+            })
+            .await?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`LocalFileSystem`] with a policy for symbolic links, that renames and copies files between
+/// the filesystems mounted under its root.
+///
+/// Files may also be written with direct I/O, and with their space allocated up front.
+#[derive(Debug)]
+pub struct LocalStore {
+    inner: Arc<LocalFileSystem>,
+    symlinks: SymlinkPolicy,
+    write_options: WriteOptions,
+}
+
+impl std::fmt::Display for LocalStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl LocalStore {
+    /// Wrap `inner`, listing and reading symbolic links as set by `symlinks`.
+    pub fn new(inner: LocalFileSystem, symlinks: SymlinkPolicy) -> Self {
+        Self {
+            inner: Arc::new(inner),
+            symlinks,
+            write_options: WriteOptions::default(),
+        }
+    }
+
+    /// Write files bypassing the page cache, with `O_DIRECT` on Linux and `F_NOCACHE` on macOS.
+    ///
+    /// Filesystems without direct I/O, such as tmpfs, are written through the page cache.
+    pub fn with_direct_io(mut self, direct_io: bool) -> Self {
+        self.write_options.direct_io = direct_io;
+        self
+    }
+
+    /// Allocate the space of files up front on Linux, when their size is known.
+    ///
+    /// The size of multipart uploads is known from a [`SizeHint`] in their options.
+    pub fn with_preallocate(mut self, preallocate: bool) -> Self {
+        self.write_options.preallocate = preallocate;
+        self
+    }
+
+    /// Whether `location`, or any directory of its path, is a symbolic link.
+    fn is_symlink(inner: &LocalFileSystem, location: &Path) -> Result<bool> {
+        let mut path = Path::default();
+        for part in location.parts() {
+            path = path.child(part);
+            match std::fs::symlink_metadata(inner.path_to_filesystem(&path)?) {
+                Ok(metadata) if metadata.file_type().is_symlink() => return Ok(true),
+                Ok(_) => {}
+                // Left to the wrapped store to report
+                Err(_) => return Ok(false),
+            }
+        }
+        Ok(false)
+    }
+
+    /// Whether `location` is listed and read under `policy`, failing on symbolic links with the
+    /// `Error` policy.
+    fn check_symlink(
+        inner: &LocalFileSystem,
+        policy: SymlinkPolicy,
+        location: &Path,
+    ) -> Result<bool> {
+        if policy == SymlinkPolicy::Follow || !Self::is_symlink(inner, location)? {
+            return Ok(true);
+        }
+        match policy {
+            SymlinkPolicy::Error => Err(object_store::Error::Generic {
+                store: STORE,
+                source: format!("{} is a symbolic link, or is under one", location).into(),
+            }),
+            _ => Ok(false),
+        }
+    }
+
+    fn filter_symlinks(
+        &self,
+        stream: BoxStream<'static, Result<ObjectMeta>>,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        if self.symlinks == SymlinkPolicy::Follow {
+            return stream;
+        }
+        let inner = self.inner.clone();
+        let policy = self.symlinks;
+        stream
+            .try_filter_map(move |meta| {
+                let result = Self::check_symlink(&inner, policy, &meta.location)
+                    .map(|included| included.then_some(meta));
+                futures::future::ready(result)
+            })
+            .boxed()
+    }
+
+    /// Copy `from` to `to`, copying the contents of the file when they are on different
+    /// filesystems.
+    async fn copy_opts(&self, from: &Path, to: &Path, overwrite: bool) -> Result<()> {
+        let result = if overwrite {
+            self.inner.copy(from, to).await
+        } else {
+            self.inner.copy_if_not_exists(from, to).await
+        };
+        match result {
+            Err(err) if is_cross_device(&err) => {
+                let from = self.inner.path_to_filesystem(from)?;
+                let to = self.inner.path_to_filesystem(to)?;
+                spawn_blocking(move || copy_contents(&from, &to, overwrite)).await
+            }
+            result => result,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for LocalStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        if self.write_options.is_default() {
+            return self.inner.put_opts(location, payload, opts).await;
+        }
+        if matches!(opts.mode, PutMode::Update(_)) || !opts.attributes.is_empty() {
+            return Err(object_store::Error::NotImplemented);
+        }
+
+        let path = self.inner.path_to_filesystem(location)?;
+        let write_options = self.write_options;
+        let e_tag = spawn_blocking(move || {
+            let size = payload.content_length() as u64;
+            let mut staged = StagedFile::create(&path, write_options, Some(size))?;
+            let file = staged.file();
+            for bytes in payload {
+                for chunk in staged.push(bytes) {
+                    if let Err(err) = chunk.write(&file) {
+                        staged.abort();
+                        return Err(object_store::Error::Generic {
+                            store: STORE,
+                            source: err.into(),
+                        });
+                    }
+                }
+            }
+            staged.finish(opts.mode)
+        })
+        .await?;
+        Ok(PutResult {
+            e_tag: Some(e_tag),
+            version: None,
+        })
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        if self.write_options.is_default() {
+            return self.inner.put_multipart_opts(location, opts).await;
+        }
+        if !opts.attributes.is_empty() {
+            return Err(object_store::Error::NotImplemented);
+        }
+
+        let path = self.inner.path_to_filesystem(location)?;
+        let write_options = self.write_options;
+        let size = opts.extensions.get::<SizeHint>().map(|hint| hint.0);
+        let staged = spawn_blocking(move || StagedFile::create(&path, write_options, size)).await?;
+        Ok(Box::new(LocalUpload {
+            staged: Some(staged),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        if !Self::check_symlink(&self.inner, self.symlinks, location)? {
+            return Err(object_store::Error::NotFound {
+                path: location.to_string(),
+                source: "Symbolic links are skipped".into(),
+            });
+        }
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.filter_symlinks(self.inner.list(prefix))
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.filter_symlinks(self.inner.list_with_offset(prefix, offset))
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut result = self.inner.list_with_delimiter(prefix).await?;
+        if self.symlinks == SymlinkPolicy::Follow {
+            return Ok(result);
+        }
+        let mut objects = Vec::with_capacity(result.objects.len());
+        for meta in result.objects {
+            if Self::check_symlink(&self.inner, self.symlinks, &meta.location)? {
+                objects.push(meta);
+            }
+        }
+        let mut common_prefixes = Vec::with_capacity(result.common_prefixes.len());
+        for prefix in result.common_prefixes {
+            if Self::check_symlink(&self.inner, self.symlinks, &prefix)? {
+                common_prefixes.push(prefix);
+            }
+        }
+        result.objects = objects;
+        result.common_prefixes = common_prefixes;
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, true).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, false).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        match self.inner.rename(from, to).await {
+            Err(err) if is_cross_device(&err) => {
+                self.copy_opts(from, to, true).await?;
+                self.inner.delete(from).await
+            }
+            result => result,
+        }
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, false).await?;
+        self.inner.delete(from).await
+    }
+}
+
 #[derive(Clone, Debug, PartialEq)]
 struct LocalConfig {
     prefix: Option<std::path::PathBuf>,
     automatic_cleanup: bool,
     mkdir: bool,
+    symlinks: SymlinkPolicy,
+    direct_io: bool,
+    preallocate: bool,
 }
 
 impl LocalConfig {
@@ -24,27 +463,36 @@ impl LocalConfig {
         let kwargs = PyDict::new(py);
         kwargs.set_item(intern!(py, "automatic_cleanup"), self.automatic_cleanup)?;
         kwargs.set_item(intern!(py, "mkdir"), self.mkdir)?;
+        if self.symlinks != SymlinkPolicy::Follow {
+            kwargs.set_item(intern!(py, "symlinks"), self.symlinks)?;
+        }
+        if self.direct_io {
+            kwargs.set_item(intern!(py, "direct_io"), true)?;
+        }
+        if self.preallocate {
+            kwargs.set_item(intern!(py, "preallocate"), true)?;
+        }
         PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
     }
 }
 
-/// A Python-facing wrapper around a [`LocalFileSystem`].
+/// A Python-facing wrapper around a [`LocalStore`].
 #[derive(Debug, Clone)]
 #[pyclass(name = "LocalStore", frozen, subclass)]
 pub struct PyLocalStore {
-    store: Arc<LocalFileSystem>,
+    store: Arc<LocalStore>,
     config: LocalConfig,
 }
 
-impl AsRef<Arc<LocalFileSystem>> for PyLocalStore {
-    fn as_ref(&self) -> &Arc<LocalFileSystem> {
+impl AsRef<Arc<LocalStore>> for PyLocalStore {
+    fn as_ref(&self) -> &Arc<LocalStore> {
         &self.store
     }
 }
 
 impl PyLocalStore {
-    /// Consume self and return the underlying [`LocalFileSystem`].
-    pub fn into_inner(self) -> Arc<LocalFileSystem> {
+    /// Consume self and return the underlying [`LocalStore`].
+    pub fn into_inner(self) -> Arc<LocalStore> {
         self.store
     }
 }
@@ -52,11 +500,14 @@ impl PyLocalStore {
 #[pymethods]
 impl PyLocalStore {
     #[new]
-    #[pyo3(signature = (prefix=None, *, automatic_cleanup=false, mkdir=false))]
+    #[pyo3(signature = (prefix=None, *, automatic_cleanup=false, mkdir=false, symlinks=SymlinkPolicy::Follow, direct_io=false, preallocate=false))]
     fn new(
         prefix: Option<std::path::PathBuf>,
         automatic_cleanup: bool,
         mkdir: bool,
+        symlinks: SymlinkPolicy,
+        direct_io: bool,
+        preallocate: bool,
     ) -> PyObjectStoreResult<Self> {
         let fs = if let Some(prefix) = &prefix {
             if mkdir {
@@ -67,23 +518,33 @@ impl PyLocalStore {
             LocalFileSystem::new()
         };
         let fs = fs.with_automatic_cleanup(automatic_cleanup);
+        let store = LocalStore::new(fs, symlinks)
+            .with_direct_io(direct_io)
+            .with_preallocate(preallocate);
         Ok(Self {
-            store: Arc::new(fs),
+            store: Arc::new(store),
             config: LocalConfig {
                 prefix,
                 automatic_cleanup,
                 mkdir,
+                symlinks,
+                direct_io,
+                preallocate,
             },
         })
     }
 
     #[classmethod]
-    #[pyo3(signature = (url, *, automatic_cleanup=false, mkdir=false))]
+    #[pyo3(signature = (url, *, automatic_cleanup=false, mkdir=false, symlinks=SymlinkPolicy::Follow, direct_io=false, preallocate=false))]
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn from_url(
         cls: &Bound<PyType>,
         url: PyUrl,
         automatic_cleanup: bool,
         mkdir: bool,
+        symlinks: SymlinkPolicy,
+        direct_io: bool,
+        preallocate: bool,
     ) -> PyObjectStoreResult<PyObject> {
         let url = url.into_inner();
         let (scheme, path) = ObjectStoreScheme::parse(&url).map_err(object_store::Error::from)?;
@@ -104,6 +565,9 @@ impl PyLocalStore {
         kwargs.set_item("prefix", full_path)?;
         kwargs.set_item("automatic_cleanup", automatic_cleanup)?;
         kwargs.set_item("mkdir", mkdir)?;
+        kwargs.set_item("symlinks", symlinks)?;
+        kwargs.set_item("direct_io", direct_io)?;
+        kwargs.set_item("preallocate", preallocate)?;
         Ok(cls.call((), Some(&kwargs))?.unbind())
     }
 
@@ -120,6 +584,18 @@ impl PyLocalStore {
         self.config.__getnewargs_ex__(py)
     }
 
+    /// This store has no secrets, so `allow_secrets` has no effect.
+    #[allow(unused_variables)]
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        encode_token(py, Self::NAME, self.config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
     fn __repr__(&self) -> String {
         if let Some(prefix) = &self.config.prefix {
             format!("LocalStore(\"{}\")", prefix.display())
@@ -142,4 +618,19 @@ impl PyLocalStore {
             Ok(py.None())
         }
     }
+
+    #[getter]
+    fn symlinks(&self) -> SymlinkPolicy {
+        self.config.symlinks
+    }
+
+    #[getter]
+    fn direct_io(&self) -> bool {
+        self.config.direct_io
+    }
+
+    #[getter]
+    fn preallocate(&self) -> bool {
+        self.config.preallocate
+    }
 }
@@ -0,0 +1,377 @@
+//! Writing the files of a [`LocalStore`][crate::LocalStore] with direct I/O and preallocation.
+//!
+//! Objects are written to a staging file next to their path, which is then moved into place, as
+//! with `LocalFileSystem`. Writes are positional, so that the parts of a multipart upload may be
+//! written concurrently.
+
+use std::fs::{File, Metadata, OpenOptions};
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use bytes::Bytes;
+use object_store::{PutMode, Result};
+
+use crate::staging::staging_path;
+
+const STORE: &str = "LocalFileSystem";
+
+/// Writes with direct I/O are made from buffers, at offsets and of lengths, aligned to this,
+/// which is a multiple of the logical block size of nearly all devices.
+const ALIGNMENT: usize = 4096;
+
+/// The largest buffer that writes with direct I/O are made from.
+const DIRECT_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The expected size of an object written with a multipart upload.
+///
+/// When set in the `extensions` of [`PutMultipartOptions`][object_store::PutMultipartOptions], a
+/// [`LocalStore`][crate::LocalStore] that preallocates files allocates this much space for the
+/// upload up front.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeHint(pub u64);
+
+/// How a [`LocalStore`][crate::LocalStore] writes files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct WriteOptions {
+    /// Bypass the page cache, with `O_DIRECT` on Linux and `F_NOCACHE` on macOS.
+    pub(crate) direct_io: bool,
+    /// Allocate the space of files up front when their size is known, on Linux.
+    pub(crate) preallocate: bool,
+}
+
+impl WriteOptions {
+    /// Whether files are written as `LocalFileSystem` writes them.
+    pub(crate) fn is_default(&self) -> bool {
+        !self.direct_io && !self.preallocate
+    }
+}
+
+/// A buffer whose contents start at an address aligned to [`ALIGNMENT`].
+struct AlignedBuffer {
+    data: Vec<u8>,
+    start: usize,
+    len: usize,
+}
+
+impl std::fmt::Debug for AlignedBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlignedBuffer")
+            .field("capacity", &self.capacity())
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+impl AlignedBuffer {
+    /// A buffer of `capacity` bytes, which is a multiple of [`ALIGNMENT`].
+    fn new(capacity: usize) -> Self {
+        // The heap allocation of the vector doesn't move with it, so the aligned start is stable
+        let data = vec![0; capacity + ALIGNMENT];
+        let start = data.as_ptr().align_offset(ALIGNMENT);
+        Self {
+            data,
+            start,
+            len: 0,
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.data.len() - ALIGNMENT
+    }
+
+    fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Copy as much of `bytes` as fits, returning the number of bytes copied.
+    fn extend(&mut self, bytes: &[u8]) -> usize {
+        let n = bytes.len().min(self.capacity() - self.len);
+        let end = self.start + self.len;
+        self.data[end..end + n].copy_from_slice(&bytes[..n]);
+        self.len += n;
+        n
+    }
+
+    /// Pad the contents with zeros up to a multiple of [`ALIGNMENT`].
+    fn pad(&mut self) {
+        let padded = self.len.next_multiple_of(ALIGNMENT);
+        let end = self.start + self.len;
+        self.data[end..self.start + padded].fill(0);
+        self.len = padded;
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        &self.data[self.start..self.start + self.len]
+    }
+}
+
+/// Data to write to a staging file at an offset.
+#[derive(Debug)]
+pub(crate) struct Chunk {
+    offset: u64,
+    data: ChunkData,
+}
+
+#[derive(Debug)]
+enum ChunkData {
+    Bytes(Bytes),
+    Aligned(AlignedBuffer),
+}
+
+impl Chunk {
+    /// Write the chunk to `file`.
+    pub(crate) fn write(&self, file: &File) -> io::Result<()> {
+        let data = match &self.data {
+            ChunkData::Bytes(bytes) => bytes.as_ref(),
+            ChunkData::Aligned(buffer) => buffer.as_slice(),
+        };
+        write_all_at(file, data, self.offset)
+    }
+}
+
+#[cfg(unix)]
+fn write_all_at(file: &File, data: &[u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, data, offset)
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &File, mut data: &[u8], mut offset: u64) -> io::Result<()> {
+    while !data.is_empty() {
+        match std::os::windows::fs::FileExt::seek_write(file, data, offset)? {
+            0 => return Err(ErrorKind::WriteZero.into()),
+            n => {
+                data = &data[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Open `path` for writing, bypassing the page cache if `direct_io` is set.
+///
+/// Filesystems without direct I/O, such as tmpfs, are written through the page cache instead.
+fn open(path: &Path, direct_io: bool) -> io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    if direct_io {
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let mut direct = options.clone();
+        match direct.custom_flags(libc::O_DIRECT).open(path) {
+            Err(err) if err.raw_os_error() == Some(libc::EINVAL) => {}
+            result => return result,
+        }
+    }
+    let file = options.open(path)?;
+    #[cfg(target_os = "macos")]
+    if direct_io {
+        use std::os::unix::io::AsRawFd;
+
+        // SAFETY: the descriptor is open for the lifetime of `file`
+        if unsafe { libc::fcntl(file.as_raw_fd(), libc::F_NOCACHE, 1) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_os = "macos")))]
+    let _ = direct_io;
+    Ok(file)
+}
+
+/// Allocate the first `size` bytes of `file`, where the filesystem supports it.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn preallocate(file: &File, size: u64) -> io::Result<()> {
+    use std::os::unix::io::AsRawFd;
+
+    let Ok(size) = libc::off_t::try_from(size) else {
+        return Ok(());
+    };
+    // SAFETY: the descriptor is open for the lifetime of `file`
+    match unsafe { libc::posix_fallocate(file.as_raw_fd(), 0, size) } {
+        0 | libc::EOPNOTSUPP => Ok(()),
+        errno => Err(io::Error::from_raw_os_error(errno)),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn preallocate(_file: &File, _size: u64) -> io::Result<()> {
+    Ok(())
+}
+
+/// The ETag of a file, computed as `LocalFileSystem` computes it so that it matches the ETag of
+/// reads.
+fn get_etag(metadata: &Metadata) -> String {
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+    let size = metadata.len();
+    let mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|mtime| mtime.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .unwrap_or_default()
+        .as_micros();
+    format!("{inode:x}-{mtime:x}-{size:x}")
+}
+
+fn io_error(path: &Path, source: io::Error) -> object_store::Error {
+    match source.kind() {
+        ErrorKind::AlreadyExists => object_store::Error::AlreadyExists {
+            path: path.display().to_string(),
+            source: source.into(),
+        },
+        _ => object_store::Error::Generic {
+            store: STORE,
+            source: format!("Unable to write {}: {}", path.display(), source).into(),
+        },
+    }
+}
+
+/// A staging file for the object at a path, written in order.
+#[derive(Debug)]
+pub(crate) struct StagedFile {
+    file: Arc<File>,
+    /// The path of the staging file
+    staging: PathBuf,
+    /// The path of the object
+    path: PathBuf,
+    /// The buffer that the next aligned write is made from, with direct I/O
+    buffer: Option<AlignedBuffer>,
+    /// The offset of the next write
+    offset: u64,
+    /// The number of bytes written so far
+    len: u64,
+}
+
+impl StagedFile {
+    /// Create a staging file for the object at `path`, allocating `size` bytes if known and
+    /// preallocation is enabled.
+    pub(crate) fn create(path: &Path, options: WriteOptions, size: Option<u64>) -> Result<Self> {
+        let staging = PathBuf::from(staging_path(&path.to_string_lossy()));
+        let file = match open(&staging, options.direct_io) {
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|err| io_error(path, err))?;
+                }
+                open(&staging, options.direct_io)
+            }
+            result => result,
+        }
+        .map_err(|err| io_error(path, err))?;
+        let staged = Self {
+            file: Arc::new(file),
+            staging,
+            path: path.to_path_buf(),
+            buffer: None,
+            offset: 0,
+            len: 0,
+        };
+        if let (true, Some(size)) = (options.preallocate, size) {
+            if let Err(err) = preallocate(&staged.file, size) {
+                staged.abort();
+                return Err(io_error(path, err));
+            }
+        }
+        let buffer_size = size.map_or(DIRECT_BUFFER_SIZE, |size| {
+            usize::try_from(size)
+                .unwrap_or(usize::MAX)
+                .clamp(ALIGNMENT, DIRECT_BUFFER_SIZE)
+                .next_multiple_of(ALIGNMENT)
+        });
+        Ok(Self {
+            buffer: options.direct_io.then(|| AlignedBuffer::new(buffer_size)),
+            ..staged
+        })
+    }
+
+    /// The file written to, which chunks are written to from any thread.
+    pub(crate) fn file(&self) -> Arc<File> {
+        self.file.clone()
+    }
+
+    /// Append `data` to the object, returning the chunks to write now.
+    ///
+    /// With direct I/O, data is copied to aligned buffers, which are only written once full.
+    pub(crate) fn push(&mut self, data: Bytes) -> Vec<Chunk> {
+        self.len += data.len() as u64;
+        let Some(buffer) = &mut self.buffer else {
+            let chunk = Chunk {
+                offset: self.offset,
+                data: ChunkData::Bytes(data),
+            };
+            self.offset = self.len;
+            return vec![chunk];
+        };
+        let mut chunks = vec![];
+        let mut data = data.as_ref();
+        while !data.is_empty() {
+            let n = buffer.extend(data);
+            data = &data[n..];
+            if buffer.is_full() {
+                let capacity = buffer.capacity();
+                let full = std::mem::replace(buffer, AlignedBuffer::new(capacity));
+                chunks.push(Chunk {
+                    offset: self.offset,
+                    data: ChunkData::Aligned(full),
+                });
+                self.offset += capacity as u64;
+            }
+        }
+        chunks
+    }
+
+    /// Write what is left of the object, and move the staging file to the object's path,
+    /// returning the ETag of the object.
+    ///
+    /// This blocks, and is called once the chunks returned by [`Self::push`] are written.
+    pub(crate) fn finish(mut self, mode: PutMode) -> Result<String> {
+        let result = self.write_tail().and_then(|_| {
+            self.file
+                .metadata()
+                .map(|metadata| get_etag(&metadata))
+                .map_err(|err| io_error(&self.path, err))
+        });
+        let result = result.and_then(|e_tag| {
+            let Self { staging, path, .. } = &self;
+            let moved = match mode {
+                PutMode::Create => std::fs::hard_link(staging, path),
+                _ => std::fs::rename(staging, path),
+            };
+            moved.map(|_| e_tag).map_err(|err| io_error(path, err))
+        });
+        if result.is_err() || matches!(mode, PutMode::Create) {
+            // Attempt to cleanup
+            let _ = std::fs::remove_file(&self.staging);
+        }
+        result
+    }
+
+    /// Write the partly filled buffer of direct I/O, and trim the file to the size of the object,
+    /// from past the padding of the buffer or unused preallocated space.
+    fn write_tail(&mut self) -> Result<()> {
+        if let Some(buffer) = &mut self.buffer {
+            if buffer.len > 0 {
+                buffer.pad();
+                let chunk = Chunk {
+                    offset: self.offset,
+                    data: ChunkData::Aligned(std::mem::replace(buffer, AlignedBuffer::new(0))),
+                };
+                chunk
+                    .write(&self.file)
+                    .map_err(|err| io_error(&self.path, err))?;
+            }
+        }
+        self.file
+            .set_len(self.len)
+            .map_err(|err| io_error(&self.path, err))
+    }
+
+    /// Remove the staging file.
+    pub(crate) fn abort(&self) {
+        let _ = std::fs::remove_file(&self.staging);
+    }
+}
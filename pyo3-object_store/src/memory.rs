@@ -1,9 +1,17 @@
 use std::sync::Arc;
 
+use bytes::Bytes;
+use futures::executor::block_on;
+use futures::TryStreamExt;
 use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::{ObjectStore, PutPayload};
+use pyo3::buffer::PyBuffer;
 use pyo3::intern;
 use pyo3::prelude::*;
-use pyo3::types::PyString;
+use pyo3::types::{PyBytes, PyDict, PyString, PyTuple, PyType};
+
+use crate::error::PyObjectStoreResult;
 
 /// A Python-facing wrapper around an [`InMemory`].
 #[derive(Debug, Clone)]
@@ -31,6 +39,25 @@ impl<'py> PyMemoryStore {
     fn __repr__(&'py self, py: Python<'py>) -> &'py Bound<'py, PyString> {
         intern!(py, "MemoryStore")
     }
+
+    /// Put the values of `files`, a mapping of paths to buffers, under `prefix`.
+    ///
+    /// Calls to an [`InMemory`] complete without waiting, so they are run to completion here
+    /// rather than on a runtime.
+    fn put_files(&self, prefix: &str, files: &Bound<PyAny>) -> PyObjectStoreResult<()> {
+        let py = files.py();
+        for item in files.call_method0(intern!(py, "items"))?.try_iter()? {
+            let (name, value): (String, Bound<PyAny>) = item?.extract()?;
+            let path = match prefix.trim_matches('/') {
+                "" => name,
+                prefix => format!("{}/{}", prefix, name),
+            };
+            let location = Path::parse(path).map_err(object_store::Error::from)?;
+            let data = Bytes::from(PyBuffer::<u8>::get(&value)?.to_vec(py)?);
+            block_on(self.0.put(&location, PutPayload::from(data)))?;
+        }
+        Ok(())
+    }
 }
 
 #[pymethods]
@@ -40,6 +67,46 @@ impl PyMemoryStore {
         Self(Arc::new(InMemory::new()))
     }
 
+    #[classmethod]
+    fn from_dict<'py>(
+        cls: &Bound<'py, PyType>,
+        mapping: &Bound<'py, PyAny>,
+    ) -> PyObjectStoreResult<Bound<'py, PyAny>> {
+        // Note: we instantiate through Python so that if cls is a subclass, we instantiate the
+        // subclass
+        let store = cls.call0()?;
+        store
+            .downcast::<PyMemoryStore>()?
+            .get()
+            .put_files("", mapping)?;
+        Ok(store)
+    }
+
+    fn fill(&self, prefix: &str, files: &Bound<PyAny>) -> PyObjectStoreResult<()> {
+        self.put_files(prefix, files)
+    }
+
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyObjectStoreResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new(py);
+        let metas = block_on(self.0.list(None).try_collect::<Vec<_>>())?;
+        for meta in metas {
+            let data = block_on(async { self.0.get(&meta.location).await?.bytes().await })?;
+            dict.set_item(meta.location.as_ref(), PyBytes::new(py, &data))?;
+        }
+        Ok(dict)
+    }
+
+    fn __reduce__<'py>(slf: &Bound<'py, Self>) -> PyObjectStoreResult<Bound<'py, PyTuple>> {
+        let py = slf.py();
+        // A pickled store is a snapshot of its contents, recreated with `from_dict`
+        let from_dict = slf.get_type().getattr(intern!(py, "from_dict"))?;
+        let contents = slf.get().to_dict(py)?;
+        Ok(PyTuple::new(
+            py,
+            [from_dict, PyTuple::new(py, [contents])?.into_any()],
+        )?)
+    }
+
     fn __eq__(slf: Py<Self>, other: &Bound<PyAny>) -> bool {
         // Two memory stores are equal only if they are the same object
         slf.is(other)
@@ -0,0 +1,386 @@
+//! An object store wrapper that writes to several stores and reads from the first that succeeds.
+
+use std::future::Future;
+use std::sync::Arc;
+
+use futures::future::{join_all, try_join_all};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    Error, GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMode,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::PyObjectStore;
+
+const STORE: &str = "MirrorStore";
+
+/// Whether a failed read is a valid response that the other stores shouldn't be asked for.
+fn is_final(err: &Error) -> bool {
+    matches!(err, Error::NotModified { .. } | Error::Precondition { .. })
+}
+
+/// The error of a write that succeeded on the primary store, but failed on some secondary stores.
+#[derive(Debug)]
+struct PartialWriteError {
+    /// The index of each store that failed among the member stores, with its error
+    failures: Vec<(usize, Error)>,
+    secondaries: usize,
+}
+
+impl std::fmt::Display for PartialWriteError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "The write succeeded on the primary store, but failed on {} of {} secondary stores",
+            self.failures.len(),
+            self.secondaries
+        )?;
+        for (i, (index, err)) in self.failures.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{}store {}: {}", sep, index, err)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for PartialWriteError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.failures
+            .first()
+            .map(|(_, err)| err as &(dyn std::error::Error + 'static))
+    }
+}
+
+/// Check the results of the writes to the secondary stores, in the order of the member stores
+/// after the primary store.
+///
+/// The writes are all awaited with `join_all`, so that one failure doesn't cancel the writes to
+/// the other stores, and all the failures are returned together.
+fn check_secondaries<T>(results: Vec<Result<T>>) -> Result<()> {
+    let secondaries = results.len();
+    let failures: Vec<_> = results
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, result)| result.err().map(|err| (i + 1, err)))
+        .collect();
+    if failures.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Generic {
+        store: STORE,
+        source: Box::new(PartialWriteError {
+            failures,
+            secondaries,
+        }),
+    })
+}
+
+/// Yield the entries of the first stream whose first item isn't a failure.
+///
+/// Once a stream has yielded an entry, any later error is passed through, since switching stores
+/// midway would repeat or skip entries.
+fn failover_stream<T: Send + 'static>(
+    streams: Vec<BoxStream<'static, Result<T>>>,
+) -> BoxStream<'static, Result<T>> {
+    futures::stream::once(async move {
+        let mut first_err = None;
+        for mut stream in streams {
+            match stream.next().await {
+                Some(Err(err)) if !is_final(&err) => {
+                    first_err.get_or_insert(err);
+                }
+                Some(first) => {
+                    return futures::stream::once(async move { first })
+                        .chain(stream)
+                        .boxed()
+                }
+                None => return futures::stream::empty().boxed(),
+            }
+        }
+        futures::stream::iter(first_err.map(Err)).boxed()
+    })
+    .flatten()
+    .boxed()
+}
+
+/// Store wrapper that mirrors writes to every member store and reads from the first member that
+/// succeeds.
+///
+/// Writes are made to the primary store first. Only once that succeeds are they made to the
+/// secondary stores, concurrently. Writes aren't atomic across the member stores: if a write to a
+/// secondary store fails, the write to the primary store is kept, and a [`PartialWriteError`]
+/// lists the stores that failed. Deleting an object that's missing from a secondary store isn't
+/// an error. Reads go to the primary store, failing over to each secondary store in turn
+/// if it errors, including if the object isn't found.
+#[derive(Debug)]
+pub struct MirrorStore {
+    stores: Vec<Arc<dyn ObjectStore>>,
+}
+
+impl std::fmt::Display for MirrorStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "MirrorStore([")?;
+        for (i, store) in self.stores.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", store)?;
+        }
+        write!(f, "])")
+    }
+}
+
+impl MirrorStore {
+    /// Create a new [`MirrorStore`] from a primary store followed by any secondary stores.
+    ///
+    /// # Panics
+    ///
+    /// If `stores` is empty.
+    pub fn new(stores: Vec<Arc<dyn ObjectStore>>) -> Self {
+        assert!(!stores.is_empty(), "MirrorStore needs at least one store");
+        Self { stores }
+    }
+
+    /// Access the member stores, starting with the primary store
+    pub fn stores(&self) -> &[Arc<dyn ObjectStore>] {
+        &self.stores
+    }
+
+    fn primary(&self) -> &Arc<dyn ObjectStore> {
+        &self.stores[0]
+    }
+
+    fn secondaries(&self) -> &[Arc<dyn ObjectStore>] {
+        &self.stores[1..]
+    }
+
+    /// Make a write to the primary store and then to all secondary stores, returning the result
+    /// of the primary store.
+    async fn write<'a, T, Fut>(&'a self, op: impl Fn(&'a Arc<dyn ObjectStore>) -> Fut) -> Result<T>
+    where
+        Fut: Future<Output = Result<T>>,
+    {
+        let result = op(self.primary()).await?;
+        check_secondaries(join_all(self.secondaries().iter().map(&op)).await)?;
+        Ok(result)
+    }
+
+    /// Make a read from each store in turn, until one succeeds.
+    ///
+    /// If every store fails, the error of the primary store is returned.
+    async fn read<'a, T, Fut>(&'a self, op: impl Fn(&'a Arc<dyn ObjectStore>) -> Fut) -> Result<T>
+    where
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut first_err = None;
+        for store in &self.stores {
+            match op(store).await {
+                Ok(result) => return Ok(result),
+                Err(err) if is_final(&err) => return Err(err),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        Err(first_err.expect("MirrorStore has at least one store"))
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for MirrorStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let result = self
+            .primary()
+            .put_opts(location, payload.clone(), opts.clone())
+            .await?;
+        // An e-tag or version of the primary store is meaningless to the other stores
+        let opts = PutOptions {
+            mode: match opts.mode {
+                PutMode::Update(_) => PutMode::Overwrite,
+                mode => mode,
+            },
+            ..opts
+        };
+        check_secondaries(
+            join_all(
+                self.secondaries()
+                    .iter()
+                    .map(|store| store.put_opts(location, payload.clone(), opts.clone())),
+            )
+            .await,
+        )?;
+        Ok(result)
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let results = join_all(
+            self.stores
+                .iter()
+                .map(|store| store.put_multipart_opts(location, opts.clone())),
+        )
+        .await;
+        let mut uploads = Vec::with_capacity(results.len());
+        let mut first_err = None;
+        for result in results {
+            match result {
+                Ok(upload) => uploads.push(upload),
+                Err(err) => {
+                    first_err.get_or_insert(err);
+                }
+            }
+        }
+        if let Some(err) = first_err {
+            // Don't leave the uploads that were started behind
+            join_all(uploads.iter_mut().map(|upload| upload.abort())).await;
+            return Err(err);
+        }
+        Ok(Box::new(MirrorUpload { uploads }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.read(|store| store.get_opts(location, options.clone()))
+            .await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        // An object missing from some stores is still deleted from the others
+        let result = self.primary().delete(location).await;
+        if matches!(&result, Err(err) if !matches!(err, Error::NotFound { .. })) {
+            return result;
+        }
+        check_secondaries(
+            join_all(self.secondaries().iter().map(|store| async move {
+                match store.delete(location).await {
+                    Err(Error::NotFound { .. }) => Ok(()),
+                    result => result,
+                }
+            }))
+            .await,
+        )?;
+        result
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        failover_stream(self.stores.iter().map(|store| store.list(prefix)).collect())
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        failover_stream(
+            self.stores
+                .iter()
+                .map(|store| store.list_with_offset(prefix, offset))
+                .collect(),
+        )
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.read(|store| store.list_with_delimiter(prefix)).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.write(|store| store.copy(from, to)).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.write(|store| store.rename(from, to)).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.write(|store| store.copy_if_not_exists(from, to)).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.write(|store| store.rename_if_not_exists(from, to))
+            .await
+    }
+}
+
+/// A multipart upload to every member store, with each part written to all of them.
+#[derive(Debug)]
+struct MirrorUpload {
+    uploads: Vec<Box<dyn MultipartUpload>>,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for MirrorUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let parts = self
+            .uploads
+            .iter_mut()
+            .map(|upload| upload.put_part(data.clone()))
+            .collect::<Vec<_>>();
+        Box::pin(async move {
+            try_join_all(parts).await?;
+            Ok(())
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let (primary, secondaries) = self
+            .uploads
+            .split_first_mut()
+            .expect("MirrorStore has at least one store");
+        let result = primary.complete().await?;
+        check_secondaries(join_all(secondaries.iter_mut().map(|upload| upload.complete())).await)?;
+        Ok(result)
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let results = join_all(self.uploads.iter_mut().map(|upload| upload.abort())).await;
+        results.into_iter().collect()
+    }
+}
+
+/// A Python-facing wrapper around a [`MirrorStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "MirrorStore", frozen, subclass)]
+pub struct PyMirrorStore(Arc<MirrorStore>);
+
+impl AsRef<Arc<MirrorStore>> for PyMirrorStore {
+    fn as_ref(&self) -> &Arc<MirrorStore> {
+        &self.0
+    }
+}
+
+impl PyMirrorStore {
+    /// Consume self and return the underlying [`MirrorStore`].
+    pub fn into_inner(self) -> Arc<MirrorStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyMirrorStore {
+    #[new]
+    fn py_new(stores: Vec<PyObjectStore>) -> PyResult<Self> {
+        if stores.is_empty() {
+            return Err(PyValueError::new_err(
+                "MirrorStore needs at least one store",
+            ));
+        }
+        Ok(Self(Arc::new(MirrorStore::new(
+            stores.into_iter().map(|store| store.into_dyn()).collect(),
+        ))))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+}
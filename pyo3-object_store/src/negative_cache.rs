@@ -0,0 +1,265 @@
+//! An object store wrapper that caches negative lookups (404s) for a short time.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures::stream::BoxStream;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result, UploadPart,
+};
+use pyo3::prelude::*;
+
+use crate::PyObjectStore;
+
+/// A set of paths known not to exist, each of which expires after a fixed time to live.
+#[derive(Debug)]
+pub(crate) struct NegativeCache {
+    ttl: Duration,
+    max_entries: usize,
+    entries: Mutex<HashMap<Path, Instant>>,
+}
+
+impl NegativeCache {
+    pub(crate) fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `location` was recently found not to exist.
+    pub(crate) fn contains(&self, location: &Path) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.get(location) {
+            Some(inserted) if inserted.elapsed() < self.ttl => true,
+            Some(_) => {
+                entries.remove(location);
+                false
+            }
+            None => false,
+        }
+    }
+
+    /// Record that `location` does not exist.
+    pub(crate) fn insert(&self, location: &Path) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries {
+            entries.retain(|_, inserted| inserted.elapsed() < self.ttl);
+        }
+        // If the cache is still full of live entries, skip caching rather than evicting them
+        if entries.len() < self.max_entries {
+            entries.insert(location.clone(), Instant::now());
+        }
+    }
+
+    /// Remove `location` from the cache, e.g. because it was just written.
+    pub(crate) fn invalidate(&self, location: &Path) {
+        self.entries.lock().unwrap().remove(location);
+    }
+
+    /// Remove all entries from the cache.
+    pub(crate) fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    fn not_found(location: &Path) -> object_store::Error {
+        object_store::Error::NotFound {
+            path: location.to_string(),
+            source: "Object not found (cached negative lookup)".into(),
+        }
+    }
+}
+
+/// Store wrapper that remembers which paths were recently found not to exist.
+///
+/// Any write to a path through this store invalidates the cached entry for that path.
+#[derive(Debug)]
+pub struct NegativeCacheStore {
+    inner: Arc<dyn ObjectStore>,
+    cache: Arc<NegativeCache>,
+}
+
+impl std::fmt::Display for NegativeCacheStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "NegativeCacheStore({})", self.inner)
+    }
+}
+
+impl NegativeCacheStore {
+    /// Create a new [`NegativeCacheStore`] wrapping `inner`
+    pub fn new(inner: Arc<dyn ObjectStore>, ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(NegativeCache::new(ttl, max_entries)),
+        }
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for NegativeCacheStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let result = self.inner.put_opts(location, payload, opts).await;
+        self.cache.invalidate(location);
+        result
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let upload = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(InvalidatingUpload {
+            inner: upload,
+            cache: self.cache.clone(),
+            location: location.clone(),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        // Requests for a specific version are not cached, as that version may exist even when the
+        // latest version does not
+        let cacheable = options.version.is_none();
+        if cacheable && self.cache.contains(location) {
+            return Err(NegativeCache::not_found(location));
+        }
+        let result = self.inner.get_opts(location, options).await;
+        if cacheable && matches!(result, Err(object_store::Error::NotFound { .. })) {
+            self.cache.insert(location);
+        }
+        result
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.copy(from, to).await;
+        self.cache.invalidate(to);
+        result
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.rename(from, to).await;
+        self.cache.invalidate(to);
+        result
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.copy_if_not_exists(from, to).await;
+        self.cache.invalidate(to);
+        result
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let result = self.inner.rename_if_not_exists(from, to).await;
+        self.cache.invalidate(to);
+        result
+    }
+}
+
+/// A multipart upload that invalidates the negative cache entry for its path on completion.
+#[derive(Debug)]
+struct InvalidatingUpload {
+    inner: Box<dyn MultipartUpload>,
+    cache: Arc<NegativeCache>,
+    location: Path,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for InvalidatingUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        self.inner.put_part(data)
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self.inner.complete().await;
+        self.cache.invalidate(&self.location);
+        result
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
+
+/// A Python-facing wrapper around a [`NegativeCacheStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "NegativeCacheStore", frozen, subclass)]
+pub struct PyNegativeCacheStore(Arc<NegativeCacheStore>);
+
+impl AsRef<Arc<NegativeCacheStore>> for PyNegativeCacheStore {
+    fn as_ref(&self) -> &Arc<NegativeCacheStore> {
+        &self.0
+    }
+}
+
+impl PyNegativeCacheStore {
+    /// Consume self and return the underlying [`NegativeCacheStore`].
+    pub fn into_inner(self) -> Arc<NegativeCacheStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyNegativeCacheStore {
+    #[new]
+    #[pyo3(signature = (store, *, ttl=Duration::from_secs(5), max_entries=10_000))]
+    fn py_new(store: PyObjectStore, ttl: Duration, max_entries: usize) -> Self {
+        Self(Arc::new(NegativeCacheStore::new(
+            store.into_dyn(),
+            ttl,
+            max_entries,
+        )))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[pyo3(signature = (path=None))]
+    fn invalidate(&self, path: Option<String>) {
+        match path {
+            Some(path) => self.0.cache.invalidate(&path.into()),
+            None => self.0.cache.clear(),
+        }
+    }
+}
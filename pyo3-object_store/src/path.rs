@@ -1,13 +1,46 @@
 use object_store::path::Path;
+use pyo3::exceptions::PyTypeError;
+use pyo3::intern;
 use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedStr;
 use pyo3::types::PyString;
 
+use crate::PyObjectStoreError;
+
+/// A path within a store, which may be passed from Python as a `str` or an `os.PathLike`.
+///
+/// Unlike [`Path::from`], which silently percent-encodes characters that some stores don't
+/// support, the path is validated with [`Path::parse`]. Empty segments, `.` and `..` segments,
+/// and control characters raise an `InvalidPathError`.
 #[derive(Clone, Debug, Default, PartialEq)]
-pub(crate) struct PyPath(Path);
+pub struct PyPath(Path);
+
+impl PyPath {
+    /// Consume self and return the underlying [`Path`].
+    pub fn into_inner(self) -> Path {
+        self.0
+    }
+}
+
+/// Extract a `str` from a `str`, or from an `os.PathLike` whose `__fspath__` returns a `str`.
+pub fn extract_path_str(ob: &Bound<PyAny>) -> PyResult<PyBackedStr> {
+    if let Ok(path) = ob.extract::<PyBackedStr>() {
+        return Ok(path);
+    }
+    let py = ob.py();
+    py.import(intern!(py, "os"))?
+        .call_method1(intern!(py, "fspath"), (ob,))?
+        .extract::<PyBackedStr>()
+        .map_err(|_| PyTypeError::new_err("Expected a str or an os.PathLike returning a str."))
+}
 
 impl<'py> FromPyObject<'py> for PyPath {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        Ok(Self(ob.extract::<String>()?.into()))
+        let path = extract_path_str(ob)?;
+        let path = Path::parse(&*path).map_err(|source| {
+            PyObjectStoreError::from(object_store::Error::InvalidPath { source })
+        })?;
+        Ok(Self(path))
     }
 }
 
@@ -0,0 +1,126 @@
+//! Recycling of the connections of a store, after a maximum lifetime or on demand with
+//! `reset_connections`.
+//!
+//! Connections are recycled by replacing the HTTP client of the store, and with it its
+//! connection pool. Requests in progress finish on the connections of the previous client.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use object_store::client::{
+    HttpClient, HttpConnector, HttpError, HttpErrorKind, HttpRequest, HttpResponse, HttpService,
+    ReqwestConnector,
+};
+use object_store::ClientOptions;
+
+use crate::resolver::{resolving_client, PyResolver};
+
+/// A handle to the connections of a store, shared by the store and all of its HTTP clients.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ConnectionPool {
+    generation: Arc<AtomicU64>,
+}
+
+impl ConnectionPool {
+    /// Replace all the connections of the store, once their requests in progress have finished.
+    pub(crate) fn reset(&self) {
+        self.generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Relaxed)
+    }
+}
+
+/// Connect the HTTP client making the requests of a store.
+fn connect(
+    options: &ClientOptions,
+    resolver: Option<&PyResolver>,
+) -> object_store::Result<HttpClient> {
+    match resolver {
+        Some(resolver) => Ok(HttpClient::new(resolving_client(
+            options,
+            resolver.clone(),
+        )?)),
+        None => ReqwestConnector::default().connect(options),
+    }
+}
+
+#[derive(Debug)]
+struct PooledClient {
+    client: HttpClient,
+    created_at: Instant,
+    generation: u64,
+}
+
+/// An HTTP client that is replaced by a new one once it's older than `max_lifetime` or its pool
+/// has been reset.
+#[derive(Debug)]
+pub(crate) struct RecyclingClient {
+    options: ClientOptions,
+    resolver: Option<PyResolver>,
+    max_lifetime: Option<Duration>,
+    pool: ConnectionPool,
+    current: RwLock<PooledClient>,
+}
+
+impl RecyclingClient {
+    pub(crate) fn new(
+        options: &ClientOptions,
+        resolver: Option<&PyResolver>,
+        max_lifetime: Option<Duration>,
+        pool: ConnectionPool,
+    ) -> object_store::Result<Self> {
+        let current = PooledClient {
+            client: connect(options, resolver)?,
+            created_at: Instant::now(),
+            generation: pool.generation(),
+        };
+        Ok(Self {
+            options: options.clone(),
+            resolver: resolver.cloned(),
+            max_lifetime,
+            pool,
+            current: RwLock::new(current),
+        })
+    }
+
+    fn is_stale(&self, client: &PooledClient, generation: u64) -> bool {
+        client.generation != generation
+            || self
+                .max_lifetime
+                .is_some_and(|max_lifetime| client.created_at.elapsed() >= max_lifetime)
+    }
+
+    /// The current client, replacing it first if it's stale.
+    fn client(&self) -> object_store::Result<HttpClient> {
+        let generation = self.pool.generation();
+        {
+            let current = self.current.read().unwrap();
+            if !self.is_stale(&current, generation) {
+                return Ok(current.client.clone());
+            }
+        }
+        let mut current = self.current.write().unwrap();
+        // Another request may have replaced the client in the meantime
+        if self.is_stale(&current, generation) {
+            *current = PooledClient {
+                client: connect(&self.options, self.resolver.as_ref())?,
+                created_at: Instant::now(),
+                generation,
+            };
+        }
+        Ok(current.client.clone())
+    }
+}
+
+#[async_trait::async_trait]
+impl HttpService for RecyclingClient {
+    async fn call(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let client = self
+            .client()
+            .map_err(|err| HttpError::new(HttpErrorKind::Connect, err))?;
+        client.execute(req).await
+    }
+}
@@ -5,16 +5,21 @@
 
 use bytes::Bytes;
 use futures::{stream::BoxStream, StreamExt, TryStreamExt};
+use pyo3::prelude::*;
 use std::borrow::Cow;
 use std::ops::Range;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock};
 
+use object_store::multipart::{MultipartStore, PartId};
 use object_store::path::Path;
 use object_store::{
-    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore, PutMultipartOpts,
-    PutOptions, PutPayload, PutResult, Result,
+    GetOptions, GetResult, ListResult, MultipartId, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
 };
 
+use crate::path::PyPath;
+use crate::PyObjectStore;
+
 static DEFAULT_PATH: OnceLock<Path> = OnceLock::new();
 
 /// Store wrapper that applies a constant prefix to all paths handled by the store.
@@ -48,8 +53,13 @@ impl<T: ObjectStore> MaybePrefixedStore<T> {
         &self.inner
     }
 
+    /// Access the prefix applied to all paths, if any
+    pub fn prefix(&self) -> Option<&Path> {
+        self.prefix.as_ref()
+    }
+
     /// Create the full path from a path relative to prefix
-    fn full_path<'a>(&'a self, location: &'a Path) -> Cow<'a, Path> {
+    pub fn full_path<'a>(&'a self, location: &'a Path) -> Cow<'a, Path> {
         if let Some(prefix) = &self.prefix {
             Cow::Owned(prefix.parts().chain(location.parts()).collect())
         } else {
@@ -133,7 +143,7 @@ impl<T: ObjectStore> ObjectStore for MaybePrefixedStore<T> {
     async fn put_multipart_opts(
         &self,
         location: &Path,
-        opts: PutMultipartOpts,
+        opts: PutMultipartOptions,
     ) -> Result<Box<dyn MultipartUpload>> {
         let full_path = self.full_path(location);
         self.inner.put_multipart_opts(&full_path, opts).await
@@ -234,3 +244,79 @@ impl<T: ObjectStore> ObjectStore for MaybePrefixedStore<T> {
         self.inner.rename_if_not_exists(&full_from, &full_to).await
     }
 }
+
+#[async_trait::async_trait]
+impl<T: ObjectStore + MultipartStore> MultipartStore for MaybePrefixedStore<T> {
+    async fn create_multipart(&self, path: &Path) -> Result<MultipartId> {
+        let full_path = self.full_path(path);
+        self.inner.create_multipart(&full_path).await
+    }
+
+    async fn put_part(
+        &self,
+        path: &Path,
+        id: &MultipartId,
+        part_idx: usize,
+        data: PutPayload,
+    ) -> Result<PartId> {
+        let full_path = self.full_path(path);
+        self.inner.put_part(&full_path, id, part_idx, data).await
+    }
+
+    async fn complete_multipart(
+        &self,
+        path: &Path,
+        id: &MultipartId,
+        parts: Vec<PartId>,
+    ) -> Result<PutResult> {
+        let full_path = self.full_path(path);
+        self.inner.complete_multipart(&full_path, id, parts).await
+    }
+
+    async fn abort_multipart(&self, path: &Path, id: &MultipartId) -> Result<()> {
+        let full_path = self.full_path(path);
+        self.inner.abort_multipart(&full_path, id).await
+    }
+}
+
+/// A Python-facing wrapper around a [`MaybePrefixedStore`] that scopes an existing store.
+#[derive(Debug, Clone)]
+#[pyclass(name = "PrefixStore", frozen, subclass)]
+pub struct PyPrefixStore(Arc<MaybePrefixedStore<Arc<dyn ObjectStore>>>);
+
+impl AsRef<Arc<MaybePrefixedStore<Arc<dyn ObjectStore>>>> for PyPrefixStore {
+    fn as_ref(&self) -> &Arc<MaybePrefixedStore<Arc<dyn ObjectStore>>> {
+        &self.0
+    }
+}
+
+impl PyPrefixStore {
+    /// Consume self and return the underlying [`MaybePrefixedStore`].
+    pub fn into_inner(self) -> Arc<MaybePrefixedStore<Arc<dyn ObjectStore>>> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyPrefixStore {
+    #[new]
+    fn py_new(store: PyObjectStore, prefix: PyPath) -> Self {
+        Self(Arc::new(MaybePrefixedStore::new(
+            store.into_dyn(),
+            Some(prefix),
+        )))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "PrefixStore({}, prefix=\"{}\")",
+            self.0.inner(),
+            self.prefix()
+        )
+    }
+
+    #[getter]
+    fn prefix(&self) -> &str {
+        self.0.prefix().map(|prefix| prefix.as_ref()).unwrap_or("")
+    }
+}
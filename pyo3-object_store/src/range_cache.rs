@@ -0,0 +1,429 @@
+//! An object store wrapper that caches byte ranges in memory.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use object_store::path::Path;
+use object_store::{
+    Attributes, GetOptions, GetRange, GetResult, GetResultPayload, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
+};
+use pyo3::prelude::*;
+
+use crate::PyObjectStore;
+
+/// The range of a request, as a hashable key.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum RangeKey {
+    Bounded(u64, u64),
+    Offset(u64),
+    Suffix(u64),
+}
+
+impl From<&GetRange> for RangeKey {
+    fn from(value: &GetRange) -> Self {
+        match value {
+            GetRange::Bounded(range) => Self::Bounded(range.start, range.end),
+            GetRange::Offset(offset) => Self::Offset(*offset),
+            GetRange::Suffix(suffix) => Self::Suffix(*suffix),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    location: Path,
+    e_tag: String,
+    range: RangeKey,
+}
+
+/// A cached response to a ranged request.
+#[derive(Debug, Clone)]
+struct CachedRange {
+    meta: ObjectMeta,
+    attributes: Attributes,
+    range: Range<u64>,
+    data: Bytes,
+}
+
+/// The cached ranges, in order of last use.
+#[derive(Debug, Default)]
+struct RangeCache {
+    /// The e-tag of the cached ranges of each path, and the number of them
+    e_tags: HashMap<Path, (String, usize)>,
+    /// The cached range of each key, and its position in `lru`
+    entries: HashMap<CacheKey, (CachedRange, u64)>,
+    /// The key of each entry, from least to most recently used
+    lru: BTreeMap<u64, CacheKey>,
+    /// The total size of all cached ranges
+    size: u64,
+    tick: u64,
+}
+
+impl RangeCache {
+    /// The key under which `range` of `location` is cached, if the e-tag of `location` is known.
+    fn key(&self, location: &Path, range: &GetRange) -> Option<CacheKey> {
+        Some(CacheKey {
+            location: location.clone(),
+            e_tag: self.e_tags.get(location)?.0.clone(),
+            range: range.into(),
+        })
+    }
+
+    /// Look up a cached range, marking it as most recently used.
+    fn get(&mut self, key: &CacheKey) -> Option<CachedRange> {
+        self.tick += 1;
+        let (entry, last_used) = self.entries.get_mut(key)?;
+        self.lru.remove(&*last_used);
+        self.lru.insert(self.tick, key.clone());
+        *last_used = self.tick;
+        Some(entry.clone())
+    }
+
+    /// Drop the cached ranges of `meta.location` if they're of a previous version.
+    fn observe(&mut self, meta: &ObjectMeta) {
+        let current = self.e_tags.get(&meta.location).map(|(e_tag, _)| e_tag);
+        if current.is_some() && current != meta.e_tag.as_ref() {
+            self.invalidate(&meta.location);
+        }
+    }
+
+    /// Insert a cached range as the most recently used, then evict the least recently used
+    /// ranges until the total size is at most `max_size`.
+    ///
+    /// The range isn't cached if its path has cached ranges with another e-tag, which a
+    /// concurrent read of another version of the object cached first.
+    fn insert(&mut self, key: CacheKey, entry: CachedRange, max_size: u64) {
+        self.remove(&key);
+        let (e_tag, count) = self
+            .e_tags
+            .entry(key.location.clone())
+            .or_insert_with(|| (key.e_tag.clone(), 0));
+        if *e_tag != key.e_tag {
+            return;
+        }
+        *count += 1;
+        self.tick += 1;
+        self.size += entry.data.len() as u64;
+        self.entries.insert(key.clone(), (entry, self.tick));
+        self.lru.insert(self.tick, key);
+
+        while self.size > max_size {
+            let Some((_, oldest)) = self.lru.first_key_value() else {
+                break;
+            };
+            let oldest = oldest.clone();
+            self.remove(&oldest);
+        }
+    }
+
+    /// Remove a cached range, and the e-tag of its path once it has no cached ranges left.
+    fn remove(&mut self, key: &CacheKey) {
+        let Some((entry, last_used)) = self.entries.remove(key) else {
+            return;
+        };
+        self.lru.remove(&last_used);
+        self.size -= entry.data.len() as u64;
+        if let Some((_, count)) = self.e_tags.get_mut(&key.location) {
+            *count -= 1;
+            if *count == 0 {
+                self.e_tags.remove(&key.location);
+            }
+        }
+    }
+
+    /// Forget the e-tag and all cached ranges of `location`.
+    fn invalidate(&mut self, location: &Path) {
+        if self.e_tags.remove(location).is_none() {
+            return;
+        }
+        let mut removed = 0;
+        self.entries.retain(|key, (entry, _)| {
+            let keep = key.location != *location;
+            if !keep {
+                removed += entry.data.len() as u64;
+            }
+            keep
+        });
+        self.lru.retain(|_, key| key.location != *location);
+        self.size -= removed;
+    }
+
+    fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Store wrapper that caches the responses to ranged reads in memory, evicting the least
+/// recently used once their total size exceeds a budget.
+///
+/// Ranges are cached by path, e-tag and the exact range requested, which suits workloads that
+/// read the same footers or headers many times. A response through this store with a different
+/// e-tag than the cached ranges of its path drops them. Unless `validate` is set, a cached range
+/// is returned without a request, so that a change by another writer is only observed once
+/// another read through this store, such as a `head`, returns the new e-tag. With `validate`,
+/// each cached range is revalidated with a conditional request.
+///
+/// Writes through this store drop the cached ranges of the affected paths.
+#[derive(Debug)]
+pub struct RangeCacheStore {
+    inner: Arc<dyn ObjectStore>,
+    max_size: u64,
+    validate: bool,
+    cache: Mutex<RangeCache>,
+}
+
+impl std::fmt::Display for RangeCacheStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RangeCacheStore({})", self.inner)
+    }
+}
+
+impl RangeCacheStore {
+    /// Create a new [`RangeCacheStore`] wrapping `inner`
+    pub fn new(inner: Arc<dyn ObjectStore>, max_size: u64, validate: bool) -> Self {
+        Self {
+            inner,
+            max_size,
+            validate,
+            cache: Mutex::new(RangeCache::default()),
+        }
+    }
+
+    /// Access the underlying store
+    pub fn inner(&self) -> &Arc<dyn ObjectStore> {
+        &self.inner
+    }
+
+    /// Drop all cached ranges.
+    pub fn clear(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    fn invalidate(&self, location: &Path) {
+        self.cache.lock().unwrap().invalidate(location);
+    }
+
+    /// Make a request through the wrapped store, recording the e-tag of the response.
+    async fn get_observed(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let result = self.inner.get_opts(location, options).await;
+        match &result {
+            Ok(result) => self.cache.lock().unwrap().observe(&result.meta),
+            Err(object_store::Error::NotFound { .. }) => self.invalidate(location),
+            Err(_) => {}
+        }
+        result
+    }
+}
+
+/// Whether the response to a request with `options` can be cached.
+///
+/// Only plain ranged reads are cached. Any other request is passed through.
+fn is_cacheable(options: &GetOptions) -> bool {
+    options.range.is_some()
+        && !options.head
+        && options.if_match.is_none()
+        && options.if_none_match.is_none()
+        && options.if_modified_since.is_none()
+        && options.if_unmodified_since.is_none()
+        && options.version.is_none()
+}
+
+fn into_result(entry: CachedRange) -> GetResult {
+    let data = entry.data;
+    GetResult {
+        payload: GetResultPayload::Stream(futures::stream::once(async move { Ok(data) }).boxed()),
+        meta: entry.meta,
+        range: entry.range,
+        attributes: entry.attributes,
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for RangeCacheStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.invalidate(location);
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.invalidate(location);
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        if !is_cacheable(&options) {
+            return self.get_observed(location, options).await;
+        }
+        // This unwrap is ok because `is_cacheable` checks that a range is set
+        let range = options.range.clone().unwrap();
+
+        let cached = {
+            let mut cache = self.cache.lock().unwrap();
+            cache
+                .key(location, &range)
+                .and_then(|key| cache.get(&key).map(|entry| (key, entry)))
+        };
+        let result = match cached {
+            Some((_, entry)) if !self.validate => return Ok(into_result(entry)),
+            Some((key, entry)) => {
+                let request = GetOptions {
+                    if_none_match: Some(key.e_tag),
+                    ..options
+                };
+                match self.get_observed(location, request).await {
+                    Err(object_store::Error::NotModified { .. }) => return Ok(into_result(entry)),
+                    result => result?,
+                }
+            }
+            None => self.get_observed(location, options).await?,
+        };
+
+        let Some(e_tag) = result.meta.e_tag.clone() else {
+            return Ok(result);
+        };
+        if result.range.end - result.range.start > self.max_size {
+            return Ok(result);
+        }
+        let meta = result.meta.clone();
+        let attributes = result.attributes.clone();
+        let returned_range = result.range.clone();
+        let data = result.bytes().await?;
+        let entry = CachedRange {
+            meta,
+            attributes,
+            range: returned_range,
+            data,
+        };
+        let key = CacheKey {
+            location: location.clone(),
+            e_tag,
+            range: (&range).into(),
+        };
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(key, entry.clone(), self.max_size);
+        Ok(into_result(entry))
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.invalidate(location);
+        self.inner.delete(location).await
+    }
+
+    fn delete_stream<'a>(
+        &'a self,
+        locations: BoxStream<'a, Result<Path>>,
+    ) -> BoxStream<'a, Result<Path>> {
+        let locations = locations
+            .map(|location| {
+                if let Ok(location) = &location {
+                    self.invalidate(location);
+                }
+                location
+            })
+            .boxed();
+        self.inner.delete_stream(locations)
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        self.inner.list_with_offset(prefix, offset)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(to);
+        self.inner.copy(from, to).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(from);
+        self.invalidate(to);
+        self.inner.rename(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(to);
+        self.inner.copy_if_not_exists(from, to).await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.invalidate(from);
+        self.invalidate(to);
+        self.inner.rename_if_not_exists(from, to).await
+    }
+}
+
+/// A Python-facing wrapper around a [`RangeCacheStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "RangeCacheStore", frozen, subclass)]
+pub struct PyRangeCacheStore(Arc<RangeCacheStore>);
+
+impl AsRef<Arc<RangeCacheStore>> for PyRangeCacheStore {
+    fn as_ref(&self) -> &Arc<RangeCacheStore> {
+        &self.0
+    }
+}
+
+impl PyRangeCacheStore {
+    /// Consume self and return the underlying [`RangeCacheStore`].
+    pub fn into_inner(self) -> Arc<RangeCacheStore> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyRangeCacheStore {
+    #[new]
+    #[pyo3(signature = (store, max_size, *, validate=false))]
+    fn py_new(store: PyObjectStore, max_size: u64, validate: bool) -> Self {
+        Self(Arc::new(RangeCacheStore::new(
+            store.into_dyn(),
+            max_size,
+            validate,
+        )))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[getter]
+    fn max_size(&self) -> u64 {
+        self.0.max_size
+    }
+
+    #[getter]
+    fn size(&self) -> u64 {
+        self.0.cache.lock().unwrap().size
+    }
+
+    fn clear(&self) {
+        self.0.clear()
+    }
+}
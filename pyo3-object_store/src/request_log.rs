@@ -0,0 +1,90 @@
+//! Logging of the HTTP requests of a store, enabled with the `log_requests` client option.
+//!
+//! Requests are logged with the [`log`] crate, under the `obstore::http` target. Applications
+//! bridge these records to their own logging, such as to Python's `logging` module with
+//! `pyo3-log`.
+
+use std::time::Instant;
+
+use http::Uri;
+use object_store::client::{HttpClient, HttpError, HttpRequest, HttpResponse, HttpService};
+
+/// The target of the log records of requests.
+const TARGET: &str = "obstore::http";
+
+/// Query parameters holding credentials, such as the signatures of SAS tokens and presigned URLs,
+/// compared case-insensitively.
+const SECRET_QUERY_PARAMS: &[&str] = &[
+    "sig",
+    "signature",
+    "x-amz-credential",
+    "x-amz-security-token",
+    "x-amz-signature",
+    "x-goog-credential",
+    "x-goog-signature",
+    "access_token",
+    "token",
+];
+
+/// The URL of a request, with the values of credentials in its query string redacted.
+fn redact_url(uri: &Uri) -> String {
+    let Some(query) = uri.query() else {
+        return uri.to_string();
+    };
+    let query = query
+        .split('&')
+        .map(|param| match param.split_once('=') {
+            Some((key, _))
+                if SECRET_QUERY_PARAMS
+                    .iter()
+                    .any(|secret| key.eq_ignore_ascii_case(secret)) =>
+            {
+                format!("{}=REDACTED", key)
+            }
+            _ => param.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("&");
+    let uri = uri.to_string();
+    let base = uri.split_once('?').map(|(base, _)| base).unwrap_or(&uri);
+    format!("{}?{}", base, query)
+}
+
+/// Wrap `client` so that every request it makes is logged.
+pub(crate) fn logging_client(client: HttpClient) -> HttpClient {
+    HttpClient::new(LoggingService { client })
+}
+
+#[derive(Debug)]
+struct LoggingService {
+    client: HttpClient,
+}
+
+#[async_trait::async_trait]
+impl HttpService for LoggingService {
+    async fn call(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let method = req.method().clone();
+        let url = redact_url(req.uri());
+        let start = Instant::now();
+        let result = self.client.execute(req).await;
+        match &result {
+            Ok(response) => log::info!(
+                target: TARGET,
+                "{} {} -> {} in {:?}",
+                method,
+                url,
+                response.status(),
+                start.elapsed()
+            ),
+            Err(err) => log::warn!(
+                target: TARGET,
+                "{} {} failed in {:?}: {}",
+                method,
+                url,
+                start.elapsed(),
+                err
+            ),
+        }
+        result
+    }
+}
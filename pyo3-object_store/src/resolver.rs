@@ -0,0 +1,314 @@
+//! Resolution of the hostnames of a store to addresses chosen by the user.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+
+use object_store::{ClientConfigKey, ClientOptions};
+use pyo3::exceptions::{PyRuntimeError, PyTypeError, PyValueError};
+use pyo3::intern;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyString, PyTuple};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use crate::tags::DEFAULT_USER_AGENT;
+
+/// Parse a single address, either an IP address or an IP address and port.
+///
+/// An address without a port is given port 0, which `reqwest` replaces with the port of the URL.
+fn parse_addr(host: &str, addr: &str) -> PyResult<SocketAddr> {
+    if let Ok(ip) = addr.parse::<IpAddr>() {
+        return Ok(SocketAddr::new(ip, 0));
+    }
+    addr.parse::<SocketAddr>().map_err(|_| {
+        PyValueError::new_err(format!(
+            "Invalid address for host '{}': '{}'. Expected an IP address, optionally with a port.",
+            host, addr
+        ))
+    })
+}
+
+/// Parse the addresses of a host, given as either a single address or a sequence of addresses.
+fn parse_addrs(host: &str, ob: &Bound<PyAny>) -> PyResult<Vec<SocketAddr>> {
+    let addrs = if let Ok(addr) = ob.downcast::<PyString>() {
+        vec![parse_addr(host, addr.to_str()?)?]
+    } else {
+        ob.extract::<Vec<String>>()?
+            .iter()
+            .map(|addr| parse_addr(host, addr))
+            .collect::<PyResult<Vec<_>>>()?
+    };
+    if addrs.is_empty() {
+        return Err(PyValueError::new_err(format!(
+            "No addresses given for host '{}'",
+            host
+        )));
+    }
+    Ok(addrs)
+}
+
+/// A user-provided resolver of hostnames, either a mapping from hostname to addresses or a
+/// callback returning the addresses of a hostname.
+///
+/// Hostnames that the resolver doesn't know about are resolved through the system's DNS.
+#[derive(Debug)]
+pub struct PyResolver {
+    /// The object passed by the user, kept for pickling and equality
+    user_resolver: PyObject,
+    /// The addresses of a static resolver, keyed by lowercase hostname. `None` for a callback.
+    hosts: Option<Arc<HashMap<String, Vec<SocketAddr>>>>,
+}
+
+impl PyResolver {
+    fn equals(&self, py: Python, other: &Self) -> PyResult<bool> {
+        self.user_resolver
+            .call_method1(py, "__eq__", PyTuple::new(py, vec![&other.user_resolver])?)?
+            .extract(py)
+    }
+
+    /// Find the addresses of `host`, or `None` if the resolver doesn't know about it.
+    async fn lookup(&self, host: String) -> PyResult<Option<Vec<SocketAddr>>> {
+        if let Some(hosts) = &self.hosts {
+            return Ok(hosts.get(&host).cloned());
+        }
+        // The callback may block, e.g. on a service discovery request
+        let callback = Python::with_gil(|py| self.user_resolver.clone_ref(py));
+        tokio::task::spawn_blocking(move || {
+            Python::with_gil(|py| {
+                let addrs = callback.call1(py, (&host,))?;
+                let addrs = addrs.bind(py);
+                if addrs.is_none() {
+                    Ok(None)
+                } else {
+                    parse_addrs(&host, addrs).map(Some)
+                }
+            })
+        })
+        .await
+        .map_err(|err| PyRuntimeError::new_err(err.to_string()))?
+    }
+}
+
+impl Clone for PyResolver {
+    fn clone(&self) -> Self {
+        let cloned_resolver = Python::with_gil(|py| self.user_resolver.clone_ref(py));
+        Self {
+            user_resolver: cloned_resolver,
+            hosts: self.hosts.clone(),
+        }
+    }
+}
+
+impl PartialEq for PyResolver {
+    fn eq(&self, other: &Self) -> bool {
+        Python::with_gil(|py| self.equals(py, other)).unwrap_or(false)
+    }
+}
+
+impl<'py> FromPyObject<'py> for PyResolver {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let hosts = if let Ok(dict) = ob.downcast::<PyDict>() {
+            let mut hosts = HashMap::with_capacity(dict.len());
+            for (host, addrs) in dict.iter() {
+                let host = host.extract::<String>()?.to_ascii_lowercase();
+                let addrs = parse_addrs(&host, &addrs)?;
+                hosts.insert(host, addrs);
+            }
+            Some(Arc::new(hosts))
+        } else if ob.hasattr(intern!(ob.py(), "__call__"))? {
+            None
+        } else {
+            return Err(PyTypeError::new_err(
+                "Expected resolver to be a dict or a callable object.",
+            ));
+        };
+        Ok(Self {
+            user_resolver: ob.clone().unbind(),
+            hosts,
+        })
+    }
+}
+
+impl<'py> IntoPyObject<'py> for PyResolver {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        (&self).into_pyobject(py)
+    }
+}
+
+impl<'py> IntoPyObject<'py> for &PyResolver {
+    type Target = PyAny;
+    type Output = Bound<'py, PyAny>;
+    type Error = PyErr;
+
+    fn into_pyobject(self, py: Python<'py>) -> Result<Self::Output, Self::Error> {
+        Ok(self.user_resolver.bind(py).clone())
+    }
+}
+
+/// A [`Resolve`] implementation for `reqwest` using a [`PyResolver`], falling back to the system's
+/// DNS.
+struct ClientResolver {
+    resolver: PyResolver,
+    randomize_addresses: bool,
+}
+
+impl Resolve for ClientResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_ascii_lowercase();
+        let resolver = self.resolver.clone();
+        let randomize_addresses = self.randomize_addresses;
+        Box::pin(async move {
+            let mut addrs = match resolver.lookup(host.clone()).await? {
+                Some(addrs) => addrs,
+                None => tokio::net::lookup_host((host.as_str(), 0)).await?.collect(),
+            };
+            if randomize_addresses {
+                addrs.shuffle(&mut StdRng::from_os_rng());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+fn config_error(key: ClientConfigKey, value: &str, expected: &str) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "Config",
+        source: format!(
+            "failed to parse \"{}\" as {} for {}",
+            value,
+            expected,
+            key.as_ref()
+        )
+        .into(),
+    }
+}
+
+fn client_error(err: reqwest::Error) -> object_store::Error {
+    object_store::Error::Generic {
+        store: "HTTP client",
+        source: Box::new(err),
+    }
+}
+
+/// Reads the client configuration back out of [`ClientOptions`], which doesn't expose the
+/// `reqwest` client it builds.
+struct ClientConfig<'a>(&'a ClientOptions);
+
+impl ClientConfig<'_> {
+    fn get(&self, key: ClientConfigKey) -> Option<String> {
+        self.0.get_config_value(&key)
+    }
+
+    fn bool(&self, key: ClientConfigKey) -> object_store::Result<bool> {
+        match self.get(key) {
+            None => Ok(false),
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "1" | "true" | "on" | "yes" | "y" => Ok(true),
+                "0" | "false" | "off" | "no" | "n" => Ok(false),
+                _ => Err(config_error(key, &value, "boolean")),
+            },
+        }
+    }
+
+    fn duration(&self, key: ClientConfigKey) -> object_store::Result<Option<std::time::Duration>> {
+        self.get(key)
+            .map(|value| {
+                humantime::parse_duration(&value).map_err(|_| config_error(key, &value, "Duration"))
+            })
+            .transpose()
+    }
+
+    fn number<T: std::str::FromStr>(
+        &self,
+        key: ClientConfigKey,
+    ) -> object_store::Result<Option<T>> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .map_err(|_| config_error(key, &value, "number"))
+            })
+            .transpose()
+    }
+}
+
+/// Build a `reqwest` client from `options` that resolves hostnames with `resolver`.
+///
+/// This mirrors how `object_store` builds its own client, which can't be given a resolver.
+pub(crate) fn resolving_client(
+    options: &ClientOptions,
+    resolver: PyResolver,
+) -> object_store::Result<reqwest::Client> {
+    let config = ClientConfig(options);
+    let mut builder = reqwest::ClientBuilder::new().user_agent(
+        config
+            .get(ClientConfigKey::UserAgent)
+            .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string()),
+    );
+
+    if let Some(proxy_url) = config.get(ClientConfigKey::ProxyUrl) {
+        let mut proxy = reqwest::Proxy::all(proxy_url).map_err(client_error)?;
+        if let Some(certificate) = config.get(ClientConfigKey::ProxyCaCertificate) {
+            let certificate = reqwest::tls::Certificate::from_pem(certificate.as_bytes())
+                .map_err(client_error)?;
+            builder = builder.add_root_certificate(certificate);
+        }
+        if let Some(proxy_excludes) = config.get(ClientConfigKey::ProxyExcludes) {
+            proxy = proxy.no_proxy(reqwest::NoProxy::from_string(&proxy_excludes));
+        }
+        builder = builder.proxy(proxy);
+    }
+
+    if let Some(timeout) = config.duration(ClientConfigKey::Timeout)? {
+        builder = builder.timeout(timeout);
+    }
+    if let Some(timeout) = config.duration(ClientConfigKey::ConnectTimeout)? {
+        builder = builder.connect_timeout(timeout);
+    }
+    if let Some(timeout) = config.duration(ClientConfigKey::PoolIdleTimeout)? {
+        builder = builder.pool_idle_timeout(timeout);
+    }
+    if let Some(max) = config.number(ClientConfigKey::PoolMaxIdlePerHost)? {
+        builder = builder.pool_max_idle_per_host(max);
+    }
+    if let Some(interval) = config.duration(ClientConfigKey::Http2KeepAliveInterval)? {
+        builder = builder.http2_keep_alive_interval(interval);
+    }
+    if let Some(timeout) = config.duration(ClientConfigKey::Http2KeepAliveTimeout)? {
+        builder = builder.http2_keep_alive_timeout(timeout);
+    }
+    if config.bool(ClientConfigKey::Http2KeepAliveWhileIdle)? {
+        builder = builder.http2_keep_alive_while_idle(true);
+    }
+    if let Some(size) = config.number(ClientConfigKey::Http2MaxFrameSize)? {
+        builder = builder.http2_max_frame_size(Some(size));
+    }
+    if config.bool(ClientConfigKey::Http1Only)? {
+        builder = builder.http1_only();
+    }
+    if config.bool(ClientConfigKey::Http2Only)? {
+        builder = builder.http2_prior_knowledge();
+    }
+    if config.bool(ClientConfigKey::AllowInvalidCertificates)? {
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    // As in object_store, compression would interfere with the `Content-Length` of objects
+    builder = builder.no_gzip().no_brotli().no_zstd().no_deflate();
+
+    builder
+        .dns_resolver(Arc::new(ClientResolver {
+            resolver,
+            randomize_addresses: config.bool(ClientConfigKey::RandomizeAddresses)?,
+        }))
+        .https_only(!config.bool(ClientConfigKey::AllowHttp)?)
+        .build()
+        .map_err(client_error)
+}
@@ -1,8 +1,22 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use http::header::RETRY_AFTER;
+use http::{HeaderMap, Method, StatusCode};
+use object_store::client::{
+    HttpClient, HttpError, HttpErrorKind, HttpRequest, HttpResponse, HttpService,
+};
 use object_store::{BackoffConfig, RetryConfig};
+use pyo3::exceptions::PyValueError;
 use pyo3::intern;
 use pyo3::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::breaker::{PyRetryCallback, RetryMonitor};
+use crate::check_keys;
+
+/// How long a circuit breaker fails requests fast by default once it opens.
+const DEFAULT_FAIL_FAST_COOLDOWN: Duration = Duration::from_secs(30);
 
 #[derive(Clone, Debug, IntoPyObject, IntoPyObjectRef, PartialEq)]
 pub struct PyBackoffConfig {
@@ -16,6 +30,7 @@ pub struct PyBackoffConfig {
 
 impl<'py> FromPyObject<'py> for PyBackoffConfig {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        check_keys(ob, "backoff", &["init_backoff", "max_backoff", "base"])?;
         let mut backoff_config = BackoffConfig::default();
         let py = ob.py();
         if let Ok(init_backoff) = ob.get_item(intern!(py, "init_backoff")) {
@@ -51,6 +66,7 @@ impl From<BackoffConfig> for PyBackoffConfig {
     }
 }
 
+/// The `retry_config` of a store, as passed from Python.
 #[derive(Clone, Debug, IntoPyObject, IntoPyObjectRef, PartialEq)]
 pub struct PyRetryConfig {
     #[pyo3(item)]
@@ -59,14 +75,110 @@ pub struct PyRetryConfig {
     max_retries: usize,
     #[pyo3(item)]
     retry_timeout: Duration,
+    #[pyo3(item)]
+    respect_retry_after: bool,
+    #[pyo3(item)]
+    throttle_backoff: Option<PyBackoffConfig>,
+    #[pyo3(item)]
+    on_retry: Option<PyRetryCallback>,
+    #[pyo3(item)]
+    fail_fast_after: Option<usize>,
+    #[pyo3(item)]
+    fail_fast_cooldown: Duration,
+    #[pyo3(item)]
+    retry_error_body: bool,
+}
+
+impl PyRetryConfig {
+    /// The policy for throttled responses, if this config changes how they are retried.
+    ///
+    /// Without it, throttled responses are retried like any other server error.
+    pub(crate) fn throttle_policy(&self) -> Option<ThrottleRetryPolicy> {
+        if !self.respect_retry_after && self.throttle_backoff.is_none() {
+            return None;
+        }
+        Some(ThrottleRetryPolicy {
+            backoff: self
+                .throttle_backoff
+                .clone()
+                .unwrap_or_else(|| self.backoff.clone())
+                .into(),
+            max_retries: self.max_retries,
+            retry_timeout: self.retry_timeout,
+            respect_retry_after: self.respect_retry_after,
+            retry_error_body: self.retry_error_body,
+        })
+    }
+}
+
+impl PyRetryConfig {
+    /// The backoff between retries of a store with this config, or with the default config if
+    /// it has none.
+    pub fn backoff(config: Option<&Self>) -> Backoff {
+        let config = config.cloned().map(RetryConfig::from).unwrap_or_default();
+        Backoff::new(&config.backoff)
+    }
+
+    /// The policy for server error responses of a store.
+    pub(crate) fn server_error_policy(config: Option<&Self>) -> ServerErrorRetryPolicy {
+        let retry_error_body = config.is_some_and(|config| config.retry_error_body);
+        let config = config.cloned().map(RetryConfig::from).unwrap_or_default();
+        ServerErrorRetryPolicy {
+            backoff: config.backoff,
+            max_retries: config.max_retries,
+            retry_timeout: config.retry_timeout,
+            retry_error_body,
+        }
+    }
+
+    /// The policy for transport errors of the requests of a store that `object_store` doesn't
+    /// make.
+    pub(crate) fn request_policy(config: Option<&Self>) -> RequestRetryPolicy {
+        let config = config.cloned().map(RetryConfig::from).unwrap_or_default();
+        RequestRetryPolicy {
+            backoff: config.backoff,
+            max_retries: config.max_retries,
+            retry_timeout: config.retry_timeout,
+        }
+    }
+
+    /// The monitor of failed requests, if this config observes them or breaks the circuit.
+    ///
+    /// A store must create a single monitor, shared by all its clients, so that the circuit
+    /// breaker sees every request of the store.
+    pub(crate) fn retry_monitor(&self) -> Option<RetryMonitor> {
+        if self.on_retry.is_none() && self.fail_fast_after.is_none() {
+            return None;
+        }
+        Some(RetryMonitor::new(
+            self.on_retry.clone(),
+            self.fail_fast_after,
+            self.fail_fast_cooldown,
+        ))
+    }
 }
 
 impl<'py> FromPyObject<'py> for PyRetryConfig {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
-        let mut retry_config = RetryConfig::default();
+        check_keys(
+            ob,
+            "retry_config",
+            &[
+                "backoff",
+                "max_retries",
+                "retry_timeout",
+                "respect_retry_after",
+                "throttle_backoff",
+                "on_retry",
+                "fail_fast_after",
+                "fail_fast_cooldown",
+                "retry_error_body",
+            ],
+        )?;
+        let mut retry_config = PyRetryConfig::from(RetryConfig::default());
         let py = ob.py();
         if let Ok(backoff) = ob.get_item(intern!(py, "backoff")) {
-            retry_config.backoff = backoff.extract::<PyBackoffConfig>()?.into();
+            retry_config.backoff = backoff.extract()?;
         }
         if let Ok(max_retries) = ob.get_item(intern!(py, "max_retries")) {
             retry_config.max_retries = max_retries.extract()?;
@@ -74,7 +186,30 @@ impl<'py> FromPyObject<'py> for PyRetryConfig {
         if let Ok(retry_timeout) = ob.get_item(intern!(py, "retry_timeout")) {
             retry_config.retry_timeout = retry_timeout.extract()?;
         }
-        Ok(retry_config.into())
+        if let Ok(respect_retry_after) = ob.get_item(intern!(py, "respect_retry_after")) {
+            retry_config.respect_retry_after = respect_retry_after.extract()?;
+        }
+        if let Ok(throttle_backoff) = ob.get_item(intern!(py, "throttle_backoff")) {
+            retry_config.throttle_backoff = throttle_backoff.extract()?;
+        }
+        if let Ok(on_retry) = ob.get_item(intern!(py, "on_retry")) {
+            retry_config.on_retry = on_retry.extract()?;
+        }
+        if let Ok(fail_fast_after) = ob.get_item(intern!(py, "fail_fast_after")) {
+            retry_config.fail_fast_after = fail_fast_after.extract()?;
+            if retry_config.fail_fast_after == Some(0) {
+                return Err(PyValueError::new_err(
+                    "fail_fast_after must be greater than 0",
+                ));
+            }
+        }
+        if let Ok(fail_fast_cooldown) = ob.get_item(intern!(py, "fail_fast_cooldown")) {
+            retry_config.fail_fast_cooldown = fail_fast_cooldown.extract()?;
+        }
+        if let Ok(retry_error_body) = ob.get_item(intern!(py, "retry_error_body")) {
+            retry_config.retry_error_body = retry_error_body.extract()?;
+        }
+        Ok(retry_config)
     }
 }
 
@@ -94,6 +229,317 @@ impl From<RetryConfig> for PyRetryConfig {
             backoff: value.backoff.into(),
             max_retries: value.max_retries,
             retry_timeout: value.retry_timeout,
+            respect_retry_after: false,
+            throttle_backoff: None,
+            on_retry: None,
+            fail_fast_after: None,
+            fail_fast_cooldown: DEFAULT_FAIL_FAST_COOLDOWN,
+            retry_error_body: false,
+        }
+    }
+}
+
+/// Whether a response status means the server is asking the client to slow down.
+fn is_throttled(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status == StatusCode::SERVICE_UNAVAILABLE
+}
+
+/// The delay requested by a `Retry-After` header, given either in seconds or as an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+            .to_std()
+            .unwrap_or_default(),
+    )
+}
+
+/// Exponential backoff with decorrelated jitter, matching the backoff used by `object_store`.
+pub struct Backoff {
+    config: BackoffConfig,
+    next: f64,
+    rng: StdRng,
+}
+
+impl Backoff {
+    /// A backoff starting at the initial backoff of `config`.
+    pub fn new(config: &BackoffConfig) -> Self {
+        Self {
+            config: config.clone(),
+            next: config.init_backoff.as_secs_f64(),
+            rng: StdRng::from_os_rng(),
+        }
+    }
+
+    /// The duration to wait before the next retry.
+    pub fn next_delay(&mut self) -> Duration {
+        let init = self.config.init_backoff.as_secs_f64();
+        let upper = self.next * self.config.base;
+        let jittered = if upper > init {
+            self.rng.random_range(init..upper)
+        } else {
+            init
+        };
+        let next = jittered.min(self.config.max_backoff.as_secs_f64());
+        Duration::from_secs_f64(std::mem::replace(&mut self.next, next))
+    }
+}
+
+/// A policy retrying throttled (429 and 503) responses with its own backoff, optionally waiting
+/// for as long as the `Retry-After` header of the response asks.
+///
+/// Throttled responses are fully handled here: once the retries are exhausted, the request fails
+/// with a [`ResponseError`], without being retried again.
+#[derive(Debug, Clone)]
+pub(crate) struct ThrottleRetryPolicy {
+    backoff: BackoffConfig,
+    max_retries: usize,
+    retry_timeout: Duration,
+    respect_retry_after: bool,
+    retry_error_body: bool,
+}
+
+impl ThrottleRetryPolicy {
+    /// Wrap `client` so that its throttled responses are retried with this policy.
+    pub(crate) fn wrap(&self, client: HttpClient) -> HttpClient {
+        HttpClient::new(ThrottleRetryService {
+            client,
+            policy: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ThrottleRetryService {
+    client: HttpClient,
+    policy: ThrottleRetryPolicy,
+}
+
+#[async_trait::async_trait]
+impl HttpService for ThrottleRetryService {
+    async fn call(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let start = Instant::now();
+        let mut backoff = Backoff::new(&self.policy.backoff);
+        let mut retries = 0;
+        loop {
+            let response = self.client.execute(req.clone()).await?;
+            let status = response.status();
+            if !is_throttled(status) {
+                return Ok(response);
+            }
+
+            let requested = if self.policy.respect_retry_after {
+                retry_after(response.headers())
+            } else {
+                None
+            };
+            let delay = requested.unwrap_or_else(|| backoff.next_delay());
+            if retries >= self.policy.max_retries
+                || start.elapsed() + delay > self.policy.retry_timeout
+            {
+                let keep_body = self.policy.retry_error_body || status.is_client_error();
+                return Err(ResponseError::give_up(response, retries, true, keep_body).await);
+            }
+            retries += 1;
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// A policy retrying server error (5xx), request timeout (408) and throttled (429) responses,
+/// with the backoff of a [`RetryConfig`].
+///
+/// These responses are fully handled here, as [`ThrottleRetryPolicy`] handles throttled ones:
+/// once the retries are exhausted, the request fails with a [`ResponseError`] with the details of
+/// the last response, without being retried again by `object_store`.
+#[derive(Debug, Clone)]
+pub(crate) struct ServerErrorRetryPolicy {
+    backoff: BackoffConfig,
+    max_retries: usize,
+    retry_timeout: Duration,
+    retry_error_body: bool,
+}
+
+impl ServerErrorRetryPolicy {
+    /// Wrap `client` so that its server error responses are retried with this policy.
+    pub(crate) fn wrap(&self, client: HttpClient) -> HttpClient {
+        HttpClient::new(ServerErrorRetryService {
+            client,
+            policy: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ServerErrorRetryService {
+    client: HttpClient,
+    policy: ServerErrorRetryPolicy,
+}
+
+#[async_trait::async_trait]
+impl HttpService for ServerErrorRetryService {
+    async fn call(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let start = Instant::now();
+        let mut backoff = Backoff::new(&self.policy.backoff);
+        let mut retries = 0;
+        loop {
+            let response = self.client.execute(req.clone()).await?;
+            let status = response.status();
+            if !(status.is_server_error()
+                || status == StatusCode::REQUEST_TIMEOUT
+                || status == StatusCode::TOO_MANY_REQUESTS)
+            {
+                return Ok(response);
+            }
+
+            let delay = backoff.next_delay();
+            if retries >= self.policy.max_retries
+                || start.elapsed() + delay > self.policy.retry_timeout
+            {
+                // Like `object_store`, the bodies of client errors are always kept
+                let keep_body = self.policy.retry_error_body || status.is_client_error();
+                return Err(ResponseError::give_up(response, retries, false, keep_body).await);
+            }
+            retries += 1;
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// The error of a request that got an error response once its retries were exhausted.
+///
+/// This is the source of the error of the request, from which the `status`, `body` and `retries`
+/// attributes of the Python exception are set.
+#[derive(Debug)]
+pub(crate) struct ResponseError {
+    status: StatusCode,
+    body: Option<String>,
+    retries: usize,
+    throttled: bool,
+}
+
+impl ResponseError {
+    /// The error for giving up on a request after `retries` retries, with `response` as its last
+    /// response, whose body is read if `keep_body`.
+    async fn give_up(
+        response: HttpResponse,
+        retries: usize,
+        throttled: bool,
+        keep_body: bool,
+    ) -> HttpError {
+        let status = response.status();
+        let body = if keep_body {
+            match response.into_body().bytes().await {
+                Ok(body) => Some(String::from_utf8_lossy(&body).into_owned()),
+                Err(err) => return err,
+            }
+        } else {
+            None
+        };
+        HttpError::new(
+            HttpErrorKind::Unknown,
+            Self {
+                status,
+                body,
+                retries,
+                throttled,
+            },
+        )
+    }
+
+    pub(crate) fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    pub(crate) fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    pub(crate) fn retries(&self) -> usize {
+        self.retries
+    }
+}
+
+impl std::fmt::Display for ResponseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.throttled {
+            write!(f, "Request was throttled with status {}", self.status)?;
+        } else {
+            write!(f, "Server returned non-2xx status code: {}", self.status)?;
+        }
+        write!(f, ", giving up after {} retries", self.retries)?;
+        if let Some(body) = &self.body {
+            write!(f, ": {}", body)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ResponseError {}
+
+/// A policy retrying requests that fail to connect, and idempotent requests that fail to be sent
+/// or time out, with the backoff of a [`RetryConfig`].
+///
+/// This is for the requests of a store that `object_store` doesn't make, and so doesn't retry
+/// itself. Their error responses are retried by the [`ServerErrorRetryPolicy`] of the client.
+#[derive(Debug, Clone)]
+pub(crate) struct RequestRetryPolicy {
+    backoff: BackoffConfig,
+    max_retries: usize,
+    retry_timeout: Duration,
+}
+
+impl RequestRetryPolicy {
+    /// Wrap `client` so that its requests are retried with this policy.
+    pub(crate) fn wrap(&self, client: HttpClient) -> HttpClient {
+        HttpClient::new(RequestRetryService {
+            client,
+            policy: self.clone(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct RequestRetryService {
+    client: HttpClient,
+    policy: RequestRetryPolicy,
+}
+
+#[async_trait::async_trait]
+impl HttpService for RequestRetryService {
+    async fn call(&self, req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        let idempotent = matches!(
+            *req.method(),
+            Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+        );
+        let start = Instant::now();
+        let mut backoff = Backoff::new(&self.policy.backoff);
+        let mut retries = 0;
+        loop {
+            let err = match self.client.execute(req.clone()).await {
+                Ok(response) => return Ok(response),
+                Err(err) => err,
+            };
+            let retryable = match err.kind() {
+                HttpErrorKind::Connect => true,
+                HttpErrorKind::Request | HttpErrorKind::Timeout | HttpErrorKind::Interrupted => {
+                    idempotent
+                }
+                _ => false,
+            };
+            let delay = backoff.next_delay();
+            if !retryable
+                || retries >= self.policy.max_retries
+                || start.elapsed() + delay > self.policy.retry_timeout
+            {
+                return Err(err);
+            }
+            retries += 1;
+            tokio::time::sleep(delay).await;
         }
     }
 }
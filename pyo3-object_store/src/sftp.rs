@@ -0,0 +1,973 @@
+//! An object store over SSH, using the SFTP protocol.
+//!
+//! SFTP servers are accessed through libssh2, whose calls block. Like `LocalFileSystem`, each call
+//! is run on a blocking thread.
+
+use std::collections::VecDeque;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use object_store::path::Path;
+use object_store::{
+    Attributes, GetOptions, GetResult, GetResultPayload, ListResult, MultipartUpload, ObjectMeta,
+    ObjectStore, PutMode, PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
+    UploadPart,
+};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple, PyType};
+use pyo3::{intern, IntoPyObjectExt, PyTypeInfo};
+use ssh2::{
+    CheckResult, ErrorCode, File, FileStat, KnownHostFileKind, OpenFlags, OpenType, Session, Sftp,
+};
+
+use crate::error::PyObjectStoreResult;
+use crate::staging::{is_staging, staging_path};
+use crate::token::{encode_token, store_from_token};
+use crate::{PyClientOptions, PyUrl};
+
+const STORE: &str = "SFTP";
+
+/// How long connecting to the server may take, unless `connect_timeout` is set. This is the
+/// default of `object_store` for the other stores.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Status codes of SFTP responses
+const FX_NO_SUCH_FILE: i32 = 2;
+const FX_PERMISSION_DENIED: i32 = 3;
+const FX_NO_SUCH_PATH: i32 = 10;
+
+/// The size of the chunks that objects are read in.
+const CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+
+fn generic_error(err: impl std::error::Error + Send + Sync + 'static) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: Box::new(err),
+    }
+}
+
+fn sftp_error(err: ssh2::Error, location: &Path) -> object_store::Error {
+    match err.code() {
+        ErrorCode::SFTP(FX_NO_SUCH_FILE | FX_NO_SUCH_PATH) => object_store::Error::NotFound {
+            path: location.to_string(),
+            source: Box::new(err),
+        },
+        ErrorCode::SFTP(FX_PERMISSION_DENIED) => object_store::Error::PermissionDenied {
+            path: location.to_string(),
+            source: Box::new(err),
+        },
+        _ => generic_error(err),
+    }
+}
+
+fn already_exists(location: &Path) -> object_store::Error {
+    object_store::Error::AlreadyExists {
+        path: location.to_string(),
+        source: "Object already exists".into(),
+    }
+}
+
+fn is_not_found(err: &ssh2::Error) -> bool {
+    matches!(
+        err.code(),
+        ErrorCode::SFTP(FX_NO_SUCH_FILE | FX_NO_SUCH_PATH)
+    )
+}
+
+fn remote(path: &str) -> &std::path::Path {
+    std::path::Path::new(path)
+}
+
+fn object_meta(location: Path, stat: &FileStat) -> ObjectMeta {
+    let mtime = stat.mtime.unwrap_or(0);
+    let size = stat.size.unwrap_or(0);
+    ObjectMeta {
+        location,
+        last_modified: DateTime::<Utc>::from_timestamp(mtime as i64, 0).unwrap_or_default(),
+        size,
+        e_tag: Some(format!("{:x}-{:x}", mtime, size)),
+        version: None,
+    }
+}
+
+/// Create the missing parent directories of `path`.
+fn create_parents(sftp: &Sftp, path: &str) -> Result<(), ssh2::Error> {
+    let Some((parent, _)) = path.rsplit_once('/') else {
+        return Ok(());
+    };
+    if parent.is_empty() || sftp.stat(remote(parent)).is_ok() {
+        return Ok(());
+    }
+    create_parents(sftp, parent)?;
+    match sftp.mkdir(remote(parent), 0o755) {
+        // The directory may have been created by another client in the meantime
+        Err(_) if sftp.stat(remote(parent)).is_ok() => Ok(()),
+        result => result,
+    }
+}
+
+/// Open `path` for writing, creating its parent directories if needed.
+fn open_for_write(sftp: &Sftp, path: &str, flags: OpenFlags) -> Result<File, ssh2::Error> {
+    let open = || {
+        sftp.open_mode(
+            remote(path),
+            OpenFlags::WRITE | OpenFlags::CREATE | flags,
+            0o644,
+            OpenType::File,
+        )
+    };
+    match open() {
+        Err(err) if is_not_found(&err) => {
+            create_parents(sftp, path)?;
+            open()
+        }
+        result => result,
+    }
+}
+
+/// Move `from` to `to`, replacing any file at `to`.
+///
+/// Servers implementing version 3 of the protocol, such as OpenSSH, don't rename onto an existing
+/// file, in which case it is removed first.
+fn replace(sftp: &Sftp, from: &str, to: &str) -> Result<(), ssh2::Error> {
+    create_parents(sftp, to)?;
+    match sftp.rename(remote(from), remote(to), None) {
+        Err(_) if sftp.stat(remote(to)).is_ok() => {
+            sftp.unlink(remote(to))?;
+            sftp.rename(remote(from), remote(to), None)
+        }
+        result => result,
+    }
+}
+
+/// The configuration of an [`SftpStore`].
+#[derive(Clone, PartialEq)]
+struct SFTPConfig {
+    host: String,
+    username: String,
+    port: u16,
+    password: Option<String>,
+    key_file: Option<PathBuf>,
+    passphrase: Option<String>,
+    /// The `known_hosts` file, or `None` for the one of the user
+    known_hosts: Option<PathBuf>,
+    insecure_skip_host_key_check: bool,
+    prefix: Option<String>,
+    client_options: Option<PyClientOptions>,
+    connect_timeout: Duration,
+}
+
+impl std::fmt::Debug for SFTPConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Leave out the password and passphrase
+        f.debug_struct("SFTPConfig")
+            .field("host", &self.host)
+            .field("username", &self.username)
+            .field("port", &self.port)
+            .field("key_file", &self.key_file)
+            .field("known_hosts", &self.known_hosts)
+            .field(
+                "insecure_skip_host_key_check",
+                &self.insecure_skip_host_key_check,
+            )
+            .field("prefix", &self.prefix)
+            .field("connect_timeout", &self.connect_timeout)
+            .finish()
+    }
+}
+
+impl SFTPConfig {
+    /// The config without the password or passphrase, for `to_token`.
+    fn without_secrets(&self) -> Self {
+        Self {
+            password: None,
+            passphrase: None,
+            client_options: self
+                .client_options
+                .as_ref()
+                .map(PyClientOptions::without_secrets),
+            ..self.clone()
+        }
+    }
+
+    fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
+        let args =
+            PyTuple::new(py, vec![self.host.clone(), self.username.clone()])?.into_py_any(py)?;
+        let kwargs = PyDict::new(py);
+        kwargs.set_item(intern!(py, "port"), self.port)?;
+        if let Some(password) = &self.password {
+            kwargs.set_item(intern!(py, "password"), password)?;
+        }
+        if let Some(key_file) = &self.key_file {
+            kwargs.set_item(intern!(py, "key_file"), key_file)?;
+        }
+        if let Some(passphrase) = &self.passphrase {
+            kwargs.set_item(intern!(py, "passphrase"), passphrase)?;
+        }
+        if let Some(known_hosts) = &self.known_hosts {
+            kwargs.set_item(intern!(py, "known_hosts"), known_hosts)?;
+        }
+        if self.insecure_skip_host_key_check {
+            kwargs.set_item(intern!(py, "insecure_skip_host_key_check"), true)?;
+        }
+        if let Some(prefix) = &self.prefix {
+            kwargs.set_item(intern!(py, "prefix"), prefix)?;
+        }
+        if let Some(client_options) = &self.client_options {
+            kwargs.set_item(intern!(py, "client_options"), client_options)?;
+        }
+        PyTuple::new(py, [args, kwargs.into_py_any(py)?])?.into_py_any(py)
+    }
+
+    /// The path on the server of the object or directory at `location`.
+    ///
+    /// Without a prefix, paths are relative to the directory the server starts sessions in,
+    /// usually the home directory of the user.
+    fn remote_path(&self, location: &Path) -> String {
+        let root = self.prefix.as_deref().unwrap_or(".").trim_end_matches('/');
+        match (root, location.as_ref()) {
+            ("", "") => "/".to_string(),
+            (root, "") => root.to_string(),
+            (root, location) => format!("{}/{}", root, location),
+        }
+    }
+
+    fn url(&self) -> String {
+        let prefix = self.prefix.as_deref().unwrap_or("");
+        let prefix = if prefix.is_empty() || prefix.starts_with('/') {
+            prefix.to_string()
+        } else {
+            format!("/~/{}", prefix)
+        };
+        format!(
+            "sftp://{}@{}:{}{}",
+            self.username, self.host, self.port, prefix
+        )
+    }
+
+    /// The `known_hosts` file that the key of the server is checked against, by default the one
+    /// of the user.
+    fn known_hosts_file(&self) -> Result<PathBuf> {
+        if let Some(known_hosts) = &self.known_hosts {
+            return Ok(known_hosts.clone());
+        }
+        let home = std::env::var_os("HOME")
+            .or_else(|| std::env::var_os("USERPROFILE"))
+            .filter(|home| !home.is_empty())
+            .ok_or_else(|| {
+                self.connection_error(
+                    "The home directory of the user, with the default known hosts file, is \
+                     unknown. Pass known_hosts to check the key of the server against.",
+                )
+            })?;
+        Ok(PathBuf::from(home).join(".ssh").join("known_hosts"))
+    }
+
+    /// Check the key of the server against the `known_hosts` file.
+    fn check_host_key(&self, session: &Session) -> Result<()> {
+        let known_hosts = self.known_hosts_file()?;
+        let mut hosts = session.known_hosts().map_err(generic_error)?;
+        hosts
+            .read_file(&known_hosts, KnownHostFileKind::OpenSSH)
+            .map_err(|err| {
+                self.connection_error(format!(
+                    "Reading the known hosts file {}: {}",
+                    known_hosts.display(),
+                    err
+                ))
+            })?;
+        let (key, _) = session
+            .host_key()
+            .ok_or_else(|| self.connection_error("The server did not send a host key"))?;
+        match hosts.check_port(&self.host, self.port, key) {
+            CheckResult::Match => Ok(()),
+            CheckResult::NotFound => Err(self.connection_error(format!(
+                "The host is not in the known hosts file {}",
+                known_hosts.display()
+            ))),
+            CheckResult::Mismatch => Err(self.connection_error(format!(
+                "The host key does not match the key in the known hosts file {}",
+                known_hosts.display()
+            ))),
+            CheckResult::Failure => Err(self.connection_error("The host key could not be checked")),
+        }
+    }
+
+    fn connection_error(&self, msg: impl std::fmt::Display) -> object_store::Error {
+        object_store::Error::Generic {
+            store: STORE,
+            source: format!("Connecting to {}: {}", self.url(), msg).into(),
+        }
+    }
+}
+
+/// An open SFTP session.
+struct Connection {
+    // Kept so that the session is closed with the SFTP channel
+    _session: Session,
+    sftp: Sftp,
+}
+
+impl Connection {
+    fn open(config: &SFTPConfig) -> Result<Self> {
+        let tcp = Self::connect(config).map_err(|err| config.connection_error(err))?;
+        let mut session = Session::new().map_err(generic_error)?;
+        session.set_tcp_stream(tcp);
+        session
+            .handshake()
+            .map_err(|err| config.connection_error(err))?;
+        if !config.insecure_skip_host_key_check {
+            config.check_host_key(&session)?;
+        }
+
+        let auth = if let Some(key_file) = &config.key_file {
+            session.userauth_pubkey_file(
+                &config.username,
+                None,
+                key_file,
+                config.passphrase.as_deref(),
+            )
+        } else if let Some(password) = &config.password {
+            session.userauth_password(&config.username, password)
+        } else {
+            // Fall back to the keys of a running SSH agent
+            session.userauth_agent(&config.username)
+        };
+        auth.map_err(|err| object_store::Error::Unauthenticated {
+            path: config.url(),
+            source: Box::new(err),
+        })?;
+
+        let sftp = session.sftp().map_err(generic_error)?;
+        Ok(Self {
+            _session: session,
+            sftp,
+        })
+    }
+
+    /// Connect to the first address of the host that accepts a connection within the connect
+    /// timeout.
+    fn connect(config: &SFTPConfig) -> std::io::Result<TcpStream> {
+        let mut last_err = None;
+        for addr in (config.host.as_str(), config.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, config.connect_timeout) {
+                Ok(tcp) => return Ok(tcp),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                "The host has no addresses to connect to",
+            )
+        }))
+    }
+}
+
+/// An [`ObjectStore`] over SSH, using the SFTP protocol.
+///
+/// The store opens a single session on its first call, over which calls are made one at a time:
+/// the session is behind a mutex, so concurrent calls wait for each other rather than running in
+/// parallel. The session is reopened for the next call after an unexpected error, which may be
+/// caused by a closed connection.
+#[derive(Clone)]
+pub struct SftpStore {
+    config: Arc<SFTPConfig>,
+    connection: Arc<Mutex<Option<Connection>>>,
+}
+
+impl std::fmt::Debug for SftpStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SftpStore")
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
+impl std::fmt::Display for SftpStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SftpStore({})", self.config.url())
+    }
+}
+
+impl SftpStore {
+    fn new(config: SFTPConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            connection: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Run `f` with the SFTP session of the store on a blocking thread, opening the session if
+    /// needed.
+    async fn run<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&SFTPConfig, &Sftp) -> Result<T> + Send + 'static,
+    {
+        let config = Arc::clone(&self.config);
+        let connection = Arc::clone(&self.connection);
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection.lock().unwrap();
+            let open = match connection.take() {
+                Some(open) => open,
+                None => Connection::open(&config)?,
+            };
+            let result = f(&config, &open.sftp);
+            // Errors about an object, such as `NotFound`, leave the session usable
+            if !matches!(result, Err(object_store::Error::Generic { .. })) {
+                *connection = Some(open);
+            }
+            result
+        })
+        .await
+        .map_err(generic_error)?
+    }
+
+    /// The objects and directories directly under `prefix`.
+    async fn read_dir(&self, prefix: Path) -> Result<Vec<(Path, FileStat)>> {
+        self.run(move |config, sftp| {
+            let entries = match sftp.readdir(remote(&config.remote_path(&prefix))) {
+                Ok(entries) => entries,
+                // A prefix that doesn't exist has no entries
+                Err(err) if is_not_found(&err) => return Ok(vec![]),
+                Err(err) => return Err(sftp_error(err, &prefix)),
+            };
+            Ok(entries
+                .into_iter()
+                .filter_map(|(path, stat)| {
+                    let name = path.file_name()?.to_str()?;
+                    (!is_staging(name)).then(|| (prefix.child(name), stat))
+                })
+                .collect())
+        })
+        .await
+    }
+
+    /// Read `range` of the object at `location`, in chunks of at most [`CHUNK_SIZE`].
+    fn read_range(&self, location: Path, range: Range<u64>) -> BoxStream<'static, Result<Bytes>> {
+        let store = self.clone();
+        let end = range.end;
+        futures::stream::try_unfold(range.start, move |position| {
+            let store = store.clone();
+            let location = location.clone();
+            async move {
+                if position >= end {
+                    return Ok(None);
+                }
+                let len = (end - position).min(CHUNK_SIZE);
+                let chunk = store
+                    .run(move |config, sftp| {
+                        let mut file = sftp
+                            .open(remote(&config.remote_path(&location)))
+                            .map_err(|err| sftp_error(err, &location))?;
+                        file.seek(SeekFrom::Start(position))
+                            .map_err(generic_error)?;
+                        let mut buf = vec![0; len as usize];
+                        file.read_exact(&mut buf).map_err(generic_error)?;
+                        Ok(Bytes::from(buf))
+                    })
+                    .await?;
+                Ok::<_, object_store::Error>(Some((chunk, position + len)))
+            }
+        })
+        .boxed()
+    }
+
+    /// Copy the object at `from` to `to`, through the client.
+    async fn copy_opts(&self, from: &Path, to: &Path, overwrite: bool) -> Result<()> {
+        let from = from.clone();
+        let to = to.clone();
+        self.run(move |config, sftp| {
+            let mut source = sftp
+                .open(remote(&config.remote_path(&from)))
+                .map_err(|err| sftp_error(err, &from))?;
+            let path = config.remote_path(&to);
+            if overwrite {
+                let staging = staging_path(&path);
+                let mut dest = open_for_write(sftp, &staging, OpenFlags::TRUNCATE)
+                    .map_err(|err| sftp_error(err, &to))?;
+                let copied = std::io::copy(&mut source, &mut dest).map_err(generic_error);
+                drop(dest);
+                if let Err(err) = copied {
+                    let _ = sftp.unlink(remote(&staging));
+                    return Err(err);
+                }
+                replace(sftp, &staging, &path).map_err(|err| sftp_error(err, &to))
+            } else {
+                let mut dest = match open_for_write(sftp, &path, OpenFlags::EXCLUSIVE) {
+                    Ok(dest) => dest,
+                    Err(_) if sftp.stat(remote(&path)).is_ok() => return Err(already_exists(&to)),
+                    Err(err) => return Err(sftp_error(err, &to)),
+                };
+                std::io::copy(&mut source, &mut dest).map_err(generic_error)?;
+                Ok(())
+            }
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for SftpStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        if matches!(opts.mode, PutMode::Update(_)) {
+            return Err(object_store::Error::NotImplemented);
+        }
+        let location = location.clone();
+        self.run(move |config, sftp| {
+            let path = config.remote_path(&location);
+            let write = |file: &mut File| {
+                payload
+                    .iter()
+                    .try_for_each(|chunk| file.write_all(chunk))
+                    .map_err(generic_error)
+            };
+            if matches!(opts.mode, PutMode::Create) {
+                let mut file = match open_for_write(sftp, &path, OpenFlags::EXCLUSIVE) {
+                    Ok(file) => file,
+                    Err(_) if sftp.stat(remote(&path)).is_ok() => {
+                        return Err(already_exists(&location))
+                    }
+                    Err(err) => return Err(sftp_error(err, &location)),
+                };
+                write(&mut file)?;
+            } else {
+                // Written to a staging file first, so that readers never see a partial object
+                let staging = staging_path(&path);
+                let mut file = open_for_write(sftp, &staging, OpenFlags::TRUNCATE)
+                    .map_err(|err| sftp_error(err, &location))?;
+                let written = write(&mut file);
+                drop(file);
+                if let Err(err) = written {
+                    let _ = sftp.unlink(remote(&staging));
+                    return Err(err);
+                }
+                replace(sftp, &staging, &path).map_err(|err| sftp_error(err, &location))?;
+            }
+            let stat = sftp
+                .stat(remote(&path))
+                .map_err(|err| sftp_error(err, &location))?;
+            Ok(PutResult {
+                e_tag: object_meta(location, &stat).e_tag,
+                version: None,
+            })
+        })
+        .await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        _opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let target = location.clone();
+        let (path, staging) = self
+            .run(move |config, sftp| {
+                let path = config.remote_path(&target);
+                let staging = staging_path(&path);
+                open_for_write(sftp, &staging, OpenFlags::TRUNCATE)
+                    .map_err(|err| sftp_error(err, &target))?;
+                Ok((path, staging))
+            })
+            .await?;
+        Ok(Box::new(SftpUpload {
+            store: self.clone(),
+            location: location.clone(),
+            path,
+            staging,
+            offset: 0,
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        let location = location.clone();
+        let meta = self
+            .run(move |config, sftp| {
+                let stat = sftp
+                    .stat(remote(&config.remote_path(&location)))
+                    .map_err(|err| sftp_error(err, &location))?;
+                if stat.is_dir() {
+                    return Err(object_store::Error::NotFound {
+                        path: location.to_string(),
+                        source: "Path is a directory".into(),
+                    });
+                }
+                Ok(object_meta(location, &stat))
+            })
+            .await?;
+        options.check_preconditions(&meta)?;
+
+        let range = match &options.range {
+            Some(range) => range.as_range(meta.size).map_err(generic_error)?,
+            None => 0..meta.size,
+        };
+        let stream = if options.head {
+            futures::stream::empty().boxed()
+        } else {
+            self.read_range(meta.location.clone(), range.clone())
+        };
+        Ok(GetResult {
+            payload: GetResultPayload::Stream(stream),
+            meta,
+            range,
+            attributes: Attributes::default(),
+        })
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        let location = location.clone();
+        self.run(move |config, sftp| {
+            sftp.unlink(remote(&config.remote_path(&location)))
+                .map_err(|err| sftp_error(err, &location))
+        })
+        .await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        let pending = VecDeque::from([prefix.cloned().unwrap_or_default()]);
+        futures::stream::try_unfold((self.clone(), pending), |(store, mut pending)| async move {
+            let Some(prefix) = pending.pop_front() else {
+                return Ok(None);
+            };
+            let mut objects = vec![];
+            for (location, stat) in store.read_dir(prefix).await? {
+                if stat.is_dir() {
+                    pending.push_back(location);
+                } else {
+                    objects.push(Ok(object_meta(location, &stat)));
+                }
+            }
+            let objects = futures::stream::iter(objects);
+            Ok::<_, object_store::Error>(Some((objects, (store, pending))))
+        })
+        .try_flatten()
+        .boxed()
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut result = ListResult {
+            common_prefixes: vec![],
+            objects: vec![],
+        };
+        for (location, stat) in self.read_dir(prefix.cloned().unwrap_or_default()).await? {
+            if stat.is_dir() {
+                result.common_prefixes.push(location);
+            } else {
+                result.objects.push(object_meta(location, &stat));
+            }
+        }
+        Ok(result)
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, true).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.copy_opts(from, to, false).await
+    }
+
+    async fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let from = from.clone();
+        let to = to.clone();
+        self.run(move |config, sftp| {
+            replace(sftp, &config.remote_path(&from), &config.remote_path(&to))
+                .map_err(|err| sftp_error(err, &from))
+        })
+        .await
+    }
+
+    async fn rename_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        let from = from.clone();
+        let to = to.clone();
+        self.run(move |config, sftp| {
+            let path = config.remote_path(&to);
+            if sftp.stat(remote(&path)).is_ok() {
+                return Err(already_exists(&to));
+            }
+            create_parents(sftp, &path).map_err(|err| sftp_error(err, &to))?;
+            sftp.rename(remote(&config.remote_path(&from)), remote(&path), None)
+                .map_err(|err| sftp_error(err, &from))
+        })
+        .await
+    }
+}
+
+/// A multipart upload to an [`SftpStore`], whose parts are written to a staging file that is
+/// moved into place on completion.
+#[derive(Debug)]
+struct SftpUpload {
+    store: SftpStore,
+    location: Path,
+    path: String,
+    staging: String,
+    /// The offset in the staging file of the next part.
+    offset: u64,
+}
+
+#[async_trait::async_trait]
+impl MultipartUpload for SftpUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let offset = self.offset;
+        self.offset += data.content_length() as u64;
+        let store = self.store.clone();
+        let location = self.location.clone();
+        let staging = self.staging.clone();
+        Box::pin(async move {
+            store
+                .run(move |_, sftp| {
+                    let mut file = sftp
+                        .open_mode(remote(&staging), OpenFlags::WRITE, 0o644, OpenType::File)
+                        .map_err(|err| sftp_error(err, &location))?;
+                    file.seek(SeekFrom::Start(offset)).map_err(generic_error)?;
+                    data.iter()
+                        .try_for_each(|chunk| file.write_all(chunk))
+                        .map_err(generic_error)
+                })
+                .await
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let location = self.location.clone();
+        let path = self.path.clone();
+        let staging = self.staging.clone();
+        self.store
+            .run(move |_, sftp| {
+                replace(sftp, &staging, &path).map_err(|err| sftp_error(err, &location))?;
+                let stat = sftp
+                    .stat(remote(&path))
+                    .map_err(|err| sftp_error(err, &location))?;
+                Ok(PutResult {
+                    e_tag: object_meta(location, &stat).e_tag,
+                    version: None,
+                })
+            })
+            .await
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        let location = self.location.clone();
+        let staging = self.staging.clone();
+        self.store
+            .run(move |_, sftp| {
+                sftp.unlink(remote(&staging))
+                    .map_err(|err| sftp_error(err, &location))
+            })
+            .await
+    }
+}
+
+/// A Python-facing wrapper around an [`SftpStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "SFTPStore", frozen, subclass)]
+pub struct PySFTPStore {
+    store: Arc<SftpStore>,
+}
+
+impl AsRef<Arc<SftpStore>> for PySFTPStore {
+    fn as_ref(&self) -> &Arc<SftpStore> {
+        &self.store
+    }
+}
+
+impl PySFTPStore {
+    /// Consume self and return the underlying [`SftpStore`].
+    pub fn into_inner(self) -> Arc<SftpStore> {
+        self.store
+    }
+
+    fn config(&self) -> &SFTPConfig {
+        &self.store.config
+    }
+}
+
+#[pymethods]
+impl PySFTPStore {
+    #[new]
+    #[pyo3(signature = (host, username, *, port=22, password=None, key_file=None, passphrase=None, known_hosts=None, insecure_skip_host_key_check=false, prefix=None, client_options=None))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        host: String,
+        username: String,
+        port: u16,
+        password: Option<String>,
+        key_file: Option<PathBuf>,
+        passphrase: Option<String>,
+        known_hosts: Option<PathBuf>,
+        insecure_skip_host_key_check: bool,
+        prefix: Option<String>,
+        client_options: Option<PyClientOptions>,
+    ) -> PyObjectStoreResult<Self> {
+        if host.is_empty() {
+            return Err(PyValueError::new_err("host must not be empty").into());
+        }
+        if username.is_empty() {
+            return Err(PyValueError::new_err("username must not be empty").into());
+        }
+        if known_hosts.is_some() && insecure_skip_host_key_check {
+            return Err(PyValueError::new_err(
+                "known_hosts can't be given with insecure_skip_host_key_check",
+            )
+            .into());
+        }
+        let connect_timeout = match &client_options {
+            Some(client_options) => client_options.connect_timeout()?,
+            None => None,
+        }
+        .unwrap_or(DEFAULT_CONNECT_TIMEOUT);
+        Ok(Self {
+            store: Arc::new(SftpStore::new(SFTPConfig {
+                host,
+                username,
+                port,
+                password,
+                key_file,
+                passphrase,
+                known_hosts,
+                insecure_skip_host_key_check,
+                prefix,
+                client_options,
+                connect_timeout,
+            })),
+        })
+    }
+
+    #[classmethod]
+    #[pyo3(signature = (url, *, password=None, key_file=None, passphrase=None, known_hosts=None, insecure_skip_host_key_check=false, client_options=None))]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn from_url(
+        cls: &Bound<PyType>,
+        url: PyUrl,
+        password: Option<String>,
+        key_file: Option<PathBuf>,
+        passphrase: Option<String>,
+        known_hosts: Option<PathBuf>,
+        insecure_skip_host_key_check: bool,
+        client_options: Option<PyClientOptions>,
+    ) -> PyObjectStoreResult<PyObject> {
+        let url = url.into_inner();
+        if url.scheme() != "sftp" {
+            return Err(PyValueError::new_err("Not an `sftp://` URL").into());
+        }
+        let decode = |value: &str| {
+            percent_encoding::percent_decode_str(value)
+                .decode_utf8()
+                .map(|value| value.into_owned())
+                .map_err(|err| PyValueError::new_err(err.to_string()))
+        };
+        let host = url
+            .host_str()
+            .ok_or_else(|| PyValueError::new_err("The URL has no host"))?;
+        let username = decode(url.username())?;
+        let password = match (password, url.password()) {
+            (Some(password), _) => Some(password),
+            (None, Some(password)) => Some(decode(password)?),
+            (None, None) => None,
+        };
+        // As with curl, paths are absolute, unless they start with `/~/`, in which case they are
+        // relative to the home directory
+        let path = decode(url.path())?;
+        let prefix = match path.as_str() {
+            "" | "/~" | "/~/" => None,
+            path => Some(path.strip_prefix("/~/").unwrap_or(path).to_string()),
+        };
+
+        // Note: we pass **back** through Python so that if cls is a subclass, we instantiate the
+        // subclass
+        let kwargs = PyDict::new(cls.py());
+        kwargs.set_item("host", host)?;
+        kwargs.set_item("username", username)?;
+        kwargs.set_item("port", url.port().unwrap_or(22))?;
+        kwargs.set_item("password", password)?;
+        kwargs.set_item("key_file", key_file)?;
+        kwargs.set_item("passphrase", passphrase)?;
+        kwargs.set_item("known_hosts", known_hosts)?;
+        kwargs.set_item("insecure_skip_host_key_check", insecure_skip_host_key_check)?;
+        kwargs.set_item("prefix", prefix)?;
+        kwargs.set_item("client_options", client_options)?;
+        Ok(cls.call((), Some(&kwargs))?.unbind())
+    }
+
+    fn __eq__(&self, other: &Bound<PyAny>) -> bool {
+        // Ensure we never error on __eq__ by returning false if the other object is not the same
+        // type
+        other
+            .downcast::<PySFTPStore>()
+            .map(|other| self.config() == other.get().config())
+            .unwrap_or(false)
+    }
+
+    fn __getnewargs_ex__(&self, py: Python) -> PyResult<PyObject> {
+        self.config().__getnewargs_ex__(py)
+    }
+
+    #[pyo3(signature = (*, allow_secrets=false))]
+    fn to_token(&self, py: Python, allow_secrets: bool) -> PyResult<String> {
+        let config = if allow_secrets {
+            self.config().clone()
+        } else {
+            self.config().without_secrets()
+        };
+        encode_token(py, Self::NAME, config.__getnewargs_ex__(py)?)
+    }
+
+    #[classmethod]
+    fn from_token<'py>(cls: &Bound<'py, PyType>, token: &str) -> PyResult<Bound<'py, PyAny>> {
+        store_from_token(cls, token)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SFTPStore(\"{}\")", self.config().url())
+    }
+
+    #[getter]
+    fn host(&self) -> &str {
+        &self.config().host
+    }
+
+    #[getter]
+    fn username(&self) -> &str {
+        &self.config().username
+    }
+
+    #[getter]
+    fn port(&self) -> u16 {
+        self.config().port
+    }
+
+    #[getter]
+    fn known_hosts(&self) -> Option<PathBuf> {
+        self.config().known_hosts.clone()
+    }
+
+    #[getter]
+    fn insecure_skip_host_key_check(&self) -> bool {
+        self.config().insecure_skip_host_key_check
+    }
+
+    #[getter]
+    fn prefix(&self) -> Option<&str> {
+        self.config().prefix.as_deref()
+    }
+
+    #[getter]
+    fn client_options(&self) -> Option<PyClientOptions> {
+        self.config().client_options.clone()
+    }
+}
@@ -7,11 +7,12 @@ use pyo3::types::{PyDict, PyType};
 use pyo3::{intern, IntoPyObjectExt};
 
 use crate::error::GenericError;
+use crate::limit::PyLimitConfig;
 use crate::retry::PyRetryConfig;
 use crate::url::PyUrl;
 use crate::{
     PyAzureStore, PyClientOptions, PyGCSStore, PyHttpStore, PyLocalStore, PyMemoryStore,
-    PyObjectStoreResult, PyS3Store,
+    PyObjectStoreResult, PyRequestTags, PyResolver, PyS3Store, SymlinkPolicy,
 };
 
 /// Simple construction of stores by url.
@@ -19,17 +20,35 @@ use crate::{
 // AWS/Azure/Google config keys could overlap. And so we don't want to accidentally parse a config
 // as an AWS config before knowing that the URL scheme is AWS.
 #[pyfunction]
-#[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, credential_provider=None, **kwargs))]
+#[pyo3(signature = (url, *, config=None, client_options=None, retry_config=None, limit_config=None, compatibility=None, credential_provider=None, resolver=None, request_tags=None, **kwargs))]
+#[allow(clippy::too_many_arguments)]
 pub fn from_url(
     py: Python,
     url: PyUrl,
     config: Option<Bound<PyAny>>,
     client_options: Option<PyClientOptions>,
     retry_config: Option<PyRetryConfig>,
+    limit_config: Option<PyLimitConfig>,
+    compatibility: Option<Bound<PyAny>>,
     credential_provider: Option<Bound<PyAny>>,
+    resolver: Option<PyResolver>,
+    request_tags: Option<PyRequestTags>,
     kwargs: Option<Bound<PyAny>>,
 ) -> PyObjectStoreResult<PyObject> {
     let (scheme, _) = ObjectStoreScheme::parse(url.as_ref()).map_err(object_store::Error::from)?;
+    // With a compatibility preset, an HTTP URL is the path-style URL of an S3-compatible service
+    let scheme = match scheme {
+        ObjectStoreScheme::Http if compatibility.is_some() => ObjectStoreScheme::AmazonS3,
+        ObjectStoreScheme::AmazonS3 => scheme,
+        _ if compatibility.is_some() => {
+            return Err(GenericError::new_err(format!(
+                "compatibility can only be passed for S3 URLs, got scheme {:?}",
+                scheme
+            ))
+            .into());
+        }
+        scheme => scheme,
+    };
     match scheme {
         ObjectStoreScheme::AmazonS3 => {
             let store = PyS3Store::from_url(
@@ -38,7 +57,12 @@ pub fn from_url(
                 config.map(|x| x.extract()).transpose()?,
                 client_options,
                 retry_config,
+                limit_config,
+                compatibility.map(|x| x.extract()).transpose()?,
                 credential_provider.map(|x| x.extract()).transpose()?,
+                resolver,
+                request_tags,
+                None,
                 kwargs.map(|x| x.extract()).transpose()?,
             )?;
             Ok(store.into_py_any(py)?)
@@ -50,7 +74,10 @@ pub fn from_url(
                 config.map(|x| x.extract()).transpose()?,
                 client_options,
                 retry_config,
+                limit_config,
                 credential_provider.map(|x| x.extract()).transpose()?,
+                resolver,
+                request_tags,
                 kwargs.map(|x| x.extract()).transpose()?,
             )?;
             Ok(store.into_py_any(py)?)
@@ -62,7 +89,10 @@ pub fn from_url(
                 config.map(|x| x.extract()).transpose()?,
                 client_options,
                 retry_config,
+                limit_config,
                 credential_provider.map(|x| x.extract()).transpose()?,
+                resolver,
+                request_tags,
                 kwargs.map(|x| x.extract()).transpose()?,
             )?;
             Ok(store.into_py_any(py)?)
@@ -75,12 +105,19 @@ pub fn from_url(
                 url,
                 client_options,
                 retry_config,
+                limit_config,
+                resolver,
+                request_tags,
+                false,
             )?;
             Ok(store.into_py_any(py)?)
         }
         ObjectStoreScheme::Local => {
             let mut automatic_cleanup = false;
             let mut mkdir = false;
+            let mut symlinks = SymlinkPolicy::default();
+            let mut direct_io = false;
+            let mut preallocate = false;
             if let Some(kwargs) = kwargs {
                 let kwargs = kwargs.extract::<Bound<PyDict>>()?;
                 if let Some(val) = kwargs.get_item(intern!(py, "automatic_cleanup"))? {
@@ -89,6 +126,15 @@ pub fn from_url(
                 if let Some(val) = kwargs.get_item(intern!(py, "mkdir"))? {
                     mkdir = val.extract()?;
                 }
+                if let Some(val) = kwargs.get_item(intern!(py, "symlinks"))? {
+                    symlinks = val.extract()?;
+                }
+                if let Some(val) = kwargs.get_item(intern!(py, "direct_io"))? {
+                    direct_io = val.extract()?;
+                }
+                if let Some(val) = kwargs.get_item(intern!(py, "preallocate"))? {
+                    preallocate = val.extract()?;
+                }
             }
 
             let store = PyLocalStore::from_url(
@@ -96,6 +142,9 @@ pub fn from_url(
                 url,
                 automatic_cleanup,
                 mkdir,
+                symlinks,
+                direct_io,
+                preallocate,
             )?;
             Ok(store.into_py_any(py)?)
         }
@@ -0,0 +1,28 @@
+//! Staging files, which stores without atomic writes write objects to before moving them into
+//! place.
+//!
+//! As with `LocalFileSystem`, the name of a staging file is the path of its object followed by `#`
+//! and digits, and such files are left out of listings.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A counter for the names of staging files, unique within this process.
+static STAGING_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Whether `name` is the name of a staging file.
+pub(crate) fn is_staging(name: &str) -> bool {
+    match name.rsplit_once('#') {
+        Some((_, suffix)) => !suffix.is_empty() && suffix.bytes().all(|b| b.is_ascii_digit()),
+        None => false,
+    }
+}
+
+/// The path of a new staging file for the object at `path`.
+pub(crate) fn staging_path(path: &str) -> String {
+    format!(
+        "{}#{}{}",
+        path,
+        std::process::id(),
+        STAGING_COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
@@ -1,4 +1,5 @@
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use object_store::ObjectStore;
 use pyo3::exceptions::{PyRuntimeWarning, PyValueError};
@@ -7,30 +8,80 @@ use pyo3::pybacked::PyBackedStr;
 use pyo3::types::{PyDict, PyTuple};
 use pyo3::{intern, PyTypeInfo};
 
-use crate::{PyAzureStore, PyGCSStore, PyHttpStore, PyLocalStore, PyMemoryStore, PyS3Store};
+use crate::azure::TieredAzureStore;
+use crate::token::token_store;
+#[cfg(feature = "sftp")]
+use crate::PySFTPStore;
+use crate::{
+    DeadlineStore, PyAzureStore, PyCacheStore, PyChaosStore, PyConsistentStore, PyDeadlineStore,
+    PyDictStore, PyEncryptedStore, PyGCSStore, PyHdfsStore, PyHttpStore, PyLocalStore,
+    PyMemoryStore, PyMirrorStore, PyNegativeCacheStore, PyPrefixStore, PyRangeCacheStore,
+    PyS3Store, PyThrottleStore,
+};
 
 /// A wrapper around a Rust ObjectStore instance that allows any rust-native implementation of
 /// ObjectStore.
 ///
 /// This will only accept ObjectStore instances created from the same library. See
-/// [register_store_module][crate::register_store_module].
-pub struct PyObjectStore(Arc<dyn ObjectStore>);
+/// [register_store_module][crate::register_store_module]. A token returned by a store's
+/// `to_token` is also accepted, in place of the store it recreates.
+pub struct PyObjectStore {
+    /// The store passed from Python
+    store: Arc<dyn ObjectStore>,
+    /// The store wrapped in the layer set with [`set_store_layer`], if any, used by operations
+    layered: Arc<dyn ObjectStore>,
+}
 
 impl<'py> FromPyObject<'py> for PyObjectStore {
     fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Some(store) = token_store(ob)? {
+            return store.extract();
+        }
         if let Ok(store) = ob.downcast::<PyS3Store>() {
-            Ok(Self(store.get().as_ref().clone()))
+            Ok(Self::new(store.get().as_ref().clone()))
         } else if let Ok(store) = ob.downcast::<PyAzureStore>() {
-            Ok(Self(store.get().as_ref().clone()))
+            // Wrapped to support the `Access-Tier` attribute of `put`
+            Ok(Self::new(Arc::new(TieredAzureStore::new(
+                store.get().clone(),
+            ))))
         } else if let Ok(store) = ob.downcast::<PyGCSStore>() {
-            Ok(Self(store.get().as_ref().clone()))
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyHdfsStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
         } else if let Ok(store) = ob.downcast::<PyHttpStore>() {
-            Ok(Self(store.get().as_ref().clone()))
+            Ok(Self::new(store.get().as_ref().clone()))
         } else if let Ok(store) = ob.downcast::<PyLocalStore>() {
-            Ok(Self(store.get().as_ref().clone()))
+            Ok(Self::new(store.get().as_ref().clone()))
         } else if let Ok(store) = ob.downcast::<PyMemoryStore>() {
-            Ok(Self(store.get().as_ref().clone()))
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyCacheStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyChaosStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyConsistentStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyDeadlineStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyDictStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyEncryptedStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyMirrorStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyNegativeCacheStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyPrefixStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyRangeCacheStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
+        } else if let Ok(store) = ob.downcast::<PyThrottleStore>() {
+            Ok(Self::new(store.get().as_ref().clone()))
         } else {
+            #[cfg(feature = "sftp")]
+            if let Ok(store) = ob.downcast::<PySFTPStore>() {
+                return Ok(Self::new(store.get().as_ref().clone()));
+            }
+
             let py = ob.py();
             // Check for object-store instance from other library
             let cls_name = ob
@@ -39,11 +90,24 @@ impl<'py> FromPyObject<'py> for PyObjectStore {
                 .extract::<PyBackedStr>()?;
             if [
                 PyAzureStore::NAME,
+                PyCacheStore::NAME,
+                PyChaosStore::NAME,
+                PyConsistentStore::NAME,
+                PyDeadlineStore::NAME,
+                PyDictStore::NAME,
+                PyEncryptedStore::NAME,
                 PyGCSStore::NAME,
+                PyHdfsStore::NAME,
                 PyHttpStore::NAME,
                 PyLocalStore::NAME,
                 PyMemoryStore::NAME,
+                PyMirrorStore::NAME,
+                PyNegativeCacheStore::NAME,
+                PyPrefixStore::NAME,
+                PyRangeCacheStore::NAME,
                 PyS3Store::NAME,
+                "SFTPStore",
+                PyThrottleStore::NAME,
             ]
             .contains(&cls_name.as_ref())
             {
@@ -60,25 +124,75 @@ impl<'py> FromPyObject<'py> for PyObjectStore {
 
 impl AsRef<Arc<dyn ObjectStore>> for PyObjectStore {
     fn as_ref(&self) -> &Arc<dyn ObjectStore> {
-        &self.0
+        &self.layered
     }
 }
 
 impl From<PyObjectStore> for Arc<dyn ObjectStore> {
     fn from(value: PyObjectStore) -> Self {
-        value.0
+        value.layered
     }
 }
 
+/// A function wrapping the store of every operation, set with [`set_store_layer`].
+pub type StoreLayer = fn(Arc<dyn ObjectStore>) -> Arc<dyn ObjectStore>;
+
+static STORE_LAYER: RwLock<Option<StoreLayer>> = RwLock::new(None);
+
+/// Set, or with `None` clear, a layer wrapping the store of every operation started afterwards.
+///
+/// The layer is applied when a [`PyObjectStore`] is extracted, so that it wraps the stores used by
+/// operations, such as to trace them, without each operation having to apply it.
+pub fn set_store_layer(layer: Option<StoreLayer>) {
+    *STORE_LAYER.write().unwrap() = layer;
+}
+
 impl PyObjectStore {
-    /// Consume self and return the underlying [`ObjectStore`].
+    fn new(store: Arc<dyn ObjectStore>) -> Self {
+        let layered = match *STORE_LAYER.read().unwrap() {
+            Some(layer) => layer(store.clone()),
+            None => store.clone(),
+        };
+        Self { store, layered }
+    }
+
+    /// Consume self and return the underlying [`ObjectStore`], wrapped in the layer set with
+    /// [`set_store_layer`], if any.
+    ///
+    /// Use this, or [`AsRef`], for the store of an operation.
     pub fn into_inner(self) -> Arc<dyn ObjectStore> {
-        self.0
+        self.layered
     }
 
-    /// Consume self and return a reference-counted [`ObjectStore`].
+    /// Consume self and return a reference-counted [`ObjectStore`], without any layer set with
+    /// [`set_store_layer`].
+    ///
+    /// Use this for a store wrapped by another store, so that the layer is only applied once,
+    /// around the outermost store.
     pub fn into_dyn(self) -> Arc<dyn ObjectStore> {
-        self.0
+        self.store
+    }
+
+    /// Whether the store is wrapped in a layer set with [`set_store_layer`].
+    ///
+    /// Requests that bypass the [`ObjectStore`] interface also bypass the layer, so operations
+    /// making them should make them only without one.
+    pub fn is_layered(&self) -> bool {
+        !Arc::ptr_eq(&self.store, &self.layered)
+    }
+
+    /// Limit every call made through this store to `timeout` from now, if given.
+    ///
+    /// This can only shorten the timeout configured on the underlying client.
+    pub fn with_timeout(self, timeout: Option<Duration>) -> Self {
+        let Some(timeout) = timeout else {
+            return self;
+        };
+        let deadline = tokio::time::Instant::now().checked_add(timeout);
+        Self {
+            store: Arc::new(DeadlineStore::new(self.store, deadline)),
+            layered: Arc::new(DeadlineStore::new(self.layered, deadline)),
+        }
     }
 }
 
@@ -88,9 +202,12 @@ enum PyExternalObjectStoreInner {
     Azure(PyAzureStore),
     #[allow(clippy::upper_case_acronyms)]
     GCS(PyGCSStore),
+    Hdfs(PyHdfsStore),
     Http(PyHttpStore),
     Local(PyLocalStore),
     S3(PyS3Store),
+    #[cfg(feature = "sftp")]
+    Sftp(PySFTPStore),
 }
 
 impl<'py> FromPyObject<'py> for PyExternalObjectStoreInner {
@@ -126,6 +243,18 @@ impl<'py> FromPyObject<'py> for PyExternalObjectStoreInner {
             return Ok(Self::GCS(store));
         }
 
+        if cls_name == PyHdfsStore::NAME {
+            let (args, kwargs): (Bound<PyTuple>, Bound<PyDict>) = ob
+                .call_method0(intern!(py, "__getnewargs_ex__"))?
+                .extract()?;
+            let store = PyHdfsStore::type_object(py)
+                .call(args, Some(&kwargs))?
+                .downcast::<PyHdfsStore>()?
+                .get()
+                .clone();
+            return Ok(Self::Hdfs(store));
+        }
+
         if cls_name == PyHttpStore::NAME {
             let (args, kwargs): (Bound<PyTuple>, Bound<PyDict>) = ob
                 .call_method0(intern!(py, "__getnewargs_ex__"))?
@@ -162,6 +291,19 @@ impl<'py> FromPyObject<'py> for PyExternalObjectStoreInner {
             return Ok(Self::S3(store));
         }
 
+        #[cfg(feature = "sftp")]
+        if cls_name == PySFTPStore::NAME {
+            let (args, kwargs): (Bound<PyTuple>, Bound<PyDict>) = ob
+                .call_method0(intern!(py, "__getnewargs_ex__"))?
+                .extract()?;
+            let store = PySFTPStore::type_object(py)
+                .call(args, Some(&kwargs))?
+                .downcast::<PySFTPStore>()?
+                .get()
+                .clone();
+            return Ok(Self::Sftp(store));
+        }
+
         Err(PyValueError::new_err(format!(
             "Expected an object store-compatible instance, got {}",
             ob.repr()?
@@ -193,18 +335,21 @@ impl<'py> FromPyObject<'py> for PyExternalObjectStoreInner {
 ///   credential providers should work.
 ///
 /// - This will not work for `PyMemoryStore` because we can't clone the internal state of the
-///   store.
+///   store, nor for `PyDictStore`, whose mapping can't be shared across libraries.
 #[derive(Debug, Clone)]
-pub struct PyExternalObjectStore(PyExternalObjectStoreInner);
+pub struct PyExternalObjectStore(Box<PyExternalObjectStoreInner>);
 
 impl From<PyExternalObjectStore> for Arc<dyn ObjectStore> {
     fn from(value: PyExternalObjectStore) -> Self {
-        match value.0 {
+        match *value.0 {
             PyExternalObjectStoreInner::Azure(store) => store.into_inner(),
             PyExternalObjectStoreInner::GCS(store) => store.into_inner(),
+            PyExternalObjectStoreInner::Hdfs(store) => store.into_inner(),
             PyExternalObjectStoreInner::Http(store) => store.into_inner(),
             PyExternalObjectStoreInner::Local(store) => store.into_inner(),
             PyExternalObjectStoreInner::S3(store) => store.into_inner(),
+            #[cfg(feature = "sftp")]
+            PyExternalObjectStoreInner::Sftp(store) => store.into_inner(),
         }
     }
 }
@@ -229,7 +374,7 @@ impl<'py> FromPyObject<'py> for PyExternalObjectStore {
                 let args = PyTuple::new(py, vec![warning])?;
                 warnings_mod.call_method1(intern!(py, "warn"), args)?;
 
-                Ok(Self(inner))
+                Ok(Self(Box::new(inner)))
             }
             Err(err) => Err(err),
         }
@@ -0,0 +1,142 @@
+//! Static tags attached to every request of a store, for attributing storage costs to workloads.
+
+use std::collections::BTreeMap;
+
+use http::header::{HeaderName, HeaderValue, USER_AGENT};
+use http::HeaderMap;
+use object_store::client::{HttpClient, HttpError, HttpRequest, HttpResponse, HttpService};
+use object_store::{ClientConfigKey, ClientOptions};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// The user agent used when the client options don't set one.
+pub(crate) const DEFAULT_USER_AGENT: &str =
+    concat!("pyo3-object_store/", env!("CARGO_PKG_VERSION"));
+
+/// The maximum number of custom audit headers accepted by GCS.
+const MAX_GCS_AUDIT_HEADERS: usize = 4;
+
+/// The maximum length of the key or value of a GCS custom audit header.
+const MAX_GCS_AUDIT_LENGTH: usize = 64;
+
+/// How request tags are sent to a provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TagStyle {
+    /// As GCS custom audit headers, `x-goog-custom-audit-<key>: <value>`, which are recorded in
+    /// the Cloud Audit Logs of the bucket.
+    GcsAuditHeaders,
+    /// As `<key>/<value>` products appended to the user agent, which providers record in their
+    /// access logs.
+    UserAgent,
+}
+
+fn is_valid_tag(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+/// Static key/value tags attached to every request of a store, ordered by key.
+#[derive(Clone, Debug, Default, PartialEq, IntoPyObject, IntoPyObjectRef)]
+pub struct PyRequestTags(BTreeMap<String, String>);
+
+impl<'py> FromPyObject<'py> for PyRequestTags {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let tags = ob.extract::<BTreeMap<String, String>>()?;
+        for (key, value) in tags.iter() {
+            if !is_valid_tag(key) || !is_valid_tag(value) {
+                return Err(PyValueError::new_err(format!(
+                    "Invalid request tag '{}': '{}'. Keys and values must be non-empty and contain only ASCII letters, digits, '-', '_' or '.'.",
+                    key, value
+                )));
+            }
+        }
+        Ok(Self(tags))
+    }
+}
+
+impl PyRequestTags {
+    /// Check that the tags can be sent with `style`.
+    pub(crate) fn validate(&self, style: TagStyle) -> PyResult<()> {
+        if style == TagStyle::GcsAuditHeaders {
+            if self.0.len() > MAX_GCS_AUDIT_HEADERS {
+                return Err(PyValueError::new_err(format!(
+                    "GCS accepts at most {} request tags, got {}",
+                    MAX_GCS_AUDIT_HEADERS,
+                    self.0.len()
+                )));
+            }
+            if let Some((key, value)) = self.0.iter().find(|(key, value)| {
+                key.len() > MAX_GCS_AUDIT_LENGTH || value.len() > MAX_GCS_AUDIT_LENGTH
+            }) {
+                return Err(PyValueError::new_err(format!(
+                    "Request tag '{}': '{}' is too long. GCS accepts keys and values of at most {} characters.",
+                    key, value, MAX_GCS_AUDIT_LENGTH
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// The headers carrying these tags, given the client options of the store.
+    fn headers(&self, style: TagStyle, options: &ClientOptions) -> HeaderMap {
+        let mut headers = HeaderMap::with_capacity(self.0.len());
+        if self.0.is_empty() {
+            return headers;
+        }
+        // Tags are validated to only contain characters that are valid in header names and
+        // values, so none of the conversions below can fail.
+        match style {
+            TagStyle::GcsAuditHeaders => {
+                for (key, value) in self.0.iter() {
+                    let name = format!("x-goog-custom-audit-{}", key.to_ascii_lowercase());
+                    headers.insert(
+                        HeaderName::try_from(name).unwrap(),
+                        HeaderValue::try_from(value.as_str()).unwrap(),
+                    );
+                }
+            }
+            TagStyle::UserAgent => {
+                let mut user_agent = options
+                    .get_config_value(&ClientConfigKey::UserAgent)
+                    .unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+                for (key, value) in self.0.iter() {
+                    user_agent.push_str(&format!(" {}/{}", key, value));
+                }
+                if let Ok(user_agent) = HeaderValue::try_from(user_agent) {
+                    headers.insert(USER_AGENT, user_agent);
+                }
+            }
+        }
+        headers
+    }
+
+    /// Wrap `client` so that every request it makes carries these tags.
+    pub(crate) fn wrap(
+        &self,
+        style: TagStyle,
+        options: &ClientOptions,
+        client: HttpClient,
+    ) -> HttpClient {
+        HttpClient::new(TaggedService {
+            client,
+            headers: self.headers(style, options),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct TaggedService {
+    client: HttpClient,
+    headers: HeaderMap,
+}
+
+#[async_trait::async_trait]
+impl HttpService for TaggedService {
+    async fn call(&self, mut req: HttpRequest) -> Result<HttpResponse, HttpError> {
+        for (name, value) in self.headers.iter() {
+            req.headers_mut().insert(name.clone(), value.clone());
+        }
+        self.client.execute(req).await
+    }
+}
@@ -0,0 +1,152 @@
+//! Python bindings for [`ThrottledStore`], a store wrapper that sleeps before or after each call.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use object_store::throttle::{ThrottleConfig, ThrottledStore};
+use object_store::ObjectStore;
+use pyo3::intern;
+use pyo3::prelude::*;
+
+use crate::{check_keys, PyObjectStore};
+
+#[derive(Clone, Debug, Default, IntoPyObject, IntoPyObjectRef, PartialEq)]
+/// A Python-facing wrapper around a [`ThrottleConfig`].
+pub struct PyThrottleConfig {
+    #[pyo3(item)]
+    wait_delete_per_call: Duration,
+    #[pyo3(item)]
+    wait_get_per_byte: Duration,
+    #[pyo3(item)]
+    wait_get_per_call: Duration,
+    #[pyo3(item)]
+    wait_list_per_call: Duration,
+    #[pyo3(item)]
+    wait_list_per_entry: Duration,
+    #[pyo3(item)]
+    wait_list_with_delimiter_per_call: Duration,
+    #[pyo3(item)]
+    wait_list_with_delimiter_per_entry: Duration,
+    #[pyo3(item)]
+    wait_put_per_call: Duration,
+}
+
+impl<'py> FromPyObject<'py> for PyThrottleConfig {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        check_keys(
+            ob,
+            "config",
+            &[
+                "wait_delete_per_call",
+                "wait_get_per_byte",
+                "wait_get_per_call",
+                "wait_list_per_call",
+                "wait_list_per_entry",
+                "wait_list_with_delimiter_per_call",
+                "wait_list_with_delimiter_per_entry",
+                "wait_put_per_call",
+            ],
+        )?;
+        let mut throttle_config = PyThrottleConfig::default();
+        let py = ob.py();
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_delete_per_call")) {
+            throttle_config.wait_delete_per_call = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_get_per_byte")) {
+            throttle_config.wait_get_per_byte = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_get_per_call")) {
+            throttle_config.wait_get_per_call = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_list_per_call")) {
+            throttle_config.wait_list_per_call = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_list_per_entry")) {
+            throttle_config.wait_list_per_entry = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_list_with_delimiter_per_call")) {
+            throttle_config.wait_list_with_delimiter_per_call = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_list_with_delimiter_per_entry")) {
+            throttle_config.wait_list_with_delimiter_per_entry = wait.extract()?;
+        }
+        if let Ok(wait) = ob.get_item(intern!(py, "wait_put_per_call")) {
+            throttle_config.wait_put_per_call = wait.extract()?;
+        }
+        Ok(throttle_config)
+    }
+}
+
+impl From<PyThrottleConfig> for ThrottleConfig {
+    fn from(value: PyThrottleConfig) -> Self {
+        ThrottleConfig {
+            wait_delete_per_call: value.wait_delete_per_call,
+            wait_get_per_byte: value.wait_get_per_byte,
+            wait_get_per_call: value.wait_get_per_call,
+            wait_list_per_call: value.wait_list_per_call,
+            wait_list_per_entry: value.wait_list_per_entry,
+            wait_list_with_delimiter_per_call: value.wait_list_with_delimiter_per_call,
+            wait_list_with_delimiter_per_entry: value.wait_list_with_delimiter_per_entry,
+            wait_put_per_call: value.wait_put_per_call,
+        }
+    }
+}
+
+impl From<ThrottleConfig> for PyThrottleConfig {
+    fn from(value: ThrottleConfig) -> Self {
+        PyThrottleConfig {
+            wait_delete_per_call: value.wait_delete_per_call,
+            wait_get_per_byte: value.wait_get_per_byte,
+            wait_get_per_call: value.wait_get_per_call,
+            wait_list_per_call: value.wait_list_per_call,
+            wait_list_per_entry: value.wait_list_per_entry,
+            wait_list_with_delimiter_per_call: value.wait_list_with_delimiter_per_call,
+            wait_list_with_delimiter_per_entry: value.wait_list_with_delimiter_per_entry,
+            wait_put_per_call: value.wait_put_per_call,
+        }
+    }
+}
+
+/// A Python-facing wrapper around a [`ThrottledStore`].
+#[derive(Debug, Clone)]
+#[pyclass(name = "ThrottleStore", frozen, subclass)]
+pub struct PyThrottleStore(Arc<ThrottledStore<Arc<dyn ObjectStore>>>);
+
+impl AsRef<Arc<ThrottledStore<Arc<dyn ObjectStore>>>> for PyThrottleStore {
+    fn as_ref(&self) -> &Arc<ThrottledStore<Arc<dyn ObjectStore>>> {
+        &self.0
+    }
+}
+
+impl PyThrottleStore {
+    /// Consume self and return the underlying [`ThrottledStore`].
+    pub fn into_inner(self) -> Arc<ThrottledStore<Arc<dyn ObjectStore>>> {
+        self.0
+    }
+}
+
+#[pymethods]
+impl PyThrottleStore {
+    #[new]
+    #[pyo3(signature = (store, config=None))]
+    fn py_new(store: PyObjectStore, config: Option<PyThrottleConfig>) -> Self {
+        Self(Arc::new(ThrottledStore::new(
+            store.into_dyn(),
+            config.unwrap_or_default().into(),
+        )))
+    }
+
+    fn __repr__(&self) -> String {
+        self.0.to_string()
+    }
+
+    #[getter]
+    fn config(&self) -> PyThrottleConfig {
+        self.0.config().into()
+    }
+
+    fn update_config(&self, config: PyThrottleConfig) {
+        let config = ThrottleConfig::from(config);
+        self.0.config_mut(|c| *c = config);
+    }
+}
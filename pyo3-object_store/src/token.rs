@@ -0,0 +1,291 @@
+//! Serialization of store configuration to tokens, compact strings that recreate a store in
+//! another process with `from_token`, or when passed in place of a store.
+//!
+//! A token is the base64-encoded JSON of the store class and the arguments returned by its
+//! `__getnewargs_ex__`, so only stores that can be recreated from their configuration have
+//! tokens. Unlike pickle, decoding a token never runs arbitrary code, but a token is still trusted
+//! input: it chooses the endpoint that requests and credentials are sent to, or the directory
+//! that a local store reads and writes, so tokens should only come from trusted sources.
+
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use base64::prelude::BASE64_URL_SAFE_NO_PAD;
+use base64::Engine;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple, PyType};
+use pyo3::{intern, PyTypeInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Number, Value};
+
+use crate::credentials::chain_providers;
+#[cfg(feature = "sftp")]
+use crate::PySFTPStore;
+use crate::{PyAzureStore, PyGCSStore, PyHdfsStore, PyHttpStore, PyLocalStore, PyS3Store};
+
+/// The prefix of every token, which versions the format.
+const TOKEN_PREFIX: &str = "obstore:v1:";
+
+/// The key of the JSON object that a `timedelta` is encoded as, in seconds.
+const TIMEDELTA_KEY: &str = "$timedelta";
+
+/// How many stores decoded from tokens passed in place of a store are kept, so that their
+/// connections are reused across calls.
+const MAX_DECODED_STORES: usize = 16;
+
+/// The stores most recently decoded from tokens passed in place of a store, by token, oldest
+/// first. Once [`MAX_DECODED_STORES`] are kept, the oldest is dropped for each new one, so the
+/// cache never holds more than that many stores or their connection pools.
+static DECODED_STORES: Mutex<VecDeque<(String, PyObject)>> = Mutex::new(VecDeque::new());
+
+#[derive(Serialize, Deserialize)]
+struct Token {
+    /// The name of the store class
+    store: String,
+    args: Vec<Value>,
+    kwargs: Map<String, Value>,
+}
+
+/// The store class named `name`, if it can be created from a token.
+fn store_type<'py>(py: Python<'py>, name: &str) -> Option<Bound<'py, PyType>> {
+    let store_type = match name {
+        PyAzureStore::NAME => PyAzureStore::type_object(py),
+        PyGCSStore::NAME => PyGCSStore::type_object(py),
+        PyHdfsStore::NAME => PyHdfsStore::type_object(py),
+        PyHttpStore::NAME => PyHttpStore::type_object(py),
+        PyLocalStore::NAME => PyLocalStore::type_object(py),
+        PyS3Store::NAME => PyS3Store::type_object(py),
+        #[cfg(feature = "sftp")]
+        PySFTPStore::NAME => PySFTPStore::type_object(py),
+        _ => return None,
+    };
+    Some(store_type)
+}
+
+/// Encode the arguments of a store, as returned by `__getnewargs_ex__`, to a token.
+///
+/// `name` is the name of the store class.
+pub(crate) fn encode_token(py: Python, name: &str, newargs: PyObject) -> PyResult<String> {
+    let (args, kwargs): (Bound<PyTuple>, Bound<PyDict>) = newargs.extract(py)?;
+    let args = args
+        .iter()
+        .map(|arg| to_json(&arg))
+        .collect::<PyResult<_>>()?;
+    let kwargs = kwargs
+        .iter()
+        .map(|(key, value)| {
+            let key = key.extract::<String>()?;
+            let value = to_json(&value).map_err(|err| {
+                PyTypeError::new_err(format!(
+                    "The {key} of the {name} can't be serialized to a token: {}",
+                    err.value(py)
+                ))
+            })?;
+            Ok((key, value))
+        })
+        .collect::<PyResult<_>>()?;
+    let token = Token {
+        store: name.to_string(),
+        args,
+        kwargs,
+    };
+    let json = serde_json::to_vec(&token).map_err(|err| PyValueError::new_err(err.to_string()))?;
+    Ok(format!(
+        "{TOKEN_PREFIX}{}",
+        BASE64_URL_SAFE_NO_PAD.encode(json)
+    ))
+}
+
+fn decode_token(token: &str) -> PyResult<Token> {
+    let invalid = |reason: String| PyValueError::new_err(format!("Invalid store token: {reason}"));
+    let encoded = token
+        .strip_prefix(TOKEN_PREFIX)
+        .ok_or_else(|| invalid(format!("expected it to start with '{TOKEN_PREFIX}'")))?;
+    let json = BASE64_URL_SAFE_NO_PAD
+        .decode(encoded)
+        .map_err(|err| invalid(err.to_string()))?;
+    serde_json::from_slice(&json).map_err(|err| invalid(err.to_string()))
+}
+
+/// Create a store of class `cls`, or a subclass of it, from a token.
+pub(crate) fn store_from_token<'py>(
+    cls: &Bound<'py, PyType>,
+    token: &str,
+) -> PyResult<Bound<'py, PyAny>> {
+    let token = decode_token(token)?;
+    let store_type = token_store_type(cls.py(), &token)?;
+    if !cls.is_subclass(&store_type)? {
+        return Err(PyValueError::new_err(format!(
+            "Expected a token of a {}, got a token of a {}",
+            cls.name()?,
+            token.store
+        )));
+    }
+    new_store(cls, token)
+}
+
+fn token_store_type<'py>(py: Python<'py>, token: &Token) -> PyResult<Bound<'py, PyType>> {
+    store_type(py, &token.store).ok_or_else(|| {
+        PyValueError::new_err(format!(
+            "Invalid store token: unknown store {}",
+            token.store
+        ))
+    })
+}
+
+fn new_store<'py>(cls: &Bound<'py, PyType>, token: Token) -> PyResult<Bound<'py, PyAny>> {
+    let py = cls.py();
+    let args = token
+        .args
+        .iter()
+        .map(|arg| from_json(py, arg))
+        .collect::<PyResult<Vec<_>>>()?;
+    let kwargs = PyDict::new(py);
+    for (key, value) in &token.kwargs {
+        kwargs.set_item(key, from_json(py, value)?)?;
+    }
+    cls.call(PyTuple::new(py, args)?, Some(&kwargs))
+}
+
+/// The store that `ob` is a token of, if it's a string of a token.
+///
+/// Recently decoded stores are reused, so that passing the same token to many calls doesn't open
+/// new connections for each call.
+pub(crate) fn token_store<'py>(ob: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let py = ob.py();
+    let Ok(token) = ob.downcast::<PyString>() else {
+        return Ok(None);
+    };
+    let token = token.to_str()?;
+    if !token.starts_with(TOKEN_PREFIX) {
+        return Ok(None);
+    }
+
+    if let Some((_, store)) = DECODED_STORES
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|(decoded, _)| decoded == token)
+    {
+        return Ok(Some(store.bind(py).clone()));
+    }
+
+    let decoded = decode_token(token)?;
+    let store = new_store(&token_store_type(py, &decoded)?, decoded)?;
+    let mut decoded_stores = DECODED_STORES.lock().unwrap();
+    if decoded_stores.len() == MAX_DECODED_STORES {
+        decoded_stores.pop_front();
+    }
+    decoded_stores.push_back((token.to_string(), store.clone().unbind()));
+    Ok(Some(store))
+}
+
+/// Resolve a store passed as a token to the store it was created from.
+///
+/// Returns `ob` itself if it isn't a token, so that this can be called on any object passed as a
+/// store before extracting it.
+pub fn resolve_store_token<'py>(ob: &Bound<'py, PyAny>) -> PyResult<Bound<'py, PyAny>> {
+    Ok(token_store(ob)?.unwrap_or_else(|| ob.clone()))
+}
+
+fn to_json(ob: &Bound<PyAny>) -> PyResult<Value> {
+    if ob.is_none() {
+        Ok(Value::Null)
+    } else if let Ok(value) = ob.downcast::<PyBool>() {
+        Ok(Value::Bool(value.is_true()))
+    } else if ob.is_instance_of::<PyInt>() {
+        match ob.extract::<i64>() {
+            Ok(value) => Ok(value.into()),
+            Err(_) => Ok(ob.extract::<u64>()?.into()),
+        }
+    } else if let Ok(value) = ob.downcast::<PyFloat>() {
+        match Number::from_f64(value.value()) {
+            Some(number) => Ok(Value::Number(number)),
+            None => Err(PyValueError::new_err(format!(
+                "{} isn't a finite number",
+                ob.repr()?
+            ))),
+        }
+    } else if let Ok(value) = ob.downcast::<PyString>() {
+        Ok(Value::String(value.to_str()?.to_string()))
+    } else if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+        let values = ob.try_iter()?.map(|value| to_json(&value?));
+        Ok(Value::Array(values.collect::<PyResult<_>>()?))
+    } else if let Ok(dict) = ob.downcast::<PyDict>() {
+        let mut map = Map::with_capacity(dict.len());
+        for (key, value) in dict.iter() {
+            let Ok(key) = key.extract::<String>() else {
+                return Err(PyTypeError::new_err(format!(
+                    "Expected string keys, got {}",
+                    key.repr()?
+                )));
+            };
+            map.insert(key, to_json(&value)?);
+        }
+        Ok(Value::Object(map))
+    } else if let Some(providers) = chain_providers(ob)? {
+        // A chain of credential providers can be passed as a list
+        let values = providers.iter().map(to_json);
+        Ok(Value::Array(values.collect::<PyResult<_>>()?))
+    } else if let Ok(duration) = ob.extract::<Duration>() {
+        let mut map = Map::with_capacity(1);
+        map.insert(TIMEDELTA_KEY.to_string(), duration.as_secs_f64().into());
+        Ok(Value::Object(map))
+    } else if ob.hasattr(intern!(ob.py(), "__fspath__"))? {
+        Ok(Value::String(
+            ob.extract::<PathBuf>()?.to_string_lossy().into_owned(),
+        ))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "{} can't be serialized",
+            ob.repr()?
+        )))
+    }
+}
+
+fn from_json<'py>(py: Python<'py>, value: &Value) -> PyResult<Bound<'py, PyAny>> {
+    match value {
+        Value::Null => Ok(py.None().into_bound(py)),
+        Value::Bool(value) => Ok(PyBool::new(py, *value).to_owned().into_any()),
+        Value::Number(number) => {
+            if let Some(value) = number.as_i64() {
+                Ok(value.into_pyobject(py)?.into_any())
+            } else if let Some(value) = number.as_u64() {
+                Ok(value.into_pyobject(py)?.into_any())
+            } else {
+                Ok(number
+                    .as_f64()
+                    .unwrap_or_default()
+                    .into_pyobject(py)?
+                    .into_any())
+            }
+        }
+        Value::String(value) => Ok(PyString::new(py, value).into_any()),
+        Value::Array(values) => {
+            let values = values
+                .iter()
+                .map(|value| from_json(py, value))
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, values)?.into_any())
+        }
+        Value::Object(map) => {
+            if let (1, Some(seconds)) = (map.len(), map.get(TIMEDELTA_KEY)) {
+                let duration = seconds
+                    .as_f64()
+                    .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+                    .ok_or_else(|| {
+                        PyValueError::new_err("Invalid store token: invalid timedelta")
+                    })?;
+                return Ok(duration.into_pyobject(py)?.into_any());
+            }
+            let dict = PyDict::new(py);
+            for (key, value) in map {
+                dict.set_item(key, from_json(py, value)?)?;
+            }
+            Ok(dict.into_any())
+        }
+    }
+}
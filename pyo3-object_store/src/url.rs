@@ -5,6 +5,8 @@ use pyo3::types::{PyAnyMethods, PyString};
 use pyo3::FromPyObject;
 use url::Url;
 
+use crate::aws::rewrite_arn_url;
+
 /// A wrapper around [`url::Url`] that implements [`FromPyObject`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct PyUrl(Url);
@@ -24,7 +26,13 @@ impl PyUrl {
 impl<'py> FromPyObject<'py> for PyUrl {
     fn extract_bound(ob: &pyo3::Bound<'py, pyo3::PyAny>) -> pyo3::PyResult<Self> {
         let s = ob.extract::<PyBackedStr>()?;
-        let url = Url::parse(&s).map_err(|err| PyValueError::new_err(err.to_string()))?;
+        // An `s3://` URL of an access point ARN isn't a valid URL, so it's parsed as the URL of
+        // the access point's endpoint instead
+        let url = match rewrite_arn_url(&s) {
+            Some(rewritten) => Url::parse(&rewritten),
+            None => Url::parse(&s),
+        }
+        .map_err(|err| PyValueError::new_err(err.to_string()))?;
         Ok(Self(url))
     }
 }
@@ -0,0 +1,347 @@
+//! An [`HttpStore`] that can list WebDAV collections with its own `PROPFIND` requests.
+//!
+//! `HttpStore` lists with a single `Depth: infinity` request, which most WebDAV servers such as
+//! Nextcloud and dCache reject, and it fails on responses with a `404` status for some of the
+//! requested properties. When listing through WebDAV is enabled, each collection is instead
+//! listed with a `Depth: 1` request for the properties that object metadata is made of.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::{StreamExt, TryStreamExt};
+use http::header::CONTENT_TYPE;
+use http::{Method, StatusCode};
+use object_store::client::{HttpClient, HttpRequestBody};
+use object_store::http::HttpStore;
+use object_store::path::Path;
+use object_store::{
+    GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, ObjectStore,
+    PutMultipartOptions, PutOptions, PutPayload, PutResult, Result,
+};
+use percent_encoding::percent_decode_str;
+use serde::Deserialize;
+use url::Url;
+
+const STORE: &str = "HTTP";
+
+/// The properties requested for each entry of a collection.
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<d:propfind xmlns:d="DAV:">
+  <d:prop>
+    <d:resourcetype/>
+    <d:getcontentlength/>
+    <d:getlastmodified/>
+    <d:getetag/>
+  </d:prop>
+</d:propfind>"#;
+
+fn webdav_error(msg: impl Into<String>) -> object_store::Error {
+    object_store::Error::Generic {
+        store: STORE,
+        source: msg.into().into(),
+    }
+}
+
+/// The response to a `PROPFIND` request.
+#[derive(Debug, Deserialize, Default)]
+struct MultiStatus {
+    #[serde(default)]
+    response: Vec<MultiStatusResponse>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MultiStatusResponse {
+    href: String,
+    /// The properties of the entry, grouped by their status. Properties that the server doesn't
+    /// have for an entry, such as the size of a collection, are given with a `404` status.
+    #[serde(default)]
+    propstat: Vec<PropStat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PropStat {
+    prop: Prop,
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Prop {
+    #[serde(rename = "getlastmodified")]
+    last_modified: Option<String>,
+    // Parsed after the status is checked, as properties with a `404` status are empty
+    #[serde(rename = "getcontentlength")]
+    content_length: Option<String>,
+    #[serde(rename = "resourcetype")]
+    resource_type: Option<ResourceType>,
+    #[serde(rename = "getetag")]
+    e_tag: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResourceType {
+    collection: Option<()>,
+}
+
+impl MultiStatusResponse {
+    /// The properties of the entry that the server has.
+    fn prop(&self) -> Result<&Prop> {
+        self.propstat
+            .iter()
+            .find(|propstat| propstat.status.split_whitespace().nth(1) == Some("200"))
+            .map(|propstat| &propstat.prop)
+            .ok_or_else(|| {
+                webdav_error(format!(
+                    "PROPFIND response for {} has no properties with status 200",
+                    self.href
+                ))
+            })
+    }
+
+    /// The path of the entry, relative to `base_url`.
+    fn path(&self, base_url: &Url) -> Result<Path> {
+        let url = Url::options()
+            .base_url(Some(base_url))
+            .parse(&self.href)
+            .map_err(|err| webdav_error(format!("Invalid href {}: {}", self.href, err)))?;
+        let decode = |path: &str| {
+            percent_decode_str(path)
+                .decode_utf8()
+                .map(|path| path.into_owned())
+                .map_err(|err| webdav_error(format!("Non-unicode href {}: {}", self.href, err)))
+        };
+        let path = decode(url.path())?;
+        let base_path = decode(base_url.path())?;
+        let relative = path
+            .strip_prefix(base_path.trim_end_matches('/'))
+            .filter(|relative| relative.is_empty() || relative.starts_with('/'))
+            .ok_or_else(|| {
+                webdav_error(format!(
+                    "href {} is outside of the store's URL {}",
+                    self.href, base_url
+                ))
+            })?;
+        Path::parse(relative.trim_matches('/')).map_err(|err| webdav_error(err.to_string()))
+    }
+
+    fn object_meta(&self, location: Path, prop: &Prop) -> Result<ObjectMeta> {
+        let size = prop
+            .content_length
+            .as_deref()
+            .and_then(|size| size.trim().parse().ok())
+            .ok_or_else(|| webdav_error(format!("Missing size for {}", self.href)))?;
+        let last_modified = prop
+            .last_modified
+            .as_deref()
+            .ok_or_else(|| webdav_error(format!("Missing last modified for {}", self.href)))?;
+        let last_modified = DateTime::parse_from_rfc2822(last_modified)
+            .map_err(|err| {
+                webdav_error(format!(
+                    "Invalid last modified {} for {}: {}",
+                    last_modified, self.href, err
+                ))
+            })?
+            .with_timezone(&Utc);
+        Ok(ObjectMeta {
+            location,
+            last_modified,
+            size,
+            e_tag: prop.e_tag.clone(),
+            version: None,
+        })
+    }
+}
+
+/// Lists the collections of a WebDAV server.
+#[derive(Debug)]
+struct Lister {
+    client: HttpClient,
+    url: Url,
+}
+
+impl Lister {
+    /// List the objects and collections directly under `prefix`.
+    async fn list_collection(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        let mut url = self.url.clone();
+        url.path_segments_mut()
+            .map_err(|_| webdav_error(format!("Invalid WebDAV URL {}", self.url)))?
+            .pop_if_empty()
+            .extend(prefix.into_iter().flat_map(|prefix| prefix.parts()))
+            // Collections are requested with a trailing slash, which some servers redirect to
+            .push("");
+
+        let request = http::Request::builder()
+            .method(Method::from_bytes(b"PROPFIND").unwrap())
+            .uri(url.as_str())
+            .header("Depth", "1")
+            .header(CONTENT_TYPE, "application/xml; charset=utf-8")
+            .body(HttpRequestBody::from(Bytes::from_static(
+                PROPFIND_BODY.as_bytes(),
+            )))
+            .map_err(|err| webdav_error(err.to_string()))?;
+        let response = self
+            .client
+            .execute(request)
+            .await
+            .map_err(|err| webdav_error(err.to_string()))?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .bytes()
+            .await
+            .map_err(|err| webdav_error(err.to_string()))?;
+        // A prefix that doesn't exist has no entries
+        if status == StatusCode::NOT_FOUND {
+            return Ok(ListResult {
+                common_prefixes: vec![],
+                objects: vec![],
+            });
+        }
+        if !status.is_success() {
+            return Err(webdav_error(format!(
+                "PROPFIND {} failed with status {}: {}",
+                url,
+                status,
+                String::from_utf8_lossy(&body)
+            )));
+        }
+        let multi_status: MultiStatus = quick_xml::de::from_str(&String::from_utf8_lossy(&body))
+            .map_err(|err| webdav_error(format!("Invalid PROPFIND response: {}", err)))?;
+
+        let prefix_len = prefix.map(|prefix| prefix.as_ref().len()).unwrap_or(0);
+        let mut result = ListResult {
+            common_prefixes: vec![],
+            objects: vec![],
+        };
+        for response in multi_status.response {
+            let location = response.path(&self.url)?;
+            // Skip the collection itself
+            if location.as_ref().len() <= prefix_len {
+                continue;
+            }
+            let prop = response.prop()?;
+            if prop
+                .resource_type
+                .as_ref()
+                .is_some_and(|resource_type| resource_type.collection.is_some())
+            {
+                result.common_prefixes.push(location);
+            } else {
+                result.objects.push(response.object_meta(location, prop)?);
+            }
+        }
+        Ok(result)
+    }
+
+    /// List all the objects under `prefix`, walking its collections one level at a time.
+    fn walk(self: Arc<Self>, prefix: Option<Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        futures::stream::try_unfold(
+            (self, VecDeque::from([prefix])),
+            |(lister, mut pending)| async move {
+                let Some(prefix) = pending.pop_front() else {
+                    return Ok(None);
+                };
+                let result = lister.list_collection(prefix.as_ref()).await?;
+                pending.extend(result.common_prefixes.into_iter().map(Some));
+                let objects = futures::stream::iter(result.objects.into_iter().map(Ok));
+                Ok::<_, object_store::Error>(Some((objects, (lister, pending))))
+            },
+        )
+        .try_flatten()
+        .boxed()
+    }
+}
+
+/// An [`HttpStore`] that lists through WebDAV `PROPFIND` requests of its own when enabled.
+#[derive(Debug)]
+pub struct WebDavStore {
+    inner: HttpStore,
+    lister: Option<Arc<Lister>>,
+}
+
+impl std::fmt::Display for WebDavStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl WebDavStore {
+    /// Wrap `inner`, which has the base URL `url`. If `client` is given, listing goes through
+    /// WebDAV with requests made by `client`.
+    pub(crate) fn new(inner: HttpStore, url: Url, client: Option<HttpClient>) -> Self {
+        Self {
+            inner,
+            lister: client.map(|client| Arc::new(Lister { client, url })),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ObjectStore for WebDavStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        self.inner.put_opts(location, payload, opts).await
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOptions,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        self.inner.put_multipart_opts(location, opts).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'static, Result<ObjectMeta>> {
+        match &self.lister {
+            Some(lister) => lister.clone().walk(prefix.cloned()),
+            None => self.inner.list(prefix),
+        }
+    }
+
+    fn list_with_offset(
+        &self,
+        prefix: Option<&Path>,
+        offset: &Path,
+    ) -> BoxStream<'static, Result<ObjectMeta>> {
+        match &self.lister {
+            Some(lister) => {
+                let offset = offset.clone();
+                lister
+                    .clone()
+                    .walk(prefix.cloned())
+                    .try_filter(move |meta| futures::future::ready(meta.location > offset))
+                    .boxed()
+            }
+            None => self.inner.list_with_offset(prefix, offset),
+        }
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        match &self.lister {
+            Some(lister) => lister.list_collection(prefix).await,
+            None => self.inner.list_with_delimiter(prefix).await,
+        }
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
@@ -1,7 +1,8 @@
 import pytest
 
-from obstore.exceptions import BaseError
-from obstore.store import AzureStore
+import obstore as obs
+from obstore.exceptions import BaseError, UnauthenticatedError
+from obstore.store import AzureStore, MemoryStore
 
 
 def test_overlapping_config_keys():
@@ -32,3 +33,36 @@ def test_eq():
     assert store == store  # noqa: PLR0124
     assert store == store2
     assert store != store3
+
+
+def test_set_tier_invalid():
+    store = AzureStore("container", account_name="account_name", skip_signature=True)
+    with pytest.raises(ValueError, match="Unexpected input for tier"):
+        obs.set_tier(store, "file.txt", "Warm")  # type: ignore (intentional test)
+
+
+def test_set_tier_unsupported_store():
+    with pytest.raises(TypeError):
+        obs.set_tier(MemoryStore(), "file.txt", "Cool")  # type: ignore (intentional test)
+
+
+def test_put_invalid_access_tier():
+    store = AzureStore("container", account_name="account_name", skip_signature=True)
+    with pytest.raises(BaseError, match="Unexpected access tier"):
+        obs.put(store, "file.txt", b"foo", attributes={"Access-Tier": "Warm"})
+
+
+def test_builtin_credential_provider_invalid():
+    with pytest.raises(ValueError, match="Unknown credential_provider"):
+        AzureStore("container", account_name="account", credential_provider="az")
+
+
+def test_builtin_workload_identity_missing_env(monkeypatch: pytest.MonkeyPatch):
+    monkeypatch.delenv("AZURE_CLIENT_ID", raising=False)
+    store = AzureStore(
+        "container",
+        account_name="account",
+        credential_provider="workload-identity",
+    )
+    with pytest.raises(UnauthenticatedError, match="AZURE_CLIENT_ID is not set"):
+        obs.head(store, "file.txt")
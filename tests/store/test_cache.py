@@ -0,0 +1,137 @@
+import json
+
+import pytest
+
+import obstore as obs
+from obstore.store import CacheStore, MemoryStore
+
+
+def _cached_paths(cache_dir):
+    return {
+        json.loads(meta.read_text())["path"] for meta in cache_dir.glob("*.json")
+    }
+
+
+def test_cache_store(tmp_path):
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"hello world")
+    store = CacheStore(inner, tmp_path, 1024)
+
+    assert store.size == 0
+    assert obs.get(store, "file.txt").bytes() == b"hello world"
+    assert store.size == 11
+    assert obs.get(store, "file.txt").bytes() == b"hello world"
+    assert obs.get_range(store, "file.txt", start=6, end=11) == b"world"
+    assert obs.get_ranges(store, "file.txt", starts=[0, 6], ends=[5, 11]) == [
+        b"hello",
+        b"world",
+    ]
+
+
+def test_cache_store_passes_through_uncached_ranges(tmp_path):
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"hello world")
+    store = CacheStore(inner, tmp_path, 1024)
+
+    # A ranged read doesn't download and cache the whole object
+    assert obs.get_range(store, "file.txt", start=6, end=11) == b"world"
+    assert store.size == 0
+
+    assert obs.get(store, "file.txt").bytes() == b"hello world"
+    assert store.size == 11
+
+    # A ranged read of a changed object is passed through, and drops the cached copy
+    obs.put(inner, "file.txt", b"HELLO WORLD")
+    assert obs.get_range(store, "file.txt", start=6, end=11) == b"WORLD"
+    assert store.size == 0
+
+
+def test_cache_store_validates_etag(tmp_path):
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"foo")
+    store = CacheStore(inner, tmp_path, 1024)
+
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+    # Writes that bypass the cache are observed on the next read
+    obs.put(inner, "file.txt", b"barbaz")
+    assert obs.get(store, "file.txt").bytes() == b"barbaz"
+    assert store.size == 6
+
+    obs.delete(inner, "file.txt")
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "file.txt")
+    assert store.size == 0
+
+
+def test_cache_store_evicts_least_recently_used(tmp_path):
+    inner = MemoryStore()
+    for name in ["a", "b", "c"]:
+        obs.put(inner, name, b"1234")
+    store = CacheStore(inner, tmp_path, 10)
+
+    obs.get(store, "a").bytes()
+    obs.get(store, "b").bytes()
+    obs.get(store, "a").bytes()
+    obs.get(store, "c").bytes()
+    assert store.size == 8
+    assert _cached_paths(tmp_path) == {"a", "c"}
+
+    obs.get(store, "b").bytes()
+    assert _cached_paths(tmp_path) == {"b", "c"}
+
+
+def test_cache_store_skips_large_objects(tmp_path):
+    inner = MemoryStore()
+    obs.put(inner, "large.txt", b"x" * 100)
+    store = CacheStore(inner, tmp_path, 10)
+
+    assert obs.get(store, "large.txt").bytes() == b"x" * 100
+    assert obs.get_range(store, "large.txt", start=0, end=5) == b"xxxxx"
+    assert store.size == 0
+
+
+def test_cache_store_writes_invalidate(tmp_path):
+    store = CacheStore(MemoryStore(), tmp_path, 1024)
+
+    obs.put(store, "file.txt", b"foo")
+    obs.get(store, "file.txt").bytes()
+    assert store.size == 3
+
+    obs.put(store, "file.txt", b"foobar")
+    assert store.size == 0
+    assert obs.get(store, "file.txt").bytes() == b"foobar"
+
+    obs.rename(store, "file.txt", "other.txt")
+    assert store.size == 0
+
+    obs.get(store, "other.txt").bytes()
+    store.clear()
+    assert store.size == 0
+    assert list(tmp_path.iterdir()) == []
+
+
+def test_cache_store_persists(tmp_path):
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"foo")
+    store = CacheStore(inner, tmp_path, 1024)
+    obs.get(store, "file.txt").bytes()
+
+    store = CacheStore(inner, tmp_path, 1024)
+    assert store.size == 3
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+    # A smaller cache evicts entries on startup
+    store = CacheStore(inner, tmp_path, 2)
+    assert store.size == 0
+
+
+@pytest.mark.asyncio
+async def test_cache_store_async(tmp_path):
+    inner = MemoryStore()
+    await obs.put_async(inner, "file.txt", b"foo")
+    store = CacheStore(inner, tmp_path, 1024)
+
+    resp = await obs.get_async(store, "file.txt")
+    assert await resp.bytes_async() == b"foo"
+    assert store.size == 3
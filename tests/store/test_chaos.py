@@ -0,0 +1,71 @@
+import time
+from datetime import timedelta
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import GenericError
+from obstore.store import ChaosStore, MemoryStore
+
+
+def test_chaos_no_failures():
+    store = ChaosStore(MemoryStore())
+
+    obs.put(store, "file.txt", b"foo")
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+    assert len(obs.list(store).collect()) == 1
+
+
+def test_chaos_always_fails():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"foo")
+    store = ChaosStore(inner, failure_rate=1.0)
+
+    with pytest.raises(GenericError, match="Injected failure"):
+        obs.get(store, "file.txt")
+    with pytest.raises(GenericError, match="Injected failure"):
+        obs.put(store, "other.txt", b"bar")
+    with pytest.raises(GenericError, match="Injected failure"):
+        obs.list(store).collect()
+
+
+def test_chaos_seed_is_reproducible():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"foo")
+
+    def outcomes(seed: int) -> list[bool]:
+        store = ChaosStore(inner, failure_rate=0.5, seed=seed)
+        results = []
+        for _ in range(20):
+            try:
+                obs.head(store, "file.txt")
+                results.append(True)
+            except GenericError:
+                results.append(False)
+        return results
+
+    first = outcomes(42)
+    assert first == outcomes(42)
+    assert True in first
+    assert False in first
+
+
+def test_chaos_latency():
+    store = ChaosStore(MemoryStore(), latency=timedelta(milliseconds=100))
+
+    start = time.monotonic()
+    obs.put(store, "file.txt", b"foo")
+    assert time.monotonic() - start >= 0.1
+
+
+def test_chaos_invalid_failure_rate():
+    with pytest.raises(ValueError, match="failure_rate"):
+        ChaosStore(MemoryStore(), failure_rate=1.5)
+
+
+@pytest.mark.asyncio
+async def test_chaos_async():
+    store = ChaosStore(MemoryStore(), failure_rate=1.0)
+
+    with pytest.raises(GenericError):
+        await obs.put_async(store, "file.txt", b"foo")
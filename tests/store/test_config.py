@@ -1,6 +1,9 @@
+import json
 from datetime import timedelta
 
-from obstore.store import HTTPStore
+import pytest
+
+from obstore.store import GCSStore, HTTPStore, LocalStore, S3Store
 
 
 def test_config_timedelta():
@@ -8,3 +11,62 @@ def test_config_timedelta():
         "https://example.com",
         client_options={"timeout": timedelta(seconds=30)},
     )
+
+
+def test_config_dict_round_trip():
+    store = S3Store(
+        "bucket",
+        prefix="data",
+        region="us-east-1",
+        secret_access_key="secret",  # noqa: S106
+        access_key_id="key",
+        client_options={"timeout": "30s"},
+        retry_config={"max_retries": 3, "retry_timeout": timedelta(seconds=60)},
+        request_tags={"team": "ml"},
+    )
+    config_dict = store.to_config_dict()
+    assert config_dict["type"] == "s3"
+    assert config_dict["retry_config"]["retry_timeout"] == 60
+    assert config_dict["config"]["secret_access_key"] == "secret"  # noqa: S105
+
+    config_dict = json.loads(json.dumps(config_dict))
+    new_store = S3Store.from_config_dict(config_dict)
+    assert new_store == store
+
+
+def test_config_dict_mask_secrets():
+    store = S3Store(
+        "bucket",
+        secret_access_key="secret",  # noqa: S106
+        access_key_id="key",
+    )
+    config_dict = store.to_config_dict(mask_secrets=True)
+    assert "secret_access_key" not in config_dict["config"]
+    assert config_dict["config"]["access_key_id"] == "key"
+
+
+def test_config_dict_http_local(tmp_path):
+    store = HTTPStore.from_url("https://example.com/data")
+    config_dict = json.loads(json.dumps(store.to_config_dict()))
+    assert config_dict["url"] == "https://example.com/data"
+    assert HTTPStore.from_config_dict(config_dict) == store
+
+    store = LocalStore(tmp_path, mkdir=True)
+    config_dict = json.loads(json.dumps(store.to_config_dict()))
+    assert config_dict["type"] == "local"
+    assert LocalStore.from_config_dict(config_dict) == store
+
+
+def test_config_dict_wrong_type():
+    config_dict = GCSStore("bucket").to_config_dict()
+    with pytest.raises(ValueError, match="Expected a config dict of type 's3'"):
+        S3Store.from_config_dict(config_dict)
+
+
+def test_config_dict_credential_provider():
+    def credential_provider():
+        return {"access_key_id": "key", "secret_access_key": "secret"}
+
+    store = S3Store("bucket", credential_provider=credential_provider)  # type: ignore
+    with pytest.raises(ValueError, match="credential_provider"):
+        store.to_config_dict()
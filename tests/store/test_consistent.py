@@ -0,0 +1,82 @@
+import time
+from datetime import timedelta
+
+import pytest
+
+import obstore as obs
+from obstore.store import ConsistentStore, MemoryStore
+
+
+def test_consistent_read_after_write():
+    store = ConsistentStore(MemoryStore(), retry_delay=timedelta(seconds=1))
+
+    start = time.monotonic()
+    obs.put(store, "file.txt", b"foo")
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+    obs.put(store, "file.txt", b"bar")
+    assert obs.head(store, "file.txt")["size"] == 3
+    assert obs.get(store, "file.txt").bytes() == b"bar"
+    obs.delete(store, "file.txt")
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "file.txt")
+
+    # Reads that observe the written version are not retried
+    assert time.monotonic() - start < 1
+
+
+def test_consistent_retries_stale_reads():
+    # Writes that bypass the wrapper look like a stale read of the tracked version
+    inner = MemoryStore()
+    store = ConsistentStore(
+        inner,
+        max_retries=2,
+        retry_delay=timedelta(milliseconds=50),
+    )
+
+    obs.put(store, "file.txt", b"foo")
+    obs.put(inner, "file.txt", b"bar")
+    start = time.monotonic()
+    # Once the retries are exhausted, the last response is returned
+    assert obs.get(store, "file.txt").bytes() == b"bar"
+    assert time.monotonic() - start >= 0.15
+
+    obs.delete(store, "file.txt")
+    obs.put(inner, "file.txt", b"baz")
+    start = time.monotonic()
+    assert obs.get(store, "file.txt").bytes() == b"baz"
+    assert time.monotonic() - start >= 0.15
+
+
+def test_consistent_forget():
+    inner = MemoryStore()
+    store = ConsistentStore(inner, retry_delay=timedelta(seconds=1))
+
+    obs.put(store, "a.txt", b"foo")
+    obs.put(store, "b.txt", b"foo")
+    obs.put(inner, "a.txt", b"bar")
+    obs.put(inner, "b.txt", b"bar")
+
+    start = time.monotonic()
+    store.forget("a.txt")
+    assert obs.get(store, "a.txt").bytes() == b"bar"
+    store.forget()
+    assert obs.get(store, "b.txt").bytes() == b"bar"
+    assert time.monotonic() - start < 1
+
+
+def test_consistent_multipart():
+    inner = MemoryStore()
+    store = ConsistentStore(
+        inner,
+        max_retries=1,
+        retry_delay=timedelta(milliseconds=100),
+    )
+
+    with obs.open_writer(store, "file.txt") as f:
+        f.write(b"foo")
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+    obs.put(inner, "file.txt", b"bar")
+    start = time.monotonic()
+    assert obs.get(store, "file.txt").bytes() == b"bar"
+    assert time.monotonic() - start >= 0.1
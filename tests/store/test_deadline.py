@@ -0,0 +1,61 @@
+import asyncio
+import sys
+from datetime import timedelta
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import TimeoutError as ObstoreTimeoutError
+from obstore.store import DeadlineStore, MemoryStore, ThrottleStore
+
+
+def test_deadline_not_reached():
+    store = DeadlineStore(MemoryStore(), timedelta(minutes=1))
+
+    obs.put(store, "file.txt", b"foo")
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+    assert len(obs.list(store).collect()) == 1
+
+    remaining = store.remaining
+    assert remaining is not None
+    assert timedelta(0) < remaining <= timedelta(minutes=1)
+
+
+def test_deadline_passed():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"foo")
+    store = DeadlineStore(inner, timedelta(0))
+    assert store.remaining == timedelta(0)
+
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        obs.get(store, "file.txt")
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        obs.put(store, "other.txt", b"bar")
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        obs.list(store).collect()
+
+
+def test_deadline_cancels_in_progress_call():
+    slow = ThrottleStore(MemoryStore(), {"wait_put_per_call": timedelta(seconds=5)})
+    store = DeadlineStore(slow, timedelta(milliseconds=50))
+
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        obs.put(store, "file.txt", b"foo")
+
+
+def test_deadline_invalid():
+    with pytest.raises(ValueError, match="timedelta or an asyncio.Timeout"):
+        DeadlineStore(MemoryStore(), "soon")  # type: ignore
+
+
+@pytest.mark.skipif(sys.version_info < (3, 11), reason="asyncio.timeout is 3.11+")
+@pytest.mark.asyncio
+async def test_deadline_from_asyncio_timeout():
+    async with asyncio.timeout(60) as timeout:
+        store = DeadlineStore(MemoryStore(), timeout)
+        remaining = store.remaining
+        assert remaining is not None
+        assert timedelta(seconds=59) < remaining <= timedelta(seconds=60)
+
+    async with asyncio.timeout(None) as timeout:
+        assert DeadlineStore(MemoryStore(), timeout).remaining is None
@@ -0,0 +1,91 @@
+import pickle
+from collections import UserDict
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import AlreadyExistsError, GenericError
+from obstore.store import DictStore
+
+
+def test_put_get_list_delete():
+    contents = {"a/1.txt": b"foo", "a/b/2.txt": bytearray(b"bar")}
+    store = DictStore(contents)
+    assert store.mapping is contents
+
+    obs.put(store, "c.txt", b"hello world")
+    assert contents["c.txt"] == b"hello world"
+    assert obs.get(store, "a/b/2.txt").bytes() == b"bar"
+    assert obs.get_range(store, "c.txt", start=6, end=11) == b"world"
+    assert obs.head(store, "c.txt")["size"] == 11
+
+    listed = obs.list(store).collect()
+    assert [meta["path"] for meta in listed] == ["a/1.txt", "a/b/2.txt", "c.txt"]
+    result = obs.list_with_delimiter(store, "a")
+    assert result["common_prefixes"] == ["a/b"]
+    assert [meta["path"] for meta in result["objects"]] == ["a/1.txt"]
+
+    obs.delete(store, "a/1.txt")
+    assert "a/1.txt" not in contents
+    # Deleting an object that doesn't exist succeeds
+    obs.delete(store, "a/1.txt")
+
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "a/1.txt")
+
+
+@pytest.mark.asyncio
+async def test_async():
+    store = DictStore()
+    await obs.put_async(store, "file.txt", b"foo")
+    assert store.mapping == {"file.txt": b"foo"}
+    resp = await obs.get_async(store, "file.txt")
+    assert await resp.bytes_async() == b"foo"
+
+
+def test_put_modes_and_copy():
+    store = DictStore()
+    obs.put(store, "a.txt", b"foo", mode="create")
+    with pytest.raises(AlreadyExistsError):
+        obs.put(store, "a.txt", b"bar", mode="create")
+
+    obs.copy(store, "a.txt", "b.txt")
+    with pytest.raises(AlreadyExistsError):
+        obs.copy(store, "a.txt", "b.txt", overwrite=False)
+    obs.rename(store, "b.txt", "c.txt")
+    assert store.mapping == {"a.txt": b"foo", "c.txt": b"foo"}
+
+
+def test_multipart():
+    store = DictStore()
+    chunks = [b"a" * 10, b"b" * 10, b"c" * 5]
+    obs.put(store, "big.bin", iter(chunks), use_multipart=True, chunk_size=10)
+    assert store.mapping == {"big.bin": b"".join(chunks)}
+
+
+def test_custom_mapping():
+    class FailingDict(UserDict):
+        def __getitem__(self, key):
+            if key == "broken":
+                msg = "Backend unavailable"
+                raise RuntimeError(msg)
+            return super().__getitem__(key)
+
+    store = DictStore(FailingDict({"file.txt": b"foo"}))
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+    with pytest.raises(GenericError, match="Backend unavailable"):
+        obs.get(store, "broken")
+
+    with pytest.raises(TypeError, match="mutable mapping"):
+        DictStore([b"foo"])  # type: ignore[arg-type]
+
+
+def test_eq_pickle():
+    contents = {"file.txt": b"foo"}
+    store = DictStore(contents)
+    assert store == DictStore(contents)
+    assert store != DictStore({"file.txt": b"foo"})
+
+    new_store: DictStore = pickle.loads(pickle.dumps(store))
+    assert new_store.mapping == contents
+    assert repr(store) == "DictStore(dict)"
@@ -0,0 +1,108 @@
+import os
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import GenericError
+from obstore.store import EncryptedStore, MemoryStore
+
+
+def test_roundtrip():
+    inner = MemoryStore()
+    store = EncryptedStore(inner, os.urandom(32))
+
+    data = os.urandom(200_000)
+    obs.put(store, "file.bin", data, attributes={"Content-Type": "text/plain"})
+
+    resp = obs.get(store, "file.bin")
+    assert resp.attributes == {"Content-Type": "text/plain"}
+    assert resp.bytes() == data
+
+    # The inner store only holds the ciphertext
+    encrypted = obs.get(inner, "file.bin").bytes()
+    assert data[:1000] not in encrypted
+    assert obs.head(store, "file.bin").size == len(data)
+    assert obs.list(store).collect()[0].size == len(data)
+
+
+@pytest.mark.parametrize(
+    ("start", "end"),
+    [(0, 10), (65_530, 65_540), (100_000, 200_000), (131_072, 131_073)],
+)
+def test_get_range(start: int, end: int):
+    store = EncryptedStore(MemoryStore(), os.urandom(32))
+    data = os.urandom(200_000)
+    obs.put(store, "file.bin", data)
+
+    buffer = obs.get_range(store, "file.bin", start=start, end=end)
+    assert memoryview(buffer) == data[start:end]
+
+
+def test_get_suffix_and_offset():
+    store = EncryptedStore(MemoryStore(), os.urandom(32))
+    data = os.urandom(100_000)
+    obs.put(store, "file.bin", data)
+
+    resp = obs.get(store, "file.bin", options={"range": {"suffix": 70_000}})
+    assert resp.bytes() == data[-70_000:]
+    resp = obs.get(store, "file.bin", options={"range": {"offset": 1}})
+    assert resp.bytes() == data[1:]
+
+
+def test_empty_object():
+    store = EncryptedStore(MemoryStore(), os.urandom(32))
+    obs.put(store, "empty.bin", b"")
+    assert obs.get(store, "empty.bin").bytes() == b""
+    assert obs.head(store, "empty.bin").size == 0
+
+
+def test_multipart():
+    store = EncryptedStore(MemoryStore(), os.urandom(32))
+    data = os.urandom(1_000_000)
+    obs.put(store, "file.bin", data, use_multipart=True, chunk_size=100_000)
+    assert obs.get(store, "file.bin").bytes() == data
+
+
+def test_wrong_key():
+    inner = MemoryStore()
+    obs.put(EncryptedStore(inner, os.urandom(32)), "file.bin", b"foo")
+
+    with pytest.raises(GenericError, match="unwrap content key"):
+        obs.get(EncryptedStore(inner, os.urandom(32)), "file.bin")
+
+
+def test_unencrypted_object():
+    inner = MemoryStore()
+    obs.put(inner, "file.bin", b"foo")
+
+    with pytest.raises(GenericError, match="not encrypted"):
+        obs.get(EncryptedStore(inner, os.urandom(32)), "file.bin")
+
+
+def test_key_provider():
+    master_key = os.urandom(32)
+
+    class XorKeyProvider:
+        def __init__(self):
+            self.calls = 0
+
+        def wrap_key(self, key: bytes) -> bytes:
+            self.calls += 1
+            return bytes(a ^ b for a, b in zip(key, master_key))
+
+        def unwrap_key(self, wrapped_key: bytes) -> bytes:
+            self.calls += 1
+            return bytes(a ^ b for a, b in zip(wrapped_key, master_key))
+
+    provider = XorKeyProvider()
+    store = EncryptedStore(MemoryStore(), provider)
+    obs.put(store, "file.bin", b"foo")
+    assert obs.get(store, "file.bin").bytes() == b"foo"
+    assert provider.calls == 2
+
+
+def test_invalid_key_provider():
+    with pytest.raises(ValueError, match="32 bytes"):
+        EncryptedStore(MemoryStore(), b"short")
+    with pytest.raises(TypeError):
+        EncryptedStore(MemoryStore(), "not a key")  # type: ignore[arg-type]
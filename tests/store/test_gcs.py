@@ -1,6 +1,10 @@
+import json
+import pickle
+
 import pytest
 
-from obstore.exceptions import BaseError
+import obstore as obs
+from obstore.exceptions import BaseError, UnauthenticatedError
 from obstore.store import GCSStore
 
 
@@ -19,3 +23,30 @@ def test_eq():
     assert store == store  # noqa: PLR0124
     assert store == store2
     assert store != store3
+
+
+def test_request_tags():
+    store = GCSStore("bucket", request_tags={"team": "ml"})
+    assert store.request_tags == {"team": "ml"}
+
+    with pytest.raises(ValueError, match="at most 4 request tags"):
+        GCSStore("bucket", request_tags={f"key{i}": "value" for i in range(5)})
+
+    with pytest.raises(ValueError, match="too long"):
+        GCSStore("bucket", request_tags={"team": "x" * 65})
+
+
+def test_builtin_credential_provider_invalid():
+    with pytest.raises(ValueError, match="Unknown credential_provider"):
+        GCSStore("bucket", credential_provider="impersonate:")
+
+
+def test_builtin_adc_credential_provider(tmp_path, monkeypatch: pytest.MonkeyPatch):
+    credentials_file = tmp_path / "credentials.json"
+    credentials_file.write_text(json.dumps({"type": "external_account"}))
+    monkeypatch.setenv("GOOGLE_APPLICATION_CREDENTIALS", str(credentials_file))
+
+    store = GCSStore("bucket", credential_provider="adc")
+    assert pickle.loads(pickle.dumps(store)) == store
+    with pytest.raises(UnauthenticatedError, match="Unsupported credentials type"):
+        obs.head(store, "file.txt")
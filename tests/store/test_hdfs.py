@@ -0,0 +1,287 @@
+import json
+import pickle
+import threading
+import time
+from http.server import BaseHTTPRequestHandler, ThreadingHTTPServer
+from urllib.parse import parse_qs, unquote, urlsplit
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import (
+    AlreadyExistsError,
+    PermissionDeniedError,
+)
+from obstore.store import HdfsStore
+
+PREFIX = "/webhdfs/v1"
+
+
+@pytest.fixture
+def webhdfs_server():
+    """Serve a minimal WebHDFS API, with files kept in memory.
+
+    Like a namenode, writes are redirected once before their data is accepted.
+    """
+    files: dict[str, tuple[bytes, int]] = {}
+    dirs: set[str] = {"/"}
+    params_seen: list[dict[str, str]] = []
+    lock = threading.Lock()
+
+    def status(path: str, suffix: str = "") -> dict:
+        if path in files:
+            data, mtime = files[path]
+            return {
+                "pathSuffix": suffix,
+                "length": len(data),
+                "modificationTime": mtime,
+                "type": "FILE",
+            }
+        return {
+            "pathSuffix": suffix,
+            "length": 0,
+            "modificationTime": 0,
+            "type": "DIRECTORY",
+        }
+
+    def add_parents(path: str):
+        parts = path.strip("/").split("/")
+        for i in range(len(parts)):
+            dirs.add("/" + "/".join(parts[:i]))
+
+    class Handler(BaseHTTPRequestHandler):
+        def parse(self) -> tuple[str, dict[str, str]]:
+            url = urlsplit(self.path)
+            path = unquote(url.path).removeprefix(PREFIX).rstrip("/") or "/"
+            params = {key: values[0] for key, values in parse_qs(url.query).items()}
+            params_seen.append(params)
+            return path, params
+
+        def body(self) -> bytes:
+            return self.rfile.read(int(self.headers.get("Content-Length", 0)))
+
+        def respond(self, status: int, body: bytes = b"", **headers: str):
+            self.send_response(status)
+            for name, value in headers.items():
+                self.send_header(name, value)
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def json(self, value: dict, status: int = 200):
+            self.respond(status, json.dumps(value).encode())
+
+        def exception(self, status: int, exception: str, message: str):
+            self.json(
+                {
+                    "RemoteException": {
+                        "exception": exception,
+                        "javaClassName": f"java.io.{exception}",
+                        "message": message,
+                    },
+                },
+                status,
+            )
+
+        def redirect_write(self, params: dict[str, str]) -> bool:
+            if "datanode" in params:
+                return False
+            self.body()
+            self.respond(307, Location=self.path + "&datanode=true")
+            return True
+
+        def do_GET(self):
+            path, params = self.parse()
+            if path.startswith("/denied"):
+                self.exception(403, "AccessControlException", "Permission denied")
+                return
+            with lock:
+                if path not in files and path not in dirs:
+                    self.exception(404, "FileNotFoundException", f"{path} not found")
+                elif params["op"] == "GETFILESTATUS":
+                    self.json({"FileStatus": status(path)})
+                elif params["op"] == "LISTSTATUS":
+                    if path in files:
+                        entries = [status(path)]
+                    else:
+                        base = path.rstrip("/") + "/"
+                        names = {
+                            child[len(base) :].split("/")[0]
+                            for child in [*files, *dirs]
+                            if child.startswith(base) and child != base
+                        }
+                        entries = [status(base + name, name) for name in sorted(names)]
+                    self.json({"FileStatuses": {"FileStatus": entries}})
+                elif params["op"] == "OPEN":
+                    data, _ = files[path]
+                    offset = int(params.get("offset", 0))
+                    length = int(params.get("length", len(data)))
+                    self.respond(200, data[offset : offset + length])
+                else:
+                    self.exception(400, "IllegalArgumentException", "Invalid op")
+
+        def do_PUT(self):
+            path, params = self.parse()
+            if params["op"] == "CREATE" and self.redirect_write(params):
+                return
+            data = self.body()
+            with lock:
+                if params["op"] == "CREATE":
+                    if path in files and params.get("overwrite") == "false":
+                        self.exception(
+                            403,
+                            "FileAlreadyExistsException",
+                            f"{path} already exists",
+                        )
+                        return
+                    files[path] = (data, int(time.time() * 1000))
+                    add_parents(path)
+                    self.respond(201)
+                elif params["op"] == "MKDIRS":
+                    dirs.add(path)
+                    add_parents(path)
+                    self.json({"boolean": True})
+                elif params["op"] == "RENAME":
+                    destination = params["destination"]
+                    if path not in files or destination in files:
+                        self.json({"boolean": False})
+                        return
+                    files[destination] = files.pop(path)
+                    self.json({"boolean": True})
+                else:
+                    self.exception(400, "IllegalArgumentException", "Invalid op")
+
+        def do_POST(self):
+            path, params = self.parse()
+            if self.redirect_write(params):
+                return
+            data = self.body()
+            with lock:
+                old, _ = files[path]
+                files[path] = (old + data, int(time.time() * 1000))
+                self.respond(200)
+
+        def do_DELETE(self):
+            path, _ = self.parse()
+            with lock:
+                deleted = files.pop(path, None) is not None
+                self.json({"boolean": deleted})
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}", files, params_seen
+    server.shutdown()
+
+
+def test_pickle():
+    store = HdfsStore("webhdfs://namenode.example.com/data", user="etl")
+    new_store: HdfsStore = pickle.loads(pickle.dumps(store))
+    assert new_store == store
+    assert new_store.user == "etl"
+
+
+def test_eq():
+    store = HdfsStore("webhdfs://namenode.example.com/data", user="etl")
+    store2 = HdfsStore.from_url("webhdfs://namenode.example.com/data", user="etl")
+    store3 = HdfsStore("webhdfs://namenode.example.com/data", user="other")
+    assert store == store  # noqa: PLR0124
+    assert store == store2
+    assert store != store3
+
+
+def test_invalid_config():
+    with pytest.raises(ValueError, match="webhdfs"):
+        HdfsStore("s3://bucket/data")
+    with pytest.raises(ValueError, match="Only one of user and delegation_token"):
+        HdfsStore(
+            "webhdfs://namenode.example.com",
+            user="etl",
+            delegation_token="token",  # noqa: S106
+        )
+
+
+def test_put_get_list_delete(webhdfs_server):
+    url, files, params_seen = webhdfs_server
+    store = HdfsStore(f"{url}/data", user="etl")
+
+    obs.put(store, "dir/a.txt", b"hello world")
+    obs.put(store, "b.txt", b"foo")
+    assert files["/data/dir/a.txt"][0] == b"hello world"
+    assert all(params["user.name"] == "etl" for params in params_seen)
+
+    assert obs.get(store, "dir/a.txt").bytes() == b"hello world"
+    assert obs.get_range(store, "dir/a.txt", start=6, end=11) == b"world"
+    assert obs.head(store, "b.txt")["size"] == 3
+
+    listed = obs.list(store).collect()
+    assert sorted(meta["path"] for meta in listed) == ["b.txt", "dir/a.txt"]
+    result = obs.list_with_delimiter(store)
+    assert result["common_prefixes"] == ["dir"]
+    assert [meta["path"] for meta in result["objects"]] == ["b.txt"]
+
+    obs.delete(store, "b.txt")
+    assert "/data/b.txt" not in files
+    # Deleting an object that doesn't exist succeeds
+    obs.delete(store, "b.txt")
+
+
+def test_put_modes(webhdfs_server):
+    url, _, _ = webhdfs_server
+    store = HdfsStore(f"{url}/data")
+
+    obs.put(store, "a.txt", b"foo", mode="create")
+    with pytest.raises(AlreadyExistsError):
+        obs.put(store, "a.txt", b"bar", mode="create")
+    obs.put(store, "a.txt", b"bar")
+    assert obs.get(store, "a.txt").bytes() == b"bar"
+
+
+def test_multipart(webhdfs_server):
+    url, files, _ = webhdfs_server
+    store = HdfsStore(f"{url}/webhdfs/v1/data")
+
+    chunks = [b"a" * 10, b"b" * 10, b"c" * 5]
+    obs.put(store, "big.bin", iter(chunks), use_multipart=True, chunk_size=10)
+    assert obs.get(store, "big.bin").bytes() == b"".join(chunks)
+    # The staging file was moved into place
+    assert list(files) == ["/data/big.bin"]
+
+
+def test_rename_copy(webhdfs_server):
+    url, files, _ = webhdfs_server
+    store = HdfsStore(f"{url}/data")
+
+    obs.put(store, "a.txt", b"foo")
+    obs.copy(store, "a.txt", "b.txt")
+    obs.rename(store, "b.txt", "sub/c.txt")
+    assert sorted(files) == ["/data/a.txt", "/data/sub/c.txt"]
+    with pytest.raises(AlreadyExistsError):
+        obs.rename(store, "a.txt", "sub/c.txt", overwrite=False)
+
+
+def test_errors(webhdfs_server):
+    url, _, _ = webhdfs_server
+    store = HdfsStore(url)
+
+    with pytest.raises(FileNotFoundError, match="FileNotFoundException"):
+        obs.get(store, "missing.txt")
+    obs.put(store, "dir/a.txt", b"foo")
+    with pytest.raises(FileNotFoundError):
+        # A directory isn't an object
+        obs.get(store, "dir")
+    with pytest.raises(PermissionDeniedError, match="AccessControlException"):
+        obs.get(store, "denied/a.txt")
+    assert obs.list(store, "missing").collect() == []
+
+
+def test_delegation_token(webhdfs_server):
+    url, _, params_seen = webhdfs_server
+    store = HdfsStore(url, delegation_token="token")  # noqa: S106
+
+    obs.put(store, "a.txt", b"foo")
+    assert all(params["delegation"] == "token" for params in params_seen)
+    assert all("user.name" not in params for params in params_seen)
@@ -1,5 +1,23 @@
+import asyncio
+import logging
 import pickle
+import select
+import threading
+import time
+from datetime import datetime, timedelta, timezone
+from http.server import BaseHTTPRequestHandler, HTTPServer, ThreadingHTTPServer
+from urllib.parse import quote, unquote
 
+import pytest
+
+import obstore as obs
+from obstore.exceptions import (
+    BaseError,
+    ConnectError,
+    GenericError,
+    StreamTimeoutError,
+)
+from obstore.exceptions import TimeoutError as ObstoreTimeoutError
 from obstore.store import HTTPStore
 
 
@@ -19,3 +37,635 @@ def test_eq():
     assert store == store  # noqa: PLR0124
     assert store == store2
     assert store != store3
+
+
+@pytest.fixture
+def throttling_server():
+    """Serve `data.txt`, responding 429 to the first `throttled` requests."""
+    state = {"throttled": 0, "requests": 0}
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            state["requests"] += 1
+            state["user_agent"] = self.headers.get("User-Agent")
+            if state["requests"] <= state["throttled"]:
+                self.send_response(429)
+                self.send_header("Retry-After", "1")
+                self.send_header("Content-Length", "0")
+                self.end_headers()
+                return
+            self.send_response(200)
+            self.send_header("Content-Length", "3")
+            self.end_headers()
+            self.wfile.write(b"foo")
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}", state
+    server.shutdown()
+
+
+def test_respect_retry_after(throttling_server):
+    url, state = throttling_server
+    state["throttled"] = 1
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True},
+        retry_config={"respect_retry_after": True},
+    )
+
+    start = time.monotonic()
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert time.monotonic() - start >= 1
+    assert state["requests"] == 2
+    assert store.retry_config is not None
+    assert store.retry_config["respect_retry_after"]
+
+
+def test_throttle_retries_exhausted(throttling_server):
+    url, state = throttling_server
+    state["throttled"] = 100
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True},
+        retry_config={
+            "max_retries": 2,
+            "throttle_backoff": {"init_backoff": timedelta(milliseconds=10)},
+        },
+    )
+
+    with pytest.raises(GenericError, match="throttled"):
+        obs.get(store, "data.txt")
+    # Throttled requests aren't retried again by the standard retry policy
+    assert state["requests"] == 3
+
+
+def test_on_retry(throttling_server):
+    url, state = throttling_server
+    state["throttled"] = 2
+    events = []
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True},
+        retry_config={
+            "throttle_backoff": {"init_backoff": timedelta(milliseconds=10)},
+            "on_retry": events.append,
+        },
+    )
+
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert [event["status"] for event in events] == [429, 429]
+    assert [event["consecutive_failures"] for event in events] == [1, 2]
+    assert events[0]["method"] == "GET"
+    assert events[0]["url"] == f"{url}/data.txt"
+
+
+def test_fail_fast_after(throttling_server):
+    url, state = throttling_server
+    state["throttled"] = 100
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True},
+        retry_config={
+            "max_retries": 1,
+            "throttle_backoff": {"init_backoff": timedelta(milliseconds=10)},
+            "fail_fast_after": 2,
+        },
+    )
+
+    with pytest.raises(GenericError):
+        obs.get(store, "data.txt")
+    assert state["requests"] == 2
+
+    # The circuit is open, so the request fails without being sent
+    with pytest.raises(GenericError, match="Failing fast"):
+        obs.get(store, "data.txt")
+    assert state["requests"] == 2
+
+
+@pytest.fixture
+def failing_server():
+    """Respond 400 to `client-error` and 500 to any other path, with a body."""
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            if self.path == "/client-error":
+                status, body = 400, b"bad request"
+            else:
+                status, body = 500, b"backend unavailable"
+            self.send_response(status)
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}"
+    server.shutdown()
+
+
+def test_error_response_details(failing_server):
+    retry_config = {
+        "max_retries": 2,
+        "backoff": {"init_backoff": timedelta(milliseconds=10)},
+    }
+    store = HTTPStore.from_url(
+        failing_server,
+        client_options={"allow_http": True},
+        retry_config=retry_config,
+    )
+
+    # Client errors aren't retried, and have their status and body in the message
+    with pytest.raises(GenericError, match="bad request") as exc:
+        obs.get(store, "client-error")
+    assert exc.value.status is None
+    assert exc.value.body is None
+    assert exc.value.retries is None
+
+    with pytest.raises(GenericError) as exc:
+        obs.get(store, "server-error")
+    assert exc.value.status == 500
+    assert exc.value.body is None
+    assert exc.value.retries == 2
+
+    store = HTTPStore.from_url(
+        failing_server,
+        client_options={"allow_http": True},
+        retry_config={**retry_config, "retry_error_body": True},
+    )
+    with pytest.raises(GenericError) as exc:
+        obs.get(store, "server-error")
+    assert exc.value.status == 500
+    assert exc.value.body == "backend unavailable"
+    assert exc.value.retries == 2
+
+
+def test_connect_error():
+    # Find a port that nothing listens on
+    server = HTTPServer(("localhost", 0), BaseHTTPRequestHandler)
+    port = server.server_port
+    server.server_close()
+
+    store = HTTPStore.from_url(
+        f"http://localhost:{port}",
+        client_options={"allow_http": True},
+        retry_config={"max_retries": 0},
+    )
+    with pytest.raises(ConnectError):
+        obs.get(store, "data.txt")
+
+
+def test_timeout_error():
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            self.send_response(200)
+            self.send_header("Content-Length", "6")
+            self.end_headers()
+            self.wfile.write(b"foo")
+            self.wfile.flush()
+            time.sleep(1)
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    try:
+        store = HTTPStore.from_url(
+            f"http://localhost:{server.server_port}",
+            client_options={"allow_http": True, "timeout": "100ms"},
+            retry_config={"max_retries": 0},
+        )
+        with pytest.raises(ObstoreTimeoutError) as exc:
+            obs.get(store, "data.txt").bytes()
+        assert isinstance(exc.value, BaseError)
+        assert isinstance(exc.value, TimeoutError)
+    finally:
+        server.shutdown()
+
+
+def test_log_requests(throttling_server, caplog):
+    url, _ = throttling_server
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True, "log_requests": True},
+    )
+    assert store.client_options is not None
+    assert store.client_options["log_requests"]
+
+    with caplog.at_level(logging.INFO, logger="obstore.http"):
+        obs.get(store, "data.txt").bytes()
+
+    records = [r for r in caplog.records if r.name == "obstore.http"]
+    assert len(records) == 1
+    assert records[0].getMessage().startswith(f"GET {url}/data.txt -> 200 OK")
+
+
+def test_resolver(throttling_server):
+    url, _ = throttling_server
+    port = url.rsplit(":", 1)[1]
+    resolver = {"Obstore.invalid": "127.0.0.1"}
+    store = HTTPStore.from_url(
+        f"http://obstore.invalid:{port}",
+        client_options={"allow_http": True},
+        resolver=resolver,
+    )
+
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert store.resolver == resolver
+
+    new_store: HTTPStore = pickle.loads(pickle.dumps(store))
+    assert new_store == store
+
+
+def test_resolver_callable(throttling_server):
+    url, _ = throttling_server
+    port = url.rsplit(":", 1)[1]
+    hosts = []
+
+    def resolver(host: str):
+        hosts.append(host)
+        return ["127.0.0.1"] if host == "obstore.invalid" else None
+
+    store = HTTPStore.from_url(
+        f"http://obstore.invalid:{port}",
+        client_options={"allow_http": True},
+        resolver=resolver,
+    )
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert hosts == ["obstore.invalid"]
+
+    # Hostnames the resolver doesn't know about fall back to the system's DNS
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True},
+        resolver=resolver,
+    )
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert hosts == ["obstore.invalid", "localhost"]
+
+
+def test_resolver_invalid():
+    with pytest.raises(ValueError, match="Invalid address for host 'example.com'"):
+        HTTPStore.from_url(
+            "https://example.com",
+            resolver={"example.com": "not-an-address"},
+        )
+
+    with pytest.raises(TypeError, match="Expected resolver"):
+        HTTPStore.from_url("https://example.com", resolver=1)  # type: ignore
+
+
+def test_request_tags(throttling_server):
+    url, state = throttling_server
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True, "user_agent": "my-app/1.0"},
+        request_tags={"team": "ml", "job": "train-1"},
+    )
+
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert state["user_agent"] == "my-app/1.0 job/train-1 team/ml"
+    assert store.request_tags == {"job": "train-1", "team": "ml"}
+
+
+def test_request_tags_invalid():
+    with pytest.raises(ValueError, match="Invalid request tag"):
+        HTTPStore.from_url("https://example.com", request_tags={"team": "m l"})
+
+
+@pytest.fixture
+def keep_alive_server():
+    """Serve `data.txt` over persistent connections, recording the client ports."""
+    ports = []
+
+    class Handler(BaseHTTPRequestHandler):
+        protocol_version = "HTTP/1.1"
+
+        def do_GET(self):
+            ports.append(self.client_address[1])
+            self.send_response(200)
+            self.send_header("Content-Length", "3")
+            self.end_headers()
+            self.wfile.write(b"foo")
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("localhost", 0), Handler)
+    server.daemon_threads = True
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}", ports
+    server.shutdown()
+
+
+def test_reset_connections(keep_alive_server):
+    url, ports = keep_alive_server
+    store = HTTPStore.from_url(url, client_options={"allow_http": True})
+
+    obs.get(store, "data.txt").bytes()
+    obs.get(store, "data.txt").bytes()
+    assert ports[0] == ports[1]
+
+    store.reset_connections()
+    assert obs.get(store, "data.txt").bytes() == b"foo"
+    assert ports[2] != ports[1]
+
+
+def test_connection_max_lifetime(keep_alive_server):
+    url, ports = keep_alive_server
+    store = HTTPStore.from_url(
+        url,
+        client_options={
+            "allow_http": True,
+            "connection_max_lifetime": timedelta(milliseconds=200),
+        },
+    )
+    assert store.client_options is not None
+    assert store.client_options["connection_max_lifetime"] == "200ms"
+
+    obs.get(store, "data.txt").bytes()
+    obs.get(store, "data.txt").bytes()
+    time.sleep(0.3)
+    obs.get(store, "data.txt").bytes()
+    assert ports[0] == ports[1]
+    assert ports[2] != ports[1]
+
+    new_store: HTTPStore = pickle.loads(pickle.dumps(store))
+    assert new_store == store
+
+
+def test_connection_max_lifetime_invalid():
+    with pytest.raises(ValueError, match="connection_max_lifetime"):
+        HTTPStore.from_url(
+            "https://example.com",
+            client_options={"connection_max_lifetime": "soon"},
+        )
+
+
+@pytest.fixture
+def hanging_server():
+    """Never respond, recording when the client closes the connection."""
+    state = {"connected": threading.Event(), "disconnected": threading.Event()}
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            state["connected"].set()
+            readable, _, _ = select.select([self.connection], [], [], 10)
+            if readable and self.connection.recv(1) == b"":
+                state["disconnected"].set()
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("localhost", 0), Handler)
+    server.daemon_threads = True
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}", state
+    server.shutdown()
+
+
+@pytest.mark.asyncio
+async def test_cancel_get_async(hanging_server):
+    url, state = hanging_server
+    store = HTTPStore.from_url(url, client_options={"allow_http": True})
+
+    task = asyncio.ensure_future(obs.get_async(store, "data.txt"))
+    assert await asyncio.to_thread(state["connected"].wait, 5)
+    task.cancel()
+    with pytest.raises(asyncio.CancelledError):
+        await task
+
+    # The request is dropped rather than left running in the background
+    assert await asyncio.to_thread(state["disconnected"].wait, 5)
+
+
+@pytest.fixture
+def stalling_server():
+    """Serve `data.txt`, stalling after sending the first half of its body."""
+    data = b"0123456789" * 100
+    resume = threading.Event()
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_GET(self):
+            self.send_response(200)
+            self.send_header("Content-Length", str(len(data)))
+            self.end_headers()
+            self.wfile.write(data[:500])
+            self.wfile.flush()
+            resume.wait(10)
+            self.wfile.write(data[500:])
+
+        def log_message(self, *args):
+            pass
+
+    server = ThreadingHTTPServer(("localhost", 0), Handler)
+    server.daemon_threads = True
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}", data, resume
+    resume.set()
+    server.shutdown()
+
+
+def test_stream_chunk_timeout(stalling_server):
+    url, data, resume = stalling_server
+    store = HTTPStore.from_url(url, client_options={"allow_http": True})
+
+    stream = obs.get(store, "data.txt").stream(
+        min_chunk_size=len(data),
+        chunk_timeout=timedelta(milliseconds=200),
+    )
+    # The data received before the stall is returned as a smaller chunk
+    assert next(stream) == data[:500]
+    with pytest.raises(StreamTimeoutError):
+        next(stream)
+
+    # The stream can be resumed after a timeout
+    resume.set()
+    assert b"".join(stream) == data[500:]
+
+
+@pytest.mark.asyncio
+async def test_stream_chunk_timeout_async(stalling_server):
+    url, data, _ = stalling_server
+    store = HTTPStore.from_url(url, client_options={"allow_http": True})
+
+    resp = await obs.get_async(store, "data.txt")
+    stream = resp.stream(chunk_timeout=timedelta(milliseconds=200))
+    assert await stream.__anext__() == data[:500]
+    with pytest.raises(TimeoutError):
+        await stream.__anext__()
+
+
+@pytest.fixture
+def authorized_server():
+    """Serve `data.txt` only to requests with the expected `Authorization` header."""
+
+    class Handler(BaseHTTPRequestHandler):
+        def _respond(self, *, body: bool):
+            if self.headers.get("Authorization") != "Bearer secret":
+                self.send_response(401)
+                self.send_header("Content-Length", "0")
+                self.end_headers()
+                return
+            self.send_response(200)
+            self.send_header("Content-Length", "3")
+            self.send_header("Last-Modified", "Wed, 01 Jan 2025 00:00:00 GMT")
+            self.end_headers()
+            if body:
+                self.wfile.write(b"foo")
+
+        def do_GET(self):
+            self._respond(body=True)
+
+        def do_HEAD(self):
+            self._respond(body=False)
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}"
+    server.shutdown()
+
+
+def test_request_headers(authorized_server):
+    store = HTTPStore.from_url(authorized_server, client_options={"allow_http": True})
+    headers = {"Authorization": "Bearer secret"}
+
+    resp = obs.get(store, "data.txt", options={"headers": headers})
+    assert resp.bytes() == b"foo"
+    assert obs.head(store, "data.txt", headers=headers)["size"] == 3
+
+    # The headers only apply to the request they're passed to
+    with pytest.raises(BaseError):
+        obs.get(store, "data.txt").bytes()
+
+
+def test_request_headers_invalid():
+    store = HTTPStore.from_url("https://example.com")
+    with pytest.raises(ValueError, match="Invalid header name"):
+        obs.get(store, "data.txt", options={"headers": {"bad name": "x"}})
+
+
+@pytest.fixture
+def webdav_server():
+    """Serve a WebDAV tree under `/dav/`, rejecting `Depth: infinity` listings."""
+    files = {"a.txt": b"foo", "dir/b.txt": b"hello", "dir/sub/c d.txt": b"x"}
+    collections = {"", "dir", "dir/sub"}
+    modified = "Wed, 01 Jan 2025 00:00:00 GMT"
+
+    def entry(path: str) -> str:
+        if path in collections:
+            href = quote(f"/dav/{path}/" if path else "/dav/")
+            return (
+                f"<d:response><d:href>{href}</d:href>"
+                "<d:propstat><d:prop><d:resourcetype><d:collection/></d:resourcetype>"
+                f"<d:getlastmodified>{modified}</d:getlastmodified></d:prop>"
+                "<d:status>HTTP/1.1 200 OK</d:status></d:propstat>"
+                "<d:propstat><d:prop><d:getcontentlength/></d:prop>"
+                "<d:status>HTTP/1.1 404 Not Found</d:status></d:propstat>"
+                "</d:response>"
+            )
+        return (
+            f"<d:response><d:href>{quote(f'/dav/{path}')}</d:href>"
+            "<d:propstat><d:prop><d:resourcetype/>"
+            f"<d:getcontentlength>{len(files[path])}</d:getcontentlength>"
+            f"<d:getlastmodified>{modified}</d:getlastmodified>"
+            '<d:getetag>"etag"</d:getetag></d:prop>'
+            "<d:status>HTTP/1.1 200 OK</d:status></d:propstat>"
+            "</d:response>"
+        )
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_PROPFIND(self):
+            self.rfile.read(int(self.headers.get("Content-Length", 0)))
+            path = unquote(self.path).removeprefix("/dav").strip("/")
+            if self.headers.get("Depth") != "1":
+                self.send_response(403)
+                self.send_header("Content-Length", "0")
+                self.end_headers()
+                return
+            if path not in collections:
+                self.send_response(404)
+                self.send_header("Content-Length", "0")
+                self.end_headers()
+                return
+            prefix = f"{path}/" if path else ""
+            children = {
+                prefix + name[len(prefix) :].split("/")[0]
+                for name in [*files, *collections]
+                if name.startswith(prefix) and name != path
+            }
+            body = (
+                '<?xml version="1.0"?><d:multistatus xmlns:d="DAV:">'
+                + entry(path)
+                + "".join(entry(child) for child in sorted(children))
+                + "</d:multistatus>"
+            ).encode()
+            self.send_response(207)
+            self.send_header("Content-Type", "application/xml")
+            self.send_header("Content-Length", str(len(body)))
+            self.end_headers()
+            self.wfile.write(body)
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}/dav/"
+    server.shutdown()
+
+
+def test_webdav_list(webdav_server):
+    store = HTTPStore.from_url(
+        webdav_server,
+        client_options={"allow_http": True},
+        webdav=True,
+    )
+    assert store.webdav
+
+    objects = obs.list(store).collect()
+    assert sorted((meta["path"], meta["size"]) for meta in objects) == [
+        ("a.txt", 3),
+        ("dir/b.txt", 5),
+        ("dir/sub/c d.txt", 1),
+    ]
+    assert objects[0]["last_modified"] == datetime(2025, 1, 1, tzinfo=timezone.utc)
+    assert [meta["path"] for meta in obs.list(store, "dir/sub").collect()] == [
+        "dir/sub/c d.txt",
+    ]
+    assert obs.list(store, "missing").collect() == []
+
+    result = obs.list_with_delimiter(store, "dir")
+    assert result["common_prefixes"] == ["dir/sub"]
+    assert [meta["path"] for meta in result["objects"]] == ["dir/b.txt"]
+    assert result["objects"][0]["e_tag"] == '"etag"'
+
+    new_store: HTTPStore = pickle.loads(pickle.dumps(store))
+    assert new_store == store
+    assert new_store.webdav
+
+
+def test_webdav_disabled(webdav_server):
+    store = HTTPStore.from_url(webdav_server, client_options={"allow_http": True})
+    assert not store.webdav
+    # The server rejects the `Depth: infinity` request made without WebDAV listing
+    with pytest.raises(BaseError):
+        obs.list(store).collect()
@@ -0,0 +1,75 @@
+import pickle
+import sys
+import time
+
+import pytest
+
+import obstore as obs
+from obstore.store import HTTPStore, S3Store
+
+from tests.conftest import TEST_BUCKET_NAME
+
+
+@pytest.mark.skipif(
+    sys.version_info < (3, 10),
+    reason="Moto doesn't seem to support Python 3.9",
+)
+def test_limit_bandwidth(s3: str):
+    store = S3Store(
+        TEST_BUCKET_NAME,
+        endpoint=s3,
+        region="us-east-1",
+        skip_signature=True,
+        client_options={"allow_http": True},
+        limit_config={"max_bytes_per_second": 10_000},
+    )
+
+    # The first second's worth of bytes isn't throttled
+    start = time.monotonic()
+    obs.put(store, "small", b"0" * 5_000)
+    assert time.monotonic() - start < 0.5
+
+    # Uploads and downloads share the limit
+    start = time.monotonic()
+    obs.put(store, "large", b"0" * 10_000)
+    assert obs.get(store, "large").bytes() == b"0" * 10_000
+    assert time.monotonic() - start >= 1
+
+
+@pytest.mark.skipif(
+    sys.version_info < (3, 10),
+    reason="Moto doesn't seem to support Python 3.9",
+)
+def test_limit_concurrent_requests(s3: str):
+    store = S3Store(
+        TEST_BUCKET_NAME,
+        endpoint=s3,
+        region="us-east-1",
+        skip_signature=True,
+        client_options={"allow_http": True},
+        limit_config={"max_concurrent_requests": 1},
+    )
+
+    # A download holds its request until its response body has been read
+    result = obs.get(store, "afile")
+    assert result.bytes() == b"hello world"
+    assert obs.head(store, "afile")["size"] == 11
+
+
+def test_limit_config_pickle():
+    limit_config = {"max_bytes_per_second": 1_000_000, "max_concurrent_requests": 4}
+    store = S3Store("bucket", region="us-east-1", limit_config=limit_config)
+    assert store.limit_config == limit_config
+
+    new_store: S3Store = pickle.loads(pickle.dumps(store))
+    assert new_store.limit_config == limit_config
+    assert store == new_store
+    assert store != S3Store("bucket", region="us-east-1")
+
+    store = HTTPStore.from_url("https://example.com", limit_config=limit_config)
+    assert pickle.loads(pickle.dumps(store)).limit_config == limit_config
+
+
+def test_limit_config_validation():
+    with pytest.raises(ValueError, match="max_concurrent_requests"):
+        S3Store("bucket", limit_config={"max_concurrent_requests": 0})
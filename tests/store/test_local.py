@@ -1,10 +1,11 @@
 import pickle
+import sys
 from pathlib import Path
 
 import pytest
 
 import obstore as obs
-from obstore.exceptions import GenericError
+from obstore.exceptions import AlreadyExistsError, GenericError
 from obstore.store import LocalStore
 
 HERE = Path()
@@ -71,6 +72,25 @@ def test_pickle(tmp_path: Path):
     assert obs.get(new_store, "path.txt").bytes() == b"foo"
 
 
+def test_token(tmp_path: Path):
+    store = LocalStore(tmp_path, mkdir=True)
+    token = store.to_token()
+    assert token.startswith("obstore:v1:")
+
+    restored = LocalStore.from_token(token)
+    assert restored == store
+
+    # A token can be passed in place of the store
+    obs.put(token, "path.txt", b"foo")
+    assert obs.get(store, "path.txt").bytes() == b"foo"
+    assert obs.get(token, "path.txt").bytes() == b"foo"
+
+
+def test_token_invalid():
+    with pytest.raises(ValueError, match="Invalid store token"):
+        LocalStore.from_token("obstore:v1:not-a-token")
+
+
 def test_eq():
     store = LocalStore(HERE, automatic_cleanup=True)
     store2 = LocalStore(HERE, automatic_cleanup=True)
@@ -78,3 +98,74 @@ def test_eq():
     assert store == store  # noqa: PLR0124
     assert store == store2
     assert store != store3
+
+
+@pytest.mark.skipif(sys.platform == "win32", reason="Symlinks need privileges")
+def test_symlinks(tmp_path: Path):
+    (tmp_path / "real").mkdir()
+    (tmp_path / "real" / "file.txt").write_bytes(b"foo")
+    (tmp_path / "link.txt").symlink_to(tmp_path / "real" / "file.txt")
+    (tmp_path / "linked_dir").symlink_to(tmp_path / "real")
+
+    store = LocalStore(tmp_path)
+    assert store.symlinks == "follow"
+    paths = sorted(meta["path"] for meta in obs.list(store).collect())
+    assert paths == ["link.txt", "linked_dir/file.txt", "real/file.txt"]
+    assert obs.get(store, "link.txt").bytes() == b"foo"
+
+    store = LocalStore(tmp_path, symlinks="skip")
+    assert [meta["path"] for meta in obs.list(store).collect()] == ["real/file.txt"]
+    result = obs.list_with_delimiter(store)
+    assert result["common_prefixes"] == ["real"]
+    assert result["objects"] == []
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "linked_dir/file.txt")
+    assert pickle.loads(pickle.dumps(store)) == store
+
+    store = LocalStore(tmp_path, symlinks="error")
+    with pytest.raises(GenericError, match="symbolic link"):
+        obs.list(store).collect()
+    with pytest.raises(GenericError, match="symbolic link"):
+        obs.get(store, "link.txt")
+    assert obs.get(store, "real/file.txt").bytes() == b"foo"
+
+    with pytest.raises(ValueError, match="symlinks"):
+        LocalStore(tmp_path, symlinks="ignore")  # type: ignore[arg-type]
+
+
+@pytest.mark.parametrize(
+    ("direct_io", "preallocate"),
+    [(True, False), (False, True), (True, True)],
+)
+def test_direct_io_preallocate(tmp_path: Path, direct_io: bool, preallocate: bool):
+    store = LocalStore(tmp_path, direct_io=direct_io, preallocate=preallocate)
+    assert store.direct_io == direct_io
+    assert store.preallocate == preallocate
+
+    # Sizes that aren't a multiple of the block size are padded, then trimmed
+    data = bytes(range(256)) * 5000 + b"tail"
+    result = obs.put(store, "dir/small.bin", b"foo")
+    assert result["e_tag"] == obs.head(store, "dir/small.bin")["e_tag"]
+    obs.put(store, "big.bin", data)
+    assert (tmp_path / "big.bin").read_bytes() == data
+
+    source = tmp_path / "source.bin"
+    source.write_bytes(data)
+    obs.put(store, "multipart.bin", source, use_multipart=True, chunk_size=100_000)
+    assert (tmp_path / "multipart.bin").read_bytes() == data
+    obs.put(store, "iter.bin", iter([data[:10], data[10:]]), use_multipart=True)
+    assert obs.get(store, "iter.bin").bytes() == data
+
+    with pytest.raises(AlreadyExistsError):
+        obs.put(store, "big.bin", b"bar", mode="create")
+    # Staging files were cleaned up
+    assert sorted(path.name for path in tmp_path.iterdir()) == [
+        "big.bin",
+        "dir",
+        "iter.bin",
+        "multipart.bin",
+        "source.bin",
+    ]
+
+    assert pickle.loads(pickle.dumps(store)) == store
+    assert store != LocalStore(tmp_path)
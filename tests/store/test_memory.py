@@ -1,3 +1,9 @@
+import pickle
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import InvalidPathError
 from obstore.store import MemoryStore
 
 
@@ -6,3 +12,34 @@ def test_eq():
     store2 = MemoryStore()
     assert store == store  # noqa: PLR0124
     assert store != store2
+
+
+def test_from_dict_to_dict():
+    store = MemoryStore.from_dict({"a.txt": b"foo", "dir/b.txt": bytearray(b"bar")})
+    assert isinstance(store, MemoryStore)
+    assert obs.get(store, "dir/b.txt").bytes() == b"bar"
+    assert store.to_dict() == {"a.txt": b"foo", "dir/b.txt": b"bar"}
+
+
+def test_fill():
+    store = MemoryStore()
+    store.fill("data/", {"a.txt": b"foo", "nested/b.txt": memoryview(b"bar")})
+    store.fill("", {"c.txt": b"baz"})
+    assert store.to_dict() == {
+        "c.txt": b"baz",
+        "data/a.txt": b"foo",
+        "data/nested/b.txt": b"bar",
+    }
+
+    with pytest.raises(InvalidPathError):
+        store.fill("data", {"a//b.txt": b"foo"})
+
+
+def test_pickle():
+    store = MemoryStore.from_dict({"a.txt": b"foo"})
+    new_store: MemoryStore = pickle.loads(pickle.dumps(store))
+    assert isinstance(new_store, MemoryStore)
+    assert new_store.to_dict() == {"a.txt": b"foo"}
+    # The pickled store is a snapshot
+    obs.put(new_store, "b.txt", b"bar")
+    assert store.to_dict() == {"a.txt": b"foo"}
@@ -0,0 +1,87 @@
+import pytest
+
+import obstore as obs
+from obstore.exceptions import GenericError
+from obstore.store import ChaosStore, MemoryStore, MirrorStore
+
+
+def test_mirror_writes_to_all_stores():
+    primary = MemoryStore()
+    secondary = MemoryStore()
+    store = MirrorStore([primary, secondary])
+
+    obs.put(store, "file.txt", b"foo")
+    assert obs.get(primary, "file.txt").bytes() == b"foo"
+    assert obs.get(secondary, "file.txt").bytes() == b"foo"
+
+    obs.copy(store, "file.txt", "copy.txt")
+    assert obs.get(secondary, "copy.txt").bytes() == b"foo"
+
+    obs.delete(store, "file.txt")
+    with pytest.raises(FileNotFoundError):
+        obs.get(primary, "file.txt")
+    with pytest.raises(FileNotFoundError):
+        obs.get(secondary, "file.txt")
+
+
+def test_mirror_multipart_writes_to_all_stores():
+    primary = MemoryStore()
+    secondary = MemoryStore()
+    store = MirrorStore([primary, secondary])
+
+    obs.put(store, "file.txt", [b"foo", b"bar"], use_multipart=True)
+    assert obs.get(primary, "file.txt").bytes() == b"foobar"
+    assert obs.get(secondary, "file.txt").bytes() == b"foobar"
+
+
+def test_mirror_secondary_write_failure():
+    primary = MemoryStore()
+    secondary = MemoryStore()
+    store = MirrorStore(
+        [primary, ChaosStore(MemoryStore(), failure_rate=1.0), secondary],
+    )
+
+    # The other stores are still written to, and the failed store is listed
+    with pytest.raises(GenericError, match="failed on 1 of 2 secondary stores: store 1"):
+        obs.put(store, "file.txt", b"foo")
+    assert obs.get(primary, "file.txt").bytes() == b"foo"
+    assert obs.get(secondary, "file.txt").bytes() == b"foo"
+
+    # A multipart upload that can't be started everywhere isn't started anywhere
+    with pytest.raises(GenericError, match="Injected failure"):
+        obs.put(store, "multipart.txt", [b"foo", b"bar"], use_multipart=True)
+    with pytest.raises(FileNotFoundError):
+        obs.get(primary, "multipart.txt")
+
+
+def test_mirror_reads_fail_over():
+    primary = MemoryStore()
+    secondary = MemoryStore()
+    store = MirrorStore([primary, secondary])
+
+    obs.put(secondary, "old.txt", b"foo")
+    assert obs.get(store, "old.txt").bytes() == b"foo"
+    assert obs.head(store, "old.txt")["size"] == 3
+
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "missing.txt")
+
+    # The primary store is read first
+    obs.put(primary, "old.txt", b"bar")
+    assert obs.get(store, "old.txt").bytes() == b"bar"
+
+
+def test_mirror_delete_missing_from_secondary():
+    primary = MemoryStore()
+    secondary = MemoryStore()
+    store = MirrorStore([primary, secondary])
+
+    obs.put(primary, "file.txt", b"foo")
+    obs.delete(store, "file.txt")
+    with pytest.raises(FileNotFoundError):
+        obs.get(primary, "file.txt")
+
+
+def test_mirror_requires_a_store():
+    with pytest.raises(ValueError, match="at least one store"):
+        MirrorStore([])
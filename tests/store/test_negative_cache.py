@@ -0,0 +1,65 @@
+import time
+from datetime import timedelta
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore, NegativeCacheStore
+
+
+def test_negative_cache():
+    inner = MemoryStore()
+    store = NegativeCacheStore(inner, ttl=timedelta(minutes=1))
+
+    with pytest.raises(FileNotFoundError):
+        obs.head(store, "_metadata")
+
+    # Writes that bypass the wrapper aren't seen while the entry is cached
+    obs.put(inner, "_metadata", b"foo")
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "_metadata")
+
+    store.invalidate("_metadata")
+    assert obs.get(store, "_metadata").bytes() == b"foo"
+
+
+def test_negative_cache_invalidated_by_writes():
+    store = NegativeCacheStore(MemoryStore(), ttl=timedelta(minutes=1))
+
+    with pytest.raises(FileNotFoundError):
+        obs.head(store, "file.txt")
+    obs.put(store, "file.txt", b"foo")
+    assert obs.head(store, "file.txt")["size"] == 3
+
+    with pytest.raises(FileNotFoundError):
+        obs.head(store, "copy.txt")
+    obs.copy(store, "file.txt", "copy.txt")
+    assert obs.get(store, "copy.txt").bytes() == b"foo"
+
+    with pytest.raises(FileNotFoundError):
+        obs.head(store, "multipart.txt")
+    with obs.open_writer(store, "multipart.txt") as f:
+        f.write(b"bar")
+    assert obs.get(store, "multipart.txt").bytes() == b"bar"
+
+
+def test_negative_cache_expires():
+    inner = MemoryStore()
+    store = NegativeCacheStore(inner, ttl=timedelta(milliseconds=10))
+
+    with pytest.raises(FileNotFoundError):
+        obs.head(store, "file.txt")
+    obs.put(inner, "file.txt", b"foo")
+    time.sleep(0.05)
+    assert obs.head(store, "file.txt")["size"] == 3
+
+
+def test_negative_cache_clear():
+    inner = MemoryStore()
+    store = NegativeCacheStore(inner)
+
+    with pytest.raises(FileNotFoundError):
+        store.head("a.txt")
+    obs.put(inner, "a.txt", b"foo")
+    store.invalidate()
+    assert store.head("a.txt")["size"] == 3
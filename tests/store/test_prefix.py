@@ -0,0 +1,35 @@
+import obstore as obs
+from obstore.store import MemoryStore, PrefixStore
+
+
+def test_prefix_store():
+    inner = MemoryStore()
+    store = PrefixStore(inner, "data/users")
+    assert store.prefix == "data/users"
+
+    obs.put(store, "alice.json", b"foo")
+    assert obs.get(inner, "data/users/alice.json").bytes() == b"foo"
+    assert obs.get(store, "alice.json").bytes() == b"foo"
+
+    obs.put(inner, "data/other.json", b"bar")
+    paths = [meta["path"] for meta in obs.list(store).collect()]
+    assert paths == ["alice.json"]
+
+    result = obs.list_with_delimiter(PrefixStore(inner, "data"))
+    assert result["common_prefixes"] == ["users"]
+    assert [meta["path"] for meta in result["objects"]] == ["other.json"]
+
+
+def test_prefix_store_nested():
+    inner = MemoryStore()
+    store = PrefixStore(PrefixStore(inner, "a"), "b")
+
+    obs.put(store, "file.txt", b"foo")
+    assert obs.get(inner, "a/b/file.txt").bytes() == b"foo"
+    assert [meta["path"] for meta in obs.list(store).collect()] == ["file.txt"]
+
+
+def test_prefix_store_repr():
+    store = PrefixStore(MemoryStore(), "data")
+    assert repr(store).startswith("PrefixStore(")
+    assert 'prefix="data"' in repr(store)
@@ -0,0 +1,70 @@
+import obstore as obs
+from obstore.store import MemoryStore, RangeCacheStore
+
+
+def test_range_cache():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"hello world")
+    store = RangeCacheStore(inner, 1024)
+
+    assert obs.get_range(store, "file.txt", start=0, end=5) == b"hello"
+    assert store.size == 5
+
+    # Cached ranges are returned without a request until a new e-tag is seen
+    obs.put(inner, "file.txt", b"HELLO WORLD")
+    assert obs.get_range(store, "file.txt", start=0, end=5) == b"hello"
+
+    obs.head(store, "file.txt")
+    assert store.size == 0
+    assert obs.get_range(store, "file.txt", start=0, end=5) == b"HELLO"
+
+
+def test_range_cache_validate():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"hello world")
+    store = RangeCacheStore(inner, 1024, validate=True)
+
+    assert obs.get_range(store, "file.txt", start=6, end=11) == b"world"
+    assert obs.get_range(store, "file.txt", start=6, end=11) == b"world"
+
+    obs.put(inner, "file.txt", b"HELLO WORLD")
+    assert obs.get_range(store, "file.txt", start=6, end=11) == b"WORLD"
+    assert store.size == 5
+
+
+def test_range_cache_eviction():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"0123456789")
+    store = RangeCacheStore(inner, 8)
+
+    obs.get_range(store, "file.txt", start=0, end=4)
+    obs.get_range(store, "file.txt", start=4, end=8)
+    assert store.size == 8
+    obs.get_range(store, "file.txt", start=6, end=10)
+    assert store.size == 8
+
+    # Ranges larger than the cache aren't cached
+    assert obs.get_range(store, "file.txt", start=0, end=10) == b"0123456789"
+    assert store.size == 8
+
+
+def test_range_cache_ignores_full_reads():
+    inner = MemoryStore()
+    obs.put(inner, "file.txt", b"foo")
+    store = RangeCacheStore(inner, 1024)
+
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+    assert store.size == 0
+
+
+def test_range_cache_writes_invalidate():
+    store = RangeCacheStore(MemoryStore(), 1024)
+    obs.put(store, "file.txt", b"hello world")
+
+    assert obs.get_range(store, "file.txt", start=0, end=5) == b"hello"
+    obs.put(store, "file.txt", b"HELLO WORLD")
+    assert store.size == 0
+    assert obs.get_range(store, "file.txt", start=0, end=5) == b"HELLO"
+
+    store.clear()
+    assert store.size == 0
@@ -0,0 +1,41 @@
+import time
+from datetime import timedelta
+
+import obstore as obs
+from obstore.store import MemoryStore, ThrottleStore
+
+
+def test_throttle_default_config():
+    store = ThrottleStore(MemoryStore())
+
+    assert store.config["wait_get_per_call"] == timedelta(0)
+    obs.put(store, "file.txt", b"foo")
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+
+def test_throttle_get():
+    store = ThrottleStore(
+        MemoryStore(),
+        {"wait_get_per_call": timedelta(milliseconds=100)},
+    )
+    obs.put(store, "file.txt", b"foo")
+
+    start = time.monotonic()
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+    assert time.monotonic() - start >= 0.1
+
+
+def test_throttle_update_config():
+    store = ThrottleStore(
+        MemoryStore(),
+        {"wait_put_per_call": timedelta(seconds=10)},
+    )
+    assert store.config["wait_put_per_call"] == timedelta(seconds=10)
+
+    store.update_config({"wait_put_per_call": timedelta(milliseconds=1)})
+    assert store.config["wait_put_per_call"] == timedelta(milliseconds=1)
+    assert store.config["wait_delete_per_call"] == timedelta(0)
+
+    start = time.monotonic()
+    obs.put(store, "file.txt", b"foo")
+    assert time.monotonic() - start < 5
@@ -0,0 +1,133 @@
+import io
+import tarfile
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def test_archive_roundtrip():
+    store = MemoryStore()
+    long_name = "a" * 150 + ".txt"
+    obs.put(store, "data/file1.txt", b"foo")
+    obs.put(store, "data/nested/file2.txt", b"x" * 1000)
+    obs.put(store, f"data/{long_name}", b"long")
+    obs.put(store, "other/file3.txt", b"not archived")
+
+    count = obs.archive(store, "data", store, "backup.tar")
+    assert count == 3
+
+    # The archive can be read by the standard library
+    buf = obs.get(store, "backup.tar").bytes().to_bytes()
+    with tarfile.open(fileobj=io.BytesIO(buf)) as tar:
+        names = sorted(tar.getnames())
+        assert names == sorted(["file1.txt", "nested/file2.txt", long_name])
+        member = tar.extractfile("nested/file2.txt")
+        assert member is not None
+        assert member.read() == b"x" * 1000
+
+    dest = MemoryStore()
+    count = obs.extract(store, "backup.tar", dest, "restored")
+    assert count == 3
+    assert obs.get(dest, "restored/file1.txt").bytes() == b"foo"
+    assert obs.get(dest, "restored/nested/file2.txt").bytes() == b"x" * 1000
+    assert obs.get(dest, f"restored/{long_name}").bytes() == b"long"
+
+
+def test_extract_stdlib_archive():
+    buf = io.BytesIO()
+    with tarfile.open(fileobj=buf, mode="w", format=tarfile.PAX_FORMAT) as tar:
+        data = b"hello world"
+        info = tarfile.TarInfo("dir/" + "b" * 120)
+        info.size = len(data)
+        tar.addfile(info, io.BytesIO(data))
+
+    store = MemoryStore()
+    obs.put(store, "archive.tar", buf.getvalue())
+    assert obs.extract(store, "archive.tar", store, "out") == 1
+    assert obs.get(store, "out/dir/" + "b" * 120).bytes() == b"hello world"
+
+
+def test_archive_compress():
+    store = MemoryStore()
+    obs.put(store, "run/metrics.json", b"{}")
+    obs.put(store, "run/model.bin", b"x" * 100_000)
+
+    assert obs.archive(store, "run", store, "run.tar.gz", compress="gzip") == 2
+    result = obs.get(store, "run.tar.gz")
+    # The compressed archive is the content, rather than a content encoding
+    assert "Content-Encoding" not in result.attributes
+    buf = result.bytes().to_bytes()
+    assert len(buf) < 100_000
+    with tarfile.open(fileobj=io.BytesIO(buf), mode="r:gz") as tar:
+        assert sorted(tar.getnames()) == ["metrics.json", "model.bin"]
+        member = tar.extractfile("model.bin")
+        assert member is not None
+        assert member.read() == b"x" * 100_000
+
+    assert obs.archive(store, "run", store, "run.tar.zst", compress="zstd") == 2
+    stream = obs.get(store, "run.tar.zst").stream(decompress="zstd")
+    buf = b"".join(stream)
+    with tarfile.open(fileobj=io.BytesIO(buf)) as tar:
+        assert sorted(tar.getnames()) == ["metrics.json", "model.bin"]
+
+
+def test_extract_decompress():
+    store = MemoryStore()
+    obs.put(store, "run/model.bin", b"x" * 100_000)
+    obs.put(store, "run/metrics.json", b"{}")
+
+    assert obs.archive(store, "run", store, "run.tar.gz", compress="gzip") == 2
+    assert obs.extract(store, "run.tar.gz", store, "gz", decompress="gzip") == 2
+    assert obs.get(store, "gz/model.bin").bytes() == b"x" * 100_000
+    assert obs.get(store, "gz/metrics.json").bytes() == b"{}"
+
+    assert obs.archive(store, "run", store, "run.tar.zst", compress="zstd") == 2
+    assert obs.extract(store, "run.tar.zst", store, "zst", decompress="auto") == 2
+    assert obs.get(store, "zst/model.bin").bytes() == b"x" * 100_000
+
+    # An archive without a known extension or encoding is read as is
+    assert obs.archive(store, "run", store, "run.tar") == 2
+    assert obs.extract(store, "run.tar", store, "plain", decompress="auto") == 2
+    assert obs.get(store, "plain/metrics.json").bytes() == b"{}"
+
+
+def test_archive_invalid_format():
+    store = MemoryStore()
+    with pytest.raises(ValueError, match="Unsupported archive format"):
+        obs.archive(store, None, store, "backup.zip", format="zip")  # type: ignore
+
+
+def test_extract_oversized_long_name():
+    info = tarfile.TarInfo("././@LongLink")
+    info.type = tarfile.GNUTYPE_LONGNAME
+    info.size = 1 << 40
+    store = MemoryStore()
+    obs.put(store, "bad.tar", info.tobuf(format=tarfile.GNU_FORMAT) + b"\0" * 1024)
+
+    with pytest.raises(OSError, match="exceeds the maximum"):
+        obs.extract(store, "bad.tar", store, "out")
+
+
+def test_extract_overflowing_entry_size():
+    # The size of a skipped entry can't overflow when its padding is added
+    info = tarfile.TarInfo("dir")
+    info.type = tarfile.DIRTYPE
+    info.size = (1 << 64) - 1
+    store = MemoryStore()
+    obs.put(store, "bad.tar", info.tobuf(format=tarfile.GNU_FORMAT) + b"\0" * 1024)
+
+    with pytest.raises(OSError, match="Invalid tar entry size"):
+        obs.extract(store, "bad.tar", store, "out")
+
+
+@pytest.mark.asyncio
+async def test_archive_async():
+    store = MemoryStore()
+    await obs.put_async(store, "file1.txt", b"foo")
+
+    assert await obs.archive_async(store, None, store, "backup/archive.tar") == 1
+    assert await obs.extract_async(store, "backup/archive.tar", store, "out") == 1
+    resp = await obs.get_async(store, "out/file1.txt")
+    assert await resp.bytes_async() == b"foo"
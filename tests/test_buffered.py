@@ -1,3 +1,8 @@
+import gzip
+import io
+import pickle
+from datetime import timedelta
+
 import pytest
 
 import obstore as obs
@@ -51,6 +56,169 @@ async def test_readable_file_async():
     assert memoryview(data[:20]) == memoryview(await file.read(20))
 
 
+def test_readable_file_readinto():
+    store = MemoryStore()
+
+    data = b"0123456789" * 10
+    obs.put(store, "data.txt", data)
+
+    file = obs.open_reader(store, "data.txt")
+    buf = bytearray(30)
+    assert file.readinto(buf) == 30
+    assert buf == data[:30]
+
+    file.seek(90)
+    assert file.readinto(buf) == 10
+    assert buf[:10] == data[90:]
+    assert file.readinto(buf) == 0
+
+    with pytest.raises(TypeError, match="writable"):
+        file.readinto(b"readonly")
+
+
+@pytest.mark.asyncio
+async def test_readable_file_readinto_async():
+    store = MemoryStore()
+
+    data = b"0123456789" * 10
+    await obs.put_async(store, "data.txt", data)
+
+    file = await obs.open_reader_async(store, "data.txt")
+    buf = memoryview(bytearray(50))
+    assert await file.readinto(buf) == 50
+    assert buf.tobytes() == data[:50]
+
+
+def test_readable_file_iter():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    data = line * 5000 + b"no trailing newline"
+    path = "big-data.txt"
+
+    obs.put(store, path, data)
+
+    lines = [line.to_bytes() for line in obs.open_reader(store, path)]
+    assert len(lines) == 5001
+    assert lines[0] == line
+    assert lines[-1] == b"no trailing newline"
+
+
+@pytest.mark.asyncio
+async def test_readable_file_aiter():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    path = "big-data.txt"
+
+    await obs.put_async(store, path, line * 100)
+
+    file = await obs.open_reader_async(store, path)
+    lines = [line async for line in file]
+    assert len(lines) == 100
+    assert all(x.to_bytes() == line for x in lines)
+
+
+def test_readable_file_pickle():
+    store = MemoryStore()
+
+    data = b"0123456789" * 10
+    obs.put(store, "data.txt", data)
+
+    file = obs.open_reader(store, "data.txt", buffer_size=16)
+    assert file.read(25) == data[:25]
+
+    # The memory store is pickled as a snapshot of its data
+    restored = pickle.loads(pickle.dumps(file))
+    assert restored.tell() == 25
+    assert restored.meta == file.meta
+    assert restored.read() == data[25:]
+    assert file.read() == data[25:]
+
+
+@pytest.mark.asyncio
+async def test_readable_file_pickle_async():
+    store = MemoryStore()
+
+    data = b"0123456789" * 10
+    await obs.put_async(store, "data.txt", data)
+
+    file = await obs.open_reader_async(store, "data.txt")
+    await file.seek(90)
+
+    restored = pickle.loads(pickle.dumps(file))
+    assert await restored.tell() == 90
+    assert await restored.read() == data[90:]
+
+
+def test_readable_file_decompress():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    data = line * 5000
+    # Concatenated members are read as one stream
+    compressed = gzip.compress(data[:1000]) + gzip.compress(data[1000:])
+    obs.put(store, "logs.txt.gz", compressed)
+
+    file = obs.open_reader(store, "logs.txt.gz", decompress="auto")
+    assert not file.seekable()
+    # The size of the decompressed object isn't known up front
+    with pytest.raises(io.UnsupportedOperation):
+        _ = file.size
+    lines = [line.to_bytes() for line in file]
+    assert len(lines) == 5000
+    assert all(x == line for x in lines)
+
+    file = obs.open_reader(store, "logs.txt.gz", decompress="gzip")
+    assert file.read(10) == data[:10]
+    assert file.seek(100) == 100
+    assert file.tell() == 100
+    assert file.read(10) == data[100:110]
+    with pytest.raises(io.UnsupportedOperation):
+        file.seek(0)
+
+    # The position is restored by decompressing the object again
+    restored = pickle.loads(pickle.dumps(file))
+    assert restored.tell() == 110
+    assert restored.read() == data[110:]
+
+    # Objects of no known codec are read as is
+    obs.put(store, "data.txt", data)
+    file = obs.open_reader(store, "data.txt", decompress="auto")
+    assert file.seekable()
+    assert file.size == len(data)
+    assert file.read() == data
+
+
+@pytest.mark.asyncio
+async def test_readable_file_decompress_async():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog\n" * 100
+    await obs.put_async(
+        store,
+        "logs",
+        gzip.compress(data),
+        attributes={"Content-Encoding": "gzip"},
+    )
+
+    file = await obs.open_reader_async(store, "logs", decompress="auto")
+    assert await file.read() == data
+
+
+def test_readable_file_decompress_invalid():
+    store = MemoryStore()
+
+    obs.put(store, "data.txt.gz", b"not gzip data")
+
+    with pytest.raises(ValueError, match="Unsupported decompression"):
+        obs.open_reader(store, "data.txt.gz", decompress="brotli")  # type: ignore
+
+    file = obs.open_reader(store, "data.txt.gz", decompress="auto")
+    with pytest.raises(OSError):  # noqa: PT011
+        file.read()
+
+
 def test_writable_file_sync():
     store = MemoryStore()
 
@@ -77,3 +245,143 @@ async def test_writable_file_async():
     resp = await obs.get_async(store, path)
     retour = await resp.bytes_async()
     assert retour == line * 50
+
+
+def test_writable_file_write_from():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    path = "big-data.txt"
+    with obs.open_writer(store, path) as writer:
+        writer.write(b"header\n")
+        assert writer.tell() == 7
+        assert writer.write_from(line for _ in range(50)) == len(line) * 50
+        assert writer.tell() == 7 + len(line) * 50
+
+        async def chunks():
+            yield line
+
+        with pytest.raises(ValueError, match="Async input not allowed"):
+            writer.write_from(chunks())
+        with pytest.raises(TypeError, match="iterable"):
+            writer.write_from(line)
+
+    assert obs.get(store, path).bytes() == b"header\n" + line * 50
+
+
+@pytest.mark.asyncio
+async def test_writable_file_write_from_async():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    path = "big-data.txt"
+
+    async def chunks():
+        for _ in range(50):
+            yield line
+
+    async with obs.open_writer_async(store, path) as writer:
+        assert await writer.write_from(chunks()) == len(line) * 50
+        assert await writer.write_from([line, line]) == len(line) * 2
+        assert writer.tell() == len(line) * 52
+
+    resp = await obs.get_async(store, path)
+    assert await resp.bytes_async() == line * 52
+
+
+def test_writable_file_flush_interval():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    path = "big-data.txt"
+    chunk = b"a" * 1024 * 1024
+    with obs.open_writer(store, path, flush_interval=5 * 1024 * 1024) as writer:
+        for _ in range(12):
+            writer.write(chunk)
+        assert writer.bytes_written == len(chunk) * 12
+
+    assert obs.get(store, path).bytes() == chunk * 12
+
+    # Less than 5 MiB is never uploaded as a part, however long ago the last part was
+    uploads: list[int] = []
+    with obs.open_writer(
+        store,
+        path,
+        flush_interval=timedelta(0),
+        progress=lambda transferred, _total: uploads.append(transferred),
+        progress_interval=timedelta(0),
+    ) as writer:
+        for _ in range(10):
+            writer.write(line)
+
+    assert obs.get(store, path).bytes() == line * 10
+    assert uploads == [len(line) * 10]
+
+    uploads.clear()
+    with obs.open_writer(
+        store,
+        path,
+        flush_interval=timedelta(0),
+        progress=lambda transferred, _total: uploads.append(transferred),
+        progress_interval=timedelta(0),
+    ) as writer:
+        for _ in range(12):
+            writer.write(chunk)
+
+    assert obs.get(store, path).bytes() == chunk * 12
+    assert len(uploads) == 3
+    assert uploads[-1] == len(chunk) * 12
+
+
+def test_writable_file_flush_interval_invalid():
+    store = MemoryStore()
+
+    with pytest.raises(ValueError, match="flush_interval"):
+        obs.open_writer(store, "path.txt", flush_interval="1s")  # type: ignore
+
+    with pytest.raises(ValueError, match="flush_interval"):
+        obs.open_writer(store, "path.txt", flush_interval=True)
+
+    with pytest.raises(ValueError, match="at least 5 MiB"):
+        obs.open_writer(store, "path.txt", flush_interval=100)
+
+
+def test_writable_file_compress():
+    store = MemoryStore()
+
+    line = b"the quick brown fox jumps over the lazy dog\n"
+    with obs.open_writer(store, "small.txt", compress="gzip") as writer:
+        writer.write(line)
+        assert writer.bytes_written == len(line)
+
+    result = store.get("small.txt")
+    assert result.attributes["Content-Encoding"] == "gzip"
+    assert gzip.decompress(result.bytes()) == line
+
+    # A small buffer size spreads the compressed data across a multipart upload
+    lines = [f"{i:08d} ".encode() + line for i in range(10_000)]
+    with obs.open_writer(
+        store,
+        "large.txt",
+        buffer_size=1024,
+        compress="zstd",
+    ) as writer:
+        for x in lines:
+            writer.write(x)
+
+    result = store.get("large.txt")
+    assert result.attributes["Content-Encoding"] == "zstd"
+    file = obs.open_reader(store, "large.txt", decompress="auto")
+    assert file.read() == b"".join(lines)
+
+
+@pytest.mark.asyncio
+async def test_writable_file_compress_async():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog\n" * 100
+    async with obs.open_writer_async(store, "data.txt", compress="gzip") as writer:
+        await writer.write(data)
+
+    result = await obs.get_async(store, "data.txt")
+    assert gzip.decompress(await result.bytes_async()) == data
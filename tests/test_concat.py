@@ -0,0 +1,41 @@
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def test_concat():
+    store = MemoryStore()
+    obs.put(store, "a.txt", b"foo")
+    obs.put(store, "b.txt", b"bar")
+    obs.put(store, "c.txt", b"baz")
+
+    chunks = list(obs.concat(store, ["c.txt", "a.txt", "b.txt"], min_chunk_size=1))
+    assert b"".join(chunks) == b"bazfoobar"
+
+    stream = obs.concat(store, ["a.txt", "b.txt"], prefetch=0)
+    assert b"".join(stream) == b"foobar"
+
+
+@pytest.mark.asyncio
+async def test_concat_async():
+    store = MemoryStore()
+    obs.put(store, "a.txt", b"foo")
+    obs.put(store, "b.txt", b"bar")
+
+    chunks = [chunk async for chunk in obs.concat(store, ["a.txt", "b.txt"])]
+    assert b"".join(chunks) == b"foobar"
+
+
+def test_concat_missing():
+    store = MemoryStore()
+    obs.put(store, "a.txt", b"foo")
+
+    with pytest.raises(FileNotFoundError):
+        list(obs.concat(store, ["a.txt", "missing.txt"], min_chunk_size=1))
+
+
+def test_concat_empty():
+    store = MemoryStore()
+    with pytest.raises(ValueError, match="at least one path"):
+        obs.concat(store, [])
@@ -0,0 +1,92 @@
+import pytest
+
+import obstore as obs
+from obstore.exceptions import PreconditionError
+from obstore.store import MemoryStore
+
+
+def test_put_if_absent():
+    store = MemoryStore()
+
+    result = obs.put_if_absent(store, "lock", b"first")
+    assert result is not None
+    assert result["e_tag"] is not None
+
+    assert obs.put_if_absent(store, "lock", b"second") is None
+    assert obs.get(store, "lock").bytes() == b"first"
+
+
+def test_update():
+    store = MemoryStore()
+
+    def increment(current: obs.Bytes | None) -> bytes:
+        value = int(current.to_bytes()) if current is not None else 0
+        return str(value + 1).encode()
+
+    obs.update(store, "counter", increment)
+    obs.update(store, "counter", increment)
+    assert obs.get(store, "counter").bytes() == b"2"
+
+
+def test_update_preserves_attributes():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo", attributes={"Content-Type": "text/plain"})
+
+    obs.update(store, "file.txt", lambda _: b"bar")
+    result = obs.get(store, "file.txt")
+    assert result.attributes == {"Content-Type": "text/plain"}
+    assert result.bytes() == b"bar"
+
+
+def test_update_abandoned():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo")
+
+    assert obs.update(store, "file.txt", lambda _: None) is None
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+
+def test_update_retries_on_conflict():
+    store = MemoryStore()
+    obs.put(store, "counter", b"0")
+    calls = []
+
+    def increment(current: obs.Bytes | None) -> bytes:
+        assert current is not None
+        calls.append(current.to_bytes())
+        # Simulate a concurrent writer on the first attempt
+        if len(calls) == 1:
+            obs.put(store, "counter", b"10")
+        return str(int(current.to_bytes()) + 1).encode()
+
+    obs.update(store, "counter", increment)
+    assert calls == [b"0", b"10"]
+    assert obs.get(store, "counter").bytes() == b"11"
+
+
+def test_update_max_retries():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"0")
+
+    def conflict(current: obs.Bytes | None) -> bytes:
+        obs.put(store, "file.txt", b"other")
+        return b"mine"
+
+    with pytest.raises(PreconditionError):
+        obs.update(store, "file.txt", conflict, max_retries=2)
+
+
+@pytest.mark.asyncio
+async def test_conditional_async():
+    store = MemoryStore()
+
+    assert await obs.put_if_absent_async(store, "file.txt", b"a") is not None
+    assert await obs.put_if_absent_async(store, "file.txt", b"b") is None
+
+    async def append(current: obs.Bytes | None) -> bytes:
+        assert current is not None
+        return current.to_bytes() + b"c"
+
+    await obs.update_async(store, "file.txt", append)
+    await obs.update_async(store, "file.txt", lambda current: b"d")
+    assert (await obs.get_async(store, "file.txt")).bytes() == b"d"
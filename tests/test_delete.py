@@ -44,6 +44,32 @@ def test_delete_many():
     assert len(store.list().collect()) == 0
 
 
+def test_delete_prefix():
+    store = MemoryStore()
+
+    for path in ["data/a.csv", "data/nested/b.csv", "database.csv", "other/c.csv"]:
+        store.put(path, b"foo")
+
+    obs.delete_prefix(store, "data")
+    assert sorted(meta["path"] for meta in store.list().collect()) == [
+        "database.csv",
+        "other/c.csv",
+    ]
+
+    # Deleting a prefix without objects succeeds
+    obs.delete_prefix(store, "missing")
+
+
+@pytest.mark.asyncio
+async def test_delete_prefix_async(tmp_path):
+    store = LocalStore(tmp_path)
+
+    await obs.put_async(store, "data/a.csv", b"foo")
+    await obs.put_async(store, "data/nested/b.csv", b"bar")
+    await obs.delete_prefix_async(store, "data")
+    assert store.list().collect() == []
+
+
 # Local filesystem errors if the file does not exist.
 def test_delete_one_local_fs():
     with TemporaryDirectory() as tmpdir:
@@ -0,0 +1,69 @@
+import obstore as obs
+from obstore.store import AzureStore, HTTPStore, MemoryStore, S3Store
+
+
+def test_diagnostics():
+    diagnostics = obs.diagnostics()
+    assert diagnostics["version"] == obs.__version__
+    assert diagnostics["object_store_version"] == obs._object_store_version
+    assert diagnostics["store"] is None
+
+
+def test_diagnostics_memory_store():
+    diagnostics = obs.diagnostics(MemoryStore())
+    assert diagnostics["store"] == "other"
+    assert diagnostics["issues"] == []
+
+
+def test_diagnostics_http_endpoint():
+    store = S3Store("bucket", endpoint="http://localhost:9000")
+    diagnostics = obs.diagnostics(store)
+    assert diagnostics["store"] == "S3Store"
+    assert "http-not-allowed" in [issue["id"] for issue in diagnostics["issues"]]
+
+    store = S3Store(
+        "bucket",
+        endpoint="http://localhost:9000",
+        client_options={"allow_http": True},
+    )
+    issues = [issue["id"] for issue in obs.diagnostics(store)["issues"]]
+    assert "http-not-allowed" not in issues
+
+    store = HTTPStore.from_url("http://localhost:8080")
+    issues = [issue["id"] for issue in obs.diagnostics(store)["issues"]]
+    assert "http-not-allowed" in issues
+
+
+def test_diagnostics_emulator_allows_http():
+    store = AzureStore("container", use_emulator=True)
+    issues = [issue["id"] for issue in obs.diagnostics(store)["issues"]]
+    assert "http-not-allowed" not in issues
+
+
+def test_diagnostics_virtual_hosted_local_endpoint():
+    store = S3Store(
+        "bucket",
+        endpoint="https://127.0.0.1:9000",
+        virtual_hosted_style_request=True,
+    )
+    issues = [issue["id"] for issue in obs.diagnostics(store)["issues"]]
+    assert "virtual-hosted-local-endpoint" in issues
+
+    store = S3Store("bucket", endpoint="https://127.0.0.1:9000", compatibility="minio")
+    issues = [issue["id"] for issue in obs.diagnostics(store)["issues"]]
+    assert "virtual-hosted-local-endpoint" not in issues
+
+
+def test_diagnostics_r2_tagging():
+    endpoint = "https://account.r2.cloudflarestorage.com"
+    store = S3Store("bucket", endpoint=endpoint)
+    issue = next(
+        issue
+        for issue in obs.diagnostics(store)["issues"]
+        if issue["id"] == "r2-tagging"
+    )
+    assert "disable_tagging" in issue["workaround"]
+
+    store = S3Store("bucket", endpoint=endpoint, disable_tagging=True)
+    issues = [issue["id"] for issue in obs.diagnostics(store)["issues"]]
+    assert "r2-tagging" not in issues
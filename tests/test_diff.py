@@ -0,0 +1,81 @@
+import pytest
+from arro3.core import Table
+
+import obstore as obs
+from obstore.store import LocalStore, MemoryStore
+
+
+def test_diff():
+    store = MemoryStore()
+    store.put("a/same.txt", b"foo")
+    store.put("a/removed.txt", b"foo")
+    store.put("a/dir/resized.txt", b"foo")
+    store.put("b/same.txt", b"foo")
+    store.put("b/added.txt", b"foo")
+    store.put("b/dir/resized.txt", b"foobar")
+
+    # MemoryStore gives every put a new ETag, so only sizes are compared
+    entries = obs.diff(store, "a", store, "b", compare_e_tag=False)
+    assert [(entry["path"], entry["change"]) for entry in entries] == [
+        ("added.txt", "added"),
+        ("dir/resized.txt", "changed"),
+        ("removed.txt", "removed"),
+    ]
+    added, changed, removed = entries
+    assert added["a"] is None
+    assert added["b"]["path"] == "b/added.txt"
+    assert changed["a"]["size"] == 3
+    assert changed["b"]["size"] == 6
+    assert removed["b"] is None
+
+    assert obs.diff(store, "a", store, "a") == []
+
+
+def test_diff_e_tag():
+    store = MemoryStore()
+    store.put("a/file.txt", b"foo")
+    store.put("b/file.txt", b"bar")
+
+    [entry] = obs.diff(store, "a", store, "b")
+    assert entry["change"] == "changed"
+    assert obs.diff(store, "a", store, "b", compare_e_tag=False) == []
+
+
+def test_diff_unsorted(tmp_path):
+    # The local store doesn't list in lexicographic order
+    local = LocalStore(tmp_path)
+    memory = MemoryStore()
+    names = [f"{i}/file{j}.txt" for i in range(10) for j in range(5)]
+    for name in names:
+        local.put(name, b"foo")
+        memory.put(name, b"foo")
+    local.put("extra.txt", b"foo")
+
+    entries = obs.diff(memory, None, local, None, compare_e_tag=False)
+    assert [(entry["path"], entry["change"]) for entry in entries] == [
+        ("extra.txt", "added"),
+    ]
+
+
+def test_diff_arrow():
+    store = MemoryStore()
+    store.put("a/file.txt", b"foo")
+    store.put("b/file.txt", b"foobar")
+    store.put("b/new.txt", b"x")
+
+    table = obs.diff(store, "a", store, "b", return_arrow=True)
+    assert isinstance(table, Table)
+    assert table.num_rows == 2
+    assert table["change"][0].as_py() == "changed"
+    assert table["size_a"][0].as_py() == 3
+    assert table["size_b"][0].as_py() == 6
+    assert table["size_a"][1].as_py() is None
+
+
+@pytest.mark.asyncio
+async def test_diff_async():
+    store = MemoryStore()
+    await store.put_async("a/file.txt", b"foo")
+
+    entries = await obs.diff_async(store, "a", store, "b")
+    assert [entry["change"] for entry in entries] == ["removed"]
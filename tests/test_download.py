@@ -0,0 +1,129 @@
+import threading
+import time
+from datetime import timedelta
+from http.server import BaseHTTPRequestHandler, HTTPServer
+from pathlib import Path
+
+import pytest
+
+import obstore as obs
+from obstore.store import HTTPStore, MemoryStore
+
+
+def test_download(tmp_path: Path):
+    store = MemoryStore()
+    data = b"the quick brown fox jumps over the lazy dog\n" * 1000
+    obs.put(store, "data.txt", data)
+
+    local_path = tmp_path / "data.txt"
+    meta = obs.download(store, "data.txt", local_path)
+    assert meta["size"] == len(data)
+    assert local_path.read_bytes() == data
+    assert not (tmp_path / "data.txt.part").exists()
+    assert not (tmp_path / "data.txt.part.etag").exists()
+
+
+def test_download_resume(tmp_path: Path):
+    store = MemoryStore()
+    data = b"0123456789" * 1000
+    obs.put(store, "data.txt", data)
+    e_tag = obs.head(store, "data.txt")["e_tag"]
+    assert e_tag is not None
+
+    # Simulate an interrupted download of the same object
+    (tmp_path / "data.txt.part").write_bytes(data[:1234])
+    (tmp_path / "data.txt.part.etag").write_text(e_tag)
+
+    obs.download(store, "data.txt", tmp_path / "data.txt", fsync="always")
+    assert (tmp_path / "data.txt").read_bytes() == data
+
+
+def test_download_restarts_if_changed(tmp_path: Path):
+    store = MemoryStore()
+    data = b"0123456789" * 1000
+    obs.put(store, "data.txt", data)
+
+    # A partial download of a different version of the object is discarded
+    (tmp_path / "data.txt.part").write_bytes(b"x" * 1234)
+    (tmp_path / "data.txt.part.etag").write_text("stale-etag")
+
+    obs.download(store, "data.txt", tmp_path / "data.txt")
+    assert (tmp_path / "data.txt").read_bytes() == data
+
+
+@pytest.fixture
+def truncating_server():
+    """Serve `data.txt`, ending the first GET response halfway through the object."""
+    data = b"0123456789"
+    state = {"gets": []}
+
+    class Handler(BaseHTTPRequestHandler):
+        def send_meta(self, status: int, length: int):
+            self.send_response(status)
+            self.send_header("Content-Length", str(length))
+            self.send_header("ETag", '"v1"')
+            self.send_header("Last-Modified", "Mon, 01 Jan 2024 00:00:00 GMT")
+
+        def do_HEAD(self):
+            self.send_meta(200, len(data))
+            self.end_headers()
+
+        def do_GET(self):
+            state["gets"].append(time.monotonic())
+            range_header = self.headers.get("Range")
+            if range_header is None:
+                self.send_meta(200, len(data) // 2)
+                self.end_headers()
+                self.wfile.write(data[: len(data) // 2])
+                return
+            start = int(range_header.removeprefix("bytes=").split("-")[0])
+            self.send_meta(206, len(data) - start)
+            content_range = f"bytes {start}-{len(data) - 1}/{len(data)}"
+            self.send_header("Content-Range", content_range)
+            self.end_headers()
+            self.wfile.write(data[start:])
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}", data, state
+    server.shutdown()
+
+
+def test_download_resume_backs_off(tmp_path: Path, truncating_server):
+    url, data, state = truncating_server
+    store = HTTPStore.from_url(
+        url,
+        client_options={"allow_http": True},
+        retry_config={
+            "backoff": {
+                "init_backoff": timedelta(milliseconds=500),
+                "max_backoff": timedelta(milliseconds=500),
+                "base": 2,
+            },
+        },
+    )
+
+    obs.download(store, "data.txt", tmp_path / "data.txt")
+    assert (tmp_path / "data.txt").read_bytes() == data
+    # The download is resumed after the backoff of the store's retry_config
+    assert len(state["gets"]) == 2
+    assert state["gets"][1] - state["gets"][0] >= 0.4
+
+
+def test_download_invalid_fsync(tmp_path: Path):
+    store = MemoryStore()
+    with pytest.raises(ValueError, match="fsync"):
+        obs.download(store, "data.txt", tmp_path / "data.txt", fsync="sometimes")  # type: ignore
+
+
+@pytest.mark.asyncio
+async def test_download_async(tmp_path: Path):
+    store = MemoryStore()
+    await obs.put_async(store, "data.txt", b"foo")
+
+    await obs.download_async(store, "data.txt", tmp_path / "data.txt")
+    assert (tmp_path / "data.txt").read_bytes() == b"foo"
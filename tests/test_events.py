@@ -0,0 +1,148 @@
+import base64
+import json
+
+import pytest
+
+import obstore as obs
+from obstore.store import S3Store
+
+AZURE_ACCOUNT_ID = (
+    "/subscriptions/id/resourceGroups/group/providers/Microsoft.Storage"
+    "/storageAccounts/account"
+)
+
+
+def s3_record(event_name: str, key: str, bucket: str = "bucket") -> dict:
+    return {
+        "eventName": event_name,
+        "eventTime": "2025-01-01T00:00:00.000Z",
+        "s3": {
+            "bucket": {"name": bucket},
+            "object": {"key": key, "size": 3, "eTag": "abc"},
+        },
+    }
+
+
+def test_parse_s3_notification():
+    payload = {
+        "Records": [
+            s3_record("ObjectCreated:Put", "data/file+name%21.txt"),
+            s3_record("ObjectRemoved:Delete", "data/old.txt"),
+            s3_record("ObjectRestore:Completed", "data/archived.txt"),
+        ],
+    }
+    events = obs.parse_events(json.dumps(payload))
+    assert [(event["change"], event["path"]) for event in events] == [
+        ("created", "data/file name!.txt"),
+        ("deleted", "data/old.txt"),
+    ]
+    assert events[0]["meta"].size == 3
+    assert events[0]["meta"].e_tag == '"abc"'
+
+
+def test_parse_s3_notification_in_sqs_message():
+    notification = {"Records": [s3_record("ObjectCreated:Put", "file.txt")]}
+    sns_message = {"Type": "Notification", "Message": json.dumps(notification)}
+    payload = {"Records": [{"eventSource": "aws:sqs", "body": json.dumps(sns_message)}]}
+    events = obs.parse_events(payload)
+    assert [event["path"] for event in events] == ["file.txt"]
+
+    assert obs.parse_events({"Event": "s3:TestEvent"}) == []
+
+
+def test_parse_events_store_relative():
+    store = S3Store("bucket", prefix="data", skip_signature=True)
+    payload = {
+        "Records": [
+            s3_record("ObjectCreated:Put", "data/a.txt"),
+            s3_record("ObjectCreated:Put", "other/b.txt"),
+            s3_record("ObjectCreated:Put", "data/c.txt", bucket="other-bucket"),
+        ],
+    }
+    events = obs.parse_events(payload, store=store)
+    assert [event["path"] for event in events] == ["a.txt"]
+
+
+def test_parse_eventbridge_event():
+    payload = {
+        "source": "aws.s3",
+        "detail-type": "Object Created",
+        "time": "2025-01-01T00:00:00Z",
+        "detail": {
+            "bucket": {"name": "bucket"},
+            "object": {"key": "file name.txt", "size": 5, "version-id": "v1"},
+        },
+    }
+    [event] = obs.parse_events(payload)
+    assert event["change"] == "created"
+    assert event["path"] == "file name.txt"
+    assert event["meta"].version == "v1"
+
+
+def test_parse_pubsub_message():
+    def message(event_type: str, **attributes: str) -> dict:
+        data = {
+            "bucket": "bucket",
+            "name": "file.txt",
+            "size": "3",
+            "updated": "2025-01-01T00:00:00.000Z",
+            "generation": "2",
+            "md5Hash": base64.b64encode(bytes(range(16))).decode(),
+        }
+        return {
+            "message": {
+                "attributes": {
+                    "eventType": event_type,
+                    "bucketId": "bucket",
+                    "objectId": "file.txt",
+                    **attributes,
+                },
+                "data": base64.b64encode(json.dumps(data).encode()).decode(),
+            },
+            "subscription": "projects/project/subscriptions/subscription",
+        }
+
+    payloads = [
+        message("OBJECT_FINALIZE"),
+        message("OBJECT_DELETE", overwrittenByGeneration="3"),
+        message("OBJECT_FINALIZE", overwroteGeneration="2"),
+        message("OBJECT_DELETE"),
+        message("OBJECT_METADATA_UPDATE"),
+    ]
+    events = [event for payload in payloads for event in obs.parse_events(payload)]
+    assert [event["change"] for event in events] == ["created", "modified", "deleted"]
+    assert events[0]["meta"].size == 3
+    assert events[0]["meta"].e_tag == '"000102030405060708090a0b0c0d0e0f"'
+
+
+def test_parse_event_grid_events():
+    payload = [
+        {
+            "topic": AZURE_ACCOUNT_ID,
+            "subject": "/blobServices/default/containers/container/blobs/dir/file.txt",
+            "eventType": "Microsoft.Storage.BlobCreated",
+            "eventTime": "2025-01-01T00:00:00.1234567Z",
+            "data": {"contentLength": 3, "eTag": "0x8D4BCC2E4835CD0"},
+        },
+        {
+            "source": AZURE_ACCOUNT_ID,
+            "subject": "/blobServices/default/containers/container/blobs/old.txt",
+            "type": "Microsoft.Storage.BlobDeleted",
+            "time": "2025-01-01T00:00:00Z",
+            "data": {},
+        },
+    ]
+    events = obs.parse_events(json.dumps(payload).encode())
+    assert [(event["change"], event["path"]) for event in events] == [
+        ("created", "dir/file.txt"),
+        ("deleted", "old.txt"),
+    ]
+    assert events[0]["meta"].size == 3
+
+
+def test_parse_events_invalid():
+    with pytest.raises(ValueError, match="not valid JSON"):
+        obs.parse_events("not json")
+
+    with pytest.raises(ValueError, match="Invalid event notification"):
+        obs.parse_events({"foo": "bar"})
@@ -0,0 +1,84 @@
+import pytest
+from arro3.core import Table
+
+import obstore as obs
+from obstore.store import LocalStore, MemoryStore
+
+
+@pytest.fixture
+def store():
+    store = MemoryStore()
+    for path in [
+        "data/year=2023/month=01/a.parquet",
+        "data/year=2023/month=07/b.parquet",
+        "data/year=2024/month=02/c.parquet",
+        "data/year=2024/month=02/c.json",
+        "data/year=2024/nested/month=03/d.parquet",
+        "data/README.md",
+        "other/e.parquet",
+    ]:
+        store.put(path, b"foo")
+    return store
+
+
+def paths(metas):
+    return [meta["path"] for meta in metas]
+
+
+def test_expand_paths(store):
+    assert paths(obs.expand_paths(store, "data/year=*/month=0[1-6]/*.parquet")) == [
+        "data/year=2023/month=01/a.parquet",
+        "data/year=2024/month=02/c.parquet",
+    ]
+    assert paths(obs.expand_paths(store, "data/year=202?/month=02/c.*")) == [
+        "data/year=2024/month=02/c.json",
+        "data/year=2024/month=02/c.parquet",
+    ]
+    assert paths(obs.expand_paths(store, "data/**/*.parquet")) == [
+        "data/year=2023/month=01/a.parquet",
+        "data/year=2023/month=07/b.parquet",
+        "data/year=2024/month=02/c.parquet",
+        "data/year=2024/nested/month=03/d.parquet",
+    ]
+    assert paths(obs.expand_paths(store, "*/*.parquet")) == ["other/e.parquet"]
+    # `*` doesn't match across directories
+    assert paths(obs.expand_paths(store, "data/*.md")) == ["data/README.md"]
+    assert paths(obs.expand_paths(store, "data/*")) == ["data/README.md"]
+
+
+def test_expand_paths_literal(store):
+    assert paths(obs.expand_paths(store, "/data/README.md")) == ["data/README.md"]
+    assert obs.expand_paths(store, "data/missing.md") == []
+    assert obs.expand_paths(store, "missing/*/*.parquet") == []
+
+
+def test_expand_paths_local(tmp_path):
+    store = LocalStore(tmp_path)
+    for path in ["a/1.csv", "a/2.txt", "b/3.csv", "b/c/4.csv"]:
+        store.put(path, b"foo")
+    assert paths(obs.expand_paths(store, "*/*.csv")) == ["a/1.csv", "b/3.csv"]
+    assert paths(obs.expand_paths(store, "**/*.csv")) == [
+        "a/1.csv",
+        "b/3.csv",
+        "b/c/4.csv",
+    ]
+
+
+def test_expand_paths_arrow(store):
+    table = obs.expand_paths(store, "data/**/*.parquet", return_arrow=True)
+    assert isinstance(table, Table)
+    assert table.num_rows == 4
+    assert table["path"][0].as_py() == "data/year=2023/month=01/a.parquet"
+
+
+def test_expand_paths_invalid(store):
+    with pytest.raises(ValueError, match="Invalid glob pattern"):
+        obs.expand_paths(store, "data/***")
+    with pytest.raises(ValueError, match="must not be empty"):
+        obs.expand_paths(store, "/")
+
+
+@pytest.mark.asyncio
+async def test_expand_paths_async(store):
+    metas = await obs.expand_paths_async(store, "data/year=2024/*/*.json")
+    assert paths(metas) == ["data/year=2024/month=02/c.json"]
@@ -0,0 +1,74 @@
+import pytest
+
+import obstore as obs
+from obstore import ObstoreFileSystem
+from obstore.store import MemoryStore
+
+
+def test_ls_and_info():
+    store = MemoryStore()
+    fs = ObstoreFileSystem(store)
+    fs.pipe_file("data/a.txt", b"foo")
+    fs.pipe_file("data/nested/b.txt", b"barbaz")
+
+    assert sorted(fs.ls("data", detail=False)) == ["data/a.txt", "data/nested"]
+
+    entries = {entry["name"]: entry for entry in fs.ls("/data/")}
+    assert entries["data/a.txt"]["type"] == "file"
+    assert entries["data/a.txt"]["size"] == 3
+    assert entries["data/nested"]["type"] == "directory"
+
+    # Listing a file returns the file itself
+    assert fs.ls("data/a.txt", detail=False) == ["data/a.txt"]
+
+    assert fs.info("data/nested/b.txt")["size"] == 6
+    assert fs.info("data/nested")["type"] == "directory"
+
+    with pytest.raises(FileNotFoundError):
+        fs.info("missing")
+
+    with pytest.raises(FileNotFoundError):
+        fs.ls("missing")
+
+
+def test_cat_file_ranges():
+    fs = ObstoreFileSystem(MemoryStore())
+    fs.pipe_file("file.txt", b"hello world")
+
+    assert fs.cat_file("file.txt") == b"hello world"
+    assert fs.cat_file("file.txt", start=6) == b"world"
+    assert fs.cat_file("file.txt", start=-5) == b"world"
+    assert fs.cat_file("file.txt", start=0, end=5) == b"hello"
+    assert fs.cat_file("file.txt", start=-5, end=-1) == b"worl"
+    assert fs.cat_file("file.txt", start=8, end=4) == b""
+
+
+def test_open_cp_rm():
+    store = MemoryStore()
+    fs = ObstoreFileSystem(store)
+
+    with fs.open("file.txt", "wb") as f:
+        f.write(b"foo")
+        f.write(b"bar")
+
+    assert fs.open("file.txt").read() == b"foobar"
+
+    with pytest.raises(ValueError, match="Unsupported mode"):
+        fs.open("file.txt", "ab")
+
+    fs.cp_file("file.txt", "dir/copy.txt")
+    fs.pipe_file("dir/other.txt", b"baz")
+    assert obs.get(store, "dir/copy.txt").bytes() == b"foobar"
+
+    fs.rm("file.txt")
+    with pytest.raises(FileNotFoundError):
+        fs.info("file.txt")
+
+    with pytest.raises(FileNotFoundError):
+        fs.rm("file.txt")
+
+    with pytest.raises(IsADirectoryError):
+        fs.rm("dir")
+
+    fs.rm("dir", recursive=True)
+    assert obs.list(store).collect() == []
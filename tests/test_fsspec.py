@@ -393,6 +393,27 @@ def test_multi_file_ops(fs: FsspecStore):
     assert out == [f"{TEST_BUCKET_NAME}/afile"]
 
 
+def test_rm_missing(fs: FsspecStore):
+    with pytest.raises(FileNotFoundError):
+        fs.rm(f"{TEST_BUCKET_NAME}/missing")
+
+    with pytest.raises(FileNotFoundError):
+        fs.rm(f"{TEST_BUCKET_NAME}/missing-dir", recursive=True)
+
+
+def test_cat_file_open_ended(fs: FsspecStore):
+    path = f"{TEST_BUCKET_NAME}/hello.txt"
+    fs.pipe_file(path, b"hello world")
+
+    assert fs.cat_file(path, start=6) == b"world"
+    assert fs.cat_file(path, start=-5) == b"world"
+    assert fs.cat_file(path, end=5) == b"hello"
+    assert fs.cat_file(path, start=-5, end=-1) == b"worl"
+    assert fs.cat_file(path, start=8, end=4) == b""
+
+    fs.rm(path)
+
+
 def test_cat_ranges_one(fs: FsspecStore):
     data1 = os.urandom(10000)
     fs.pipe_file(f"{TEST_BUCKET_NAME}/data1", data1)
@@ -1,6 +1,14 @@
+import gzip
+import hashlib
+import io
+import pickle
+from datetime import timedelta
+
 import pytest
 
-from obstore.store import MemoryStore
+import obstore as obs
+from obstore.exceptions import TimeoutError as ObstoreTimeoutError
+from obstore.store import LocalStore, MemoryStore, ThrottleStore
 
 
 def test_stream_sync():
@@ -46,6 +54,102 @@ async def test_stream_async():
     assert pos == len(data)
 
 
+def test_stream_decompress():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    store.put("data.txt.gz", gzip.compress(data))
+
+    resp = store.get("data.txt.gz")
+    chunks = list(resp.stream(min_chunk_size=1024, decompress="auto"))
+    assert b"".join(chunks) == data
+    assert all(len(chunk) >= 1024 for chunk in chunks[:-1])
+
+    resp = store.get("data.txt.gz")
+    assert b"".join(resp.stream(decompress="gzip")) == data
+
+
+def test_stream_decompress_zstd():
+    zstandard = pytest.importorskip("zstandard")
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    store.put("data.zst", zstandard.ZstdCompressor().compress(data))
+
+    resp = store.get("data.zst")
+    assert b"".join(resp.stream(decompress="auto")) == data
+
+
+@pytest.mark.asyncio
+async def test_stream_decompress_async():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    await store.put_async(
+        "data",
+        gzip.compress(data),
+        attributes={"Content-Encoding": "gzip"},
+    )
+
+    resp = await store.get_async("data")
+    chunks = [chunk async for chunk in resp.stream(decompress="auto")]
+    assert b"".join(chunks) == data
+
+
+def test_stream_to(tmp_path):
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    store.put("big-data.txt", data)
+
+    path = tmp_path / "big-data.txt"
+    path.write_bytes(b"previous contents, which are longer than the new ones" * 5000)
+    assert store.get("big-data.txt").stream_to(path) == len(data)
+    assert path.read_bytes() == data
+
+    buf = io.BytesIO()
+    nbytes, checksum = store.get("big-data.txt").stream_to(buf, checksum="sha256")
+    assert nbytes == len(data)
+    assert checksum == hashlib.sha256(data).hexdigest()
+    assert buf.getvalue() == data
+
+    resp = store.get("big-data.txt")
+    with pytest.raises(TypeError, match="file-like"):
+        resp.stream_to(1)  # type: ignore[call-overload]
+    with pytest.raises(ValueError, match="checksum"):
+        resp.stream_to(buf, checksum="md5")  # type: ignore[call-overload]
+
+
+@pytest.mark.asyncio
+async def test_stream_to_async(tmp_path):
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    await store.put_async("big-data.txt", data)
+
+    resp = await store.get_async("big-data.txt")
+    buf = io.BytesIO()
+    nbytes, checksum = await resp.stream_to_async(buf, checksum="sha256")
+    assert nbytes == len(data)
+    assert checksum == hashlib.sha256(data).hexdigest()
+    assert buf.getvalue() == data
+
+    resp = await store.get_async("big-data.txt")
+    assert await resp.stream_to_async(str(tmp_path / "big-data.txt")) == len(data)
+
+
+def test_get_result_pickle():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo")
+
+    resp = obs.get(store, "file.txt")
+    with pytest.raises(TypeError, match="GetResult can't be pickled"):
+        pickle.dumps(resp)
+
+    with pytest.raises(TypeError, match="BytesStream can't be pickled"):
+        pickle.dumps(resp.stream())
+
+
 def test_get_with_options():
     store = MemoryStore()
 
@@ -96,6 +200,40 @@ def test_get_with_options_suffix():
     assert buf == data[result_range[0] : result_range[1]]
 
 
+def test_get_if_changed():
+    store = MemoryStore()
+    store.put("file.txt", b"foo")
+
+    result = obs.get_if_changed(store, "file.txt")
+    assert result is not None
+    etag = result.meta.e_tag
+    # Python datetimes drop the nanoseconds that MemoryStore compares
+    last_modified = result.meta.last_modified + timedelta(microseconds=1)
+    assert etag is not None
+    assert result.bytes() == b"foo"
+
+    assert obs.get_if_changed(store, "file.txt", etag) is None
+    assert store.get_if_changed("file.txt", last_modified=last_modified) is None
+
+    store.put("file.txt", b"bar")
+    result = obs.get_if_changed(store, "file.txt", etag)
+    assert result is not None
+    assert result.meta.e_tag != etag
+    assert result.bytes() == b"bar"
+
+    with pytest.raises(FileNotFoundError):
+        obs.get_if_changed(store, "missing.txt", etag)
+
+
+@pytest.mark.asyncio
+async def test_get_if_changed_async():
+    store = MemoryStore()
+    etag = store.put("file.txt", b"foo").e_tag
+
+    assert await obs.get_if_changed_async(store, "file.txt", etag) is None
+    assert await store.get_if_changed_async("file.txt", "other") is not None
+
+
 def test_get_range():
     store = MemoryStore()
 
@@ -112,6 +250,77 @@ def test_get_range():
     assert view == data[5:15]
 
 
+def test_get_range_numpy():
+    np = pytest.importorskip("numpy")
+    store = MemoryStore()
+
+    data = np.arange(100, dtype=np.float32)
+    path = "array.bin"
+
+    store.put(path, data.tobytes())
+    arr = store.get_range(path, start=40, length=40, return_numpy=True, dtype="float32")
+    assert arr.dtype == np.float32
+    assert not arr.flags.writeable
+    np.testing.assert_array_equal(arr, data[10:20])
+
+    arr = store.get_range(path, start=0, length=4, return_numpy=True)
+    assert arr.dtype == np.uint8
+    assert arr.tobytes() == data.tobytes()[:4]
+
+    with pytest.raises(ValueError, match="return_numpy"):
+        obs.get_range(store, path, start=0, length=4, dtype="float32")
+
+
+@pytest.mark.asyncio
+async def test_get_range_numpy_async():
+    np = pytest.importorskip("numpy")
+    store = MemoryStore()
+
+    data = np.arange(100, dtype=np.int64)
+    path = "array.bin"
+
+    await store.put_async(path, data.tobytes())
+    arr = await store.get_range_async(
+        path,
+        start=0,
+        length=80,
+        return_numpy=True,
+        dtype=np.int64,
+    )
+    np.testing.assert_array_equal(arr, data[:10])
+
+
+def test_get_result_to_numpy():
+    np = pytest.importorskip("numpy")
+    store = MemoryStore()
+
+    data = np.arange(1000, dtype=np.float64)
+    path = "array.bin"
+
+    store.put(path, data.tobytes())
+    arr = store.get(path).to_numpy(dtype=np.float64)
+    assert not arr.flags.writeable
+    np.testing.assert_array_equal(arr, data)
+
+    with pytest.raises(ValueError, match="multiple of element size"):
+        store.get(path, options={"range": (0, 7)}).to_numpy(dtype=np.float64)
+
+
+@pytest.mark.asyncio
+async def test_get_result_to_numpy_async():
+    np = pytest.importorskip("numpy")
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog"
+    path = "data.txt"
+
+    await store.put_async(path, data)
+    resp = await store.get_async(path)
+    arr = await resp.to_numpy_async()
+    assert arr.dtype == np.uint8
+    assert arr.tobytes() == data
+
+
 def test_get_ranges():
     store = MemoryStore()
 
@@ -136,6 +345,92 @@ def test_get_ranges():
         assert memoryview(buffer) == data[start:end]
 
 
+def test_read_ranges_into():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 100
+    path = "big-data.txt"
+
+    store.put(path, data)
+
+    buffers = [bytearray(10), bytearray(20)]
+    written = store.read_ranges_into(path, buffers, starts=[5, 100])
+    assert written == [10, 20]
+    assert buffers[0] == data[5:15]
+    assert buffers[1] == data[100:120]
+
+    # Ranges shorter than their buffers are written to the start of the buffer
+    buffer = bytearray(b"x" * 10)
+    written = store.read_ranges_into(
+        path,
+        [memoryview(buffer)],
+        starts=[0],
+        lengths=[4],
+    )
+    assert written == [4]
+    assert buffer == data[:4] + b"x" * 6
+
+    # A range past the end of the object writes the remainder of the object
+    buffer = bytearray(10)
+    written = store.read_ranges_into(path, [buffer], starts=[len(data) - 3])
+    assert written == [3]
+    assert buffer[:3] == data[-3:]
+
+
+@pytest.mark.asyncio
+async def test_read_ranges_into_async():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 100
+    path = "big-data.txt"
+
+    await store.put_async(path, data)
+
+    buffers = [bytearray(10), bytearray(10)]
+    written = await store.read_ranges_into_async(
+        path,
+        buffers,
+        starts=[0, 50],
+        ends=[10, 60],
+    )
+    assert written == [10, 10]
+    assert buffers == [data[0:10], data[50:60]]
+
+
+def test_read_ranges_into_coalesced(tmp_path):
+    # LocalStore streams the body of each request in chunks
+    store = LocalStore(tmp_path)
+
+    data = bytes(range(256)) * 12_000
+    path = "big-data.bin"
+    store.put(path, data)
+
+    # Unordered and overlapping ranges, with the last too far from the others to share a request
+    starts = [100_000, 0, 50, len(data) - 10]
+    lengths = [50_000, 100, 100, 10]
+    buffers = [bytearray(length) for length in lengths]
+    written = store.read_ranges_into(path, buffers, starts=starts, lengths=lengths)
+    assert written == lengths
+    for buffer, start, length in zip(buffers, starts, lengths):
+        assert buffer == data[start : start + length]
+
+
+def test_read_ranges_into_invalid_buffers():
+    store = MemoryStore()
+
+    path = "data.txt"
+    store.put(path, b"the quick brown fox jumps over the lazy dog")
+
+    with pytest.raises(ValueError, match="one buffer for each"):
+        store.read_ranges_into(path, [bytearray(4)], starts=[0, 4])
+
+    with pytest.raises(ValueError, match="too small"):
+        store.read_ranges_into(path, [bytearray(4)], starts=[0], lengths=[8])
+
+    with pytest.raises(BufferError, match="writable"):
+        store.read_ranges_into(path, [b"read-only"], starts=[0])
+
+
 def test_get_range_invalid_range():
     store = MemoryStore()
 
@@ -168,3 +463,102 @@ def test_get_ranges_invalid_range():
 
     with pytest.raises(ValueError, match="Invalid range"):
         store.get_ranges(path, starts=[10, 20], lengths=[10, 0])
+
+
+def test_get_timeout():
+    inner = MemoryStore()
+    inner.put("file.txt", b"foo")
+    store = ThrottleStore(inner, {"wait_get_per_call": timedelta(seconds=5)})
+
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        obs.get(store, "file.txt", timeout=timedelta(milliseconds=50))
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        store.head("file.txt", timeout=timedelta(milliseconds=50))
+
+    # A generous timeout doesn't affect the call
+    assert obs.get(inner, "file.txt", timeout=timedelta(seconds=5)).bytes() == b"foo"
+
+
+@pytest.mark.asyncio
+async def test_get_async_timeout():
+    inner = MemoryStore()
+    inner.put("file.txt", b"foo")
+    store = ThrottleStore(inner, {"wait_get_per_call": timedelta(seconds=5)})
+
+    with pytest.raises(ObstoreTimeoutError, match="Deadline exceeded"):
+        await obs.get_async(store, "file.txt", timeout=timedelta(milliseconds=50))
+
+
+def test_get_result_context_manager():
+    store = MemoryStore()
+    store.put("file.txt", b"foo")
+
+    with obs.get(store, "file.txt") as resp:
+        assert resp.meta.size == 3
+
+    with pytest.raises(ValueError, match="disposed"):
+        resp.bytes()
+
+    # Exiting after the body was consumed is a no-op
+    with obs.get(store, "file.txt") as resp:
+        assert resp.bytes() == b"foo"
+
+
+@pytest.mark.asyncio
+async def test_get_result_context_manager_async():
+    store = MemoryStore()
+    await store.put_async("file.txt", b"foo")
+
+    async with await obs.get_async(store, "file.txt") as resp:
+        assert resp.meta.size == 3
+
+    with pytest.raises(ValueError, match="disposed"):
+        await resp.bytes_async()
+
+
+def test_iter_for_response():
+    store = MemoryStore()
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    store.put("data.txt", data, attributes={"Content-Type": "text/plain"})
+
+    stream = obs.get(store, "data.txt").iter_for_response(chunk_size=1000)
+    assert stream.status == 200
+    assert stream.headers["content-length"] == str(len(data))
+    assert stream.headers["content-type"] == "text/plain"
+    assert "content-range" not in stream.headers
+
+    chunks = list(stream)
+    assert all(isinstance(chunk, bytes) for chunk in chunks)
+    assert all(len(chunk) <= 1000 for chunk in chunks)
+    assert b"".join(chunks) == data
+
+    stream = store.get("data.txt", options={"range": (10, 20)}).iter_for_response()
+    assert stream.status == 206
+    assert stream.headers["content-range"] == f"bytes 10-19/{len(data)}"
+    assert list(stream) == [data[10:20]]
+
+    with pytest.raises(ValueError, match="chunk_size"):
+        obs.get(store, "data.txt").iter_for_response(chunk_size=0)
+
+
+@pytest.mark.asyncio
+async def test_iter_for_response_async():
+    store = MemoryStore()
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    await store.put_async("data.txt", data)
+
+    resp = await obs.get_async(store, "data.txt")
+    stream = resp.iter_for_response(chunk_size=1000)
+    chunks = [chunk async for chunk in stream]
+    assert all(len(chunk) <= 1000 for chunk in chunks)
+    assert b"".join(chunks) == data
+
+
+def test_iter_for_response_close():
+    store = MemoryStore()
+    store.put("data.txt", b"foo" * 1000)
+
+    stream = obs.get(store, "data.txt").iter_for_response(chunk_size=100)
+    assert len(next(stream)) == 100
+    stream.close()
+    assert list(stream) == []
@@ -1,10 +1,17 @@
+import pickle
+import sys
+from datetime import timedelta
+
 import pandas as pd
 import polars as pl
 import pyarrow as pa
 import pytest
 from arro3.core import RecordBatch, Table
 
-from obstore.store import MemoryStore
+import obstore as obs
+from obstore.exceptions import InvalidPathError
+from obstore.runtime import set_object_meta_dicts
+from obstore.store import AzureStore, LocalStore, MemoryStore
 
 
 def test_list():
@@ -96,6 +103,26 @@ def test_list_with_delimiter():
     assert objects["path"][1].as_py() == "a/file2.txt"
 
 
+def test_list_result():
+    store = MemoryStore()
+    store.put("a/file1.txt", b"foo")
+    store.put("b/file2.txt", b"bar")
+    store.put("file3.txt", b"baz")
+
+    result = store.list_with_delimiter()
+    assert isinstance(result, obs.ListResult)
+    assert result.common_prefixes == ["a", "b"]
+    assert [meta.path for meta in result.objects] == ["file3.txt"]
+    assert result == store.list_with_delimiter()
+    assert repr(result) == "ListResult(common_prefixes=2, objects=1)"
+    assert result.to_dict()["common_prefixes"] == ["a", "b"]
+
+    batch = pa.record_batch(result)
+    assert batch.num_rows == 1
+    assert batch["path"][0].as_py() == "file3.txt"
+    assert RecordBatch(result.to_arrow()).num_rows == 1
+
+
 @pytest.mark.asyncio
 async def test_list_with_delimiter_async():
     store = MemoryStore()
@@ -140,3 +167,359 @@ def test_list_as_arrow_to_polars():
     stream = store.list(return_arrow=True, chunk_size=10)
     _pl_df = pl.DataFrame(next(stream))
     _df = pa.record_batch(next(stream)).to_pandas(types_mapper=pd.ArrowDtype)
+
+
+def test_list_arrow_c_stream():
+    store = MemoryStore()
+
+    for i in range(25):
+        store.put(f"file{i}.txt", b"foo")
+
+    stream = store.list(chunk_size=10)
+    reader = pa.RecordBatchReader.from_stream(stream)
+    assert reader.schema.names == ["path", "last_modified", "size", "e_tag", "version"]
+
+    batches = list(reader)
+    assert [batch.num_rows for batch in batches] == [10, 10, 5]
+
+    table = pa.Table.from_batches(batches)
+    assert sorted(table["path"].to_pylist()) == sorted(f"file{i}.txt" for i in range(25))
+
+    # The stream has been consumed
+    assert stream.collect() == []
+
+
+def test_list_arrow_c_stream_to_polars():
+    store = MemoryStore()
+
+    for i in range(10):
+        store.put(f"file{i}.txt", b"foo")
+
+    df = pl.DataFrame(store.list(return_arrow=True))
+    assert df.height == 10
+
+
+def test_list_stable_chunks():
+    store = MemoryStore()
+
+    for i in range(25):
+        store.put(f"file{i:02}.txt", b"foo")
+
+    stream = store.list(chunk_size=10, return_arrow=True, stable_chunks=True)
+    batches = [RecordBatch(batch) for batch in stream]
+    assert [batch.num_rows for batch in batches] == [10, 10, 5]
+
+    paths = [path.as_py() for batch in batches for path in batch["path"]]
+    assert paths == [f"file{i:02}.txt" for i in range(25)]
+
+
+def test_list_order_by():
+    store = MemoryStore()
+
+    for i, size in enumerate([3, 1, 2]):
+        store.put(f"file{i}.txt", b"x" * size)
+
+    paths = [meta.path for meta in store.list(order_by="size").collect()]
+    assert paths == ["file1.txt", "file2.txt", "file0.txt"]
+
+    stream = store.list(order_by="path", descending=True)
+    paths = [meta.path for meta in stream.collect()]
+    assert paths == ["file2.txt", "file1.txt", "file0.txt"]
+
+    with pytest.raises(ValueError, match="descending requires order_by"):
+        store.list(descending=True)
+
+    with pytest.raises(ValueError, match="Unexpected order_by"):
+        store.list(order_by="name")  # type: ignore
+
+
+def test_list_order_by_spills_to_disk():
+    store = MemoryStore()
+
+    n = 2500
+    for i in range(n):
+        store.put(f"file{i:04}.txt", b"x" * ((i * 7919) % n))
+
+    # A tiny memory limit sorts in runs spilled to temporary files
+    stream = store.list(order_by="size", descending=True, sort_memory_limit=1)
+    sizes = [meta.size for chunk in stream for meta in chunk]
+    assert sizes == sorted(sizes, reverse=True)
+    assert len(sizes) == n
+
+
+def test_list_chunk_timeout():
+    store = MemoryStore()
+
+    for i in range(25):
+        store.put(f"file{i:02}.txt", b"foo")
+
+    # Objects that arrive in time fill their chunks as usual
+    stream = store.list(chunk_size=10, chunk_timeout=timedelta(seconds=5))
+    assert [len(chunk) for chunk in stream] == [10, 10, 5]
+
+
+def test_list_glob():
+    store = MemoryStore()
+
+    store.put("a/file1.parquet", b"foo")
+    store.put("a/b/file2.parquet", b"foo")
+    store.put("a/b/file3.csv", b"foo")
+    store.put("file4.parquet", b"foo")
+
+    paths = sorted(meta["path"] for meta in store.list(glob="**/*.parquet").collect())
+    assert paths == ["a/b/file2.parquet", "a/file1.parquet", "file4.parquet"]
+
+    # `*` does not match across path segments
+    paths = sorted(meta["path"] for meta in store.list(glob="a/*.parquet").collect())
+    assert paths == ["a/file1.parquet"]
+
+    # The literal part of the pattern narrows the listing, within the given prefix
+    paths = sorted(meta["path"] for meta in store.list("a", glob="a/b/*").collect())
+    assert paths == ["a/b/file2.parquet", "a/b/file3.csv"]
+
+    paths = sorted(meta["path"] for meta in store.list("a/b", glob="a/*.parquet").collect())
+    assert paths == []
+
+    with pytest.raises(ValueError, match="Invalid glob pattern"):
+        store.list(glob="a/***")
+
+
+def test_list_filter():
+    store = MemoryStore()
+
+    store.put("small.txt", b"a")
+    store.put("medium.txt", b"a" * 10)
+    store.put("large.txt", b"a" * 100)
+
+    result = store.list(filter={"min_size": 5}).collect()
+    assert sorted(meta["path"] for meta in result) == ["large.txt", "medium.txt"]
+
+    result = store.list(filter={"min_size": 5, "max_size": 50}).collect()
+    assert [meta["path"] for meta in result] == ["medium.txt"]
+
+    last_modified = store.head("large.txt")["last_modified"]
+    result = store.list(filter={"modified_after": last_modified}).collect()
+    assert result == []
+
+    result = store.list(glob="*.txt", filter={"max_size": 1}).collect()
+    assert [meta["path"] for meta in result] == ["small.txt"]
+
+    with pytest.raises(ValueError, match="did you mean 'min_size'"):
+        store.list(filter={"minsize": 5})
+
+
+def test_list_page():
+    store = MemoryStore()
+
+    for i in range(25):
+        store.put(f"data/file{i:02}.txt", b"foo")
+    store.put("other.txt", b"foo")
+
+    paths = []
+    start_after = None
+    pages = 0
+    while True:
+        page = obs.list_page(store, "data", max_keys=10, start_after=start_after)
+        paths.extend(meta["path"] for meta in page["objects"])
+        pages += 1
+        start_after = page["next_start_after"]
+        if start_after is None:
+            break
+
+    assert pages == 3
+    assert paths == [f"data/file{i:02}.txt" for i in range(25)]
+
+    with pytest.raises(ValueError, match="max_keys"):
+        obs.list_page(store, max_keys=0)
+
+
+def test_list_page_unordered_store(tmp_path):
+    store = LocalStore(tmp_path)
+
+    names = [f"file{i:02}.txt" for i in range(15)]
+    for name in reversed(names):
+        store.put(name, b"foo")
+
+    page = obs.list_page(store, max_keys=10)
+    assert [meta["path"] for meta in page["objects"]] == names[:10]
+    assert page["next_start_after"] == names[9]
+
+    page = obs.list_page(store, max_keys=10, start_after=page["next_start_after"])
+    assert [meta["path"] for meta in page["objects"]] == names[10:]
+    assert page["next_start_after"] is None
+
+    page = obs.list_page(store, max_keys=sys.maxsize, start_after=names[4])
+    assert [meta["path"] for meta in page["objects"]] == names[5:]
+
+
+def test_list_page_unordered_store_listing(tmp_path):
+    store = LocalStore(tmp_path)
+    for i in range(4):
+        store.put(f"file{i}.txt", b"foo")
+
+    # The pages after the first are served from the listing of the first page
+    page = obs.list_page(store, max_keys=2)
+    store.put("file9.txt", b"foo")
+    page = obs.list_page(store, max_keys=2, start_after=page["next_start_after"])
+    assert [meta["path"] for meta in page["objects"]] == ["file2.txt", "file3.txt"]
+    assert page["next_start_after"] is None
+
+    # A new first page lists the store again
+    page = obs.list_page(store, max_keys=2)
+    page = obs.list_page(store, max_keys=3, start_after=page["next_start_after"])
+    assert [meta["path"] for meta in page["objects"]] == [
+        "file2.txt",
+        "file3.txt",
+        "file9.txt",
+    ]
+
+
+def test_list_page_unordered_store_nested(tmp_path):
+    store = LocalStore(tmp_path)
+
+    # The first keys found may well be in order, with smaller keys only found later on
+    names = [f"{d}/file{i}.txt" for d in ["c", "b", "a"] for i in range(4)]
+    for name in names:
+        store.put(name, b"foo")
+
+    paths = []
+    start_after = None
+    while True:
+        page = obs.list_page(store, max_keys=3, start_after=start_after)
+        paths.extend(meta["path"] for meta in page["objects"])
+        start_after = page["next_start_after"]
+        if start_after is None:
+            break
+
+    assert paths == sorted(names)
+
+
+@pytest.mark.asyncio
+async def test_list_page_async():
+    store = MemoryStore()
+    await store.put_async("a.txt", b"foo")
+    await store.put_async("b.txt", b"foo")
+
+    page = await obs.list_page_async(store, max_keys=1)
+    assert [meta["path"] for meta in page["objects"]] == ["a.txt"]
+    assert page["next_start_after"] == "a.txt"
+
+
+# macOS and Windows don't allow file names that aren't valid UTF-8
+non_utf8_names = pytest.mark.skipif(
+    sys.platform != "linux",
+    reason="requires a file system that allows non-UTF-8 file names",
+)
+
+
+def _write_non_utf8_file(tmp_path):
+    (tmp_path / "good.txt").write_bytes(b"foo")
+    with open(bytes(tmp_path) + b"/bad\xff.txt", "wb") as f:  # noqa: PTH123
+        f.write(b"bar")
+
+
+@non_utf8_names
+def test_list_invalid_key_error(tmp_path):
+    _write_non_utf8_file(tmp_path)
+    store = LocalStore(tmp_path)
+
+    with pytest.raises(InvalidPathError, match="non-unicode"):
+        obs.list(store).collect()
+
+
+@non_utf8_names
+def test_list_invalid_key_skip(tmp_path):
+    _write_non_utf8_file(tmp_path)
+    store = LocalStore(tmp_path)
+
+    stream = obs.list(store, on_invalid_key="skip")
+    assert [meta["path"] for meta in stream.collect()] == ["good.txt"]
+    assert stream.invalid_keys == ["bad%FF.txt"]
+
+
+@non_utf8_names
+def test_list_invalid_key_lossy(tmp_path):
+    _write_non_utf8_file(tmp_path)
+    store = LocalStore(tmp_path)
+
+    stream = obs.list(store, on_invalid_key="lossy", return_arrow=True)
+    batch = pa.record_batch(stream.collect())
+    paths = sorted(batch["path"].to_pylist())
+    assert paths == ["bad\ufffd.txt", "good.txt"]
+    assert stream.invalid_keys == ["bad%FF.txt"]
+
+
+def test_list_invalid_key_policy():
+    with pytest.raises(ValueError, match="on_invalid_key"):
+        obs.list(MemoryStore(), on_invalid_key="ignore")  # type: ignore[arg-type]
+
+
+def test_list_include_metadata_unsupported_store():
+    store = MemoryStore()
+    with pytest.raises(ValueError, match="only supported for AzureStore"):
+        obs.list(store, include_metadata=True)
+
+
+def test_list_include_metadata_with_offset():
+    store = AzureStore("container", account_name="account")
+    with pytest.raises(ValueError, match="offset is not supported"):
+        obs.list(store, offset="a", include_metadata=True)
+
+
+def test_list_timeout():
+    store = MemoryStore()
+    store.put("file.txt", b"foo")
+
+    result = obs.list(store, timeout=timedelta(seconds=5)).collect()
+    assert len(result) == 1
+
+    with pytest.raises(ValueError, match="timeout is not supported"):
+        obs.list(
+            AzureStore("container", account_name="account"),
+            include_metadata=True,
+            timeout=timedelta(seconds=5),
+        )
+
+
+def test_object_meta():
+    store = MemoryStore()
+    store.put("file.txt", b"foo")
+
+    meta = obs.head(store, "file.txt")
+    assert isinstance(meta, obs.ObjectMeta)
+    assert meta.path == "file.txt"
+    assert meta.size == 3
+    assert meta.metadata is None
+    assert meta["path"] == meta.path
+    with pytest.raises(KeyError):
+        meta["metadata"]
+
+    assert store.list().collect() == [meta]
+    assert store.get("file.txt").meta == meta
+    assert "ObjectMeta(path='file.txt'" in repr(meta)
+    assert meta.to_dict()["size"] == 3
+
+    with pytest.raises(AttributeError):
+        meta.size = 4  # type: ignore[misc]
+
+
+def test_object_meta_pickle():
+    store = MemoryStore()
+    store.put("file.txt", b"foo")
+    meta = obs.head(store, "file.txt")
+    assert pickle.loads(pickle.dumps(meta)) == meta
+
+
+def test_object_meta_dicts():
+    store = MemoryStore()
+    store.put("file.txt", b"foo")
+
+    set_object_meta_dicts(True)
+    try:
+        meta = obs.head(store, "file.txt")
+        assert isinstance(meta, dict)
+        assert meta == obs.head(store, "file.txt")
+    finally:
+        set_object_meta_dicts(False)
+
+    assert obs.head(store, "file.txt").to_dict() == meta
@@ -0,0 +1,53 @@
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def test_get_attributes():
+    store = MemoryStore()
+    attributes = {"Content-Type": "text/plain", "Cache-Control": "no-cache"}
+    obs.put(store, "file.txt", b"foo", attributes=attributes)
+
+    assert obs.get_attributes(store, "file.txt") == attributes
+
+
+def test_get_attributes_not_found():
+    store = MemoryStore()
+    with pytest.raises(FileNotFoundError):
+        obs.get_attributes(store, "missing.txt")
+
+
+def test_put_attributes():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo", attributes={"Content-Type": "text/plain"})
+
+    obs.put_attributes(store, "file.txt", {"Content-Language": "en-US"})
+    assert obs.get_attributes(store, "file.txt") == {"Content-Language": "en-US"}
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+
+def test_put_tags_preserves_attributes():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo", attributes={"Content-Type": "text/plain"})
+
+    obs.put_tags(store, "file.txt", {"project": "obstore"})
+    assert obs.get_attributes(store, "file.txt") == {"Content-Type": "text/plain"}
+    assert obs.get(store, "file.txt").bytes() == b"foo"
+
+
+def test_get_tags_unsupported_store():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo")
+    with pytest.raises(ValueError, match="only supported for S3Store"):
+        obs.get_tags(store, "file.txt")  # type: ignore[arg-type]
+
+
+@pytest.mark.asyncio
+async def test_attributes_async():
+    store = MemoryStore()
+    await obs.put_async(store, "file.txt", b"foo")
+
+    await obs.put_attributes_async(store, "file.txt", {"Content-Type": "text/plain"})
+    attributes = await obs.get_attributes_async(store, "file.txt")
+    assert attributes == {"Content-Type": "text/plain"}
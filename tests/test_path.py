@@ -0,0 +1,54 @@
+from pathlib import PurePosixPath
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import InvalidPathError
+from obstore.path import normalize
+from obstore.store import MemoryStore
+
+
+def test_path_like():
+    store = MemoryStore()
+    path = PurePosixPath("data") / "file.txt"
+    obs.put(store, path, b"foo")
+
+    assert obs.get(store, path).bytes() == b"foo"
+    assert obs.head(store, path)["path"] == "data/file.txt"
+    assert [meta["path"] for meta in obs.list(store, PurePosixPath("data")).collect()] == [
+        "data/file.txt",
+    ]
+
+    obs.delete(store, [path])
+    with pytest.raises(FileNotFoundError):
+        obs.head(store, "data/file.txt")
+
+
+@pytest.mark.asyncio
+async def test_path_like_async():
+    store = MemoryStore()
+    path = PurePosixPath("data/file.txt")
+    await obs.put_async(store, path, b"foo")
+    await obs.copy_async(store, path, PurePosixPath("data/copy.txt"))
+
+    resp = await obs.get_async(store, PurePosixPath("data/copy.txt"))
+    assert await resp.bytes_async() == b"foo"
+
+
+@pytest.mark.parametrize("path", ["data//file.txt", "data/../file.txt", "data/./file.txt"])
+def test_invalid_path(path: str):
+    store = MemoryStore()
+    with pytest.raises(InvalidPathError):
+        obs.put(store, path, b"foo")
+
+
+def test_invalid_type():
+    store = MemoryStore()
+    with pytest.raises(TypeError):
+        obs.put(store, 1, b"foo")  # type: ignore[arg-type]
+
+
+def test_normalize():
+    assert normalize("/data//2024/file.txt") == "data/2024/file.txt"
+    assert normalize("data/../file.txt") == "data/%2E%2E/file.txt"
+    assert normalize(PurePosixPath("data/file.txt")) == "data/file.txt"
@@ -0,0 +1,82 @@
+import threading
+from http.server import BaseHTTPRequestHandler, HTTPServer
+
+import pytest
+
+import obstore as obs
+from obstore.exceptions import GenericError
+from obstore.store import HTTPStore, MemoryStore
+
+
+def test_probe():
+    store = MemoryStore()
+    obs.put(store, "file.json", b"{}", attributes={"Content-Type": "application/json"})
+    obs.put(store, "empty.txt", b"")
+
+    result = obs.probe(store, "file.json")
+    assert result["path"] == "file.json"
+    assert result["size"] == 2
+    assert result["e_tag"] is not None
+    assert result["content_type"] == "application/json"
+    assert result["supports_ranges"]
+
+    result = obs.probe(store, "empty.txt")
+    assert result["size"] == 0
+    assert result["content_type"] is None
+    assert not result["supports_ranges"]
+
+
+@pytest.mark.asyncio
+async def test_probe_async():
+    store = MemoryStore()
+    await obs.put_async(store, "file.txt", b"foo")
+
+    result = await obs.probe_async(store, "file.txt")
+    assert result["size"] == 3
+    assert result["supports_ranges"]
+
+
+@pytest.fixture
+def no_range_server():
+    """Serve `data.txt` without support for ranges, and fail every GET of `broken.txt`."""
+
+    class Handler(BaseHTTPRequestHandler):
+        def do_HEAD(self):
+            self.send_response(200)
+            self.send_header("Content-Length", "3")
+            self.end_headers()
+
+        def do_GET(self):
+            if self.path == "/broken.txt":
+                self.send_response(500)
+                self.send_header("Content-Length", "0")
+                self.end_headers()
+                return
+            self.send_response(200)
+            self.send_header("Content-Length", "3")
+            self.end_headers()
+            self.wfile.write(b"foo")
+
+        def log_message(self, *args):
+            pass
+
+    server = HTTPServer(("localhost", 0), Handler)
+    thread = threading.Thread(target=server.serve_forever, daemon=True)
+    thread.start()
+    yield f"http://localhost:{server.server_port}"
+    server.shutdown()
+
+
+def test_probe_http(no_range_server):
+    store = HTTPStore.from_url(
+        no_range_server,
+        client_options={"allow_http": True},
+        retry_config={"max_retries": 0},
+    )
+    result = obs.probe(store, "data.txt")
+    assert result["size"] == 3
+    assert not result["supports_ranges"]
+
+    # Errors other than an unsupported range are raised
+    with pytest.raises(GenericError):
+        obs.probe(store, "broken.txt")
@@ -0,0 +1,125 @@
+import itertools
+from datetime import timedelta
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+class Recorder:
+    """Progress callback that records every call."""
+
+    def __init__(self) -> None:
+        self.calls: list[tuple[int, int | None]] = []
+
+    def __call__(self, transferred: int, total: int | None) -> None:  # noqa: D102
+        self.calls.append((transferred, total))
+
+
+def test_get_progress():
+    store = MemoryStore()
+    data = b"0123456789" * 100
+    obs.put(store, "file.bin", data)
+
+    recorder = Recorder()
+    assert obs.get(store, "file.bin", progress=recorder).bytes() == data
+    assert recorder.calls[-1] == (len(data), len(data))
+
+    recorder = Recorder()
+    result = obs.get(store, "file.bin", options={"range": (0, 10)}, progress=recorder)
+    assert b"".join(result.stream()) == data[:10]
+    assert recorder.calls[-1] == (10, 10)
+
+
+def test_put_progress():
+    store = MemoryStore()
+    data = b"0123456789" * 100
+
+    recorder = Recorder()
+    obs.put(store, "file.bin", data, progress=recorder)
+    assert recorder.calls == [(len(data), len(data))]
+
+    recorder = Recorder()
+    obs.put(
+        store,
+        "file.bin",
+        data,
+        use_multipart=True,
+        chunk_size=100,
+        progress=recorder,
+    )
+    assert recorder.calls[-1] == (len(data), len(data))
+    transferred = [transferred for transferred, _ in recorder.calls]
+    assert transferred == sorted(transferred)
+
+    recorder = Recorder()
+    obs.put(store, "file.bin", itertools.repeat(data, 3), progress=recorder)
+    assert recorder.calls[-1] == (len(data) * 3, None)
+
+
+def test_progress_interval():
+    store = MemoryStore()
+    data = b"0123456789" * 100
+
+    # A zero interval reports every part, and a long interval only the first and last
+    recorder = Recorder()
+    obs.put(
+        store,
+        "file.bin",
+        data,
+        use_multipart=True,
+        chunk_size=100,
+        progress=recorder,
+        progress_interval=timedelta(0),
+    )
+    assert len(recorder.calls) == 10
+
+    recorder = Recorder()
+    obs.put(
+        store,
+        "file.bin",
+        data,
+        use_multipart=True,
+        chunk_size=100,
+        progress=recorder,
+        progress_interval=timedelta(hours=1),
+    )
+    assert len(recorder.calls) == 2
+
+
+def test_open_writer_progress():
+    store = MemoryStore()
+
+    recorder = Recorder()
+    with obs.open_writer(store, "file.bin", buffer_size=100, progress=recorder) as f:
+        for _ in range(5):
+            f.write(b"0123456789" * 10)
+    assert recorder.calls[-1] == (500, None)
+
+
+@pytest.mark.filterwarnings("ignore::pytest.PytestUnraisableExceptionWarning")
+def test_progress_callback_error():
+    store = MemoryStore()
+
+    def callback(_transferred: int, _total: int | None) -> None:
+        raise RuntimeError
+
+    # Errors in the callback don't interrupt the transfer
+    obs.put(store, "file.bin", b"foo", progress=callback)
+    assert obs.get(store, "file.bin").bytes() == b"foo"
+
+
+@pytest.mark.asyncio
+async def test_progress_async():
+    store = MemoryStore()
+    data = b"0123456789" * 100
+
+    recorder = Recorder()
+    await obs.put_async(store, "file.bin", data, progress=recorder)
+    assert recorder.calls == [(len(data), len(data))]
+
+    recorder = Recorder()
+    result = await obs.get_async(store, "file.bin", progress=recorder)
+    assert await result.bytes_async() == data
+    assert recorder.calls[-1] == (len(data), len(data))
@@ -1,9 +1,12 @@
+import gzip
 import itertools
 
+import pyarrow as pa
 import pytest
 
+import obstore as obs
 from obstore.exceptions import AlreadyExistsError
-from obstore.store import MemoryStore
+from obstore.store import LocalStore, MemoryStore
 
 
 def test_put_non_multipart():
@@ -93,3 +96,193 @@ def test_put_sync_iterable():
     store.put(path, iterator)
 
     assert store.get(path).bytes() == data
+
+
+def test_put_multipart_policy_auto():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog," * 1000
+    store.put("file1.txt", data, multipart_policy="auto")
+    assert store.get("file1.txt").bytes() == data
+
+    store.put("file2.txt", itertools.repeat(data, 5), multipart_policy="auto")
+    assert store.get("file2.txt").bytes() == data * 5
+
+
+def test_put_multipart_policy_max_parts():
+    store = MemoryStore()
+
+    data = b"0123456789" * 10
+    policy = {"threshold": 0, "part_size": 10, "max_parts": 10}
+    store.put("file1.txt", data, multipart_policy=policy)
+    assert store.get("file1.txt").bytes() == data
+
+    # Known size that doesn't fit is rejected before uploading
+    with pytest.raises(ValueError, match="more than 10 parts"):
+        store.put("file2.txt", data + b"0", multipart_policy=policy)
+
+    # Unknown size fails once the limit is hit
+    with pytest.raises(ValueError, match="maximum of 10 parts"):
+        store.put("file3.txt", itertools.repeat(data, 2), multipart_policy=policy)
+    assert [meta["path"] for meta in store.list().collect()] == ["file1.txt"]
+
+
+def test_put_multipart_policy_growth():
+    store = MemoryStore()
+
+    # Part sizes double after every part: 10 + 20 + 40 + ... bytes fit in 10 parts
+    policy = {"part_size": 10, "part_size_growth": 2.0, "max_parts": 10}
+    data = b"0123456789" * 500
+    store.put("file1.txt", itertools.repeat(data, 2), multipart_policy=policy)
+    assert store.get("file1.txt").bytes() == data * 2
+
+
+def test_put_multipart_policy_invalid():
+    store = MemoryStore()
+
+    with pytest.raises(ValueError, match="Unexpected input for multipart_policy"):
+        store.put("file1.txt", b"foo", multipart_policy="fast")  # type: ignore[arg-type]
+
+    with pytest.raises(ValueError, match="part_size_growth"):
+        store.put("file1.txt", b"foo", multipart_policy={"part_size_growth": 0.5})
+
+
+def test_put_attributes_case_insensitive():
+    store = MemoryStore()
+
+    attributes = {
+        "content-type": "text/plain",
+        "CONTENT-ENCODING": "gzip",
+        "Content-Language": "en",
+        "cachecontrol": "no-cache",
+        "x-custom": "foo",
+    }
+    store.put("file.txt", b"foo", attributes=attributes)
+    assert store.get("file.txt").attributes == {
+        "Content-Type": "text/plain",
+        "Content-Encoding": "gzip",
+        "Content-Language": "en",
+        "Cache-Control": "no-cache",
+        "x-custom": "foo",
+    }
+
+
+def test_put_slices():
+    store = MemoryStore()
+
+    buffers = [b"foo", bytearray(b"bar"), memoryview(b"baz"), b""]
+    obs.put_slices(store, "file1.txt", buffers)
+    assert store.get("file1.txt").bytes() == b"foobarbaz"
+
+    # Parts of a multipart upload can span several buffers
+    data = [b"0123456789"] * 10 + [b"abc"]
+    obs.put_slices(store, "file2.txt", data, use_multipart=True, chunk_size=15)
+    assert store.get("file2.txt").bytes() == b"".join(data)
+
+    obs.put_slices(store, "file3.txt", [], use_multipart=False)
+    assert store.get("file3.txt").bytes() == b""
+
+    with pytest.raises(AlreadyExistsError):
+        obs.put_slices(store, "file1.txt", [b"foo"], mode="create")
+
+
+@pytest.mark.asyncio
+async def test_put_slices_async():
+    store = MemoryStore()
+
+    await obs.put_slices_async(store, "file1.txt", [b"foo", b"bar"])
+    assert store.get("file1.txt").bytes() == b"foobar"
+
+
+def test_put_result():
+    store = MemoryStore()
+
+    result = store.put("file1.txt", b"foo")
+    assert isinstance(result, obs.PutResult)
+    assert result.e_tag is not None
+    assert result["e_tag"] == result.e_tag
+    assert result.to_dict() == {"e_tag": result.e_tag, "version": result.version}
+    assert result != store.put("file1.txt", b"bar")
+    assert repr(result).startswith("PutResult(e_tag=")
+
+    batch = pa.record_batch(result)
+    assert batch.num_rows == 1
+    assert batch["e_tag"][0].as_py() == result.e_tag
+
+
+def test_put_result_dict_compat():
+    store = MemoryStore()
+
+    result = store.put("file1.txt", b"foo")
+    assert result.get("e_tag") == result.e_tag
+    assert result.get("other") is None
+    assert result.get("other", "default") == "default"
+    assert list(result.keys()) == ["e_tag", "version"]
+    assert result == {"e_tag": result.e_tag, "version": result.version}
+    assert result != {"e_tag": result.e_tag}
+    with pytest.raises(KeyError):
+        result["other"]  # type: ignore
+
+    # Comparisons with other types are left to the other type
+    assert result != object()
+    assert result.__eq__(object()) is NotImplemented
+
+
+def test_put_compress():
+    store = MemoryStore()
+
+    data = b"the quick brown fox jumps over the lazy dog\n" * 1000
+    store.put("file1.txt", data, compress="gzip")
+    result = store.get("file1.txt")
+    assert result.attributes["Content-Encoding"] == "gzip"
+    compressed = result.bytes()
+    assert len(compressed) < len(data)
+    assert gzip.decompress(compressed) == data
+
+    # Iterators are compressed chunk by chunk, across the parts of a multipart upload
+    iterator = itertools.repeat(data, 100)
+    store.put("file2.txt", iterator, chunk_size=5 * 1024, compress="gzip")
+    assert gzip.decompress(store.get("file2.txt").bytes()) == data * 100
+
+    store.put("file3.txt", data, compress="zstd")
+    result = store.get("file3.txt")
+    assert result.attributes["Content-Encoding"] == "zstd"
+    assert b"".join(result.stream(decompress="auto")) == data
+
+    # An explicit Content-Encoding takes precedence
+    attributes = {"Content-Encoding": "x-gzip"}
+    store.put("file4.txt", data, attributes=attributes, compress="gzip")
+    assert store.get("file4.txt").attributes["Content-Encoding"] == "x-gzip"
+
+
+@pytest.mark.asyncio
+async def test_put_compress_async():
+    store = MemoryStore()
+
+    async def chunks():
+        for i in range(100):
+            yield f"line {i}\n".encode()
+
+    await store.put_async("file1.txt", chunks(), compress="gzip")
+    result = await store.get_async("file1.txt")
+    expected = b"".join(f"line {i}\n".encode() for i in range(100))
+    assert gzip.decompress(await result.bytes_async()) == expected
+
+
+def test_put_compress_local_store(tmp_path):
+    # The Content-Encoding is dropped for stores that don't support attributes
+    store = LocalStore(tmp_path)
+
+    data = b"the quick brown fox jumps over the lazy dog\n" * 1000
+    store.put("file1.txt.gz", data, compress="gzip")
+    assert gzip.decompress((tmp_path / "file1.txt.gz").read_bytes()) == data
+
+    store.put("file2.txt.gz", [data] * 10, compress="gzip")
+    assert gzip.decompress((tmp_path / "file2.txt.gz").read_bytes()) == data * 10
+
+
+def test_put_compress_invalid():
+    store = MemoryStore()
+
+    with pytest.raises(ValueError, match="Unsupported compression"):
+        store.put("file1.txt", b"foo", compress="brotli")  # type: ignore[arg-type]
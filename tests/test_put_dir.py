@@ -0,0 +1,70 @@
+import hashlib
+import json
+from pathlib import Path
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def make_tree(root: Path):
+    (root / "nested").mkdir()
+    (root / "index.html").write_bytes(b"<html></html>")
+    (root / "nested" / "data.csv").write_bytes(b"a,b\n1,2\n")
+    (root / "nested" / "blob").write_bytes(b"\x00" * 10)
+
+
+def test_put_dir(tmp_path: Path):
+    make_tree(tmp_path)
+    store = MemoryStore()
+
+    entries = obs.put_dir(store, tmp_path, "site", chunk_size=4)
+    assert [entry["path"] for entry in entries] == [
+        "site/index.html",
+        "site/nested/blob",
+        "site/nested/data.csv",
+    ]
+    assert [entry["content_type"] for entry in entries] == [
+        "text/html",
+        "application/octet-stream",
+        "text/csv",
+    ]
+    assert entries[2]["size"] == 8
+
+    assert obs.get(store, "site/nested/data.csv").bytes() == b"a,b\n1,2\n"
+    attributes = obs.get(store, "site/index.html").attributes
+    assert attributes["Content-Type"] == "text/html"
+
+
+def test_put_dir_manifest(tmp_path: Path):
+    make_tree(tmp_path)
+    store = MemoryStore()
+
+    entries = obs.put_dir(store, tmp_path, manifest="manifest.json")
+    manifest = json.loads(obs.get(store, "manifest.json").bytes().to_bytes())
+    assert manifest["version"] == 1
+    assert manifest["prefix"] == ""
+    assert manifest["algorithm"] == "sha256"
+    assert manifest["size"] == sum(entry["size"] for entry in entries)
+    assert manifest["objects"] == entries
+    expected = hashlib.sha256(b"<html></html>").hexdigest()
+    assert manifest["objects"][0]["sha256"] == expected
+
+
+def test_put_dir_not_a_directory(tmp_path: Path):
+    path = tmp_path / "file.txt"
+    path.write_bytes(b"foo")
+
+    with pytest.raises(ValueError, match="Not a directory"):
+        obs.put_dir(MemoryStore(), path)
+
+
+@pytest.mark.asyncio
+async def test_put_dir_async(tmp_path: Path):
+    make_tree(tmp_path)
+    store = MemoryStore()
+
+    entries = await obs.put_dir_async(store, tmp_path, "site")
+    assert len(entries) == 3
+    assert obs.get(store, "site/nested/blob").bytes() == b"\x00" * 10
@@ -0,0 +1,103 @@
+import pytest
+
+import obstore as obs
+from obstore.runtime import disable_tracing, enable_tracing, set_strict_keys, stats
+from obstore.store import HTTPStore, MemoryStore
+
+
+def test_stats():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo")
+
+    runtime_stats = stats()
+    assert runtime_stats["workers"] > 0
+    assert runtime_stats["alive_tasks"] >= 0
+    assert runtime_stats["global_queue_depth"] >= 0
+
+
+@pytest.mark.asyncio
+async def test_stats_async():
+    store = MemoryStore()
+    await obs.put_async(store, "file.txt", b"foo")
+
+    runtime_stats = stats("async")
+    assert runtime_stats["workers"] > 0
+    assert set(runtime_stats) == {
+        "workers",
+        "alive_tasks",
+        "global_queue_depth",
+        "blocking_threads",
+        "idle_blocking_threads",
+        "worker_utilization",
+    }
+
+
+def test_stats_invalid_runtime():
+    with pytest.raises(ValueError, match="Expected one of 'sync' or 'async'"):
+        stats("other")  # type: ignore[arg-type]
+
+
+def test_tracing():
+    store = MemoryStore()
+    records = []
+    enable_tracing(records.append)
+    try:
+        obs.put(store, "file.txt", b"foo")
+        assert obs.get(store, "file.txt").bytes() == b"foo"
+        obs.list(store).collect()
+        obs.delete(store, "file.txt")
+    finally:
+        disable_tracing()
+
+    assert [r["operation"] for r in records] == ["put", "get", "list", "delete"]
+    assert [r["path"] for r in records] == ["file.txt", "file.txt", "", "file.txt"]
+    assert [r["bytes"] for r in records] == [3, 3, 0, 0]
+    assert all(r["error"] is None for r in records)
+
+    obs.put(store, "file.txt", b"foo")
+    assert len(records) == 4
+
+
+def test_tracing_error():
+    store = MemoryStore()
+    records = []
+    enable_tracing(records.append)
+    try:
+        with pytest.raises(FileNotFoundError):
+            obs.head(store, "missing.txt")
+    finally:
+        disable_tracing()
+
+    assert len(records) == 1
+    assert records[0]["operation"] == "head"
+    assert records[0]["error"] is not None
+
+
+def test_tracing_invalid_callback():
+    with pytest.raises(TypeError, match="Expected callback to be callable"):
+        enable_tracing("callback")  # type: ignore[arg-type]
+
+
+def test_strict_keys():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo")
+
+    options: dict = {"if_matches": "etag"}
+    with pytest.raises(ValueError, match="'if_matches' \\(did you mean 'if_match'"):
+        obs.get(store, "file.txt", options=options)
+
+    retry_config: dict = {"max_retry": 3, "foo": 1}
+    with pytest.raises(ValueError, match="Unknown keys in retry_config"):
+        HTTPStore.from_url("https://example.com", retry_config=retry_config)
+
+
+def test_strict_keys_disabled():
+    store = MemoryStore()
+    obs.put(store, "file.txt", b"foo")
+
+    options: dict = {"if_matches": "etag"}
+    set_strict_keys(False)
+    try:
+        assert obs.get(store, "file.txt", options=options).bytes() == b"foo"
+    finally:
+        set_strict_keys(True)
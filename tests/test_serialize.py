@@ -0,0 +1,97 @@
+import pytest
+
+import obstore as obs
+from obstore.store import LocalStore, MemoryStore
+
+
+def test_json_roundtrip():
+    store = MemoryStore()
+
+    obj = {"step": 10, "loss": [0.5, 0.25], "name": "run-1"}
+    obs.put_json(store, "state.json", obj)
+    assert obs.get_json(store, "state.json") == obj
+    assert obs.get(store, "state.json").attributes == {
+        "Content-Type": "application/json",
+    }
+
+
+def test_pickle_roundtrip():
+    store = MemoryStore()
+
+    obj = {"tuple": (1, 2), "set": {3}, "bytes": b"foo"}
+    obs.put_pickle(store, "state.pkl", obj)
+    assert obs.get_pickle(store, "state.pkl") == obj
+    assert obs.get(store, "state.pkl").attributes == {
+        "Content-Type": "application/python-pickle",
+    }
+
+
+def test_compression():
+    store = MemoryStore()
+
+    obj = ["the quick brown fox jumps over the lazy dog"] * 100
+    obs.put_json(store, "state.json.gz", obj, compression="gzip")
+    result = obs.get(store, "state.json.gz")
+    assert result.attributes["Content-Encoding"] == "gzip"
+    assert len(result.bytes()) < 1000
+    assert obs.get_json(store, "state.json.gz") == obj
+
+    obs.put_pickle(store, "state.pkl.gz", obj, compression="gzip")
+    assert obs.get_pickle(store, "state.pkl.gz") == obj
+
+    obs.put_json(store, "state.json.zst", obj, compression="zstd")
+    result = obs.get(store, "state.json.zst")
+    assert result.attributes["Content-Encoding"] == "zstd"
+    assert len(result.bytes()) < 1000
+    assert obs.get_json(store, "state.json.zst") == obj
+
+
+def test_attributes_override():
+    store = MemoryStore()
+
+    obs.put_json(store, "a.json", [], attributes={"Content-Type": "text/plain"})
+    assert obs.get(store, "a.json").attributes == {"Content-Type": "text/plain"}
+
+
+def test_local_store_without_attributes(tmp_path):
+    store = LocalStore(tmp_path)
+
+    obs.put_json(store, "state.json", {"a": 1}, compression="gzip")
+    assert obs.get_json(store, "state.json") == {"a": 1}
+
+
+def test_max_size():
+    store = MemoryStore()
+
+    with pytest.raises(ValueError, match="larger than max_size"):
+        obs.put_json(store, "big.json", list(range(100)), max_size=10)
+    with pytest.raises(FileNotFoundError):
+        obs.get(store, "big.json")
+
+    obs.put_json(store, "big.json", list(range(100)))
+    with pytest.raises(ValueError, match="larger than max_size"):
+        obs.get_json(store, "big.json", max_size=10)
+
+    # The decompressed size is limited too
+    obs.put_json(store, "bomb.json", "a" * 10_000, compression="gzip")
+    assert len(obs.get(store, "bomb.json").bytes()) < 1000
+    with pytest.raises(ValueError, match="larger than max_size"):
+        obs.get_json(store, "bomb.json", max_size=1000)
+
+
+def test_invalid_compression():
+    store = MemoryStore()
+
+    with pytest.raises(ValueError, match="Unsupported compression"):
+        obs.put_json(store, "a.json", [], compression="lz4")  # type: ignore[arg-type]
+
+
+@pytest.mark.asyncio
+async def test_serialize_async():
+    store = MemoryStore()
+
+    await obs.put_json_async(store, "state.json", {"a": 1})
+    assert await obs.get_json_async(store, "state.json") == {"a": 1}
+
+    await obs.put_pickle_async(store, "state.pkl", (1, 2), compression="gzip")
+    assert await obs.get_pickle_async(store, "state.pkl") == (1, 2)
@@ -0,0 +1,54 @@
+from urllib.error import HTTPError, URLError
+from urllib.request import Request, urlopen
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def test_serve():
+    store = MemoryStore()
+    store.put("dir/file.txt", b"0123456789", attributes={"Content-Type": "text/plain"})
+
+    with obs.serve(store) as server:
+        assert server.url == f"http://127.0.0.1:{server.port}/"
+
+        with urlopen(f"{server.url}dir/file.txt") as response:
+            assert response.status == 200
+            assert response.headers["Content-Type"] == "text/plain"
+            assert response.headers["Accept-Ranges"] == "bytes"
+            assert response.read() == b"0123456789"
+
+        request = Request(f"{server.url}dir/file.txt", headers={"Range": "bytes=2-4"})
+        with urlopen(request) as response:
+            assert response.status == 206
+            assert response.headers["Content-Range"] == "bytes 2-4/10"
+            assert response.read() == b"234"
+
+        with pytest.raises(HTTPError) as exc_info:
+            urlopen(f"{server.url}dir/missing.txt")
+        assert exc_info.value.code == 404
+
+        with urlopen(server.url) as response:
+            assert b'href="dir/"' in response.read()
+
+    with pytest.raises(URLError):
+        urlopen(server.url, timeout=1)
+
+
+def test_serve_prefix():
+    store = MemoryStore()
+    store.put("data/file.txt", b"foo")
+    store.put("other/file.txt", b"bar")
+
+    server = obs.serve(store, prefix="data")
+    with urlopen(f"{server.url}file.txt") as response:
+        assert response.read() == b"foo"
+
+    with pytest.raises(HTTPError) as exc_info:
+        urlopen(f"{server.url}other/file.txt")
+    assert exc_info.value.code == 404
+
+    server.shutdown()
+    server.shutdown()
@@ -0,0 +1,90 @@
+import sys
+import sysconfig
+from concurrent.futures import ThreadPoolExecutor
+
+import pytest
+
+import obstore as obs
+from obstore.store import LocalStore, MemoryStore
+
+NUM_THREADS = 16
+
+
+def test_gil_not_enabled():
+    if not sysconfig.get_config_var("Py_GIL_DISABLED"):
+        pytest.skip("Requires free-threaded Python")
+
+    # Importing a module that relies on the GIL enables it again
+    assert not sys._is_gil_enabled()  # type: ignore[attr-defined]
+
+
+def test_concurrent_put_get():
+    store = MemoryStore()
+
+    def put_get(i: int) -> bytes:
+        data = str(i).encode() * 1000
+        obs.put(store, f"file{i}.txt", data)
+        return obs.get(store, f"file{i}.txt").bytes().to_bytes()
+
+    with ThreadPoolExecutor(NUM_THREADS) as executor:
+        results = list(executor.map(put_get, range(NUM_THREADS * 8)))
+
+    for i, result in enumerate(results):
+        assert result == str(i).encode() * 1000
+
+
+def test_concurrent_get_same_object():
+    store = MemoryStore()
+    data = b"the quick brown fox jumps over the lazy dog," * 5000
+    obs.put(store, "data.txt", data)
+
+    def get(_: int) -> bytes:
+        return obs.get(store, "data.txt").bytes().to_bytes()
+
+    with ThreadPoolExecutor(NUM_THREADS) as executor:
+        assert all(result == data for result in executor.map(get, range(64)))
+
+
+def test_concurrent_streams(tmp_path):
+    store = LocalStore(tmp_path)
+    data = b"0123456789" * 100_000
+    for i in range(NUM_THREADS):
+        obs.put(store, f"file{i}.bin", data)
+
+    def read_stream(i: int) -> int:
+        stream = obs.get(store, f"file{i}.bin").stream(min_chunk_size=1024)
+        return sum(len(chunk) for chunk in stream)
+
+    with ThreadPoolExecutor(NUM_THREADS) as executor:
+        assert list(executor.map(read_stream, range(NUM_THREADS))) == [
+            len(data),
+        ] * NUM_THREADS
+
+
+def test_concurrent_list():
+    store = MemoryStore()
+    for i in range(100):
+        obs.put(store, f"prefix/file{i}.txt", b"foo")
+
+    def list_all(_: int) -> int:
+        return sum(len(chunk) for chunk in obs.list(store, "prefix", chunk_size=10))
+
+    with ThreadPoolExecutor(NUM_THREADS) as executor:
+        assert set(executor.map(list_all, range(NUM_THREADS))) == {100}
+
+
+def test_shared_stream_across_threads():
+    store = MemoryStore()
+    for i in range(1000):
+        obs.put(store, f"file{i:04}.txt", b"foo")
+
+    # Each chunk of a stream is yielded to exactly one thread
+    stream = obs.list(store, chunk_size=10)
+
+    def drain(_: int) -> list[str]:
+        return [meta["path"] for chunk in stream for meta in chunk]
+
+    with ThreadPoolExecutor(8) as executor:
+        paths = [path for result in executor.map(drain, range(8)) for path in result]
+
+    assert sorted(paths) == [f"file{i:04}.txt" for i in range(1000)]
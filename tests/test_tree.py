@@ -0,0 +1,105 @@
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def test_tree():
+    store = MemoryStore()
+    store.put("a/b/file1.txt", b"foo")
+    store.put("a/b/c/file2.txt", b"barbaz")
+    store.put("a/file3.txt", b"x")
+    store.put("d/file4.txt", b"12")
+    store.put("file5.txt", b"1234")
+
+    root = obs.tree(store)
+    assert root["count"] == 5
+    assert root["size"] == 16
+    assert set(root["children"]) == {"a", "d"}
+    assert root["children"]["a"]["count"] == 3
+    assert root["children"]["a"]["size"] == 10
+    # Only one level is expanded by default
+    assert root["children"]["a"]["children"] == {}
+
+    root = obs.tree(store, depth=3)
+    b = root["children"]["a"]["children"]["b"]
+    assert b["path"] == "a/b"
+    assert b["count"] == 2
+    assert b["children"]["c"]["size"] == 6
+
+    a = obs.tree(store, "a", depth=2)
+    assert a["path"] == "a"
+    assert a["count"] == 3
+    assert a["children"]["b"]["children"]["c"]["count"] == 1
+
+
+@pytest.mark.asyncio
+async def test_tree_async():
+    store = MemoryStore()
+    await store.put_async("a/file1.txt", b"foo")
+    await store.put_async("b/file2.txt", b"bar")
+
+    root = await obs.tree_async(store, depth=0)
+    assert root["count"] == 2
+    assert root["children"] == {}
+
+
+def test_stat_prefix():
+    store = MemoryStore()
+    store.put("a/b/file1.txt", b"foo")
+    store.put("a/file2.txt", b"barbaz")
+    store.put("d/file3.txt", b"12")
+    store.put("file4.txt", b"1234")
+    metas = {meta["path"]: meta for meta in obs.list(store).collect()}
+    modified = [meta["last_modified"] for meta in metas.values()]
+
+    stats = obs.stat_prefix(store)
+    assert stats["count"] == 4
+    assert stats["size"] == 15
+    assert stats["min_last_modified"] == min(modified)
+    assert stats["max_last_modified"] == max(modified)
+    assert "directories" not in stats
+
+    stats = obs.stat_prefix(store, "a", by_directory=True)
+    assert stats["count"] == 2
+    assert stats["size"] == 9
+    assert set(stats["directories"]) == {"b"}
+    b = stats["directories"]["b"]
+    assert b["count"] == 1
+    assert b["min_last_modified"] == metas["a/b/file1.txt"]["last_modified"]
+
+    stats = obs.stat_prefix(store, "missing")
+    assert stats["count"] == 0
+    assert stats["min_last_modified"] is None
+
+
+@pytest.mark.asyncio
+async def test_stat_prefix_async():
+    store = MemoryStore()
+    await store.put_async("a/file1.txt", b"foo")
+    await store.put_async("b/file2.txt", b"bar")
+
+    stats = await obs.stat_prefix_async(store, by_directory=True)
+    assert stats["size"] == 6
+    assert set(stats["directories"]) == {"a", "b"}
+
+
+def test_distinct_prefixes():
+    store = MemoryStore()
+    store.put("a/b/c/file1.txt", b"foo")
+    store.put("a/d/file2.txt", b"foo")
+    store.put("a-b/file3.txt", b"foo")
+    store.put("file4.txt", b"foo")
+
+    assert obs.distinct_prefixes(store) == ["a", "a-b"]
+    assert obs.distinct_prefixes(store, depth=2) == ["a", "a/b", "a/d", "a-b"]
+    assert obs.distinct_prefixes(store, "a", 5) == ["a/b", "a/b/c", "a/d"]
+    assert obs.distinct_prefixes(store, depth=0) == []
+
+
+@pytest.mark.asyncio
+async def test_distinct_prefixes_async():
+    store = MemoryStore()
+    await store.put_async("a/b/file1.txt", b"foo")
+
+    assert await obs.distinct_prefixes_async(store, depth=2) == ["a", "a/b"]
@@ -0,0 +1,68 @@
+import json
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+
+def test_verify_multipart():
+    store = MemoryStore()
+
+    data = b"0123456789" * 1000
+    obs.put(
+        store,
+        "data.bin",
+        data,
+        use_multipart=True,
+        chunk_size=3000,
+        manifest="data.bin.manifest.json",
+    )
+    manifest = json.loads(obs.get(store, "data.bin.manifest.json").bytes().to_bytes())
+    assert manifest["path"] == "data.bin"
+    assert manifest["size"] == len(data)
+    assert [part["size"] for part in manifest["parts"]] == [3000, 3000, 3000, 1000]
+
+    obs.verify(store, "data.bin", "data.bin.manifest.json")
+
+
+def test_verify_single_part():
+    store = MemoryStore()
+
+    obs.put(store, "data.bin", b"foo", manifest="data.bin.manifest.json")
+    obs.verify(store, "data.bin", "data.bin.manifest.json")
+
+
+def test_verify_mismatch():
+    store = MemoryStore()
+
+    obs.put(store, "data.bin", b"foo", manifest="data.bin.manifest.json")
+
+    obs.put(store, "data.bin", b"bar")
+    with pytest.raises(ValueError, match="Checksum mismatch for part 0"):
+        obs.verify(store, "data.bin", "data.bin.manifest.json")
+
+    obs.put(store, "data.bin", b"foobar")
+    with pytest.raises(ValueError, match="Size mismatch"):
+        obs.verify(store, "data.bin", "data.bin.manifest.json")
+
+
+def test_verify_invalid_manifest():
+    store = MemoryStore()
+
+    obs.put(store, "data.bin", b"foo")
+    obs.put(store, "manifest.json", b"not json")
+    with pytest.raises(ValueError, match="Invalid manifest"):
+        obs.verify(store, "data.bin", "manifest.json")
+
+
+@pytest.mark.asyncio
+async def test_verify_async():
+    store = MemoryStore()
+
+    async def chunks():
+        for _ in range(3):
+            yield b"the quick brown fox jumps over the lazy dog,"
+
+    await obs.put_async(store, "data.bin", chunks(), chunk_size=50, manifest="m.json")
+    await obs.verify_async(store, "data.bin", "m.json")
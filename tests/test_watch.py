@@ -0,0 +1,55 @@
+import asyncio
+from datetime import timedelta
+
+import pytest
+
+import obstore as obs
+from obstore.store import MemoryStore
+
+POLL_INTERVAL = timedelta(milliseconds=10)
+
+
+@pytest.mark.asyncio
+async def test_watch():
+    store = MemoryStore()
+    store.put("data/existing.txt", b"foo")
+    store.put("data/deleted.txt", b"foo")
+    store.put("other/file.txt", b"foo")
+
+    stream = obs.watch(store, "data", POLL_INTERVAL)
+    # The first poll only records the existing objects
+    next_event = asyncio.ensure_future(stream.__anext__())
+    await asyncio.sleep(0.05)
+    assert not next_event.done()
+
+    store.put("data/existing.txt", b"bar")
+    store.put("data/new.txt", b"foo")
+    store.delete("data/deleted.txt")
+    store.put("other/file.txt", b"bar")
+
+    events = [await asyncio.wait_for(next_event, 5)]
+    for _ in range(2):
+        events.append(await asyncio.wait_for(stream.__anext__(), 5))
+
+    assert [(event["change"], event["path"]) for event in events] == [
+        ("deleted", "data/deleted.txt"),
+        ("modified", "data/existing.txt"),
+        ("created", "data/new.txt"),
+    ]
+    assert events[1]["meta"].size == 3
+
+
+@pytest.mark.asyncio
+async def test_watch_include_existing():
+    store = MemoryStore()
+    store.put("a.txt", b"foo")
+    store.put("b.txt", b"foo")
+
+    paths = []
+    stream = obs.watch(store, poll_interval=POLL_INTERVAL, include_existing=True)
+    async for event in stream:
+        assert event["change"] == "created"
+        paths.append(event["path"])
+        if len(paths) == 2:
+            break
+    assert paths == ["a.txt", "b.txt"]